@@ -29,19 +29,20 @@ function read_socks_proxy() {
 function usage() {
     cat <<EOF
 Usage:
-  generate-btc-adapter-config [-b bitcoind_addr.conf] [-s socks_proxy.conf] -o ic-btc-adapter.json5
+  generate-btc-adapter-config [-b bitcoind_addr.conf] [-s socks_proxy.conf] [-c cache_dir] -o ic-btc-adapter.json5
 
   Generate the bitcoin adapter config.
 
   -b bitcoind_addr.conf: Optional, bitcoind address
   -s socks_proxy.conf: Optional, socks proxy url
+  -c cache_dir: Optional, directory in which the adapter persists the header chain across restarts
   -m If set, we will use bitcoin mainnet dns seeds 
   -o outfile: output ic-btc-adapter.json5 file
 EOF
 }
 
 MAINNET=false
-while getopts "b:mo:s:" OPT; do
+while getopts "b:c:mo:s:" OPT; do
     case "${OPT}" in
         b)
             BITCOIND_ADDR_FILE="${OPTARG}"
@@ -49,6 +50,9 @@ while getopts "b:mo:s:" OPT; do
         s)
             SOCKS_FILE="${OPTARG}"
             ;;
+        c)
+            CACHE_DIR="${OPTARG}"
+            ;;
         o)
             OUT_FILE="${OPTARG}"
             ;;
@@ -93,6 +97,13 @@ if [ "${OUT_FILE}" == "" ]; then
     exit 1
 fi
 
+# The cache directory must be on a persistent partition, otherwise the adapter
+# downloads all headers again after every reboot. The adapter creates it if needed.
+CACHE_DIR_CONFIG=""
+if [ "${CACHE_DIR}" != "" ]; then
+    CACHE_DIR_CONFIG='"cache_dir": "'"${CACHE_DIR}"'",'
+fi
+
 # BITCOIND_ADDR indicates that we are in system test environment. No socks proxy needed.
 # bitcoin_addr.conf should be formatted like this: key 'bitcoind_addr', comma separated values, NO "" around addresses, NO trailing ',' AND spaces
 # Example: bitcoind_addr=seed.bitcoin.sipa.be,regtest.random.me,regtest.random.org
@@ -107,6 +118,7 @@ if [ "${BITCOIND_ADDR_FILE}" != "" -a -e "${BITCOIND_ADDR_FILE}" ]; then
         "network": "regtest",
         "dns_seeds": [],
         "nodes": ['"${bitcoind_addr:+\"${bitcoind_addr//,/\",\"}\"}"'],
+        '"${CACHE_DIR_CONFIG}"'
         "logger": {
             "format": "json",
             "level": "info"
@@ -116,6 +128,7 @@ else
     echo '{
         "network": '"${BITCOIN_NETWORK}"',
         "dns_seeds": ['"${DNS_SEEDS}"'],
+        '"${CACHE_DIR_CONFIG}"'
         "logger": {
             "format": "json",
             "level": "info"
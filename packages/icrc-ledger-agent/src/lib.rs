@@ -342,7 +342,14 @@ impl Icrc1Agent {
         self.verify_root_hash(&certificate, &hash_tree.digest())
             .await?;
 
-        let last_block_hash_vec = lookup_leaf(&hash_tree, "tip_hash")?;
+        // Ledgers following ICRC-3 certify the tip under `last_block_hash` and
+        // encode `last_block_index` with LEB128, while older ledgers certify it
+        // under `tip_hash` and encode `last_block_index` as big-endian u64.
+        let (last_block_hash_vec, is_icrc3_tree) = match lookup_leaf(&hash_tree, "last_block_hash")?
+        {
+            Some(last_block_hash_vec) => (Some(last_block_hash_vec), true),
+            None => (lookup_leaf(&hash_tree, "tip_hash")?, false),
+        };
         if let Some(last_block_hash_vec) = last_block_hash_vec {
             let last_block_hash: Hash = match last_block_hash_vec.clone().try_into() {
                 Ok(last_block_hash) => last_block_hash,
@@ -356,22 +363,30 @@ impl Icrc1Agent {
 
             let last_block_index_vec = lookup_leaf(&hash_tree, "last_block_index")?;
             if let Some(last_block_index_vec) = last_block_index_vec {
-                let last_block_index_bytes: [u8; 8] = match last_block_index_vec.clone().try_into()
-                {
-                    Ok(last_block_index_bytes) => last_block_index_bytes,
-                    Err(_) => {
+                let last_block_index = if is_icrc3_tree {
+                    decode_leb128_index(&last_block_index_vec)
+                } else {
+                    last_block_index_vec
+                        .clone()
+                        .try_into()
+                        .ok()
+                        .map(|bytes| Nat::from(u64::from_be_bytes(bytes)))
+                };
+                let last_block_index = match last_block_index {
+                    Some(last_block_index) => last_block_index,
+                    None => {
                         return Err(Icrc1AgentError::VerificationFailed(format!(
                     "DataCertificate hash_tree bytes: {}, cannot be decoded as last_block_index",
                     hex::encode(last_block_index_vec)
                 )))
                     }
                 };
-                let last_block_index = u64::from_be_bytes(last_block_index_bytes);
 
-                return Ok(Some((last_block_hash, Nat::from(last_block_index))));
+                return Ok(Some((last_block_hash, last_block_index)));
             } else {
                 return Err(Icrc1AgentError::VerificationFailed(
-                    "certified hash_tree contains tip_hash but not last_block_index".to_string(),
+                    "certified hash_tree contains the tip hash but not last_block_index"
+                        .to_string(),
                 ));
             }
         }
@@ -380,6 +395,13 @@ impl Icrc1Agent {
     }
 }
 
+/// Decodes a LEB128 encoded block index, rejecting trailing bytes.
+fn decode_leb128_index(bytes: &[u8]) -> Option<Nat> {
+    let mut reader = bytes;
+    let index = Nat::decode(&mut reader).ok()?;
+    reader.is_empty().then_some(index)
+}
+
 fn lookup_leaf(hash_tree: &HashTree, leaf_name: &str) -> Result<Option<Vec<u8>>, Icrc1AgentError> {
     match hash_tree.lookup_subtree([leaf_name.as_bytes()]) {
         SubtreeLookupResult::Found(tree) => match tree.as_ref() {
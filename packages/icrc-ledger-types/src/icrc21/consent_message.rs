@@ -0,0 +1,733 @@
+//! Human-readable consent messages for the ICRC-1 and ICRC-2 ledger endpoints,
+//! as specified by ICRC-21.
+use super::errors::{ErrorInfo, Icrc21Error};
+use super::requests::{ConsentMessageMetadata, ConsentMessageRequest, DisplayMessageType};
+use super::responses::{ConsentInfo, ConsentMessage, LineDisplayPage};
+use crate::icrc1::account::{Account, Subaccount};
+use crate::icrc1::transfer::{Memo, TransferArg};
+use crate::icrc2::approve::ApproveArgs;
+use crate::icrc2::transfer_from::TransferFromArgs;
+use candid::{Decode, Nat, Principal};
+
+/// The maximum size of a Candid argument for which a consent message is built.
+/// Arguments of the supported endpoints are much smaller than that.
+pub const MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES: u16 = 500;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Language {
+    English,
+    German,
+}
+
+impl Language {
+    /// Returns the language matching the primary subtag of a BCP-47 language
+    /// tag, falling back to English for unsupported languages.
+    fn from_tag(tag: &str) -> Self {
+        let primary_subtag = tag.split('-').next().unwrap_or_default();
+        match primary_subtag.to_ascii_lowercase().as_str() {
+            "de" => Self::German,
+            _ => Self::English,
+        }
+    }
+
+    fn tag(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Text {
+    TransferIntro,
+    ApproveTitle,
+    ApproveIntro,
+    TransferFromIntro,
+    From,
+    FromSubaccount,
+    Amount,
+    To,
+    Fees,
+    Memo,
+    Spender,
+    SpenderSubaccount,
+    RequestedAllowance,
+    ExistingAllowance,
+    Expiration,
+    NoExpiration,
+    DefaultSubaccount,
+}
+
+impl Text {
+    fn translate(self, language: Language) -> &'static str {
+        use Language::*;
+        match (self, language) {
+            (Text::TransferIntro, English) => {
+                "You are approving a transfer of funds from your account."
+            }
+            (Text::TransferIntro, German) => "Sie genehmigen eine Überweisung von Ihrem Konto.",
+            (Text::ApproveTitle, English) => "Approve spending",
+            (Text::ApproveTitle, German) => "Ausgaben genehmigen",
+            (Text::ApproveIntro, English) => {
+                "You are authorizing another address to withdraw funds from your account."
+            }
+            (Text::ApproveIntro, German) => {
+                "Sie erlauben einer anderen Adresse, Guthaben von Ihrem Konto abzuheben."
+            }
+            (Text::TransferFromIntro, English) => {
+                "You are approving a transfer of funds from a withdrawal account."
+            }
+            (Text::TransferFromIntro, German) => {
+                "Sie genehmigen eine Überweisung von einem Abhebungskonto."
+            }
+            (Text::From, English) => "From",
+            (Text::From, German) => "Von",
+            (Text::FromSubaccount, English) => "From subaccount",
+            (Text::FromSubaccount, German) => "Von Unterkonto",
+            (Text::Amount, English) => "Amount",
+            (Text::Amount, German) => "Betrag",
+            (Text::To, English) => "To",
+            (Text::To, German) => "An",
+            (Text::Fees, English) => "Fees",
+            (Text::Fees, German) => "Gebühren",
+            (Text::Memo, English) => "Memo",
+            (Text::Memo, German) => "Memo",
+            (Text::Spender, English) => "Spender",
+            (Text::Spender, German) => "Berechtigte Adresse",
+            (Text::SpenderSubaccount, English) => "Spender subaccount",
+            (Text::SpenderSubaccount, German) => "Unterkonto der berechtigten Adresse",
+            (Text::RequestedAllowance, English) => "Requested allowance",
+            (Text::RequestedAllowance, German) => "Beantragte Freigabe",
+            (Text::ExistingAllowance, English) => "Existing allowance",
+            (Text::ExistingAllowance, German) => "Bestehende Freigabe",
+            (Text::Expiration, English) => "Approval expiration",
+            (Text::Expiration, German) => "Ablauf der Freigabe",
+            (Text::NoExpiration, English) => "This approval does not expire.",
+            (Text::NoExpiration, German) => "Diese Freigabe läuft nicht ab.",
+            (Text::DefaultSubaccount, English) => "Default subaccount",
+            (Text::DefaultSubaccount, German) => "Standard-Unterkonto",
+        }
+    }
+}
+
+/// The token parameters used to render amounts.
+#[derive(Clone, Debug)]
+pub struct TokenInfo {
+    pub symbol: String,
+    pub decimals: u8,
+    /// The ledger fee, displayed if the call does not specify a fee.
+    pub fee: Nat,
+}
+
+/// A consent message before it is rendered for a specific display type.
+struct Message {
+    title: String,
+    intro: &'static str,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl Message {
+    fn to_generic_display(&self) -> String {
+        let mut message = format!("# {}\n\n{}", self.title, self.intro);
+        for (label, value) in &self.fields {
+            message.push_str(&format!("\n\n**{}:**\n{}", label, value));
+        }
+        message
+    }
+
+    fn to_line_display(
+        &self,
+        characters_per_line: usize,
+        lines_per_page: usize,
+    ) -> Vec<LineDisplayPage> {
+        let mut lines = wrap(&self.title, characters_per_line);
+        lines.extend(wrap(self.intro, characters_per_line));
+        for (label, value) in &self.fields {
+            lines.extend(wrap(&format!("{}:", label), characters_per_line));
+            lines.extend(wrap(value, characters_per_line));
+        }
+        lines
+            .chunks(lines_per_page)
+            .map(|lines| LineDisplayPage {
+                lines: lines.to_vec(),
+            })
+            .collect()
+    }
+}
+
+/// Builds the consent message for a call to `icrc1_transfer`, `icrc2_approve`
+/// or `icrc2_transfer_from` of a ledger.
+///
+/// `caller` is the principal that will make the call. If it is anonymous,
+/// e.g., because the message is displayed before the call is signed, only
+/// the subaccount of the caller is shown.
+pub fn build_consent_info(
+    request: ConsentMessageRequest,
+    caller: Principal,
+    token: &TokenInfo,
+) -> Result<ConsentInfo, Icrc21Error> {
+    if request.arg.len() > MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES as usize {
+        return Err(unsupported_canister_call(format!(
+            "the argument size {} exceeds the maximum of {} bytes",
+            request.arg.len(),
+            MAX_CONSENT_MESSAGE_ARG_SIZE_BYTES
+        )));
+    }
+
+    let metadata = request.user_preferences.metadata;
+    let language = Language::from_tag(&metadata.language);
+    let fmt = Formatter {
+        language,
+        token,
+        utc_offset_minutes: metadata.utc_offset_minutes,
+    };
+    let caller = (caller != Principal::anonymous()).then_some(caller);
+
+    let message = match request.method.as_str() {
+        "icrc1_transfer" => {
+            let arg = Decode!(request.arg.as_slice(), TransferArg).map_err(|e| {
+                unsupported_canister_call(format!("failed to decode TransferArg: {}", e))
+            })?;
+            let mut fields = vec![fmt.caller_account(
+                caller,
+                arg.from_subaccount,
+                Text::From,
+                Text::FromSubaccount,
+            )];
+            fields.push(fmt.field(Text::Amount, fmt.amount(&arg.amount)));
+            fields.push(fmt.field(Text::To, arg.to.to_string()));
+            fields.push(fmt.field(
+                Text::Fees,
+                fmt.amount(arg.fee.as_ref().unwrap_or(&token.fee)),
+            ));
+            fields.extend(
+                arg.memo
+                    .as_ref()
+                    .map(|memo| fmt.field(Text::Memo, format_memo(memo))),
+            );
+            Message {
+                title: fmt.transfer_title(),
+                intro: Text::TransferIntro.translate(language),
+                fields,
+            }
+        }
+        "icrc2_approve" => {
+            let arg = Decode!(request.arg.as_slice(), ApproveArgs).map_err(|e| {
+                unsupported_canister_call(format!("failed to decode ApproveArgs: {}", e))
+            })?;
+            let mut fields = vec![fmt.caller_account(
+                caller,
+                arg.from_subaccount,
+                Text::From,
+                Text::FromSubaccount,
+            )];
+            fields.push(fmt.field(Text::Spender, arg.spender.to_string()));
+            fields.push(fmt.field(Text::RequestedAllowance, fmt.amount(&arg.amount)));
+            fields.extend(
+                arg.expected_allowance
+                    .as_ref()
+                    .map(|allowance| fmt.field(Text::ExistingAllowance, fmt.amount(allowance))),
+            );
+            fields.push(fmt.field(
+                Text::Expiration,
+                match arg.expires_at {
+                    Some(expires_at) => fmt.timestamp(expires_at),
+                    None => Text::NoExpiration.translate(language).to_string(),
+                },
+            ));
+            fields.push(fmt.field(
+                Text::Fees,
+                fmt.amount(arg.fee.as_ref().unwrap_or(&token.fee)),
+            ));
+            fields.extend(
+                arg.memo
+                    .as_ref()
+                    .map(|memo| fmt.field(Text::Memo, format_memo(memo))),
+            );
+            Message {
+                title: Text::ApproveTitle.translate(language).to_string(),
+                intro: Text::ApproveIntro.translate(language),
+                fields,
+            }
+        }
+        "icrc2_transfer_from" => {
+            let arg = Decode!(request.arg.as_slice(), TransferFromArgs).map_err(|e| {
+                unsupported_canister_call(format!("failed to decode TransferFromArgs: {}", e))
+            })?;
+            let mut fields = vec![
+                fmt.field(Text::From, arg.from.to_string()),
+                fmt.field(Text::Amount, fmt.amount(&arg.amount)),
+                fmt.caller_account(
+                    caller,
+                    arg.spender_subaccount,
+                    Text::Spender,
+                    Text::SpenderSubaccount,
+                ),
+                fmt.field(Text::To, arg.to.to_string()),
+                fmt.field(
+                    Text::Fees,
+                    fmt.amount(arg.fee.as_ref().unwrap_or(&token.fee)),
+                ),
+            ];
+            fields.extend(
+                arg.memo
+                    .as_ref()
+                    .map(|memo| fmt.field(Text::Memo, format_memo(memo))),
+            );
+            Message {
+                title: fmt.transfer_title(),
+                intro: Text::TransferFromIntro.translate(language),
+                fields,
+            }
+        }
+        method => {
+            return Err(unsupported_canister_call(format!(
+                "no consent message is available for method {}",
+                method
+            )))
+        }
+    };
+
+    let consent_message = match request.user_preferences.device_spec {
+        None | Some(DisplayMessageType::GenericDisplay) => {
+            ConsentMessage::GenericDisplayMessage(message.to_generic_display())
+        }
+        Some(DisplayMessageType::LineDisplay {
+            characters_per_line,
+            lines_per_page,
+        }) => {
+            if characters_per_line == 0 || lines_per_page == 0 {
+                return Err(Icrc21Error::ConsentMessageUnavailable(ErrorInfo {
+                    description: "the line display must have at least one line of one character"
+                        .to_string(),
+                }));
+            }
+            ConsentMessage::LineDisplayMessage {
+                pages: message
+                    .to_line_display(characters_per_line as usize, lines_per_page as usize),
+            }
+        }
+    };
+
+    Ok(ConsentInfo {
+        consent_message,
+        metadata: ConsentMessageMetadata {
+            language: language.tag().to_string(),
+            utc_offset_minutes: metadata.utc_offset_minutes,
+        },
+    })
+}
+
+fn unsupported_canister_call(description: String) -> Icrc21Error {
+    Icrc21Error::UnsupportedCanisterCall(ErrorInfo { description })
+}
+
+struct Formatter<'a> {
+    language: Language,
+    token: &'a TokenInfo,
+    utc_offset_minutes: Option<i16>,
+}
+
+impl Formatter<'_> {
+    fn field(&self, label: Text, value: String) -> (&'static str, String) {
+        (label.translate(self.language), value)
+    }
+
+    fn transfer_title(&self) -> String {
+        match self.language {
+            Language::English => format!("Send {}", self.token.symbol),
+            Language::German => format!("{} senden", self.token.symbol),
+        }
+    }
+
+    /// Displays the account of the caller, or only its subaccount if the
+    /// caller is not known.
+    fn caller_account(
+        &self,
+        caller: Option<Principal>,
+        subaccount: Option<Subaccount>,
+        account_label: Text,
+        subaccount_label: Text,
+    ) -> (&'static str, String) {
+        match caller {
+            Some(owner) => self.field(account_label, Account { owner, subaccount }.to_string()),
+            None => self.field(
+                subaccount_label,
+                match subaccount {
+                    Some(subaccount) if subaccount != [0; 32] => hex::encode(subaccount),
+                    _ => Text::DefaultSubaccount.translate(self.language).to_string(),
+                },
+            ),
+        }
+    }
+
+    fn amount(&self, amount: &Nat) -> String {
+        format!(
+            "{} {}",
+            format_amount(amount, self.token.decimals),
+            self.token.symbol
+        )
+    }
+
+    fn timestamp(&self, nanos_since_unix_epoch: u64) -> String {
+        format_timestamp(nanos_since_unix_epoch, self.utc_offset_minutes.unwrap_or(0))
+    }
+}
+
+/// Formats a token amount given in the smallest unit as a decimal number,
+/// e.g., `150_000_000` with 8 decimals as `1.5`.
+fn format_amount(amount: &Nat, decimals: u8) -> String {
+    let digits = amount.0.to_str_radix(10);
+    let decimals = decimals as usize;
+    let (integer, fraction) = if digits.len() > decimals {
+        let (integer, fraction) = digits.split_at(digits.len() - decimals);
+        (integer.to_string(), fraction.to_string())
+    } else {
+        (
+            "0".to_string(),
+            format!("{:0>width$}", digits, width = decimals),
+        )
+    };
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        integer
+    } else {
+        format!("{}.{}", integer, fraction)
+    }
+}
+
+/// The ranges of characters in the Unicode general category `Cf` (format) as of
+/// Unicode 15.0. These are invisible but affect the display of the surrounding
+/// text, e.g. U+202E RIGHT-TO-LEFT OVERRIDE or U+200B ZERO WIDTH SPACE.
+const FORMAT_CHARACTERS: &[(char, char)] = &[
+    ('\u{00AD}', '\u{00AD}'),
+    ('\u{0600}', '\u{0605}'),
+    ('\u{061C}', '\u{061C}'),
+    ('\u{06DD}', '\u{06DD}'),
+    ('\u{070F}', '\u{070F}'),
+    ('\u{0890}', '\u{0891}'),
+    ('\u{08E2}', '\u{08E2}'),
+    ('\u{180E}', '\u{180E}'),
+    ('\u{200B}', '\u{200F}'),
+    ('\u{202A}', '\u{202E}'),
+    ('\u{2060}', '\u{2064}'),
+    ('\u{2066}', '\u{206F}'),
+    ('\u{FEFF}', '\u{FEFF}'),
+    ('\u{FFF9}', '\u{FFFB}'),
+    ('\u{110BD}', '\u{110BD}'),
+    ('\u{110CD}', '\u{110CD}'),
+    ('\u{13430}', '\u{1343F}'),
+    ('\u{1BCA0}', '\u{1BCA3}'),
+    ('\u{1D173}', '\u{1D17A}'),
+    ('\u{E0001}', '\u{E0001}'),
+    ('\u{E0020}', '\u{E007F}'),
+];
+
+fn is_format(c: char) -> bool {
+    FORMAT_CHARACTERS
+        .iter()
+        .any(|(first, last)| (*first..=*last).contains(&c))
+}
+
+/// Displays the memo as text if it is valid UTF-8 without control or format
+/// characters, and as hex otherwise, so that the displayed text cannot be
+/// reordered or padded with invisible characters.
+fn format_memo(memo: &Memo) -> String {
+    match std::str::from_utf8(memo.0.as_slice()) {
+        Ok(text) if !text.chars().any(|c| c.is_control() || is_format(c)) => text.to_string(),
+        _ => format!("0x{}", hex::encode(memo.0.as_slice())),
+    }
+}
+
+/// Formats a timestamp as `YYYY-MM-DD HH:MM:SS` in the time zone with the
+/// given offset to UTC.
+fn format_timestamp(nanos_since_unix_epoch: u64, utc_offset_minutes: i16) -> String {
+    let secs = (nanos_since_unix_epoch / NANOS_PER_SEC) as i64 + utc_offset_minutes as i64 * 60;
+    let (days, secs_of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    let (year, month, day) = civil_from_days(days);
+    let offset = match utc_offset_minutes {
+        0 => "UTC".to_string(),
+        offset => format!(
+            "UTC{}{:02}:{:02}",
+            if offset < 0 { '-' } else { '+' },
+            offset.unsigned_abs() / 60,
+            offset.unsigned_abs() % 60
+        ),
+    };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        offset
+    )
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) date in the
+/// proleptic Gregorian calendar.
+/// See http://howardhinnant.github.io/date_algorithms.html#civil_from_days.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Splits the text into lines of at most `width` characters, breaking at
+/// whitespace where possible and splitting words that do not fit on a line.
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![];
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let mut word: Vec<char> = word.chars().collect();
+        let line_len = line.chars().count();
+        if line_len > 0 && line_len + 1 + word.len() <= width {
+            line.push(' ');
+            line.extend(word);
+            continue;
+        }
+        if line_len > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+        while word.len() > width {
+            lines.push(word.drain(..width).collect());
+        }
+        line = word.into_iter().collect();
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::icrc21::requests::ConsentMessageSpec;
+    use candid::Encode;
+    use serde_bytes::ByteBuf;
+
+    fn token() -> TokenInfo {
+        TokenInfo {
+            symbol: "XTKN".to_string(),
+            decimals: 8,
+            fee: Nat::from(10_000_u64),
+        }
+    }
+
+    fn request(
+        method: &str,
+        arg: Vec<u8>,
+        language: &str,
+        device_spec: Option<DisplayMessageType>,
+    ) -> ConsentMessageRequest {
+        ConsentMessageRequest {
+            method: method.to_string(),
+            arg: ByteBuf::from(arg),
+            user_preferences: ConsentMessageSpec {
+                metadata: ConsentMessageMetadata {
+                    language: language.to_string(),
+                    utc_offset_minutes: None,
+                },
+                device_spec,
+            },
+        }
+    }
+
+    fn transfer_arg() -> TransferArg {
+        TransferArg {
+            from_subaccount: None,
+            to: Account::from(Principal::management_canister()),
+            fee: None,
+            created_at_time: None,
+            memo: Some(Memo::from(b"coffee".to_vec())),
+            amount: Nat::from(150_000_000_u64),
+        }
+    }
+
+    #[test]
+    fn should_format_amounts() {
+        assert_eq!(format_amount(&Nat::from(150_000_000_u64), 8), "1.5");
+        assert_eq!(format_amount(&Nat::from(10_000_u64), 8), "0.0001");
+        assert_eq!(format_amount(&Nat::from(0_u64), 8), "0");
+        assert_eq!(format_amount(&Nat::from(1_000_u64), 0), "1000");
+        assert_eq!(format_amount(&Nat::from(1_000_000_000_u64), 8), "10");
+    }
+
+    #[test]
+    fn should_format_timestamps() {
+        assert_eq!(format_timestamp(0, 0), "1970-01-01 00:00:00 UTC");
+        // 2024-02-29 23:30:00 UTC
+        let nanos = 1_709_249_400 * NANOS_PER_SEC;
+        assert_eq!(format_timestamp(nanos, 0), "2024-02-29 23:30:00 UTC");
+        assert_eq!(format_timestamp(nanos, 90), "2024-03-01 01:00:00 UTC+01:30");
+        assert_eq!(
+            format_timestamp(nanos, -60),
+            "2024-02-29 22:30:00 UTC-01:00"
+        );
+    }
+
+    #[test]
+    fn should_format_memos() {
+        assert_eq!(format_memo(&Memo::from(b"coffee".to_vec())), "coffee");
+        assert_eq!(
+            format_memo(&Memo::from("caf\u{e9}".as_bytes().to_vec())),
+            "caf\u{e9}"
+        );
+        assert_eq!(format_memo(&Memo::from(vec![0xff, 0x00])), "0xff00");
+        assert_eq!(format_memo(&Memo::from(b"a\nb".to_vec())), "0x610a62");
+        for format_character in ['\u{202E}', '\u{2066}', '\u{2069}', '\u{200B}', '\u{FEFF}'] {
+            let memo = format!("pay{}me", format_character);
+            assert_eq!(
+                format_memo(&Memo::from(memo.as_bytes().to_vec())),
+                format!("0x{}", hex::encode(memo.as_bytes())),
+                "memo with {:?} should be displayed as hex",
+                format_character
+            );
+        }
+    }
+
+    #[test]
+    fn should_wrap_lines() {
+        assert_eq!(
+            wrap("a bb ccc dddddddd", 4),
+            vec!["a bb", "ccc", "dddd", "dddd"]
+        );
+        assert_eq!(wrap("", 4), Vec::<String>::new());
+    }
+
+    #[test]
+    fn should_build_transfer_message() {
+        let caller = Principal::from_slice(&[1; 29]);
+        let info = build_consent_info(
+            request(
+                "icrc1_transfer",
+                Encode!(&transfer_arg()).unwrap(),
+                "en-US",
+                None,
+            ),
+            caller,
+            &token(),
+        )
+        .unwrap();
+        assert_eq!(info.metadata.language, "en");
+        let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+            panic!("expected a generic display message");
+        };
+        assert!(message.starts_with("# Send XTKN"));
+        assert!(message.contains(&format!("**From:**\n{}", caller)));
+        assert!(message.contains("**Amount:**\n1.5 XTKN"));
+        assert!(message.contains("**Fees:**\n0.0001 XTKN"));
+        assert!(message.contains("**Memo:**\ncoffee"));
+    }
+
+    #[test]
+    fn should_show_subaccount_for_anonymous_caller_in_german() {
+        let info = build_consent_info(
+            request(
+                "icrc1_transfer",
+                Encode!(&transfer_arg()).unwrap(),
+                "de",
+                None,
+            ),
+            Principal::anonymous(),
+            &token(),
+        )
+        .unwrap();
+        assert_eq!(info.metadata.language, "de");
+        let ConsentMessage::GenericDisplayMessage(message) = info.consent_message else {
+            panic!("expected a generic display message");
+        };
+        assert!(message.starts_with("# XTKN senden"));
+        assert!(message.contains("**Von Unterkonto:**\nStandard-Unterkonto"));
+    }
+
+    #[test]
+    fn should_paginate_line_display_messages() {
+        let info = build_consent_info(
+            request(
+                "icrc1_transfer",
+                Encode!(&transfer_arg()).unwrap(),
+                "en",
+                Some(DisplayMessageType::LineDisplay {
+                    characters_per_line: 20,
+                    lines_per_page: 4,
+                }),
+            ),
+            Principal::anonymous(),
+            &token(),
+        )
+        .unwrap();
+        let ConsentMessage::LineDisplayMessage { pages } = info.consent_message else {
+            panic!("expected a line display message");
+        };
+        assert!(pages.len() > 1);
+        for page in &pages {
+            assert!(page.lines.len() <= 4);
+            assert!(page.lines.iter().all(|line| line.chars().count() <= 20));
+        }
+        assert_eq!(pages[0].lines[0], "Send XTKN");
+    }
+
+    #[test]
+    fn should_reject_unsupported_calls() {
+        let token = token();
+        assert!(matches!(
+            build_consent_info(
+                request("icrc1_balance_of", vec![], "en", None),
+                Principal::anonymous(),
+                &token
+            ),
+            Err(Icrc21Error::UnsupportedCanisterCall(_))
+        ));
+        assert!(matches!(
+            build_consent_info(
+                request(
+                    "icrc2_approve",
+                    Encode!(&transfer_arg()).unwrap(),
+                    "en",
+                    None
+                ),
+                Principal::anonymous(),
+                &token
+            ),
+            Err(Icrc21Error::UnsupportedCanisterCall(_))
+        ));
+        assert!(matches!(
+            build_consent_info(
+                request("icrc1_transfer", vec![0; 501], "en", None),
+                Principal::anonymous(),
+                &token
+            ),
+            Err(Icrc21Error::UnsupportedCanisterCall(_))
+        ));
+        assert!(matches!(
+            build_consent_info(
+                request(
+                    "icrc1_transfer",
+                    Encode!(&transfer_arg()).unwrap(),
+                    "en",
+                    Some(DisplayMessageType::LineDisplay {
+                        characters_per_line: 0,
+                        lines_per_page: 4,
+                    })
+                ),
+                Principal::anonymous(),
+                &token
+            ),
+            Err(Icrc21Error::ConsentMessageUnavailable(_))
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+use candid::{CandidType, Deserialize, Nat};
+use serde::Serialize;
+use std::fmt;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ErrorInfo {
+    pub description: String,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum Icrc21Error {
+    UnsupportedCanisterCall(ErrorInfo),
+    ConsentMessageUnavailable(ErrorInfo),
+    InsufficientPayment(ErrorInfo),
+    GenericError {
+        error_code: Nat,
+        description: String,
+    },
+}
+
+impl fmt::Display for Icrc21Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedCanisterCall(info) => {
+                write!(f, "unsupported canister call: {}", info.description)
+            }
+            Self::ConsentMessageUnavailable(info) => {
+                write!(f, "consent message unavailable: {}", info.description)
+            }
+            Self::InsufficientPayment(info) => {
+                write!(f, "insufficient payment: {}", info.description)
+            }
+            Self::GenericError {
+                error_code,
+                description,
+            } => write!(f, "error {}: {}", error_code, description),
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use super::requests::ConsentMessageMetadata;
+use candid::{CandidType, Deserialize};
+use serde::Serialize;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct LineDisplayPage {
+    pub lines: Vec<String>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum ConsentMessage {
+    /// A Markdown formatted message for devices that can render rich text.
+    GenericDisplayMessage(String),
+    /// Plain text pages for devices with a fixed line display.
+    LineDisplayMessage { pages: Vec<LineDisplayPage> },
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsentInfo {
+    pub consent_message: ConsentMessage,
+    /// The metadata the message was actually rendered with, which might differ
+    /// from the requested one, e.g., if the language is not supported.
+    pub metadata: ConsentMessageMetadata,
+}
@@ -1,9 +1,11 @@
+use crate::icrc::generic_value::{Hash, Value};
+use crate::icrc1::transfer::BlockIndex;
 use crate::icrc3::archive::ArchivedRange;
-use crate::icrc3::archive::QueryBlockArchiveFn;
-use crate::{icrc::generic_value::Value, icrc1::transfer::BlockIndex};
-use candid::{CandidType, Deserialize, Nat};
+use crate::icrc3::archive::{ICRC3ArchiveFn, QueryBlockArchiveFn};
+use candid::{CandidType, Deserialize, Int, Nat};
 use serde::Serialize;
 use serde_bytes::ByteBuf;
+use std::collections::BTreeMap;
 
 pub type GenericBlock = Value;
 
@@ -53,3 +55,179 @@ pub struct DataCertificate {
     pub certificate: Option<serde_bytes::ByteBuf>,
     pub hash_tree: serde_bytes::ByteBuf,
 }
+
+/// A block value as defined by the ICRC-3 standard.
+///
+/// Unlike [Value], this type has no `Nat64` variant: 64-bit numbers are
+/// represented as `Nat`, which has the same representation-independent hash.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ICRC3Value {
+    Blob(ByteBuf),
+    Text(String),
+    Nat(Nat),
+    Int(Int),
+    Array(Vec<ICRC3Value>),
+    Map(BTreeMap<String, ICRC3Value>),
+}
+
+impl ICRC3Value {
+    /// Computes the representation-independent hash of a value.
+    pub fn hash(&self) -> Hash {
+        Value::from(self.clone()).hash()
+    }
+}
+
+impl From<Value> for ICRC3Value {
+    fn from(value: Value) -> Self {
+        match value {
+            Value::Blob(blob) => Self::Blob(blob),
+            Value::Text(text) => Self::Text(text),
+            Value::Nat(nat) => Self::Nat(nat),
+            Value::Nat64(n) => Self::Nat(Nat::from(n)),
+            Value::Int(int) => Self::Int(int),
+            Value::Array(values) => Self::Array(values.into_iter().map(Self::from).collect()),
+            Value::Map(map) => {
+                Self::Map(map.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl From<ICRC3Value> for Value {
+    fn from(value: ICRC3Value) -> Self {
+        match value {
+            ICRC3Value::Blob(blob) => Self::Blob(blob),
+            ICRC3Value::Text(text) => Self::Text(text),
+            ICRC3Value::Nat(nat) => Self::Nat(nat),
+            ICRC3Value::Int(int) => Self::Int(int),
+            ICRC3Value::Array(values) => Self::Array(values.into_iter().map(Self::from).collect()),
+            ICRC3Value::Map(map) => {
+                Self::Map(map.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct BlockWithId {
+    pub id: Nat,
+    pub block: ICRC3Value,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ArchivedBlocks {
+    pub args: Vec<GetBlocksRequest>,
+    pub callback: ICRC3ArchiveFn,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct GetBlocksResult {
+    pub log_length: Nat,
+    pub blocks: Vec<BlockWithId>,
+    pub archived_blocks: Vec<ArchivedBlocks>,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ICRC3DataCertificate {
+    /// See https://internetcomputer.org/docs/current/references/ic-interface-spec#certification
+    pub certificate: ByteBuf,
+    /// CBOR encoded hash_tree
+    pub hash_tree: ByteBuf,
+}
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct SupportedBlockType {
+    pub block_type: String,
+    pub url: String,
+}
+
+/// Returns the value of the `phash` field of an ICRC-3 block, i.e., the hash
+/// of its parent block, or `None` if the block is the first block of the log.
+pub fn get_parent_hash(block: &ICRC3Value) -> Result<Option<Hash>, String> {
+    match block {
+        ICRC3Value::Map(map) => match map.get("phash") {
+            Some(ICRC3Value::Blob(phash)) => phash
+                .as_slice()
+                .try_into()
+                .map(Some)
+                .map_err(|_| format!("phash {} is not a hash", hex::encode(phash))),
+            Some(_) => Err("phash should be a blob".to_string()),
+            None => Ok(None),
+        },
+        _ => Err("top level element should be a map".to_string()),
+    }
+}
+
+/// Checks that the given blocks form a contiguous hash chain, i.e., that
+/// every block stores the hash of the block preceding it in its `phash`
+/// field. `parent_hash` is the hash of the block preceding the first block,
+/// if any.
+///
+/// Returns the hash of the last block, which can be compared with the
+/// `last_block_hash` in the certified tip of the ledger.
+pub fn verify_hash_chain<'a>(
+    parent_hash: Option<Hash>,
+    blocks: impl IntoIterator<Item = &'a ICRC3Value>,
+) -> Result<Option<Hash>, String> {
+    let mut expected_parent_hash = parent_hash;
+    for (i, block) in blocks.into_iter().enumerate() {
+        let phash = get_parent_hash(block).map_err(|e| format!("block #{}: {}", i, e))?;
+        if phash != expected_parent_hash {
+            return Err(format!(
+                "block #{}: phash {:?} does not match the hash of the previous block {:?}",
+                i,
+                phash.map(hex::encode),
+                expected_parent_hash.map(hex::encode),
+            ));
+        }
+        expected_parent_hash = Some(block.hash());
+    }
+    Ok(expected_parent_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(phash: Option<Hash>, amount: u64) -> ICRC3Value {
+        let mut map = BTreeMap::new();
+        if let Some(phash) = phash {
+            map.insert(
+                "phash".to_string(),
+                ICRC3Value::Blob(ByteBuf::from(phash.to_vec())),
+            );
+        }
+        map.insert("amt".to_string(), ICRC3Value::Nat(Nat::from(amount)));
+        ICRC3Value::Map(map)
+    }
+
+    #[test]
+    fn nat64_and_nat_have_the_same_hash() {
+        let value = Value::map(vec![("amt", Value::Nat64(1_000_000))]);
+        assert_eq!(ICRC3Value::from(value.clone()).hash(), value.hash());
+    }
+
+    #[test]
+    fn verify_hash_chain_accepts_linked_blocks() {
+        let b0 = block(None, 1);
+        let b1 = block(Some(b0.hash()), 2);
+        let b2 = block(Some(b1.hash()), 3);
+        assert_eq!(
+            verify_hash_chain(None, [&b0, &b1, &b2]),
+            Ok(Some(b2.hash()))
+        );
+        assert_eq!(
+            verify_hash_chain(Some(b0.hash()), [&b1, &b2]),
+            Ok(Some(b2.hash()))
+        );
+    }
+
+    #[test]
+    fn verify_hash_chain_rejects_broken_links() {
+        let b0 = block(None, 1);
+        let b1 = block(Some(b0.hash()), 2);
+        let b2 = block(Some(b0.hash()), 3);
+        assert!(verify_hash_chain(None, [&b0, &b1, &b2]).is_err());
+        assert!(verify_hash_chain(None, [&b1]).is_err());
+    }
+}
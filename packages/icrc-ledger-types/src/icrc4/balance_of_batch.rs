@@ -0,0 +1,30 @@
+use crate::icrc1::account::Account;
+use candid::{CandidType, Deserialize, Nat};
+use serde::Serialize;
+use std::fmt;
+
+pub type BalanceOfBatchArgs = Vec<Account>;
+
+/// The error returned for a balance query that is rejected as a whole.
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum BalanceOfBatchError {
+    TooManyRequests { limit: Nat },
+    GenericBatchError { error_code: Nat, message: String },
+}
+
+/// The balances of the accounts, in the same order as the arguments.
+pub type BalanceOfBatchResult = Result<Vec<Nat>, BalanceOfBatchError>;
+
+impl fmt::Display for BalanceOfBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyRequests { limit } => {
+                write!(f, "the batch contains more than {} accounts", limit)
+            }
+            Self::GenericBatchError {
+                error_code,
+                message,
+            } => write!(f, "{} {}", error_code, message),
+        }
+    }
+}
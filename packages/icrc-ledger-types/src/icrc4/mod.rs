@@ -0,0 +1,2 @@
+pub mod balance_of_batch;
+pub mod transfer_batch;
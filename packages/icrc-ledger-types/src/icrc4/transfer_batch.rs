@@ -0,0 +1,100 @@
+use crate::icrc1::transfer::{BlockIndex, TransferArg, TransferError};
+use candid::{CandidType, Deserialize, Nat};
+use serde::Serialize;
+use std::fmt;
+
+pub type TransferBatchArgs = Vec<TransferArg>;
+
+#[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub enum TransferBatchError {
+    InsufficientFunds { balance: Nat },
+    BadFee { expected_fee: Nat },
+    BadBurn { min_burn_amount: Nat },
+    Duplicate { duplicate_of: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    TooManyRequests { limit: Nat },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+    GenericBatchError { error_code: Nat, message: String },
+}
+
+/// The result of a single transfer of a batch.
+pub type TransferBatchResult = Result<BlockIndex, TransferBatchError>;
+
+/// The results of the transfers of a batch, in the same order as the arguments.
+/// A transfer that was not processed has no result.
+pub type TransferBatchResults = Vec<Option<TransferBatchResult>>;
+
+/// The response to a batch of transfers. A batch that is rejected as a whole,
+/// e.g. because it contains too many transfers, yields a single
+/// [TransferBatchError::TooManyRequests] or
+/// [TransferBatchError::GenericBatchError] and none of its transfers is
+/// processed.
+///
+/// Batches are not atomic: each transfer is applied on its own, so the
+/// transfers that succeed are applied even if other transfers of the same
+/// batch fail.
+pub type TransferBatchResponse = Result<TransferBatchResults, TransferBatchError>;
+
+impl From<TransferError> for TransferBatchError {
+    fn from(err: TransferError) -> Self {
+        match err {
+            TransferError::BadFee { expected_fee } => Self::BadFee { expected_fee },
+            TransferError::BadBurn { min_burn_amount } => Self::BadBurn { min_burn_amount },
+            TransferError::InsufficientFunds { balance } => Self::InsufficientFunds { balance },
+            TransferError::TooOld => Self::TooOld,
+            TransferError::CreatedInFuture { ledger_time } => Self::CreatedInFuture { ledger_time },
+            TransferError::TemporarilyUnavailable => Self::TemporarilyUnavailable,
+            TransferError::Duplicate { duplicate_of } => Self::Duplicate { duplicate_of },
+            TransferError::GenericError {
+                error_code,
+                message,
+            } => Self::GenericError {
+                error_code,
+                message,
+            },
+        }
+    }
+}
+
+impl fmt::Display for TransferBatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InsufficientFunds { balance } => write!(
+                f,
+                "the debit account doesn't have enough funds to complete the transaction, current balance: {}",
+                balance
+            ),
+            Self::BadFee { expected_fee } => write!(f, "transfer fee should be {}", expected_fee),
+            Self::BadBurn { min_burn_amount } => write!(
+                f,
+                "the minimum number of tokens to be burned is {}",
+                min_burn_amount
+            ),
+            Self::Duplicate { duplicate_of } => write!(
+                f,
+                "transaction is a duplicate of another transaction in block {}",
+                duplicate_of
+            ),
+            Self::TooOld => write!(f, "transaction's created_at_time is too far in the past"),
+            Self::CreatedInFuture { ledger_time } => write!(
+                f,
+                "transaction's created_at_time is in future, current ledger time is {}",
+                ledger_time
+            ),
+            Self::TooManyRequests { limit } => {
+                write!(f, "the batch contains more than {} transfers", limit)
+            }
+            Self::TemporarilyUnavailable => write!(f, "the ledger is temporarily unavailable"),
+            Self::GenericError {
+                error_code,
+                message,
+            }
+            | Self::GenericBatchError {
+                error_code,
+                message,
+            } => write!(f, "{} {}", error_code, message),
+        }
+    }
+}
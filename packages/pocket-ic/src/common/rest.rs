@@ -339,6 +339,7 @@ impl From<SubnetConfigSet> for ExtendedSubnetConfigSet {
             bitcoin: if bitcoin { Some(SubnetSpec::New) } else { None },
             system: vec![SubnetSpec::New; system],
             application: vec![SubnetSpec::New; application],
+            bitcoin_adapter_uds_path: None,
         }
     }
 }
@@ -352,6 +353,12 @@ pub struct ExtendedSubnetConfigSet {
     pub bitcoin: Option<SubnetSpec>,
     pub system: Vec<SubnetSpec>,
     pub application: Vec<SubnetSpec>,
+    /// The Unix domain socket of a Bitcoin adapter for the testnet or regtest
+    /// network to which the Bitcoin subnet is connected, e.g. an adapter
+    /// connected to a local `bitcoind -regtest`. The path must be on a
+    /// filesystem accessible to the server process.
+    #[serde(default)]
+    pub bitcoin_adapter_uds_path: Option<PathBuf>,
 }
 
 /// Specifies whether the subnet should be created from scratch or loaded
@@ -403,6 +410,12 @@ impl ExtendedSubnetConfigSet {
     }
 
     pub fn validate(&self) -> Result<(), String> {
+        if self.bitcoin_adapter_uds_path.is_some() && self.bitcoin.is_none() {
+            return Err(
+                "ExtendedSubnetConfigSet must contain a bitcoin subnet to connect to a Bitcoin adapter"
+                    .to_owned(),
+            );
+        }
         if !self.system.is_empty()
             || !self.application.is_empty()
             || self.nns.is_some()
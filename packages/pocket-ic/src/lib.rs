@@ -179,6 +179,19 @@ impl PocketIcBuilder {
         }
     }
 
+    /// Connect the bitcoin subnet to the Bitcoin adapter listening on the given
+    /// Unix domain socket. The adapter must serve the testnet or regtest network
+    /// and the socket must be accessible to the PocketIC server.
+    /// The Bitcoin canister must be installed at the testnet Bitcoin canister ID.
+    pub fn with_bitcoin_adapter_uds_path(self, bitcoin_adapter_uds_path: PathBuf) -> Self {
+        Self {
+            config: ExtendedSubnetConfigSet {
+                bitcoin_adapter_uds_path: Some(bitcoin_adapter_uds_path),
+                ..self.config
+            },
+        }
+    }
+
     /// Add an empty generic system subnet
     pub fn with_system_subnet(mut self) -> Self {
         self.config.system.push(SubnetSpec::New);
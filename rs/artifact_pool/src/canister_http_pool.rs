@@ -189,7 +189,9 @@ mod tests {
     use ic_test_utilities::{consensus::fake::FakeSigner, types::ids::node_test_id};
     use ic_test_utilities_time::mock_time;
     use ic_types::{
-        canister_http::{CanisterHttpResponseContent, CanisterHttpResponseMetadata},
+        canister_http::{
+            CanisterHttpResponseContent, CanisterHttpResponseMetadata, CanisterHttpResponseSummary,
+        },
         crypto::{CryptoHash, Signed},
         messages::CallbackId,
         signature::BasicSignature,
@@ -215,6 +217,7 @@ mod tests {
                 timeout: mock_time(),
                 content_hash: CryptoHashOf::from(CryptoHash(vec![1, 2, 3])),
                 registry_version: RegistryVersion::from(id),
+                summary: CanisterHttpResponseSummary::default(),
             },
             signature: BasicSignature::fake(node_test_id(id)),
         }
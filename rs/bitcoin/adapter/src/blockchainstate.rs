@@ -1,12 +1,22 @@
 //! The module is responsible for keeping track of the blockchain state.
 //!
-use crate::{common::BlockHeight, config::Config, metrics::BlockchainStateMetrics};
+use crate::{
+    common::BlockHeight, config::Config, header_log::HeaderLog, metrics::BlockchainStateMetrics,
+};
 use bitcoin::{blockdata::constants::genesis_block, Block, BlockHash, BlockHeader, Network};
 use ic_btc_validation::{validate_header, HeaderStore, ValidateHeaderError};
+use ic_logger::{info, warn, ReplicaLogger};
 use ic_metrics::MetricsRegistry;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Instant,
+};
 use thiserror::Error;
 
+/// Forks whose tip is more than this number of blocks below the active tip are removed
+/// from the header cache. Such forks are extremely unlikely to become the active chain.
+const STALE_FORK_DEPTH: BlockHeight = 1_000;
+
 /// This field contains the datatype used to store "work" of a Bitcoin blockchain
 pub type Work = bitcoin::util::uint::Uint256;
 
@@ -100,6 +110,9 @@ pub struct BlockchainState {
     /// Used to determine how validation should be handled with `validate_header`.
     network: Network,
     metrics: BlockchainStateMetrics,
+
+    /// The file persisting the headers of the header cache, if enabled in the config.
+    header_log: Option<HeaderLog>,
 }
 
 impl BlockchainState {
@@ -122,9 +135,85 @@ impl BlockchainState {
             tips,
             network: config.network,
             metrics: BlockchainStateMetrics::new(metrics_registry),
+            header_log: None,
         }
     }
 
+    /// Creates a new BlockChainState object initialized with the headers persisted in the
+    /// cache directory of the config. Headers added later are persisted as well.
+    ///
+    /// Persisted headers are validated again while loading. The log is truncated at the first
+    /// invalid header and compacted if it contains duplicate headers or stale forks.
+    /// Without a cache directory, this function is equivalent to [BlockchainState::new].
+    pub fn load(
+        config: &Config,
+        metrics_registry: &MetricsRegistry,
+        logger: ReplicaLogger,
+    ) -> Self {
+        let mut state = Self::new(config, metrics_registry);
+        let cache_dir = match &config.cache_dir {
+            Some(cache_dir) => cache_dir,
+            None => return state,
+        };
+
+        let start = Instant::now();
+        let (mut header_log, loaded) =
+            match HeaderLog::open(cache_dir, config.network, logger.clone()) {
+                Ok(result) => result,
+                Err(err) => {
+                    warn!(
+                        logger,
+                        "Failed to open the header log in {}, headers will not be persisted: {}",
+                        cache_dir.display(),
+                        err
+                    );
+                    return state;
+                }
+            };
+
+        if loaded.discarded_bytes > 0 {
+            warn!(
+                logger,
+                "Discarded {} bytes of an incomplete header at the end of the header log",
+                loaded.discarded_bytes
+            );
+        }
+
+        let (_, maybe_err) = state.add_headers(&loaded.headers);
+        if let Some(err) = maybe_err {
+            warn!(
+                logger,
+                "Dropping the headers of the header log starting from an invalid header: {}", err
+            );
+        }
+
+        // The genesis header is not persisted.
+        let cached_headers = state.header_cache.len() - 1;
+        if cached_headers != loaded.headers.len() {
+            header_log.rewrite(state.headers_in_insertion_order().iter());
+        }
+        state.header_log = Some(header_log);
+
+        let elapsed = start.elapsed();
+        state
+            .metrics
+            .header_log_load_duration
+            .set(elapsed.as_secs_f64());
+        state
+            .metrics
+            .header_log_loaded_headers
+            .set(cached_headers as i64);
+        info!(
+            logger,
+            "Loaded {} headers from the header log in {:?}, the active tip is at height {}",
+            cached_headers,
+            elapsed,
+            state.get_active_chain_tip().height
+        );
+
+        state
+    }
+
     /// Returns the genesis header that the store is initialized with.
     pub fn genesis(&self) -> &BlockHeader {
         &self.genesis_block_header
@@ -160,6 +249,10 @@ impl BlockchainState {
 
         // Sort the tips by the total work
         self.tips.sort_unstable_by(|a, b| b.work.cmp(&a.work));
+        self.prune_stale_forks();
+        if let Some(header_log) = self.header_log.as_mut() {
+            header_log.flush();
+        }
         self.metrics.tips.set(self.tips.len() as i64);
         self.metrics
             .tip_height
@@ -220,6 +313,9 @@ impl BlockchainState {
         };
 
         self.header_cache.insert(block_hash, cached_header);
+        if let Some(header_log) = self.header_log.as_mut() {
+            header_log.append(&header);
+        }
 
         self.metrics.header_cache_size.inc();
         Ok(AddHeaderResult::HeaderAdded(header.block_hash()))
@@ -238,6 +334,9 @@ impl BlockchainState {
             .add_header(block.header)
             .map_err(AddBlockError::Header)?;
         self.tips.sort_unstable_by(|a, b| b.work.cmp(&a.work));
+        if let Some(header_log) = self.header_log.as_mut() {
+            header_log.flush();
+        }
         self.block_cache.insert(block_hash, block);
         self.metrics
             .block_cache_size
@@ -248,6 +347,56 @@ impl BlockchainState {
         Ok(())
     }
 
+    /// Removes the forks whose tip is more than [STALE_FORK_DEPTH] blocks below the active tip
+    /// from the header cache, along with their cached blocks.
+    fn prune_stale_forks(&mut self) {
+        let min_height = self
+            .get_active_chain_tip()
+            .height
+            .saturating_sub(STALE_FORK_DEPTH);
+        // The active tip is never stale, and partitioning keeps the tips sorted.
+        let (stale_tips, tips): (Vec<_>, Vec<_>) = std::mem::take(&mut self.tips)
+            .into_iter()
+            .partition(|tip| tip.height < min_height);
+        self.tips = tips;
+
+        for tip in stale_tips {
+            // Walk down the fork until reaching a header that still has other children.
+            let mut hash = tip.header.block_hash();
+            while let Some(node) = self.header_cache.get(&hash) {
+                if !node.children.is_empty() {
+                    break;
+                }
+                let prev_hash = node.header.prev_blockhash;
+                self.header_cache.remove(&hash);
+                self.block_cache.remove(&hash);
+                self.metrics.header_cache_size.dec();
+                self.metrics.pruned_headers.inc();
+                match self.header_cache.get_mut(&prev_hash) {
+                    Some(parent) => parent.children.retain(|child| child != &hash),
+                    None => break,
+                }
+                hash = prev_hash;
+            }
+        }
+    }
+
+    /// Returns the cached headers, excluding the genesis header, such that every header comes
+    /// after its parent.
+    fn headers_in_insertion_order(&self) -> Vec<BlockHeader> {
+        let mut headers = Vec::with_capacity(self.header_cache.len());
+        let mut queue: VecDeque<_> = VecDeque::from([self.genesis_block_header.block_hash()]);
+        while let Some(hash) = queue.pop_front() {
+            if let Some(node) = self.header_cache.get(&hash) {
+                if hash != self.genesis_block_header.block_hash() {
+                    headers.push(node.header);
+                }
+                queue.extend(node.children.iter().copied());
+            }
+        }
+        headers
+    }
+
     /// This method returns the tip header with the highest cumulative work.
     #[allow(clippy::indexing_slicing)]
     pub fn get_active_chain_tip(&self) -> &Tip {
@@ -355,6 +504,7 @@ mod test {
     use super::*;
     use crate::{common::test_common::TestState, config::test::ConfigBuilder};
     use ic_btc_adapter_test_utils::{block_1, block_2, generate_header, generate_headers};
+    use ic_logger::replica_logger::no_op_logger;
     use std::collections::HashSet;
 
     #[test]
@@ -683,6 +833,117 @@ mod test {
         assert_eq!(state.get_active_chain_tip().header, h4);
     }
 
+    /// Tests that forks falling more than `STALE_FORK_DEPTH` blocks behind the active tip
+    /// are removed from the header cache.
+    #[test]
+    fn test_stale_forks_are_pruned() {
+        let config = ConfigBuilder::new().with_network(Network::Regtest).build();
+        let mut state = BlockchainState::new(&config, &MetricsRegistry::default());
+        let initial_header = *state.genesis();
+
+        let chain = generate_headers(initial_header.block_hash(), initial_header.time, 16, &[]);
+        let chain_hashes: Vec<BlockHash> = chain.iter().map(|header| header.block_hash()).collect();
+        state.add_headers(&chain);
+
+        let fork_chain = generate_headers(chain_hashes[10], chain[10].time, 2, &chain_hashes);
+        state.add_headers(&fork_chain);
+        assert_eq!(state.tips.len(), 2);
+
+        let last_header = chain.last().unwrap();
+        let extension = generate_headers(
+            last_header.block_hash(),
+            last_header.time,
+            STALE_FORK_DEPTH,
+            &[],
+        );
+        let (_, maybe_err) = state.add_headers(&extension);
+        assert!(maybe_err.is_none());
+
+        assert_eq!(state.tips.len(), 1);
+        assert_eq!(state.get_active_chain_tip().height, 16 + STALE_FORK_DEPTH);
+        for header in &fork_chain {
+            assert!(state.get_cached_header(&header.block_hash()).is_none());
+        }
+        assert_eq!(
+            state.get_cached_header(&chain_hashes[10]).unwrap().children,
+            vec![chain_hashes[11]]
+        );
+        assert_eq!(
+            state.header_cache.len(),
+            (16 + STALE_FORK_DEPTH + 1) as usize
+        );
+    }
+
+    /// Tests that headers persisted in the cache directory are loaded on restart.
+    #[test]
+    fn test_headers_are_loaded_from_the_header_log() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ConfigBuilder::new()
+            .with_network(Network::Regtest)
+            .with_cache_dir(dir.path().to_path_buf())
+            .build();
+
+        let mut state = BlockchainState::load(&config, &MetricsRegistry::default(), no_op_logger());
+        let initial_header = *state.genesis();
+        let chain = generate_headers(initial_header.block_hash(), initial_header.time, 16, &[]);
+        let chain_hashes: Vec<BlockHash> = chain.iter().map(|header| header.block_hash()).collect();
+        state.add_headers(&chain);
+        let fork_chain = generate_headers(chain_hashes[10], chain[10].time, 2, &chain_hashes);
+        state
+            .add_block(Block {
+                header: fork_chain[0],
+                txdata: Vec::new(),
+            })
+            .unwrap();
+        drop(state);
+
+        let state = BlockchainState::load(&config, &MetricsRegistry::default(), no_op_logger());
+        assert_eq!(state.tips.len(), 2);
+        assert_eq!(state.get_active_chain_tip().height, 16);
+        assert_eq!(
+            state.get_active_chain_tip().header.block_hash(),
+            chain_hashes[15]
+        );
+        assert!(state
+            .get_cached_header(&fork_chain[0].block_hash())
+            .is_some());
+        assert_eq!(state.metrics.header_log_loaded_headers.get(), 17);
+    }
+
+    /// Tests that the header log is truncated at the first header that fails validation.
+    #[test]
+    fn test_header_log_is_truncated_at_invalid_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ConfigBuilder::new()
+            .with_network(Network::Regtest)
+            .with_cache_dir(dir.path().to_path_buf())
+            .build();
+
+        let mut state = BlockchainState::load(&config, &MetricsRegistry::default(), no_op_logger());
+        let initial_header = *state.genesis();
+        let chain = generate_headers(initial_header.block_hash(), initial_header.time, 16, &[]);
+        state.add_headers(&chain);
+        drop(state);
+
+        // Corrupt the previous block hash of the sixth header.
+        let path = dir.path().join(crate::header_log::HEADER_LOG_FILE_NAME);
+        let mut bytes = std::fs::read(&path).unwrap();
+        let offset = 4 + 5 * 80 + 4;
+        bytes[offset..offset + 32].fill(0);
+        std::fs::write(&path, bytes).unwrap();
+
+        let state = BlockchainState::load(&config, &MetricsRegistry::default(), no_op_logger());
+        assert_eq!(state.get_active_chain_tip().height, 5);
+        assert_eq!(
+            state.get_active_chain_tip().header.block_hash(),
+            chain[4].block_hash()
+        );
+        drop(state);
+
+        // The log only contains the valid headers after the compaction.
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 4 + 5 * 80);
+    }
+
     /// Test header store `get_header` function.
     #[test]
     fn test_headerstore_get_header() {
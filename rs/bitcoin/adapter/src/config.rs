@@ -49,6 +49,11 @@ pub struct Config {
     /// Specifies the address limits used by the `AddressBook`.
     #[serde(default)]
     pub address_limits: (usize, usize),
+    /// The directory in which the adapter persists the header chain, so that it does not
+    /// need to download all headers again after a restart.
+    /// Headers are only kept in memory if this field is not set.
+    #[serde(default)]
+    pub cache_dir: Option<PathBuf>,
 }
 
 /// Set the default idle seconds to one hour.
@@ -90,6 +95,7 @@ impl Default for Config {
             logger: LoggerConfig::default(),
             incoming_source: Default::default(),
             address_limits: address_limits(Network::Bitcoin), // Address limits used for Bitcoin mainnet
+            cache_dir: None,
         }
     }
 }
@@ -132,6 +138,11 @@ pub mod test {
             self
         }
 
+        pub fn with_cache_dir(mut self, cache_dir: PathBuf) -> Self {
+            self.config.cache_dir = Some(cache_dir);
+            self
+        }
+
         pub fn build(self) -> Config {
             self.config
         }
@@ -0,0 +1,281 @@
+//! An append-only file persisting the Bitcoin headers known to the adapter, so that
+//! the header chain does not have to be downloaded again after a restart.
+//!
+//! The file starts with the magic bytes of the Bitcoin network followed by a sequence of
+//! consensus-encoded headers. Every header is written after its parent, so that replaying
+//! the file in order rebuilds the header tree.
+use bitcoin::{
+    consensus::{deserialize, serialize},
+    BlockHeader, Network,
+};
+use ic_logger::{warn, ReplicaLogger};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+/// The name of the header log file in the adapter cache directory.
+pub const HEADER_LOG_FILE_NAME: &str = "headers.bin";
+
+/// The size of a consensus-encoded block header.
+const HEADER_SIZE: usize = 80;
+
+/// The size of the file prefix containing the network magic.
+const MAGIC_SIZE: usize = 4;
+
+/// The headers read from a header log.
+pub struct LoadedHeaders {
+    /// The decoded headers in the order they were appended.
+    pub headers: Vec<BlockHeader>,
+    /// The number of trailing bytes that did not form a complete header, e.g., because the
+    /// adapter stopped while appending a header. These bytes are removed from the file.
+    pub discarded_bytes: u64,
+}
+
+/// Appends headers to the header log file.
+pub struct HeaderLog {
+    path: PathBuf,
+    network: Network,
+    writer: Option<BufWriter<File>>,
+    logger: ReplicaLogger,
+}
+
+impl std::fmt::Debug for HeaderLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HeaderLog")
+            .field("path", &self.path)
+            .field("network", &self.network)
+            .field("failed", &self.writer.is_none())
+            .finish()
+    }
+}
+
+impl HeaderLog {
+    /// Opens the header log in the given directory, creating it if it does not exist,
+    /// and returns the headers it contains.
+    ///
+    /// Fails if the file belongs to a different network or cannot be accessed.
+    pub fn open(
+        cache_dir: &Path,
+        network: Network,
+        logger: ReplicaLogger,
+    ) -> io::Result<(Self, LoadedHeaders)> {
+        fs::create_dir_all(cache_dir)?;
+        let path = cache_dir.join(HEADER_LOG_FILE_NAME);
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        let mut bytes = vec![];
+        file.read_to_end(&mut bytes)?;
+
+        if bytes.len() < MAGIC_SIZE {
+            // The file is new or the adapter stopped before writing the magic.
+            file.set_len(0)?;
+            file.seek(SeekFrom::Start(0))?;
+            file.write_all(&network.magic().to_le_bytes())?;
+            file.sync_all()?;
+            bytes = network.magic().to_le_bytes().to_vec();
+        }
+
+        let (magic, records) = bytes.split_at(MAGIC_SIZE);
+        if magic != network.magic().to_le_bytes() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the header log {} does not belong to network {}",
+                    path.display(),
+                    network
+                ),
+            ));
+        }
+
+        let chunks = records.chunks_exact(HEADER_SIZE);
+        let discarded_bytes = chunks.remainder().len() as u64;
+        let headers = chunks
+            .map(deserialize::<BlockHeader>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        if discarded_bytes > 0 {
+            file.set_len(bytes.len() as u64 - discarded_bytes)?;
+        }
+        file.seek(SeekFrom::End(0))?;
+
+        Ok((
+            Self {
+                path,
+                network,
+                writer: Some(BufWriter::new(file)),
+                logger,
+            },
+            LoadedHeaders {
+                headers,
+                discarded_bytes,
+            },
+        ))
+    }
+
+    /// Appends a header to the log. The header is only guaranteed to be written after
+    /// the next call to [HeaderLog::flush].
+    pub fn append(&mut self, header: &BlockHeader) {
+        let result = match self.writer.as_mut() {
+            Some(writer) => writer.write_all(&serialize(header)),
+            None => return,
+        };
+        self.handle_result(result);
+    }
+
+    /// Writes the buffered headers to the file.
+    pub fn flush(&mut self) {
+        let result = match self.writer.as_mut() {
+            Some(writer) => writer.flush(),
+            None => return,
+        };
+        self.handle_result(result);
+    }
+
+    /// Replaces the content of the log with the given headers, e.g., to drop pruned forks.
+    /// Every header must come after its parent.
+    pub fn rewrite<'a>(&mut self, headers: impl Iterator<Item = &'a BlockHeader>) {
+        let result = self.try_rewrite(headers);
+        self.handle_result(result);
+    }
+
+    fn try_rewrite<'a>(
+        &mut self,
+        headers: impl Iterator<Item = &'a BlockHeader>,
+    ) -> io::Result<()> {
+        // Write the new content to a temporary file first and atomically rename it,
+        // so that the log is never left half-written.
+        let tmp_path = self.path.with_extension("tmp");
+        let mut tmp = BufWriter::new(File::create(&tmp_path)?);
+        tmp.write_all(&self.network.magic().to_le_bytes())?;
+        for header in headers {
+            tmp.write_all(&serialize(header))?;
+        }
+        tmp.into_inner()
+            .map_err(|err| err.into_error())?
+            .sync_all()?;
+        // Drop the handle to the old file before replacing it.
+        self.writer = None;
+        fs::rename(&tmp_path, &self.path)?;
+        let file = OpenOptions::new().append(true).open(&self.path)?;
+        self.writer = Some(BufWriter::new(file));
+        Ok(())
+    }
+
+    /// Stops writing to the log after the first error. Headers appended after a failed write
+    /// could follow a header missing from the file, which would break the replay.
+    fn handle_result(&mut self, result: io::Result<()>) {
+        if let Err(err) = result {
+            warn!(
+                self.logger,
+                "Failed to write to the header log {}, headers are no longer persisted: {}",
+                self.path.display(),
+                err
+            );
+            self.writer = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bitcoin::blockdata::constants::genesis_block;
+    use ic_btc_adapter_test_utils::generate_headers;
+    use ic_logger::replica_logger::no_op_logger;
+
+    fn test_headers(count: usize) -> Vec<BlockHeader> {
+        let genesis = genesis_block(Network::Regtest).header;
+        generate_headers(genesis.block_hash(), genesis.time, count as u32, &[])
+    }
+
+    #[test]
+    fn test_appended_headers_are_loaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let headers = test_headers(10);
+
+        let (mut log, loaded) = HeaderLog::open(dir.path(), Network::Regtest, no_op_logger())
+            .expect("failed to create the header log");
+        assert!(loaded.headers.is_empty());
+        for header in &headers {
+            log.append(header);
+        }
+        log.flush();
+        drop(log);
+
+        let (_, loaded) = HeaderLog::open(dir.path(), Network::Regtest, no_op_logger())
+            .expect("failed to open the header log");
+        assert_eq!(loaded.headers, headers);
+        assert_eq!(loaded.discarded_bytes, 0);
+    }
+
+    #[test]
+    fn test_incomplete_header_is_discarded() {
+        let dir = tempfile::tempdir().unwrap();
+        let headers = test_headers(3);
+
+        let (mut log, _) = HeaderLog::open(dir.path(), Network::Regtest, no_op_logger()).unwrap();
+        for header in &headers {
+            log.append(header);
+        }
+        log.flush();
+        drop(log);
+
+        // Simulate a crash in the middle of appending a header.
+        let path = dir.path().join(HEADER_LOG_FILE_NAME);
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&serialize(&headers[0])[..HEADER_SIZE / 2])
+            .unwrap();
+        drop(file);
+
+        let (mut log, loaded) =
+            HeaderLog::open(dir.path(), Network::Regtest, no_op_logger()).unwrap();
+        assert_eq!(loaded.headers, headers);
+        assert_eq!(loaded.discarded_bytes, (HEADER_SIZE / 2) as u64);
+
+        // Appending after the truncation keeps the file consistent.
+        let more_headers = test_headers(4);
+        log.append(&more_headers[3]);
+        log.flush();
+        drop(log);
+
+        let (_, loaded) = HeaderLog::open(dir.path(), Network::Regtest, no_op_logger()).unwrap();
+        assert_eq!(loaded.headers.len(), 4);
+        assert_eq!(loaded.discarded_bytes, 0);
+    }
+
+    #[test]
+    fn test_log_of_another_network_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        HeaderLog::open(dir.path(), Network::Regtest, no_op_logger()).unwrap();
+
+        let err = HeaderLog::open(dir.path(), Network::Bitcoin, no_op_logger())
+            .expect_err("opening the log of another network should fail");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_rewrite_replaces_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let headers = test_headers(10);
+
+        let (mut log, _) = HeaderLog::open(dir.path(), Network::Regtest, no_op_logger()).unwrap();
+        for header in &headers {
+            log.append(header);
+        }
+        log.rewrite(headers[..5].iter());
+        log.append(&headers[5]);
+        log.flush();
+        drop(log);
+
+        let (_, loaded) = HeaderLog::open(dir.path(), Network::Regtest, no_op_logger()).unwrap();
+        assert_eq!(loaded.headers, headers[..6].to_vec());
+    }
+}
@@ -34,6 +34,8 @@ mod connection;
 /// This module contains code that is used to manage multiple connections to
 /// BTC nodes.
 mod connectionmanager;
+/// This module contains the append-only file persisting the header chain across restarts.
+mod header_log;
 mod metrics;
 /// The module is responsible for awaiting messages from bitcoin peers and dispaching them
 /// to the correct component.
@@ -200,7 +202,11 @@ pub fn start_grpc_server_and_router(
     adapter_state: AdapterState,
 ) {
     let (blockchain_manager_tx, blockchain_manager_rx) = channel(100);
-    let blockchain_state = Arc::new(Mutex::new(BlockchainState::new(config, metrics_registry)));
+    let blockchain_state = Arc::new(Mutex::new(BlockchainState::load(
+        config,
+        metrics_registry,
+        logger.clone(),
+    )));
     let get_successors_handler = GetSuccessorsHandler::new(
         config,
         // The get successor handler should be low latency, and instead of not sharing state and
@@ -2,7 +2,7 @@ use ic_metrics::{
     buckets::{decimal_buckets, exponential_buckets},
     MetricsRegistry,
 };
-use prometheus::{Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge};
+use prometheus::{Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge};
 
 pub(crate) const LABEL_GET_SUCCESSOR: &str = "get_successor";
 pub(crate) const LABEL_REQUEST_TYPE: &str = "type";
@@ -96,6 +96,9 @@ pub struct BlockchainStateMetrics {
     pub block_cache_elements: IntGauge,
     pub header_cache_size: IntGauge,
     pub tips: IntGauge,
+    pub pruned_headers: IntCounter,
+    pub header_log_load_duration: Gauge,
+    pub header_log_loaded_headers: IntGauge,
 }
 
 impl BlockchainStateMetrics {
@@ -113,6 +116,18 @@ impl BlockchainStateMetrics {
                 "Number of headers stored in the adapter.",
             ),
             tips: metrics_registry.int_gauge("blockchain_tips", "Number of active tips."),
+            pruned_headers: metrics_registry.int_counter(
+                "pruned_headers_total",
+                "Number of headers of stale forks removed from the header cache.",
+            ),
+            header_log_load_duration: metrics_registry.gauge(
+                "header_log_load_duration_seconds",
+                "Time spent loading and validating the persisted headers at startup.",
+            ),
+            header_log_loaded_headers: metrics_registry.int_gauge(
+                "header_log_loaded_headers",
+                "Number of persisted headers loaded at startup.",
+            ),
         }
     }
 }
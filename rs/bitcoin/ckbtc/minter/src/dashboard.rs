@@ -360,6 +360,9 @@ pub fn build_submitted_transactions() -> String {
                         .unwrap();
 
                         write!(buf, "<td rowspan='{}'>", rowspan).unwrap();
+                        if tx.requests.is_empty() {
+                            write!(buf, "<i>UTXO consolidation</i>").unwrap();
+                        }
                         for req in &tx.requests {
                             write!(
                                 buf,
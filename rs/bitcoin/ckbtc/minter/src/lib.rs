@@ -68,6 +68,25 @@ pub const CKBTC_LEDGER_MEMO_SIZE: u16 = 80;
 /// when building transactions.
 pub const UTXOS_COUNT_THRESHOLD: usize = 1_000;
 
+/// The number of available UTXOs above which the minter starts consolidating
+/// its smallest UTXOs into a single output to its main address.
+pub const UTXOS_CONSOLIDATION_THRESHOLD: usize = 10_000;
+
+/// The maximum number of inputs of a consolidation transaction.
+/// Keeps the transaction well below the standard transaction size limit.
+pub const MAX_CONSOLIDATION_INPUTS: usize = 500;
+
+/// The maximum median fee (in millisatoshi per vbyte) at which the minter
+/// submits consolidation transactions.
+pub const MAX_CONSOLIDATION_FEE_PER_VBYTE: MillisatoshiPerByte = 10_000;
+
+/// The minimum value of a transaction output.
+/// The default dustRelayFee is 3 sat/vB,
+/// which translates to a dust threshold of 546 satoshi for P2PKH outputs.
+/// The threshold for other types is lower,
+/// so we simply use 546 satoshi as the minimum amount per output.
+const MIN_OUTPUT_AMOUNT: u64 = 546;
+
 #[derive(Clone, serde::Serialize, Deserialize, Debug)]
 pub enum Priority {
     P0,
@@ -410,6 +429,155 @@ async fn submit_pending_requests() {
     }
 }
 
+/// Consolidates the smallest UTXOs of the minter into a single output to its
+/// main address if the minter manages more than [UTXOS_CONSOLIDATION_THRESHOLD]
+/// UTXOs and the fees are low.
+///
+/// Without consolidation, large withdrawals might need more inputs than fit into
+/// a standard transaction. The Bitcoin fee of a consolidation transaction is
+/// deducted from the consolidated UTXOs, i.e., the minter pays it out of the
+/// minter fees it collected on withdrawals.
+async fn consolidate_utxos() {
+    let (utxo_count, consolidation_in_progress) = state::read_state(|s| {
+        (
+            s.available_utxos.len(),
+            s.submitted_transactions
+                .iter()
+                .any(|tx| tx.requests.is_empty()),
+        )
+    });
+    // We wait for the previous consolidation transaction to finalize before
+    // sending a new one to limit the amount of BTC locked in unconfirmed
+    // transactions.
+    if utxo_count <= UTXOS_CONSOLIDATION_THRESHOLD || consolidation_in_progress {
+        return;
+    }
+
+    let fee_millisatoshi_per_vbyte = match estimate_fee_per_vbyte().await {
+        Some(fee) => fee,
+        None => return,
+    };
+
+    if fee_millisatoshi_per_vbyte > MAX_CONSOLIDATION_FEE_PER_VBYTE {
+        log!(
+            P1,
+            "[consolidate_utxos]: postponing the consolidation of {} UTXOs: the median fee {} exceeds {} millisatoshi/vbyte",
+            utxo_count,
+            fee_millisatoshi_per_vbyte,
+            MAX_CONSOLIDATION_FEE_PER_VBYTE
+        );
+        return;
+    }
+
+    let main_account = Account {
+        owner: ic_cdk::id(),
+        subaccount: None,
+    };
+
+    let ecdsa_public_key = updates::get_btc_address::init_ecdsa_public_key().await;
+    let main_address = address::account_to_bitcoin_address(&ecdsa_public_key, &main_account);
+
+    let maybe_sign_request = state::mutate_state(|s| {
+        match build_consolidation_transaction(
+            &mut s.available_utxos,
+            main_address,
+            fee_millisatoshi_per_vbyte,
+        ) {
+            Ok((unsigned_tx, change_output, utxos)) => Some(SignTxRequest {
+                key_name: s.ecdsa_key_name.clone(),
+                ecdsa_public_key,
+                change_output,
+                outpoint_account: filter_output_accounts(s, &unsigned_tx),
+                network: s.btc_network,
+                unsigned_tx,
+                requests: vec![],
+                utxos,
+            }),
+            Err(err) => {
+                log!(
+                    P0,
+                    "[consolidate_utxos]: failed to build a consolidation transaction: {:?}",
+                    err
+                );
+                None
+            }
+        }
+    });
+
+    let req = match maybe_sign_request {
+        Some(req) => req,
+        None => return,
+    };
+
+    log!(
+        P1,
+        "[consolidate_utxos]: signing a new consolidation transaction with {} inputs: {}",
+        req.utxos.len(),
+        hex::encode(tx::encode_into(&req.unsigned_tx, Vec::new()))
+    );
+
+    // This guard ensures that we return the UTXOs back to the state if the
+    // signing or sending a transaction fails or panics.
+    let utxos_guard = guard(req.utxos, |utxos| {
+        undo_sign_request(vec![], utxos);
+    });
+
+    let txid = req.unsigned_tx.txid();
+
+    let signed_tx = match sign_transaction(
+        req.key_name,
+        &req.ecdsa_public_key,
+        &req.outpoint_account,
+        req.unsigned_tx,
+    )
+    .await
+    {
+        Ok(signed_tx) => signed_tx,
+        Err(err) => {
+            log!(
+                P0,
+                "[consolidate_utxos]: failed to sign a BTC transaction: {}",
+                err
+            );
+            return;
+        }
+    };
+
+    match management::send_transaction(&signed_tx, req.network).await {
+        Ok(()) => {
+            log!(
+                P0,
+                "[consolidate_utxos]: sent consolidation transaction {}",
+                &txid,
+            );
+
+            // Defuse the guard because we sent the transaction successfully.
+            let used_utxos = ScopeGuard::into_inner(utxos_guard);
+
+            state::mutate_state(|s| {
+                state::audit::sent_transaction(
+                    s,
+                    state::SubmittedBtcTransaction {
+                        requests: vec![],
+                        txid,
+                        used_utxos,
+                        change_output: Some(req.change_output),
+                        submitted_at: ic_cdk::api::time(),
+                        fee_per_vbyte: Some(fee_millisatoshi_per_vbyte),
+                    },
+                );
+            });
+        }
+        Err(err) => {
+            log!(
+                P0,
+                "[consolidate_utxos]: failed to send a bitcoin transaction: {}",
+                err
+            );
+        }
+    }
+}
+
 fn finalization_time_estimate(min_confirmations: u32, network: Network) -> Duration {
     Duration::from_nanos(
         min_confirmations as u64
@@ -620,18 +788,21 @@ async fn finalize_requests() {
             None => fee_per_vbyte,
         };
 
-        let outputs = submitted_tx
-            .requests
-            .iter()
-            .map(|req| (req.address.clone(), req.amount))
-            .collect();
+        let maybe_tx = if submitted_tx.requests.is_empty() {
+            // Consolidation transactions have no requests, their only output
+            // goes to the minter's main address.
+            build_consolidation_transaction(&mut utxos, main_address.clone(), tx_fee_per_vbyte)
+        } else {
+            let outputs = submitted_tx
+                .requests
+                .iter()
+                .map(|req| (req.address.clone(), req.amount))
+                .collect();
 
-        let (unsigned_tx, change_output, used_utxos) = match build_unsigned_transaction(
-            &mut utxos,
-            outputs,
-            main_address.clone(),
-            tx_fee_per_vbyte,
-        ) {
+            build_unsigned_transaction(&mut utxos, outputs, main_address.clone(), tx_fee_per_vbyte)
+        };
+
+        let (unsigned_tx, change_output, used_utxos) = match maybe_tx {
             Ok(tx) => tx,
             // If it's impossible to build a new transaction, the fees probably became too high.
             // Let's ignore this transaction and wait for fees to go down.
@@ -1028,11 +1199,6 @@ pub fn build_unsigned_transaction(
     }
 
     let fee_shares = distribute(fee + minter_fee, outputs.len() as u64);
-    // The default dustRelayFee is 3 sat/vB,
-    // which translates to a dust threshold of 546 satoshi for P2PKH outputs.
-    // The threshold for other types is lower,
-    // so we simply use 546 satoshi as the minimum amount per output.
-    const MIN_OUTPUT_AMOUNT: u64 = 546;
 
     for (output, fee_share) in unsigned_tx.outputs.iter_mut().zip(fee_shares.iter()) {
         if output.address != main_address {
@@ -1058,6 +1224,74 @@ pub fn build_unsigned_transaction(
     ))
 }
 
+/// Builds a transaction that moves up to [MAX_CONSOLIDATION_INPUTS] of the
+/// smallest UTXOs from the specified set to a single output to the minter's
+/// main address. The Bitcoin fee is deducted from the output.
+///
+/// The output is the transaction change output, so consolidation transactions
+/// are finalized and resubmitted like withdrawal transactions.
+///
+/// # Error case properties
+///
+/// * In case of errors, the function does not modify the inputs.
+/// ```text
+/// result.is_err() => minter_utxos' == minter_utxos
+/// ```
+pub fn build_consolidation_transaction(
+    minter_utxos: &mut BTreeSet<Utxo>,
+    main_address: BitcoinAddress,
+    fee_per_vbyte: u64,
+) -> Result<(tx::UnsignedTransaction, state::ChangeOutput, Vec<Utxo>), BuildTxError> {
+    /// See the comment in [build_unsigned_transaction].
+    const SEQUENCE_RBF_ENABLED: u32 = 0xfffffffd;
+
+    let mut input_utxos: Vec<_> = minter_utxos.iter().cloned().collect();
+    input_utxos.sort_by_key(|u| u.value);
+    input_utxos.truncate(MAX_CONSOLIDATION_INPUTS);
+
+    if input_utxos.is_empty() {
+        return Err(BuildTxError::NotEnoughFunds);
+    }
+
+    let inputs_value = input_utxos.iter().map(|u| u.value).sum::<u64>();
+
+    let mut unsigned_tx = tx::UnsignedTransaction {
+        inputs: input_utxos
+            .iter()
+            .map(|utxo| tx::UnsignedInput {
+                previous_output: utxo.outpoint.clone(),
+                value: utxo.value,
+                sequence: SEQUENCE_RBF_ENABLED,
+            })
+            .collect(),
+        outputs: vec![tx::TxOut {
+            address: main_address,
+            value: inputs_value,
+        }],
+        lock_time: 0,
+    };
+
+    let tx_vsize = fake_sign(&unsigned_tx).vsize();
+    let fee = (tx_vsize as u64 * fee_per_vbyte) / 1000;
+
+    if fee + MIN_OUTPUT_AMOUNT > inputs_value {
+        return Err(BuildTxError::AmountTooLow);
+    }
+
+    unsigned_tx.outputs[0].value = inputs_value - fee;
+
+    for utxo in input_utxos.iter() {
+        assert!(minter_utxos.remove(utxo));
+    }
+
+    let change_output = state::ChangeOutput {
+        vout: 0,
+        value: inputs_value - fee,
+    };
+
+    Ok((unsigned_tx, change_output, input_utxos))
+}
+
 /// Distributes an amount across the specified number of shares as fairly as
 /// possible.
 ///
@@ -1191,6 +1425,22 @@ pub fn timer() {
                 schedule_after(FEE_ESTIMATE_DELAY, TaskType::RefreshFeePercentiles);
             });
         }
+        TaskType::ConsolidateUtxos => {
+            ic_cdk::spawn(async {
+                let _guard = match crate::guard::ConsolidateUtxosGuard::new() {
+                    Some(guard) => guard,
+                    None => return,
+                };
+
+                const CONSOLIDATION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+                let _enqueue_followup_guard = guard((), |_| {
+                    schedule_after(CONSOLIDATION_INTERVAL, TaskType::ConsolidateUtxos)
+                });
+
+                consolidate_utxos().await;
+            });
+        }
         TaskType::DistributeKytFee => {
             ic_cdk::spawn(async {
                 let _guard = match crate::guard::DistributeKytFeeGuard::new() {
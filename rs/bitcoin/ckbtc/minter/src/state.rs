@@ -329,6 +329,10 @@ pub struct CkBtcMinterState {
     /// The total amount of ckBTC burned.
     pub tokens_burned: u64,
 
+    /// The total amount of BTC (in satoshi) the minter spent on fees of
+    /// finalized UTXO consolidation transactions.
+    pub consolidation_fees: u64,
+
     /// The CanisterId of the ckBTC Ledger.
     pub ledger_id: CanisterId,
 
@@ -362,6 +366,9 @@ pub struct CkBtcMinterState {
     #[serde(skip)]
     pub is_distributing_fee: bool,
 
+    #[serde(skip)]
+    pub is_consolidating_utxos: bool,
+
     /// The mode in which the minter runs.
     pub mode: Mode,
 
@@ -788,6 +795,16 @@ impl CkBtcMinterState {
             ));
         };
 
+        if finalized_tx.requests.is_empty() {
+            // Consolidation transactions pay the fee out of the minter's own UTXOs.
+            let input_value: u64 = finalized_tx.used_utxos.iter().map(|u| u.value).sum();
+            let change_value = finalized_tx
+                .change_output
+                .as_ref()
+                .map(|out| out.value)
+                .unwrap_or_default();
+            self.consolidation_fees += input_value.saturating_sub(change_value);
+        }
         for utxo in finalized_tx.used_utxos.iter() {
             self.forget_utxo(utxo);
         }
@@ -1248,6 +1265,7 @@ impl From<InitArgs> for CkBtcMinterState {
             finalized_requests_count: 0,
             tokens_minted: 0,
             tokens_burned: 0,
+            consolidation_fees: 0,
             ledger_id: args.ledger_id,
             kyt_principal: args.kyt_principal,
             available_utxos: Default::default(),
@@ -1256,6 +1274,7 @@ impl From<InitArgs> for CkBtcMinterState {
             finalized_utxos: Default::default(),
             is_timer_running: false,
             is_distributing_fee: false,
+            is_consolidating_utxos: false,
             mode: args.mode,
             last_fee_per_vbyte: vec![1; 100],
             kyt_fee: args
@@ -62,6 +62,7 @@ pub enum Event {
     #[serde(rename = "sent_transaction")]
     SentBtcTransaction {
         /// Block indices of retrieve_btc requests that caused the transaction.
+        /// Empty for transactions consolidating the minter's UTXOs.
         #[serde(rename = "requests")]
         request_block_indices: Vec<u64>,
         /// The Txid of the Bitcoin transaction.
@@ -1,7 +1,7 @@
 use crate::MINTER_FEE_CONSTANT;
 use crate::{
-    address::BitcoinAddress, build_unsigned_transaction, estimate_fee, fake_sign, greedy,
-    signature::EncodedSignature, tx, BuildTxError,
+    address::BitcoinAddress, build_consolidation_transaction, build_unsigned_transaction,
+    estimate_fee, fake_sign, greedy, signature::EncodedSignature, tx, BuildTxError,
 };
 use crate::{
     lifecycle::init::InitArgs,
@@ -373,6 +373,198 @@ fn test_no_dust_outputs() {
     assert_eq!(available_utxos.len(), 1);
 }
 
+#[test]
+fn consolidation_tx_spends_smallest_utxos() {
+    let utxo_count = crate::MAX_CONSOLIDATION_INPUTS as u64 + 10;
+    let mut available_utxos: BTreeSet<Utxo> = (1..=utxo_count)
+        .map(|i| dummy_utxo_from_value(10_000 * i))
+        .collect();
+
+    let minter_addr = BitcoinAddress::P2wpkhV0([0; 20]);
+    let fee_per_vbyte = 2000;
+
+    let (tx, change_output, used_utxos) =
+        build_consolidation_transaction(&mut available_utxos, minter_addr.clone(), fee_per_vbyte)
+            .expect("failed to build a consolidation transaction");
+
+    assert_eq!(tx.inputs.len(), crate::MAX_CONSOLIDATION_INPUTS);
+    assert_eq!(used_utxos.len(), crate::MAX_CONSOLIDATION_INPUTS);
+    assert_eq!(available_utxos.len(), 10);
+    let max_used_value = used_utxos.iter().map(|u| u.value).max().unwrap();
+    assert!(available_utxos.iter().all(|u| u.value > max_used_value));
+
+    assert_eq!(tx.outputs.len(), 1);
+    assert_eq!(tx.outputs[0].address, minter_addr);
+    assert_eq!(
+        change_output,
+        ChangeOutput {
+            vout: 0,
+            value: tx.outputs[0].value
+        }
+    );
+
+    let inputs_value = used_utxos.iter().map(|u| u.value).sum::<u64>();
+    let fee = fake_sign(&tx).vsize() as u64 * fee_per_vbyte / 1000;
+    assert_eq!(tx.outputs[0].value, inputs_value - fee);
+}
+
+#[test]
+fn consolidation_tx_does_not_modify_utxos_on_error() {
+    let mut available_utxos: BTreeSet<Utxo> = (1..100u64).map(dummy_utxo_from_value).collect();
+    let utxos_copy = available_utxos.clone();
+
+    assert_eq!(
+        build_consolidation_transaction(
+            &mut available_utxos,
+            BitcoinAddress::P2wpkhV0([0; 20]),
+            10_000
+        ),
+        Err(BuildTxError::AmountTooLow)
+    );
+    assert_eq!(available_utxos, utxos_copy);
+
+    assert_eq!(
+        build_consolidation_transaction(
+            &mut BTreeSet::new(),
+            BitcoinAddress::P2wpkhV0([0; 20]),
+            10_000
+        ),
+        Err(BuildTxError::NotEnoughFunds)
+    );
+}
+
+#[test]
+fn consolidation_tx_lifecycle_survives_replay() {
+    use crate::state::eventlog::{replay, Event};
+
+    let init_args = InitArgs {
+        btc_network: Network::Regtest.into(),
+        ecdsa_key_name: "".to_string(),
+        retrieve_btc_min_amount: 100_000,
+        ledger_id: CanisterId::from_u64(42),
+        max_time_in_queue_nanos: 0,
+        min_confirmations: None,
+        mode: Mode::GeneralAvailability,
+        kyt_fee: None,
+        kyt_principal: None,
+    };
+    let account = Account {
+        owner: Principal::from_slice(&[1; 29]),
+        subaccount: None,
+    };
+    let main_address = BitcoinAddress::P2wpkhV0([0; 20]);
+    let utxos: Vec<Utxo> = (1..=20u64)
+        .map(|i| dummy_utxo_from_value(100_000 * i))
+        .collect();
+
+    let mut state = CkBtcMinterState::from(init_args.clone());
+    let mut events = vec![
+        Event::Init(init_args),
+        Event::ReceivedUtxos {
+            mint_txid: None,
+            to_account: account,
+            utxos: utxos.clone(),
+        },
+    ];
+    state.add_utxos(account, utxos);
+
+    // Step 1: send a consolidation transaction.
+    let fee_per_vbyte = 2_000;
+    let (tx, change_output, used_utxos) = build_consolidation_transaction(
+        &mut state.available_utxos,
+        main_address.clone(),
+        fee_per_vbyte,
+    )
+    .expect("failed to build a consolidation transaction");
+    let txid = tx.txid();
+
+    events.push(Event::SentBtcTransaction {
+        request_block_indices: vec![],
+        txid,
+        utxos: used_utxos.clone(),
+        change_output: Some(change_output.clone()),
+        submitted_at: 1_000,
+        fee_per_vbyte: Some(fee_per_vbyte),
+    });
+    state.push_submitted_transaction(SubmittedBtcTransaction {
+        requests: vec![],
+        txid,
+        used_utxos: used_utxos.clone(),
+        change_output: Some(change_output.clone()),
+        submitted_at: 1_000,
+        fee_per_vbyte: Some(fee_per_vbyte),
+    });
+    state
+        .check_invariants()
+        .expect("violated invariants after sending");
+
+    // Step 2: resubmit the transaction with a higher fee.
+    let new_fee_per_vbyte = 2 * fee_per_vbyte;
+    let (new_tx, new_change_output, new_used_utxos) = build_consolidation_transaction(
+        &mut used_utxos.iter().cloned().collect(),
+        main_address,
+        new_fee_per_vbyte,
+    )
+    .expect("failed to build a replacement consolidation transaction");
+    let new_txid = new_tx.txid();
+
+    assert_ne!(new_txid, txid);
+    assert_eq!(
+        new_used_utxos.iter().collect::<BTreeSet<_>>(),
+        used_utxos.iter().collect::<BTreeSet<_>>()
+    );
+    assert!(new_change_output.value < change_output.value);
+
+    events.push(Event::ReplacedBtcTransaction {
+        old_txid: txid,
+        new_txid,
+        change_output: new_change_output.clone(),
+        submitted_at: 2_000,
+        fee_per_vbyte: new_fee_per_vbyte,
+    });
+    state.replace_transaction(
+        &txid,
+        SubmittedBtcTransaction {
+            requests: vec![],
+            txid: new_txid,
+            used_utxos: used_utxos.clone(),
+            change_output: Some(new_change_output.clone()),
+            submitted_at: 2_000,
+            fee_per_vbyte: Some(new_fee_per_vbyte),
+        },
+    );
+    assert_eq!(state.longest_resubmission_chain_size(), 1);
+    state
+        .check_invariants()
+        .expect("violated invariants after resubmission");
+
+    // Step 3: finalize the replacement transaction.
+    events.push(Event::ConfirmedBtcTransaction { txid: new_txid });
+    state.finalize_transaction(&new_txid);
+    state
+        .check_invariants()
+        .expect("violated invariants after finalization");
+
+    assert_eq!(state.submitted_transactions, vec![]);
+    assert_eq!(state.stuck_transactions, vec![]);
+    assert_eq!(state.finalized_requests_count, 0);
+    let inputs_value = used_utxos.iter().map(|u| u.value).sum::<u64>();
+    assert_eq!(
+        state.consolidation_fees,
+        inputs_value - new_change_output.value
+    );
+
+    // Step 4: replay the event log.
+    let replayed_state = replay(events.into_iter()).expect("failed to replay the event log");
+    replayed_state
+        .check_invariants()
+        .expect("violated invariants after replay");
+    replayed_state
+        .check_semantically_eq(&state)
+        .expect("replayed state does not match");
+    assert_eq!(replayed_state.consolidation_fees, state.consolidation_fees);
+}
+
 #[test]
 fn blocklist_is_sorted() {
     use crate::blocklist::BTC_ADDRESS_BLOCKLIST;
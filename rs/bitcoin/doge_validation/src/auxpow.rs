@@ -0,0 +1,431 @@
+//! Merge-mining proofs (AuxPoW).
+//!
+//! A merge-mined Dogecoin block is not mined directly. Instead, the hash of its header is
+//! committed in the coinbase transaction of a block of a parent chain (e.g., Litecoin), and
+//! the proof of work of the parent block header counts for the Dogecoin block.
+use crate::scrypt::scrypt_1024_1_1_256;
+use bitcoin::{
+    consensus::{encode, serialize, Decodable, Encodable},
+    hashes::{sha256d, Hash, HashEngine},
+    BlockHash, BlockHeader, Transaction, TxMerkleNode,
+};
+use std::io;
+
+/// The bytes announcing the merkle root of the merge-mined chains in the parent coinbase.
+const MERGED_MINING_HEADER: [u8; 4] = [0xfa, 0xbe, b'm', b'm'];
+
+/// The maximum length of the merkle branch of the merge-mined chains.
+const MAX_CHAIN_MERKLE_BRANCH_LENGTH: usize = 30;
+
+/// Without merged mining header, the merkle root of the merge-mined chains must start
+/// within this many bytes of the coinbase script.
+const MAX_ROOT_OFFSET_WITHOUT_HEADER: usize = 20;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuxPowError {
+    /// The coinbase transaction is not the first transaction of the parent block.
+    NotACoinbase,
+    /// The parent block header has the chain ID of the merge-mined chain.
+    ParentHasOurChainId,
+    /// The merkle branch of the merge-mined chains is too long.
+    ChainMerkleBranchTooLong,
+    /// The coinbase transaction is not part of the parent block.
+    InvalidMerkleRoot,
+    /// The coinbase transaction has no input.
+    MissingCoinbaseInput,
+    /// The coinbase script does not contain the merkle root of the merge-mined chains.
+    MissingChainMerkleRoot,
+    /// The merged mining header appears more than once in the coinbase script.
+    MultipleMergedMiningHeaders,
+    /// The merged mining header is not immediately followed by the chain merkle root.
+    MergedMiningHeaderNotBeforeRoot,
+    /// Without merged mining header, the chain merkle root starts too late in the script.
+    ChainMerkleRootTooLate,
+    /// The coinbase script ends before the size and nonce of the chain merkle tree.
+    MissingSizeAndNonce,
+    /// The size of the chain merkle tree does not match the length of the merkle branch.
+    WrongChainMerkleTreeSize,
+    /// The merge-mined block is not at the position determined by the nonce and chain ID.
+    WrongChainIndex,
+}
+
+/// The proof that a block was merge-mined, as serialized after the header of a merge-mined
+/// block on the wire.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuxPow {
+    /// The coinbase transaction of the parent block.
+    pub coinbase_tx: Transaction,
+    /// The hash of the parent block. Not used for validation.
+    pub parent_hash: BlockHash,
+    /// The merkle branch linking the coinbase transaction to the parent block.
+    pub coinbase_branch: Vec<TxMerkleNode>,
+    /// The index of the coinbase transaction in the parent block, which must be 0.
+    pub coinbase_index: i32,
+    /// The merkle branch linking the merge-mined block to the merkle root in the coinbase.
+    pub blockchain_branch: Vec<TxMerkleNode>,
+    /// The index of the merge-mined block in the merkle tree of the merge-mined chains.
+    pub blockchain_index: i32,
+    /// The header of the parent block.
+    pub parent_block_header: BlockHeader,
+}
+
+impl AuxPow {
+    /// Checks that the proof commits to the block with the given hash and chain ID.
+    ///
+    /// The proof of work of the parent block is checked separately, see
+    /// [AuxPow::parent_pow_hash].
+    pub fn check(
+        &self,
+        aux_block_hash: BlockHash,
+        chain_id: i32,
+        strict_chain_id: bool,
+    ) -> Result<(), AuxPowError> {
+        if self.coinbase_index != 0 {
+            return Err(AuxPowError::NotACoinbase);
+        }
+        if strict_chain_id && chain_id_of(self.parent_block_header.version) == chain_id {
+            return Err(AuxPowError::ParentHasOurChainId);
+        }
+        if self.blockchain_branch.len() > MAX_CHAIN_MERKLE_BRANCH_LENGTH {
+            return Err(AuxPowError::ChainMerkleBranchTooLong);
+        }
+
+        let mut root_hash = check_merkle_branch(
+            aux_block_hash.into_inner(),
+            &self.blockchain_branch,
+            self.blockchain_index,
+        );
+        // The root is committed in the coinbase in the reverse byte order.
+        root_hash.reverse();
+
+        let coinbase_root = check_merkle_branch(
+            self.coinbase_tx.txid().into_inner(),
+            &self.coinbase_branch,
+            self.coinbase_index,
+        );
+        if coinbase_root != self.parent_block_header.merkle_root.into_inner() {
+            return Err(AuxPowError::InvalidMerkleRoot);
+        }
+
+        let script = self
+            .coinbase_tx
+            .input
+            .first()
+            .ok_or(AuxPowError::MissingCoinbaseInput)?
+            .script_sig
+            .as_bytes();
+
+        let root_pos = find(script, &root_hash).ok_or(AuxPowError::MissingChainMerkleRoot)?;
+        match find(script, &MERGED_MINING_HEADER) {
+            Some(header_pos) => {
+                // Only one chain merkle root may be committed, right after the single header.
+                if find(&script[header_pos + 1..], &MERGED_MINING_HEADER).is_some() {
+                    return Err(AuxPowError::MultipleMergedMiningHeaders);
+                }
+                if header_pos + MERGED_MINING_HEADER.len() != root_pos {
+                    return Err(AuxPowError::MergedMiningHeaderNotBeforeRoot);
+                }
+            }
+            None => {
+                // Legacy coinbases without header must commit the root early in the script.
+                if root_pos > MAX_ROOT_OFFSET_WITHOUT_HEADER {
+                    return Err(AuxPowError::ChainMerkleRootTooLate);
+                }
+            }
+        }
+
+        let rest = &script[root_pos + root_hash.len()..];
+        if rest.len() < 8 {
+            return Err(AuxPowError::MissingSizeAndNonce);
+        }
+        let size = u32::from_le_bytes(rest[0..4].try_into().unwrap());
+        let nonce = u32::from_le_bytes(rest[4..8].try_into().unwrap());
+        let merkle_height = self.blockchain_branch.len() as u32;
+        if size != 1 << merkle_height {
+            return Err(AuxPowError::WrongChainMerkleTreeSize);
+        }
+        if self.blockchain_index != expected_index(nonce, chain_id, merkle_height) as i32 {
+            return Err(AuxPowError::WrongChainIndex);
+        }
+        Ok(())
+    }
+
+    /// Returns the scrypt proof-of-work hash of the parent block header.
+    pub fn parent_pow_hash(&self) -> [u8; 32] {
+        scrypt_1024_1_1_256(&serialize(&self.parent_block_header))
+    }
+}
+
+/// Returns the chain ID encoded in the version of a block header.
+pub fn chain_id_of(version: i32) -> i32 {
+    version / (1 << 16)
+}
+
+/// Computes the root of the merkle tree from a leaf and the merkle branch of the leaf.
+fn check_merkle_branch(hash: [u8; 32], branch: &[TxMerkleNode], index: i32) -> [u8; 32] {
+    if index == -1 {
+        return [0; 32];
+    }
+    let mut index = index;
+    let mut hash = hash;
+    for node in branch {
+        let mut engine = sha256d::Hash::engine();
+        if index & 1 == 1 {
+            engine.input(&node[..]);
+            engine.input(&hash);
+        } else {
+            engine.input(&hash);
+            engine.input(&node[..]);
+        }
+        hash = sha256d::Hash::from_engine(engine).into_inner();
+        index >>= 1;
+    }
+    hash
+}
+
+/// The position of a chain in the merkle tree of the merge-mined chains, chosen
+/// pseudo-randomly from the nonce so that a chain cannot be committed at several positions.
+fn expected_index(nonce: u32, chain_id: i32, merkle_height: u32) -> u32 {
+    let mut rand = nonce;
+    rand = rand.wrapping_mul(1103515245).wrapping_add(12345);
+    rand = rand.wrapping_add(chain_id as u32);
+    rand = rand.wrapping_mul(1103515245).wrapping_add(12345);
+    rand % (1 << merkle_height)
+}
+
+/// Returns the position of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+impl Encodable for AuxPow {
+    fn consensus_encode<W: io::Write>(&self, mut writer: W) -> Result<usize, io::Error> {
+        let mut len = 0;
+        len += self.coinbase_tx.consensus_encode(&mut writer)?;
+        len += self.parent_hash.consensus_encode(&mut writer)?;
+        len += self.coinbase_branch.consensus_encode(&mut writer)?;
+        len += self.coinbase_index.consensus_encode(&mut writer)?;
+        len += self.blockchain_branch.consensus_encode(&mut writer)?;
+        len += self.blockchain_index.consensus_encode(&mut writer)?;
+        len += self.parent_block_header.consensus_encode(&mut writer)?;
+        Ok(len)
+    }
+}
+
+impl Decodable for AuxPow {
+    fn consensus_decode<D: io::Read>(mut d: D) -> Result<Self, encode::Error> {
+        Ok(Self {
+            coinbase_tx: Decodable::consensus_decode(&mut d)?,
+            parent_hash: Decodable::consensus_decode(&mut d)?,
+            coinbase_branch: Decodable::consensus_decode(&mut d)?,
+            coinbase_index: Decodable::consensus_decode(&mut d)?,
+            blockchain_branch: Decodable::consensus_decode(&mut d)?,
+            blockchain_index: Decodable::consensus_decode(&mut d)?,
+            parent_block_header: Decodable::consensus_decode(&mut d)?,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use crate::network::DOGECOIN_CHAIN_ID;
+    use bitcoin::{consensus::deserialize, OutPoint, Script, TxIn, TxOut, Witness};
+
+    /// The version of a Litecoin block header, whose chain ID differs from Dogecoin's.
+    const PARENT_VERSION: i32 = 0x2000_0000;
+
+    /// Builds a proof committing to `aux_block_hash` in a coinbase with the given script
+    /// prefix and suffix around the chain merkle root.
+    pub(crate) fn build_auxpow(aux_block_hash: BlockHash, bits: u32) -> AuxPow {
+        let mut script = vec![0x03, 0x01, 0x02, 0x03];
+        script.extend_from_slice(&MERGED_MINING_HEADER);
+        script.extend_from_slice(&reversed(aux_block_hash.into_inner()));
+        script.extend_from_slice(&1_u32.to_le_bytes());
+        script.extend_from_slice(&0_u32.to_le_bytes());
+        auxpow_with_script(script, bits)
+    }
+
+    fn auxpow_with_script(script: Vec<u8>, bits: u32) -> AuxPow {
+        let coinbase_tx = Transaction {
+            version: 1,
+            lock_time: 0,
+            input: vec![TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: Script::from(script),
+                sequence: u32::MAX,
+                witness: Witness::default(),
+            }],
+            output: vec![TxOut {
+                value: 50_0000_0000,
+                script_pubkey: Script::new(),
+            }],
+        };
+        let parent_block_header = BlockHeader {
+            version: PARENT_VERSION,
+            prev_blockhash: BlockHash::default(),
+            merkle_root: TxMerkleNode::from_inner(coinbase_tx.txid().into_inner()),
+            time: 1_700_000_000,
+            bits,
+            nonce: 0,
+        };
+        AuxPow {
+            coinbase_tx,
+            parent_hash: parent_block_header.block_hash(),
+            coinbase_branch: vec![],
+            coinbase_index: 0,
+            blockchain_branch: vec![],
+            blockchain_index: 0,
+            parent_block_header,
+        }
+    }
+
+    fn reversed(mut hash: [u8; 32]) -> [u8; 32] {
+        hash.reverse();
+        hash
+    }
+
+    fn aux_block_hash() -> BlockHash {
+        BlockHash::hash(b"merge-mined block")
+    }
+
+    #[test]
+    fn test_valid_auxpow() {
+        let auxpow = build_auxpow(aux_block_hash(), 0x207fffff);
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_auxpow_encoding_roundtrip() {
+        let auxpow = build_auxpow(aux_block_hash(), 0x207fffff);
+        let decoded: AuxPow = deserialize(&serialize(&auxpow)).unwrap();
+        assert_eq!(decoded, auxpow);
+    }
+
+    #[test]
+    fn test_auxpow_for_another_block_is_rejected() {
+        let auxpow = build_auxpow(aux_block_hash(), 0x207fffff);
+        assert_eq!(
+            auxpow.check(BlockHash::hash(b"other"), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::MissingChainMerkleRoot)
+        );
+    }
+
+    #[test]
+    fn test_parent_with_our_chain_id_is_rejected() {
+        let mut auxpow = build_auxpow(aux_block_hash(), 0x207fffff);
+        auxpow.parent_block_header.version = DOGECOIN_CHAIN_ID << 16 | 0x104;
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::ParentHasOurChainId)
+        );
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID, false),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_coinbase_not_in_parent_block_is_rejected() {
+        let mut auxpow = build_auxpow(aux_block_hash(), 0x207fffff);
+        auxpow.parent_block_header.merkle_root = TxMerkleNode::hash(b"other");
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::InvalidMerkleRoot)
+        );
+
+        let mut auxpow = build_auxpow(aux_block_hash(), 0x207fffff);
+        auxpow.coinbase_index = 1;
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::NotACoinbase)
+        );
+    }
+
+    #[test]
+    fn test_merged_mining_header_rules() {
+        let root = reversed(aux_block_hash().into_inner());
+        let size_and_nonce = [1, 0, 0, 0, 0, 0, 0, 0];
+
+        // A second merged mining header.
+        let script = [
+            &MERGED_MINING_HEADER[..],
+            &root,
+            &size_and_nonce,
+            &MERGED_MINING_HEADER,
+        ]
+        .concat();
+        assert_eq!(
+            auxpow_with_script(script, 0x207fffff).check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::MultipleMergedMiningHeaders)
+        );
+
+        // The header is not right before the root.
+        let script = [&MERGED_MINING_HEADER[..], &[0], &root, &size_and_nonce].concat();
+        assert_eq!(
+            auxpow_with_script(script, 0x207fffff).check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::MergedMiningHeaderNotBeforeRoot)
+        );
+
+        // Without header, the root must be within the first 20 bytes.
+        let script = [&[0; 20][..], &root, &size_and_nonce].concat();
+        assert_eq!(
+            auxpow_with_script(script, 0x207fffff).check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Ok(())
+        );
+        let script = [&[0; 21][..], &root, &size_and_nonce].concat();
+        assert_eq!(
+            auxpow_with_script(script, 0x207fffff).check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::ChainMerkleRootTooLate)
+        );
+
+        // The size and nonce are missing.
+        let script = [&MERGED_MINING_HEADER[..], &root, &[1, 0, 0, 0]].concat();
+        assert_eq!(
+            auxpow_with_script(script, 0x207fffff).check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::MissingSizeAndNonce)
+        );
+    }
+
+    #[test]
+    fn test_chain_merkle_branch() {
+        let sibling = TxMerkleNode::hash(b"other chain");
+        let auxpow_with_size_and_nonce = |size: u32, nonce: u32| {
+            let index = expected_index(nonce, DOGECOIN_CHAIN_ID, 1) as i32;
+            let root = check_merkle_branch(aux_block_hash().into_inner(), &[sibling], index);
+            let mut script = MERGED_MINING_HEADER.to_vec();
+            script.extend_from_slice(&reversed(root));
+            script.extend_from_slice(&size.to_le_bytes());
+            script.extend_from_slice(&nonce.to_le_bytes());
+            let mut auxpow = auxpow_with_script(script, 0x207fffff);
+            auxpow.blockchain_branch = vec![sibling];
+            auxpow.blockchain_index = index;
+            auxpow
+        };
+
+        let auxpow = auxpow_with_size_and_nonce(2, 7);
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Ok(())
+        );
+
+        // The size of the tree must match the length of the branch.
+        let auxpow = auxpow_with_size_and_nonce(4, 7);
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID, true),
+            Err(AuxPowError::WrongChainMerkleTreeSize)
+        );
+
+        // The chain must be at the position derived from the nonce and the chain ID.
+        let auxpow = auxpow_with_size_and_nonce(2, 7);
+        assert_eq!(
+            auxpow.check(aux_block_hash(), DOGECOIN_CHAIN_ID + 1, true),
+            Err(AuxPowError::WrongChainIndex)
+        );
+    }
+}
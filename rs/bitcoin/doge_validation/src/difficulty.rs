@@ -0,0 +1,167 @@
+//! The difficulty adjustment rules of Dogecoin.
+//!
+//! Before DigiShield, the difficulty is adjusted every 240 blocks like in Litecoin, with
+//! tighter bounds on the adjustment during the first 10,000 blocks. From the DigiShield
+//! height on, the difficulty is adjusted at every block based on the time between the
+//! previous two blocks, dampened to an eighth of the deviation from the target spacing.
+use crate::{network::DogecoinNetwork, BlockHeight, HeaderStore, ValidateHeaderError};
+use bitcoin::{util::uint::Uint256, BlockHeader};
+
+/// The target spacing between two blocks, in seconds.
+const TARGET_SPACING: i64 = 60;
+
+/// The retargeting timespan before DigiShield, in seconds.
+const LEGACY_TARGET_TIMESPAN: i64 = 4 * 60 * 60;
+
+/// The number of blocks between two retargets before DigiShield.
+const LEGACY_DIFFICULTY_ADJUSTMENT_INTERVAL: u32 = (LEGACY_TARGET_TIMESPAN / TARGET_SPACING) as u32;
+
+/// The retargeting timespan with DigiShield, in seconds. DigiShield retargets at every block.
+const DIGISHIELD_TARGET_TIMESPAN: i64 = TARGET_SPACING;
+
+/// Returns the compact target that the block following `prev_header` must have.
+pub fn get_next_work_required(
+    network: &DogecoinNetwork,
+    store: &impl HeaderStore,
+    prev_header: &BlockHeader,
+    prev_height: BlockHeight,
+    header: &BlockHeader,
+) -> Result<u32, ValidateHeaderError> {
+    let pow_limit_bits = network.pow_limit_bits();
+    if network.no_retargeting() {
+        return Ok(prev_header.bits);
+    }
+    if allow_digishield_min_difficulty_for_block(network, prev_header, prev_height, header) {
+        return Ok(pow_limit_bits);
+    }
+
+    let digishield = prev_height >= network.digishield_height();
+    let interval = if digishield {
+        1
+    } else {
+        LEGACY_DIFFICULTY_ADJUSTMENT_INTERVAL
+    };
+
+    if (prev_height + 1) % interval != 0 {
+        if network.allow_min_difficulty_blocks() {
+            // A block coming more than twice the target spacing after its parent may
+            // have the minimum difficulty.
+            if header.time as i64 > prev_header.time as i64 + 2 * TARGET_SPACING {
+                return Ok(pow_limit_bits);
+            }
+            // Otherwise, it has the difficulty of the last block not mined with the
+            // minimum difficulty.
+            let mut last = *prev_header;
+            let mut last_height = prev_height;
+            while last_height % LEGACY_DIFFICULTY_ADJUSTMENT_INTERVAL != 0
+                && last.bits == pow_limit_bits
+            {
+                match store.get_header(&last.prev_blockhash) {
+                    Some((header, height)) => {
+                        last = header;
+                        last_height = height;
+                    }
+                    None => break,
+                }
+            }
+            return Ok(last.bits);
+        }
+        return Ok(prev_header.bits);
+    }
+
+    // Go back by a full interval, except for the first retarget after genesis.
+    // Going back one block less would allow an attacker to change the difficulty at will.
+    let blocks_to_go_back = if prev_height + 1 != interval {
+        interval
+    } else {
+        interval - 1
+    };
+    let first_header = get_ancestor(
+        store,
+        prev_header,
+        prev_height,
+        prev_height - blocks_to_go_back,
+    )
+    .ok_or(ValidateHeaderError::AncestorNotFound)?;
+
+    Ok(calculate_next_work_required(
+        network,
+        prev_header,
+        prev_height + 1,
+        first_header.time,
+        digishield,
+    ))
+}
+
+/// With DigiShield, blocks are allowed to have the minimum difficulty on networks
+/// allowing minimum difficulty blocks if they come long enough after their parent.
+fn allow_digishield_min_difficulty_for_block(
+    network: &DogecoinNetwork,
+    prev_header: &BlockHeader,
+    prev_height: BlockHeight,
+    header: &BlockHeader,
+) -> bool {
+    network.allow_min_difficulty_blocks()
+        && prev_height >= network.digishield_min_difficulty_height()
+        && header.time as i64 > prev_header.time as i64 + 2 * TARGET_SPACING
+}
+
+/// Computes the new compact target from the time it took to mine the blocks since the
+/// block with timestamp `first_block_time`.
+fn calculate_next_work_required(
+    network: &DogecoinNetwork,
+    prev_header: &BlockHeader,
+    height: BlockHeight,
+    first_block_time: u32,
+    digishield: bool,
+) -> u32 {
+    let actual_timespan = prev_header.time as i64 - first_block_time as i64;
+    let (target_timespan, modulated_timespan, min_timespan, max_timespan) = if digishield {
+        let target_timespan = DIGISHIELD_TARGET_TIMESPAN;
+        (
+            target_timespan,
+            // Amplitude filter: only an eighth of the deviation is taken into account.
+            target_timespan + (actual_timespan - target_timespan) / 8,
+            target_timespan - target_timespan / 4,
+            target_timespan + target_timespan / 2,
+        )
+    } else {
+        let target_timespan = LEGACY_TARGET_TIMESPAN;
+        let min_timespan = if height > 10_000 {
+            target_timespan / 4
+        } else if height > 5_000 {
+            target_timespan / 8
+        } else {
+            target_timespan / 16
+        };
+        (
+            target_timespan,
+            actual_timespan,
+            min_timespan,
+            target_timespan * 4,
+        )
+    };
+    let modulated_timespan = modulated_timespan.clamp(min_timespan, max_timespan);
+
+    let target = BlockHeader::u256_from_compact_target(prev_header.bits)
+        .mul_u32(modulated_timespan as u32)
+        / Uint256::from_u64(target_timespan as u64).unwrap();
+    BlockHeader::compact_target_from_u256(&target.min(network.pow_limit()))
+}
+
+/// Returns the ancestor of `header` at the given height, if it is in the store.
+fn get_ancestor(
+    store: &impl HeaderStore,
+    header: &BlockHeader,
+    height: BlockHeight,
+    ancestor_height: BlockHeight,
+) -> Option<BlockHeader> {
+    let mut current = *header;
+    let mut current_height = height;
+    while current_height > ancestor_height {
+        let (parent, parent_height) = store.get_header(&current.prev_blockhash)?;
+        current = parent;
+        current_height = parent_height;
+    }
+    Some(current)
+}
@@ -0,0 +1,140 @@
+use crate::{
+    auxpow::chain_id_of, difficulty::get_next_work_required, network::DOGECOIN_CHAIN_ID,
+    scrypt::scrypt_1024_1_1_256, AuxPow, DogecoinNetwork, HeaderStore, ValidateHeaderError,
+};
+use bitcoin::{consensus::serialize, util::uint::Uint256, BlockHeader};
+
+/// The flag in the header version announcing a merge-mining proof.
+const VERSION_AUXPOW: i32 = 1 << 8;
+
+/// The number of previous blocks whose median timestamp a new block must exceed.
+const MEDIAN_TIME_SPAN: usize = 11;
+
+/// The maximum number of seconds a timestamp may be ahead of the current time.
+const MAX_FUTURE_BLOCK_TIME: u64 = 2 * 60 * 60;
+
+/// Validates a header against the headers in the store.
+///
+/// `auxpow` is the merge-mining proof sent along with the header, which must be present
+/// if and only if the header version announces it. `current_time` is the current time
+/// in seconds since the Unix epoch.
+pub fn validate_header(
+    network: &DogecoinNetwork,
+    store: &impl HeaderStore,
+    header: &BlockHeader,
+    auxpow: Option<&AuxPow>,
+    current_time: u64,
+) -> Result<(), ValidateHeaderError> {
+    let (prev_header, prev_height) = store
+        .get_header(&header.prev_blockhash)
+        .ok_or(ValidateHeaderError::PrevHeaderNotFound)?;
+
+    check_version(network, header, prev_height + 1)?;
+    check_timestamp(store, header, &prev_header, current_time)?;
+
+    let expected_bits = get_next_work_required(network, store, &prev_header, prev_height, header)?;
+    if header.bits != expected_bits {
+        return Err(ValidateHeaderError::InvalidPoWForComputedTarget);
+    }
+
+    check_proof_of_work(network, header, auxpow)
+}
+
+/// Returns true if the header version predates merge-mining and has no chain ID.
+pub fn is_legacy_version(version: i32) -> bool {
+    // Version 2 headers have chain ID 0.
+    version == 1 || version == 2
+}
+
+/// Returns true if the header version announces a merge-mining proof.
+pub fn is_auxpow_version(version: i32) -> bool {
+    version & VERSION_AUXPOW != 0
+}
+
+fn check_version(
+    network: &DogecoinNetwork,
+    header: &BlockHeader,
+    height: u32,
+) -> Result<(), ValidateHeaderError> {
+    if is_legacy_version(header.version) {
+        if height >= network.auxpow_height() {
+            return Err(ValidateHeaderError::LegacyBlockAfterAuxPowHeight);
+        }
+        return Ok(());
+    }
+    let chain_id = chain_id_of(header.version);
+    if network.strict_chain_id() && chain_id != DOGECOIN_CHAIN_ID {
+        return Err(ValidateHeaderError::WrongChainId { chain_id });
+    }
+    if is_auxpow_version(header.version) && height < network.auxpow_height() {
+        return Err(ValidateHeaderError::AuxPowBeforeAuxPowHeight);
+    }
+    Ok(())
+}
+
+fn check_timestamp(
+    store: &impl HeaderStore,
+    header: &BlockHeader,
+    prev_header: &BlockHeader,
+    current_time: u64,
+) -> Result<(), ValidateHeaderError> {
+    let mut times = vec![prev_header.time];
+    let mut current = *prev_header;
+    while times.len() < MEDIAN_TIME_SPAN {
+        match store.get_header(&current.prev_blockhash) {
+            Some((parent, _)) => {
+                times.push(parent.time);
+                current = parent;
+            }
+            None => break,
+        }
+    }
+    times.sort_unstable();
+    if header.time <= times[times.len() / 2] {
+        return Err(ValidateHeaderError::HeaderIsOld);
+    }
+
+    let max_allowed_time = current_time + MAX_FUTURE_BLOCK_TIME;
+    if header.time as u64 > max_allowed_time {
+        return Err(ValidateHeaderError::HeaderIsTooFarInFuture {
+            block_time: header.time as u64,
+            max_allowed_time,
+        });
+    }
+    Ok(())
+}
+
+fn check_proof_of_work(
+    network: &DogecoinNetwork,
+    header: &BlockHeader,
+    auxpow: Option<&AuxPow>,
+) -> Result<(), ValidateHeaderError> {
+    let target = header.target();
+    if target == Uint256::default() || target > network.pow_limit() {
+        return Err(ValidateHeaderError::TargetDifficultyAboveMax);
+    }
+
+    let pow_hash = match (auxpow, is_auxpow_version(header.version)) {
+        (None, false) => scrypt_1024_1_1_256(&serialize(header)),
+        (Some(auxpow), true) => {
+            auxpow
+                .check(
+                    header.block_hash(),
+                    chain_id_of(header.version),
+                    network.strict_chain_id(),
+                )
+                .map_err(ValidateHeaderError::InvalidAuxPow)?;
+            auxpow.parent_pow_hash()
+        }
+        (None, true) => return Err(ValidateHeaderError::MissingAuxPow),
+        (Some(_), false) => return Err(ValidateHeaderError::UnexpectedAuxPow),
+    };
+
+    // The hash is compared with the target as a little-endian number.
+    let mut pow_hash = pow_hash;
+    pow_hash.reverse();
+    if Uint256::from_be_bytes(pow_hash) > target {
+        return Err(ValidateHeaderError::InvalidPoWForHeaderTarget);
+    }
+    Ok(())
+}
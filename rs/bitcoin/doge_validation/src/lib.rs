@@ -0,0 +1,74 @@
+//! Validation of Dogecoin block headers.
+//!
+//! Dogecoin headers have the same layout as Bitcoin headers, but their proof of work uses
+//! scrypt instead of double SHA-256, the difficulty is adjusted with Dogecoin's own rules
+//! (DigiShield) and, from the AuxPoW height on, blocks are merge-mined with a parent chain.
+//!
+//! Only header validation lives here. The adapter, the consensus payload builder and the
+//! management canister API are still tied to the network types of the `bitcoin` and
+//! `ic-btc-interface` crates, so Dogecoin is not yet reachable through them.
+mod auxpow;
+mod difficulty;
+mod header;
+mod network;
+mod scrypt;
+#[cfg(test)]
+mod tests;
+
+pub use auxpow::{chain_id_of, AuxPow, AuxPowError};
+pub use difficulty::get_next_work_required;
+pub use header::{is_auxpow_version, is_legacy_version, validate_header};
+pub use network::{DogecoinNetwork, DOGECOIN_CHAIN_ID};
+pub use scrypt::scrypt_1024_1_1_256;
+
+use bitcoin::{BlockHash, BlockHeader};
+
+pub type BlockHeight = u32;
+
+/// Gives access to the headers preceding a header to validate.
+///
+/// This is the same interface as the one used to validate Bitcoin headers, so that a
+/// header cache can serve both.
+pub trait HeaderStore {
+    /// Returns the header with the given hash and its height.
+    fn get_header(&self, hash: &BlockHash) -> Option<(BlockHeader, BlockHeight)>;
+
+    /// Returns the height of the tip of the longest chain.
+    fn get_height(&self) -> BlockHeight;
+
+    /// Returns the hash of the first header of the store.
+    fn get_initial_hash(&self) -> BlockHash;
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidateHeaderError {
+    /// The timestamp is not after the median time of the previous 11 blocks.
+    HeaderIsOld,
+    /// The timestamp is more than 2 hours after the current time.
+    HeaderIsTooFarInFuture {
+        block_time: u64,
+        max_allowed_time: u64,
+    },
+    /// The header version does not have the chain ID of Dogecoin.
+    WrongChainId { chain_id: i32 },
+    /// The header is a legacy (not merge-mined) header above the AuxPoW height.
+    LegacyBlockAfterAuxPowHeight,
+    /// The header is merge-mined below the AuxPoW height.
+    AuxPowBeforeAuxPowHeight,
+    /// The header version announces a merge-mining proof, but there is none.
+    MissingAuxPow,
+    /// There is a merge-mining proof, but the header version does not announce one.
+    UnexpectedAuxPow,
+    /// The merge-mining proof is invalid.
+    InvalidAuxPow(AuxPowError),
+    /// The target of the header is zero or above the maximum target of the network.
+    TargetDifficultyAboveMax,
+    /// The proof-of-work hash is above the target of the header.
+    InvalidPoWForHeaderTarget,
+    /// The target of the header differs from the target computed from the previous headers.
+    InvalidPoWForComputedTarget,
+    /// The previous header is not in the store.
+    PrevHeaderNotFound,
+    /// A header needed to compute the target is not in the store.
+    AncestorNotFound,
+}
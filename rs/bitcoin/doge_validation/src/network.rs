@@ -0,0 +1,140 @@
+//! Consensus parameters of the Dogecoin networks.
+use bitcoin::{hashes::hex::FromHex, util::uint::Uint256, BlockHash, BlockHeader, TxMerkleNode};
+
+/// The merkle root of the genesis block, which is the same on all networks.
+const GENESIS_MERKLE_ROOT: &str =
+    "5b2a3f53f605d62c53e62932dac6925e3d74afa5a4b459745c36d42d0ed26a69";
+
+/// The chain ID of Dogecoin in the version of merge-mined headers.
+pub const DOGECOIN_CHAIN_ID: i32 = 0x62;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DogecoinNetwork {
+    Mainnet,
+    Testnet,
+    Regtest,
+}
+
+impl DogecoinNetwork {
+    /// The magic bytes starting every P2P message of the network.
+    pub fn magic(&self) -> u32 {
+        match self {
+            Self::Mainnet => 0xc0c0c0c0,
+            Self::Testnet => 0xdcb7c1fc,
+            Self::Regtest => 0xdab5bffa,
+        }
+    }
+
+    /// The header of the genesis block.
+    pub fn genesis_block_header(&self) -> BlockHeader {
+        let (time, bits, nonce) = match self {
+            Self::Mainnet => (1386325540, 0x1e0ffff0, 99943),
+            Self::Testnet => (1391503289, 0x1e0ffff0, 997879),
+            Self::Regtest => (1296688602, 0x207fffff, 2),
+        };
+        BlockHeader {
+            version: 1,
+            prev_blockhash: BlockHash::default(),
+            merkle_root: TxMerkleNode::from_hex(GENESIS_MERKLE_ROOT)
+                .expect("the genesis merkle root is valid hex"),
+            time,
+            bits,
+            nonce,
+        }
+    }
+
+    /// The highest target allowed for a block.
+    pub fn pow_limit(&self) -> Uint256 {
+        match self {
+            Self::Mainnet | Self::Testnet => !Uint256::default() >> 20,
+            Self::Regtest => !Uint256::default() >> 1,
+        }
+    }
+
+    /// The compact representation of [DogecoinNetwork::pow_limit].
+    pub fn pow_limit_bits(&self) -> u32 {
+        BlockHeader::compact_target_from_u256(&self.pow_limit())
+    }
+
+    /// Whether a block may use the minimum difficulty if it comes long enough after its parent.
+    pub fn allow_min_difficulty_blocks(&self) -> bool {
+        match self {
+            Self::Mainnet => false,
+            Self::Testnet | Self::Regtest => true,
+        }
+    }
+
+    /// Whether the difficulty never changes.
+    pub fn no_retargeting(&self) -> bool {
+        *self == Self::Regtest
+    }
+
+    /// The height of the first block whose difficulty is computed with DigiShield.
+    pub fn digishield_height(&self) -> u32 {
+        match self {
+            Self::Mainnet | Self::Testnet => 145_000,
+            Self::Regtest => 10,
+        }
+    }
+
+    /// The height of the first block for which the minimum difficulty rule also applies
+    /// with DigiShield.
+    pub fn digishield_min_difficulty_height(&self) -> u32 {
+        157_500
+    }
+
+    /// The height of the first block that may be merge-mined.
+    /// Legacy blocks are no longer accepted from this height on.
+    pub fn auxpow_height(&self) -> u32 {
+        match self {
+            Self::Mainnet => 371_337,
+            Self::Testnet => 158_100,
+            Self::Regtest => 20,
+        }
+    }
+
+    /// Whether blocks must carry the Dogecoin chain ID and merge-mined parent blocks must not.
+    pub fn strict_chain_id(&self) -> bool {
+        match self {
+            Self::Mainnet | Self::Testnet => true,
+            Self::Regtest => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_genesis_block_hashes() {
+        for (network, hash) in [
+            (
+                DogecoinNetwork::Mainnet,
+                "1a91e3dace36e2be3bf030a65679fe821aa1d6ef92e7c9902eb318182c355691",
+            ),
+            (
+                DogecoinNetwork::Testnet,
+                "bb0a78264637406b6360aad926284d544d7049f45189db5664f3c4d07350559e",
+            ),
+            (
+                DogecoinNetwork::Regtest,
+                "3d2160a3b5dc4a9d62e7e66a295f70313ac808440ef7400d6c0772171ce973a5",
+            ),
+        ] {
+            assert_eq!(
+                network.genesis_block_header().block_hash().to_string(),
+                hash,
+                "unexpected genesis block hash on {:?}",
+                network
+            );
+        }
+    }
+
+    #[test]
+    fn test_pow_limit_bits() {
+        assert_eq!(DogecoinNetwork::Mainnet.pow_limit_bits(), 0x1e0fffff);
+        assert_eq!(DogecoinNetwork::Testnet.pow_limit_bits(), 0x1e0fffff);
+        assert_eq!(DogecoinNetwork::Regtest.pow_limit_bits(), 0x207fffff);
+    }
+}
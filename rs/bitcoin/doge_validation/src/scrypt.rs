@@ -0,0 +1,59 @@
+//! The scrypt hash function used by Dogecoin (and Litecoin) for proof of work.
+//!
+//! The proof-of-work hash of a header is `scrypt(header, header, N = 1024, r = 1, p = 1)`
+//! with a 32-byte output, where the 80-byte consensus encoding of the header is used both
+//! as password and as salt.
+
+/// The base-2 logarithm of the CPU/memory cost parameter `N = 1024`.
+const LOG_N: u8 = 10;
+
+/// The block size parameter `r`.
+const R: u32 = 1;
+
+/// The parallelization parameter `p`.
+const P: u32 = 1;
+
+/// The length of the proof-of-work hash in bytes.
+const OUTPUT_LEN: usize = 32;
+
+/// Computes the scrypt proof-of-work hash of the given input.
+///
+/// The result is in the byte order of the hash function, i.e., it must be interpreted as a
+/// little-endian number when compared with a target.
+pub fn scrypt_1024_1_1_256(input: &[u8]) -> [u8; OUTPUT_LEN] {
+    let params = scrypt::Params::new(LOG_N, R, P, OUTPUT_LEN)
+        .expect("BUG: invalid Dogecoin scrypt parameters");
+    let mut output = [0_u8; OUTPUT_LEN];
+    scrypt::scrypt(input, input, &params, &mut output).expect("BUG: invalid scrypt output length");
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scrypt_of_dogecoin_genesis_header() {
+        let header = hex::decode(
+            "010000000000000000000000000000000000000000000000000000000000000000000000\
+             696ad20e2dd4365c7459b4a4a5af743d5e92c6da3229e6532cd605f6533f2a5b\
+             24a6a152f0ff0f1e67860100",
+        )
+        .unwrap();
+        let mut hash = scrypt_1024_1_1_256(&header);
+        hash.reverse();
+        assert_eq!(
+            hex::encode(hash),
+            "0000026f3f7874ca0c251314eaed2d2fcf83d7da3acfaacf59417d485310b448"
+        );
+    }
+
+    #[test]
+    fn test_scrypt_of_arbitrary_input() {
+        // Reference value computed with `hashlib.scrypt(b"abc", salt=b"abc", n=1024, r=1, p=1, dklen=32)`.
+        assert_eq!(
+            hex::encode(scrypt_1024_1_1_256(b"abc")),
+            "e652c1c3b7a8cd99d2edc49d4509f545c80e4395765e7225c4dde5d80dd76519"
+        );
+    }
+}
@@ -0,0 +1,417 @@
+use crate::{
+    auxpow::test::build_auxpow, get_next_work_required, scrypt_1024_1_1_256, validate_header,
+    AuxPow, BlockHeight, DogecoinNetwork, HeaderStore, ValidateHeaderError, DOGECOIN_CHAIN_ID,
+};
+use bitcoin::{
+    consensus::serialize, hashes::Hash, util::uint::Uint256, BlockHash, BlockHeader, TxMerkleNode,
+};
+use std::collections::HashMap;
+
+/// The version of a merge-mined header.
+const AUXPOW_VERSION: i32 = DOGECOIN_CHAIN_ID << 16 | 0x100 | 4;
+
+/// The version of a header mined directly after the AuxPoW height.
+const NON_AUXPOW_VERSION: i32 = DOGECOIN_CHAIN_ID << 16 | 4;
+
+const REGTEST_BITS: u32 = 0x207fffff;
+
+struct SimpleHeaderStore {
+    headers: HashMap<BlockHash, (BlockHeader, BlockHeight)>,
+    height: BlockHeight,
+    initial_hash: BlockHash,
+}
+
+impl SimpleHeaderStore {
+    fn new(initial_header: BlockHeader, height: BlockHeight) -> Self {
+        let initial_hash = initial_header.block_hash();
+        Self {
+            headers: HashMap::from([(initial_hash, (initial_header, height))]),
+            height,
+            initial_hash,
+        }
+    }
+
+    fn add(&mut self, header: BlockHeader) {
+        let (_, prev_height) = self
+            .get_header(&header.prev_blockhash)
+            .expect("the parent should be in the store");
+        self.height = self.height.max(prev_height + 1);
+        self.headers
+            .insert(header.block_hash(), (header, prev_height + 1));
+    }
+}
+
+impl HeaderStore for SimpleHeaderStore {
+    fn get_header(&self, hash: &BlockHash) -> Option<(BlockHeader, BlockHeight)> {
+        self.headers.get(hash).copied()
+    }
+
+    fn get_height(&self) -> BlockHeight {
+        self.height
+    }
+
+    fn get_initial_hash(&self) -> BlockHash {
+        self.initial_hash
+    }
+}
+
+fn next_header(prev: &BlockHeader, version: i32, time_delta: u32, bits: u32) -> BlockHeader {
+    BlockHeader {
+        version,
+        prev_blockhash: prev.block_hash(),
+        merkle_root: TxMerkleNode::hash(&prev.nonce.to_le_bytes()),
+        time: prev.time + time_delta,
+        bits,
+        nonce: 0,
+    }
+}
+
+fn meets_target(input: &[u8], bits: u32) -> bool {
+    let mut hash = scrypt_1024_1_1_256(input);
+    hash.reverse();
+    Uint256::from_be_bytes(hash) <= BlockHeader::u256_from_compact_target(bits)
+}
+
+/// Finds a nonce such that the header meets its target.
+fn mine(mut header: BlockHeader) -> BlockHeader {
+    while !meets_target(&serialize(&header), header.bits) {
+        header.nonce += 1;
+    }
+    header
+}
+
+/// Builds a merge-mining proof for the header and mines the parent block.
+fn mine_auxpow(header: &BlockHeader) -> AuxPow {
+    let mut auxpow = build_auxpow(header.block_hash(), header.bits);
+    while !meets_target(&serialize(&auxpow.parent_block_header), header.bits) {
+        auxpow.parent_block_header.nonce += 1;
+    }
+    auxpow
+}
+
+fn regtest_store() -> (SimpleHeaderStore, BlockHeader) {
+    let genesis = DogecoinNetwork::Regtest.genesis_block_header();
+    (SimpleHeaderStore::new(genesis, 0), genesis)
+}
+
+/// Extends the regtest chain with legacy headers up to the given height.
+fn extend_regtest_chain(
+    store: &mut SimpleHeaderStore,
+    mut tip: BlockHeader,
+    height: BlockHeight,
+) -> BlockHeader {
+    while store.get_height() < height {
+        tip = mine(next_header(&tip, 1, 60, REGTEST_BITS));
+        store.add(tip);
+    }
+    tip
+}
+
+#[test]
+fn test_regtest_chain_with_auxpow_is_valid() {
+    let network = DogecoinNetwork::Regtest;
+    let (mut store, mut tip) = regtest_store();
+
+    for height in 1..=network.auxpow_height() + 5 {
+        let (header, auxpow) = if height < network.auxpow_height() {
+            (mine(next_header(&tip, 1, 60, REGTEST_BITS)), None)
+        } else if height % 2 == 0 {
+            let header = next_header(&tip, AUXPOW_VERSION, 60, REGTEST_BITS);
+            (header, Some(mine_auxpow(&header)))
+        } else {
+            (
+                mine(next_header(&tip, NON_AUXPOW_VERSION, 60, REGTEST_BITS)),
+                None,
+            )
+        };
+        assert_eq!(
+            validate_header(
+                &network,
+                &store,
+                &header,
+                auxpow.as_ref(),
+                header.time as u64
+            ),
+            Ok(()),
+            "header at height {} should be valid",
+            height
+        );
+        store.add(header);
+        tip = header;
+    }
+}
+
+#[test]
+fn test_header_versions_around_auxpow_height() {
+    let network = DogecoinNetwork::Regtest;
+    let (mut store, genesis) = regtest_store();
+    let tip = extend_regtest_chain(&mut store, genesis, network.auxpow_height() - 2);
+
+    // Merge-mining is not allowed yet.
+    let header = next_header(&tip, AUXPOW_VERSION, 60, REGTEST_BITS);
+    assert_eq!(
+        validate_header(
+            &network,
+            &store,
+            &header,
+            Some(&mine_auxpow(&header)),
+            u64::MAX / 2
+        ),
+        Err(ValidateHeaderError::AuxPowBeforeAuxPowHeight)
+    );
+
+    // Legacy headers are no longer allowed from the AuxPoW height on.
+    let tip = extend_regtest_chain(&mut store, tip, network.auxpow_height() - 1);
+    let header = mine(next_header(&tip, 1, 60, REGTEST_BITS));
+    assert_eq!(
+        validate_header(&network, &store, &header, None, u64::MAX / 2),
+        Err(ValidateHeaderError::LegacyBlockAfterAuxPowHeight)
+    );
+
+    // The merge-mining proof must be present if and only if the version announces it.
+    let header = next_header(&tip, AUXPOW_VERSION, 60, REGTEST_BITS);
+    assert_eq!(
+        validate_header(&network, &store, &header, None, u64::MAX / 2),
+        Err(ValidateHeaderError::MissingAuxPow)
+    );
+    let header = mine(next_header(&tip, NON_AUXPOW_VERSION, 60, REGTEST_BITS));
+    assert_eq!(
+        validate_header(
+            &network,
+            &store,
+            &header,
+            Some(&mine_auxpow(&header)),
+            u64::MAX / 2
+        ),
+        Err(ValidateHeaderError::UnexpectedAuxPow)
+    );
+
+    // The proof must commit to the header.
+    let header = next_header(&tip, AUXPOW_VERSION, 60, REGTEST_BITS);
+    let other_header = next_header(&tip, AUXPOW_VERSION, 61, REGTEST_BITS);
+    assert!(matches!(
+        validate_header(
+            &network,
+            &store,
+            &header,
+            Some(&mine_auxpow(&other_header)),
+            u64::MAX / 2
+        ),
+        Err(ValidateHeaderError::InvalidAuxPow(_))
+    ));
+}
+
+#[test]
+fn test_wrong_chain_id_is_rejected() {
+    let network = DogecoinNetwork::Mainnet;
+    let genesis = network.genesis_block_header();
+    let store = SimpleHeaderStore::new(genesis, 0);
+
+    let header = next_header(
+        &genesis,
+        (DOGECOIN_CHAIN_ID + 1) << 16 | 4,
+        60,
+        genesis.bits,
+    );
+    assert_eq!(
+        validate_header(&network, &store, &header, None, u64::MAX / 2),
+        Err(ValidateHeaderError::WrongChainId {
+            chain_id: DOGECOIN_CHAIN_ID + 1
+        })
+    );
+}
+
+#[test]
+fn test_timestamp_rules() {
+    let network = DogecoinNetwork::Regtest;
+    let (mut store, genesis) = regtest_store();
+    let tip = extend_regtest_chain(&mut store, genesis, 11);
+
+    // The timestamp must be after the median time of the last 11 blocks.
+    let median_time = tip.time - 5 * 60;
+    let mut header = next_header(&tip, 1, 0, REGTEST_BITS);
+    header.time = median_time;
+    assert_eq!(
+        validate_header(&network, &store, &mine(header), None, tip.time as u64),
+        Err(ValidateHeaderError::HeaderIsOld)
+    );
+    header.time = median_time + 1;
+    assert_eq!(
+        validate_header(&network, &store, &mine(header), None, tip.time as u64),
+        Ok(())
+    );
+
+    // The timestamp must not be more than 2 hours in the future.
+    let header = mine(next_header(&tip, 1, 2 * 60 * 60 + 1, REGTEST_BITS));
+    assert_eq!(
+        validate_header(&network, &store, &header, None, tip.time as u64),
+        Err(ValidateHeaderError::HeaderIsTooFarInFuture {
+            block_time: header.time as u64,
+            max_allowed_time: tip.time as u64 + 2 * 60 * 60,
+        })
+    );
+}
+
+#[test]
+fn test_proof_of_work_rules() {
+    let network = DogecoinNetwork::Regtest;
+    let (store, genesis) = regtest_store();
+
+    let mut header = mine(next_header(&genesis, 1, 60, REGTEST_BITS));
+    while meets_target(&serialize(&header), header.bits) {
+        header.nonce += 1;
+    }
+    assert_eq!(
+        validate_header(&network, &store, &header, None, u64::MAX / 2),
+        Err(ValidateHeaderError::InvalidPoWForHeaderTarget)
+    );
+
+    let header = next_header(&genesis, 1, 60, 0x1f7fffff);
+    assert_eq!(
+        validate_header(&network, &store, &header, None, u64::MAX / 2),
+        Err(ValidateHeaderError::InvalidPoWForComputedTarget)
+    );
+}
+
+#[test]
+fn test_prev_header_not_found() {
+    let network = DogecoinNetwork::Regtest;
+    let (store, genesis) = regtest_store();
+    let header = next_header(
+        &next_header(&genesis, 1, 60, REGTEST_BITS),
+        1,
+        60,
+        REGTEST_BITS,
+    );
+    assert_eq!(
+        validate_header(&network, &store, &header, None, u64::MAX / 2),
+        Err(ValidateHeaderError::PrevHeaderNotFound)
+    );
+}
+
+/// Returns a store with headers from `start_height` on, spaced by the given times,
+/// and the last header.
+fn store_with_times(
+    start_height: BlockHeight,
+    bits: u32,
+    time_deltas: &[u32],
+) -> (SimpleHeaderStore, BlockHeader) {
+    let mut tip = BlockHeader {
+        version: AUXPOW_VERSION,
+        prev_blockhash: BlockHash::default(),
+        merkle_root: TxMerkleNode::default(),
+        time: 1_600_000_000,
+        bits,
+        nonce: 0,
+    };
+    let mut store = SimpleHeaderStore::new(tip, start_height);
+    for delta in time_deltas {
+        tip = next_header(&tip, AUXPOW_VERSION, *delta, bits);
+        store.add(tip);
+    }
+    (store, tip)
+}
+
+#[test]
+fn test_digishield_difficulty_adjustment() {
+    let network = DogecoinNetwork::Mainnet;
+    for (time_delta, expected_bits) in [
+        // Blocks on schedule keep the difficulty.
+        (60, 0x1c00ffff),
+        // Slow blocks decrease the difficulty by at most a third.
+        (10_000, 0x1c017ffe),
+        // Fast blocks increase the difficulty by an eighth of the deviation.
+        (0, 0x1c00e221),
+    ] {
+        let (store, prev) = store_with_times(200_000, 0x1c00ffff, &[time_delta]);
+        let header = next_header(&prev, AUXPOW_VERSION, 60, 0);
+        assert_eq!(
+            get_next_work_required(&network, &store, &prev, 200_001, &header),
+            Ok(expected_bits),
+            "unexpected target after {} seconds",
+            time_delta
+        );
+    }
+
+    // The target never exceeds the maximum target.
+    let (store, prev) = store_with_times(200_000, 0x1e0fffff, &[10_000]);
+    let header = next_header(&prev, AUXPOW_VERSION, 60, 0);
+    assert_eq!(
+        get_next_work_required(&network, &store, &prev, 200_001, &header),
+        Ok(0x1e0fffff)
+    );
+
+    // The parent of the previous header is needed.
+    let (store, prev) = store_with_times(200_000, 0x1c00ffff, &[]);
+    assert_eq!(
+        get_next_work_required(&network, &store, &prev, 200_000, &header),
+        Err(ValidateHeaderError::AncestorNotFound)
+    );
+}
+
+#[test]
+fn test_legacy_difficulty_adjustment() {
+    let network = DogecoinNetwork::Mainnet;
+    // Headers from height 239 to 479, mined twice as fast as expected.
+    let (store, prev) = store_with_times(239, 0x1c00ffff, &[30; 240]);
+    assert_eq!(store.get_height(), 479);
+
+    // The difficulty only changes every 240 blocks.
+    let header = next_header(&prev, 1, 30, 0);
+    let (parent, _) = store.get_header(&prev.prev_blockhash).unwrap();
+    assert_eq!(
+        get_next_work_required(&network, &store, &parent, 478, &prev),
+        Ok(0x1c00ffff)
+    );
+    // At a retarget, the target is halved.
+    assert_eq!(
+        get_next_work_required(&network, &store, &prev, 479, &header),
+        Ok(0x1b7fff80)
+    );
+}
+
+#[test]
+fn test_testnet_min_difficulty_blocks() {
+    let network = DogecoinNetwork::Testnet;
+    let pow_limit_bits = network.pow_limit_bits();
+
+    // Before DigiShield, a block more than 2 minutes after its parent may have the
+    // minimum difficulty.
+    let (mut store, prev) = store_with_times(1_000, 0x1c00ffff, &[]);
+    let slow = next_header(&prev, 1, 121, pow_limit_bits);
+    assert_eq!(
+        get_next_work_required(&network, &store, &prev, 1_000, &slow),
+        Ok(pow_limit_bits)
+    );
+    // Otherwise, the difficulty is the one of the last regular block.
+    store.add(slow);
+    let header = next_header(&slow, 1, 60, 0);
+    assert_eq!(
+        get_next_work_required(&network, &store, &slow, 1_001, &header),
+        Ok(0x1c00ffff)
+    );
+    // Such blocks are not allowed on mainnet.
+    assert_eq!(
+        get_next_work_required(&DogecoinNetwork::Mainnet, &store, &prev, 1_000, &slow),
+        Ok(0x1c00ffff)
+    );
+
+    // With DigiShield, the minimum difficulty is allowed from the height 157,500 on.
+    let (store, prev) = store_with_times(157_499, 0x1c00ffff, &[60]);
+    let slow = next_header(&prev, AUXPOW_VERSION, 121, 0);
+    assert_eq!(
+        get_next_work_required(&network, &store, &prev, 157_500, &slow),
+        Ok(pow_limit_bits)
+    );
+    let header = next_header(&prev, AUXPOW_VERSION, 60, 0);
+    assert_eq!(
+        get_next_work_required(&network, &store, &prev, 157_500, &header),
+        Ok(0x1c00ffff)
+    );
+    let (store, prev) = store_with_times(157_498, 0x1c00ffff, &[60]);
+    let slow = next_header(&prev, AUXPOW_VERSION, 121, 0);
+    assert_eq!(
+        get_next_work_required(&network, &store, &prev, 157_499, &slow),
+        Ok(0x1c00ffff)
+    );
+}
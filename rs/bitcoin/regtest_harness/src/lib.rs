@@ -0,0 +1,386 @@
+//! A harness running the Bitcoin canister on a [StateMachine] against a local
+//! Bitcoin regtest network.
+//!
+//! The harness starts the Bitcoin adapter in-process and connects it to a
+//! `bitcoind` node running in regtest mode, or to any other P2P nodes such as
+//! the fake node of `ic-btc-adapter-test-utils`. On every tick, the state
+//! machine forwards the outstanding `bitcoin_get_successors` and
+//! `bitcoin_send_transaction_internal` requests of the Bitcoin canister to the
+//! adapter and delivers the responses in the next batch, as a replica does.
+//!
+//! ```ignore
+//! let harness = RegtestHarness::new(bitcoin_canister_wasm);
+//! let address = harness.bitcoind_client().get_new_address(None, None).unwrap();
+//! harness.mine_blocks(101, &address);
+//! harness.sync(MAX_TICKS);
+//! let utxos = harness.get_utxos(&address.to_string());
+//! ```
+//!
+//! PocketIC instances are connected to a local regtest network through a
+//! [RegtestAdapter], whose socket is passed to the PocketIC server:
+//!
+//! ```ignore
+//! let adapter = RegtestAdapter::new();
+//! let pic = PocketIcBuilder::new()
+//!     .with_bitcoin_subnet()
+//!     .with_bitcoin_adapter_uds_path(adapter.uds_path().to_path_buf())
+//!     .build();
+//! pic.create_canister_with_id(None, None, bitcoin_canister_id().into()).unwrap();
+//! pic.install_canister(bitcoin_canister_id().into(), bitcoin_canister_wasm, bitcoin_canister_init_arg(), None);
+//! ```
+use bitcoin::{Address, BlockHash, Script};
+use bitcoincore_rpc::{Auth, Client, RpcApi};
+use bitcoind::{BitcoinD, Conf, P2P};
+use candid::{Decode, Encode};
+use ic_btc_adapter::{
+    config::{Config as AdapterConfig, IncomingSource},
+    start_grpc_server_and_router, AdapterState,
+};
+use ic_btc_interface::{
+    Config as BitcoinCanisterConfig, Fees, Flag, GetUtxosRequest, GetUtxosResponse, Height,
+    Network, NetworkInRequest, UtxosFilterInRequest,
+};
+use ic_config::execution_environment::BITCOIN_TESTNET_CANISTER_ID;
+use ic_logger::replica_logger::no_op_logger;
+use ic_metrics::MetricsRegistry;
+use ic_state_machine_tests::{
+    CanisterId, Cycles, PrincipalId, StateMachine, StateMachineBuilder, WasmResult,
+};
+use std::{net::SocketAddr, path::Path, str::FromStr, sync::Arc};
+use tempfile::TempPath;
+use tokio::runtime::Runtime;
+
+/// The number of confirmations after which the Bitcoin canister considers a
+/// block stable.
+pub const STABILITY_THRESHOLD: u128 = 6;
+
+/// The ID of the Bitcoin canister, which is installed at the ID of the testnet
+/// canister since the adapter for testnet also serves regtest.
+pub fn bitcoin_canister_id() -> CanisterId {
+    CanisterId::try_from(PrincipalId::from_str(BITCOIN_TESTNET_CANISTER_ID).unwrap()).unwrap()
+}
+
+/// The Candid-encoded init argument of the Bitcoin canister for the regtest
+/// network, with all fees set to zero.
+pub fn bitcoin_canister_init_arg() -> Vec<u8> {
+    Encode!(&BitcoinCanisterConfig {
+        stability_threshold: STABILITY_THRESHOLD,
+        network: Network::Regtest,
+        blocks_source: candid::Principal::management_canister(),
+        syncing: Flag::Enabled,
+        fees: Fees {
+            get_utxos_base: 0,
+            get_utxos_cycles_per_ten_instructions: 0,
+            get_utxos_maximum: 0,
+            get_balance: 0,
+            get_balance_maximum: 0,
+            get_current_fee_percentiles: 0,
+            get_current_fee_percentiles_maximum: 0,
+            send_transaction_base: 0,
+            send_transaction_per_byte: 0,
+        },
+        api_access: Flag::Enabled,
+        disable_api_if_not_fully_synced: Flag::Disabled,
+        watchdog_canister: None,
+    })
+    .unwrap()
+}
+
+/// Starts a `bitcoind` node in regtest mode from the binary at `BITCOIN_CORE_PATH`.
+pub fn start_bitcoind() -> BitcoinD {
+    let mut conf = Conf::default();
+    conf.p2p = P2P::Yes;
+
+    let path =
+        std::env::var("BITCOIN_CORE_PATH").expect("Failed to get bitcoin core path env variable");
+
+    BitcoinD::with_conf(path, &conf).expect("Failed to start bitcoind")
+}
+
+/// Returns the address under which `bitcoind` accepts P2P connections.
+pub fn bitcoind_p2p_address(bitcoind: &BitcoinD) -> SocketAddr {
+    match bitcoind.p2p_connect(true).unwrap() {
+        P2P::Connect(addr, _) => addr.into(),
+        _ => panic!("bitcoind does not accept P2P connections"),
+    }
+}
+
+/// Starts a Bitcoin adapter for the regtest network connected to the given
+/// nodes, listening on a fresh Unix domain socket. The adapter runs on `rt`
+/// and stops with it. The socket is removed when the returned path is dropped.
+pub fn start_adapter(rt: &Runtime, nodes: Vec<SocketAddr>) -> TempPath {
+    // Only reserve a path, the adapter binds the socket itself.
+    let (_, uds_path) = tempfile::Builder::new()
+        .make(|_| Ok(()))
+        .expect("Failed to create a path for the adapter socket")
+        .into_parts();
+
+    let config = AdapterConfig {
+        network: bitcoin::Network::Regtest,
+        incoming_source: IncomingSource::Path(uds_path.to_path_buf()),
+        nodes,
+        ipv6_only: true,
+        address_limits: (1, 1),
+        ..Default::default()
+    };
+    rt.block_on(async {
+        let adapter_state = AdapterState::new(config.idle_seconds);
+        // The adapter only syncs blocks while it is not idle.
+        adapter_state.received_now();
+        start_grpc_server_and_router(
+            &config,
+            &MetricsRegistry::new(),
+            no_op_logger(),
+            adapter_state,
+        );
+    });
+    uds_path
+}
+
+/// A `bitcoind` node in regtest mode and an in-process adapter connected to it,
+/// for connecting a PocketIC instance to a local regtest network.
+///
+/// The PocketIC server connects its bitcoin subnet to the adapter's socket when
+/// the instance is created with `PocketIcBuilder::with_bitcoin_adapter_uds_path`,
+/// and then forwards the requests of the Bitcoin canister to the adapter on
+/// every tick.
+pub struct RegtestAdapter {
+    bitcoind: BitcoinD,
+    bitcoind_client: Client,
+    // Dropped before the runtime that serves the adapter.
+    uds_path: TempPath,
+    _runtime: Runtime,
+}
+
+impl RegtestAdapter {
+    /// Starts `bitcoind` in regtest mode and an adapter connected to it.
+    pub fn new() -> Self {
+        let bitcoind = start_bitcoind();
+        let bitcoind_client = Client::new(
+            bitcoind.rpc_url().as_str(),
+            Auth::CookieFile(bitcoind.params.cookie_file.clone()),
+        )
+        .expect("Failed to create a bitcoind client");
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .enable_all()
+            .build()
+            .expect("Failed to create a tokio runtime");
+        let uds_path = start_adapter(&runtime, vec![bitcoind_p2p_address(&bitcoind)]);
+        Self {
+            bitcoind,
+            bitcoind_client,
+            uds_path,
+            _runtime: runtime,
+        }
+    }
+
+    /// The socket on which the adapter listens.
+    pub fn uds_path(&self) -> &Path {
+        &self.uds_path
+    }
+
+    /// The `bitcoind` node.
+    pub fn bitcoind(&self) -> &BitcoinD {
+        &self.bitcoind
+    }
+
+    /// The RPC client of the `bitcoind` node.
+    pub fn bitcoind_client(&self) -> &Client {
+        &self.bitcoind_client
+    }
+
+    /// Mines `count` blocks paying their rewards to `address` and returns their hashes.
+    pub fn mine_blocks(&self, count: u64, address: &Address) -> Vec<BlockHash> {
+        self.bitcoind_client
+            .generate_to_address(count, address)
+            .expect("Failed to mine blocks")
+    }
+}
+
+impl Default for RegtestAdapter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The Bitcoin canister on a [StateMachine], synced through an in-process
+/// adapter with a local regtest network.
+pub struct RegtestHarness {
+    env: StateMachine,
+    bitcoind: Option<BitcoinD>,
+    bitcoind_client: Option<Client>,
+    // Dropped after the state machine, whose adapter client uses the runtime.
+    _adapter_uds_path: TempPath,
+    _runtime: Arc<Runtime>,
+}
+
+impl RegtestHarness {
+    /// Starts `bitcoind` in regtest mode, an adapter connected to it, and a state
+    /// machine running the given Bitcoin canister wasm.
+    pub fn new(bitcoin_canister_wasm: Vec<u8>) -> Self {
+        let bitcoind = start_bitcoind();
+        let bitcoind_client = Client::new(
+            bitcoind.rpc_url().as_str(),
+            Auth::CookieFile(bitcoind.params.cookie_file.clone()),
+        )
+        .expect("Failed to create a bitcoind client");
+        let nodes = vec![bitcoind_p2p_address(&bitcoind)];
+        Self {
+            bitcoind: Some(bitcoind),
+            bitcoind_client: Some(bitcoind_client),
+            ..Self::with_nodes(bitcoin_canister_wasm, nodes)
+        }
+    }
+
+    /// Starts an adapter connected to the given P2P nodes, e.g. a scripted
+    /// stand-in for `bitcoind`, and a state machine running the given Bitcoin
+    /// canister wasm.
+    pub fn with_nodes(bitcoin_canister_wasm: Vec<u8>, nodes: Vec<SocketAddr>) -> Self {
+        // The adapter and its client need a runtime that makes progress while the
+        // state machine blocks on requests.
+        let runtime = Arc::new(
+            tokio::runtime::Builder::new_multi_thread()
+                .enable_all()
+                .build()
+                .expect("Failed to create a tokio runtime"),
+        );
+        let adapter_uds_path = start_adapter(&runtime, nodes);
+
+        let canister_id = bitcoin_canister_id();
+        let env = StateMachineBuilder::new()
+            .with_default_canister_range()
+            .with_extra_canister_range(canister_id..=canister_id)
+            .with_runtime(runtime.clone())
+            .with_bitcoin_testnet_uds_path(adapter_uds_path.to_path_buf())
+            .build();
+
+        env.create_canister_with_cycles(Some(canister_id.into()), Cycles::new(0), None);
+        env.install_existing_canister(
+            canister_id,
+            bitcoin_canister_wasm,
+            bitcoin_canister_init_arg(),
+        )
+        .expect("Failed to install the Bitcoin canister");
+
+        Self {
+            env,
+            bitcoind: None,
+            bitcoind_client: None,
+            _adapter_uds_path: adapter_uds_path,
+            _runtime: runtime,
+        }
+    }
+
+    /// The state machine running the Bitcoin canister.
+    pub fn env(&self) -> &StateMachine {
+        &self.env
+    }
+
+    /// The RPC client of the `bitcoind` node started by [RegtestHarness::new].
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the harness was created with [RegtestHarness::with_nodes].
+    pub fn bitcoind_client(&self) -> &Client {
+        self.bitcoind_client
+            .as_ref()
+            .expect("The harness does not run bitcoind")
+    }
+
+    /// Returns the `bitcoind` node started by [RegtestHarness::new], if any.
+    pub fn bitcoind(&self) -> Option<&BitcoinD> {
+        self.bitcoind.as_ref()
+    }
+
+    /// Mines `count` blocks paying their rewards to `address` and returns their hashes.
+    pub fn mine_blocks(&self, count: u64, address: &Address) -> Vec<BlockHash> {
+        self.bitcoind_client()
+            .generate_to_address(count, address)
+            .expect("Failed to mine blocks")
+    }
+
+    /// Returns the height of the tip of the main chain of the Bitcoin canister.
+    pub fn tip_height(&self) -> Height {
+        // Any address does, as only the tip in the response is of interest.
+        let address = Address::p2wsh(&Script::new(), bitcoin::Network::Regtest);
+        self.get_utxos(&address.to_string()).tip_height
+    }
+
+    /// Ticks until the tip of the Bitcoin canister has the given height.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the canister did not reach the height within
+    /// `max_ticks` ticks.
+    pub fn sync_to_height(&self, height: Height, max_ticks: usize) {
+        for _ in 0..max_ticks {
+            if self.tip_height() >= height {
+                return;
+            }
+            self.env.tick();
+            // Give the adapter time to download blocks announced to it.
+            std::thread::sleep(std::time::Duration::from_millis(100));
+        }
+        panic!(
+            "The Bitcoin canister did not reach height {} after {} ticks, its tip is at height {}",
+            height,
+            max_ticks,
+            self.tip_height()
+        );
+    }
+
+    /// Ticks until the Bitcoin canister is synced with the tip of `bitcoind`.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if the canister did not sync within `max_ticks` ticks.
+    pub fn sync(&self, max_ticks: usize) {
+        let height = self
+            .bitcoind_client()
+            .get_block_count()
+            .expect("Failed to get the block count of bitcoind");
+        self.sync_to_height(height as Height, max_ticks);
+    }
+
+    /// Returns the UTXOs of `address` known to the Bitcoin canister.
+    pub fn get_utxos(&self, address: &str) -> GetUtxosResponse {
+        self.get_utxos_with_filter(address, None)
+    }
+
+    /// Returns the UTXOs of `address` known to the Bitcoin canister that have at
+    /// least `min_confirmations` confirmations.
+    pub fn get_utxos_with_min_confirmations(
+        &self,
+        address: &str,
+        min_confirmations: u32,
+    ) -> GetUtxosResponse {
+        self.get_utxos_with_filter(
+            address,
+            Some(UtxosFilterInRequest::MinConfirmations(min_confirmations)),
+        )
+    }
+
+    fn get_utxos_with_filter(
+        &self,
+        address: &str,
+        filter: Option<UtxosFilterInRequest>,
+    ) -> GetUtxosResponse {
+        let result = self
+            .env
+            .execute_ingress(
+                bitcoin_canister_id(),
+                "bitcoin_get_utxos",
+                Encode!(&GetUtxosRequest {
+                    address: address.to_string(),
+                    network: NetworkInRequest::Regtest,
+                    filter,
+                })
+                .unwrap(),
+            )
+            .expect("Failed to call bitcoin_get_utxos");
+        match result {
+            WasmResult::Reply(bytes) => Decode!(&bytes, GetUtxosResponse).unwrap(),
+            WasmResult::Reject(reject) => panic!("bitcoin_get_utxos was rejected: {}", reject),
+        }
+    }
+}
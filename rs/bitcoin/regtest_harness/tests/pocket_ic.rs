@@ -0,0 +1,71 @@
+use bitcoincore_rpc::RpcApi;
+use candid::{Decode, Encode, Principal};
+use ic_btc_interface::{GetUtxosRequest, GetUtxosResponse, NetworkInRequest};
+use ic_btc_regtest_harness::{bitcoin_canister_id, bitcoin_canister_init_arg, RegtestAdapter};
+use pocket_ic::{PocketIc, PocketIcBuilder, WasmResult};
+
+const MAX_TICKS: usize = 100;
+
+fn bitcoin_canister_wasm() -> Vec<u8> {
+    let path = std::env::var("IC_BTC_CANISTER_WASM_PATH")
+        .expect("Failed to get the Bitcoin canister wasm path env variable");
+    std::fs::read(path).expect("Failed to read the Bitcoin canister wasm")
+}
+
+fn get_utxos(pic: &PocketIc, canister_id: Principal, address: &str) -> GetUtxosResponse {
+    let result = pic
+        .update_call(
+            canister_id,
+            Principal::anonymous(),
+            "bitcoin_get_utxos",
+            Encode!(&GetUtxosRequest {
+                address: address.to_string(),
+                network: NetworkInRequest::Regtest,
+                filter: None,
+            })
+            .unwrap(),
+        )
+        .expect("Failed to call bitcoin_get_utxos");
+    match result {
+        WasmResult::Reply(bytes) => Decode!(&bytes, GetUtxosResponse).unwrap(),
+        WasmResult::Reject(reject) => panic!("bitcoin_get_utxos was rejected: {}", reject),
+    }
+}
+
+#[test]
+fn test_pocket_ic_get_utxos_of_mined_blocks() {
+    let adapter = RegtestAdapter::new();
+    let pic = PocketIcBuilder::new()
+        .with_bitcoin_subnet()
+        .with_bitcoin_adapter_uds_path(adapter.uds_path().to_path_buf())
+        .build();
+
+    let canister_id = bitcoin_canister_id().get().0;
+    pic.create_canister_with_id(None, None, canister_id)
+        .expect("Failed to create the Bitcoin canister");
+    pic.install_canister(
+        canister_id,
+        bitcoin_canister_wasm(),
+        bitcoin_canister_init_arg(),
+        None,
+    );
+
+    let address = adapter
+        .bitcoind_client()
+        .get_new_address(None, None)
+        .unwrap();
+    adapter.mine_blocks(10, &address);
+
+    for _ in 0..MAX_TICKS {
+        if get_utxos(&pic, canister_id, &address.to_string()).tip_height >= 10 {
+            break;
+        }
+        pic.tick();
+        // Give the adapter time to download blocks announced to it.
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+
+    let response = get_utxos(&pic, canister_id, &address.to_string());
+    assert_eq!(response.tip_height, 10);
+    assert_eq!(response.utxos.len(), 10);
+}
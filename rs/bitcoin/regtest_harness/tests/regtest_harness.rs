@@ -0,0 +1,38 @@
+use bitcoincore_rpc::RpcApi;
+use ic_btc_regtest_harness::RegtestHarness;
+
+const MAX_TICKS: usize = 100;
+
+/// The reward of a block below height 150 on regtest.
+const BLOCK_REWARD: u64 = 50 * 100_000_000;
+
+fn bitcoin_canister_wasm() -> Vec<u8> {
+    let path = std::env::var("IC_BTC_CANISTER_WASM_PATH")
+        .expect("Failed to get the Bitcoin canister wasm path env variable");
+    std::fs::read(path).expect("Failed to read the Bitcoin canister wasm")
+}
+
+#[test]
+fn test_get_utxos_of_mined_blocks() {
+    let harness = RegtestHarness::new(bitcoin_canister_wasm());
+    let address = harness
+        .bitcoind_client()
+        .get_new_address(None, None)
+        .unwrap();
+
+    harness.mine_blocks(10, &address);
+    harness.sync(MAX_TICKS);
+
+    let response = harness.get_utxos(&address.to_string());
+    assert_eq!(response.tip_height, 10);
+    assert_eq!(response.utxos.len(), 10);
+    assert!(response.utxos.iter().all(|utxo| utxo.value == BLOCK_REWARD));
+
+    // Blocks mined later are picked up by the canister as well.
+    harness.mine_blocks(5, &address);
+    harness.sync(MAX_TICKS);
+
+    let response = harness.get_utxos_with_min_confirmations(&address.to_string(), 6);
+    assert_eq!(response.tip_height, 15);
+    assert_eq!(response.utxos.len(), 10);
+}
@@ -766,6 +766,7 @@ pub async fn status(
         impl_hash: None,
         replica_health_status: Some(health),
         certified_height: None,
+        node_keys: None,
     };
 
     // Serialize to CBOR
@@ -57,6 +57,14 @@ const INSTRUCTION_OVERHEAD_PER_CANISTER_FOR_FINALIZATION: NumInstructions =
 // 2) execution does not waste the time available per round.
 const MAX_INSTRUCTIONS_PER_ROUND: NumInstructions = NumInstructions::new(7 * B);
 
+// Instructions reserved for `Heartbeat` and `GlobalTimer` tasks on top of the
+// round limit. Once the round limit is reached, the scheduler does not start
+// new input message executions, but still runs the heartbeats and timers that
+// are due until this reserve is used up. This keeps timers responsive when the
+// subnet is busy with regular messages.
+const RESERVED_INSTRUCTIONS_PER_ROUND_FOR_SYSTEM_TASKS: NumInstructions =
+    NumInstructions::new(500 * M);
+
 // Limit per `install_code` message. It's bigger than the limit for a regular
 // update call to allow for canisters with bigger state to be upgraded.
 // This is a temporary measure until a longer term solution that alleviates the
@@ -168,6 +176,10 @@ pub struct SchedulerConfig {
     /// thread).
     pub max_instructions_per_round: NumInstructions,
 
+    /// Amount of instructions that only `Heartbeat` and `GlobalTimer` tasks
+    /// may use after `max_instructions_per_round` is reached.
+    pub reserved_instructions_per_round_for_system_tasks: NumInstructions,
+
     /// Maximum amount of instructions a single message execution can consume.
     pub max_instructions_per_message: NumInstructions,
 
@@ -251,6 +263,8 @@ impl SchedulerConfig {
             max_paused_executions: MAX_PAUSED_EXECUTIONS,
             subnet_heap_delta_capacity: SUBNET_HEAP_DELTA_CAPACITY,
             max_instructions_per_round: MAX_INSTRUCTIONS_PER_ROUND,
+            reserved_instructions_per_round_for_system_tasks:
+                RESERVED_INSTRUCTIONS_PER_ROUND_FOR_SYSTEM_TASKS,
             max_instructions_per_message: MAX_INSTRUCTIONS_PER_MESSAGE,
             max_instructions_per_message_without_dts: MAX_INSTRUCTIONS_PER_MESSAGE_WITHOUT_DTS,
             max_instructions_per_slice: MAX_INSTRUCTIONS_PER_SLICE,
@@ -280,6 +294,8 @@ impl SchedulerConfig {
             max_paused_executions: MAX_PAUSED_EXECUTIONS,
             subnet_heap_delta_capacity: SUBNET_HEAP_DELTA_CAPACITY,
             max_instructions_per_round: MAX_INSTRUCTIONS_PER_ROUND * SYSTEM_SUBNET_FACTOR,
+            reserved_instructions_per_round_for_system_tasks:
+                RESERVED_INSTRUCTIONS_PER_ROUND_FOR_SYSTEM_TASKS * SYSTEM_SUBNET_FACTOR,
             // Effectively disable DTS on system subnets.
             max_instructions_per_message: max_instructions_per_message_without_dts,
             max_instructions_per_message_without_dts,
@@ -316,6 +332,8 @@ impl SchedulerConfig {
             max_paused_executions: MAX_PAUSED_EXECUTIONS,
             subnet_heap_delta_capacity: SUBNET_HEAP_DELTA_CAPACITY,
             max_instructions_per_round: MAX_INSTRUCTIONS_PER_ROUND,
+            reserved_instructions_per_round_for_system_tasks:
+                RESERVED_INSTRUCTIONS_PER_ROUND_FOR_SYSTEM_TASKS,
             max_instructions_per_message: MAX_INSTRUCTIONS_PER_MESSAGE,
             max_instructions_per_message_without_dts: MAX_INSTRUCTIONS_PER_MESSAGE_WITHOUT_DTS,
             max_instructions_per_slice: MAX_INSTRUCTIONS_PER_SLICE,
@@ -23,7 +23,10 @@ use ic_test_utilities_registry::{setup_registry_non_final, SubnetRecordBuilder};
 use ic_test_utilities_time::FastForwardTimeSource;
 use ic_types::{
     batch::{BatchPayload, ValidationContext},
-    consensus::{block_maker::SubnetRecords, Payload},
+    consensus::{
+        block_maker::{SubnetLoad, SubnetRecords},
+        Payload,
+    },
     replica_config::ReplicaConfig,
     Height, RegistryVersion, SubnetId, Time,
 };
@@ -41,6 +44,7 @@ mock! {
             past_payloads: &[(Height, Time, Payload)],
             context: &ValidationContext,
             subnet_records: &SubnetRecords,
+            subnet_load: &SubnetLoad,
         ) -> BatchPayload;
 
         fn validate_payload<'a>(
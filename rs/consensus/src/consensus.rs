@@ -11,6 +11,7 @@ pub(crate) mod metrics;
 mod notary;
 mod payload;
 pub mod payload_builder;
+mod payload_sizing;
 mod priority;
 mod purger;
 mod random_beacon_maker;
@@ -248,6 +249,7 @@ impl ConsensusImpl {
                 dkg_pool.clone(),
                 ecdsa_pool.clone(),
                 state_manager.clone(),
+                message_routing.clone(),
                 stable_registry_version_age,
                 metrics_registry.clone(),
                 logger.clone(),
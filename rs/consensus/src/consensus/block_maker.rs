@@ -13,7 +13,8 @@ use ic_consensus_utils::{
     get_subnet_record, is_time_to_make_block, membership::Membership, pool_reader::PoolReader,
 };
 use ic_interfaces::{
-    consensus::PayloadBuilder, dkg::DkgPool, ecdsa::EcdsaPool, time_source::TimeSource,
+    consensus::PayloadBuilder, dkg::DkgPool, ecdsa::EcdsaPool, messaging::MessageRouting,
+    time_source::TimeSource,
 };
 use ic_interfaces_registry::RegistryClient;
 use ic_interfaces_state_manager::StateManager;
@@ -23,8 +24,9 @@ use ic_replicated_state::ReplicatedState;
 use ic_types::{
     batch::{BatchPayload, ValidationContext},
     consensus::{
-        block_maker::SubnetRecords, dkg, hashed, Block, BlockPayload, BlockProposal, DataPayload,
-        HasRank, Payload, RandomBeacon, Rank, SummaryPayload,
+        block_maker::{SubnetLoad, SubnetRecords},
+        dkg, hashed, Block, BlockPayload, BlockProposal, DataPayload, HasRank, Payload,
+        RandomBeacon, Rank, SummaryPayload,
     },
     crypto::CryptoHashOf,
     replica_config::ReplicaConfig,
@@ -70,6 +72,7 @@ pub struct BlockMaker {
     dkg_pool: Arc<RwLock<dyn DkgPool>>,
     ecdsa_pool: Arc<RwLock<dyn EcdsaPool>>,
     pub(crate) state_manager: Arc<dyn StateManager<State = ReplicatedState>>,
+    message_routing: Arc<dyn MessageRouting>,
     metrics: BlockMakerMetrics,
     ecdsa_payload_metrics: EcdsaPayloadMetrics,
     pub(crate) log: ReplicaLogger,
@@ -92,6 +95,7 @@ impl BlockMaker {
         dkg_pool: Arc<RwLock<dyn DkgPool>>,
         ecdsa_pool: Arc<RwLock<dyn EcdsaPool>>,
         state_manager: Arc<dyn StateManager<State = ReplicatedState>>,
+        message_routing: Arc<dyn MessageRouting>,
         stable_registry_version_age: Duration,
         metrics_registry: MetricsRegistry,
         log: ReplicaLogger,
@@ -106,6 +110,7 @@ impl BlockMaker {
             dkg_pool,
             ecdsa_pool,
             state_manager,
+            message_routing,
             log,
             metrics: BlockMakerMetrics::new(metrics_registry.clone()),
             ecdsa_payload_metrics: EcdsaPayloadMetrics::new(metrics_registry),
@@ -435,9 +440,14 @@ impl BlockMaker {
     ) -> BatchPayload {
         let past_payloads =
             pool.get_payloads_from_height(certified_height.increment(), parent.clone());
-        let payload =
-            self.payload_builder
-                .get_payload(height, &past_payloads, context, subnet_records);
+        let subnet_load = self.get_subnet_load(pool, parent, context);
+        let payload = self.payload_builder.get_payload(
+            height,
+            &past_payloads,
+            context,
+            subnet_records,
+            &subnet_load,
+        );
 
         self.metrics
             .get_payload_calls
@@ -447,6 +457,50 @@ impl BlockMaker {
         payload
     }
 
+    /// Returns the load signals of the subnet for a block on top of `parent`
+    /// with the given validation context.
+    fn get_subnet_load(
+        &self,
+        pool: &PoolReader<'_>,
+        parent: &Block,
+        context: &ValidationContext,
+    ) -> SubnetLoad {
+        let finalized_height = pool.get_finalized_height();
+        let finalization_latency = pool
+            .chain_iterator(parent.clone())
+            .take_while(|block| block.height > finalized_height)
+            .last()
+            .map(|oldest_unfinalized| {
+                context
+                    .time
+                    .saturating_duration_since(oldest_unfinalized.context.time)
+            })
+            .unwrap_or_default();
+
+        // Batches up to the catch-up package height are not delivered when
+        // the replica catches up via state sync.
+        let next_batch_height = self
+            .message_routing
+            .expected_batch_height()
+            .max(pool.get_catch_up_height().increment());
+        let batch_delivery_backlog = finalized_height
+            .increment()
+            .get()
+            .saturating_sub(next_batch_height.get());
+
+        self.metrics
+            .finalization_latency
+            .set(finalization_latency.as_secs_f64());
+        self.metrics
+            .batch_delivery_backlog
+            .set(batch_delivery_backlog as i64);
+
+        SubnetLoad {
+            finalization_latency,
+            batch_delivery_backlog,
+        }
+    }
+
     /// Log an entry for the proposed block and each of its ingress messages
     fn log_block(&self, block: &Block) {
         let hash = get_block_hash_string(block);
@@ -524,7 +578,10 @@ mod tests {
     use ic_interfaces::consensus_pool::ConsensusPool;
     use ic_logger::replica_logger::no_op_logger;
     use ic_metrics::MetricsRegistry;
-    use ic_test_utilities::types::ids::{node_test_id, subnet_test_id};
+    use ic_test_utilities::{
+        message_routing::FakeMessageRouting,
+        types::ids::{node_test_id, subnet_test_id},
+    };
     use ic_test_utilities_registry::{add_subnet_record, SubnetRecordBuilder};
     use ic_types::{
         consensus::{dkg, HasHeight, HasVersion},
@@ -588,6 +645,7 @@ mod tests {
                 dkg_pool.clone(),
                 ecdsa_pool.clone(),
                 state_manager.clone(),
+                Arc::new(FakeMessageRouting::new()),
                 Duration::from_millis(0),
                 MetricsRegistry::new(),
                 no_op_logger(),
@@ -637,7 +695,7 @@ mod tests {
 
             payload_builder
                 .expect_get_payload()
-                .withf(move |_, payloads, context, _| {
+                .withf(move |_, payloads, context, _, _| {
                     matches_expected_payloads(payloads) && context == &expected_context
                 })
                 .return_const(BatchPayload::default());
@@ -666,6 +724,7 @@ mod tests {
                 dkg_pool,
                 ecdsa_pool,
                 state_manager,
+                Arc::new(FakeMessageRouting::new()),
                 Duration::from_millis(0),
                 MetricsRegistry::new(),
                 no_op_logger(),
@@ -797,6 +856,7 @@ mod tests {
                 dkg_pool.clone(),
                 ecdsa_pool.clone(),
                 state_manager.clone(),
+                Arc::new(FakeMessageRouting::new()),
                 Duration::from_millis(0),
                 MetricsRegistry::new(),
                 no_op_logger(),
@@ -836,6 +896,7 @@ mod tests {
                 dkg_pool,
                 ecdsa_pool,
                 state_manager,
+                Arc::new(FakeMessageRouting::new()),
                 Duration::from_millis(0),
                 MetricsRegistry::new(),
                 no_op_logger(),
@@ -911,6 +972,7 @@ mod tests {
                 dkg_pool,
                 ecdsa_pool,
                 state_manager,
+                Arc::new(FakeMessageRouting::new()),
                 Duration::from_millis(0),
                 MetricsRegistry::new(),
                 no_op_logger(),
@@ -959,4 +1021,84 @@ mod tests {
             );
         })
     }
+
+    #[test]
+    fn test_get_subnet_load() {
+        ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
+            let node_ids = [node_test_id(0)];
+            let record = SubnetRecordBuilder::from(&node_ids)
+                .with_dkg_interval_length(100)
+                .build();
+            let subnet_id = subnet_test_id(0);
+            let Dependencies {
+                registry,
+                crypto,
+                mut pool,
+                time_source,
+                replica_config,
+                state_manager,
+                dkg_pool,
+                ecdsa_pool,
+                ..
+            } = dependencies_with_subnet_params(pool_config, subnet_id, vec![(1, record)]);
+            let membership = Arc::new(Membership::new(
+                pool.get_cache(),
+                registry.clone(),
+                replica_config.subnet_id,
+            ));
+            let message_routing = Arc::new(FakeMessageRouting::new());
+
+            let block_maker = BlockMaker::new(
+                Arc::clone(&time_source) as Arc<_>,
+                replica_config,
+                Arc::clone(&registry) as Arc<dyn RegistryClient>,
+                membership,
+                crypto,
+                Arc::new(MockPayloadBuilder::new()),
+                dkg_pool,
+                ecdsa_pool,
+                state_manager,
+                message_routing.clone(),
+                Duration::from_millis(0),
+                MetricsRegistry::new(),
+                no_op_logger(),
+            );
+
+            // Blocks up to height 4 are finalized, but only batches up to
+            // height 2 are delivered.
+            assert_eq!(pool.advance_round_normal_operation_n(4), Height::from(4));
+            *message_routing.next_batch_height.write().unwrap() = Height::from(3);
+            let parent = pool.latest_notarized_blocks().next().unwrap();
+            let context = ValidationContext {
+                certified_height: Height::from(0),
+                registry_version: RegistryVersion::from(1),
+                time: parent.context.time + Duration::from_secs(1),
+            };
+            assert_eq!(
+                block_maker.get_subnet_load(&PoolReader::new(&pool), &parent, &context),
+                SubnetLoad {
+                    finalization_latency: Duration::ZERO,
+                    batch_delivery_backlog: 2,
+                }
+            );
+
+            // Blocks at heights 5 and 6 are notarized but not finalized.
+            pool.prepare_round().dont_finalize().advance();
+            let oldest_unfinalized = pool.latest_notarized_blocks().next().unwrap();
+            pool.prepare_round().dont_finalize().advance();
+            let parent = pool.latest_notarized_blocks().next().unwrap();
+            assert_eq!(parent.height, Height::from(6));
+            let context = ValidationContext {
+                time: oldest_unfinalized.context.time + Duration::from_secs(10),
+                ..context
+            };
+            assert_eq!(
+                block_maker.get_subnet_load(&PoolReader::new(&pool), &parent, &context),
+                SubnetLoad {
+                    finalization_latency: Duration::from_secs(10),
+                    batch_delivery_backlog: 2,
+                }
+            );
+        })
+    }
 }
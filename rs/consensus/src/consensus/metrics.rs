@@ -17,7 +17,7 @@ use ic_types::{
     CountBytes, Height,
 };
 use prometheus::{
-    GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
 use std::{collections::BTreeMap, sync::RwLock};
 
@@ -36,6 +36,8 @@ pub(crate) const CRITICAL_ERROR_ECDSA_RETAIN_ACTIVE_TRANSCRIPTS: &str =
 pub struct BlockMakerMetrics {
     pub get_payload_calls: IntCounterVec,
     pub block_size_bytes_estimate: IntGaugeVec,
+    pub finalization_latency: Gauge,
+    pub batch_delivery_backlog: IntGauge,
 }
 
 impl BlockMakerMetrics {
@@ -49,7 +51,15 @@ impl BlockMakerMetrics {
             block_size_bytes_estimate: metrics_registry.int_gauge_vec(
                 "consensus_block_size_bytes_estimate",
                 "An estimate about the block size produced by the block maker.",
-                &["payload_type"])
+                &["payload_type"]),
+            finalization_latency: metrics_registry.gauge(
+                "consensus_block_maker_finalization_latency_seconds",
+                "The time since the oldest notarized but not yet finalized block was proposed, as observed by the block maker.",
+            ),
+            batch_delivery_backlog: metrics_registry.int_gauge(
+                "consensus_block_maker_batch_delivery_backlog",
+                "The number of finalized blocks not yet delivered to message routing, as observed by the block maker.",
+            ),
         }
     }
 
@@ -481,6 +491,7 @@ pub struct PayloadBuilderMetrics {
     pub get_payload_duration: Histogram,
     pub validate_payload_duration: Histogram,
     pub past_payloads_length: Histogram,
+    pub adaptive_payload_budget: IntGaugeVec,
 
     /// Critical error for payloads above the maximum supported size
     pub critical_error_payload_too_large: IntCounter,
@@ -514,6 +525,11 @@ impl PayloadBuilderMetrics {
                 "The length of past_payloads in payload selection",
                 linear_buckets(0.0, 1.0, 6),
             ),
+            adaptive_payload_budget: metrics_registry.int_gauge_vec(
+                "consensus_adaptive_payload_budget_bytes",
+                "The byte budget of the adaptively sized sections of the last proposed payload",
+                &["section"],
+            ),
             critical_error_payload_too_large: metrics_registry
                 .error_counter(CRITICAL_ERROR_PAYLOAD_TOO_LARGE),
             critical_error_validation_not_passed: metrics_registry
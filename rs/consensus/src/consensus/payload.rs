@@ -10,6 +10,7 @@ use ic_interfaces::{
     self_validating_payload::SelfValidatingPayloadBuilder,
 };
 use ic_logger::{error, warn, ReplicaLogger};
+use ic_protobuf::registry::subnet::v1::PayloadSection;
 use ic_types::{
     batch::{BatchPayload, IngressPayload, SelfValidatingPayload, XNetPayload},
     consensus::Payload,
@@ -46,6 +47,18 @@ pub(crate) enum BatchPayloadSectionBuilder {
 }
 
 impl BatchPayloadSectionBuilder {
+    /// Returns the [`PayloadSection`] built by this builder, which identifies
+    /// the section in the adaptive payload sizing configuration.
+    pub(crate) fn section(&self) -> PayloadSection {
+        match self {
+            Self::Ingress(_) => PayloadSection::Ingress,
+            Self::XNet(_) => PayloadSection::Xnet,
+            Self::SelfValidating(_) => PayloadSection::SelfValidating,
+            Self::CanisterHttp(_) => PayloadSection::CanisterHttp,
+            Self::QueryStats(_) => PayloadSection::QueryStats,
+        }
+    }
+
     /// Called to build the payload.
     ///
     /// # Arguments:
@@ -5,6 +5,7 @@ use crate::consensus::{
         PayloadBuilderMetrics, CRITICAL_ERROR_PAYLOAD_TOO_LARGE, CRITICAL_ERROR_SUBNET_RECORD_ISSUE,
     },
     payload::BatchPayloadSectionBuilder,
+    payload_sizing::{section_label, AdaptivePayloadSizing},
 };
 use ic_consensus_utils::get_subnet_record;
 use ic_interfaces::{
@@ -21,7 +22,10 @@ use ic_metrics::MetricsRegistry;
 use ic_protobuf::registry::subnet::v1::SubnetRecord;
 use ic_types::{
     batch::{BatchPayload, ValidationContext, MAX_BITCOIN_PAYLOAD_IN_BYTES},
-    consensus::{block_maker::SubnetRecords, Payload},
+    consensus::{
+        block_maker::{SubnetLoad, SubnetRecords},
+        Payload,
+    },
     messages::MAX_XNET_PAYLOAD_IN_BYTES,
     Height, NodeId, NumBytes, SubnetId, Time,
 };
@@ -102,6 +106,7 @@ impl PayloadBuilder for PayloadBuilderImpl {
         past_payloads: &[(Height, Time, Payload)],
         context: &ValidationContext,
         subnet_records: &SubnetRecords,
+        subnet_load: &SubnetLoad,
     ) -> BatchPayload {
         let _timer = self.metrics.get_payload_duration.start_timer();
         self.metrics
@@ -121,11 +126,41 @@ impl PayloadBuilder for PayloadBuilderImpl {
         let max_block_payload_size =
             self.get_max_block_payload_size_bytes(&subnet_records.context_version);
 
+        // Sections with configured bounds get a budget that shrinks when the
+        // subnet is overloaded.
+        let adaptive_budgets = subnet_records
+            .context_version
+            .adaptive_payload_sizing_config
+            .as_ref()
+            .map(|config| {
+                AdaptivePayloadSizing::new(
+                    config,
+                    max_block_payload_size,
+                    subnet_records.context_version.max_ingress_bytes_per_message,
+                )
+                .budgets(subnet_load)
+            })
+            .unwrap_or_default();
+        for (section, budget) in &adaptive_budgets {
+            self.metrics
+                .adaptive_payload_budget
+                .with_label_values(&[section_label(*section)])
+                .set(budget.get() as i64);
+        }
+
         let mut batch_payload = BatchPayload::default();
         let mut accumulated_size = 0;
 
         for section_id in section_select {
-            accumulated_size += self.section_builder[section_id]
+            let section_builder = &self.section_builder[section_id];
+            let mut max_size = max_block_payload_size
+                .get()
+                .saturating_sub(accumulated_size);
+            if let Some(budget) = adaptive_budgets.get(&section_builder.section()) {
+                max_size = max_size.min(budget.get());
+            }
+
+            accumulated_size += section_builder
                 .build_payload(
                     &mut batch_payload,
                     height,
@@ -133,11 +168,7 @@ impl PayloadBuilder for PayloadBuilderImpl {
                         proposer: self.node_id,
                         validation_context: context,
                     },
-                    NumBytes::new(
-                        max_block_payload_size
-                            .get()
-                            .saturating_sub(accumulated_size),
-                    ),
+                    NumBytes::new(max_size),
                     past_payloads,
                     &self.metrics,
                     &self.logger,
@@ -243,6 +274,9 @@ pub(crate) mod test {
     use ic_consensus_mocks::{dependencies, Dependencies};
     use ic_https_outcalls_consensus::test_utils::FakeCanisterHttpPayloadBuilder;
     use ic_logger::replica_logger::no_op_logger;
+    use ic_protobuf::registry::subnet::v1::{
+        AdaptivePayloadSizingConfig, PayloadSection, PayloadSectionBounds,
+    };
     use ic_test_utilities::{
         consensus::{batch::MockBatchPayloadBuilder, fake::Fake},
         ingress_selector::FakeIngressSelector,
@@ -364,7 +398,13 @@ pub(crate) mod test {
             };
 
             let batch_messages = payload_builder
-                .get_payload(Height::from(1), &prev_payloads, &context, &subnet_records)
+                .get_payload(
+                    Height::from(1),
+                    &prev_payloads,
+                    &context,
+                    &subnet_records,
+                    &SubnetLoad::default(),
+                )
                 .into_messages()
                 .unwrap();
 
@@ -417,4 +457,67 @@ pub(crate) mod test {
             }
         }
     }
+
+    // Test that the ingress section is limited to its adaptive budget when the
+    // batch delivery backlog exceeds its target, and to its maximum otherwise.
+    #[test]
+    fn test_get_payload_with_adaptive_payload_sizing() {
+        ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
+            let Dependencies { registry, .. } = dependencies(pool_config, 1);
+            let ingress_messages = (0..100)
+                .map(|i| SignedIngressBuilder::new().nonce(i).build())
+                .collect::<Vec<_>>();
+            let subnet_record = SubnetRecordBuilder::from(&[node_test_id(0)])
+                .with_max_ingress_bytes_per_message(1_000)
+                .with_adaptive_payload_sizing_config(AdaptivePayloadSizingConfig {
+                    target_finalization_latency_millis: 0,
+                    target_batch_delivery_backlog_blocks: 1,
+                    section_bounds: vec![PayloadSectionBounds {
+                        section: PayloadSection::Ingress.into(),
+                        min_bytes: 1_000,
+                        max_bytes: 4 * 1024 * 1024,
+                    }],
+                })
+                .build();
+            let subnet_records = SubnetRecords {
+                membership_version: subnet_record.clone(),
+                context_version: subnet_record,
+            };
+
+            let get_ingress_messages = |batch_delivery_backlog: u64| {
+                let payload_builder = make_test_payload_impl(
+                    registry.clone(),
+                    vec![ingress_messages.clone()],
+                    vec![],
+                    vec![],
+                    vec![],
+                );
+                let context = ValidationContext {
+                    certified_height: Height::from(0),
+                    registry_version: RegistryVersion::from(1),
+                    time: mock_time(),
+                };
+                let subnet_load = SubnetLoad {
+                    batch_delivery_backlog,
+                    ..SubnetLoad::default()
+                };
+                payload_builder
+                    .get_payload(
+                        Height::from(1),
+                        &[],
+                        &context,
+                        &subnet_records,
+                        &subnet_load,
+                    )
+                    .into_messages()
+                    .unwrap()
+                    .signed_ingress_msgs
+            };
+
+            // The backlog is within the target, so all messages fit.
+            assert_eq!(get_ingress_messages(1), ingress_messages);
+            // The budget shrinks to the minimum, which the messages exceed.
+            assert!(get_ingress_messages(1_000_000).is_empty());
+        })
+    }
 }
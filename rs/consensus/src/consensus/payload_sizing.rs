@@ -0,0 +1,243 @@
+//! Adaptive sizing of the block payload sections.
+//!
+//! The [`AdaptivePayloadSizing`] policy derives a byte budget for every payload
+//! section with configured bounds from two load signals that are observed by
+//! the block maker (see [`SubnetLoad`]):
+//!
+//! - The finalization latency, i.e. the time since the oldest notarized but not
+//!   yet finalized block on the chain of the proposed block was proposed.
+//! - The batch delivery backlog, i.e. the number of finalized blocks that have
+//!   not yet been delivered to message routing because execution cannot keep
+//!   up.
+//!
+//! As long as both signals are below their targets, the budget of a section
+//! equals its maximum. Above the targets, the budget is reduced proportionally
+//! to the larger of the two load ratios, but never below the minimum of the
+//! section. The budgets are computed from scratch for every block, so they grow
+//! back as soon as the subnet recovers.
+//!
+//! The IDKG payload is not covered, as its size is bounded by the number of
+//! ongoing signature requests (see `EcdsaConfig::max_queue_size`) rather than
+//! by a byte budget.
+use ic_protobuf::registry::subnet::v1::{AdaptivePayloadSizingConfig, PayloadSection};
+use ic_types::{consensus::block_maker::SubnetLoad, NumBytes};
+use std::{collections::BTreeMap, time::Duration};
+
+/// Returns the label of `section` used in metrics.
+pub(crate) fn section_label(section: PayloadSection) -> &'static str {
+    match section {
+        PayloadSection::Unspecified => "unspecified",
+        PayloadSection::Ingress => "ingress",
+        PayloadSection::Xnet => "xnet",
+        PayloadSection::SelfValidating => "self_validating",
+        PayloadSection::CanisterHttp => "canister_http",
+        PayloadSection::QueryStats => "query_stats",
+    }
+}
+
+pub(crate) struct AdaptivePayloadSizing {
+    target_finalization_latency: Option<Duration>,
+    target_batch_delivery_backlog: Option<u64>,
+    /// The minimum and maximum budget of each section with configured bounds.
+    section_bounds: BTreeMap<PayloadSection, (NumBytes, NumBytes)>,
+}
+
+impl AdaptivePayloadSizing {
+    /// Creates the policy from the registry configuration.
+    ///
+    /// The bounds are clamped such that no section exceeds
+    /// `max_block_payload_size` and that the ingress section always leaves
+    /// room for at least one message. Bounds of unknown sections are ignored
+    /// and if a section has multiple bounds, the last one applies.
+    pub(crate) fn new(
+        config: &AdaptivePayloadSizingConfig,
+        max_block_payload_size: NumBytes,
+        max_ingress_bytes_per_message: u64,
+    ) -> Self {
+        let section_bounds = config
+            .section_bounds
+            .iter()
+            .filter_map(|bounds| {
+                let section = PayloadSection::try_from(bounds.section)
+                    .ok()
+                    .filter(|section| *section != PayloadSection::Unspecified)?;
+                let max_bytes = bounds.max_bytes.min(max_block_payload_size.get());
+                let mut min_bytes = bounds.min_bytes;
+                if section == PayloadSection::Ingress {
+                    min_bytes = min_bytes.max(max_ingress_bytes_per_message);
+                }
+                let min_bytes = min_bytes.min(max_bytes);
+                Some((
+                    section,
+                    (NumBytes::new(min_bytes), NumBytes::new(max_bytes)),
+                ))
+            })
+            .collect();
+
+        Self {
+            target_finalization_latency: Some(config.target_finalization_latency_millis)
+                .filter(|millis| *millis > 0)
+                .map(Duration::from_millis),
+            target_batch_delivery_backlog: Some(config.target_batch_delivery_backlog_blocks)
+                .filter(|blocks| *blocks > 0),
+            section_bounds,
+        }
+    }
+
+    /// Returns the budget of every section with configured bounds, given the
+    /// observed `subnet_load`.
+    pub(crate) fn budgets(&self, subnet_load: &SubnetLoad) -> BTreeMap<PayloadSection, NumBytes> {
+        let load = self.load(subnet_load);
+        self.section_bounds
+            .iter()
+            .map(|(section, (min_size, max_size))| {
+                let budget = if load <= 1.0 {
+                    *max_size
+                } else {
+                    NumBytes::new((max_size.get() as f64 / load) as u64).max(*min_size)
+                };
+                (*section, budget)
+            })
+            .collect()
+    }
+
+    /// Returns the largest ratio of an observed load signal to its target.
+    /// Values above 1 mean that the subnet is overloaded.
+    fn load(&self, subnet_load: &SubnetLoad) -> f64 {
+        let mut load: f64 = 0.0;
+        if let Some(target) = self.target_finalization_latency {
+            load = load.max(subnet_load.finalization_latency.as_secs_f64() / target.as_secs_f64());
+        }
+        if let Some(target) = self.target_batch_delivery_backlog {
+            load = load.max(subnet_load.batch_delivery_backlog as f64 / target as f64);
+        }
+        load
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_protobuf::registry::subnet::v1::PayloadSectionBounds;
+
+    const MAX_BLOCK_PAYLOAD_SIZE: NumBytes = NumBytes::new(4_000_000);
+
+    fn bounds(section: PayloadSection, min_bytes: u64, max_bytes: u64) -> PayloadSectionBounds {
+        PayloadSectionBounds {
+            section: section.into(),
+            min_bytes,
+            max_bytes,
+        }
+    }
+
+    fn sizing(
+        target_finalization_latency_millis: u64,
+        target_batch_delivery_backlog_blocks: u64,
+        section_bounds: Vec<PayloadSectionBounds>,
+    ) -> AdaptivePayloadSizing {
+        AdaptivePayloadSizing::new(
+            &AdaptivePayloadSizingConfig {
+                target_finalization_latency_millis,
+                target_batch_delivery_backlog_blocks,
+                section_bounds,
+            },
+            MAX_BLOCK_PAYLOAD_SIZE,
+            1_000,
+        )
+    }
+
+    fn subnet_load(finalization_latency_millis: u64, batch_delivery_backlog: u64) -> SubnetLoad {
+        SubnetLoad {
+            finalization_latency: Duration::from_millis(finalization_latency_millis),
+            batch_delivery_backlog,
+        }
+    }
+
+    #[test]
+    fn maximum_budgets_below_targets() {
+        let sizing = sizing(
+            3_000,
+            10,
+            vec![
+                bounds(PayloadSection::Ingress, 1_000_000, 4_000_000),
+                bounds(PayloadSection::CanisterHttp, 100_000, 2_000_000),
+            ],
+        );
+        assert_eq!(
+            sizing.budgets(&subnet_load(2_000, 5)),
+            BTreeMap::from([
+                (PayloadSection::Ingress, NumBytes::new(4_000_000)),
+                (PayloadSection::CanisterHttp, NumBytes::new(2_000_000)),
+            ])
+        );
+    }
+
+    #[test]
+    fn budgets_shrink_with_batch_delivery_backlog() {
+        let sizing = sizing(
+            0,
+            10,
+            vec![
+                bounds(PayloadSection::Xnet, 1_000_000, 4_000_000),
+                bounds(PayloadSection::QueryStats, 10_000, 400_000),
+            ],
+        );
+        assert_eq!(
+            sizing.budgets(&subnet_load(60_000, 20)),
+            BTreeMap::from([
+                (PayloadSection::Xnet, NumBytes::new(2_000_000)),
+                (PayloadSection::QueryStats, NumBytes::new(200_000)),
+            ])
+        );
+        // The budgets do not drop below the minimum.
+        assert_eq!(
+            sizing.budgets(&subnet_load(0, 1_000)),
+            BTreeMap::from([
+                (PayloadSection::Xnet, NumBytes::new(1_000_000)),
+                (PayloadSection::QueryStats, NumBytes::new(10_000)),
+            ])
+        );
+    }
+
+    #[test]
+    fn budgets_shrink_with_finalization_latency() {
+        let sizing = sizing(
+            2_000,
+            0,
+            vec![bounds(PayloadSection::SelfValidating, 0, 2_000_000)],
+        );
+        assert_eq!(
+            sizing.budgets(&subnet_load(8_000, 1_000)),
+            BTreeMap::from([(PayloadSection::SelfValidating, NumBytes::new(500_000))])
+        );
+    }
+
+    #[test]
+    fn bounds_are_clamped() {
+        let sizing = sizing(
+            0,
+            1,
+            vec![
+                bounds(PayloadSection::Unspecified, 0, 1_000),
+                bounds(PayloadSection::Ingress, 0, 8_000_000),
+                bounds(PayloadSection::Xnet, 3_000_000, 2_000_000),
+            ],
+        );
+        // The ingress section leaves room for a message and no section
+        // exceeds the maximum block payload size.
+        assert_eq!(
+            sizing.budgets(&subnet_load(0, 0)),
+            BTreeMap::from([
+                (PayloadSection::Ingress, MAX_BLOCK_PAYLOAD_SIZE),
+                (PayloadSection::Xnet, NumBytes::new(2_000_000)),
+            ])
+        );
+        assert_eq!(
+            sizing.budgets(&subnet_load(0, 1_000_000)),
+            BTreeMap::from([
+                (PayloadSection::Ingress, NumBytes::new(1_000)),
+                (PayloadSection::Xnet, NumBytes::new(2_000_000)),
+            ])
+        );
+    }
+}
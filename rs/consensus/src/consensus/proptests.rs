@@ -13,7 +13,7 @@ use ic_test_utilities_time::mock_time;
 use ic_types::{
     batch::{BatchPayload, ValidationContext},
     consensus::{
-        block_maker::SubnetRecords,
+        block_maker::{SubnetLoad, SubnetRecords},
         certification::{Certification, CertificationContent},
         dkg::Dealings,
         BlockPayload, DataPayload, Payload,
@@ -83,8 +83,13 @@ fn proptest_round(
             make_test_payload_impl(registry, vec![ingress], vec![xnet], vec![], vec![]);
 
         // Build the payload and validate it
-        let payload =
-            payload_builder.get_payload(Height::from(0), &[], &validation_context, &subnet_records);
+        let payload = payload_builder.get_payload(
+            Height::from(0),
+            &[],
+            &validation_context,
+            &subnet_records,
+            &SubnetLoad::default(),
+        );
 
         let wrapped_payload = wrap_batch_payload(0, payload);
         payload_builder
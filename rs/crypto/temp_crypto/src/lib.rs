@@ -1025,6 +1025,8 @@ impl EcdsaSubnetConfig {
                 subnet_type: SubnetType::Application.into(),
                 is_halted: false,
                 halt_at_cup_height: false,
+                ingress_admission_config: None,
+                adaptive_payload_sizing_config: None,
                 max_instructions_per_message: 5_000_000_000,
                 max_instructions_per_round: 7_000_000_000,
                 max_instructions_per_install_code: 200_000_000_000,
@@ -1109,6 +1109,18 @@ impl CyclesAccountManager {
             * (subnet_size as u64)
     }
 
+    /// Returns the fee of an http request made by a single node.
+    ///
+    /// The request is charged as if it was made on a subnet of a single node,
+    /// since no other node makes it or agrees on its response.
+    pub fn non_replicated_http_request_fee(
+        &self,
+        request_size: NumBytes,
+        response_size_limit: Option<NumBytes>,
+    ) -> Cycles {
+        self.http_request_fee(request_size, response_size_limit, 1)
+    }
+
     /// Returns the default value of the reserved balance limit for the case
     /// when the canister doesn't have it set in the settings.
     pub fn default_reserved_balance_limit(&self) -> Cycles {
@@ -1239,6 +1251,12 @@ mod tests {
             cycles_account_manager.http_request_fee(request_size, None, subnet_size as usize),
             Cycles::from(1_605_046_800u64) * subnet_size
         );
+
+        // Non-replicated requests are charged for a single node, whatever the subnet size.
+        assert_eq!(
+            cycles_account_manager.non_replicated_http_request_fee(request_size, None),
+            Cycles::from(1_603_066_800u64)
+        );
     }
 
     #[test]
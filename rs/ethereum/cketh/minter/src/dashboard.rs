@@ -7,6 +7,7 @@ use ic_cketh_minter::endpoints::{EthTransaction, RetrieveEthStatus};
 use ic_cketh_minter::eth_logs::{EventSource, ReceivedEthEvent};
 use ic_cketh_minter::eth_rpc::Hash;
 use ic_cketh_minter::eth_rpc_client::responses::TransactionStatus;
+use ic_cketh_minter::eth_rpc_client::{provider_stats, EthRpcClient, ProviderStats};
 use ic_cketh_minter::lifecycle::EthereumNetwork;
 use ic_cketh_minter::numeric::{BlockNumber, LedgerBurnIndex, TransactionNonce, Wei};
 use ic_cketh_minter::state::transactions::{EthWithdrawalRequest, Reimbursed};
@@ -47,6 +48,11 @@ pub struct DashboardFinalizedTransaction {
     pub status: TransactionStatus,
 }
 
+pub struct DashboardRpcProvider {
+    pub url: String,
+    pub stats: ProviderStats,
+}
+
 #[derive(Template)]
 #[template(path = "dashboard.html")]
 pub struct DashboardTemplate {
@@ -69,6 +75,7 @@ pub struct DashboardTemplate {
     pub reimbursed_transactions: Vec<Reimbursed>,
     pub eth_balance: EthBalance,
     pub skipped_blocks: BTreeSet<BlockNumber>,
+    pub rpc_providers: Vec<DashboardRpcProvider>,
 }
 
 impl DashboardTemplate {
@@ -129,6 +136,15 @@ impl DashboardTemplate {
         reimbursed_transactions
             .sort_unstable_by_key(|reimbursed_tx| std::cmp::Reverse(reimbursed_tx.withdrawal_id));
 
+        let mut stats = provider_stats();
+        let rpc_providers = EthRpcClient::from_state(state)
+            .provider_urls()
+            .map(|url| DashboardRpcProvider {
+                url: url.to_string(),
+                stats: stats.remove(url).unwrap_or_default(),
+            })
+            .collect();
+
         DashboardTemplate {
             ethereum_network: state.ethereum_network,
             ecdsa_key_name: state.ecdsa_key_name.clone(),
@@ -154,6 +170,7 @@ impl DashboardTemplate {
             reimbursed_transactions,
             eth_balance: state.eth_balance.clone(),
             skipped_blocks: state.skipped_blocks.clone(),
+            rpc_providers,
         }
     }
 }
@@ -80,6 +80,51 @@ fn should_display_block_sync() {
     );
 }
 
+#[test]
+fn should_display_rpc_providers() {
+    DashboardAssert::assert_that(initial_dashboard())
+        .has_rpc_providers(
+            1,
+            &vec!["https://rpc.ankr.com/eth_sepolia", "0", "0", "0", "N/A"],
+        )
+        .has_rpc_providers(
+            2,
+            &vec![
+                "https://ethereum-sepolia.publicnode.com",
+                "0",
+                "0",
+                "0",
+                "N/A",
+            ],
+        );
+
+    let dashboard = {
+        use ic_cketh_minter::eth_rpc_client::config::{EthRpcConfig, RpcApi};
+        use ic_cketh_minter::lifecycle::upgrade::UpgradeArg;
+
+        let mut state = initial_state();
+        apply_state_transition(
+            &mut state,
+            &EventType::Upgrade(UpgradeArg {
+                eth_rpc_config: Some(EthRpcConfig {
+                    providers: Some(vec![RpcApi {
+                        url: "https://eth-sepolia.example.com/v2".to_string(),
+                        headers: None,
+                        max_response_bytes: None,
+                    }]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+        );
+        DashboardTemplate::from_state(&state)
+    };
+    DashboardAssert::assert_that(dashboard).has_rpc_providers(
+        1,
+        &vec!["https://eth-sepolia.example.com/v2", "0", "0", "0", "N/A"],
+    );
+}
+
 #[test]
 fn should_display_events_to_mint_sorted_by_decreasing_block_number() {
     DashboardAssert::assert_that(initial_dashboard()).has_no_elements_matching("#events-to-mint");
@@ -618,6 +663,8 @@ fn initial_state() -> State {
         minimum_withdrawal_amount: Wei::TWO.into(),
         next_transaction_nonce: TransactionNonce::ZERO.into(),
         last_scraped_block_number: candid::Nat::from(3_956_206_u32),
+        eth_rpc_config: None,
+        ethereum_block_confirmations: None,
     })
     .expect("valid init args")
 }
@@ -705,6 +752,7 @@ fn withdrawal_flow(
         gas_used: signed_tx.transaction().gas_limit,
         status: tx_status,
         transaction_hash: signed_tx.hash(),
+        l1_fee: None,
     };
     (withdrawal_request, transaction, signed_tx, tx_receipt)
 }
@@ -866,6 +914,14 @@ mod assertions {
             )
         }
 
+        pub fn has_rpc_providers(&self, row_index: u8, expected_value: &Vec<&str>) -> &Self {
+            self.has_table_row_string_value(
+                &format!("#rpc-providers + table > tbody > tr:nth-child({row_index})"),
+                expected_value,
+                "rpc-providers",
+            )
+        }
+
         pub fn has_events_to_mint(&self, row_index: u8, expected_value: &Vec<&str>) -> &Self {
             self.has_table_row_string_value(
                 &format!("#events-to-mint + table > tbody > tr:nth-child({row_index})"),
@@ -1,5 +1,8 @@
-use crate::eth_logs::{report_transaction_error, ReceivedEthEventError};
-use crate::eth_rpc::{BlockSpec, HttpOutcallError};
+use crate::eth_logs::{
+    last_received_events, report_transaction_error, ReceivedErc20Event, ReceivedEthEvent,
+    ReceivedEthEventError, ReceivedEvent, RECEIVED_ERC20_EVENT_TOPIC, RECEIVED_ETH_EVENT_TOPIC,
+};
+use crate::eth_rpc::{BlockSpec, HttpOutcallError, LogEntry};
 use crate::eth_rpc_client::EthRpcClient;
 use crate::guard::TimerGuard;
 use crate::logs::{DEBUG, INFO};
@@ -13,38 +16,54 @@ use num_traits::ToPrimitive;
 use std::cmp::{min, Ordering};
 use std::time::Duration;
 
-async fn mint_cketh() {
+async fn mint() {
     use icrc_ledger_client_cdk::{CdkRuntime, ICRC1Client};
     use icrc_ledger_types::icrc1::transfer::TransferArg;
 
-    let _guard = match TimerGuard::new(TaskType::MintCkEth) {
+    let _guard = match TimerGuard::new(TaskType::Mint) {
         Ok(guard) => guard,
         Err(_) => return,
     };
 
-    let (ledger_canister_id, events) = read_state(|s| (s.ledger_id, s.events_to_mint.clone()));
-    let client = ICRC1Client {
-        runtime: CdkRuntime,
-        ledger_canister_id,
-    };
+    let (cketh_ledger_canister_id, events) = read_state(|s| (s.ledger_id, s.all_events_to_mint()));
 
     let mut error_count = 0;
 
-    for (event_source, event) in events {
+    for event in events {
+        let (token_symbol, ledger_canister_id) = match &event {
+            ReceivedEvent::Eth(_) => ("ckETH".to_string(), cketh_ledger_canister_id),
+            ReceivedEvent::Erc20(erc20_event) => {
+                let ckerc20_token = read_state(|s| {
+                    s.find_ck_erc20_token_by_contract_address(&erc20_event.erc20_contract_address)
+                        .cloned()
+                })
+                .unwrap_or_else(|| {
+                    panic!("BUG: accepted ERC-20 deposit {erc20_event:?} for an unsupported token")
+                });
+                (
+                    ckerc20_token.ckerc20_token_symbol,
+                    ckerc20_token.ckerc20_ledger_id,
+                )
+            }
+        };
+        let client = ICRC1Client {
+            runtime: CdkRuntime,
+            ledger_canister_id,
+        };
         let block_index = match client
             .transfer(TransferArg {
                 from_subaccount: None,
-                to: event.principal.into(),
+                to: event.principal().into(),
                 fee: None,
                 created_at_time: None,
                 memo: Some(event.clone().into()),
-                amount: candid::Nat::from(event.value),
+                amount: event.value(),
             })
             .await
         {
             Ok(Ok(block_index)) => block_index.0.to_u64().expect("nat does not fit into u64"),
             Ok(Err(err)) => {
-                log!(INFO, "Failed to mint ckETH: {event:?} {err}");
+                log!(INFO, "Failed to mint {token_symbol}: {event:?} {err}");
                 error_count += 1;
                 continue;
             }
@@ -57,20 +76,30 @@ async fn mint_cketh() {
                 continue;
             }
         };
+        let event_source = event.source();
+        let mint_block_index = LedgerMintIndex::new(block_index);
         mutate_state(|s| {
             process_event(
                 s,
-                EventType::MintedCkEth {
-                    event_source,
-                    mint_block_index: LedgerMintIndex::new(block_index),
+                match &event {
+                    ReceivedEvent::Eth(_) => EventType::MintedCkEth {
+                        event_source,
+                        mint_block_index,
+                    },
+                    ReceivedEvent::Erc20(erc20_event) => EventType::MintedCkErc20 {
+                        event_source,
+                        mint_block_index,
+                        ckerc20_token_symbol: token_symbol.clone(),
+                        erc20_contract_address: erc20_event.erc20_contract_address,
+                    },
                 },
             )
         });
         log!(
             INFO,
-            "Minted {} ckWei to {} in block {block_index}",
-            event.value,
-            event.principal
+            "Minted {} {token_symbol} to {} in block {block_index}",
+            event.value(),
+            event.principal()
         );
     }
 
@@ -79,7 +108,70 @@ async fn mint_cketh() {
             INFO,
             "Failed to mint {error_count} events, rescheduling the minting"
         );
-        ic_cdk_timers::set_timer(crate::MINT_RETRY_DELAY, || ic_cdk::spawn(mint_cketh()));
+        ic_cdk_timers::set_timer(crate::MINT_RETRY_DELAY, || ic_cdk::spawn(mint()));
+    }
+}
+
+/// Abstraction over the logs of the helper smart contracts scraped by the minter.
+trait LogScraping {
+    /// Deposit event emitted by the helper smart contract.
+    type Event: TryFrom<LogEntry, Error = ReceivedEthEventError> + Into<ReceivedEvent>;
+    /// Keccak256 hash of the event signature.
+    const TOPIC: [u8; 32];
+    /// Human-readable name of the scraped logs, used in logs.
+    const NAME: &'static str;
+
+    fn contract_address(state: &State) -> Option<Address>;
+    fn last_scraped_block_number(state: &State) -> BlockNumber;
+    fn set_last_scraped_block_number(state: &mut State, block_number: BlockNumber);
+    fn skipped_block_event(block_number: BlockNumber) -> EventType;
+}
+
+enum ReceivedEthLogScraping {}
+
+impl LogScraping for ReceivedEthLogScraping {
+    type Event = ReceivedEthEvent;
+    const TOPIC: [u8; 32] = RECEIVED_ETH_EVENT_TOPIC;
+    const NAME: &'static str = "ETH";
+
+    fn contract_address(state: &State) -> Option<Address> {
+        state.ethereum_contract_address
+    }
+
+    fn last_scraped_block_number(state: &State) -> BlockNumber {
+        state.last_scraped_block_number
+    }
+
+    fn set_last_scraped_block_number(state: &mut State, block_number: BlockNumber) {
+        state.last_scraped_block_number = block_number;
+    }
+
+    fn skipped_block_event(block_number: BlockNumber) -> EventType {
+        EventType::SkippedBlock(block_number)
+    }
+}
+
+enum ReceivedErc20LogScraping {}
+
+impl LogScraping for ReceivedErc20LogScraping {
+    type Event = ReceivedErc20Event;
+    const TOPIC: [u8; 32] = RECEIVED_ERC20_EVENT_TOPIC;
+    const NAME: &'static str = "ERC-20";
+
+    fn contract_address(state: &State) -> Option<Address> {
+        state.erc20_helper_contract_address
+    }
+
+    fn last_scraped_block_number(state: &State) -> BlockNumber {
+        state.last_erc20_scraped_block_number
+    }
+
+    fn set_last_scraped_block_number(state: &mut State, block_number: BlockNumber) {
+        state.last_erc20_scraped_block_number = block_number;
+    }
+
+    fn skipped_block_event(block_number: BlockNumber) -> EventType {
+        EventType::SkippedErc20Block(block_number)
     }
 }
 
@@ -87,7 +179,7 @@ async fn mint_cketh() {
 /// require that the number of blocks queried is no greater than MAX_BLOCK_SPREAD.
 /// Returns the last block number that was scraped (which is `min(from + MAX_BLOCK_SPREAD, to)`) if there
 /// was no error when querying the providers, otherwise returns `None`.
-async fn scrap_eth_logs_range_inclusive(
+async fn scrap_logs_range_inclusive<S: LogScraping>(
     contract_address: Address,
     from: BlockNumber,
     to: BlockNumber,
@@ -103,14 +195,16 @@ async fn scrap_eth_logs_range_inclusive(
             let mut last_block_number = min(max_to, to);
             log!(
                 DEBUG,
-                "Scrapping ETH logs from block {:?} to block {:?}...",
+                "Scrapping {} logs from block {:?} to block {:?}...",
+                S::NAME,
                 from,
                 last_block_number
             );
 
             let (transaction_events, errors) = loop {
-                match crate::eth_logs::last_received_eth_events(
+                match last_received_events::<S::Event>(
                     contract_address,
+                    S::TOPIC,
                     from,
                     last_block_number,
                 )
@@ -120,15 +214,16 @@ async fn scrap_eth_logs_range_inclusive(
                     Err(e) => {
                         log!(
                         INFO,
-                        "Failed to get ETH logs from block {from} to block {last_block_number}: {e:?}",
+                        "Failed to get {} logs from block {from} to block {last_block_number}: {e:?}",
+                        S::NAME,
                     );
                         if e.has_http_outcall_error_matching(
                             HttpOutcallError::is_response_too_large,
                         ) {
                             if from == last_block_number {
                                 mutate_state(|s| {
-                                    process_event(s, EventType::SkippedBlock(last_block_number));
-                                    s.last_scraped_block_number = last_block_number;
+                                    process_event(s, S::skipped_block_event(last_block_number));
+                                    S::set_last_scraped_block_number(s, last_block_number);
                                 });
                                 return Some(last_block_number);
                             } else {
@@ -149,37 +244,10 @@ async fn scrap_eth_logs_range_inclusive(
             };
 
             for event in transaction_events {
-                log!(
-                    INFO,
-                    "Received event {event:?}; will mint {} wei to {}",
-                    event.value,
-                    event.principal
-                );
-                if crate::blocklist::is_blocked(event.from_address) {
-                    log!(
-                        INFO,
-                        "Received event from a blocked address: {} for {} WEI",
-                        event.from_address,
-                        event.value,
-                    );
-                    mutate_state(|s| {
-                        process_event(
-                            s,
-                            EventType::InvalidDeposit {
-                                event_source: crate::eth_logs::EventSource {
-                                    transaction_hash: event.transaction_hash,
-                                    log_index: event.log_index,
-                                },
-                                reason: format!("blocked address {}", event.from_address),
-                            },
-                        )
-                    });
-                } else {
-                    mutate_state(|s| process_event(s, EventType::AcceptedDeposit(event)));
-                }
+                register_deposit_event(event.into());
             }
             if read_state(State::has_events_to_mint) {
-                ic_cdk_timers::set_timer(Duration::from_secs(0), || ic_cdk::spawn(mint_cketh()));
+                ic_cdk_timers::set_timer(Duration::from_secs(0), || ic_cdk::spawn(mint()));
             }
             for error in errors {
                 if let ReceivedEthEventError::InvalidEventSource { source, error } = &error {
@@ -195,7 +263,7 @@ async fn scrap_eth_logs_range_inclusive(
                 }
                 report_transaction_error(error);
             }
-            mutate_state(|s| s.last_scraped_block_number = last_block_number);
+            mutate_state(|s| S::set_last_scraped_block_number(s, last_block_number));
             Some(last_block_number)
         }
         Ordering::Greater => {
@@ -207,38 +275,90 @@ async fn scrap_eth_logs_range_inclusive(
     }
 }
 
+fn register_deposit_event(event: ReceivedEvent) {
+    log!(
+        INFO,
+        "Received event {event:?}; will mint {} to {}",
+        event.value(),
+        event.principal()
+    );
+    let invalid_reason = if crate::blocklist::is_blocked(event.from_address()) {
+        log!(
+            INFO,
+            "Received event from a blocked address: {} for {}",
+            event.from_address(),
+            event.value(),
+        );
+        Some(format!("blocked address {}", event.from_address()))
+    } else {
+        match &event {
+            ReceivedEvent::Erc20(erc20_event)
+                if read_state(|s| {
+                    s.find_ck_erc20_token_by_contract_address(&erc20_event.erc20_contract_address)
+                        .is_none()
+                }) =>
+            {
+                Some(format!(
+                    "unsupported ERC-20 contract address {}",
+                    erc20_event.erc20_contract_address
+                ))
+            }
+            _ => None,
+        }
+    };
+    mutate_state(|s| match invalid_reason {
+        Some(reason) => process_event(
+            s,
+            EventType::InvalidDeposit {
+                event_source: event.source(),
+                reason,
+            },
+        ),
+        None => match event {
+            ReceivedEvent::Eth(event) => process_event(s, EventType::AcceptedDeposit(event)),
+            ReceivedEvent::Erc20(event) => process_event(s, EventType::AcceptedErc20Deposit(event)),
+        },
+    });
+}
+
 pub async fn scrap_eth_logs() {
     let _guard = match TimerGuard::new(TaskType::ScrapEthLogs) {
         Ok(guard) => guard,
         Err(_) => return,
     };
-    let contract_address = match read_state(|s| s.ethereum_contract_address) {
-        Some(address) => address,
+    let last_block_number = match update_last_observed_block_number().await {
+        Some(block_number) => block_number,
         None => {
             log!(
                 DEBUG,
-                "[scrap_eth_logs]: skipping scrapping ETH logs: no contract address"
+                "[scrap_eth_logs]: skipping scrapping logs: no last observed block number"
             );
             return;
         }
     };
-    let last_block_number = match update_last_observed_block_number().await {
-        Some(block_number) => block_number,
+    scrap_logs::<ReceivedEthLogScraping>(last_block_number).await;
+    scrap_logs::<ReceivedErc20LogScraping>(last_block_number).await;
+}
+
+async fn scrap_logs<S: LogScraping>(last_block_number: BlockNumber) {
+    let contract_address = match read_state(S::contract_address) {
+        Some(address) => address,
         None => {
             log!(
                 DEBUG,
-                "[scrap_eth_logs]: skipping scrapping ETH logs: no last observed block number"
+                "[scrap_logs]: skipping scrapping {} logs: no contract address",
+                S::NAME
             );
             return;
         }
     };
-    let mut last_scraped_block_number = read_state(|s| s.last_scraped_block_number);
+    let mut last_scraped_block_number = read_state(S::last_scraped_block_number);
 
     while last_scraped_block_number < last_block_number {
         let next_block_to_query = last_scraped_block_number
             .checked_increment()
             .unwrap_or(BlockNumber::MAX);
-        last_scraped_block_number = match scrap_eth_logs_range_inclusive(
+        last_scraped_block_number = match scrap_logs_range_inclusive::<S>(
             contract_address,
             next_block_to_query,
             last_block_number,
@@ -255,12 +375,18 @@ pub async fn scrap_eth_logs() {
 
 pub async fn update_last_observed_block_number() -> Option<BlockNumber> {
     let block_height = read_state(State::ethereum_block_height);
+    let block_confirmations = read_state(State::ethereum_block_confirmations);
     match read_state(EthRpcClient::from_state)
         .eth_get_block_by_number(BlockSpec::Tag(block_height))
         .await
     {
         Ok(latest_block) => {
-            let block_number = Some(latest_block.number);
+            let block_number = Some(
+                latest_block
+                    .number
+                    .checked_sub(block_confirmations)
+                    .unwrap_or(BlockNumber::ZERO),
+            );
             mutate_state(|s| s.last_observed_block_number = block_number);
             block_number
         }
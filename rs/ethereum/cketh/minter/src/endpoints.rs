@@ -1,6 +1,6 @@
-use crate::state::transactions::EthWithdrawalRequest;
+use crate::state::transactions::{Erc20WithdrawalRequest, EthWithdrawalRequest};
 use crate::tx::{SignedEip1559TransactionRequest, TransactionPrice};
-use candid::{CandidType, Deserialize, Nat};
+use candid::{CandidType, Deserialize, Nat, Principal};
 use icrc_ledger_types::icrc2::transfer_from::TransferFromError;
 use minicbor::{Decode, Encode};
 use std::fmt::{Display, Formatter};
@@ -165,6 +165,145 @@ impl From<TransferFromError> for WithdrawalError {
     }
 }
 
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AddCkErc20Token {
+    pub chain_id: Nat,
+    pub address: String,
+    pub ckerc20_token_symbol: String,
+    pub ckerc20_ledger_id: Principal,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct CkErc20Token {
+    pub ckerc20_token_symbol: String,
+    pub erc20_contract_address: String,
+    pub ledger_canister_id: Principal,
+}
+
+impl From<crate::erc20::CkErc20Token> for CkErc20Token {
+    fn from(value: crate::erc20::CkErc20Token) -> Self {
+        Self {
+            ckerc20_token_symbol: value.ckerc20_token_symbol,
+            erc20_contract_address: value.erc20_contract_address.to_string(),
+            ledger_canister_id: value.ckerc20_ledger_id,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct WithdrawErc20Arg {
+    pub amount: Nat,
+    pub ckerc20_ledger_id: Principal,
+    pub recipient: String,
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct RetrieveErc20Request {
+    pub cketh_block_index: Nat,
+    pub ckerc20_block_index: Nat,
+}
+
+impl From<Erc20WithdrawalRequest> for RetrieveErc20Request {
+    fn from(value: Erc20WithdrawalRequest) -> Self {
+        Self {
+            cketh_block_index: candid::Nat::from(value.cketh_ledger_burn_index.get()),
+            ckerc20_block_index: candid::Nat::from(value.ckerc20_ledger_burn_index.get()),
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq)]
+pub enum WithdrawErc20Error {
+    TokenNotSupported {
+        supported_tokens: Vec<CkErc20Token>,
+    },
+    RecipientAddressBlocked {
+        address: String,
+    },
+    CkEthLedgerError {
+        error: LedgerError,
+    },
+    CkErc20LedgerError {
+        cketh_block_index: Nat,
+        error: LedgerError,
+    },
+    TemporarilyUnavailable(String),
+}
+
+#[derive(CandidType, Deserialize, Debug, PartialEq)]
+pub enum LedgerError {
+    InsufficientFunds {
+        balance: Nat,
+        failed_burn_amount: Nat,
+        token_symbol: String,
+        ledger_id: Principal,
+    },
+    AmountTooLow {
+        minimum_burn_amount: Nat,
+        failed_burn_amount: Nat,
+        token_symbol: String,
+        ledger_id: Principal,
+    },
+    InsufficientAllowance {
+        allowance: Nat,
+        failed_burn_amount: Nat,
+        token_symbol: String,
+        ledger_id: Principal,
+    },
+    TemporarilyUnavailable(String),
+}
+
+impl LedgerError {
+    /// Converts an error returned by `icrc2_transfer_from` when the minter
+    /// burns `failed_burn_amount` tokens on the ledger `ledger_id`.
+    pub fn from_transfer_from_error(
+        transfer_from_error: TransferFromError,
+        ledger_id: Principal,
+        token_symbol: String,
+        failed_burn_amount: Nat,
+    ) -> Self {
+        match transfer_from_error {
+            TransferFromError::BadFee { expected_fee } => {
+                panic!("bug: bad fee, expected fee: {expected_fee}")
+            }
+            TransferFromError::BadBurn { min_burn_amount } => Self::AmountTooLow {
+                minimum_burn_amount: min_burn_amount,
+                failed_burn_amount,
+                token_symbol,
+                ledger_id,
+            },
+            TransferFromError::InsufficientFunds { balance } => Self::InsufficientFunds {
+                balance,
+                failed_burn_amount,
+                token_symbol,
+                ledger_id,
+            },
+            TransferFromError::InsufficientAllowance { allowance } => Self::InsufficientAllowance {
+                allowance,
+                failed_burn_amount,
+                token_symbol,
+                ledger_id,
+            },
+            TransferFromError::TooOld => panic!("bug: transfer too old"),
+            TransferFromError::CreatedInFuture { ledger_time } => {
+                panic!("bug: created in future, ledger time: {ledger_time}")
+            }
+            TransferFromError::Duplicate { duplicate_of } => {
+                panic!("bug: duplicate transfer of: {duplicate_of}")
+            }
+            TransferFromError::TemporarilyUnavailable => Self::TemporarilyUnavailable(format!(
+                "{token_symbol} ledger temporarily unavailable, try again"
+            )),
+            TransferFromError::GenericError {
+                error_code,
+                message,
+            } => Self::TemporarilyUnavailable(format!(
+                "{token_symbol} ledger unreachable, error code: {error_code}, with message: {message}"
+            )),
+        }
+    }
+}
+
 pub mod events {
     use crate::lifecycle::init::InitArg;
     use crate::lifecycle::upgrade::UpgradeArg;
@@ -228,6 +367,7 @@ pub mod events {
         pub gas_used: Nat,
         pub status: TransactionStatus,
         pub transaction_hash: String,
+        pub l1_fee: Option<Nat>,
     }
 
     #[derive(CandidType, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -286,5 +426,58 @@ pub mod events {
         SkippedBlock {
             block_number: Nat,
         },
+        AddedCkErc20Token {
+            chain_id: Nat,
+            address: String,
+            ckerc20_token_symbol: String,
+            ckerc20_ledger_id: Principal,
+        },
+        AcceptedErc20Deposit {
+            transaction_hash: String,
+            block_number: Nat,
+            log_index: Nat,
+            from_address: String,
+            value: Nat,
+            principal: Principal,
+            erc20_contract_address: String,
+        },
+        MintedCkErc20 {
+            event_source: EventSource,
+            mint_block_index: Nat,
+            ckerc20_token_symbol: String,
+            erc20_contract_address: String,
+        },
+        SyncedErc20ToBlock {
+            block_number: Nat,
+        },
+        SkippedErc20Block {
+            block_number: Nat,
+        },
+        AcceptedErc20WithdrawalRequest {
+            max_transaction_fee: Nat,
+            withdrawal_amount: Nat,
+            erc20_contract_address: String,
+            destination: String,
+            cketh_ledger_burn_index: Nat,
+            ckerc20_ledger_id: Principal,
+            ckerc20_ledger_burn_index: Nat,
+            from: Principal,
+            from_subaccount: Option<[u8; 32]>,
+            created_at: u64,
+        },
+        FailedErc20WithdrawalRequest {
+            withdrawal_id: Nat,
+            reimbursed_amount: Nat,
+            to: Principal,
+            to_subaccount: Option<[u8; 32]>,
+        },
+        ReimbursedErc20Withdrawal {
+            withdrawal_id: Nat,
+            burn_in_block: Nat,
+            reimbursed_in_block: Nat,
+            ledger_id: Principal,
+            reimbursed_amount: Nat,
+            transaction_hash: Option<String>,
+        },
     }
 }
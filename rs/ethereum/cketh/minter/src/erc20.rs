@@ -0,0 +1,97 @@
+//! Support for ERC-20 tokens (ckERC20).
+
+#[cfg(test)]
+mod tests;
+
+use crate::endpoints::AddCkErc20Token;
+use crate::lifecycle::EthereumNetwork;
+use crate::numeric::{Erc20Value, GasAmount};
+use candid::Principal;
+use hex_literal::hex;
+use ic_ethereum_types::Address;
+use minicbor::{Decode, Encode};
+use num_traits::ToPrimitive;
+use std::fmt;
+use std::str::FromStr;
+
+/// Function selector of `transfer(address,uint256)`,
+/// i.e., the first 4 bytes of the Keccak-256 hash of the function signature.
+const ERC_20_TRANSFER_FUNCTION_SELECTOR: [u8; 4] = hex!("a9059cbb");
+
+/// Gas limit used for ckERC20 withdrawals.
+/// Calling `transfer` on an ERC-20 contract costs more than a plain ETH transfer (21_000 gas)
+/// and the exact amount depends on the token contract. Typical values for well-known tokens
+/// like USDC or USDT are below 65_000 gas.
+pub const CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT: GasAmount = GasAmount::new(65_000);
+
+/// A ckERC20 token supported by the minter.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct CkErc20Token {
+    /// The Ethereum network on which the ERC-20 contract is deployed.
+    #[n(0)]
+    pub erc20_ethereum_network: EthereumNetwork,
+    /// The address of the ERC-20 contract.
+    #[n(1)]
+    pub erc20_contract_address: Address,
+    /// The symbol of the corresponding ckERC20 token, e.g., `ckUSDC`.
+    #[n(2)]
+    pub ckerc20_token_symbol: String,
+    /// The ICRC-1 ledger handling the ckERC20 token.
+    #[cbor(n(3), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+}
+
+impl fmt::Debug for CkErc20Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CkErc20Token")
+            .field("erc20_ethereum_network", &self.erc20_ethereum_network)
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .field("ckerc20_token_symbol", &self.ckerc20_token_symbol)
+            .field(
+                "ckerc20_ledger_id",
+                &format_args!("{}", self.ckerc20_ledger_id),
+            )
+            .finish()
+    }
+}
+
+impl TryFrom<AddCkErc20Token> for CkErc20Token {
+    type Error = String;
+
+    fn try_from(value: AddCkErc20Token) -> Result<Self, Self::Error> {
+        let chain_id = value
+            .chain_id
+            .0
+            .to_u64()
+            .ok_or_else(|| format!("chain_id {} is not a u64", value.chain_id))?;
+        let erc20_ethereum_network = EthereumNetwork::try_from(chain_id)?;
+        let erc20_contract_address = Address::from_str(&value.address)?;
+        if erc20_contract_address == Address::ZERO {
+            return Err("ERC-20 contract address cannot be the zero address".to_string());
+        }
+        if value.ckerc20_token_symbol.trim().is_empty() {
+            return Err("ckERC20 token symbol cannot be blank".to_string());
+        }
+        if value.ckerc20_ledger_id == Principal::anonymous() {
+            return Err("ckERC20 ledger ID cannot be the anonymous principal".to_string());
+        }
+        Ok(Self {
+            erc20_ethereum_network,
+            erc20_contract_address,
+            ckerc20_token_symbol: value.ckerc20_token_symbol,
+            ckerc20_ledger_id: value.ckerc20_ledger_id,
+        })
+    }
+}
+
+/// Encodes the call data of `transfer(to, value)` on an ERC-20 contract,
+/// as specified by the [Contract ABI](https://docs.soliditylang.org/en/latest/abi-spec.html):
+/// the 4-byte function selector followed by each argument left-padded to 32 bytes.
+pub fn encode_erc20_transfer_data(to: &Address, value: Erc20Value) -> Vec<u8> {
+    let mut data = Vec::with_capacity(4 + 32 + 32);
+    data.extend_from_slice(&ERC_20_TRANSFER_FUNCTION_SELECTOR);
+    data.extend_from_slice(&[0_u8; 12]);
+    data.extend_from_slice(to.as_ref());
+    data.extend_from_slice(&value.to_be_bytes());
+    data
+}
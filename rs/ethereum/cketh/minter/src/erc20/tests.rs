@@ -0,0 +1,93 @@
+mod encode_erc20_transfer_data {
+    use crate::erc20::encode_erc20_transfer_data;
+    use crate::numeric::Erc20Value;
+    use ic_ethereum_types::Address;
+    use std::str::FromStr;
+
+    #[test]
+    fn should_encode_transfer_call() {
+        // Transfer 1 USDC (10^6 units) to 0xdd2851cdd40ae6536831558dd46db62fac7a844d.
+        let to = Address::from_str("0xdd2851cdd40ae6536831558dd46db62fac7a844d").unwrap();
+
+        let data = encode_erc20_transfer_data(&to, Erc20Value::new(1_000_000));
+
+        assert_eq!(
+            hex::encode(data),
+            "a9059cbb\
+            000000000000000000000000dd2851cdd40ae6536831558dd46db62fac7a844d\
+            00000000000000000000000000000000000000000000000000000000000f4240"
+        );
+    }
+
+    #[test]
+    fn should_encode_max_value() {
+        let data = encode_erc20_transfer_data(&Address::ZERO, Erc20Value::MAX);
+
+        assert_eq!(data.len(), 68);
+        assert_eq!(&data[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(&data[4..36], &[0_u8; 32]);
+        assert_eq!(&data[36..], &[0xff_u8; 32]);
+    }
+}
+
+mod ckerc20_token {
+    use crate::endpoints::AddCkErc20Token;
+    use crate::erc20::CkErc20Token;
+    use crate::lifecycle::EthereumNetwork;
+    use assert_matches::assert_matches;
+    use candid::{Nat, Principal};
+
+    #[test]
+    fn should_parse_valid_token() {
+        let token = CkErc20Token::try_from(ckusdc()).unwrap();
+
+        assert_eq!(
+            token,
+            CkErc20Token {
+                erc20_ethereum_network: EthereumNetwork::Mainnet,
+                erc20_contract_address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48"
+                    .parse()
+                    .unwrap(),
+                ckerc20_token_symbol: "ckUSDC".to_string(),
+                ckerc20_ledger_id: "mxzaz-hqaaa-aaaar-qaada-cai".parse().unwrap(),
+            }
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_token() {
+        for invalid_token in [
+            AddCkErc20Token {
+                chain_id: Nat::from(56_u8),
+                ..ckusdc()
+            },
+            AddCkErc20Token {
+                address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB4".to_string(),
+                ..ckusdc()
+            },
+            AddCkErc20Token {
+                address: "0x0000000000000000000000000000000000000000".to_string(),
+                ..ckusdc()
+            },
+            AddCkErc20Token {
+                ckerc20_token_symbol: " ".to_string(),
+                ..ckusdc()
+            },
+            AddCkErc20Token {
+                ckerc20_ledger_id: Principal::anonymous(),
+                ..ckusdc()
+            },
+        ] {
+            assert_matches!(CkErc20Token::try_from(invalid_token), Err(_));
+        }
+    }
+
+    fn ckusdc() -> AddCkErc20Token {
+        AddCkErc20Token {
+            chain_id: Nat::from(1_u8),
+            address: "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string(),
+            ckerc20_token_symbol: "ckUSDC".to_string(),
+            ckerc20_ledger_id: "mxzaz-hqaaa-aaaar-qaada-cai".parse().unwrap(),
+        }
+    }
+}
@@ -4,7 +4,7 @@ mod tests;
 use crate::eth_rpc::{FixedSizeData, Hash, LogEntry};
 use crate::eth_rpc_client::{EthRpcClient, MultiCallError};
 use crate::logs::{DEBUG, INFO};
-use crate::numeric::{BlockNumber, LogIndex, Wei};
+use crate::numeric::{BlockNumber, Erc20Value, LogIndex, Wei};
 use crate::state::read_state;
 use candid::Principal;
 use hex_literal::hex;
@@ -17,6 +17,10 @@ use thiserror::Error;
 pub(crate) const RECEIVED_ETH_EVENT_TOPIC: [u8; 32] =
     hex!("257e057bb61920d8d0ed2cb7b720ac7f9c513cd1110bc9fa543079154f45f435");
 
+// Keccak256("ReceivedErc20(address,address,uint256,bytes32)")
+pub(crate) const RECEIVED_ERC20_EVENT_TOPIC: [u8; 32] =
+    hex!("4d69d0bd4287b7f66c548f90154dc81bc98f65a1b362775df5ae171a2ccd262b");
+
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
 pub struct ReceivedEthEvent {
     #[n(0)]
@@ -46,6 +50,88 @@ impl fmt::Debug for ReceivedEthEvent {
     }
 }
 
+/// ERC-20 deposit emitted by the ERC-20 helper smart contract.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
+pub struct ReceivedErc20Event {
+    #[n(0)]
+    pub transaction_hash: Hash,
+    #[n(1)]
+    pub block_number: BlockNumber,
+    #[cbor(n(2))]
+    pub log_index: LogIndex,
+    #[n(3)]
+    pub from_address: Address,
+    #[n(4)]
+    pub value: Erc20Value,
+    #[cbor(n(5), with = "crate::cbor::principal")]
+    pub principal: Principal,
+    #[n(6)]
+    pub erc20_contract_address: Address,
+}
+
+impl fmt::Debug for ReceivedErc20Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReceivedErc20Event")
+            .field("transaction_hash", &self.transaction_hash)
+            .field("block_number", &self.block_number)
+            .field("log_index", &self.log_index)
+            .field("from_address", &self.from_address)
+            .field("value", &self.value)
+            .field("principal", &format_args!("{}", self.principal))
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .finish()
+    }
+}
+
+/// A deposit event parsed from the logs of one of the helper smart contracts.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReceivedEvent {
+    Eth(ReceivedEthEvent),
+    Erc20(ReceivedErc20Event),
+}
+
+impl From<ReceivedEthEvent> for ReceivedEvent {
+    fn from(event: ReceivedEthEvent) -> Self {
+        ReceivedEvent::Eth(event)
+    }
+}
+
+impl From<ReceivedErc20Event> for ReceivedEvent {
+    fn from(event: ReceivedErc20Event) -> Self {
+        ReceivedEvent::Erc20(event)
+    }
+}
+
+impl ReceivedEvent {
+    pub fn source(&self) -> EventSource {
+        match self {
+            ReceivedEvent::Eth(event) => event.source(),
+            ReceivedEvent::Erc20(event) => event.source(),
+        }
+    }
+
+    pub fn from_address(&self) -> Address {
+        match self {
+            ReceivedEvent::Eth(event) => event.from_address,
+            ReceivedEvent::Erc20(event) => event.from_address,
+        }
+    }
+
+    pub fn principal(&self) -> Principal {
+        match self {
+            ReceivedEvent::Eth(event) => event.principal,
+            ReceivedEvent::Erc20(event) => event.principal,
+        }
+    }
+
+    pub fn value(&self) -> candid::Nat {
+        match self {
+            ReceivedEvent::Eth(event) => event.value.into(),
+            ReceivedEvent::Erc20(event) => event.value.into(),
+        }
+    }
+}
+
 /// A unique identifier of the event source: the source transaction hash and the log
 /// entry index.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Encode, Decode)]
@@ -71,11 +157,25 @@ impl ReceivedEthEvent {
     }
 }
 
-pub async fn last_received_eth_events(
+impl ReceivedErc20Event {
+    pub fn source(&self) -> EventSource {
+        EventSource {
+            transaction_hash: self.transaction_hash,
+            log_index: self.log_index,
+        }
+    }
+}
+
+/// Retrieves all events with the given topic emitted by the given contract in the given block range.
+pub async fn last_received_events<T>(
     contract_address: Address,
+    topic: [u8; 32],
     from: BlockNumber,
     to: BlockNumber,
-) -> Result<(Vec<ReceivedEthEvent>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>> {
+) -> Result<(Vec<T>, Vec<ReceivedEthEventError>), MultiCallError<Vec<LogEntry>>>
+where
+    T: TryFrom<LogEntry, Error = ReceivedEthEventError>,
+{
     use crate::eth_rpc::GetLogsParam;
 
     if from > to {
@@ -90,15 +190,13 @@ pub async fn last_received_eth_events(
             from_block: from.into(),
             to_block: to.into(),
             address: vec![contract_address],
-            topics: vec![FixedSizeData(RECEIVED_ETH_EVENT_TOPIC)],
+            topics: vec![FixedSizeData(topic)],
         })
         .await?;
 
-    let (ok, not_ok): (Vec<_>, Vec<_>) = result
-        .into_iter()
-        .map(ReceivedEthEvent::try_from)
-        .partition(Result::is_ok);
-    let valid_transactions: Vec<ReceivedEthEvent> = ok.into_iter().map(Result::unwrap).collect();
+    let (ok, not_ok): (Vec<_>, Vec<_>) =
+        result.into_iter().map(T::try_from).partition(Result::is_ok);
+    let valid_transactions: Vec<T> = ok.into_iter().map(Result::unwrap).collect();
     let errors: Vec<ReceivedEthEventError> = not_ok.into_iter().map(Result::unwrap_err).collect();
     Ok((valid_transactions, errors))
 }
@@ -141,6 +239,58 @@ impl TryFrom<LogEntry> for ReceivedEthEvent {
     type Error = ReceivedEthEventError;
 
     fn try_from(entry: LogEntry) -> Result<Self, Self::Error> {
+        let parsed = ParsedLogEntry::parse(entry, 3)?;
+        let from_address = parsed.parse_address(1)?;
+        let principal = parsed.parse_principal(2)?;
+        let value = Wei::from_be_bytes(parsed.parse_value()?);
+
+        Ok(ReceivedEthEvent {
+            transaction_hash: parsed.transaction_hash,
+            block_number: parsed.block_number,
+            log_index: parsed.log_index,
+            from_address,
+            value,
+            principal,
+        })
+    }
+}
+
+impl TryFrom<LogEntry> for ReceivedErc20Event {
+    type Error = ReceivedEthEventError;
+
+    fn try_from(entry: LogEntry) -> Result<Self, Self::Error> {
+        let parsed = ParsedLogEntry::parse(entry, 4)?;
+        let erc20_contract_address = parsed.parse_address(1)?;
+        let from_address = parsed.parse_address(2)?;
+        let principal = parsed.parse_principal(3)?;
+        let value = Erc20Value::from_be_bytes(parsed.parse_value()?);
+
+        Ok(ReceivedErc20Event {
+            transaction_hash: parsed.transaction_hash,
+            block_number: parsed.block_number,
+            log_index: parsed.log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
+        })
+    }
+}
+
+/// A finalized log entry emitted by one of the helper smart contracts,
+/// where the indexed event arguments are stored in the topics
+/// and the non-indexed 32-byte value is stored in the data.
+struct ParsedLogEntry {
+    transaction_hash: Hash,
+    block_number: BlockNumber,
+    log_index: LogIndex,
+    event_source: EventSource,
+    topics: Vec<FixedSizeData>,
+    data: Vec<u8>,
+}
+
+impl ParsedLogEntry {
+    fn parse(entry: LogEntry, expected_num_topics: usize) -> Result<Self, ReceivedEthEventError> {
         let _block_hash = entry
             .block_hash
             .ok_or(ReceivedEthEventError::PendingLogEntry)?;
@@ -170,55 +320,62 @@ impl TryFrom<LogEntry> for ReceivedEthEvent {
             });
         }
 
-        if entry.topics.len() != 3 {
+        if entry.topics.len() != expected_num_topics {
             return Err(ReceivedEthEventError::InvalidEventSource {
                 source: event_source,
                 error: EventSourceError::InvalidEvent(format!(
-                    "Expected exactly 3 topics, got {}",
+                    "Expected exactly {} topics, got {}",
+                    expected_num_topics,
                     entry.topics.len()
                 )),
             });
         }
-        let from_address = Address::try_from(&entry.topics[1].0).map_err(|err| {
+
+        Ok(Self {
+            transaction_hash,
+            block_number,
+            log_index,
+            event_source,
+            topics: entry.topics,
+            data: entry.data.0,
+        })
+    }
+
+    fn parse_address(&self, topic_index: usize) -> Result<Address, ReceivedEthEventError> {
+        Address::try_from(&self.topics[topic_index].0).map_err(|err| {
             ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
+                source: self.event_source,
                 error: EventSourceError::InvalidEvent(format!(
                     "Invalid address in log entry: {}",
                     err
                 )),
             }
-        })?;
-        let principal = parse_principal_from_slice(entry.topics[2].as_ref()).map_err(|_err| {
+        })
+    }
+
+    fn parse_principal(&self, topic_index: usize) -> Result<Principal, ReceivedEthEventError> {
+        parse_principal_from_slice(self.topics[topic_index].as_ref()).map_err(|_err| {
             ReceivedEthEventError::InvalidEventSource {
-                source: event_source,
+                source: self.event_source,
                 error: EventSourceError::InvalidPrincipal {
-                    invalid_principal: entry.topics[2].clone(),
+                    invalid_principal: self.topics[topic_index].clone(),
                 },
             }
-        })?;
-        let value_bytes: [u8; 32] =
-            entry
-                .data
-                .0
-                .try_into()
-                .map_err(|data| ReceivedEthEventError::InvalidEventSource {
-                    source: event_source,
-                    error: EventSourceError::InvalidEvent(format!(
-                        "Invalid data length; expected 32-byte value, got {}",
-                        hex::encode(data)
-                    )),
-                })?;
-        let value = Wei::from_be_bytes(value_bytes);
-
-        Ok(ReceivedEthEvent {
-            transaction_hash,
-            block_number,
-            log_index,
-            from_address,
-            value,
-            principal,
         })
     }
+
+    fn parse_value(&self) -> Result<[u8; 32], ReceivedEthEventError> {
+        self.data
+            .clone()
+            .try_into()
+            .map_err(|data| ReceivedEthEventError::InvalidEventSource {
+                source: self.event_source,
+                error: EventSourceError::InvalidEvent(format!(
+                    "Invalid data length; expected 32-byte value, got {}",
+                    hex::encode(data)
+                )),
+            })
+    }
 }
 
 /// Decode a candid::Principal from a slice of at most 32 bytes
@@ -2,6 +2,7 @@
 //! interface.
 
 use crate::endpoints::CandidBlockTag;
+use crate::eth_rpc_client::config::RpcHeader;
 use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::eth_rpc_error::{sanitize_send_raw_transaction_result, Parser};
 use crate::logs::{DEBUG, TRACE_HTTP};
@@ -56,6 +57,8 @@ impl AsRef<[u8]> for Data {
     }
 }
 
+impl HttpResponsePayload for Data {}
+
 #[derive(Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
 #[serde(transparent)]
 pub struct FixedSizeData(#[serde(with = "ic_ethereum_types::serde_data")] pub [u8; 32]);
@@ -554,30 +557,49 @@ pub fn are_errors_consistent<T: PartialEq>(
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct ResponseSizeEstimate(u64);
+pub struct ResponseSizeEstimate {
+    estimate: u64,
+    max: u64,
+}
 
 impl ResponseSizeEstimate {
     pub fn new(num_bytes: u64) -> Self {
         assert!(num_bytes > 0);
         assert!(num_bytes <= MAX_PAYLOAD_SIZE);
-        Self(num_bytes)
+        Self {
+            estimate: num_bytes,
+            max: MAX_PAYLOAD_SIZE,
+        }
+    }
+
+    /// Caps the estimate and all its future adjustments to `max_num_bytes`.
+    pub fn with_max(self, max_num_bytes: u64) -> Self {
+        assert!(max_num_bytes > 0);
+        let max = max_num_bytes.min(self.max);
+        Self {
+            estimate: self.estimate.min(max),
+            max,
+        }
     }
 
     /// Describes the expected (90th percentile) number of bytes in the HTTP response body.
     /// This number should be less than `MAX_PAYLOAD_SIZE`.
     pub fn get(self) -> u64 {
-        self.0
+        self.estimate
     }
 
     /// Returns a higher estimate for the payload size.
     pub fn adjust(self) -> Self {
-        Self(self.0.max(1024).saturating_mul(2).min(MAX_PAYLOAD_SIZE))
+        Self {
+            estimate: self.estimate.max(1024).saturating_mul(2).min(self.max),
+            max: self.max,
+        }
     }
 }
 
 impl fmt::Display for ResponseSizeEstimate {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.0)
+        write!(f, "{}", self.estimate)
     }
 }
 
@@ -594,6 +616,7 @@ impl HttpResponsePayload for TransactionCount {}
 /// Calls a JSON-RPC method on an Ethereum node at the specified URL.
 pub async fn call<I, O>(
     url: impl Into<String>,
+    extra_headers: &[RpcHeader],
     method: impl Into<String>,
     params: I,
     mut response_size_estimate: ResponseSizeEstimate,
@@ -610,6 +633,14 @@ where
         id: 1,
     };
     let url = url.into();
+    let mut headers = vec![HttpHeader {
+        name: "Content-Type".to_string(),
+        value: "application/json".to_string(),
+    }];
+    headers.extend(extra_headers.iter().map(|header| HttpHeader {
+        name: header.name.clone(),
+        value: header.value.clone(),
+    }));
     let mut retries = 0;
 
     loop {
@@ -635,10 +666,7 @@ where
             url: url.clone(),
             max_response_bytes: Some(effective_size_estimate),
             method: HttpMethod::POST,
-            headers: vec![HttpHeader {
-                name: "Content-Type".to_string(),
-                value: "application/json".to_string(),
-            }],
+            headers: headers.clone(),
             body: Some(payload.as_bytes().to_vec()),
             transform: Some(TransformContext::from_name(
                 "cleanup_response".to_owned(),
@@ -0,0 +1,188 @@
+//! Configuration of the Ethereum JSON-RPC providers queried by the minter
+//! and of the policy used to reconcile their responses.
+
+use crate::eth_rpc::MAX_PAYLOAD_SIZE;
+use candid::{CandidType, Deserialize};
+use minicbor::{Decode, Encode};
+use std::collections::BTreeSet;
+use std::fmt;
+
+const REDACTED: &str = "<redacted>";
+
+/// An HTTP header added to every request sent to an RPC provider,
+/// e.g., to authenticate with an API key.
+#[derive(CandidType, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode)]
+pub struct RpcHeader {
+    #[n(0)]
+    pub name: String,
+    #[n(1)]
+    pub value: String,
+}
+
+impl fmt::Debug for RpcHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Header values usually contain API keys and must not end up in the logs.
+        f.debug_struct("RpcHeader")
+            .field("name", &self.name)
+            .field("value", &REDACTED)
+            .finish()
+    }
+}
+
+/// A JSON-RPC endpoint of an Ethereum node provider.
+#[derive(
+    CandidType, Deserialize, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Encode, Decode,
+)]
+pub struct RpcApi {
+    /// URL of the endpoint, e.g., `https://ethereum.publicnode.com`.
+    /// The URL is public and must not contain any secret.
+    #[n(0)]
+    pub url: String,
+    /// Additional HTTP headers sent with each request.
+    #[n(1)]
+    pub headers: Option<Vec<RpcHeader>>,
+    /// Maximum number of bytes in the body of a response from this provider.
+    /// Larger responses are treated as errors.
+    #[n(2)]
+    pub max_response_bytes: Option<u64>,
+}
+
+/// Policy used to reduce the responses of several providers to a single result.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum ConsensusStrategy {
+    /// All providers must return the same non-error result.
+    #[n(0)]
+    Equality,
+    /// At least `min` providers must return the same non-error result,
+    /// and no other result may be returned by `min` providers or more.
+    #[n(1)]
+    Threshold {
+        #[n(0)]
+        min: u8,
+    },
+}
+
+/// Providers and per-method consensus strategies used by the minter.
+/// Any field that is not set falls back to the built-in default.
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct EthRpcConfig {
+    /// Providers to query. An empty list restores the default providers.
+    #[n(0)]
+    pub providers: Option<Vec<RpcApi>>,
+    #[n(1)]
+    pub eth_get_logs: Option<ConsensusStrategy>,
+    #[n(2)]
+    pub eth_get_block_by_number: Option<ConsensusStrategy>,
+    #[n(3)]
+    pub eth_get_transaction_receipt: Option<ConsensusStrategy>,
+    #[n(4)]
+    pub eth_fee_history: Option<ConsensusStrategy>,
+    /// Only applies to the transaction count at the finalized block.
+    #[n(5)]
+    pub eth_get_transaction_count: Option<ConsensusStrategy>,
+}
+
+impl EthRpcConfig {
+    /// Overrides the fields of `self` with the fields set in `other`.
+    pub fn merge(&mut self, other: EthRpcConfig) {
+        let EthRpcConfig {
+            providers,
+            eth_get_logs,
+            eth_get_block_by_number,
+            eth_get_transaction_receipt,
+            eth_fee_history,
+            eth_get_transaction_count,
+        } = other;
+        if let Some(providers) = providers {
+            self.providers = if providers.is_empty() {
+                None
+            } else {
+                Some(providers)
+            };
+        }
+        if eth_get_logs.is_some() {
+            self.eth_get_logs = eth_get_logs;
+        }
+        if eth_get_block_by_number.is_some() {
+            self.eth_get_block_by_number = eth_get_block_by_number;
+        }
+        if eth_get_transaction_receipt.is_some() {
+            self.eth_get_transaction_receipt = eth_get_transaction_receipt;
+        }
+        if eth_fee_history.is_some() {
+            self.eth_fee_history = eth_fee_history;
+        }
+        if eth_get_transaction_count.is_some() {
+            self.eth_get_transaction_count = eth_get_transaction_count;
+        }
+    }
+
+    /// Checks that the configuration is usable with `num_providers` providers.
+    pub fn validate(&self, num_providers: usize) -> Result<(), String> {
+        if let Some(providers) = &self.providers {
+            let mut urls = BTreeSet::new();
+            for provider in providers {
+                provider.validate()?;
+                if !urls.insert(&provider.url) {
+                    return Err(format!("duplicate provider URL {}", provider.url));
+                }
+            }
+        }
+        for (method, strategy) in [
+            ("eth_getLogs", &self.eth_get_logs),
+            ("eth_getBlockByNumber", &self.eth_get_block_by_number),
+            (
+                "eth_getTransactionReceipt",
+                &self.eth_get_transaction_receipt,
+            ),
+            ("eth_feeHistory", &self.eth_fee_history),
+            ("eth_getTransactionCount", &self.eth_get_transaction_count),
+        ] {
+            if let Some(ConsensusStrategy::Threshold { min }) = strategy {
+                if *min == 0 || usize::from(*min) > num_providers {
+                    return Err(format!(
+                        "threshold {min} for {method} must be between 1 and the number of providers ({num_providers})"
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a copy of the configuration without the values of the HTTP headers,
+    /// so that it can be publicly exposed.
+    pub fn redact_secrets(mut self) -> Self {
+        for provider in self.providers.iter_mut().flatten() {
+            for header in provider.headers.iter_mut().flatten() {
+                header.value = REDACTED.to_string();
+            }
+        }
+        self
+    }
+}
+
+impl RpcApi {
+    fn validate(&self) -> Result<(), String> {
+        match self.url.strip_prefix("https://") {
+            Some(rest) if !rest.is_empty() => {}
+            _ => return Err(format!("provider URL {} must use HTTPS", self.url)),
+        }
+        if self
+            .headers
+            .iter()
+            .flatten()
+            .any(|header| header.name.trim().is_empty())
+        {
+            return Err(format!("provider {} has a blank header name", self.url));
+        }
+        if let Some(max_response_bytes) = self.max_response_bytes {
+            if max_response_bytes == 0 || max_response_bytes > MAX_PAYLOAD_SIZE {
+                return Err(format!(
+                    "max_response_bytes of provider {} must be between 1 and {MAX_PAYLOAD_SIZE}",
+                    self.url
+                ));
+            }
+        }
+        Ok(())
+    }
+}
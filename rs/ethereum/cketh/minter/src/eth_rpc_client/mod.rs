@@ -1,48 +1,82 @@
 use crate::eth_rpc::{
-    self, are_errors_consistent, Block, BlockSpec, FeeHistory, FeeHistoryParams, GetLogsParam,
-    Hash, HttpOutcallError, HttpOutcallResult, HttpResponsePayload, JsonRpcResult, LogEntry,
-    ResponseSizeEstimate, SendRawTransactionResult,
+    self, are_errors_consistent, Block, BlockSpec, BlockTag, Data, FeeHistory, FeeHistoryParams,
+    GetLogsParam, Hash, HttpOutcallError, HttpOutcallResult, HttpResponsePayload, JsonRpcResult,
+    LogEntry, ResponseSizeEstimate, SendRawTransactionResult,
 };
-use crate::eth_rpc_client::providers::{RpcNodeProvider, MAINNET_PROVIDERS, SEPOLIA_PROVIDERS};
-use crate::eth_rpc_client::requests::GetTransactionCountParams;
+use crate::eth_rpc_client::config::{ConsensusStrategy, EthRpcConfig};
+use crate::eth_rpc_client::providers::{default_providers, RpcNodeProvider};
+use crate::eth_rpc_client::requests::{EthCallParams, GetTransactionCountParams};
 use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::lifecycle::EthereumNetwork;
 use crate::logs::{DEBUG, INFO};
 use crate::numeric::TransactionCount;
 use crate::state::State;
 use ic_canister_log::log;
+use ic_ethereum_types::Address;
 use serde::{de::DeserializeOwned, Serialize};
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 
+pub mod config;
 mod providers;
 pub mod requests;
 pub mod responses;
 
+pub use metrics::{encode as encode_metrics, provider_stats, ProviderStats};
+
 #[cfg(test)]
 mod tests;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct EthRpcClient {
     chain: EthereumNetwork,
+    providers: Vec<RpcNodeProvider>,
+    config: EthRpcConfig,
 }
 
 impl EthRpcClient {
-    const fn new(chain: EthereumNetwork) -> Self {
-        Self { chain }
+    #[cfg(test)]
+    fn new(chain: EthereumNetwork) -> Self {
+        Self::with_config(chain, EthRpcConfig::default())
     }
 
-    pub const fn from_state(state: &State) -> Self {
-        Self::new(state.ethereum_network())
+    fn with_config(chain: EthereumNetwork, config: EthRpcConfig) -> Self {
+        let providers = match &config.providers {
+            Some(providers) => providers
+                .iter()
+                .cloned()
+                .map(RpcNodeProvider::Custom)
+                .collect(),
+            None => default_providers(chain).to_vec(),
+        };
+        Self {
+            chain,
+            providers,
+            config,
+        }
     }
 
-    fn providers(&self) -> &[RpcNodeProvider] {
-        match self.chain {
-            EthereumNetwork::Mainnet => &MAINNET_PROVIDERS,
-            EthereumNetwork::Sepolia => &SEPOLIA_PROVIDERS,
+    pub fn from_state(state: &State) -> Self {
+        Self::with_config(state.ethereum_network(), state.eth_rpc_config.clone())
+    }
+
+    /// Number of providers that would be queried with the given configuration.
+    pub fn num_providers(chain: EthereumNetwork, config: &EthRpcConfig) -> usize {
+        match &config.providers {
+            Some(providers) => providers.len(),
+            None => default_providers(chain).len(),
         }
     }
 
+    fn providers(&self) -> &[RpcNodeProvider] {
+        &self.providers
+    }
+
+    /// URLs of the queried providers, in the order in which they are queried.
+    pub fn provider_urls(&self) -> impl Iterator<Item = &str> {
+        self.providers.iter().map(|provider| provider.url())
+    }
+
     /// Query all providers in sequence until one returns an ok result
     /// (which could still be a JsonRpcResult::Error).
     /// If none of the providers return an ok result, return the last error.
@@ -65,8 +99,8 @@ impl EthRpcClient {
                 "[sequential_call_until_ok]: calling provider: {:?}",
                 provider
             );
-            let result = eth_rpc::call(
-                provider.url().to_string(),
+            let result = call_provider(
+                provider,
                 method.clone(),
                 params.clone(),
                 response_size_estimate,
@@ -110,8 +144,8 @@ impl EthRpcClient {
             let mut fut = Vec::with_capacity(providers.len());
             for provider in providers {
                 log!(DEBUG, "[parallel_call]: will call provider: {:?}", provider);
-                fut.push(eth_rpc::call(
-                    provider.url().to_string(),
+                fut.push(call_provider(
+                    provider,
                     method.clone(),
                     params.clone(),
                     response_size_estimate,
@@ -130,7 +164,10 @@ impl EthRpcClient {
         let results: MultiCallResults<Vec<LogEntry>> = self
             .parallel_call("eth_getLogs", vec![params], ResponseSizeEstimate::new(100))
             .await;
-        results.reduce_with_equality()
+        match &self.config.eth_get_logs {
+            Some(strategy) => results.reduce_with_strategy(strategy),
+            None => results.reduce_with_equality(),
+        }
     }
 
     pub async fn eth_get_block_by_number(
@@ -141,7 +178,10 @@ impl EthRpcClient {
 
         let expected_block_size = match self.chain {
             EthereumNetwork::Sepolia => 12 * 1024,
-            EthereumNetwork::Mainnet => 24 * 1024,
+            EthereumNetwork::Mainnet
+            | EthereumNetwork::Arbitrum
+            | EthereumNetwork::Base
+            | EthereumNetwork::Optimism => 24 * 1024,
         };
 
         let results: MultiCallResults<Block> = self
@@ -154,7 +194,10 @@ impl EthRpcClient {
                 ResponseSizeEstimate::new(expected_block_size),
             )
             .await;
-        results.reduce_with_equality()
+        match &self.config.eth_get_block_by_number {
+            Some(strategy) => results.reduce_with_strategy(strategy),
+            None => results.reduce_with_equality(),
+        }
     }
 
     pub async fn eth_get_transaction_receipt(
@@ -168,7 +211,10 @@ impl EthRpcClient {
                 ResponseSizeEstimate::new(700),
             )
             .await;
-        results.reduce_with_equality()
+        match &self.config.eth_get_transaction_receipt {
+            Some(strategy) => results.reduce_with_strategy(strategy),
+            None => results.reduce_with_equality(),
+        }
     }
 
     pub async fn eth_fee_history(
@@ -179,7 +225,12 @@ impl EthRpcClient {
         let results: MultiCallResults<FeeHistory> = self
             .parallel_call("eth_feeHistory", params, ResponseSizeEstimate::new(512))
             .await;
-        results.reduce_with_strict_majority_by_key(|fee_history| fee_history.oldest_block)
+        match &self.config.eth_fee_history {
+            Some(strategy) => results.reduce_with_strategy(strategy),
+            None => {
+                results.reduce_with_strict_majority_by_key(|fee_history| fee_history.oldest_block)
+            }
+        }
     }
 
     pub async fn eth_send_raw_transaction(
@@ -207,6 +258,63 @@ impl EthRpcClient {
         )
         .await
     }
+
+    /// Executes a read-only call of a contract at the latest block.
+    /// Responses of different providers are not reduced, since they may legitimately differ
+    /// when providers are at different blocks.
+    pub async fn eth_call(&self, params: EthCallParams) -> MultiCallResults<Data> {
+        // The response contains at most a few ABI-encoded words.
+        self.parallel_call("eth_call", params, ResponseSizeEstimate::new(256))
+            .await
+    }
+
+    pub async fn eth_get_finalized_transaction_count(
+        &self,
+        address: Address,
+    ) -> Result<TransactionCount, MultiCallError<TransactionCount>> {
+        let results = self
+            .eth_get_transaction_count(GetTransactionCountParams {
+                address,
+                block: BlockSpec::Tag(BlockTag::Finalized),
+            })
+            .await;
+        match &self.config.eth_get_transaction_count {
+            Some(strategy) => results.reduce_with_strategy(strategy),
+            None => results.reduce_with_equality(),
+        }
+    }
+}
+
+/// Calls a single provider and records the outcome of the call in the provider statistics.
+async fn call_provider<I, O>(
+    provider: &RpcNodeProvider,
+    method: impl Into<String>,
+    params: I,
+    response_size_estimate: ResponseSizeEstimate,
+) -> HttpOutcallResult<JsonRpcResult<O>>
+where
+    I: Serialize,
+    O: DeserializeOwned + HttpResponsePayload,
+{
+    let response_size_estimate = match provider.max_response_bytes() {
+        Some(max_response_bytes) => response_size_estimate.with_max(max_response_bytes),
+        None => response_size_estimate,
+    };
+    let start_ns = ic_cdk::api::time();
+    let result = eth_rpc::call(
+        provider.url().to_string(),
+        provider.headers(),
+        method,
+        params,
+        response_size_estimate,
+    )
+    .await;
+    metrics::observe_call(
+        provider.url(),
+        &result,
+        ic_cdk::api::time().saturating_sub(start_ns),
+    );
+    result
 }
 
 /// Aggregates responses of different providers to the same query.
@@ -330,6 +438,62 @@ impl<T: Debug + PartialEq> MultiCallResults<T> {
         Ok(base_result)
     }
 
+    pub fn reduce_with_strategy(self, strategy: &ConsensusStrategy) -> Result<T, MultiCallError<T>>
+    where
+        T: Clone,
+    {
+        match strategy {
+            ConsensusStrategy::Equality => self.reduce_with_equality(),
+            ConsensusStrategy::Threshold { min } => self.reduce_with_threshold(usize::from(*min)),
+        }
+    }
+
+    /// Expects at least `min` providers to return the same ok result.
+    /// Errors returned by other providers are ignored, but it is an error
+    /// if several different results are each returned by at least `min` providers.
+    pub fn reduce_with_threshold(self, min: usize) -> Result<T, MultiCallError<T>>
+    where
+        T: Clone,
+    {
+        assert!(min > 0, "BUG: threshold must be positive");
+        if self
+            .results
+            .values()
+            .all(|result| !matches!(result, Ok(JsonRpcResult::Result(_))))
+        {
+            return Err(self
+                .all_ok()
+                .expect_err("BUG: results without any ok result must be an error"));
+        }
+
+        let mut votes: Vec<(T, Vec<RpcNodeProvider>)> = Vec::new();
+        for (provider, result) in &self.results {
+            if let Ok(JsonRpcResult::Result(value)) = result {
+                match votes
+                    .iter_mut()
+                    .find(|(other_value, _)| other_value == value)
+                {
+                    Some((_, providers)) => providers.push(provider.clone()),
+                    None => votes.push((value.clone(), vec![provider.clone()])),
+                }
+            }
+        }
+        let mut winners = votes
+            .into_iter()
+            .filter(|(_value, providers)| providers.len() >= min);
+        match (winners.next(), winners.next()) {
+            (Some((value, _providers)), None) => Ok(value),
+            _ => {
+                let error = MultiCallError::InconsistentResults(self);
+                log!(
+                    INFO,
+                    "[reduce_with_threshold]: no result returned by at least {min} providers {error:?}"
+                );
+                Err(error)
+            }
+        }
+    }
+
     pub fn reduce_with_min_by_key<F: FnMut(&T) -> K, K: Ord>(
         self,
         extractor: F,
@@ -342,6 +506,18 @@ impl<T: Debug + PartialEq> MultiCallResults<T> {
         Ok(min)
     }
 
+    /// Returns the median result, or the upper median if there is an even number of results,
+    /// so that a single provider cannot move the result arbitrarily far.
+    pub fn reduce_with_median_by_key<F: FnMut(&T) -> K, K: Ord>(
+        self,
+        extractor: F,
+    ) -> Result<T, MultiCallError<T>> {
+        let mut results: Vec<T> = self.all_ok()?.into_values().collect();
+        results.sort_by_cached_key(extractor);
+        let median_index = results.len() / 2;
+        Ok(results.swap_remove(median_index))
+    }
+
     pub fn reduce_with_strict_majority_by_key<F: Fn(&T) -> K, K: Ord>(
         self,
         extractor: F,
@@ -421,3 +597,102 @@ impl<T: Debug + PartialEq> MultiCallResults<T> {
         }
     }
 }
+
+pub(super) mod metrics {
+    use crate::eth_rpc::{HttpOutcallResult, JsonRpcResult};
+    use ic_metrics_encoder::MetricsEncoder;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    /// Statistics about the calls made to a single provider since the last upgrade.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct ProviderStats {
+        pub num_calls: u64,
+        pub num_http_outcall_errors: u64,
+        pub num_json_rpc_errors: u64,
+        pub total_latency_ns: u64,
+    }
+
+    impl ProviderStats {
+        pub fn average_latency_ms(&self) -> Option<u64> {
+            self.total_latency_ns
+                .checked_div(self.num_calls)
+                .map(|latency_ns| latency_ns / 1_000_000)
+        }
+    }
+
+    thread_local! {
+        static PROVIDER_STATS: RefCell<BTreeMap<String, ProviderStats>> = RefCell::default();
+    }
+
+    /// Records the outcome and the latency of a call to the provider with the given URL.
+    pub fn observe_call<T>(
+        url: &str,
+        result: &HttpOutcallResult<JsonRpcResult<T>>,
+        latency_ns: u64,
+    ) {
+        PROVIDER_STATS.with(|stats| {
+            let mut stats = stats.borrow_mut();
+            let provider_stats = stats.entry(url.to_string()).or_default();
+            provider_stats.num_calls += 1;
+            provider_stats.total_latency_ns =
+                provider_stats.total_latency_ns.saturating_add(latency_ns);
+            match result {
+                Ok(JsonRpcResult::Result(_)) => {}
+                Ok(JsonRpcResult::Error { .. }) => provider_stats.num_json_rpc_errors += 1,
+                Err(_) => provider_stats.num_http_outcall_errors += 1,
+            }
+        })
+    }
+
+    /// Returns the statistics of all providers called since the last upgrade, indexed by URL.
+    pub fn provider_stats() -> BTreeMap<String, ProviderStats> {
+        PROVIDER_STATS.with(|stats| stats.borrow().clone())
+    }
+
+    /// Encodes the metrics related to the RPC providers.
+    pub fn encode<W: std::io::Write>(encoder: &mut MetricsEncoder<W>) -> std::io::Result<()> {
+        PROVIDER_STATS.with(|stats| {
+            let stats = stats.borrow();
+            if stats.is_empty() {
+                return Ok(());
+            }
+            let mut calls = encoder.counter_vec(
+                "cketh_eth_rpc_provider_calls",
+                "The number of ETH RPC calls by provider and outcome.",
+            )?;
+            for (url, provider_stats) in stats.iter() {
+                let successful_calls = provider_stats.num_calls
+                    - provider_stats.num_http_outcall_errors
+                    - provider_stats.num_json_rpc_errors;
+                calls = calls
+                    .value(
+                        &[("provider", url.as_str()), ("outcome", "ok")],
+                        successful_calls as f64,
+                    )?
+                    .value(
+                        &[
+                            ("provider", url.as_str()),
+                            ("outcome", "http_outcall_error"),
+                        ],
+                        provider_stats.num_http_outcall_errors as f64,
+                    )?
+                    .value(
+                        &[("provider", url.as_str()), ("outcome", "json_rpc_error")],
+                        provider_stats.num_json_rpc_errors as f64,
+                    )?;
+            }
+            let mut latency = encoder.counter_vec(
+                "cketh_eth_rpc_provider_latency_seconds_total",
+                "The total time spent waiting for ETH RPC responses by provider.",
+            )?;
+            for (url, provider_stats) in stats.iter() {
+                latency = latency.value(
+                    &[("provider", url.as_str())],
+                    provider_stats.total_latency_ns as f64 / 1e9,
+                )?;
+            }
+            Ok(())
+        })
+    }
+}
@@ -1,3 +1,6 @@
+use crate::eth_rpc_client::config::{RpcApi, RpcHeader};
+use crate::lifecycle::EthereumNetwork;
+
 pub(crate) const MAINNET_PROVIDERS: [RpcNodeProvider; 3] = [
     RpcNodeProvider::Ethereum(EthereumProvider::Ankr),
     RpcNodeProvider::Ethereum(EthereumProvider::PublicNode),
@@ -9,10 +12,40 @@ pub(crate) const SEPOLIA_PROVIDERS: [RpcNodeProvider; 2] = [
     RpcNodeProvider::Sepolia(SepoliaProvider::PublicNode),
 ];
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub(crate) const ARBITRUM_PROVIDERS: [RpcNodeProvider; 2] = [
+    RpcNodeProvider::Arbitrum(L2Provider::Ankr),
+    RpcNodeProvider::Arbitrum(L2Provider::PublicNode),
+];
+
+pub(crate) const BASE_PROVIDERS: [RpcNodeProvider; 2] = [
+    RpcNodeProvider::Base(L2Provider::Ankr),
+    RpcNodeProvider::Base(L2Provider::PublicNode),
+];
+
+pub(crate) const OPTIMISM_PROVIDERS: [RpcNodeProvider; 2] = [
+    RpcNodeProvider::Optimism(L2Provider::Ankr),
+    RpcNodeProvider::Optimism(L2Provider::PublicNode),
+];
+
+/// Providers queried when no providers are configured.
+pub(crate) fn default_providers(network: EthereumNetwork) -> &'static [RpcNodeProvider] {
+    match network {
+        EthereumNetwork::Mainnet => &MAINNET_PROVIDERS,
+        EthereumNetwork::Sepolia => &SEPOLIA_PROVIDERS,
+        EthereumNetwork::Arbitrum => &ARBITRUM_PROVIDERS,
+        EthereumNetwork::Base => &BASE_PROVIDERS,
+        EthereumNetwork::Optimism => &OPTIMISM_PROVIDERS,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub(crate) enum RpcNodeProvider {
     Ethereum(EthereumProvider),
     Sepolia(SepoliaProvider),
+    Arbitrum(L2Provider),
+    Base(L2Provider),
+    Optimism(L2Provider),
+    Custom(RpcApi),
 }
 
 impl RpcNodeProvider {
@@ -20,6 +53,32 @@ impl RpcNodeProvider {
         match self {
             Self::Ethereum(provider) => provider.ethereum_mainnet_endpoint_url(),
             Self::Sepolia(provider) => provider.ethereum_sepolia_endpoint_url(),
+            Self::Arbitrum(provider) => provider.arbitrum_endpoint_url(),
+            Self::Base(provider) => provider.base_endpoint_url(),
+            Self::Optimism(provider) => provider.optimism_endpoint_url(),
+            Self::Custom(api) => &api.url,
+        }
+    }
+
+    pub(crate) fn headers(&self) -> &[RpcHeader] {
+        match self {
+            Self::Ethereum(_)
+            | Self::Sepolia(_)
+            | Self::Arbitrum(_)
+            | Self::Base(_)
+            | Self::Optimism(_) => &[],
+            Self::Custom(api) => api.headers.as_deref().unwrap_or_default(),
+        }
+    }
+
+    pub(crate) fn max_response_bytes(&self) -> Option<u64> {
+        match self {
+            Self::Ethereum(_)
+            | Self::Sepolia(_)
+            | Self::Arbitrum(_)
+            | Self::Base(_)
+            | Self::Optimism(_) => None,
+            Self::Custom(api) => api.max_response_bytes,
         }
     }
 }
@@ -60,3 +119,35 @@ impl SepoliaProvider {
         }
     }
 }
+
+/// Providers of the supported L2 networks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Ord, PartialOrd, Hash)]
+pub(crate) enum L2Provider {
+    // https://www.ankr.com/rpc/
+    Ankr,
+    // https://publicnode.com/
+    PublicNode,
+}
+
+impl L2Provider {
+    fn arbitrum_endpoint_url(&self) -> &str {
+        match self {
+            L2Provider::Ankr => "https://rpc.ankr.com/arbitrum",
+            L2Provider::PublicNode => "https://arbitrum-one.publicnode.com",
+        }
+    }
+
+    fn base_endpoint_url(&self) -> &str {
+        match self {
+            L2Provider::Ankr => "https://rpc.ankr.com/base",
+            L2Provider::PublicNode => "https://base.publicnode.com",
+        }
+    }
+
+    fn optimism_endpoint_url(&self) -> &str {
+        match self {
+            L2Provider::Ankr => "https://rpc.ankr.com/optimism",
+            L2Provider::PublicNode => "https://optimism.publicnode.com",
+        }
+    }
+}
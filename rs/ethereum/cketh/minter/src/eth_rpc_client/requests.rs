@@ -1,4 +1,4 @@
-use crate::eth_rpc::BlockSpec;
+use crate::eth_rpc::{BlockSpec, Data};
 use ic_ethereum_types::Address;
 use serde::Serialize;
 
@@ -17,3 +17,28 @@ impl From<GetTransactionCountParams> for (Address, BlockSpec) {
         (params.address, params.block)
     }
 }
+
+/// Parameters of the [`eth_call`](https://ethereum.org/en/developers/docs/apis/json-rpc/#eth_call) call.
+#[derive(Debug, Serialize, Clone)]
+#[serde(into = "(TransactionCall, BlockSpec)")]
+pub struct EthCallParams {
+    /// The call to execute.
+    pub transaction: TransactionCall,
+    /// Block at which the call is executed.
+    pub block: BlockSpec,
+}
+
+impl From<EthCallParams> for (TransactionCall, BlockSpec) {
+    fn from(params: EthCallParams) -> Self {
+        (params.transaction, params.block)
+    }
+}
+
+/// A message call to a contract, without sender nor value.
+#[derive(Debug, Serialize, Clone)]
+pub struct TransactionCall {
+    /// The address of the called contract.
+    pub to: Address,
+    /// ABI-encoded call data.
+    pub data: Data,
+}
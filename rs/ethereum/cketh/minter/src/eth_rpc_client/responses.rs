@@ -30,13 +30,26 @@ pub struct TransactionReceipt {
     /// The hash of the transaction
     #[n(5)]
     pub transaction_hash: Hash,
+
+    /// The fee paid for posting the transaction data to L1.
+    /// Only reported by OP Stack chains, where it is charged on top of the gas fee.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[n(6)]
+    pub l1_fee: Option<Wei>,
 }
 
 impl TransactionReceipt {
     pub fn effective_transaction_fee(&self) -> Wei {
-        self.effective_gas_price
+        let gas_fee = self
+            .effective_gas_price
             .transaction_cost(self.gas_used)
-            .expect("ERROR: overflow during transaction fee calculation")
+            .expect("ERROR: overflow during transaction fee calculation");
+        match self.l1_fee {
+            Some(l1_fee) => gas_fee
+                .checked_add(l1_fee)
+                .expect("ERROR: overflow during transaction fee calculation"),
+            None => gas_fee,
+        }
     }
 }
 
@@ -1,5 +1,8 @@
 mod eth_rpc_client {
-    use crate::eth_rpc_client::providers::{EthereumProvider, RpcNodeProvider, SepoliaProvider};
+    use crate::eth_rpc_client::config::{EthRpcConfig, RpcApi, RpcHeader};
+    use crate::eth_rpc_client::providers::{
+        EthereumProvider, L2Provider, RpcNodeProvider, SepoliaProvider,
+    };
     use crate::eth_rpc_client::EthRpcClient;
     use crate::lifecycle::EthereumNetwork;
 
@@ -33,6 +36,95 @@ mod eth_rpc_client {
             ]
         );
     }
+
+    #[test]
+    fn should_retrieve_l2_providers_of_the_configured_network() {
+        for (network, expected_urls) in [
+            (
+                EthereumNetwork::Arbitrum,
+                [
+                    "https://rpc.ankr.com/arbitrum",
+                    "https://arbitrum-one.publicnode.com",
+                ],
+            ),
+            (
+                EthereumNetwork::Base,
+                ["https://rpc.ankr.com/base", "https://base.publicnode.com"],
+            ),
+            (
+                EthereumNetwork::Optimism,
+                [
+                    "https://rpc.ankr.com/optimism",
+                    "https://optimism.publicnode.com",
+                ],
+            ),
+        ] {
+            let client = EthRpcClient::new(network);
+
+            assert_eq!(
+                client.provider_urls().collect::<Vec<_>>(),
+                expected_urls.to_vec()
+            );
+        }
+
+        assert_eq!(
+            EthRpcClient::new(EthereumNetwork::Base).providers(),
+            &[
+                RpcNodeProvider::Base(L2Provider::Ankr),
+                RpcNodeProvider::Base(L2Provider::PublicNode)
+            ]
+        );
+    }
+
+    #[test]
+    fn should_use_configured_providers_in_order() {
+        let config = EthRpcConfig {
+            providers: Some(vec![
+                RpcApi {
+                    url: "https://eth-mainnet.example.com/v2".to_string(),
+                    headers: Some(vec![RpcHeader {
+                        name: "X-Api-Key".to_string(),
+                        value: "secret".to_string(),
+                    }]),
+                    max_response_bytes: Some(10_000),
+                },
+                RpcApi {
+                    url: "https://ethereum.publicnode.com".to_string(),
+                    headers: None,
+                    max_response_bytes: None,
+                },
+            ]),
+            ..Default::default()
+        };
+        let client = EthRpcClient::with_config(EthereumNetwork::Mainnet, config);
+
+        assert_eq!(
+            client.provider_urls().collect::<Vec<_>>(),
+            vec![
+                "https://eth-mainnet.example.com/v2",
+                "https://ethereum.publicnode.com"
+            ]
+        );
+        assert_eq!(client.providers()[0].max_response_bytes(), Some(10_000));
+        assert_eq!(client.providers()[1].headers(), &[]);
+    }
+
+    #[test]
+    fn should_not_log_header_values() {
+        let provider = RpcNodeProvider::Custom(RpcApi {
+            url: "https://eth-mainnet.example.com/v2".to_string(),
+            headers: Some(vec![RpcHeader {
+                name: "X-Api-Key".to_string(),
+                value: "secret".to_string(),
+            }]),
+            max_response_bytes: None,
+        });
+
+        let debug = format!("{:?}", provider);
+
+        assert!(debug.contains("X-Api-Key"));
+        assert!(!debug.contains("secret"));
+    }
 }
 
 mod multi_call_results {
@@ -226,6 +318,108 @@ mod multi_call_results {
         }
     }
 
+    mod reduce_with_median_by_key {
+        use crate::eth_rpc::JsonRpcResult;
+        use crate::eth_rpc_client::tests::multi_call_results::{ANKR, CLOUDFLARE, PUBLIC_NODE};
+        use crate::eth_rpc_client::MultiCallResults;
+
+        #[test]
+        fn should_get_median_value() {
+            let results: MultiCallResults<u64> = MultiCallResults::from_non_empty_iter(vec![
+                (ANKR, Ok(JsonRpcResult::Result(1_000_000))),
+                (PUBLIC_NODE, Ok(JsonRpcResult::Result(10))),
+                (CLOUDFLARE, Ok(JsonRpcResult::Result(12))),
+            ]);
+
+            assert_eq!(results.reduce_with_median_by_key(|value| *value), Ok(12));
+        }
+
+        #[test]
+        fn should_get_upper_median_value_for_even_number_of_results() {
+            let results: MultiCallResults<u64> = MultiCallResults::from_non_empty_iter(vec![
+                (ANKR, Ok(JsonRpcResult::Result(12))),
+                (PUBLIC_NODE, Ok(JsonRpcResult::Result(10))),
+            ]);
+
+            assert_eq!(results.reduce_with_median_by_key(|value| *value), Ok(12));
+        }
+    }
+
+    mod reduce_with_threshold {
+        use crate::eth_rpc::{HttpOutcallError, JsonRpcResult};
+        use crate::eth_rpc_client::config::ConsensusStrategy;
+        use crate::eth_rpc_client::tests::multi_call_results::{ANKR, CLOUDFLARE, PUBLIC_NODE};
+        use crate::eth_rpc_client::{MultiCallError, MultiCallResults};
+        use ic_cdk::api::call::RejectionCode;
+
+        #[test]
+        fn should_get_result_when_threshold_reached_despite_error() {
+            let results: MultiCallResults<String> = MultiCallResults::from_non_empty_iter(vec![
+                (ANKR, Ok(JsonRpcResult::Result("hello".to_string()))),
+                (
+                    PUBLIC_NODE,
+                    Err(HttpOutcallError::IcError {
+                        code: RejectionCode::SysTransient,
+                        message: "transient".to_string(),
+                    }),
+                ),
+                (CLOUDFLARE, Ok(JsonRpcResult::Result("hello".to_string()))),
+            ]);
+
+            assert_eq!(
+                results
+                    .clone()
+                    .reduce_with_strategy(&ConsensusStrategy::Threshold { min: 2 }),
+                Ok("hello".to_string())
+            );
+            assert_eq!(
+                results.clone().reduce_with_threshold(3),
+                Err(MultiCallError::InconsistentResults(results))
+            );
+        }
+
+        #[test]
+        fn should_fail_when_several_results_reach_threshold() {
+            let results: MultiCallResults<String> = MultiCallResults::from_non_empty_iter(vec![
+                (ANKR, Ok(JsonRpcResult::Result("hello".to_string()))),
+                (PUBLIC_NODE, Ok(JsonRpcResult::Result("world".to_string()))),
+            ]);
+
+            assert_eq!(
+                results.clone().reduce_with_threshold(1),
+                Err(MultiCallError::InconsistentResults(results))
+            );
+        }
+
+        #[test]
+        fn should_be_consistent_error_when_no_ok_result() {
+            let results: MultiCallResults<String> = MultiCallResults::from_non_empty_iter(vec![
+                (
+                    ANKR,
+                    Ok(JsonRpcResult::Error {
+                        code: -32700,
+                        message: "error".to_string(),
+                    }),
+                ),
+                (
+                    PUBLIC_NODE,
+                    Ok(JsonRpcResult::Error {
+                        code: -32700,
+                        message: "error".to_string(),
+                    }),
+                ),
+            ]);
+
+            assert_eq!(
+                results.reduce_with_threshold(1),
+                Err(MultiCallError::ConsistentJsonRpcError {
+                    code: -32700,
+                    message: "error".to_string()
+                })
+            );
+        }
+    }
+
     mod reduce_with_stable_majority_by_key {
         use crate::eth_rpc::{FeeHistory, JsonRpcResult};
         use crate::eth_rpc_client::tests::multi_call_results::{ANKR, CLOUDFLARE, PUBLIC_NODE};
@@ -501,7 +695,7 @@ mod multi_call_results {
 mod eth_get_transaction_receipt {
     use crate::eth_rpc::Hash;
     use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
-    use crate::numeric::{BlockNumber, GasAmount, WeiPerGas};
+    use crate::numeric::{BlockNumber, GasAmount, Wei, WeiPerGas};
     use assert_matches::assert_matches;
     use proptest::proptest;
     use std::str::FromStr;
@@ -542,10 +736,39 @@ mod eth_get_transaction_receipt {
                     "0x0e59bd032b9b22aca5e2784e4cf114783512db00988c716cf17a1cc755a0a93d"
                 )
                 .unwrap(),
+                l1_fee: None,
             }
         )
     }
 
+    #[test]
+    fn should_deserialize_op_stack_transaction_receipt_with_l1_fee() {
+        const RECEIPT: &str = r#"{
+        "transactionHash": "0x0e59bd032b9b22aca5e2784e4cf114783512db00988c716cf17a1cc755a0a93d",
+        "blockHash": "0x82005d2f17b251900968f01b0ed482cb49b7e1d797342bc504904d442b64dbe4",
+        "blockNumber": "0x4132ec",
+        "effectiveGasPrice": "0xf4240",
+        "gasUsed": "0x5208",
+        "l1Fee": "0x2540be400",
+        "l1GasPrice": "0x3b9aca00",
+        "l1GasUsed": "0x640",
+        "status": "0x1"
+    }"#;
+
+        let receipt: TransactionReceipt = serde_json::from_str(RECEIPT).unwrap();
+
+        assert_eq!(receipt.l1_fee, Some(Wei::new(10_000_000_000)));
+        assert_eq!(
+            receipt.effective_transaction_fee(),
+            Wei::new(21_000 * 1_000_000 + 10_000_000_000)
+        );
+        let serialized = serde_json::to_string(&receipt).unwrap();
+        assert_eq!(
+            serde_json::from_str::<TransactionReceipt>(&serialized).unwrap(),
+            receipt
+        );
+    }
+
     #[test]
     fn should_deserialize_transaction_status() {
         let status: TransactionStatus = serde_json::from_str("\"0x01\"").unwrap();
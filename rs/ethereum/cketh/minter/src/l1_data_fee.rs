@@ -0,0 +1,234 @@
+//! Estimation of the fee charged by L2 networks for posting transaction data to Ethereum (L1).
+//!
+//! On an L2 network, the cost of a transaction consists of the L2 execution fee and of an
+//! L1 data fee, which depends on the size of the transaction and on the gas price on L1.
+//! The minter covers the L1 data fee by increasing the gas limit of its transactions by the
+//! equivalent amount of L2 gas, so that the maximum transaction fee `max_fee_per_gas * gas_limit`
+//! charged to users also covers the L1 data fee.
+
+#[cfg(test)]
+mod tests;
+
+use crate::checked_amount::CheckedAmountOf;
+use crate::erc20::encode_erc20_transfer_data;
+use crate::eth_rpc::{BlockSpec, BlockTag, Data};
+use crate::eth_rpc_client::requests::{EthCallParams, TransactionCall};
+use crate::eth_rpc_client::EthRpcClient;
+use crate::lifecycle::{EthereumNetwork, L1DataFeeModel};
+use crate::numeric::{Erc20Value, GasAmount, TransactionNonce, Wei, WeiPerGas};
+use crate::state::{read_state, State};
+use crate::tx::{AccessList, Eip1559TransactionRequest, TransactionPrice};
+use ethnum::u256;
+use hex_literal::hex;
+use ic_ethereum_types::Address;
+
+/// Address of the `GasPriceOracle` predeploy on OP Stack chains.
+/// See <https://docs.optimism.io/stack/smart-contracts#gaspriceoracle>
+const OP_STACK_GAS_PRICE_ORACLE: Address =
+    Address::new(hex!("420000000000000000000000000000000000000f"));
+
+/// Function selector of `getL1Fee(bytes)`,
+/// i.e., the first 4 bytes of the Keccak-256 hash of the function signature.
+const GET_L1_FEE_FUNCTION_SELECTOR: [u8; 4] = hex!("49948e0e");
+
+/// Address of the `NodeInterface` virtual contract on Arbitrum.
+/// See <https://docs.arbitrum.io/build-decentralized-apps/nodeinterface/reference>
+const ARBITRUM_NODE_INTERFACE: Address =
+    Address::new(hex!("00000000000000000000000000000000000000c8"));
+
+/// Function selector of `gasEstimateL1Component(address,bool,bytes)`,
+/// i.e., the first 4 bytes of the Keccak-256 hash of the function signature.
+const GAS_ESTIMATE_L1_COMPONENT_FUNCTION_SELECTOR: [u8; 4] = hex!("77d488a2");
+
+/// The L1 gas price may increase between the estimation and the inclusion of the transaction,
+/// hence the estimated L1 data fee is multiplied by this factor.
+const L1_DATA_FEE_MARGIN: u8 = 2;
+
+/// Placeholder for the addresses of a withdrawal transaction.
+/// Addresses are not compressible in general, which the estimated data fee must reflect.
+const PLACEHOLDER_ADDRESS: Address = Address::new(hex!("b44b5e756a894775fc32eddf3314bb1b1944dc34"));
+
+/// Kind of the transaction for which the L1 data fee is estimated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WithdrawalTransactionKind {
+    /// A plain ETH transfer.
+    Eth,
+    /// A call to `transfer` on an ERC-20 contract.
+    Erc20,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum L1DataFeeEstimationError {
+    RpcError(String),
+    InvalidResponse(String),
+    Overflow(String),
+}
+
+/// Increases the gas limit of `transaction_price` to cover the L1 data fee of a transaction
+/// of the given kind. The price is returned unchanged on networks without L1 data fee.
+pub async fn add_l1_data_fee(
+    transaction_price: TransactionPrice,
+    kind: WithdrawalTransactionKind,
+) -> Result<TransactionPrice, L1DataFeeEstimationError> {
+    let ethereum_network = read_state(State::ethereum_network);
+    let model = match ethereum_network.l1_data_fee_model() {
+        Some(model) => model,
+        None => return Ok(transaction_price),
+    };
+    let transaction = representative_transaction(ethereum_network, &transaction_price, kind);
+    let l1_gas = match model {
+        L1DataFeeModel::OpStack => {
+            let l1_data_fee: Wei = eth_call_uint256(
+                OP_STACK_GAS_PRICE_ORACLE,
+                encode_get_l1_fee_call(&transaction),
+            )
+            .await?;
+            l1_data_fee_to_gas(l1_data_fee, transaction_price.max_fee_per_gas)?
+        }
+        L1DataFeeModel::Arbitrum => {
+            let l1_gas: GasAmount = eth_call_uint256(
+                ARBITRUM_NODE_INTERFACE,
+                encode_gas_estimate_l1_component_call(&transaction),
+            )
+            .await?;
+            l1_gas.checked_mul(L1_DATA_FEE_MARGIN).ok_or_else(|| {
+                L1DataFeeEstimationError::Overflow(format!(
+                    "failed to apply margin to {l1_gas:?} L1 gas"
+                ))
+            })?
+        }
+    };
+    add_l1_gas(transaction_price, l1_gas)
+}
+
+/// Converts an L1 data fee into the amount of L2 gas paying for it, including the safety margin.
+fn l1_data_fee_to_gas(
+    l1_data_fee: Wei,
+    max_fee_per_gas: WeiPerGas,
+) -> Result<GasAmount, L1DataFeeEstimationError> {
+    l1_data_fee
+        .checked_mul(L1_DATA_FEE_MARGIN)
+        .and_then(|fee| fee.checked_div_ceil(max_fee_per_gas.into_inner()))
+        .map(Wei::change_units)
+        .ok_or_else(|| {
+            L1DataFeeEstimationError::Overflow(format!(
+                "failed to convert L1 data fee {l1_data_fee:?} into gas at {max_fee_per_gas:?}"
+            ))
+        })
+}
+
+/// Adds the L2 gas paying for the L1 data fee to the gas limit.
+fn add_l1_gas(
+    transaction_price: TransactionPrice,
+    l1_gas: GasAmount,
+) -> Result<TransactionPrice, L1DataFeeEstimationError> {
+    let overflow = || {
+        L1DataFeeEstimationError::Overflow(format!(
+            "failed to add {l1_gas:?} L1 gas to {transaction_price:?}"
+        ))
+    };
+    let gas_limit = transaction_price
+        .gas_limit
+        .checked_add(l1_gas)
+        .ok_or_else(overflow)?;
+    Ok(TransactionPrice {
+        gas_limit,
+        ..transaction_price
+    })
+}
+
+/// A transaction at least as large as any withdrawal transaction of the given kind.
+fn representative_transaction(
+    ethereum_network: EthereumNetwork,
+    transaction_price: &TransactionPrice,
+    kind: WithdrawalTransactionKind,
+) -> Eip1559TransactionRequest {
+    let (amount, data) = match kind {
+        WithdrawalTransactionKind::Eth => (Wei::MAX, vec![]),
+        WithdrawalTransactionKind::Erc20 => (
+            Wei::ZERO,
+            encode_erc20_transfer_data(&PLACEHOLDER_ADDRESS, Erc20Value::MAX),
+        ),
+    };
+    Eip1559TransactionRequest {
+        chain_id: ethereum_network.chain_id(),
+        nonce: TransactionNonce::from(u64::MAX),
+        max_priority_fee_per_gas: transaction_price.max_priority_fee_per_gas,
+        max_fee_per_gas: transaction_price.max_fee_per_gas,
+        gas_limit: transaction_price.gas_limit,
+        destination: PLACEHOLDER_ADDRESS,
+        amount,
+        data,
+        access_list: AccessList::new(),
+    }
+}
+
+/// Encodes the call data of `getL1Fee(bytes)`, whose argument is the unsigned transaction,
+/// i.e., `0x02 || rlp([chain_id, nonce, ..., access_list])`.
+fn encode_get_l1_fee_call(transaction: &Eip1559TransactionRequest) -> Vec<u8> {
+    use rlp::Encodable;
+    let mut unsigned_transaction = vec![transaction.transaction_type()];
+    unsigned_transaction.extend_from_slice(&transaction.rlp_bytes());
+
+    let mut data = GET_L1_FEE_FUNCTION_SELECTOR.to_vec();
+    // offset of the dynamic `bytes` argument
+    data.extend_from_slice(&u256::from(32_u8).to_be_bytes());
+    encode_bytes(&mut data, &unsigned_transaction);
+    data
+}
+
+/// Encodes the call data of `gasEstimateL1Component(to, false, data)`.
+fn encode_gas_estimate_l1_component_call(transaction: &Eip1559TransactionRequest) -> Vec<u8> {
+    let mut data = GAS_ESTIMATE_L1_COMPONENT_FUNCTION_SELECTOR.to_vec();
+    data.extend_from_slice(&[0_u8; 12]);
+    data.extend_from_slice(transaction.destination.as_ref());
+    // contractCreation = false
+    data.extend_from_slice(&[0_u8; 32]);
+    // offset of the dynamic `bytes` argument, after 3 words of head
+    data.extend_from_slice(&u256::from(96_u8).to_be_bytes());
+    encode_bytes(&mut data, &transaction.data);
+    data
+}
+
+/// Appends the ABI encoding of a `bytes` value: its length followed by its content
+/// right-padded to a multiple of 32 bytes.
+fn encode_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend_from_slice(&u256::from(bytes.len() as u64).to_be_bytes());
+    buffer.extend_from_slice(bytes);
+    let padding = (32 - bytes.len() % 32) % 32;
+    buffer.extend(std::iter::repeat(0_u8).take(padding));
+}
+
+/// Decodes the first word of the ABI-encoded return data as an unsigned integer.
+/// Both `getL1Fee` and `gasEstimateL1Component` return the relevant value in their first word.
+fn decode_first_word(data: &Data) -> Option<u256> {
+    let word: [u8; 32] = data.0.get(..32)?.try_into().ok()?;
+    Some(u256::from_be_bytes(word))
+}
+
+/// Calls a view function returning an unsigned integer on all providers and keeps the median
+/// value, since providers may be at different blocks and a single provider must not be able
+/// to inflate the fee charged to users. The safety margin covers small differences.
+async fn eth_call_uint256<Unit>(
+    to: Address,
+    data: Vec<u8>,
+) -> Result<CheckedAmountOf<Unit>, L1DataFeeEstimationError> {
+    let result = read_state(EthRpcClient::from_state)
+        .eth_call(EthCallParams {
+            transaction: TransactionCall {
+                to,
+                data: Data(data),
+            },
+            block: BlockSpec::Tag(BlockTag::Latest),
+        })
+        .await
+        .reduce_with_median_by_key(decode_first_word)
+        .map_err(|e| L1DataFeeEstimationError::RpcError(format!("{e:?}")))?;
+    decode_first_word(&result)
+        .map(|value| CheckedAmountOf::from_be_bytes(value.to_be_bytes()))
+        .ok_or_else(|| {
+            L1DataFeeEstimationError::InvalidResponse(format!(
+                "expected at least 32 bytes, got {result:?}"
+            ))
+        })
+}
@@ -0,0 +1,130 @@
+use crate::eth_rpc::Data;
+use crate::l1_data_fee::{
+    add_l1_gas, decode_first_word, encode_gas_estimate_l1_component_call, encode_get_l1_fee_call,
+    l1_data_fee_to_gas, representative_transaction, L1DataFeeEstimationError,
+    WithdrawalTransactionKind, GAS_ESTIMATE_L1_COMPONENT_FUNCTION_SELECTOR,
+    GET_L1_FEE_FUNCTION_SELECTOR,
+};
+use crate::lifecycle::EthereumNetwork;
+use crate::numeric::{GasAmount, Wei, WeiPerGas};
+use crate::tx::TransactionPrice;
+use assert_matches::assert_matches;
+use ethnum::u256;
+
+#[test]
+fn should_have_correct_function_selectors() {
+    for (signature, selector) in [
+        ("getL1Fee(bytes)", GET_L1_FEE_FUNCTION_SELECTOR),
+        (
+            "gasEstimateL1Component(address,bool,bytes)",
+            GAS_ESTIMATE_L1_COMPONENT_FUNCTION_SELECTOR,
+        ),
+    ] {
+        let hash = ic_crypto_sha3::Keccak256::hash(signature.as_bytes());
+        assert_eq!(hash[..4], selector, "wrong selector for {signature}");
+    }
+}
+
+#[test]
+fn should_encode_get_l1_fee_call() {
+    use rlp::Encodable;
+    let transaction = representative_transaction(
+        EthereumNetwork::Base,
+        &transaction_price(),
+        WithdrawalTransactionKind::Eth,
+    );
+    let unsigned_transaction_len = 1 + transaction.rlp_bytes().len();
+
+    let data = encode_get_l1_fee_call(&transaction);
+
+    assert_eq!(data[..4], GET_L1_FEE_FUNCTION_SELECTOR);
+    assert_eq!(word(&data, 0), u256::from(32_u8));
+    assert_eq!(word(&data, 1), u256::from(unsigned_transaction_len as u64));
+    assert_eq!(data[4 + 64], 2, "EIP-1559 transaction type");
+    assert_eq!((data.len() - 4) % 32, 0);
+    assert!(data.len() - 4 - 64 >= unsigned_transaction_len);
+}
+
+#[test]
+fn should_encode_gas_estimate_l1_component_call() {
+    let transaction = representative_transaction(
+        EthereumNetwork::Arbitrum,
+        &transaction_price(),
+        WithdrawalTransactionKind::Erc20,
+    );
+
+    let data = encode_gas_estimate_l1_component_call(&transaction);
+
+    assert_eq!(data[..4], GAS_ESTIMATE_L1_COMPONENT_FUNCTION_SELECTOR);
+    assert_eq!(&data[4 + 12..4 + 32], transaction.destination.as_ref());
+    assert_eq!(word(&data, 1), u256::ZERO);
+    assert_eq!(word(&data, 2), u256::from(96_u8));
+    assert_eq!(word(&data, 3), u256::from(68_u8));
+    assert_eq!(&data[4 + 128..4 + 128 + 68], transaction.data.as_slice());
+    assert_eq!(data.len(), 4 + 128 + 96);
+}
+
+#[test]
+fn should_convert_l1_data_fee_to_gas_with_margin() {
+    assert_eq!(
+        l1_data_fee_to_gas(Wei::new(1_000_000_000_000), WeiPerGas::new(1_000_000_000)),
+        Ok(GasAmount::new(2_000))
+    );
+    assert_eq!(
+        l1_data_fee_to_gas(Wei::ONE, WeiPerGas::new(3)),
+        Ok(GasAmount::ONE)
+    );
+    assert_eq!(
+        l1_data_fee_to_gas(Wei::ZERO, WeiPerGas::ONE),
+        Ok(GasAmount::ZERO)
+    );
+}
+
+#[test]
+fn should_fail_to_convert_l1_data_fee_to_gas() {
+    assert_matches!(
+        l1_data_fee_to_gas(Wei::ONE, WeiPerGas::ZERO),
+        Err(L1DataFeeEstimationError::Overflow(_))
+    );
+    assert_matches!(
+        l1_data_fee_to_gas(Wei::MAX, WeiPerGas::ONE),
+        Err(L1DataFeeEstimationError::Overflow(_))
+    );
+}
+
+#[test]
+fn should_add_l1_gas_to_gas_limit() {
+    assert_eq!(
+        add_l1_gas(transaction_price(), GasAmount::new(5_000)),
+        Ok(TransactionPrice {
+            gas_limit: GasAmount::new(26_000),
+            ..transaction_price()
+        })
+    );
+    assert_matches!(
+        add_l1_gas(transaction_price(), GasAmount::MAX),
+        Err(L1DataFeeEstimationError::Overflow(_))
+    );
+}
+
+#[test]
+fn should_decode_first_word() {
+    let mut bytes = vec![0_u8; 96];
+    bytes[31] = 42;
+    bytes[63] = 1;
+    assert_eq!(decode_first_word(&Data(bytes)), Some(u256::from(42_u8)));
+    assert_eq!(decode_first_word(&Data(vec![0_u8; 31])), None);
+}
+
+fn transaction_price() -> TransactionPrice {
+    TransactionPrice {
+        gas_limit: GasAmount::new(21_000),
+        max_fee_per_gas: WeiPerGas::new(100_000_000),
+        max_priority_fee_per_gas: WeiPerGas::new(1_000_000),
+    }
+}
+
+fn word(data: &[u8], index: usize) -> u256 {
+    let start = 4 + 32 * index;
+    u256::from_be_bytes(data[start..start + 32].try_into().unwrap())
+}
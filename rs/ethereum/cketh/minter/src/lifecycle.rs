@@ -28,6 +28,23 @@ pub enum EthereumNetwork {
     #[n(11155111)]
     #[default]
     Sepolia,
+    #[n(42161)]
+    Arbitrum,
+    #[n(8453)]
+    Base,
+    #[n(10)]
+    Optimism,
+}
+
+/// How a network charges for posting transaction data to Ethereum (L1).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum L1DataFeeModel {
+    /// The L1 data fee is charged on top of the L2 execution fee and reported
+    /// separately in the `l1Fee` field of the transaction receipt (Base, Optimism).
+    OpStack,
+    /// The L1 data fee is charged as additional L2 gas,
+    /// which must be covered by the transaction gas limit (Arbitrum).
+    Arbitrum,
 }
 
 impl EthereumNetwork {
@@ -35,15 +52,48 @@ impl EthereumNetwork {
         match self {
             EthereumNetwork::Mainnet => 1,
             EthereumNetwork::Sepolia => 11155111,
+            EthereumNetwork::Arbitrum => 42161,
+            EthereumNetwork::Base => 8453,
+            EthereumNetwork::Optimism => 10,
+        }
+    }
+
+    /// Returns the model used to charge the L1 data fee,
+    /// or `None` if the network is not a rollup.
+    pub fn l1_data_fee_model(&self) -> Option<L1DataFeeModel> {
+        match self {
+            EthereumNetwork::Mainnet | EthereumNetwork::Sepolia => None,
+            EthereumNetwork::Arbitrum => Some(L1DataFeeModel::Arbitrum),
+            EthereumNetwork::Base | EthereumNetwork::Optimism => Some(L1DataFeeModel::OpStack),
         }
     }
 }
 
+impl TryFrom<u64> for EthereumNetwork {
+    type Error = String;
+
+    fn try_from(chain_id: u64) -> Result<Self, Self::Error> {
+        [
+            EthereumNetwork::Mainnet,
+            EthereumNetwork::Sepolia,
+            EthereumNetwork::Arbitrum,
+            EthereumNetwork::Base,
+            EthereumNetwork::Optimism,
+        ]
+        .into_iter()
+        .find(|network| network.chain_id() == chain_id)
+        .ok_or_else(|| format!("unsupported chain_id {chain_id}"))
+    }
+}
+
 impl Display for EthereumNetwork {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             EthereumNetwork::Mainnet => write!(f, "Ethereum Mainnet"),
             EthereumNetwork::Sepolia => write!(f, "Ethereum Testnet Sepolia"),
+            EthereumNetwork::Arbitrum => write!(f, "Arbitrum One"),
+            EthereumNetwork::Base => write!(f, "Base"),
+            EthereumNetwork::Optimism => write!(f, "OP Mainnet"),
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::endpoints::CandidBlockTag;
 use crate::eth_rpc::BlockTag;
+use crate::eth_rpc_client::config::EthRpcConfig;
 use crate::lifecycle::EthereumNetwork;
 use crate::numeric::{BlockNumber, TransactionNonce, Wei};
 use crate::state::transactions::EthTransactions;
@@ -28,6 +29,10 @@ pub struct InitArg {
     pub next_transaction_nonce: Nat,
     #[cbor(n(8), with = "crate::cbor::nat")]
     pub last_scraped_block_number: Nat,
+    #[n(9)]
+    pub eth_rpc_config: Option<EthRpcConfig>,
+    #[cbor(n(10), with = "crate::cbor::nat::option")]
+    pub ethereum_block_confirmations: Option<Nat>,
 }
 
 impl TryFrom<InitArg> for State {
@@ -42,6 +47,8 @@ impl TryFrom<InitArg> for State {
             minimum_withdrawal_amount,
             next_transaction_nonce,
             last_scraped_block_number,
+            eth_rpc_config,
+            ethereum_block_confirmations,
         }: InitArg,
     ) -> Result<Self, Self::Error> {
         use std::str::FromStr;
@@ -69,6 +76,15 @@ impl TryFrom<InitArg> for State {
                         "ERROR: last_scraped_block_number is at maximum value".to_string(),
                     )
                 })?;
+        let ethereum_block_confirmations = ethereum_block_confirmations
+            .map(BlockNumber::try_from)
+            .transpose()
+            .map_err(|e| InvalidStateError::InvalidBlockConfirmations(format!("ERROR: {}", e)))?
+            .unwrap_or(BlockNumber::ZERO);
+        let mut rpc_config = EthRpcConfig::default();
+        if let Some(config) = eth_rpc_config {
+            rpc_config.merge(config);
+        }
         let state = Self {
             ethereum_network,
             ecdsa_key_name,
@@ -78,6 +94,7 @@ impl TryFrom<InitArg> for State {
             ledger_id,
             minimum_withdrawal_amount,
             ethereum_block_height: BlockTag::from(ethereum_block_height),
+            ethereum_block_confirmations,
             first_scraped_block_number,
             last_scraped_block_number,
             last_observed_block_number: None,
@@ -87,6 +104,14 @@ impl TryFrom<InitArg> for State {
             invalid_events: Default::default(),
             eth_balance: Default::default(),
             skipped_blocks: Default::default(),
+            ledger_suite_orchestrator_id: None,
+            erc20_helper_contract_address: None,
+            last_erc20_scraped_block_number: last_scraped_block_number,
+            ckerc20_tokens: Default::default(),
+            erc20_events_to_mint: Default::default(),
+            minted_erc20_events: Default::default(),
+            erc20_skipped_blocks: Default::default(),
+            eth_rpc_config: rpc_config,
             active_tasks: Default::default(),
             http_request_counter: 0,
         };
@@ -99,6 +99,49 @@ mod init {
             minimum_withdrawal_amount: Wei::TWO.into(),
             next_transaction_nonce: TransactionNonce::ZERO.into(),
             last_scraped_block_number: Default::default(),
+            eth_rpc_config: None,
+            ethereum_block_confirmations: None,
         }
     }
 }
+
+mod ethereum_network {
+    use crate::lifecycle::{EthereumNetwork, L1DataFeeModel};
+
+    #[test]
+    fn should_convert_chain_id_to_network() {
+        for network in [
+            EthereumNetwork::Mainnet,
+            EthereumNetwork::Sepolia,
+            EthereumNetwork::Arbitrum,
+            EthereumNetwork::Base,
+            EthereumNetwork::Optimism,
+        ] {
+            assert_eq!(EthereumNetwork::try_from(network.chain_id()), Ok(network));
+            let encoded = minicbor::to_vec(network).unwrap();
+            assert_eq!(
+                minicbor::decode::<EthereumNetwork>(&encoded).unwrap(),
+                network
+            );
+        }
+        assert!(EthereumNetwork::try_from(56).is_err());
+    }
+
+    #[test]
+    fn should_only_have_l1_data_fee_on_rollups() {
+        assert_eq!(EthereumNetwork::Mainnet.l1_data_fee_model(), None);
+        assert_eq!(EthereumNetwork::Sepolia.l1_data_fee_model(), None);
+        assert_eq!(
+            EthereumNetwork::Arbitrum.l1_data_fee_model(),
+            Some(L1DataFeeModel::Arbitrum)
+        );
+        assert_eq!(
+            EthereumNetwork::Base.l1_data_fee_model(),
+            Some(L1DataFeeModel::OpStack)
+        );
+        assert_eq!(
+            EthereumNetwork::Optimism.l1_data_fee_model(),
+            Some(L1DataFeeModel::OpStack)
+        );
+    }
+}
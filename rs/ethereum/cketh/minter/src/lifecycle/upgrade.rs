@@ -1,10 +1,11 @@
 use crate::endpoints::CandidBlockTag;
+use crate::eth_rpc_client::config::EthRpcConfig;
 use crate::logs::INFO;
 use crate::state::audit::{process_event, replay_events, EventType};
 use crate::state::mutate_state;
 use crate::state::STATE;
 use crate::storage::total_event_count;
-use candid::{CandidType, Deserialize, Nat};
+use candid::{CandidType, Deserialize, Nat, Principal};
 use ic_canister_log::log;
 use minicbor::{Decode, Encode};
 
@@ -18,6 +19,16 @@ pub struct UpgradeArg {
     pub ethereum_contract_address: Option<String>,
     #[n(3)]
     pub ethereum_block_height: Option<CandidBlockTag>,
+    #[cbor(n(4), with = "crate::cbor::principal::option")]
+    pub ledger_suite_orchestrator_id: Option<Principal>,
+    #[n(5)]
+    pub erc20_helper_contract_address: Option<String>,
+    #[cbor(n(6), with = "crate::cbor::nat::option")]
+    pub last_erc20_scraped_block_number: Option<Nat>,
+    #[n(7)]
+    pub eth_rpc_config: Option<EthRpcConfig>,
+    #[cbor(n(8), with = "crate::cbor::nat::option")]
+    pub ethereum_block_confirmations: Option<Nat>,
 }
 
 pub fn post_upgrade(upgrade_args: Option<UpgradeArg>) {
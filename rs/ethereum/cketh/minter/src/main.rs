@@ -8,18 +8,24 @@ use ic_cketh_minter::endpoints::events::{
     Event as CandidEvent, EventSource as CandidEventSource, GetEventsArg, GetEventsResult,
 };
 use ic_cketh_minter::endpoints::{
-    Eip1559TransactionPrice, RetrieveEthRequest, RetrieveEthStatus, WithdrawalArg, WithdrawalError,
+    AddCkErc20Token, CkErc20Token, Eip1559TransactionPrice, LedgerError, RetrieveErc20Request,
+    RetrieveEthRequest, RetrieveEthStatus, WithdrawErc20Arg, WithdrawErc20Error, WithdrawalArg,
+    WithdrawalError,
 };
-use ic_cketh_minter::eth_logs::{EventSource, ReceivedEthEvent};
+use ic_cketh_minter::erc20::CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT;
+use ic_cketh_minter::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use ic_cketh_minter::guard::retrieve_eth_guard;
+use ic_cketh_minter::l1_data_fee::{add_l1_data_fee, WithdrawalTransactionKind};
 use ic_cketh_minter::lifecycle::MinterArg;
 use ic_cketh_minter::logs::{DEBUG, INFO};
 use ic_cketh_minter::memo::BurnMemo;
-use ic_cketh_minter::numeric::{LedgerBurnIndex, Wei};
+use ic_cketh_minter::numeric::{Erc20Value, LedgerBurnIndex, Wei};
 use ic_cketh_minter::state::audit::{process_event, Event, EventType};
-use ic_cketh_minter::state::transactions::{EthWithdrawalRequest, Reimbursed};
+use ic_cketh_minter::state::transactions::{
+    Erc20Reimbursed, Erc20WithdrawalRequest, EthWithdrawalRequest, Reimbursed, ReimbursementRequest,
+};
 use ic_cketh_minter::state::{lazy_call_ecdsa_public_key, mutate_state, read_state, State, STATE};
-use ic_cketh_minter::tx::estimate_transaction_price;
+use ic_cketh_minter::tx::{estimate_transaction_price, TransactionPrice};
 use ic_cketh_minter::withdraw::{
     eth_fee_history, process_reimbursement, process_retrieve_eth_requests,
 };
@@ -89,6 +95,9 @@ fn emit_preupgrade_events() {
         storage::record_event(EventType::SyncedToBlock {
             block_number: s.last_scraped_block_number,
         });
+        storage::record_event(EventType::SyncedErc20ToBlock {
+            block_number: s.last_erc20_scraped_block_number,
+        });
     });
 }
 
@@ -131,8 +140,12 @@ async fn eip_1559_transaction_price() -> Eip1559TransactionPrice {
         &eth_fee_history()
             .await
             .expect("ERROR: failed to retrieve fee history"),
+        read_state(State::ethereum_network),
     )
     .expect("ERROR: failed to estimate transaction price");
+    let transaction_price = add_l1_data_fee(transaction_price, WithdrawalTransactionKind::Eth)
+        .await
+        .expect("ERROR: failed to estimate the L1 data fee");
     Eip1559TransactionPrice::from(transaction_price)
 }
 
@@ -236,6 +249,299 @@ async fn withdraw_eth(
     }
 }
 
+/// Withdraws ERC-20 tokens to the given Ethereum address.
+///
+/// The caller must have approved the minter to burn both
+/// * ckETH to pay for the Ethereum transaction fees, and
+/// * the requested amount of ckERC20 tokens.
+///
+/// The ckETH burn index identifies the withdrawal and can be used with `retrieve_eth_status`.
+#[update]
+async fn withdraw_erc20(
+    WithdrawErc20Arg {
+        amount,
+        ckerc20_ledger_id,
+        recipient,
+    }: WithdrawErc20Arg,
+) -> Result<RetrieveErc20Request, WithdrawErc20Error> {
+    let caller = validate_caller_not_anonymous();
+    let _guard = retrieve_eth_guard(caller).unwrap_or_else(|e| {
+        ic_cdk::trap(&format!(
+            "Failed retrieving guard for principal {}: {:?}",
+            caller, e
+        ))
+    });
+
+    let destination = validate_address_as_destination(&recipient).map_err(|e| match e {
+        AddressValidationError::Invalid { .. } | AddressValidationError::NotSupported(_) => {
+            ic_cdk::trap(&e.to_string())
+        }
+        AddressValidationError::Blocked(address) => WithdrawErc20Error::RecipientAddressBlocked {
+            address: address.to_string(),
+        },
+    })?;
+    let ckerc20_withdrawal_amount =
+        Erc20Value::try_from(amount).expect("failed to convert Nat to u256");
+
+    let ckerc20_token = read_state(|s| {
+        s.find_ck_erc20_token_by_ledger_id(&ckerc20_ledger_id)
+            .cloned()
+    })
+    .ok_or_else(|| WithdrawErc20Error::TokenNotSupported {
+        supported_tokens: read_state(|s| {
+            s.supported_ck_erc20_tokens()
+                .cloned()
+                .map(CkErc20Token::from)
+                .collect()
+        }),
+    })?;
+    let cketh_ledger_id = read_state(|s| s.ledger_id);
+
+    let ethereum_network = read_state(State::ethereum_network);
+    let transaction_price = match eth_fee_history().await {
+        Ok(fee_history) => {
+            estimate_transaction_price(&fee_history, ethereum_network).map_err(|e| {
+                WithdrawErc20Error::TemporarilyUnavailable(format!(
+                    "failed to estimate the transaction price: {e:?}"
+                ))
+            })?
+        }
+        Err(e) => {
+            log!(
+                INFO,
+                "[withdraw_erc20]: failed to retrieve fee history: {e:?}"
+            );
+            return Err(WithdrawErc20Error::TemporarilyUnavailable(
+                "failed to retrieve current gas fees".to_string(),
+            ));
+        }
+    };
+    let erc20_tx_fee = add_l1_data_fee(
+        TransactionPrice {
+            gas_limit: CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+            ..transaction_price
+        },
+        WithdrawalTransactionKind::Erc20,
+    )
+    .await
+    .map_err(|e| {
+        WithdrawErc20Error::TemporarilyUnavailable(format!(
+            "failed to estimate the L1 data fee: {e:?}"
+        ))
+    })?
+    .max_transaction_fee();
+
+    let now = ic_cdk::api::time();
+
+    log!(
+        INFO,
+        "[withdraw_erc20]: burning {:?} ckETH to pay for the transaction fee",
+        erc20_tx_fee
+    );
+    let cketh_client = ICRC1Client {
+        runtime: CdkRuntime,
+        ledger_canister_id: cketh_ledger_id,
+    };
+    let cketh_ledger_burn_index = match cketh_client
+        .transfer_from(TransferFromArgs {
+            spender_subaccount: None,
+            from: caller.into(),
+            to: ic_cdk::id().into(),
+            amount: Nat::from(erc20_tx_fee),
+            fee: None,
+            memo: Some(Memo::from(BurnMemo::Erc20GasFee {
+                ckerc20_token_symbol: ckerc20_token.ckerc20_token_symbol.clone(),
+                ckerc20_withdrawal_amount,
+                to_address: destination,
+            })),
+            created_at_time: None,
+        })
+        .await
+    {
+        Ok(Ok(block_index)) => {
+            LedgerBurnIndex::new(block_index.0.to_u64().expect("nat does not fit into u64"))
+        }
+        Ok(Err(error)) => {
+            log!(
+                DEBUG,
+                "[withdraw_erc20]: failed to burn ckETH with error: {error:?}"
+            );
+            return Err(WithdrawErc20Error::CkEthLedgerError {
+                error: LedgerError::from_transfer_from_error(
+                    error,
+                    cketh_ledger_id,
+                    "ckETH".to_string(),
+                    Nat::from(erc20_tx_fee),
+                ),
+            });
+        }
+        Err((error_code, message)) => {
+            log!(
+                DEBUG,
+                "[withdraw_erc20]: failed to call ckETH ledger with error_code: {error_code} and message: {message}",
+            );
+            return Err(WithdrawErc20Error::CkEthLedgerError {
+                error: LedgerError::TemporarilyUnavailable(format!(
+                    "failed to call ckETH ledger with error_code: {error_code} and message: {message}"
+                )),
+            });
+        }
+    };
+
+    log!(
+        INFO,
+        "[withdraw_erc20]: burning {:?} {}",
+        ckerc20_withdrawal_amount,
+        ckerc20_token.ckerc20_token_symbol
+    );
+    let ckerc20_client = ICRC1Client {
+        runtime: CdkRuntime,
+        ledger_canister_id: ckerc20_token.ckerc20_ledger_id,
+    };
+    let ckerc20_burn_error = match ckerc20_client
+        .transfer_from(TransferFromArgs {
+            spender_subaccount: None,
+            from: caller.into(),
+            to: ic_cdk::id().into(),
+            amount: Nat::from(ckerc20_withdrawal_amount),
+            fee: None,
+            memo: Some(Memo::from(BurnMemo::Erc20Convert {
+                ckerc20_withdrawal_id: cketh_ledger_burn_index.get(),
+                to_address: destination,
+            })),
+            created_at_time: None,
+        })
+        .await
+    {
+        Ok(Ok(block_index)) => {
+            let withdrawal_request = Erc20WithdrawalRequest {
+                max_transaction_fee: erc20_tx_fee,
+                withdrawal_amount: ckerc20_withdrawal_amount,
+                destination,
+                cketh_ledger_burn_index,
+                erc20_contract_address: ckerc20_token.erc20_contract_address,
+                ckerc20_ledger_id: ckerc20_token.ckerc20_ledger_id,
+                ckerc20_ledger_burn_index: LedgerBurnIndex::new(
+                    block_index.0.to_u64().expect("nat does not fit into u64"),
+                ),
+                from: caller,
+                from_subaccount: None,
+                created_at: now,
+            };
+            log!(
+                INFO,
+                "[withdraw_erc20]: queuing withdrawal request {:?}",
+                withdrawal_request
+            );
+            mutate_state(|s| {
+                process_event(
+                    s,
+                    EventType::AcceptedErc20WithdrawalRequest(withdrawal_request.clone()),
+                );
+            });
+            return Ok(RetrieveErc20Request::from(withdrawal_request));
+        }
+        Ok(Err(error)) => {
+            log!(
+                DEBUG,
+                "[withdraw_erc20]: failed to burn {} with error: {error:?}",
+                ckerc20_token.ckerc20_token_symbol
+            );
+            LedgerError::from_transfer_from_error(
+                error,
+                ckerc20_token.ckerc20_ledger_id,
+                ckerc20_token.ckerc20_token_symbol.clone(),
+                Nat::from(ckerc20_withdrawal_amount),
+            )
+        }
+        Err((error_code, message)) => {
+            log!(
+                DEBUG,
+                "[withdraw_erc20]: failed to call {} ledger with error_code: {error_code} and message: {message}",
+                ckerc20_token.ckerc20_token_symbol
+            );
+            LedgerError::TemporarilyUnavailable(format!(
+                "failed to call {} ledger with error_code: {error_code} and message: {message}",
+                ckerc20_token.ckerc20_token_symbol
+            ))
+        }
+    };
+
+    // The ckETH for the transaction fee was already burned and must be reimbursed.
+    let reimbursement_request = ReimbursementRequest {
+        withdrawal_id: cketh_ledger_burn_index,
+        reimbursed_amount: erc20_tx_fee,
+        to: caller,
+        to_subaccount: None,
+        transaction_hash: None,
+    };
+    log!(
+        INFO,
+        "[withdraw_erc20]: scheduling reimbursement {:?}",
+        reimbursement_request
+    );
+    mutate_state(|s| {
+        process_event(
+            s,
+            EventType::FailedErc20WithdrawalRequest(reimbursement_request),
+        );
+    });
+    Err(WithdrawErc20Error::CkErc20LedgerError {
+        cketh_block_index: Nat::from(cketh_ledger_burn_index.get()),
+        error: ckerc20_burn_error,
+    })
+}
+
+/// Adds a new ckERC20 token.
+/// Can only be called by the ledger suite orchestrator.
+#[update]
+async fn add_ckerc20_token(erc20_token: AddCkErc20Token) {
+    let orchestrator_id = read_state(|s| s.ledger_suite_orchestrator_id)
+        .unwrap_or_else(|| ic_cdk::trap("ERROR: ERC-20 feature is not activated"));
+    if orchestrator_id != ic_cdk::caller() {
+        ic_cdk::trap(&format!(
+            "ERROR: only the orchestrator {} can add ERC-20 tokens",
+            orchestrator_id
+        ));
+    }
+    let ckerc20_token = ic_cketh_minter::erc20::CkErc20Token::try_from(erc20_token)
+        .unwrap_or_else(|e| ic_cdk::trap(&format!("ERROR: {}", e)));
+    if ckerc20_token.erc20_ethereum_network != read_state(State::ethereum_network) {
+        ic_cdk::trap(&format!(
+            "ERROR: ERC-20 token {:?} is not on the same network as the minter",
+            ckerc20_token
+        ));
+    }
+    let existing_token = read_state(|s| {
+        s.find_ck_erc20_token_by_ledger_id(&ckerc20_token.ckerc20_ledger_id)
+            .or_else(|| {
+                s.find_ck_erc20_token_by_contract_address(&ckerc20_token.erc20_contract_address)
+            })
+            .cloned()
+    });
+    match existing_token {
+        Some(existing_token) if existing_token == ckerc20_token => {
+            log!(
+                INFO,
+                "[add_ckerc20_token]: token {:?} was already added",
+                ckerc20_token
+            );
+        }
+        Some(existing_token) => ic_cdk::trap(&format!(
+            "ERROR: token {:?} conflicts with existing token {:?}",
+            ckerc20_token, existing_token
+        )),
+        None => {
+            log!(
+                INFO,
+                "[add_ckerc20_token]: adding token {:?}",
+                ckerc20_token
+            );
+            mutate_state(|s| process_event(s, EventType::AddedCkErc20Token(ckerc20_token)));
+        }
+    }
+}
+
 #[update]
 async fn retrieve_eth_status(block_index: u64) -> RetrieveEthStatus {
     let ledger_burn_index = LedgerBurnIndex::new(block_index);
@@ -323,16 +629,25 @@ fn get_events(arg: GetEventsArg) -> GetEventsResult {
                 TransactionStatus::Failure => CandidTransactionStatus::Failure,
             },
             transaction_hash: receipt.transaction_hash.to_string(),
+            l1_fee: receipt.l1_fee.map(|fee| fee.into()),
         }
     }
 
     fn map_event(Event { timestamp, payload }: Event) -> CandidEvent {
         use ic_cketh_minter::endpoints::events::EventPayload as EP;
+        use ic_cketh_minter::eth_rpc_client::config::EthRpcConfig;
+        use ic_cketh_minter::lifecycle::{init::InitArg, upgrade::UpgradeArg};
         CandidEvent {
             timestamp,
             payload: match payload {
-                EventType::Init(args) => EP::Init(args),
-                EventType::Upgrade(args) => EP::Upgrade(args),
+                EventType::Init(args) => EP::Init(InitArg {
+                    eth_rpc_config: args.eth_rpc_config.map(EthRpcConfig::redact_secrets),
+                    ..args
+                }),
+                EventType::Upgrade(args) => EP::Upgrade(UpgradeArg {
+                    eth_rpc_config: args.eth_rpc_config.map(EthRpcConfig::redact_secrets),
+                    ..args
+                }),
                 EventType::AcceptedDeposit(ReceivedEthEvent {
                     transaction_hash,
                     block_number,
@@ -422,6 +737,96 @@ fn get_events(arg: GetEventsArg) -> GetEventsResult {
                 EventType::SkippedBlock(block_number) => EP::SkippedBlock {
                     block_number: block_number.into(),
                 },
+                EventType::AddedCkErc20Token(token) => EP::AddedCkErc20Token {
+                    chain_id: token.erc20_ethereum_network.chain_id().into(),
+                    address: token.erc20_contract_address.to_string(),
+                    ckerc20_token_symbol: token.ckerc20_token_symbol,
+                    ckerc20_ledger_id: token.ckerc20_ledger_id,
+                },
+                EventType::AcceptedErc20Deposit(ReceivedErc20Event {
+                    transaction_hash,
+                    block_number,
+                    log_index,
+                    from_address,
+                    value,
+                    principal,
+                    erc20_contract_address,
+                }) => EP::AcceptedErc20Deposit {
+                    transaction_hash: transaction_hash.to_string(),
+                    block_number: block_number.into(),
+                    log_index: log_index.into(),
+                    from_address: from_address.to_string(),
+                    value: value.into(),
+                    principal,
+                    erc20_contract_address: erc20_contract_address.to_string(),
+                },
+                EventType::MintedCkErc20 {
+                    event_source,
+                    mint_block_index,
+                    ckerc20_token_symbol,
+                    erc20_contract_address,
+                } => EP::MintedCkErc20 {
+                    event_source: map_event_source(event_source),
+                    mint_block_index: mint_block_index.get().into(),
+                    ckerc20_token_symbol,
+                    erc20_contract_address: erc20_contract_address.to_string(),
+                },
+                EventType::SyncedErc20ToBlock { block_number } => EP::SyncedErc20ToBlock {
+                    block_number: block_number.into(),
+                },
+                EventType::SkippedErc20Block(block_number) => EP::SkippedErc20Block {
+                    block_number: block_number.into(),
+                },
+                EventType::AcceptedErc20WithdrawalRequest(Erc20WithdrawalRequest {
+                    max_transaction_fee,
+                    withdrawal_amount,
+                    destination,
+                    cketh_ledger_burn_index,
+                    erc20_contract_address,
+                    ckerc20_ledger_id,
+                    ckerc20_ledger_burn_index,
+                    from,
+                    from_subaccount,
+                    created_at,
+                }) => EP::AcceptedErc20WithdrawalRequest {
+                    max_transaction_fee: max_transaction_fee.into(),
+                    withdrawal_amount: withdrawal_amount.into(),
+                    erc20_contract_address: erc20_contract_address.to_string(),
+                    destination: destination.to_string(),
+                    cketh_ledger_burn_index: cketh_ledger_burn_index.get().into(),
+                    ckerc20_ledger_id,
+                    ckerc20_ledger_burn_index: ckerc20_ledger_burn_index.get().into(),
+                    from,
+                    from_subaccount: from_subaccount.map(|s| s.0),
+                    created_at,
+                },
+                EventType::FailedErc20WithdrawalRequest(ReimbursementRequest {
+                    withdrawal_id,
+                    reimbursed_amount,
+                    to,
+                    to_subaccount,
+                    transaction_hash: _,
+                }) => EP::FailedErc20WithdrawalRequest {
+                    withdrawal_id: withdrawal_id.get().into(),
+                    reimbursed_amount: reimbursed_amount.into(),
+                    to,
+                    to_subaccount: to_subaccount.map(|s| s.0),
+                },
+                EventType::ReimbursedErc20Withdrawal(Erc20Reimbursed {
+                    withdrawal_id,
+                    burn_in_block,
+                    reimbursed_in_block,
+                    ledger_id,
+                    reimbursed_amount,
+                    transaction_hash,
+                }) => EP::ReimbursedErc20Withdrawal {
+                    withdrawal_id: withdrawal_id.get().into(),
+                    burn_in_block: burn_in_block.get().into(),
+                    reimbursed_in_block: reimbursed_in_block.get().into(),
+                    ledger_id,
+                    reimbursed_amount: reimbursed_amount.into(),
+                    transaction_hash: transaction_hash.map(|h| h.to_string()),
+                },
             },
         }
     }
@@ -493,6 +898,18 @@ fn http_request(req: HttpRequest) -> HttpResponse {
                 .value(&[("status", "accepted")], s.minted_events.len() as f64)?
                 .value(&[("status", "rejected")], s.invalid_events.len() as f64)?;
 
+                w.encode_gauge(
+                    "cketh_minter_last_processed_erc20_block",
+                    s.last_erc20_scraped_block_number.as_f64(),
+                    "The last Ethereum block the ckETH minter checked for ERC-20 deposits.",
+                )?;
+
+                w.encode_gauge(
+                    "cketh_minter_minted_erc20_deposits",
+                    s.minted_erc20_events.len() as f64,
+                    "The number of ERC-20 deposits for which the ckETH minter minted ckERC20 tokens.",
+                )?;
+
                 w.encode_gauge(
                     "cketh_event_count",
                     storage::total_event_count() as f64,
@@ -513,6 +930,11 @@ fn http_request(req: HttpRequest) -> HttpResponse {
                     s.eth_balance.total_unspent_tx_fees().as_f64(),
                     "Total amount of unspent fees across all finalized transaction ckETH -> ETH",
                 )?;
+                w.encode_gauge(
+                    "cketh_minter_total_uncovered_tx_fees",
+                    s.eth_balance.total_uncovered_tx_fees().as_f64(),
+                    "Total amount of fees paid by the minter in excess of the fees charged for withdrawals",
+                )?;
 
                 let now_nanos = ic_cdk::api::time();
                 let age_nanos = now_nanos.saturating_sub(
@@ -527,6 +949,7 @@ fn http_request(req: HttpRequest) -> HttpResponse {
                 )?;
 
                 ic_cketh_minter::eth_rpc::encode_metrics(w)?;
+                ic_cketh_minter::eth_rpc_client::encode_metrics(w)?;
 
                 Ok(())
             })
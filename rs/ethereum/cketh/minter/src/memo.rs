@@ -1,9 +1,9 @@
 #[cfg(test)]
 mod tests;
 
-use crate::eth_logs::ReceivedEthEvent;
+use crate::eth_logs::{ReceivedErc20Event, ReceivedEthEvent, ReceivedEvent};
 use crate::eth_rpc::Hash;
-use crate::numeric::LogIndex;
+use crate::numeric::{Erc20Value, LogIndex};
 use crate::state::transactions::ReimbursementRequest;
 use ic_ethereum_types::Address;
 use icrc_ledger_types::icrc1::transfer::Memo;
@@ -39,6 +39,15 @@ pub enum MintMemo {
         /// Hash of the failed transaction.
         tx_hash: Hash,
     },
+    #[n(2)]
+    /// The minter reimbursed a withdrawal request that failed
+    /// before any transaction was created, e.g., because
+    /// the ckERC20 tokens could not be burned.
+    ReimburseWithdrawal {
+        #[n(0)]
+        /// The id corresponding to the withdrawal request.
+        withdrawal_id: u64,
+    },
 }
 
 impl From<MintMemo> for Memo {
@@ -56,6 +65,31 @@ pub enum BurnMemo {
         /// The destination of the withdraw request.
         to_address: Address,
     },
+    #[n(1)]
+    /// The minter processed an ERC-20 withdraw request
+    /// and burned ckETH to pay for the transaction fee.
+    Erc20GasFee {
+        #[n(0)]
+        /// The symbol of the ckERC20 token to withdraw.
+        ckerc20_token_symbol: String,
+        #[n(1)]
+        /// The amount of ckERC20 tokens to withdraw.
+        ckerc20_withdrawal_amount: Erc20Value,
+        #[n(2)]
+        /// The destination of the withdraw request.
+        to_address: Address,
+    },
+    #[n(2)]
+    /// The minter processed an ERC-20 withdraw request.
+    Erc20Convert {
+        #[n(0)]
+        /// The id corresponding to the withdrawal request,
+        /// i.e., the burn index of the transaction fee on the ckETH ledger.
+        ckerc20_withdrawal_id: u64,
+        #[n(1)]
+        /// The destination of the withdraw request.
+        to_address: Address,
+    },
 }
 
 impl From<BurnMemo> for Memo {
@@ -74,13 +108,34 @@ impl From<ReceivedEthEvent> for Memo {
     }
 }
 
+impl From<ReceivedErc20Event> for Memo {
+    fn from(event: ReceivedErc20Event) -> Self {
+        Memo::from(MintMemo::Convert {
+            from_address: event.from_address,
+            tx_hash: event.transaction_hash,
+            log_index: event.log_index,
+        })
+    }
+}
+
+impl From<ReceivedEvent> for Memo {
+    fn from(event: ReceivedEvent) -> Self {
+        match event {
+            ReceivedEvent::Eth(event) => Memo::from(event),
+            ReceivedEvent::Erc20(event) => Memo::from(event),
+        }
+    }
+}
+
 impl From<ReimbursementRequest> for Memo {
     fn from(reimbursement_request: ReimbursementRequest) -> Self {
-        Memo::from(MintMemo::Reimburse {
-            withdrawal_id: reimbursement_request.withdrawal_id.get(),
-            tx_hash: reimbursement_request
-                .transaction_hash
-                .expect("A hash should be set for reimbursement memos."),
-        })
+        let withdrawal_id = reimbursement_request.withdrawal_id.get();
+        match reimbursement_request.transaction_hash {
+            Some(tx_hash) => Memo::from(MintMemo::Reimburse {
+                withdrawal_id,
+                tx_hash,
+            }),
+            None => Memo::from(MintMemo::ReimburseWithdrawal { withdrawal_id }),
+        }
     }
 }
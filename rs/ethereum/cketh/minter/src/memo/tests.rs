@@ -3,7 +3,7 @@ mod decode_memo {
     use crate::eth_rpc::Hash;
     use crate::memo::{Address, ReceivedEthEvent};
     use crate::memo::{BurnMemo, MintMemo};
-    use crate::numeric::{BlockNumber, LedgerBurnIndex, LogIndex, Wei};
+    use crate::numeric::{BlockNumber, Erc20Value, LedgerBurnIndex, LogIndex, Wei};
     use crate::state::transactions::ReimbursementRequest;
     use candid::Principal;
     use icrc_ledger_types::icrc1::transfer::Memo;
@@ -44,6 +44,33 @@ mod decode_memo {
             })?;
         }
 
+        #[test]
+        fn mint_reimburse_withdrawal_memo_round_trip(
+            withdrawal_id in any::<u64>(),
+        ) {
+            check_roundtrip(&MintMemo::ReimburseWithdrawal {
+                withdrawal_id,
+            })?;
+        }
+
+        #[test]
+        fn burn_erc20_memo_round_trip(
+            ckerc20_token_symbol in "[a-zA-Z0-9]{1,20}",
+            ckerc20_withdrawal_amount in any::<u128>(),
+            ckerc20_withdrawal_id in any::<u64>(),
+            to_address in arb_address(),
+        ) {
+            check_roundtrip(&BurnMemo::Erc20GasFee {
+                ckerc20_token_symbol,
+                ckerc20_withdrawal_amount: Erc20Value::from(ckerc20_withdrawal_amount),
+                to_address,
+            })?;
+            check_roundtrip(&BurnMemo::Erc20Convert {
+                ckerc20_withdrawal_id,
+                to_address,
+            })?;
+        }
+
         #[test]
         fn burn_memo_round_trip(
             to_address in arb_address(),
@@ -109,6 +136,20 @@ mod decode_memo {
         );
     }
 
+    #[test]
+    fn encode_mint_reimburse_withdrawal_memo_is_stable() {
+        let reimbursment_request = ReimbursementRequest {
+            withdrawal_id: LedgerBurnIndex::from(1234_u64),
+            reimbursed_amount: Wei::from(100_u64),
+            to: Principal::anonymous(),
+            to_subaccount: None,
+            transaction_hash: None,
+        };
+        let memo: Memo = reimbursment_request.into();
+
+        assert_eq!(memo.0, [130, 2, 129, 25, 4, 210]);
+    }
+
     #[test]
     fn encode_burn_memo_is_stable() {
         let memo = Memo::from(BurnMemo::Convert {
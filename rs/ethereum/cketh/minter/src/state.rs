@@ -1,10 +1,14 @@
 use crate::address::ecdsa_public_key_to_address;
-use crate::eth_logs::{EventSource, ReceivedEthEvent};
+use crate::erc20::CkErc20Token;
+use crate::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent, ReceivedEvent};
 use crate::eth_rpc::BlockTag;
+use crate::eth_rpc_client::config::EthRpcConfig;
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
+use crate::eth_rpc_client::EthRpcClient;
 use crate::lifecycle::upgrade::UpgradeArg;
 use crate::lifecycle::EthereumNetwork;
 use crate::logs::DEBUG;
+use crate::map::MultiKeyMap;
 use crate::numeric::{BlockNumber, LedgerBurnIndex, LedgerMintIndex, TransactionNonce, Wei};
 use candid::Principal;
 use ic_canister_log::log;
@@ -39,6 +43,13 @@ impl MintedEvent {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MintedErc20Event {
+    pub deposit_event: ReceivedErc20Event,
+    pub mint_block_index: LedgerMintIndex,
+    pub ckerc20_token_symbol: String,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct State {
     pub ethereum_network: EthereumNetwork,
@@ -48,6 +59,9 @@ pub struct State {
     pub ecdsa_public_key: Option<EcdsaPublicKeyResponse>,
     pub minimum_withdrawal_amount: Wei,
     pub ethereum_block_height: BlockTag,
+    /// Number of blocks the minter waits for on top of the block given by
+    /// `ethereum_block_height` before considering a block final.
+    pub ethereum_block_confirmations: BlockNumber,
     pub first_scraped_block_number: BlockNumber,
     pub last_scraped_block_number: BlockNumber,
     pub last_observed_block_number: Option<BlockNumber>,
@@ -57,6 +71,21 @@ pub struct State {
     pub eth_transactions: EthTransactions,
     pub skipped_blocks: BTreeSet<BlockNumber>,
 
+    /// Principal of the ledger suite orchestrator, the only principal allowed
+    /// to register new ckERC20 tokens.
+    pub ledger_suite_orchestrator_id: Option<Principal>,
+    /// Address of the helper smart contract used to deposit ERC-20 tokens.
+    pub erc20_helper_contract_address: Option<Address>,
+    pub last_erc20_scraped_block_number: BlockNumber,
+    /// Supported ckERC20 tokens, indexed by their ledger ID and their ERC-20 contract address.
+    pub ckerc20_tokens: MultiKeyMap<Principal, Address, CkErc20Token>,
+    pub erc20_events_to_mint: BTreeMap<EventSource, ReceivedErc20Event>,
+    pub minted_erc20_events: BTreeMap<EventSource, MintedErc20Event>,
+    pub erc20_skipped_blocks: BTreeSet<BlockNumber>,
+
+    /// Ethereum JSON-RPC providers and the policy used to reconcile their responses.
+    pub eth_rpc_config: EthRpcConfig,
+
     /// Current balance of ETH held by minter.
     /// Computed based on audit events.
     pub eth_balance: EthBalance,
@@ -79,6 +108,10 @@ pub enum InvalidStateError {
     InvalidEthereumContractAddress(String),
     InvalidMinimumWithdrawalAmount(String),
     InvalidLastScrapedBlockNumber(String),
+    InvalidBlockConfirmations(String),
+    InvalidLedgerSuiteOrchestratorId(String),
+    InvalidErc20HelperContractAddress(String),
+    InvalidEthRpcConfig(String),
 }
 
 impl State {
@@ -107,6 +140,26 @@ impl State {
                 "minimum_withdrawal_amount must be positive".to_string(),
             ));
         }
+        if self.ledger_suite_orchestrator_id == Some(Principal::anonymous()) {
+            return Err(InvalidStateError::InvalidLedgerSuiteOrchestratorId(
+                "ledger_suite_orchestrator_id cannot be the anonymous principal".to_string(),
+            ));
+        }
+        if self
+            .erc20_helper_contract_address
+            .iter()
+            .any(|address| address == &Address::ZERO)
+        {
+            return Err(InvalidStateError::InvalidErc20HelperContractAddress(
+                "erc20_helper_contract_address cannot be the zero address".to_string(),
+            ));
+        }
+        self.eth_rpc_config
+            .validate(EthRpcClient::num_providers(
+                self.ethereum_network,
+                &self.eth_rpc_config,
+            ))
+            .map_err(InvalidStateError::InvalidEthRpcConfig)?;
         Ok(())
     }
 
@@ -132,17 +185,52 @@ impl State {
         self.update_eth_balance_upon_deposit(event)
     }
 
+    fn record_erc20_event_to_mint(&mut self, event: &ReceivedErc20Event) {
+        let event_source = event.source();
+        assert!(
+            self.ckerc20_tokens
+                .contains_alt(&event.erc20_contract_address),
+            "BUG: unsupported ERC-20 contract address in event {event:?}"
+        );
+        assert!(
+            !self.erc20_events_to_mint.contains_key(&event_source),
+            "there must be no two different events with the same source"
+        );
+        assert!(!self.minted_erc20_events.contains_key(&event_source));
+        assert!(!self.invalid_events.contains_key(&event_source));
+
+        self.erc20_events_to_mint
+            .insert(event_source, event.clone());
+    }
+
+    /// Returns all deposits, either ETH or ERC-20, that were accepted but not yet minted.
+    pub fn all_events_to_mint(&self) -> Vec<ReceivedEvent> {
+        self.events_to_mint
+            .values()
+            .cloned()
+            .map(ReceivedEvent::from)
+            .chain(
+                self.erc20_events_to_mint
+                    .values()
+                    .cloned()
+                    .map(ReceivedEvent::from),
+            )
+            .collect()
+    }
+
     pub fn has_events_to_mint(&self) -> bool {
-        !self.events_to_mint.is_empty()
+        !self.events_to_mint.is_empty() || !self.erc20_events_to_mint.is_empty()
     }
 
     fn record_invalid_deposit(&mut self, source: EventSource, error: String) -> bool {
         assert!(
-            !self.events_to_mint.contains_key(&source),
+            !self.events_to_mint.contains_key(&source)
+                && !self.erc20_events_to_mint.contains_key(&source),
             "attempted to mark an accepted event as invalid"
         );
         assert!(
-            !self.minted_events.contains_key(&source),
+            !self.minted_events.contains_key(&source)
+                && !self.minted_erc20_events.contains_key(&source),
             "attempted to mark a minted event {source:?} as invalid"
         );
 
@@ -178,6 +266,72 @@ impl State {
         );
     }
 
+    fn record_successful_erc20_mint(
+        &mut self,
+        source: EventSource,
+        ckerc20_token_symbol: &str,
+        mint_block_index: LedgerMintIndex,
+    ) {
+        assert!(
+            !self.invalid_events.contains_key(&source),
+            "attempted to mint an event previously marked as invalid {source:?}"
+        );
+        let deposit_event = match self.erc20_events_to_mint.remove(&source) {
+            Some(event) => event,
+            None => panic!("attempted to mint ckERC20 for an unknown event {source:?}"),
+        };
+        assert_eq!(
+            self.minted_erc20_events.insert(
+                source,
+                MintedErc20Event {
+                    deposit_event,
+                    mint_block_index,
+                    ckerc20_token_symbol: ckerc20_token_symbol.to_string(),
+                }
+            ),
+            None,
+            "attempted to mint ckERC20 twice for the same event {source:?}"
+        );
+    }
+
+    fn record_add_ckerc20_token(&mut self, ckerc20_token: CkErc20Token) {
+        assert_eq!(
+            self.ethereum_network, ckerc20_token.erc20_ethereum_network,
+            "BUG: ERC-20 token {ckerc20_token:?} is on a different network than the minter"
+        );
+        let ckerc20_ledger_id = ckerc20_token.ckerc20_ledger_id;
+        let erc20_contract_address = ckerc20_token.erc20_contract_address;
+        assert_eq!(
+            self.ckerc20_tokens.try_insert(
+                ckerc20_ledger_id,
+                erc20_contract_address,
+                ckerc20_token
+            ),
+            Ok(()),
+            "BUG: ckERC20 token with ledger {ckerc20_ledger_id} or ERC-20 contract {erc20_contract_address} already exists"
+        );
+    }
+
+    pub fn find_ck_erc20_token_by_ledger_id(
+        &self,
+        ckerc20_ledger_id: &Principal,
+    ) -> Option<&CkErc20Token> {
+        self.ckerc20_tokens.get(ckerc20_ledger_id)
+    }
+
+    pub fn find_ck_erc20_token_by_contract_address(
+        &self,
+        erc20_contract_address: &Address,
+    ) -> Option<&CkErc20Token> {
+        self.ckerc20_tokens.get_alt(erc20_contract_address)
+    }
+
+    pub fn supported_ck_erc20_tokens(&self) -> impl Iterator<Item = &CkErc20Token> {
+        self.ckerc20_tokens
+            .iter()
+            .map(|(_ledger_id, _address, token)| token)
+    }
+
     pub fn record_finalized_transaction(
         &mut self,
         withdrawal_id: &LedgerBurnIndex,
@@ -212,9 +366,24 @@ impl State {
             .get_alt(withdrawal_id)
             .expect("BUG: missing finalized transaction");
         let charged_tx_fee = tx.transaction_price().max_transaction_fee();
-        let unspent_tx_fee = charged_tx_fee.checked_sub(tx_fee).expect(
-            "BUG: charged transaction fee MUST always be at least the effective transaction fee",
-        );
+        // On OP Stack chains, the L1 data fee is only estimated when the transaction is created
+        // and may exceed the corresponding share of the charged fee if the L1 gas price spiked.
+        // The difference is then covered by the minter.
+        let unspent_tx_fee = match charged_tx_fee.checked_sub(tx_fee) {
+            Some(unspent_tx_fee) => unspent_tx_fee,
+            None => {
+                assert!(
+                    receipt.l1_fee.is_some(),
+                    "BUG: charged transaction fee MUST always be at least the effective transaction fee"
+                );
+                self.eth_balance.total_uncovered_tx_fees_add(
+                    tx_fee
+                        .checked_sub(charged_tx_fee)
+                        .expect("BUG: effective transaction fee is greater than charged fee"),
+                );
+                Wei::ZERO
+            }
+        };
         let debited_amount = match receipt.status {
             TransactionStatus::Success => tx
                 .transaction()
@@ -236,6 +405,14 @@ impl State {
         );
     }
 
+    pub fn record_skipped_erc20_block(&mut self, block_number: BlockNumber) {
+        assert!(
+            self.erc20_skipped_blocks.insert(block_number),
+            "BUG: block {} was already skipped for ERC-20 deposits",
+            block_number
+        );
+    }
+
     pub const fn ethereum_network(&self) -> EthereumNetwork {
         self.ethereum_network
     }
@@ -244,6 +421,10 @@ impl State {
         self.ethereum_block_height
     }
 
+    pub const fn ethereum_block_confirmations(&self) -> BlockNumber {
+        self.ethereum_block_confirmations
+    }
+
     fn upgrade(&mut self, upgrade_args: UpgradeArg) -> Result<(), InvalidStateError> {
         use std::str::FromStr;
 
@@ -252,6 +433,11 @@ impl State {
             minimum_withdrawal_amount,
             ethereum_contract_address,
             ethereum_block_height,
+            ledger_suite_orchestrator_id,
+            erc20_helper_contract_address,
+            last_erc20_scraped_block_number,
+            eth_rpc_config,
+            ethereum_block_confirmations,
         } = upgrade_args;
         if let Some(nonce) = next_transaction_nonce {
             let nonce = TransactionNonce::try_from(nonce)
@@ -273,6 +459,30 @@ impl State {
         if let Some(block_height) = ethereum_block_height {
             self.ethereum_block_height = block_height.into();
         }
+        if let Some(confirmations) = ethereum_block_confirmations {
+            self.ethereum_block_confirmations =
+                BlockNumber::try_from(confirmations).map_err(|e| {
+                    InvalidStateError::InvalidBlockConfirmations(format!("ERROR: {}", e))
+                })?;
+        }
+        if let Some(orchestrator_id) = ledger_suite_orchestrator_id {
+            self.ledger_suite_orchestrator_id = Some(orchestrator_id);
+        }
+        if let Some(address) = erc20_helper_contract_address {
+            let erc20_helper_contract_address = Address::from_str(&address).map_err(|e| {
+                InvalidStateError::InvalidErc20HelperContractAddress(format!("ERROR: {}", e))
+            })?;
+            self.erc20_helper_contract_address = Some(erc20_helper_contract_address);
+        }
+        if let Some(block_number) = last_erc20_scraped_block_number {
+            self.last_erc20_scraped_block_number =
+                BlockNumber::try_from(block_number).map_err(|e| {
+                    InvalidStateError::InvalidLastScrapedBlockNumber(format!("ERROR: {}", e))
+                })?;
+        }
+        if let Some(config) = eth_rpc_config {
+            self.eth_rpc_config.merge(config);
+        }
         self.validate_config()
     }
 
@@ -307,9 +517,29 @@ impl State {
             other.last_scraped_block_number
         );
         ensure_eq!(self.ethereum_block_height, other.ethereum_block_height);
+        ensure_eq!(
+            self.ethereum_block_confirmations,
+            other.ethereum_block_confirmations
+        );
         ensure_eq!(self.events_to_mint, other.events_to_mint);
         ensure_eq!(self.minted_events, other.minted_events);
         ensure_eq!(self.invalid_events, other.invalid_events);
+        ensure_eq!(
+            self.ledger_suite_orchestrator_id,
+            other.ledger_suite_orchestrator_id
+        );
+        ensure_eq!(
+            self.erc20_helper_contract_address,
+            other.erc20_helper_contract_address
+        );
+        ensure_eq!(
+            self.last_erc20_scraped_block_number,
+            other.last_erc20_scraped_block_number
+        );
+        ensure_eq!(self.ckerc20_tokens, other.ckerc20_tokens);
+        ensure_eq!(self.erc20_events_to_mint, other.erc20_events_to_mint);
+        ensure_eq!(self.minted_erc20_events, other.minted_erc20_events);
+        ensure_eq!(self.eth_rpc_config, other.eth_rpc_config);
 
         self.eth_transactions
             .is_equivalent_to(&other.eth_transactions)
@@ -394,6 +624,9 @@ pub struct EthBalance {
     /// Total amount of fees that were charged to the user during the withdrawal
     /// but not consumed by the finalized transaction ckETH -> ETH
     total_unspent_tx_fees: Wei,
+    /// Total amount of fees paid by the minter in excess of the fees charged to the user,
+    /// which happens when the L1 data fee on OP Stack chains exceeds its estimate.
+    total_uncovered_tx_fees: Wei,
 }
 
 impl Default for EthBalance {
@@ -402,6 +635,7 @@ impl Default for EthBalance {
             eth_balance: Wei::ZERO,
             total_effective_tx_fees: Wei::ZERO,
             total_unspent_tx_fees: Wei::ZERO,
+            total_uncovered_tx_fees: Wei::ZERO,
         }
     }
 }
@@ -449,6 +683,18 @@ impl EthBalance {
             })
     }
 
+    fn total_uncovered_tx_fees_add(&mut self, value: Wei) {
+        self.total_uncovered_tx_fees = self
+            .total_uncovered_tx_fees
+            .checked_add(value)
+            .unwrap_or_else(|| {
+                panic!(
+                    "BUG: overflow when adding {} to {}",
+                    value, self.total_uncovered_tx_fees
+                )
+            })
+    }
+
     pub fn eth_balance(&self) -> Wei {
         self.eth_balance
     }
@@ -459,11 +705,15 @@ impl EthBalance {
     pub fn total_unspent_tx_fees(&self) -> Wei {
         self.total_unspent_tx_fees
     }
+
+    pub fn total_uncovered_tx_fees(&self) -> Wei {
+        self.total_uncovered_tx_fees
+    }
 }
 
 #[derive(Debug, Hash, Copy, Clone, PartialEq, Eq, EnumIter)]
 pub enum TaskType {
-    MintCkEth,
+    Mint,
     RetrieveEth,
     ScrapEthLogs,
     Reimbursement,
@@ -82,6 +82,45 @@ pub fn apply_state_transition(state: &mut State, payload: &EventType) {
         EventType::SkippedBlock(block_number) => {
             state.record_skipped_block(*block_number);
         }
+        EventType::AddedCkErc20Token(ckerc20_token) => {
+            state.record_add_ckerc20_token(ckerc20_token.clone());
+        }
+        EventType::AcceptedErc20Deposit(erc20_event) => {
+            state.record_erc20_event_to_mint(erc20_event);
+        }
+        EventType::MintedCkErc20 {
+            event_source,
+            mint_block_index,
+            ckerc20_token_symbol,
+            erc20_contract_address: _,
+        } => {
+            state.record_successful_erc20_mint(
+                *event_source,
+                ckerc20_token_symbol,
+                *mint_block_index,
+            );
+        }
+        EventType::SyncedErc20ToBlock { block_number } => {
+            state.last_erc20_scraped_block_number = *block_number;
+        }
+        EventType::SkippedErc20Block(block_number) => {
+            state.record_skipped_erc20_block(*block_number);
+        }
+        EventType::AcceptedErc20WithdrawalRequest(request) => {
+            state
+                .eth_transactions
+                .record_erc20_withdrawal_request(request.clone());
+        }
+        EventType::FailedErc20WithdrawalRequest(request) => {
+            state
+                .eth_transactions
+                .record_failed_erc20_withdrawal_request(request.clone());
+        }
+        EventType::ReimbursedErc20Withdrawal(reimbursed) => {
+            state
+                .eth_transactions
+                .record_finalized_erc20_reimbursement(reimbursed.clone());
+        }
     }
 }
 
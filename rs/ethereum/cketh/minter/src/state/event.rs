@@ -1,9 +1,13 @@
-use crate::eth_logs::{EventSource, ReceivedEthEvent};
+use crate::erc20::CkErc20Token;
+use crate::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::lifecycle::{init::InitArg, upgrade::UpgradeArg};
 use crate::numeric::{BlockNumber, LedgerBurnIndex, LedgerMintIndex};
-use crate::state::transactions::{EthWithdrawalRequest, Reimbursed};
+use crate::state::transactions::{
+    Erc20Reimbursed, Erc20WithdrawalRequest, EthWithdrawalRequest, Reimbursed, ReimbursementRequest,
+};
 use crate::tx::{Eip1559TransactionRequest, SignedEip1559TransactionRequest};
+use ic_ethereum_types::Address;
 use minicbor::{Decode, Encode};
 
 /// The event describing the ckETH minter state transition.
@@ -93,6 +97,49 @@ pub enum EventType {
     /// The minter could not scrap the logs for that block.
     #[n(13)]
     SkippedBlock(#[n(0)] BlockNumber),
+    /// Add a new ckERC20 token.
+    #[n(14)]
+    AddedCkErc20Token(#[n(0)] CkErc20Token),
+    /// The minter discovered a ckERC20 deposit in the ERC-20 helper contract logs.
+    #[n(15)]
+    AcceptedErc20Deposit(#[n(0)] ReceivedErc20Event),
+    /// The minter minted ckERC20 in response to a deposit.
+    #[n(16)]
+    MintedCkErc20 {
+        /// The unique identifier of the deposit on the Ethereum network.
+        #[n(0)]
+        event_source: EventSource,
+        /// The transaction index on the ckERC20 ledger.
+        #[cbor(n(1), with = "crate::cbor::id")]
+        mint_block_index: LedgerMintIndex,
+        /// The symbol of the minted ckERC20 token.
+        #[n(2)]
+        ckerc20_token_symbol: String,
+        /// The address of the deposited ERC-20 contract.
+        #[n(3)]
+        erc20_contract_address: Address,
+    },
+    /// The minter processed the ERC-20 helper smart contract logs up to the specified height.
+    #[n(17)]
+    SyncedErc20ToBlock {
+        /// The last processed block number (inclusive).
+        #[n(0)]
+        block_number: BlockNumber,
+    },
+    /// The minter could not scrap the ERC-20 helper smart contract logs for that block.
+    #[n(18)]
+    SkippedErc20Block(#[n(0)] BlockNumber),
+    /// The minter accepted a new ERC-20 withdrawal request.
+    #[n(19)]
+    AcceptedErc20WithdrawalRequest(#[n(0)] Erc20WithdrawalRequest),
+    /// The minter burned the ckETH transaction fee of an ERC-20 withdrawal
+    /// but failed to burn the requested ckERC20 amount.
+    /// The burned ckETH must be reimbursed.
+    #[n(20)]
+    FailedErc20WithdrawalRequest(#[n(0)] ReimbursementRequest),
+    /// The minter successfully reimbursed a failed ERC-20 withdrawal.
+    #[n(21)]
+    ReimbursedErc20Withdrawal(#[n(0)] Erc20Reimbursed),
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq)]
@@ -1,7 +1,9 @@
 use crate::checked_amount::CheckedAmountOf;
 use crate::endpoints::CandidBlockTag;
-use crate::eth_logs::{EventSource, ReceivedEthEvent};
+use crate::erc20::CkErc20Token;
+use crate::eth_logs::{EventSource, ReceivedErc20Event, ReceivedEthEvent};
 use crate::eth_rpc::{BlockTag, Hash};
+use crate::eth_rpc_client::config::{ConsensusStrategy, EthRpcConfig, RpcApi, RpcHeader};
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
 use crate::lifecycle::init::InitArg;
 use crate::lifecycle::upgrade::UpgradeArg;
@@ -11,6 +13,9 @@ use crate::numeric::{
     TransactionNonce, Wei, WeiPerGas,
 };
 use crate::state::event::{Event, EventType};
+use crate::state::transactions::{
+    Erc20Reimbursed, Erc20WithdrawalRequest, ReimbursementRequest, Subaccount,
+};
 use crate::state::State;
 use crate::tx::{
     AccessList, AccessListItem, Eip1559Signature, Eip1559TransactionRequest,
@@ -57,6 +62,8 @@ fn a_state() -> State {
         minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
         next_transaction_nonce: Default::default(),
         last_scraped_block_number: Default::default(),
+        eth_rpc_config: None,
+        ethereum_block_confirmations: None,
     })
     .expect("init args should be valid")
 }
@@ -184,6 +191,8 @@ mod mint_transaction {
             minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
             next_transaction_nonce: Default::default(),
             last_scraped_block_number: Default::default(),
+            eth_rpc_config: None,
+            ethereum_block_confirmations: None,
         })
         .expect("init args should be valid")
     }
@@ -208,6 +217,8 @@ fn received_eth_event() -> ReceivedEthEvent {
 
 mod upgrade {
     use crate::eth_rpc::BlockTag;
+    use crate::eth_rpc_client::config::{ConsensusStrategy, EthRpcConfig, RpcApi, RpcHeader};
+    use crate::eth_rpc_client::EthRpcClient;
     use crate::lifecycle::upgrade::UpgradeArg;
     use crate::numeric::{wei_from_milli_ether, TransactionNonce, Wei};
     use crate::state::{InvalidStateError, State};
@@ -258,6 +269,24 @@ mod upgrade {
             }),
             Err(InvalidStateError::InvalidEthereumContractAddress(_))
         );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                ledger_suite_orchestrator_id: Some(Principal::anonymous()),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidLedgerSuiteOrchestratorId(_))
+        );
+
+        let mut state = initial_state();
+        assert_matches!(
+            state.upgrade(UpgradeArg {
+                erc20_helper_contract_address: Some("invalid".to_string()),
+                ..Default::default()
+            }),
+            Err(InvalidStateError::InvalidErc20HelperContractAddress(_))
+        );
     }
 
     #[test]
@@ -271,6 +300,26 @@ mod upgrade {
                 "0xb44B5e756A894775FC32EDdf3314Bb1B1944dC34".to_string(),
             ),
             ethereum_block_height: Some(CandidBlockTag::Safe),
+            ledger_suite_orchestrator_id: Some(
+                Principal::from_text("vxkom-oyaaa-aaaar-qafda-cai").unwrap(),
+            ),
+            erc20_helper_contract_address: Some(
+                "0xE1788E4834c896F1932188645cc36c54d1b80AC1".to_string(),
+            ),
+            last_erc20_scraped_block_number: Some(Nat::from(5_000_000_u32)),
+            eth_rpc_config: Some(EthRpcConfig {
+                providers: Some(vec![RpcApi {
+                    url: "https://eth-mainnet.example.com/v2".to_string(),
+                    headers: Some(vec![RpcHeader {
+                        name: "X-Api-Key".to_string(),
+                        value: "secret".to_string(),
+                    }]),
+                    max_response_bytes: Some(1_000_000),
+                }]),
+                eth_get_logs: Some(ConsensusStrategy::Threshold { min: 1 }),
+                ..Default::default()
+            }),
+            ethereum_block_confirmations: Some(Nat::from(10_u8)),
         };
 
         state.upgrade(upgrade_arg).expect("valid upgrade args");
@@ -285,6 +334,124 @@ mod upgrade {
             Some(Address::from_str("0xb44B5e756A894775FC32EDdf3314Bb1B1944dC34").unwrap())
         );
         assert_eq!(state.ethereum_block_height, BlockTag::Safe);
+        assert_eq!(state.ethereum_block_confirmations, BlockNumber::new(10));
+        assert_eq!(
+            state.ledger_suite_orchestrator_id,
+            Some(Principal::from_text("vxkom-oyaaa-aaaar-qafda-cai").unwrap())
+        );
+        assert_eq!(
+            state.erc20_helper_contract_address,
+            Some(Address::from_str("0xE1788E4834c896F1932188645cc36c54d1b80AC1").unwrap())
+        );
+        assert_eq!(
+            state.last_erc20_scraped_block_number,
+            BlockNumber::new(5_000_000)
+        );
+        assert_eq!(
+            EthRpcClient::from_state(&state)
+                .provider_urls()
+                .collect::<Vec<_>>(),
+            vec!["https://eth-mainnet.example.com/v2"]
+        );
+        assert_eq!(
+            state.eth_rpc_config.eth_get_logs,
+            Some(ConsensusStrategy::Threshold { min: 1 })
+        );
+    }
+
+    #[test]
+    fn should_restore_default_providers_when_empty() {
+        let mut state = initial_state();
+        let default_providers: Vec<String> = EthRpcClient::from_state(&state)
+            .provider_urls()
+            .map(String::from)
+            .collect();
+        state
+            .upgrade(UpgradeArg {
+                eth_rpc_config: Some(EthRpcConfig {
+                    providers: Some(vec![rpc_api("https://eth.example.com")]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .expect("valid upgrade args");
+
+        state
+            .upgrade(UpgradeArg {
+                eth_rpc_config: Some(EthRpcConfig {
+                    providers: Some(vec![]),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            })
+            .expect("valid upgrade args");
+
+        assert_eq!(
+            EthRpcClient::from_state(&state)
+                .provider_urls()
+                .collect::<Vec<_>>(),
+            default_providers
+        );
+    }
+
+    #[test]
+    fn should_fail_when_eth_rpc_config_invalid() {
+        for invalid_config in [
+            EthRpcConfig {
+                providers: Some(vec![rpc_api("http://eth.example.com")]),
+                ..Default::default()
+            },
+            EthRpcConfig {
+                providers: Some(vec![
+                    rpc_api("https://eth.example.com"),
+                    rpc_api("https://eth.example.com"),
+                ]),
+                ..Default::default()
+            },
+            EthRpcConfig {
+                providers: Some(vec![RpcApi {
+                    max_response_bytes: Some(0),
+                    ..rpc_api("https://eth.example.com")
+                }]),
+                ..Default::default()
+            },
+            EthRpcConfig {
+                providers: Some(vec![RpcApi {
+                    headers: Some(vec![RpcHeader {
+                        name: " ".to_string(),
+                        value: "secret".to_string(),
+                    }]),
+                    ..rpc_api("https://eth.example.com")
+                }]),
+                ..Default::default()
+            },
+            EthRpcConfig {
+                eth_fee_history: Some(ConsensusStrategy::Threshold { min: 0 }),
+                ..Default::default()
+            },
+            EthRpcConfig {
+                providers: Some(vec![rpc_api("https://eth.example.com")]),
+                eth_get_logs: Some(ConsensusStrategy::Threshold { min: 2 }),
+                ..Default::default()
+            },
+        ] {
+            let mut state = initial_state();
+            assert_matches!(
+                state.upgrade(UpgradeArg {
+                    eth_rpc_config: Some(invalid_config),
+                    ..Default::default()
+                }),
+                Err(InvalidStateError::InvalidEthRpcConfig(_))
+            );
+        }
+    }
+
+    fn rpc_api(url: &str) -> RpcApi {
+        RpcApi {
+            url: url.to_string(),
+            headers: None,
+            max_response_bytes: None,
+        }
     }
 
     fn initial_state() -> State {
@@ -300,6 +467,8 @@ mod upgrade {
             minimum_withdrawal_amount: wei_from_milli_ether(10).into(),
             next_transaction_nonce: Default::default(),
             last_scraped_block_number: Default::default(),
+            eth_rpc_config: None,
+            ethereum_block_confirmations: None,
         })
         .expect("valid init args")
     }
@@ -368,6 +537,7 @@ prop_compose! {
         ledger_id in arb_principal(),
         ecdsa_key_name in "[a-z_]*",
         last_scraped_block_number in arb_nat(),
+        eth_rpc_config in proptest::option::of(arb_eth_rpc_config()),
     ) -> InitArg {
         InitArg {
             ethereum_network: EthereumNetwork::Sepolia,
@@ -377,7 +547,9 @@ prop_compose! {
             ethereum_block_height,
             minimum_withdrawal_amount,
             next_transaction_nonce,
-            last_scraped_block_number
+            last_scraped_block_number,
+            eth_rpc_config,
+            ethereum_block_confirmations: None,
         }
     }
 }
@@ -388,12 +560,134 @@ prop_compose! {
         ethereum_block_height in proptest::option::of(arb_block_tag()),
         minimum_withdrawal_amount in proptest::option::of(arb_nat()),
         next_transaction_nonce in proptest::option::of(arb_nat()),
+        ledger_suite_orchestrator_id in proptest::option::of(arb_principal()),
+        erc20_helper_contract_address in proptest::option::of(arb_address()),
+        last_erc20_scraped_block_number in proptest::option::of(arb_nat()),
+        eth_rpc_config in proptest::option::of(arb_eth_rpc_config()),
     ) -> UpgradeArg {
         UpgradeArg {
             ethereum_contract_address: contract_address.map(|addr| addr.to_string()),
             ethereum_block_height,
             minimum_withdrawal_amount,
             next_transaction_nonce,
+            ledger_suite_orchestrator_id,
+            erc20_helper_contract_address: erc20_helper_contract_address.map(|addr| addr.to_string()),
+            last_erc20_scraped_block_number,
+            eth_rpc_config,
+            ethereum_block_confirmations: None,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_rpc_api()(
+        url in "https://[a-z]{1,10}\\.[a-z]{2,3}/[a-z0-9]*",
+        headers in proptest::option::of(pvec(("[A-Za-z-]{1,20}", ".*").prop_map(|(name, value)| RpcHeader { name, value }), 0..3)),
+        max_response_bytes in proptest::option::of(any::<u64>()),
+    ) -> RpcApi {
+        RpcApi {
+            url,
+            headers,
+            max_response_bytes,
+        }
+    }
+}
+
+fn arb_consensus_strategy() -> impl Strategy<Value = ConsensusStrategy> {
+    prop_oneof![
+        Just(ConsensusStrategy::Equality),
+        any::<u8>().prop_map(|min| ConsensusStrategy::Threshold { min }),
+    ]
+}
+
+prop_compose! {
+    fn arb_eth_rpc_config()(
+        providers in proptest::option::of(pvec(arb_rpc_api(), 0..5)),
+        eth_get_logs in proptest::option::of(arb_consensus_strategy()),
+        eth_get_block_by_number in proptest::option::of(arb_consensus_strategy()),
+        eth_get_transaction_receipt in proptest::option::of(arb_consensus_strategy()),
+        eth_fee_history in proptest::option::of(arb_consensus_strategy()),
+        eth_get_transaction_count in proptest::option::of(arb_consensus_strategy()),
+    ) -> EthRpcConfig {
+        EthRpcConfig {
+            providers,
+            eth_get_logs,
+            eth_get_block_by_number,
+            eth_get_transaction_receipt,
+            eth_fee_history,
+            eth_get_transaction_count,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_received_erc20_event()(
+        transaction_hash in arb_hash(),
+        block_number in arb_checked_amount_of(),
+        log_index in arb_checked_amount_of(),
+        from_address in arb_address(),
+        value in arb_checked_amount_of(),
+        principal in arb_principal(),
+        erc20_contract_address in arb_address(),
+    ) -> ReceivedErc20Event {
+        ReceivedErc20Event {
+            transaction_hash,
+            block_number,
+            log_index,
+            from_address,
+            value,
+            principal,
+            erc20_contract_address,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_ckerc20_token()(
+        erc20_ethereum_network in prop_oneof![
+            Just(EthereumNetwork::Mainnet),
+            Just(EthereumNetwork::Sepolia),
+            Just(EthereumNetwork::Arbitrum),
+            Just(EthereumNetwork::Base),
+            Just(EthereumNetwork::Optimism),
+        ],
+        erc20_contract_address in arb_address(),
+        ckerc20_token_symbol in "ck[A-Z]{3,5}",
+        ckerc20_ledger_id in arb_principal(),
+    ) -> CkErc20Token {
+        CkErc20Token {
+            erc20_ethereum_network,
+            erc20_contract_address,
+            ckerc20_token_symbol,
+            ckerc20_ledger_id,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_erc20_withdrawal_request()(
+        max_transaction_fee in arb_checked_amount_of(),
+        withdrawal_amount in arb_checked_amount_of(),
+        destination in arb_address(),
+        cketh_ledger_burn_index in any::<u64>(),
+        erc20_contract_address in arb_address(),
+        ckerc20_ledger_id in arb_principal(),
+        ckerc20_ledger_burn_index in any::<u64>(),
+        from in arb_principal(),
+        from_subaccount in proptest::option::of(uniform32(any::<u8>())),
+        created_at in any::<u64>(),
+    ) -> Erc20WithdrawalRequest {
+        Erc20WithdrawalRequest {
+            max_transaction_fee,
+            withdrawal_amount,
+            destination,
+            cketh_ledger_burn_index: cketh_ledger_burn_index.into(),
+            erc20_contract_address,
+            ckerc20_ledger_id,
+            ckerc20_ledger_burn_index: ckerc20_ledger_burn_index.into(),
+            from,
+            from_subaccount: from_subaccount.map(Subaccount),
+            created_at,
         }
     }
 }
@@ -477,6 +771,7 @@ prop_compose! {
         gas_used in arb_checked_amount_of(),
         status in arb_transaction_status(),
         transaction_hash in arb_hash(),
+        l1_fee in proptest::option::of(arb_checked_amount_of()),
     ) -> TransactionReceipt {
         TransactionReceipt {
             block_hash,
@@ -485,6 +780,45 @@ prop_compose! {
             gas_used,
             status,
             transaction_hash,
+            l1_fee,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_reimbursement_request()(
+        withdrawal_id in any::<u64>(),
+        reimbursed_amount in arb_checked_amount_of(),
+        to in arb_principal(),
+        to_subaccount in proptest::option::of(uniform32(any::<u8>())),
+        transaction_hash in proptest::option::of(arb_hash()),
+    ) -> ReimbursementRequest {
+        ReimbursementRequest {
+            withdrawal_id: withdrawal_id.into(),
+            reimbursed_amount,
+            to,
+            to_subaccount: to_subaccount.map(Subaccount),
+            transaction_hash,
+        }
+    }
+}
+
+prop_compose! {
+    fn arb_erc20_reimbursed()(
+        withdrawal_id in any::<u64>(),
+        burn_in_block in any::<u64>(),
+        reimbursed_in_block in any::<u64>(),
+        ledger_id in arb_principal(),
+        reimbursed_amount in arb_checked_amount_of(),
+        transaction_hash in proptest::option::of(arb_hash()),
+    ) -> Erc20Reimbursed {
+        Erc20Reimbursed {
+            withdrawal_id: withdrawal_id.into(),
+            burn_in_block: burn_in_block.into(),
+            reimbursed_in_block: reimbursed_in_block.into(),
+            ledger_id,
+            reimbursed_amount,
+            transaction_hash,
         }
     }
 }
@@ -529,6 +863,30 @@ fn arb_event_type() -> impl Strategy<Value = EventType> {
                 transaction_receipt,
             }
         }),
+        arb_ckerc20_token().prop_map(EventType::AddedCkErc20Token),
+        arb_received_erc20_event().prop_map(EventType::AcceptedErc20Deposit),
+        (
+            arb_event_source(),
+            any::<u64>(),
+            "ck[A-Z]{3,5}",
+            arb_address()
+        )
+            .prop_map(
+                |(event_source, index, ckerc20_token_symbol, erc20_contract_address)| {
+                    EventType::MintedCkErc20 {
+                        event_source,
+                        mint_block_index: index.into(),
+                        ckerc20_token_symbol,
+                        erc20_contract_address,
+                    }
+                }
+            ),
+        arb_checked_amount_of()
+            .prop_map(|block_number| EventType::SyncedErc20ToBlock { block_number }),
+        arb_checked_amount_of().prop_map(EventType::SkippedErc20Block),
+        arb_erc20_withdrawal_request().prop_map(EventType::AcceptedErc20WithdrawalRequest),
+        arb_reimbursement_request().prop_map(EventType::FailedErc20WithdrawalRequest),
+        arb_erc20_reimbursed().prop_map(EventType::ReimbursedErc20Withdrawal),
     ]
 }
 
@@ -673,6 +1031,7 @@ fn state_equivalence() {
                     "0x06afc3c693dc2ba2c19b5c287c4dddce040d766bea5fd13c8a7268b04aa94f2d"
                         .parse()
                         .unwrap(),
+                l1_fee: None,
             })
             .expect("valid receipt"),
         ),
@@ -708,6 +1067,10 @@ fn state_equivalence() {
                 withdrawal_id: LedgerBurnIndex::new(6),
             },
         },
+        erc20_withdrawal_requests: Default::default(),
+        erc20_maybe_reimburse: Default::default(),
+        erc20_reimbursement_requests: Default::default(),
+        erc20_reimbursed: Default::default(),
     };
     let state = State {
         ethereum_network: EthereumNetwork::Mainnet,
@@ -724,6 +1087,7 @@ fn state_equivalence() {
         }),
         minimum_withdrawal_amount: Wei::new(1_000_000_000_000_000),
         ethereum_block_height: BlockTag::Finalized,
+        ethereum_block_confirmations: BlockNumber::ZERO,
         first_scraped_block_number: BlockNumber::new(1_000_001),
         last_scraped_block_number: BlockNumber::new(1_000_000),
         last_observed_block_number: Some(BlockNumber::new(2_000_000)),
@@ -759,6 +1123,18 @@ fn state_equivalence() {
         http_request_counter: 100,
         eth_balance: Default::default(),
         skipped_blocks: Default::default(),
+        ledger_suite_orchestrator_id: Some("vxkom-oyaaa-aaaar-qafda-cai".parse().unwrap()),
+        erc20_helper_contract_address: Some(
+            "0xE1788E4834c896F1932188645cc36c54d1b80AC1"
+                .parse()
+                .unwrap(),
+        ),
+        last_erc20_scraped_block_number: BlockNumber::new(1_000_000),
+        ckerc20_tokens: Default::default(),
+        erc20_events_to_mint: Default::default(),
+        minted_erc20_events: Default::default(),
+        erc20_skipped_blocks: Default::default(),
+        eth_rpc_config: Default::default(),
     };
 
     assert_eq!(
@@ -826,6 +1202,15 @@ fn state_equivalence() {
         "changing essential fields should break equivalence",
     );
 
+    assert_ne!(
+        Ok(()),
+        state.is_equivalent_to(&State {
+            ethereum_block_confirmations: BlockNumber::new(100),
+            ..state.clone()
+        }),
+        "changing essential fields should break equivalence",
+    );
+
     assert_ne!(
         Ok(()),
         state.is_equivalent_to(&State {
@@ -1060,7 +1445,8 @@ mod eth_balance {
                 total_unspent_tx_fees: balance_before_withdrawal
                     .total_unspent_tx_fees
                     .checked_add(Wei::from(65_945_724_957_000_u64))
-                    .unwrap()
+                    .unwrap(),
+                total_uncovered_tx_fees: Wei::ZERO,
             }
         );
 
@@ -1089,6 +1475,78 @@ mod eth_balance {
         );
     }
 
+    #[test]
+    fn should_include_l1_fee_in_effective_transaction_fee() {
+        let mut state = a_state();
+        apply_state_transition(
+            &mut state,
+            &EventType::AcceptedDeposit(received_eth_event()),
+        );
+        let balance_before_withdrawal = state.eth_balance.clone();
+        let withdrawal_flow = WithdrawalFlow {
+            withdrawal_amount: Wei::new(10_000_000_000_000_000),
+            tx_fee: TransactionPrice {
+                gas_limit: GasAmount::from(30_000_u32),
+                max_fee_per_gas: WeiPerGas::from(2_000_000_u64),
+                max_priority_fee_per_gas: WeiPerGas::from(1_000_000_u64),
+            },
+            effective_gas_price: WeiPerGas::from(1_000_000_u64),
+            l1_fee: Some(Wei::from(15_000_000_000_u64)),
+            ..Default::default()
+        };
+
+        let receipt = withdrawal_flow.apply(&mut state);
+
+        assert_eq!(
+            receipt.effective_transaction_fee(),
+            Wei::from(45_000_000_000_u64)
+        );
+        assert_eq!(
+            state.eth_balance,
+            EthBalance {
+                eth_balance: balance_before_withdrawal
+                    .eth_balance
+                    .checked_sub(Wei::from(9_999_985_000_000_000_u64))
+                    .unwrap(),
+                total_effective_tx_fees: Wei::from(45_000_000_000_u64),
+                total_unspent_tx_fees: Wei::from(15_000_000_000_u64),
+                total_uncovered_tx_fees: Wei::ZERO,
+            }
+        );
+    }
+
+    #[test]
+    fn should_not_panic_when_l1_fee_exceeds_charged_fee() {
+        let mut state = a_state();
+        apply_state_transition(
+            &mut state,
+            &EventType::AcceptedDeposit(received_eth_event()),
+        );
+        let withdrawal_flow = WithdrawalFlow {
+            withdrawal_amount: Wei::new(1_000_000_000_000_000),
+            tx_fee: TransactionPrice {
+                gas_limit: GasAmount::from(21_000_u32),
+                max_fee_per_gas: WeiPerGas::from(2_000_000_u64),
+                max_priority_fee_per_gas: WeiPerGas::from(1_000_000_u64),
+            },
+            effective_gas_price: WeiPerGas::from(2_000_000_u64),
+            l1_fee: Some(Wei::from(1_000_000_000_u64)),
+            ..Default::default()
+        };
+
+        withdrawal_flow.apply(&mut state);
+
+        assert_eq!(
+            state.eth_balance.total_effective_tx_fees(),
+            Wei::from(43_000_000_000_u64)
+        );
+        assert_eq!(state.eth_balance.total_unspent_tx_fees(), Wei::ZERO);
+        assert_eq!(
+            state.eth_balance.total_uncovered_tx_fees(),
+            Wei::from(1_000_000_000_u64)
+        );
+    }
+
     #[derive(Clone)]
     struct WithdrawalFlow {
         ledger_burn_index: LedgerBurnIndex,
@@ -1097,6 +1555,7 @@ mod eth_balance {
         tx_fee: TransactionPrice,
         effective_gas_price: WeiPerGas,
         tx_status: TransactionStatus,
+        l1_fee: Option<Wei>,
     }
 
     impl Default for WithdrawalFlow {
@@ -1112,6 +1571,7 @@ mod eth_balance {
                 },
                 effective_gas_price: WeiPerGas::ONE,
                 tx_status: TransactionStatus::Success,
+                l1_fee: None,
             }
         }
     }
@@ -1182,6 +1642,7 @@ mod eth_balance {
                 gas_used: signed_tx.transaction().gas_limit,
                 status: self.tx_status,
                 transaction_hash: signed_tx.hash(),
+                l1_fee: self.l1_fee,
             };
             apply_state_transition(
                 state,
@@ -2,12 +2,15 @@
 mod tests;
 
 use crate::endpoints::{EthTransaction, RetrieveEthStatus, TxFinalizedStatus};
+use crate::erc20::encode_erc20_transfer_data;
 use crate::eth_rpc::Hash;
 use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::eth_rpc_client::responses::TransactionStatus;
 use crate::lifecycle::EthereumNetwork;
 use crate::map::MultiKeyMap;
-use crate::numeric::{LedgerBurnIndex, LedgerMintIndex, TransactionCount, TransactionNonce, Wei};
+use crate::numeric::{
+    Erc20Value, LedgerBurnIndex, LedgerMintIndex, TransactionCount, TransactionNonce, Wei,
+};
 use crate::tx::{
     Eip1559TransactionRequest, FinalizedEip1559Transaction, SignedEip1559TransactionRequest,
     TransactionPrice,
@@ -42,6 +45,46 @@ pub struct EthWithdrawalRequest {
     pub created_at: Option<u64>,
 }
 
+/// ERC-20 withdrawal request issued by the user.
+///
+/// The transaction fees are paid in ckETH, which is burned by the minter
+/// before the ckERC20 tokens are burned. Both burn operations must succeed
+/// for the request to be accepted.
+#[derive(Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Erc20WithdrawalRequest {
+    /// Amount of burned ckETH that can be used to pay for the Ethereum transaction fees.
+    #[n(0)]
+    pub max_transaction_fee: Wei,
+    /// The ERC-20 amount that the receiver will get.
+    #[n(1)]
+    pub withdrawal_amount: Erc20Value,
+    /// The recipient's address of the sent ERC-20 tokens.
+    #[n(2)]
+    pub destination: Address,
+    /// The transaction ID of the ckETH burn operation on the ckETH ledger.
+    /// Also used as the withdrawal ID.
+    #[cbor(n(3), with = "crate::cbor::id")]
+    pub cketh_ledger_burn_index: LedgerBurnIndex,
+    /// Address of the ERC-20 contract.
+    #[n(4)]
+    pub erc20_contract_address: Address,
+    /// The ckERC20 ledger on which the minter burned the ckERC20 tokens.
+    #[cbor(n(5), with = "crate::cbor::principal")]
+    pub ckerc20_ledger_id: Principal,
+    /// The transaction ID of the ckERC20 burn operation on the ckERC20 ledger.
+    #[cbor(n(6), with = "crate::cbor::id")]
+    pub ckerc20_ledger_burn_index: LedgerBurnIndex,
+    /// The owner of the account from which the minter burned ckETH and ckERC20.
+    #[cbor(n(7), with = "crate::cbor::principal")]
+    pub from: Principal,
+    /// The subaccount from which the minter burned ckETH and ckERC20.
+    #[n(8)]
+    pub from_subaccount: Option<Subaccount>,
+    /// The IC time at which the withdrawal request arrived.
+    #[n(9)]
+    pub created_at: u64,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
 pub struct ReimbursementRequest {
     #[cbor(n(0), with = "crate::cbor::id")]
@@ -71,6 +114,39 @@ pub struct Reimbursed {
     pub transaction_hash: Option<Hash>,
 }
 
+/// Reimbursement of the ckERC20 tokens burned for a failed ERC-20 withdrawal.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Erc20ReimbursementRequest {
+    pub withdrawal_id: LedgerBurnIndex,
+    pub ledger_id: Principal,
+    pub burn_in_block: LedgerBurnIndex,
+    pub reimbursed_amount: Erc20Value,
+    pub to: Principal,
+    pub to_subaccount: Option<Subaccount>,
+    /// Transaction hash of the failed ERC-20 transaction.
+    pub transaction_hash: Hash,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+pub struct Erc20Reimbursed {
+    /// The withdrawal ID, i.e., the burn index on the ckETH ledger.
+    #[cbor(n(0), with = "crate::cbor::id")]
+    pub withdrawal_id: LedgerBurnIndex,
+    /// The burn index on the ckERC20 ledger.
+    #[cbor(n(1), with = "crate::cbor::id")]
+    pub burn_in_block: LedgerBurnIndex,
+    /// The mint index on the ckERC20 ledger.
+    #[cbor(n(2), with = "crate::cbor::id")]
+    pub reimbursed_in_block: LedgerMintIndex,
+    /// The ckERC20 ledger on which the minter reimbursed the user.
+    #[cbor(n(3), with = "crate::cbor::principal")]
+    pub ledger_id: Principal,
+    #[n(4)]
+    pub reimbursed_amount: Erc20Value,
+    #[n(5)]
+    pub transaction_hash: Option<Hash>,
+}
+
 #[derive(Clone, Eq, PartialEq, Encode, Decode)]
 #[cbor(transparent)]
 pub struct Subaccount(#[cbor(n(0), with = "minicbor::bytes")] pub [u8; 32]);
@@ -101,6 +177,25 @@ impl fmt::Debug for EthWithdrawalRequest {
     }
 }
 
+impl fmt::Debug for Erc20WithdrawalRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("Erc20WithdrawalRequest")
+            .field("max_transaction_fee", &self.max_transaction_fee)
+            .field("withdrawal_amount", &self.withdrawal_amount)
+            .field("destination", &self.destination)
+            .field("cketh_ledger_burn_index", &self.cketh_ledger_burn_index)
+            .field("erc20_contract_address", &self.erc20_contract_address)
+            .field(
+                "ckerc20_ledger_id",
+                &DebugPrincipal(&self.ckerc20_ledger_id),
+            )
+            .field("ckerc20_ledger_burn_index", &self.ckerc20_ledger_burn_index)
+            .field("from", &DebugPrincipal(&self.from))
+            .field("from_subaccount", &self.from_subaccount)
+            .finish()
+    }
+}
+
 /// State machine holding Ethereum transactions issued by the minter.
 /// Overall the transaction lifecycle is as follows:
 /// 1. The user's withdrawal request is enqueued and processed in a FIFO order.
@@ -115,6 +210,11 @@ impl fmt::Debug for EthWithdrawalRequest {
 ///    The others sent transactions for that nonce were never mined and can be discarded.
 /// 6. If a given transaction fails the minter will reimburse the user who requested the
 ///    withdrawal with the corresponding amount minus fees.
+///
+/// ERC-20 withdrawal requests follow the same lifecycle and share the same transaction nonces.
+/// They are identified by the burn index on the ckETH ledger (where the transaction fees were paid).
+/// If an ERC-20 transaction fails, the minter reimburses the burned ckERC20 tokens
+/// but not the ckETH that was consumed to pay for the transaction fees.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct EthTransactions {
     pub(in crate::state) withdrawal_requests: VecDeque<EthWithdrawalRequest>,
@@ -129,6 +229,12 @@ pub struct EthTransactions {
     pub(in crate::state) maybe_reimburse: BTreeMap<LedgerBurnIndex, EthWithdrawalRequest>,
     pub(in crate::state) reimbursement_requests: BTreeMap<LedgerBurnIndex, ReimbursementRequest>,
     pub(in crate::state) reimbursed: BTreeMap<LedgerBurnIndex, Reimbursed>,
+
+    pub(in crate::state) erc20_withdrawal_requests: VecDeque<Erc20WithdrawalRequest>,
+    pub(in crate::state) erc20_maybe_reimburse: BTreeMap<LedgerBurnIndex, Erc20WithdrawalRequest>,
+    pub(in crate::state) erc20_reimbursement_requests:
+        BTreeMap<LedgerBurnIndex, Erc20ReimbursementRequest>,
+    pub(in crate::state) erc20_reimbursed: BTreeMap<LedgerBurnIndex, Erc20Reimbursed>,
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -138,6 +244,11 @@ pub enum CreateTransactionError {
         withdrawal_amount: Wei,
         max_transaction_fee: Wei,
     },
+    InsufficientTransactionFee {
+        cketh_ledger_burn_index: LedgerBurnIndex,
+        allowed_max_transaction_fee: Wei,
+        actual_max_transaction_fee: Wei,
+    },
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -148,6 +259,12 @@ pub enum ResubmitTransactionError {
         transaction_amount: Wei,
         max_transaction_fee: Wei,
     },
+    InsufficientTransactionFee {
+        ledger_burn_index: LedgerBurnIndex,
+        transaction_nonce: TransactionNonce,
+        allowed_max_transaction_fee: Wei,
+        max_transaction_fee: Wei,
+    },
 }
 
 impl EthTransactions {
@@ -161,6 +278,10 @@ impl EthTransactions {
             maybe_reimburse: Default::default(),
             reimbursement_requests: Default::default(),
             reimbursed: Default::default(),
+            erc20_withdrawal_requests: VecDeque::new(),
+            erc20_maybe_reimburse: Default::default(),
+            erc20_reimbursement_requests: Default::default(),
+            erc20_reimbursed: Default::default(),
         }
     }
 
@@ -180,19 +301,57 @@ impl EthTransactions {
         self.reimbursed.values().cloned().collect()
     }
 
+    pub fn get_erc20_reimbursement_requests(&self) -> Vec<Erc20ReimbursementRequest> {
+        self.erc20_reimbursement_requests
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub fn get_erc20_reimbursed_transactions(&self) -> Vec<Erc20Reimbursed> {
+        self.erc20_reimbursed.values().cloned().collect()
+    }
+
     pub fn record_withdrawal_request(&mut self, request: EthWithdrawalRequest) {
-        let burn_index = request.ledger_burn_index;
+        self.assert_unknown_burn_index(&request.ledger_burn_index);
+        self.withdrawal_requests.push_back(request);
+    }
+
+    pub fn record_erc20_withdrawal_request(&mut self, request: Erc20WithdrawalRequest) {
+        self.assert_unknown_burn_index(&request.cketh_ledger_burn_index);
+        self.erc20_withdrawal_requests.push_back(request);
+    }
+
+    /// Record a failed ERC-20 withdrawal request, where the ckETH for the transaction fees
+    /// was burned but the ckERC20 tokens could not be burned.
+    /// The burned ckETH must be reimbursed.
+    pub fn record_failed_erc20_withdrawal_request(&mut self, request: ReimbursementRequest) {
+        let withdrawal_id = request.withdrawal_id;
+        self.assert_unknown_burn_index(&withdrawal_id);
+        assert_eq!(
+            self.reimbursement_requests.insert(withdrawal_id, request),
+            None,
+            "BUG: duplicate reimbursement request for withdrawal ID {withdrawal_id}"
+        );
+    }
+
+    fn assert_unknown_burn_index(&self, burn_index: &LedgerBurnIndex) {
         if self
             .withdrawal_requests
             .iter()
-            .any(|r| r.ledger_burn_index == burn_index)
-            || self.created_tx.contains_alt(&burn_index)
-            || self.sent_tx.contains_alt(&burn_index)
-            || self.finalized_tx.contains_alt(&burn_index)
+            .any(|r| &r.ledger_burn_index == burn_index)
+            || self
+                .erc20_withdrawal_requests
+                .iter()
+                .any(|r| &r.cketh_ledger_burn_index == burn_index)
+            || self.created_tx.contains_alt(burn_index)
+            || self.sent_tx.contains_alt(burn_index)
+            || self.finalized_tx.contains_alt(burn_index)
+            || self.reimbursement_requests.contains_key(burn_index)
+            || self.reimbursed.contains_key(burn_index)
         {
             panic!("BUG: duplicate ledger burn index {burn_index}");
         }
-        self.withdrawal_requests.push_back(request);
     }
 
     /// Move an existing withdrawal request to the back of the queue.
@@ -210,11 +369,34 @@ impl EthTransactions {
         self.record_withdrawal_request(request);
     }
 
+    /// Move an existing ERC-20 withdrawal request to the back of the queue.
+    pub fn reschedule_erc20_withdrawal_request(&mut self, request: Erc20WithdrawalRequest) {
+        assert_eq!(
+            self.erc20_withdrawal_requests
+                .iter()
+                .filter(|r| r.cketh_ledger_burn_index == request.cketh_ledger_burn_index)
+                .count(),
+            1,
+            "BUG: expected exactly one ERC-20 withdrawal request with ckETH ledger burn index {}",
+            request.cketh_ledger_burn_index
+        );
+        self.erc20_withdrawal_requests.retain(|r| r != &request);
+        self.record_erc20_withdrawal_request(request);
+    }
+
     pub fn record_created_transaction(
         &mut self,
         withdrawal_id: LedgerBurnIndex,
         transaction: Eip1559TransactionRequest,
     ) {
+        if let Some(erc20_withdrawal_request) = self
+            .erc20_withdrawal_requests
+            .iter()
+            .find(|req| req.cketh_ledger_burn_index == withdrawal_id)
+            .cloned()
+        {
+            return self.record_created_erc20_transaction(erc20_withdrawal_request, transaction);
+        }
         let withdrawal_request = self
             .withdrawal_requests
             .iter()
@@ -249,6 +431,51 @@ impl EthTransactions {
             .insert(withdrawal_id, withdrawal_request);
     }
 
+    fn record_created_erc20_transaction(
+        &mut self,
+        withdrawal_request: Erc20WithdrawalRequest,
+        transaction: Eip1559TransactionRequest,
+    ) {
+        let withdrawal_id = withdrawal_request.cketh_ledger_burn_index;
+        assert_eq!(
+            withdrawal_request.erc20_contract_address, transaction.destination,
+            "BUG: ERC-20 transaction must be sent to the ERC-20 contract"
+        );
+        assert_eq!(
+            transaction.amount,
+            Wei::ZERO,
+            "BUG: ERC-20 transaction must not transfer any ETH"
+        );
+        assert_eq!(
+            transaction.data,
+            encode_erc20_transfer_data(
+                &withdrawal_request.destination,
+                withdrawal_request.withdrawal_amount
+            ),
+            "BUG: ERC-20 transaction data does not match withdrawal request"
+        );
+        assert!(
+            transaction.transaction_price().max_transaction_fee()
+                <= withdrawal_request.max_transaction_fee,
+            "BUG: ERC-20 transaction fee exceeds the burned ckETH amount"
+        );
+        let nonce = self.next_nonce;
+        assert_eq!(transaction.nonce, nonce, "BUG: transaction nonce mismatch");
+        self.next_nonce = self
+            .next_nonce
+            .checked_increment()
+            .expect("Transaction nonce overflow");
+        self.erc20_withdrawal_requests
+            .retain(|r| r != &withdrawal_request);
+        assert_eq!(
+            self.created_tx
+                .try_insert(nonce, withdrawal_id, transaction),
+            Ok(())
+        );
+        self.erc20_maybe_reimburse
+            .insert(withdrawal_id, withdrawal_request);
+    }
+
     pub fn record_signed_transaction(
         &mut self,
         signed_transaction: SignedEip1559TransactionRequest,
@@ -308,6 +535,29 @@ impl EthTransactions {
                 let new_tx_price = last_tx_price
                     .increase_by_10_percent()
                     .max(current_transaction_price.clone());
+                if let Some(erc20_request) = self.erc20_maybe_reimburse.get(burn_index) {
+                    // The fees of ERC-20 transactions are paid with the burned ckETH
+                    // and the transaction amount (in ETH) is always zero.
+                    if new_tx_price.max_transaction_fee() > erc20_request.max_transaction_fee {
+                        transactions_to_resubmit.push(Err(
+                            ResubmitTransactionError::InsufficientTransactionFee {
+                                ledger_burn_index: *burn_index,
+                                transaction_nonce: *nonce,
+                                allowed_max_transaction_fee: erc20_request.max_transaction_fee,
+                                max_transaction_fee: new_tx_price.max_transaction_fee(),
+                            },
+                        ));
+                        return transactions_to_resubmit;
+                    }
+                    let new_tx = Eip1559TransactionRequest {
+                        max_priority_fee_per_gas: new_tx_price.max_priority_fee_per_gas,
+                        max_fee_per_gas: new_tx_price.max_fee_per_gas,
+                        gas_limit: new_tx_price.gas_limit,
+                        ..last_tx
+                    };
+                    transactions_to_resubmit.push(Ok((*burn_index, new_tx)));
+                    continue;
+                }
                 let new_amount = match last_tx.amount.checked_sub(
                     new_tx_price
                         .max_transaction_fee()
@@ -408,6 +658,23 @@ impl EthTransactions {
             Ok(())
         );
 
+        if let Some(erc20_request) = self.erc20_maybe_reimburse.remove(&ledger_burn_index) {
+            if receipt.status == TransactionStatus::Failure {
+                self.erc20_reimbursement_requests.insert(
+                    ledger_burn_index,
+                    Erc20ReimbursementRequest {
+                        withdrawal_id: ledger_burn_index,
+                        ledger_id: erc20_request.ckerc20_ledger_id,
+                        burn_in_block: erc20_request.ckerc20_ledger_burn_index,
+                        reimbursed_amount: erc20_request.withdrawal_amount,
+                        to: erc20_request.from,
+                        to_subaccount: erc20_request.from_subaccount,
+                        transaction_hash: receipt.transaction_hash,
+                    },
+                );
+            }
+            return;
+        }
         let maybe_reimburse = self.maybe_reimburse.remove(&ledger_burn_index).expect(
             "failed to remove entry from maybe_reimburse map with block index: {ledger_burn_index}",
         );
@@ -448,11 +715,42 @@ impl EthTransactions {
         );
     }
 
+    pub fn record_finalized_erc20_reimbursement(&mut self, reimbursed: Erc20Reimbursed) {
+        let withdrawal_id = reimbursed.withdrawal_id;
+        let reimbursement_request = self
+            .erc20_reimbursement_requests
+            .remove(&withdrawal_id)
+            .expect("failed to remove ERC-20 reimbursement request");
+        assert_eq!(
+            (
+                reimbursement_request.ledger_id,
+                reimbursement_request.burn_in_block,
+                reimbursement_request.reimbursed_amount
+            ),
+            (
+                reimbursed.ledger_id,
+                reimbursed.burn_in_block,
+                reimbursed.reimbursed_amount
+            ),
+            "BUG: ERC-20 reimbursement does not match the reimbursement request"
+        );
+        assert_eq!(
+            self.erc20_reimbursed.insert(withdrawal_id, reimbursed),
+            None
+        );
+    }
+
+    /// Returns the status of the withdrawal request identified by the given burn index on the ckETH ledger.
+    /// For ERC-20 withdrawals, the ckERC20 amount is reimbursed in case of a failed transaction.
     pub fn transaction_status(&self, burn_index: &LedgerBurnIndex) -> RetrieveEthStatus {
         if self
             .withdrawal_requests
             .iter()
             .any(|r| &r.ledger_burn_index == burn_index)
+            || self
+                .erc20_withdrawal_requests
+                .iter()
+                .any(|r| &r.cketh_ledger_burn_index == burn_index)
         {
             return RetrieveEthStatus::Pending;
         }
@@ -473,6 +771,13 @@ impl EthTransactions {
                     reimbursed_amount: reimbursed.reimbursed_amount.into(),
                 });
             }
+            if let Some(reimbursed) = self.erc20_reimbursed.get(burn_index) {
+                return RetrieveEthStatus::TxFinalized(TxFinalizedStatus::Reimbursed {
+                    reimbursed_in_block: reimbursed.reimbursed_in_block.get().into(),
+                    transaction_hash: tx.transaction_hash().to_string(),
+                    reimbursed_amount: reimbursed.reimbursed_amount.into(),
+                });
+            }
             if tx.transaction_status() == &TransactionStatus::Failure {
                 return RetrieveEthStatus::TxFinalized(TxFinalizedStatus::PendingReimbursement(
                     EthTransaction {
@@ -493,6 +798,24 @@ impl EthTransactions {
         &self,
         requested_batch_size: usize,
     ) -> Vec<EthWithdrawalRequest> {
+        self.withdrawal_requests_iter()
+            .take(self.available_batch_size(requested_batch_size))
+            .cloned()
+            .collect()
+    }
+
+    pub fn erc20_withdrawal_requests_batch(
+        &self,
+        requested_batch_size: usize,
+    ) -> Vec<Erc20WithdrawalRequest> {
+        self.erc20_withdrawal_requests
+            .iter()
+            .take(self.available_batch_size(requested_batch_size))
+            .cloned()
+            .collect()
+    }
+
+    fn available_batch_size(&self, requested_batch_size: usize) -> usize {
         // The number of pending transaction nonces is counted and not the number of pending transactions
         // because a nonce may be associated with several distinct transactions (due to re-submission and dynamic fees).
         // However, once a nonce is chosen for a withdrawal request, it's in our interest that the corresponding transaction be finalized asap.
@@ -500,15 +823,11 @@ impl EthTransactions {
         const MAX_NUM_PENDING_TRANSACTION_NONCES: usize = 1000;
         let unique_pending_transaction_nonces: BTreeSet<_> =
             self.created_tx.keys().chain(self.sent_tx.keys()).collect();
-        let actual_batch_size = min(
+        min(
             MAX_NUM_PENDING_TRANSACTION_NONCES
                 .saturating_sub(unique_pending_transaction_nonces.len()),
             requested_batch_size,
-        );
-        self.withdrawal_requests_iter()
-            .take(actual_batch_size)
-            .cloned()
-            .collect()
+        )
     }
 
     pub fn withdrawal_requests_iter(&self) -> impl Iterator<Item = &EthWithdrawalRequest> {
@@ -519,6 +838,10 @@ impl EthTransactions {
         self.withdrawal_requests.len()
     }
 
+    pub fn erc20_withdrawal_requests_iter(&self) -> impl Iterator<Item = &Erc20WithdrawalRequest> {
+        self.erc20_withdrawal_requests.iter()
+    }
+
     pub fn transactions_to_sign_iter(
         &self,
     ) -> impl Iterator<
@@ -590,6 +913,7 @@ impl EthTransactions {
 
     pub fn has_pending_requests(&self) -> bool {
         !self.withdrawal_requests.is_empty()
+            || !self.erc20_withdrawal_requests.is_empty()
             || !self.created_tx.is_empty()
             || !self.sent_tx.is_empty()
     }
@@ -650,6 +974,25 @@ impl EthTransactions {
         ensure_eq!(self.reimbursement_requests, other.reimbursement_requests);
         ensure_eq!(self.reimbursed, other.reimbursed);
 
+        fn sorted_erc20_requests(
+            requests: &VecDeque<Erc20WithdrawalRequest>,
+        ) -> Vec<Erc20WithdrawalRequest> {
+            let mut buf: Vec<_> = requests.iter().cloned().collect();
+            buf.sort_unstable_by_key(|req| req.cketh_ledger_burn_index);
+            buf
+        }
+
+        ensure_eq!(
+            sorted_erc20_requests(&self.erc20_withdrawal_requests),
+            sorted_erc20_requests(&other.erc20_withdrawal_requests)
+        );
+        ensure_eq!(self.erc20_maybe_reimburse, other.erc20_maybe_reimburse);
+        ensure_eq!(
+            self.erc20_reimbursement_requests,
+            other.erc20_reimbursement_requests
+        );
+        ensure_eq!(self.erc20_reimbursed, other.erc20_reimbursed);
+
         Ok(())
     }
 
@@ -658,6 +1001,12 @@ impl EthTransactions {
             .iter()
             .chain(self.maybe_reimburse.values())
             .flat_map(|req| req.created_at.into_iter())
+            .chain(
+                self.erc20_withdrawal_requests
+                    .iter()
+                    .chain(self.erc20_maybe_reimburse.values())
+                    .map(|req| req.created_at),
+            )
             .min()
     }
 }
@@ -701,6 +1050,43 @@ pub fn create_transaction(
     })
 }
 
+/// Creates an EIP-1559 transaction calling `transfer` on the ERC-20 contract
+/// for the given ERC-20 withdrawal request.
+/// The transaction fees were already paid by the user by burning ckETH
+/// and the transaction does not transfer any ETH.
+///
+/// # Errors
+/// * `CreateTransactionError::InsufficientTransactionFee` if the burned ckETH does not cover the transaction fee.
+pub fn create_erc20_transaction(
+    withdrawal_request: &Erc20WithdrawalRequest,
+    nonce: TransactionNonce,
+    transaction_price: TransactionPrice,
+    ethereum_network: EthereumNetwork,
+) -> Result<Eip1559TransactionRequest, CreateTransactionError> {
+    let actual_max_transaction_fee = transaction_price.max_transaction_fee();
+    if actual_max_transaction_fee > withdrawal_request.max_transaction_fee {
+        return Err(CreateTransactionError::InsufficientTransactionFee {
+            cketh_ledger_burn_index: withdrawal_request.cketh_ledger_burn_index,
+            allowed_max_transaction_fee: withdrawal_request.max_transaction_fee,
+            actual_max_transaction_fee,
+        });
+    }
+    Ok(Eip1559TransactionRequest {
+        chain_id: ethereum_network.chain_id(),
+        nonce,
+        max_priority_fee_per_gas: transaction_price.max_priority_fee_per_gas,
+        max_fee_per_gas: transaction_price.max_fee_per_gas,
+        gas_limit: transaction_price.gas_limit,
+        destination: withdrawal_request.erc20_contract_address,
+        amount: Wei::ZERO,
+        data: encode_erc20_transfer_data(
+            &withdrawal_request.destination,
+            withdrawal_request.withdrawal_amount,
+        ),
+        access_list: Default::default(),
+    })
+}
+
 /// Returns true if the two transactions are equal ignoring the transaction fee and amount.
 /// The following fields are ignored:
 /// * `max_fee_per_gas`
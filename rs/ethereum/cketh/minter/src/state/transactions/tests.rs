@@ -1993,6 +1993,7 @@ fn transaction_receipt(
         gas_used: signed_tx.transaction().gas_limit,
         status,
         transaction_hash: signed_tx.hash(),
+        l1_fee: None,
     }
 }
 
@@ -48,9 +48,9 @@ fn deserialize_json_reply() {
 }
 
 mod eth_get_logs {
-    use crate::eth_logs::ReceivedEthEvent;
+    use crate::eth_logs::{ReceivedErc20Event, ReceivedEthEvent};
     use crate::eth_rpc::LogEntry;
-    use crate::numeric::{BlockNumber, LogIndex, Wei};
+    use crate::numeric::{BlockNumber, Erc20Value, LogIndex, Wei};
     use candid::Principal;
     use ic_crypto_sha3::Keccak256;
     use ic_ethereum_types::Address;
@@ -141,6 +141,87 @@ mod eth_get_logs {
         assert_eq!(parsed_event, expected_event);
     }
 
+    #[test]
+    fn should_have_correct_erc20_topic() {
+        use crate::eth_logs::RECEIVED_ERC20_EVENT_TOPIC;
+
+        //must match event signature in ERC20DepositHelper.sol
+        let event_signature = "ReceivedErc20(address,address,uint256,bytes32)";
+        let topic = Keccak256::hash(event_signature);
+        assert_eq!(topic, RECEIVED_ERC20_EVENT_TOPIC)
+    }
+
+    #[test]
+    fn should_parse_received_erc20_event() {
+        let event = r#"{
+            "address": "0xe1788e4834c896f1932188645cc36c54d1b80ac1",
+            "topics": [
+                "0x4d69d0bd4287b7f66c548f90154dc81bc98f65a1b362775df5ae171a2ccd262b",
+                "0x0000000000000000000000001c7d4b196cb0c7b01d743fbc6116a902379c7238",
+                "0x000000000000000000000000dd2851cdd40ae6536831558dd46db62fac7a844d",
+                "0x09efcdab00000000000100000000000000000000000000000000000000000000"
+            ],
+            "data": "0x00000000000000000000000000000000000000000000000000000000000f4240",
+            "blockNumber": "0x5146a4",
+            "transactionHash": "0x44d8e93a8f4bbc89ad35fc4fbbdb12cb597b4832da09c0b2300777be180fde87",
+            "transactionIndex": "0x22",
+            "blockHash": "0x0cbfb260a6e7ef817c1b3d4c8d8d3a5e0a1d4ef1d6eb5a8e5ef3f1bbc5ee4f5d",
+            "logIndex": "0x27",
+            "removed": false
+        }"#;
+        let parsed_event =
+            ReceivedErc20Event::try_from(serde_json::from_str::<LogEntry>(event).unwrap()).unwrap();
+        let expected_event = ReceivedErc20Event {
+            transaction_hash: "0x44d8e93a8f4bbc89ad35fc4fbbdb12cb597b4832da09c0b2300777be180fde87"
+                .parse()
+                .unwrap(),
+            block_number: BlockNumber::new(5326500),
+            log_index: LogIndex::from(39_u8),
+            from_address: "0xdd2851cdd40ae6536831558dd46db62fac7a844d"
+                .parse()
+                .unwrap(),
+            value: Erc20Value::from(1_000_000_u64),
+            principal: Principal::from_str("2chl6-4hpzw-vqaaa-aaaaa-c").unwrap(),
+            erc20_contract_address: "0x1c7d4b196cb0c7b01d743fbc6116a902379c7238"
+                .parse()
+                .unwrap(),
+        };
+
+        assert_eq!(parsed_event, expected_event);
+    }
+
+    #[test]
+    fn should_not_parse_erc20_event_with_wrong_number_of_topics() {
+        use crate::eth_logs::{EventSourceError, ReceivedEthEventError};
+        use assert_matches::assert_matches;
+        let event = r#"{
+            "address": "0xb44b5e756a894775fc32eddf3314bb1b1944dc34",
+            "topics": [
+                "0x257e057bb61920d8d0ed2cb7b720ac7f9c513cd1110bc9fa543079154f45f435",
+                "0x000000000000000000000000dd2851cdd40ae6536831558dd46db62fac7a844d",
+                "0x09efcdab00000000000100000000000000000000000000000000000000000000"
+            ],
+            "data": "0x000000000000000000000000000000000000000000000000002386f26fc10000",
+            "blockNumber": "0x3ca487",
+            "transactionHash": "0x705f826861c802b407843e99af986cfde8749b669e5e0a5a150f4350bcaa9bc3",
+            "transactionIndex": "0x22",
+            "blockHash": "0x8436209a391f7bc076123616ecb229602124eb6c1007f5eae84df8e098885d3c",
+            "logIndex": "0x27",
+            "removed": false
+        }"#;
+
+        let parsed_event =
+            ReceivedErc20Event::try_from(serde_json::from_str::<LogEntry>(event).unwrap());
+
+        assert_matches!(
+            parsed_event,
+            Err(ReceivedEthEventError::InvalidEventSource {
+                error: EventSourceError::InvalidEvent(msg),
+                ..
+            }) if msg == "Expected exactly 4 topics, got 3"
+        );
+    }
+
     #[test]
     fn should_not_parse_removed_event() {
         use crate::eth_logs::{EventSource, EventSourceError, ReceivedEthEventError};
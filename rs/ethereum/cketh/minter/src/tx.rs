@@ -3,6 +3,7 @@ mod tests;
 
 use crate::eth_rpc::{FeeHistory, Hash};
 use crate::eth_rpc_client::responses::{TransactionReceipt, TransactionStatus};
+use crate::lifecycle::EthereumNetwork;
 use crate::numeric::{BlockNumber, GasAmount, TransactionNonce, Wei, WeiPerGas};
 use crate::state::{lazy_call_ecdsa_public_key, read_state};
 use ethnum::u256;
@@ -439,12 +440,20 @@ pub enum TransactionPriceEstimationError {
 }
 pub fn estimate_transaction_price(
     fee_history: &FeeHistory,
+    ethereum_network: EthereumNetwork,
 ) -> Result<TransactionPrice, TransactionPriceEstimationError> {
     // average value between the `minSuggestedMaxPriorityFeePerGas`
     // used by Metamask, see
     // https://github.com/MetaMask/core/blob/f5a4f52e17f407c6411e4ef9bd6685aab184b91d/packages/gas-fee-controller/src/fetchGasEstimatesViaEthFeeHistory/calculateGasFeeEstimatesForPriorityLevels.ts#L14
     const MIN_MAX_PRIORITY_FEE_PER_GAS: WeiPerGas = WeiPerGas::new(1_500_000_000); //1.5 gwei
+                                                                                   // L2 sequencers mostly order transactions on a first-come, first-served basis
+                                                                                   // and typical tips are several orders of magnitude lower than on Ethereum.
+    const L2_MIN_MAX_PRIORITY_FEE_PER_GAS: WeiPerGas = WeiPerGas::new(1_000_000); //0.001 gwei
     const TRANSACTION_GAS_LIMIT: GasAmount = GasAmount::new(21_000);
+    let min_max_priority_fee_per_gas = match ethereum_network.l1_data_fee_model() {
+        None => MIN_MAX_PRIORITY_FEE_PER_GAS,
+        Some(_) => L2_MIN_MAX_PRIORITY_FEE_PER_GAS,
+    };
     let base_fee_of_next_finalized_block = *fee_history.base_fee_per_gas.last().ok_or(
         TransactionPriceEstimationError::InvalidFeeHistory(
             "base_fee_per_gas should not be empty to be able to evaluate transaction price"
@@ -457,7 +466,7 @@ pub fn estimate_transaction_price(
             **median(&mut rewards).ok_or(TransactionPriceEstimationError::InvalidFeeHistory(
                 "should be non-empty with rewards of the last 5 blocks".to_string(),
             ))?;
-        historic_max_priority_fee_per_gas.max(MIN_MAX_PRIORITY_FEE_PER_GAS)
+        historic_max_priority_fee_per_gas.max(min_max_priority_fee_per_gas)
     };
     let max_fee_per_gas = base_fee_of_next_finalized_block
         .checked_mul(2_u8)
@@ -3,6 +3,7 @@ use proptest::strategy::Strategy;
 
 mod estimate_transaction_price {
     use crate::eth_rpc::FeeHistory;
+    use crate::lifecycle::EthereumNetwork;
     use crate::numeric::{BlockNumber, GasAmount, WeiPerGas};
     use crate::tx::{
         estimate_transaction_price, TransactionPrice, TransactionPriceEstimationError,
@@ -30,7 +31,7 @@ mod estimate_transaction_price {
             let expected_max_fee_per_gas =
                 2_u128 * (expected_base_fee_per_gas as u128) + (expected_max_priority_fee_per_gas as u128);
 
-            let result = estimate_transaction_price(&fee_history);
+            let result = estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet);
 
             prop_assert_eq!(
                 result,
@@ -43,6 +44,35 @@ mod estimate_transaction_price {
         }
     }
 
+    #[test]
+    fn should_use_lower_minimum_priority_fee_on_l2() {
+        let fee_history = fee_history(
+            vec![10_000_000_u64; 6],
+            vec![0_u64, 500_000, 2_000_000, 0, 0],
+        );
+
+        for network in [
+            EthereumNetwork::Arbitrum,
+            EthereumNetwork::Base,
+            EthereumNetwork::Optimism,
+        ] {
+            assert_eq!(
+                estimate_transaction_price(&fee_history, network),
+                Ok(TransactionPrice {
+                    gas_limit: GasAmount::from(21_000_u64),
+                    max_fee_per_gas: WeiPerGas::from(21_000_000_u64),
+                    max_priority_fee_per_gas: WeiPerGas::from(1_000_000_u64),
+                })
+            );
+        }
+        assert_eq!(
+            estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet)
+                .unwrap()
+                .max_priority_fee_per_gas,
+            WeiPerGas::from(1_500_000_000_u64)
+        );
+    }
+
     #[test]
     fn should_fail_when_base_fee_per_gas_overflows() {
         let fee_history = fee_history(
@@ -57,7 +87,7 @@ mod estimate_transaction_price {
             vec![0_u8, 0, 0, 0, 0],
         );
 
-        let result = estimate_transaction_price(&fee_history);
+        let result = estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet);
 
         assert_matches!(result, Err(TransactionPriceEstimationError::Overflow(_)));
     }
@@ -65,7 +95,7 @@ mod estimate_transaction_price {
     #[test]
     fn should_fail_when_max_priority_fee_per_gas_overflows() {
         let fee_history = fee_history(vec![0_u8, 0, 0, 0, 0, 1], [WeiPerGas::MAX; 5].to_vec());
-        let result = estimate_transaction_price(&fee_history);
+        let result = estimate_transaction_price(&fee_history, EthereumNetwork::Mainnet);
         assert_matches!(result, Err(TransactionPriceEstimationError::Overflow(_)));
     }
 
@@ -1,3 +1,4 @@
+use crate::erc20::CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT;
 use crate::eth_rpc::JsonRpcResult;
 use crate::eth_rpc::{
     BlockSpec, BlockTag, FeeHistory, FeeHistoryParams, Quantity, SendRawTransactionResult,
@@ -7,11 +8,14 @@ use crate::eth_rpc_client::responses::TransactionReceipt;
 use crate::eth_rpc_client::EthRpcClient;
 use crate::eth_rpc_client::MultiCallError;
 use crate::guard::TimerGuard;
+use crate::l1_data_fee::{add_l1_data_fee, WithdrawalTransactionKind};
 use crate::logs::{DEBUG, INFO};
+use crate::memo::MintMemo;
 use crate::numeric::{LedgerBurnIndex, LedgerMintIndex, TransactionCount};
 use crate::state::audit::{process_event, EventType};
 use crate::state::transactions::{
-    create_transaction, CreateTransactionError, Reimbursed, ReimbursementRequest,
+    create_erc20_transaction, create_transaction, CreateTransactionError, Erc20Reimbursed,
+    Erc20ReimbursementRequest, Reimbursed, ReimbursementRequest,
 };
 use crate::state::{mutate_state, read_state, State, TaskType};
 use crate::tx::{estimate_transaction_price, TransactionPrice};
@@ -37,6 +41,11 @@ pub async fn process_reimbursement() {
         }
     };
 
+    reimburse_cketh().await;
+    reimburse_ckerc20().await;
+}
+
+async fn reimburse_cketh() {
     let reimbursement_requests: Vec<ReimbursementRequest> =
         read_state(|s| s.eth_transactions.get_reimbursement_requests());
     if reimbursement_requests.is_empty() {
@@ -104,6 +113,85 @@ pub async fn process_reimbursement() {
     }
 }
 
+async fn reimburse_ckerc20() {
+    let reimbursement_requests: Vec<Erc20ReimbursementRequest> =
+        read_state(|s| s.eth_transactions.get_erc20_reimbursement_requests());
+    if reimbursement_requests.is_empty() {
+        return;
+    }
+
+    let mut error_count = 0;
+
+    for reimbursement_request in reimbursement_requests {
+        let ledger_canister_id = reimbursement_request.ledger_id;
+        let client = ICRC1Client {
+            runtime: CdkRuntime,
+            ledger_canister_id,
+        };
+        let args = TransferArg {
+            from_subaccount: None,
+            to: Account {
+                owner: reimbursement_request.to,
+                subaccount: reimbursement_request
+                    .to_subaccount
+                    .as_ref()
+                    .map(|subaccount| subaccount.0),
+            },
+            fee: None,
+            created_at_time: None,
+            memo: Some(
+                MintMemo::Reimburse {
+                    withdrawal_id: reimbursement_request.withdrawal_id.get(),
+                    tx_hash: reimbursement_request.transaction_hash,
+                }
+                .into(),
+            ),
+            amount: Nat::from(reimbursement_request.reimbursed_amount),
+        };
+        let block_index = match client.transfer(args).await {
+            Ok(Ok(block_index)) => block_index
+                .0
+                .to_u64()
+                .expect("block index should fit into u64"),
+            Ok(Err(err)) => {
+                log!(
+                    INFO,
+                    "[reimburse_ckerc20] Failed to mint ckERC20 on ledger {ledger_canister_id}: {err}"
+                );
+                error_count += 1;
+                continue;
+            }
+            Err(err) => {
+                log!(
+                    INFO,
+                    "[reimburse_ckerc20] Failed to send a message to the ledger ({ledger_canister_id}): {err:?}"
+                );
+                error_count += 1;
+                continue;
+            }
+        };
+        mutate_state(|s| {
+            process_event(
+                s,
+                EventType::ReimbursedErc20Withdrawal(Erc20Reimbursed {
+                    withdrawal_id: reimbursement_request.withdrawal_id,
+                    burn_in_block: reimbursement_request.burn_in_block,
+                    reimbursed_in_block: LedgerMintIndex::new(block_index),
+                    ledger_id: ledger_canister_id,
+                    reimbursed_amount: reimbursement_request.reimbursed_amount,
+                    transaction_hash: Some(reimbursement_request.transaction_hash),
+                }),
+            )
+        });
+    }
+    if error_count > 0 {
+        log!(
+            INFO,
+            "[reimburse_ckerc20] Failed to reimburse {error_count} users, retrying later."
+        );
+    }
+}
+
 pub async fn process_retrieve_eth_requests() {
     let _guard = match TimerGuard::new(TaskType::RetrieveEth) {
         Ok(guard) => guard,
@@ -130,12 +218,46 @@ pub async fn process_retrieve_eth_requests() {
             return;
         }
     };
-    let transaction_price = match estimate_transaction_price(&fee_history) {
+    let base_transaction_price =
+        match estimate_transaction_price(&fee_history, read_state(State::ethereum_network)) {
+            Ok(transaction_price) => transaction_price,
+            Err(e) => {
+                log!(
+                    INFO,
+                    "Failed estimating transaction price to process ETH requests: {e:?}",
+                );
+                return;
+            }
+        };
+    let transaction_price = match add_l1_data_fee(
+        base_transaction_price.clone(),
+        WithdrawalTransactionKind::Eth,
+    )
+    .await
+    {
         Ok(transaction_price) => transaction_price,
         Err(e) => {
             log!(
                 INFO,
-                "Failed estimating transaction price to process ETH requests: {e:?}",
+                "Failed estimating L1 data fee to process ETH requests: {e:?}",
+            );
+            return;
+        }
+    };
+    let erc20_transaction_price = match add_l1_data_fee(
+        TransactionPrice {
+            gas_limit: CKERC20_WITHDRAWAL_TRANSACTION_GAS_LIMIT,
+            ..base_transaction_price
+        },
+        WithdrawalTransactionKind::Erc20,
+    )
+    .await
+    {
+        Ok(transaction_price) => transaction_price,
+        Err(e) => {
+            log!(
+                INFO,
+                "Failed estimating L1 data fee to process ckERC20 requests: {e:?}",
             );
             return;
         }
@@ -148,7 +270,7 @@ pub async fn process_retrieve_eth_requests() {
     );
     let latest_transaction_count = latest_transaction_count().await;
     resubmit_transactions_batch(latest_transaction_count, &transaction_price).await;
-    create_transactions_batch(transaction_price);
+    create_transactions_batch(transaction_price, erc20_transaction_price);
     sign_transactions_batch().await;
     send_transactions_batch(latest_transaction_count).await;
     finalize_transactions_batch().await;
@@ -218,7 +340,10 @@ async fn resubmit_transactions_batch(
     }
 }
 
-fn create_transactions_batch(transaction_price: TransactionPrice) {
+fn create_transactions_batch(
+    transaction_price: TransactionPrice,
+    erc20_transaction_price: TransactionPrice,
+) {
     for request in read_state(|s| {
         s.eth_transactions
             .withdrawal_requests_batch(WITHDRAWAL_REQUESTS_BATCH_SIZE)
@@ -256,6 +381,60 @@ fn create_transactions_batch(transaction_price: TransactionPrice) {
                 );
                 mutate_state(|s| s.eth_transactions.reschedule_withdrawal_request(request));
             }
+            Err(CreateTransactionError::InsufficientTransactionFee { .. }) => {
+                panic!("BUG: ETH withdrawal request {request:?} does not have a maximum transaction fee")
+            }
+        };
+    }
+
+    for request in read_state(|s| {
+        s.eth_transactions
+            .erc20_withdrawal_requests_batch(WITHDRAWAL_REQUESTS_BATCH_SIZE)
+    }) {
+        log!(DEBUG, "[create_transactions_batch]: processing {request:?}",);
+        let ethereum_network = read_state(State::ethereum_network);
+        let nonce = read_state(|s| s.eth_transactions.next_transaction_nonce());
+        match create_erc20_transaction(
+            &request,
+            nonce,
+            erc20_transaction_price.clone(),
+            ethereum_network,
+        ) {
+            Ok(transaction) => {
+                log!(
+                    DEBUG,
+                    "[create_transactions_batch]: created ERC-20 transaction {transaction:?}",
+                );
+
+                mutate_state(|s| {
+                    process_event(
+                        s,
+                        EventType::CreatedTransaction {
+                            withdrawal_id: request.cketh_ledger_burn_index,
+                            transaction,
+                        },
+                    );
+                });
+            }
+            Err(CreateTransactionError::InsufficientTransactionFee {
+                cketh_ledger_burn_index,
+                allowed_max_transaction_fee,
+                actual_max_transaction_fee,
+            }) => {
+                log!(
+                    INFO,
+                    "[create_transactions_batch]: ERC-20 withdrawal request with ckETH burn index {cketh_ledger_burn_index} \
+                    allows a maximum transaction fee of {allowed_max_transaction_fee:?}, which is lower than the current \
+                    maximum transaction fee {actual_max_transaction_fee:?}. Request moved back to end of queue."
+                );
+                mutate_state(|s| {
+                    s.eth_transactions
+                        .reschedule_erc20_withdrawal_request(request)
+                });
+            }
+            Err(CreateTransactionError::InsufficientAmount { .. }) => {
+                panic!("BUG: ERC-20 withdrawal request {request:?} does not pay fees from the withdrawal amount")
+            }
         };
     }
 }
@@ -416,12 +595,8 @@ async fn finalize_transactions_batch() {
 async fn finalized_transaction_count() -> Result<TransactionCount, MultiCallError<TransactionCount>>
 {
     read_state(EthRpcClient::from_state)
-        .eth_get_transaction_count(GetTransactionCountParams {
-            address: crate::state::minter_address().await,
-            block: BlockSpec::Tag(BlockTag::Finalized),
-        })
+        .eth_get_finalized_transaction_count(crate::state::minter_address().await)
         .await
-        .reduce_with_equality()
 }
 
 pub async fn eth_fee_history() -> Result<FeeHistory, MultiCallError<FeeHistory>> {
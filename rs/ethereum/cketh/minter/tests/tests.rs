@@ -164,6 +164,7 @@ fn should_deposit_and_withdraw() {
                 status: TransactionStatus::Success,
                 transaction_hash:
                 "0x2cf1763e8ee3990103a31a5709b17b83f167738abb400844e67f608a98b0bdb5".to_string(),
+                l1_fee: None,
             },
         },
     ]);
@@ -529,6 +530,7 @@ fn should_reimburse() {
                 status: TransactionStatus::Failure,
                 transaction_hash:
                 "0x2cf1763e8ee3990103a31a5709b17b83f167738abb400844e67f608a98b0bdb5".to_string(),
+                l1_fee: None,
             }},
         EventPayload::ReimbursedEthWithdrawal {
             transaction_hash: Some("0x2cf1763e8ee3990103a31a5709b17b83f167738abb400844e67f608a98b0bdb5".to_string()),
@@ -710,6 +712,7 @@ fn should_resubmit_new_transaction_when_price_increased() {
                 gas_used: Nat::from(21_000_u32),
                 status: TransactionStatus::Success,
                 transaction_hash: format!("{:?}", resubmitted_tx_hash),
+                l1_fee: None,
             },
         },
     ]);
@@ -1121,6 +1124,8 @@ fn install_minter(env: &StateMachine, ledger_id: CanisterId, minter_id: Canister
         ethereum_contract_address: Some(HELPER_SMART_CONTRACT_ADDRESS.to_string()),
         minimum_withdrawal_amount: CKETH_TRANSFER_FEE.into(),
         last_scraped_block_number: LAST_SCRAPED_BLOCK_NUMBER_AT_INSTALL.into(),
+        eth_rpc_config: None,
+        ethereum_block_confirmations: None,
     };
     let minter_arg = MinterArg::InitArg(args);
     env.install_existing_canister(minter_id, minter_wasm(), Encode!(&minter_arg).unwrap())
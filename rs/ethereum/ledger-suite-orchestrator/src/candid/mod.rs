@@ -13,10 +13,16 @@ pub enum OrchestratorArg {
 }
 
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
-pub struct InitArg {}
+pub struct InitArg {
+    /// The ckETH minter to notify when a new ckERC20 ledger suite was installed.
+    pub minter_id: Option<Principal>,
+}
 
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
-pub struct UpgradeArg {}
+pub struct UpgradeArg {
+    /// Change the ckETH minter to notify when a new ckERC20 ledger suite was installed.
+    pub minter_id: Option<Principal>,
+}
 
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct AddErc20Arg {
@@ -54,6 +60,15 @@ pub struct LedgerInitArg {
     pub accounts_overflow_trim_quantity: Option<u64>,
 }
 
+/// Argument of the `add_ckerc20_token` endpoint of the ckETH minter.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct AddCkErc20Token {
+    pub chain_id: Nat,
+    pub address: String,
+    pub ckerc20_token_symbol: String,
+    pub ckerc20_ledger_id: Principal,
+}
+
 #[derive(CandidType, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct ManagedCanisterIds {
     pub ledger: Option<Principal>,
@@ -148,7 +148,7 @@ fn initial_dashboard() -> DashboardTemplate {
 }
 
 fn initial_state() -> State {
-    State::from(InitArg {})
+    State::from(InitArg { minter_id: None })
 }
 mod assertions {
     use crate::dashboard::DashboardTemplate;
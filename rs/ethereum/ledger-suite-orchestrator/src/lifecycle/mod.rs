@@ -19,15 +19,31 @@ pub fn init(init_arg: InitArg) {
     setup_timers()
 }
 
-pub fn post_upgrade(_upgrade_arg: Option<UpgradeArg>) {
+pub fn post_upgrade(upgrade_arg: Option<UpgradeArg>) {
     //TODO: in case UpgradeArg is present, 1) refresh wasms binaries in stable memory and plan upgrade of managed canisters
+    if let Some(UpgradeArg {
+        minter_id: Some(minter_id),
+    }) = upgrade_arg
+    {
+        log!(INFO, "[post_upgrade]: setting minter ID to {}", minter_id);
+        mutate_state(|s| s.set_minter_id(minter_id));
+    }
     setup_timers()
 }
 
 pub fn add_erc20(token: AddErc20Arg) {
     match read_state(|s| InstallLedgerSuiteArgs::validate_add_erc20(s, token.clone())) {
         Ok(args) => {
-            mutate_state(|s| s.add_task(Task::InstallLedgerSuite(args)));
+            let erc20_token = args.contract().clone();
+            mutate_state(|s| {
+                s.add_task(Task::InstallLedgerSuite(args));
+                if let Some(&minter_id) = s.minter_id() {
+                    s.add_task(Task::NotifyErc20Added {
+                        erc20_token,
+                        minter_id,
+                    });
+                }
+            });
             ic_cdk_timers::set_timer(Duration::from_secs(0), || ic_cdk::spawn(execute_tasks()));
         }
         Err(e) => {
@@ -9,6 +9,7 @@ use ic_ic00_types::{
 };
 use serde::de::DeserializeOwned;
 use std::fmt;
+use std::fmt::Debug;
 
 // TODO: extract to common crate since copied form ckETH
 
@@ -107,6 +108,17 @@ pub trait CanisterRuntime {
         wasm_module: Wasm,
         arg: Vec<u8>,
     ) -> Result<(), CallError>;
+
+    /// Calls the given method on another canister.
+    async fn call_canister<I, O>(
+        &self,
+        canister_id: Principal,
+        method: &str,
+        args: I,
+    ) -> Result<O, CallError>
+    where
+        I: CandidType + Debug + Send + 'static,
+        O: CandidType + DeserializeOwned + Debug + 'static;
 }
 
 pub struct IcCanisterRuntime {}
@@ -193,4 +205,24 @@ impl CanisterRuntime for IcCanisterRuntime {
 
         Ok(())
     }
+
+    async fn call_canister<I, O>(
+        &self,
+        canister_id: Principal,
+        method: &str,
+        args: I,
+    ) -> Result<O, CallError>
+    where
+        I: CandidType + Debug + Send + 'static,
+        O: CandidType + DeserializeOwned + Debug + 'static,
+    {
+        let res: Result<(O,), _> = ic_cdk::api::call::call(canister_id, method, (&args,)).await;
+        match res {
+            Ok((output,)) => Ok(output),
+            Err((code, msg)) => Err(CallError {
+                method: method.to_string(),
+                reason: Reason::from_reject(code, msg),
+            }),
+        }
+    }
 }
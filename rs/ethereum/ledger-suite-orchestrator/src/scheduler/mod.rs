@@ -3,7 +3,7 @@ pub mod test_fixtures;
 #[cfg(test)]
 mod tests;
 
-use crate::candid::{AddErc20Arg, LedgerInitArg};
+use crate::candid::{AddCkErc20Token, AddErc20Arg, LedgerInitArg};
 use crate::logs::INFO;
 use crate::management::{CallError, CanisterRuntime};
 use crate::state::{
@@ -36,13 +36,23 @@ impl Tasks {
     pub fn add_task(&mut self, task: Task) {
         self.0.push_back(task);
     }
+
+    /// ERC-20 tokens whose ledger suite is scheduled to be installed.
+    pub fn pending_ledger_suite_installations(&self) -> impl Iterator<Item = &Erc20Token> {
+        self.0.iter().filter_map(|task| match task {
+            Task::InstallLedgerSuite(args) => Some(args.contract()),
+            Task::NotifyErc20Added { .. } => None,
+        })
+    }
 }
 
 impl Tasks {
     // TODO XC-29: next task should be executed if the current one failed.
     /// Execute task one by one in order and stop at the first failure.
     /// If a task succeeds, it is removed from the queue.
-    /// If a task fails, it is put back at the front of the queue.
+    /// If a task fails, it is put back at the front of the queue, unless it
+    /// depends on a task that did not run yet, in which case it is put back
+    /// at the end of the queue.
     pub async fn execute<R: CanisterRuntime>(&mut self, runtime: &R) -> Result<(), TaskError> {
         while let Some(task) = self.0.pop_front() {
             match task.execute(runtime).await {
@@ -50,7 +60,14 @@ impl Tasks {
                     log!(INFO, "task {:?} accomplished", task);
                 }
                 Err(e) => {
-                    if e.is_recoverable() {
+                    if let TaskError::LedgerNotInstalled(_) = e {
+                        log!(
+                            INFO,
+                            "task {:?} is waiting for the ledger to be installed. Will retry later.",
+                            task
+                        );
+                        self.0.push_back(task);
+                    } else if e.is_recoverable() {
                         log!(INFO, "task {:?} failed: {:?}. Will retry later.", task, e);
                         self.0.push_front(task);
                     } else {
@@ -67,6 +84,10 @@ impl Tasks {
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum Task {
     InstallLedgerSuite(InstallLedgerSuiteArgs),
+    NotifyErc20Added {
+        erc20_token: Erc20Token,
+        minter_id: Principal,
+    },
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -86,6 +107,10 @@ pub enum InvalidAddErc20ArgError {
 }
 
 impl InstallLedgerSuiteArgs {
+    pub fn contract(&self) -> &Erc20Token {
+        &self.contract
+    }
+
     pub fn validate_add_erc20(
         state: &State,
         args: AddErc20Arg,
@@ -145,6 +170,9 @@ pub enum TaskError {
     CanisterCreationError(CallError),
     InstallCodeError(CallError),
     WasmHashNotFound(WasmHash),
+    InterCanisterCallError(CallError),
+    LedgerNotFound(Erc20Token),
+    LedgerNotInstalled(Erc20Token),
 }
 
 impl TaskError {
@@ -155,6 +183,9 @@ impl TaskError {
             TaskError::CanisterCreationError(_) => true,
             TaskError::InstallCodeError(_) => true,
             TaskError::WasmHashNotFound(_) => false,
+            TaskError::InterCanisterCallError(_) => true,
+            TaskError::LedgerNotFound(_) => false,
+            TaskError::LedgerNotInstalled(_) => true,
         }
     }
 }
@@ -163,6 +194,10 @@ impl Task {
     pub async fn execute<R: CanisterRuntime>(&self, runtime: &R) -> Result<(), TaskError> {
         match self {
             Task::InstallLedgerSuite(args) => install_ledger_suite(args, runtime).await,
+            Task::NotifyErc20Added {
+                erc20_token,
+                minter_id,
+            } => notify_erc20_added(erc20_token, minter_id, runtime).await,
         }
     }
 }
@@ -203,6 +238,51 @@ async fn install_ledger_suite<R: CanisterRuntime>(
     Ok(())
 }
 
+async fn notify_erc20_added<R: CanisterRuntime>(
+    token: &Erc20Token,
+    minter_id: &Principal,
+    runtime: &R,
+) -> Result<(), TaskError> {
+    let managed_canisters = read_state(|s| s.managed_canisters(token).cloned());
+    match managed_canisters {
+        Some(Canisters {
+            ledger: Some(ledger),
+            metadata,
+            ..
+        }) if ledger.installed_wasm_hash().is_some() => {
+            let args = AddCkErc20Token {
+                chain_id: candid::Nat::from(*token.chain_id().as_ref()),
+                address: token.address().to_string(),
+                ckerc20_token_symbol: metadata.ckerc20_token_symbol,
+                ckerc20_ledger_id: *ledger.canister_id(),
+            };
+            runtime
+                .call_canister::<_, ()>(*minter_id, "add_ckerc20_token", args.clone())
+                .await
+                .map_err(|e| {
+                    log!(
+                        INFO,
+                        "failed to notify minter {} about new ckERC20 token {:?}: {}",
+                        minter_id,
+                        args,
+                        e
+                    );
+                    TaskError::InterCanisterCallError(e)
+                })?;
+            log!(
+                INFO,
+                "notified minter {} about new ckERC20 token {:?}",
+                minter_id,
+                args
+            );
+            Ok(())
+        }
+        // The ledger suite of a managed token is still being installed.
+        Some(_) => Err(TaskError::LedgerNotInstalled(token.clone())),
+        None => Err(TaskError::LedgerNotFound(token.clone())),
+    }
+}
+
 fn record_new_erc20_token_once(contract: Erc20Token, metadata: CanistersMetadata) {
     mutate_state(|s| {
         if s.managed_canisters(&contract).is_some() {
@@ -1,16 +1,18 @@
-use crate::candid::{InitArg, LedgerInitArg};
+use crate::candid::{AddCkErc20Token, InitArg, LedgerInitArg};
 use crate::management::{CallError, Reason};
-use crate::scheduler::test_fixtures::usdc_metadata;
+use crate::scheduler::test_fixtures::{usdc_metadata, usdt, usdt_metadata};
 use crate::scheduler::tests::mock::MockCanisterRuntime;
 use crate::scheduler::{Erc20Token, InstallLedgerSuiteArgs, Task, TaskError, Tasks};
 use crate::state::{
-    read_state, Canisters, IndexCanister, LedgerCanister, ManagedCanisterStatus, State, WasmHash,
+    mutate_state, read_state, Canisters, IndexCanister, LedgerCanister, ManagedCanisterStatus,
+    State, WasmHash,
 };
 use candid::Principal;
 
 const ORCHESTRATOR_PRINCIPAL: Principal = Principal::from_slice(&[0_u8; 29]);
 const LEDGER_PRINCIPAL: Principal = Principal::from_slice(&[1_u8; 29]);
 const INDEX_PRINCIPAL: Principal = Principal::from_slice(&[2_u8; 29]);
+const MINTER_PRINCIPAL: Principal = Principal::from_slice(&[3_u8; 29]);
 
 #[tokio::test]
 async fn should_install_ledger_suite() {
@@ -253,8 +255,175 @@ async fn should_discard_add_erc20_task_when_index_wasm_not_found() {
     );
 }
 
+#[tokio::test]
+async fn should_notify_minter_after_ledger_suite_installed() {
+    init_state();
+    let mut tasks = Tasks::default();
+    tasks.add_task(Task::InstallLedgerSuite(usdc_install_args()));
+    tasks.add_task(Task::NotifyErc20Added {
+        erc20_token: usdc(),
+        minter_id: MINTER_PRINCIPAL,
+    });
+    let mut runtime = MockCanisterRuntime::new();
+
+    runtime
+        .expect_id()
+        .times(1)
+        .return_const(ORCHESTRATOR_PRINCIPAL);
+    expect_create_canister_returning(
+        &mut runtime,
+        vec![Ok(LEDGER_PRINCIPAL), Ok(INDEX_PRINCIPAL)],
+    );
+    runtime.expect_install_code().times(2).return_const(Ok(()));
+    let expected_args = AddCkErc20Token {
+        chain_id: 1_u8.into(),
+        address: usdc().address().to_string(),
+        ckerc20_token_symbol: "ckUSDC".to_string(),
+        ckerc20_ledger_id: LEDGER_PRINCIPAL,
+    };
+    runtime
+        .expect_call_canister::<AddCkErc20Token, ()>()
+        .withf(move |canister_id, method, args| {
+            canister_id == &MINTER_PRINCIPAL
+                && method == "add_ckerc20_token"
+                && args == &expected_args
+        })
+        .times(1)
+        .return_const(Ok(()));
+
+    assert_eq!(tasks.execute(&runtime).await, Ok(()));
+    assert_eq!(tasks, Tasks::default());
+}
+
+#[tokio::test]
+async fn should_retry_notifying_minter_when_call_fails() {
+    init_state();
+    let mut tasks = Tasks::default();
+    tasks.add_task(Task::InstallLedgerSuite(usdc_install_args()));
+    tasks.add_task(Task::NotifyErc20Added {
+        erc20_token: usdc(),
+        minter_id: MINTER_PRINCIPAL,
+    });
+    let mut runtime = MockCanisterRuntime::new();
+
+    runtime
+        .expect_id()
+        .times(1)
+        .return_const(ORCHESTRATOR_PRINCIPAL);
+    expect_create_canister_returning(
+        &mut runtime,
+        vec![Ok(LEDGER_PRINCIPAL), Ok(INDEX_PRINCIPAL)],
+    );
+    runtime.expect_install_code().times(2).return_const(Ok(()));
+    let expected_error = CallError {
+        method: "add_ckerc20_token".to_string(),
+        reason: Reason::TransientInternalError("minter is stopped".to_string()),
+    };
+    runtime
+        .expect_call_canister::<AddCkErc20Token, ()>()
+        .times(1)
+        .return_const(Err(expected_error.clone()));
+
+    assert_eq!(
+        tasks.execute(&runtime).await,
+        Err(TaskError::InterCanisterCallError(expected_error))
+    );
+
+    runtime.checkpoint();
+    runtime
+        .expect_call_canister::<AddCkErc20Token, ()>()
+        .times(1)
+        .return_const(Ok(()));
+
+    assert_eq!(tasks.execute(&runtime).await, Ok(()));
+    assert_eq!(tasks, Tasks::default());
+}
+
+#[tokio::test]
+async fn should_discard_notify_minter_task_when_ledger_not_installed() {
+    init_state();
+    let mut tasks = Tasks::default();
+    tasks.add_task(Task::NotifyErc20Added {
+        erc20_token: usdc(),
+        minter_id: MINTER_PRINCIPAL,
+    });
+    let runtime = MockCanisterRuntime::new();
+
+    assert_eq!(
+        tasks.execute(&runtime).await,
+        Err(TaskError::LedgerNotFound(usdc()))
+    );
+    assert_eq!(tasks, Tasks::default());
+}
+
+#[tokio::test]
+async fn should_reschedule_notify_minter_task_until_ledger_installed() {
+    init_state();
+    mutate_state(|s| s.record_new_erc20_token(usdc(), usdc_metadata()));
+    let mut tasks = Tasks::default();
+    tasks.add_task(Task::NotifyErc20Added {
+        erc20_token: usdc(),
+        minter_id: MINTER_PRINCIPAL,
+    });
+    tasks.add_task(Task::InstallLedgerSuite(usdc_install_args()));
+    let mut runtime = MockCanisterRuntime::new();
+
+    assert_eq!(
+        tasks.execute(&runtime).await,
+        Err(TaskError::LedgerNotInstalled(usdc()))
+    );
+    let mut expected_tasks = Tasks::default();
+    expected_tasks.add_task(Task::InstallLedgerSuite(usdc_install_args()));
+    expected_tasks.add_task(Task::NotifyErc20Added {
+        erc20_token: usdc(),
+        minter_id: MINTER_PRINCIPAL,
+    });
+    assert_eq!(tasks, expected_tasks);
+
+    runtime
+        .expect_id()
+        .times(1)
+        .return_const(ORCHESTRATOR_PRINCIPAL);
+    expect_create_canister_returning(
+        &mut runtime,
+        vec![Ok(LEDGER_PRINCIPAL), Ok(INDEX_PRINCIPAL)],
+    );
+    runtime.expect_install_code().times(2).return_const(Ok(()));
+    runtime
+        .expect_call_canister::<AddCkErc20Token, ()>()
+        .times(1)
+        .return_const(Ok(()));
+
+    assert_eq!(tasks.execute(&runtime).await, Ok(()));
+    assert_eq!(tasks, Tasks::default());
+}
+
+#[test]
+fn should_notify_minter_about_tokens_added_before_minter_id_was_set() {
+    let mut state = State::from(InitArg { minter_id: None });
+    state.record_new_erc20_token(usdt(), usdt_metadata());
+    let mut tasks = Tasks::default();
+    tasks.add_task(Task::InstallLedgerSuite(usdc_install_args()));
+    state.set_tasks(tasks.clone());
+
+    state.set_minter_id(MINTER_PRINCIPAL);
+
+    assert_eq!(state.minter_id(), Some(&MINTER_PRINCIPAL));
+    for erc20_token in [usdc(), usdt()] {
+        tasks.add_task(Task::NotifyErc20Added {
+            erc20_token,
+            minter_id: MINTER_PRINCIPAL,
+        });
+    }
+    assert_eq!(state.tasks(), &tasks);
+
+    // Setting the same minter again does not notify it again.
+    state.set_minter_id(MINTER_PRINCIPAL);
+    assert_eq!(state.tasks(), &tasks);
+}
+
 fn init_state() {
-    crate::state::init_state(State::from(InitArg {}));
+    crate::state::init_state(State::from(InitArg { minter_id: None }));
 }
 
 fn usdc_install_args() -> InstallLedgerSuiteArgs {
@@ -328,8 +497,10 @@ mod mock {
     use crate::scheduler::CallError;
     use crate::state::Wasm;
     use async_trait::async_trait;
-    use candid::Principal;
+    use candid::{CandidType, Principal};
     use mockall::mock;
+    use serde::de::DeserializeOwned;
+    use std::fmt::Debug;
 
     mock! {
        pub CanisterRuntime{}
@@ -350,6 +521,16 @@ mod mock {
                 wasm_module: Wasm,
                 arg: Vec<u8>,
             ) -> Result<(), CallError>;
+
+            async fn call_canister<I, O>(
+                &self,
+                canister_id: Principal,
+                method: &str,
+                args: I,
+            ) -> Result<O, CallError>
+            where
+                I: CandidType + Debug + Send + 'static,
+                O: CandidType + DeserializeOwned + Debug + 'static;
         }
     }
 }
@@ -518,6 +699,6 @@ mod install_ledger_suite_args {
     }
 
     fn initial_state() -> State {
-        State::from(InitArg {})
+        State::from(InitArg { minter_id: None })
     }
 }
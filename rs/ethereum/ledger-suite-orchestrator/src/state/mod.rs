@@ -11,7 +11,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_bytes::ByteBuf;
 use std::borrow::Cow;
 use std::cell::RefCell;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt::{Debug, Display, Formatter};
 use std::marker::PhantomData;
 use std::str::FromStr;
@@ -305,9 +305,38 @@ pub struct State {
     managed_canisters: ManagedCanisters,
     tasks: Tasks,
     processing_tasks_guard: bool,
+    #[serde(default)]
+    minter_id: Option<Principal>,
 }
 
 impl State {
+    pub fn minter_id(&self) -> Option<&Principal> {
+        self.minter_id.as_ref()
+    }
+
+    /// Sets the minter ID and schedules notifying the minter about all the
+    /// ERC-20 tokens added so far, since those were notified to no minter
+    /// or to another one.
+    pub fn set_minter_id(&mut self, minter_id: Principal) {
+        if self.minter_id == Some(minter_id) {
+            return;
+        }
+        self.minter_id = Some(minter_id);
+        let erc20_tokens: BTreeSet<Erc20Token> = self
+            .managed_canisters
+            .canisters
+            .keys()
+            .chain(self.tasks.pending_ledger_suite_installations())
+            .cloned()
+            .collect();
+        for erc20_token in erc20_tokens {
+            self.tasks.add_task(Task::NotifyErc20Added {
+                erc20_token,
+                minter_id,
+            });
+        }
+    }
+
     pub fn tasks(&self) -> &Tasks {
         &self.tasks
     }
@@ -516,7 +545,7 @@ impl RetrieveCanisterWasm<Index> for State {
 }
 
 impl From<InitArg> for State {
-    fn from(InitArg {}: InitArg) -> Self {
+    fn from(InitArg { minter_id }: InitArg) -> Self {
         Self {
             ledger_wasm: Wasm::from(LEDGER_BYTECODE),
             index_wasm: Wasm::from(INDEX_BYTECODE),
@@ -524,6 +553,7 @@ impl From<InitArg> for State {
             managed_canisters: Default::default(),
             tasks: Default::default(),
             processing_tasks_guard: false,
+            minter_id,
         }
     }
 }
@@ -205,5 +205,5 @@ fn expect_panic_with_message<F: FnOnce() -> R, R: std::fmt::Debug>(f: F, expecte
 }
 
 fn init_state() -> State {
-    State::from(InitArg {})
+    State::from(InitArg { minter_id: None })
 }
@@ -239,7 +239,7 @@ impl LedgerSuiteOrchestrator {
 }
 
 fn install_ledger_orchestrator(env: &StateMachine, ledger_suite_orchestrator_id: CanisterId) {
-    let args = OrchestratorArg::InitArg(InitArg {});
+    let args = OrchestratorArg::InitArg(InitArg { minter_id: None });
     env.install_existing_canister(
         ledger_suite_orchestrator_id,
         ledger_suite_orchestrator_wasm(),
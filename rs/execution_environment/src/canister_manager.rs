@@ -651,6 +651,9 @@ impl CanisterManager {
         if let Some(log_visibility) = settings.log_visibility() {
             canister.system_state.log_visibility = log_visibility;
         }
+        if let Some(timer_priority) = settings.timer_priority() {
+            canister.system_state.timer_priority = timer_priority;
+        }
     }
 
     /// Tries to apply the requested settings on the canister identified by
@@ -1190,6 +1193,7 @@ impl CanisterManager {
         let freeze_threshold = canister.system_state.freeze_threshold;
         let reserved_cycles_limit = canister.system_state.reserved_balance_limit();
         let log_visibility = canister.system_state.log_visibility;
+        let timer_priority = canister.system_state.timer_priority;
 
         Ok(CanisterStatusResultV2::new(
             canister.status(),
@@ -1206,6 +1210,7 @@ impl CanisterManager {
             freeze_threshold.get(),
             reserved_cycles_limit.map(|x| x.get()),
             log_visibility,
+            timer_priority,
             self.cycles_account_manager
                 .idle_cycles_burned_rate(
                     memory_allocation,
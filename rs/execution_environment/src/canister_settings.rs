@@ -1,7 +1,7 @@
 use ic_base_types::{NumBytes, NumSeconds};
 use ic_cycles_account_manager::{CyclesAccountManager, ResourceSaturation};
 use ic_error_types::{ErrorCode, UserError};
-use ic_ic00_types::{CanisterSettingsArgs, LogVisibility};
+use ic_ic00_types::{CanisterSettingsArgs, LogVisibility, TimerPriority};
 use ic_interfaces::execution_environment::SubnetAvailableMemory;
 use ic_types::{
     ComputeAllocation, Cycles, InvalidComputeAllocationError, InvalidMemoryAllocationError,
@@ -22,6 +22,7 @@ pub(crate) struct CanisterSettings {
     pub(crate) freezing_threshold: Option<NumSeconds>,
     pub(crate) reserved_cycles_limit: Option<Cycles>,
     pub(crate) log_visibility: Option<LogVisibility>,
+    pub(crate) timer_priority: Option<TimerPriority>,
 }
 
 impl CanisterSettings {
@@ -33,6 +34,7 @@ impl CanisterSettings {
         freezing_threshold: Option<NumSeconds>,
         reserved_cycles_limit: Option<Cycles>,
         log_visibility: Option<LogVisibility>,
+        timer_priority: Option<TimerPriority>,
     ) -> Self {
         Self {
             controller,
@@ -42,6 +44,7 @@ impl CanisterSettings {
             freezing_threshold,
             reserved_cycles_limit,
             log_visibility,
+            timer_priority,
         }
     }
 
@@ -72,6 +75,10 @@ impl CanisterSettings {
     pub fn log_visibility(&self) -> Option<LogVisibility> {
         self.log_visibility
     }
+
+    pub fn timer_priority(&self) -> Option<TimerPriority> {
+        self.timer_priority
+    }
 }
 
 impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
@@ -119,6 +126,7 @@ impl TryFrom<CanisterSettingsArgs> for CanisterSettings {
             freezing_threshold,
             reserved_cycles_limit,
             input.log_visibility,
+            input.timer_priority,
         ))
     }
 }
@@ -142,6 +150,7 @@ pub(crate) struct CanisterSettingsBuilder {
     freezing_threshold: Option<NumSeconds>,
     reserved_cycles_limit: Option<Cycles>,
     log_visibility: Option<LogVisibility>,
+    timer_priority: Option<TimerPriority>,
 }
 
 #[allow(dead_code)]
@@ -155,6 +164,7 @@ impl CanisterSettingsBuilder {
             freezing_threshold: None,
             reserved_cycles_limit: None,
             log_visibility: None,
+            timer_priority: None,
         }
     }
 
@@ -167,6 +177,7 @@ impl CanisterSettingsBuilder {
             freezing_threshold: self.freezing_threshold,
             reserved_cycles_limit: self.reserved_cycles_limit,
             log_visibility: self.log_visibility,
+            timer_priority: self.timer_priority,
         }
     }
 
@@ -218,6 +229,13 @@ impl CanisterSettingsBuilder {
             ..self
         }
     }
+
+    pub fn with_timer_priority(self, timer_priority: TimerPriority) -> Self {
+        Self {
+            timer_priority: Some(timer_priority),
+            ..self
+        }
+    }
 }
 
 pub enum UpdateSettingsError {
@@ -285,6 +303,7 @@ pub(crate) struct ValidatedCanisterSettings {
     reserved_cycles_limit: Option<Cycles>,
     reservation_cycles: Cycles,
     log_visibility: Option<LogVisibility>,
+    timer_priority: Option<TimerPriority>,
 }
 
 impl ValidatedCanisterSettings {
@@ -319,6 +338,10 @@ impl ValidatedCanisterSettings {
     pub fn log_visibility(&self) -> Option<LogVisibility> {
         self.log_visibility
     }
+
+    pub fn timer_priority(&self) -> Option<TimerPriority> {
+        self.timer_priority
+    }
 }
 
 /// Validates the new canisters settings:
@@ -516,5 +539,6 @@ pub(crate) fn validate_canister_settings(
         reserved_cycles_limit: settings.reserved_cycles_limit(),
         reservation_cycles,
         log_visibility: settings.log_visibility(),
+        timer_priority: settings.timer_priority(),
     })
 }
@@ -449,6 +449,7 @@ impl InstallCodeHelper {
                 freezing_threshold: None,
                 reserved_cycles_limit: None,
                 log_visibility: None,
+                timer_priority: None,
             },
             self.canister.memory_usage(),
             self.canister.message_memory_usage(),
@@ -30,9 +30,9 @@ use ic_cycles_account_manager::{
 };
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 use ic_ic00_types::{
-    CanisterChangeOrigin, CanisterHttpRequestArgs, CanisterIdRecord, CanisterInfoRequest,
-    CanisterInfoResponse, CanisterSettingsArgs, CanisterStatusType, ClearChunkStoreArgs,
-    ComputeInitialEcdsaDealingsArgs, CreateCanisterArgs, ECDSAPublicKeyArgs,
+    CanisterChangeOrigin, CanisterHttpRequestArgs, CanisterHttpResponsePayload, CanisterIdRecord,
+    CanisterInfoRequest, CanisterInfoResponse, CanisterSettingsArgs, CanisterStatusType,
+    ClearChunkStoreArgs, ComputeInitialEcdsaDealingsArgs, CreateCanisterArgs, ECDSAPublicKeyArgs,
     ECDSAPublicKeyResponse, EcdsaKeyId, EmptyBlob, InstallChunkedCodeArgs, InstallCodeArgsV2,
     Method as Ic00Method, NodeMetricsHistoryArgs, Payload as Ic00Payload,
     ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs, SetupInitialDKGArgs,
@@ -58,7 +58,7 @@ use ic_replicated_state::{
 };
 use ic_system_api::{ExecutionParameters, InstructionLimits};
 use ic_types::{
-    canister_http::CanisterHttpRequestContext,
+    canister_http::{CanisterHttpRequestContext, Replication},
     crypto::canister_threshold_sig::{ExtendedDerivationPath, MasterEcdsaPublicKey},
     crypto::threshold_sig::ni_dkg::NiDkgTargetId,
     ingress::{IngressState, IngressStatus, WasmResult},
@@ -76,7 +76,7 @@ use ic_types::{messages::MessageId, methods::WasmMethod};
 use ic_wasm_types::WasmHash;
 use phantom_newtype::AmountOf;
 use prometheus::IntCounter;
-use rand::RngCore;
+use rand::{seq::SliceRandom, RngCore};
 use std::{
     collections::{BTreeMap, HashMap},
     convert::Into,
@@ -460,13 +460,23 @@ impl ExecutionEnvironment {
                             state.metadata.subnet_metrics.ecdsa_signature_agreements += 1;
                         }
 
+                        let response_payload = match &context {
+                            SubnetCallContext::CanisterHttpRequest(
+                                CanisterHttpRequestContext {
+                                    replication: Replication::NonReplicated(_),
+                                    ..
+                                },
+                            ) => mark_unverified_http_response(&response.response_payload),
+                            _ => response.response_payload.clone(),
+                        };
+
                         state.push_subnet_output_response(
                             Response {
                                 originator: request.sender,
                                 respondent: CanisterId::from(self.own_subnet_id),
                                 originator_reply_callback: request.sender_reply_callback,
                                 refund: request.payment,
-                                response_payload: response.response_payload.clone(),
+                                response_payload,
                             }
                             .into(),
                         );
@@ -811,19 +821,31 @@ impl ExecutionEnvironment {
                     CanisterCall::Request(request) => {
                         match CanisterHttpRequestArgs::decode(payload) {
                             Err(err) => Some((Err(err), msg.take_cycles())),
-                            Ok(args) => match CanisterHttpRequestContext::try_from((
-                                state.time(),
+                            Ok(args) => match self.canister_http_request_context(
+                                &state,
                                 request.as_ref(),
                                 args,
-                            )) {
-                                Err(err) => Some((Err(err.into()), msg.take_cycles())),
+                                rng,
+                            ) {
+                                Err(err) => Some((Err(err), msg.take_cycles())),
                                 Ok(mut canister_http_request_context) => {
-                                    let http_request_fee =
-                                        self.cycles_account_manager.http_request_fee(
-                                            canister_http_request_context.variable_parts_size(),
-                                            canister_http_request_context.max_response_bytes,
-                                            registry_settings.subnet_size,
-                                        );
+                                    let http_request_fee = match canister_http_request_context
+                                        .replication
+                                    {
+                                        Replication::FullyReplicated | Replication::Majority => {
+                                            self.cycles_account_manager.http_request_fee(
+                                                canister_http_request_context.variable_parts_size(),
+                                                canister_http_request_context.max_response_bytes,
+                                                registry_settings.subnet_size,
+                                            )
+                                        }
+                                        Replication::NonReplicated(_) => self
+                                            .cycles_account_manager
+                                            .non_replicated_http_request_fee(
+                                                canister_http_request_context.variable_parts_size(),
+                                                canister_http_request_context.max_response_bytes,
+                                            ),
+                                    };
                                     if request.payment < http_request_fee {
                                         let err = Err(UserError::new(
                                                         ErrorCode::CanisterRejectedMessage,
@@ -2267,6 +2289,36 @@ impl ExecutionEnvironment {
         Ok(())
     }
 
+    /// Creates the context of a canister http request. The node making a
+    /// non-replicated request is picked at random among the nodes of the subnet.
+    fn canister_http_request_context(
+        &self,
+        state: &ReplicatedState,
+        request: &Request,
+        args: CanisterHttpRequestArgs,
+        rng: &mut dyn RngCore,
+    ) -> Result<CanisterHttpRequestContext, UserError> {
+        let non_replicated = args.is_non_replicated();
+        let mut context = CanisterHttpRequestContext::try_from((state.time(), request, args))?;
+        if non_replicated {
+            let nodes: Vec<_> = state
+                .metadata
+                .network_topology
+                .subnets
+                .get(&state.metadata.own_subnet_id)
+                .map(|subnet_topology| subnet_topology.nodes.iter().copied().collect())
+                .unwrap_or_default();
+            let designated_node = nodes.choose(rng).ok_or_else(|| {
+                UserError::new(
+                    ErrorCode::CanisterRejectedMessage,
+                    "No node is available to make the non-replicated http request.".to_string(),
+                )
+            })?;
+            context.replication = Replication::NonReplicated(*designated_node);
+        }
+        Ok(context)
+    }
+
     fn compute_initial_ecdsa_dealings(
         &self,
         state: &mut ReplicatedState,
@@ -3120,6 +3172,23 @@ pub(crate) fn subnet_memory_capacity(config: &ExecutionConfig) -> SubnetAvailabl
     )
 }
 
+/// Adds the [`ic_ic00_types::UNVERIFIED_RESPONSE_HEADER`] to the response of a
+/// non-replicated canister http request. Rejects and replies that do not
+/// decode as `http_response`, which only a faulty transform function produces,
+/// are returned unchanged.
+fn mark_unverified_http_response(payload: &Payload) -> Payload {
+    match payload {
+        Payload::Data(data) => match CanisterHttpResponsePayload::decode(data) {
+            Ok(mut http_response) => {
+                http_response.mark_unverified();
+                Payload::Data(http_response.encode())
+            }
+            Err(_) => payload.clone(),
+        },
+        Payload::Reject(_) => payload.clone(),
+    }
+}
+
 fn get_canister(
     canister_id: CanisterId,
     state: &ReplicatedState,
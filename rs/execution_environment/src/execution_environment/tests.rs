@@ -12,8 +12,9 @@ use ic_ic00_types::{
     self as ic00, BitcoinGetUtxosArgs, BitcoinNetwork, BoundedHttpHeaders, CanisterChange,
     CanisterHttpRequestArgs, CanisterIdRecord, CanisterStatusResultV2, CanisterStatusType,
     DerivationPath, EcdsaCurve, EcdsaKeyId, EmptyBlob, FetchCanisterLogsRequest, HttpMethod,
-    LogVisibility, Method, Payload as Ic00Payload, ProvisionalCreateCanisterWithCyclesArgs,
-    ProvisionalTopUpCanisterArgs, TransformContext, TransformFunc, IC_00,
+    HttpOutcallReplication, LogVisibility, Method, Payload as Ic00Payload,
+    ProvisionalCreateCanisterWithCyclesArgs, ProvisionalTopUpCanisterArgs, TimerPriority,
+    TransformContext, TransformFunc, IC_00,
 };
 use ic_registry_routing_table::canister_id_into_u64;
 use ic_registry_routing_table::CanisterIdRange;
@@ -30,7 +31,7 @@ use ic_test_utilities_execution_environment::{
 };
 use ic_test_utilities_metrics::{fetch_histogram_vec_count, fetch_int_counter, metric_vec};
 use ic_test_utilities_time::mock_time;
-use ic_types::canister_http::Transform;
+use ic_types::canister_http::{Replication, Transform};
 use ic_types::{
     canister_http::CanisterHttpMethod,
     ingress::{IngressState, IngressStatus, WasmResult},
@@ -1922,6 +1923,7 @@ fn execute_canister_http_request() {
             }),
             context: transform_context.clone(),
         }),
+        replication: None,
     };
 
     // Create request to HTTP_REQUEST method.
@@ -1976,6 +1978,94 @@ fn execute_canister_http_request() {
     );
 }
 
+#[test]
+fn execute_non_replicated_canister_http_request() {
+    let own_subnet = subnet_test_id(1);
+    let caller_canister = canister_test_id(10);
+    let mut test = ExecutionTestBuilder::new()
+        .with_own_subnet_id(own_subnet)
+        .with_caller(own_subnet, caller_canister)
+        .build();
+    test.state_mut().metadata.own_subnet_features.http_requests = true;
+
+    let response_size_limit = 1000u64;
+    let args = CanisterHttpRequestArgs {
+        url: "https://".to_string(),
+        max_response_bytes: Some(response_size_limit),
+        headers: BoundedHttpHeaders::new(vec![]),
+        body: None,
+        method: HttpMethod::GET,
+        transform: None,
+        replication: Some(HttpOutcallReplication::NonReplicated),
+    };
+
+    let payment = Cycles::new(1_000_000_000);
+    test.inject_call_to_ic00(Method::HttpRequest, args.encode(), payment);
+    test.execute_all();
+
+    let http_request_context = test
+        .state()
+        .metadata
+        .subnet_call_context_manager
+        .canister_http_request_contexts
+        .get(&CallbackId::from(0))
+        .unwrap()
+        .clone();
+
+    // The request is made by a node of the subnet.
+    let designated_node = match http_request_context.replication {
+        Replication::NonReplicated(node_id) => node_id,
+        Replication::FullyReplicated | Replication::Majority => {
+            panic!("Expected a non-replicated request")
+        }
+    };
+    assert!(test.state().metadata.network_topology.subnets[&own_subnet]
+        .nodes
+        .contains(&designated_node));
+
+    // The request is charged for a single node.
+    let fee = test.non_replicated_http_request_fee(
+        http_request_context.variable_parts_size(),
+        Some(NumBytes::from(response_size_limit)),
+    );
+    assert!(
+        fee < test.http_request_fee(
+            http_request_context.variable_parts_size(),
+            Some(NumBytes::from(response_size_limit)),
+        )
+    );
+    assert_eq!(http_request_context.request.payment, payment - fee);
+}
+
+#[test]
+fn non_replicated_canister_http_response_is_marked_unverified() {
+    let http_response = ic00::CanisterHttpResponsePayload {
+        status: 200,
+        headers: vec![ic00::HttpHeader {
+            name: "content-type".to_string(),
+            value: "text/plain".to_string(),
+        }],
+        body: b"hello".to_vec(),
+    };
+    assert!(!http_response.is_unverified());
+
+    let marked = match super::mark_unverified_http_response(&Payload::Data(http_response.encode()))
+    {
+        Payload::Data(data) => ic00::CanisterHttpResponsePayload::decode(&data).unwrap(),
+        Payload::Reject(reject) => panic!("Unexpected reject: {:?}", reject),
+    };
+    assert!(marked.is_unverified());
+    assert_eq!(marked.status, http_response.status);
+    assert_eq!(marked.body, http_response.body);
+    assert_eq!(marked.headers[..1], http_response.headers[..]);
+
+    // Rejects and undecodable replies are passed through.
+    let reject = Payload::Reject(RejectContext::new(RejectCode::SysTransient, "timeout"));
+    assert_eq!(super::mark_unverified_http_response(&reject), reject);
+    let garbage = Payload::Data(vec![1, 2, 3]);
+    assert_eq!(super::mark_unverified_http_response(&garbage), garbage);
+}
+
 #[test]
 fn execute_canister_http_request_disabled() {
     let own_subnet = subnet_test_id(1);
@@ -2001,6 +2091,7 @@ fn execute_canister_http_request_disabled() {
             }),
             context: vec![0, 1, 2],
         }),
+        replication: None,
     };
 
     // Create request to HTTP_REQUEST method.
@@ -3214,6 +3305,43 @@ fn test_ingress_snapshot_rejected_because_feature_is_disabled() {
     }
 }
 
+#[test]
+fn test_canister_settings_timer_priority_default_normal() {
+    // Arrange.
+    let mut test = ExecutionTestBuilder::new().build();
+    let canister_id = test.create_canister(Cycles::new(1_000_000_000));
+    // Act.
+    let result = test.canister_status(canister_id);
+    let canister_status = CanisterStatusResultV2::decode(&get_reply(result)).unwrap();
+    // Assert.
+    assert_eq!(
+        canister_status.settings().timer_priority(),
+        TimerPriority::Normal
+    );
+}
+
+#[test]
+fn test_canister_settings_timer_priority_create_with_settings() {
+    // Arrange.
+    let mut test = ExecutionTestBuilder::new().build();
+    // Act.
+    let canister_id = test
+        .create_canister_with_settings(
+            Cycles::new(1_000_000_000),
+            ic00::CanisterSettingsArgsBuilder::new()
+                .with_timer_priority(TimerPriority::High)
+                .build(),
+        )
+        .unwrap();
+    let result = test.canister_status(canister_id);
+    let canister_status = CanisterStatusResultV2::decode(&get_reply(result)).unwrap();
+    // Assert.
+    assert_eq!(
+        canister_status.settings().timer_priority(),
+        TimerPriority::High
+    );
+}
+
 #[test]
 fn test_canister_settings_log_visibility_default_controllers() {
     // Arrange.
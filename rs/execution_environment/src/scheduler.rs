@@ -12,7 +12,7 @@ use ic_config::subnet_config::SchedulerConfig;
 use ic_crypto_prng::{Csprng, RandomnessPurpose::ExecutionThread};
 use ic_cycles_account_manager::CyclesAccountManager;
 use ic_error_types::{ErrorCode, UserError};
-use ic_ic00_types::{CanisterStatusType, EcdsaKeyId, Method as Ic00Method};
+use ic_ic00_types::{CanisterStatusType, EcdsaKeyId, Method as Ic00Method, TimerPriority};
 use ic_interfaces::execution_environment::{ExecutionRoundType, RegistryExecutionSettings};
 use ic_interfaces::execution_environment::{
     IngressHistoryWriter, Scheduler, SubnetAvailableMemory,
@@ -32,8 +32,9 @@ use ic_types::{
     crypto::canister_threshold_sig::MasterEcdsaPublicKey,
     ingress::{IngressState, IngressStatus},
     messages::{CanisterMessage, Ingress, MessageId, StopCanisterContext},
-    AccumulatedPriority, CanisterId, ComputeAllocation, Cycles, ExecutionRound, LongExecutionMode,
-    MemoryAllocation, NumBytes, NumInstructions, NumSlices, Randomness, SubnetId, Time,
+    AccumulatedPriority, CanisterId, CanisterTimer, ComputeAllocation, Cycles, ExecutionRound,
+    LongExecutionMode, MemoryAllocation, NumBytes, NumInstructions, NumSlices, Randomness,
+    SubnetId, Time,
 };
 use ic_types::{nominal_cycles::NominalCycles, NumMessages};
 use num_rational::Ratio;
@@ -43,6 +44,7 @@ use std::{
     collections::{BTreeMap, BTreeSet},
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 mod scheduler_metrics;
@@ -68,6 +70,10 @@ const SPAMMY_LOG_INTERVAL_ROUNDS: u64 = 10 * 60;
 /// work here.
 const SUBNET_MESSAGES_LIMIT_FRACTION: u64 = 16;
 
+/// Global timers that run later than this after their deadline are logged
+/// together with the ID of their canister.
+const LATE_GLOBAL_TIMER_LOG_THRESHOLD: Duration = Duration::from_secs(10);
+
 #[cfg(test)]
 pub(crate) mod test_utilities;
 #[cfg(test)]
@@ -83,6 +89,10 @@ struct SchedulerRoundLimits {
     /// Keeps track of remaining instruction to be used by subnet messages in this execution round.
     subnet_instructions: RoundInstructions,
 
+    /// Keeps track of remaining instructions that only `Heartbeat` and
+    /// `GlobalTimer` tasks may use after `instructions` is used up.
+    system_tasks_instructions: RoundInstructions,
+
     /// Keeps track of the available storage memory. It decreases if
     /// - Wasm execution grows the Wasm/stable memory.
     /// - Wasm execution pushes a new request to the output queue.
@@ -160,6 +170,7 @@ impl SchedulerImpl {
             (
                 Reverse(rs.long_execution_mode),
                 Reverse(rs.has_aborted_or_paused_execution),
+                Reverse(rs.has_due_prioritized_timer),
                 Reverse(rs.accumulated_priority),
                 rs.canister_id,
             )
@@ -177,6 +188,7 @@ impl SchedulerImpl {
         scheduler_cores: usize,
         current_round: ExecutionRound,
         accumulated_priority_reset_interval: ExecutionRound,
+        now: Time,
         canister_states: &mut BTreeMap<CanisterId, CanisterState>,
     ) -> RoundSchedule {
         let number_of_canisters = canister_states.len();
@@ -227,12 +239,21 @@ impl SchedulerImpl {
 
             let compute_allocation = canister.scheduler_state.compute_allocation;
             let accumulated_priority = canister.scheduler_state.accumulated_priority;
+            // Timers of canisters that opted into a high timer priority take
+            // precedence over other new executions, as long as the canister
+            // pays for a compute allocation.
+            let has_due_prioritized_timer = canister.system_state.timer_priority
+                == TimerPriority::High
+                && compute_allocation.as_percent() > 0
+                && canister.exports_global_timer_method()
+                && canister.system_state.global_timer.has_reached_deadline(now);
             round_states.push(CanisterRoundState {
                 canister_id,
                 accumulated_priority,
                 compute_allocation,
                 long_execution_mode: canister.scheduler_state.long_execution_mode,
                 has_aborted_or_paused_execution,
+                has_due_prioritized_timer,
             });
 
             total_compute_allocation_percent += compute_allocation.as_percent() as i64;
@@ -651,6 +672,7 @@ impl SchedulerImpl {
         let mut ingress_execution_results = Vec::new();
         let mut is_first_iteration = true;
         let mut round_filtered_canisters = FilteredCanisters::new();
+        let mut global_timer_lateness = vec![];
 
         let mut total_heap_delta = NumBytes::from(0);
 
@@ -706,6 +728,7 @@ impl SchedulerImpl {
             let measurement_scope =
                 MeasurementScope::nested(&self.metrics.round_inner_iteration, &measurement_scope);
             let mut round_limits = scheduler_round_limits.canister_round_limits();
+            let mut system_tasks_instructions = scheduler_round_limits.system_tasks_instructions;
             let preparation_timer = self.metrics.round_inner_iteration_prep.start_timer();
 
             // Add `Heartbeat` and `GlobalTimer` tasks to be executed before input messages.
@@ -742,7 +765,7 @@ impl SchedulerImpl {
             drop(preparation_timer);
 
             let execution_timer = self.metrics.round_inner_iteration_exe.start_timer();
-            let instructions_before = round_limits.instructions;
+            let instructions_before = round_limits.instructions + system_tasks_instructions;
             let (executed_canisters, mut loop_ingress_execution_results, heap_delta) = self
                 .execute_canisters_in_inner_round(
                     active_canisters_partitioned_by_cores,
@@ -751,9 +774,12 @@ impl SchedulerImpl {
                     Arc::new(state.metadata.network_topology.clone()),
                     &measurement_scope,
                     &mut round_limits,
+                    &mut system_tasks_instructions,
+                    &mut global_timer_lateness,
                     registry_settings.subnet_size,
                 );
-            let instructions_consumed = instructions_before - round_limits.instructions;
+            let instructions_consumed =
+                instructions_before - round_limits.instructions - system_tasks_instructions;
             drop(execution_timer);
 
             let finalization_timer = self.metrics.round_inner_iteration_fin.start_timer();
@@ -779,6 +805,7 @@ impl SchedulerImpl {
                     * state.num_canisters() as u64,
             );
             scheduler_round_limits.update_canister_round_limits(&round_limits);
+            scheduler_round_limits.system_tasks_instructions = system_tasks_instructions;
 
             if instructions_consumed == RoundInstructions::from(0) {
                 break state;
@@ -872,6 +899,9 @@ impl SchedulerImpl {
             .heap_delta_rate_limited_canisters_per_round
             .observe(round_filtered_canisters.rate_limited_canister_ids.len() as f64);
 
+        self.metrics
+            .observe_global_timer_lateness_by_canister(current_round, global_timer_lateness);
+
         (state, round_filtered_canisters.active_canister_ids)
     }
 
@@ -893,6 +923,8 @@ impl SchedulerImpl {
         network_topology: Arc<NetworkTopology>,
         measurement_scope: &MeasurementScope,
         round_limits: &mut RoundLimits,
+        system_tasks_instructions: &mut RoundInstructions,
+        global_timer_lateness: &mut Vec<(CanisterId, Duration)>,
         subnet_size: usize,
     ) -> (
         Vec<CanisterState>,
@@ -925,6 +957,11 @@ impl SchedulerImpl {
                 / self.config.scheduler_cores as i64),
             compute_allocation_used: round_limits.compute_allocation_used,
         };
+        // Distribute the instructions reserved for system tasks equally between
+        // the threads, so that the reserve is shared by the whole round.
+        let system_tasks_instructions_per_thread = RoundInstructions::from(
+            system_tasks_instructions.get().max(0) / self.config.scheduler_cores as i64,
+        );
         // Run canisters in parallel. The results will be stored in `results_by_thread`.
         thread_pool.scoped(|scope| {
             // Zip together the input and the output of each thread.
@@ -960,6 +997,7 @@ impl SchedulerImpl {
                         rate_limiting_of_heap_delta,
                         deterministic_time_slicing,
                         round_limits,
+                        system_tasks_instructions_per_thread,
                         subnet_size,
                     );
                 });
@@ -976,10 +1014,18 @@ impl SchedulerImpl {
         for mut result in results_by_thread.into_iter() {
             canisters.append(&mut result.canisters);
             ingress_results.append(&mut result.ingress_results);
+            global_timer_lateness.append(&mut result.global_timer_lateness);
             let instructions_executed = as_num_instructions(
                 round_limits_per_thread.instructions - result.round_limits.instructions,
             );
-            total_instructions_executed += instructions_executed;
+            let system_tasks_instructions_executed = as_num_instructions(
+                system_tasks_instructions_per_thread - result.system_tasks_instructions,
+            );
+            // Unlike the round limit, the reserve for system tasks is consumed
+            // by all threads together.
+            *system_tasks_instructions -= as_round_instructions(system_tasks_instructions_executed);
+            total_instructions_executed +=
+                instructions_executed + system_tasks_instructions_executed;
             max_instructions_executed_per_thread =
                 max_instructions_executed_per_thread.max(instructions_executed);
 
@@ -991,7 +1037,7 @@ impl SchedulerImpl {
             // Propagate the metrics from `execution_round_inner_iteration_thread`
             // to `execution_round_inner_iteration`.
             measurement_scope.add(
-                instructions_executed,
+                instructions_executed + system_tasks_instructions_executed,
                 result.slices_executed,
                 result.messages_executed,
             );
@@ -1293,6 +1339,7 @@ impl SchedulerImpl {
                         compute_allocation: Default::default(), // not used
                         long_execution_mode: canister.scheduler_state.long_execution_mode,
                         has_aborted_or_paused_execution: true,
+                        has_due_prioritized_timer: false, // not used
                     })
                 } else {
                     None
@@ -1539,6 +1586,9 @@ impl Scheduler for SchedulerImpl {
                 subnet_instructions: as_round_instructions(
                     self.config.max_instructions_per_round / SUBNET_MESSAGES_LIMIT_FRACTION,
                 ),
+                system_tasks_instructions: as_round_instructions(
+                    self.config.reserved_instructions_per_round_for_system_tasks,
+                ),
                 subnet_available_memory: self.exec_env.subnet_available_memory(&state),
                 compute_allocation_used: state.total_compute_allocation(),
             }
@@ -1646,6 +1696,7 @@ impl Scheduler for SchedulerImpl {
                 self.config.scheduler_cores,
                 current_round,
                 self.config.accumulated_priority_reset_interval,
+                state.time(),
                 &mut canisters,
             );
             state.put_canister_states(canisters);
@@ -1871,6 +1922,8 @@ struct ExecutionThreadResult {
     messages_executed: NumMessages,
     heap_delta: NumBytes,
     round_limits: RoundLimits,
+    system_tasks_instructions: RoundInstructions,
+    global_timer_lateness: Vec<(CanisterId, Duration)>,
 }
 
 /// Executes the given canisters one by one. For each canister it
@@ -1891,6 +1944,7 @@ fn execute_canisters_on_thread(
     rate_limiting_of_heap_delta: FlagStatus,
     deterministic_time_slicing: FlagStatus,
     mut round_limits: RoundLimits,
+    mut system_tasks_instructions: RoundInstructions,
     subnet_size: usize,
 ) -> ExecutionThreadResult {
     // Since this function runs on a helper thread, we cannot use a nested scope
@@ -1904,6 +1958,7 @@ fn execute_canisters_on_thread(
     let mut total_slices_executed = NumSlices::from(0);
     let mut total_messages_executed = NumMessages::from(0);
     let mut total_heap_delta = NumBytes::from(0);
+    let mut global_timer_lateness = vec![];
 
     let instruction_limits = InstructionLimits::new(
         deterministic_time_slicing,
@@ -1914,7 +1969,9 @@ fn execute_canisters_on_thread(
     for (rank, mut canister) in canisters_to_execute.into_iter().enumerate() {
         // If no more instructions are left or if heap delta is already too
         // large, then skip execution of the canister and keep its old state.
-        if round_limits.reached() || total_heap_delta >= config.max_heap_delta_per_iteration {
+        if !may_execute_next_task(&canister, &round_limits, system_tasks_instructions)
+            || total_heap_delta >= config.max_heap_delta_per_iteration
+        {
             canisters.push(canister);
             continue;
         }
@@ -1932,13 +1989,35 @@ fn execute_canisters_on_thread(
                 NextExecution::StartNew | NextExecution::ContinueLong => {}
             }
 
-            if round_limits.reached() {
+            if !may_execute_next_task(&canister, &round_limits, system_tasks_instructions) {
                 canister
                     .system_state
                     .canister_metrics
                     .interrupted_during_execution += 1;
                 break;
             }
+            if let (Some(ExecutionTask::GlobalTimer), CanisterTimer::Active(deadline)) = (
+                canister.system_state.task_queue.front(),
+                canister.system_state.global_timer,
+            ) {
+                let lateness_nanos = time
+                    .as_nanos_since_unix_epoch()
+                    .saturating_sub(deadline.as_nanos_since_unix_epoch());
+                let lateness = Duration::from_nanos(lateness_nanos);
+                metrics
+                    .global_timer_lateness
+                    .observe(lateness.as_secs_f64());
+                global_timer_lateness.push((canister.canister_id(), lateness));
+                if lateness > LATE_GLOBAL_TIMER_LOG_THRESHOLD {
+                    info!(
+                        logger,
+                        "Global timer of canister {} runs {:?} after its deadline",
+                        canister.canister_id(),
+                        lateness;
+                        messaging.canister_id => canister.canister_id().to_string(),
+                    );
+                }
+            }
             let measurement_scope = MeasurementScope::nested(
                 &metrics.round_inner_iteration_thread_message,
                 &measurement_scope,
@@ -1946,6 +2025,10 @@ fn execute_canisters_on_thread(
             let timer = metrics.msg_execution_duration.start_timer();
 
             let instructions_before = round_limits.instructions;
+            let is_system_task = matches!(
+                canister.system_state.task_queue.front(),
+                Some(ExecutionTask::Heartbeat) | Some(ExecutionTask::GlobalTimer)
+            );
             let canister_had_paused_execution = canister.has_paused_execution();
             let ExecuteCanisterResult {
                 canister: new_canister,
@@ -1966,6 +2049,16 @@ fn execute_canisters_on_thread(
             ingress_results.extend(ingress_status);
             let round_instructions_executed =
                 as_num_instructions(instructions_before - round_limits.instructions);
+            // System tasks that run past the round limit are paid for by the
+            // reserve, so that the reserve is not granted again in the same round.
+            if is_system_task && round_limits.reached() {
+                let overdraft = std::cmp::min(
+                    RoundInstructions::from(0) - round_limits.instructions,
+                    instructions_before - round_limits.instructions,
+                );
+                system_tasks_instructions -= overdraft;
+                round_limits.instructions += overdraft;
+            }
             let messages = NumMessages::from(instructions_used.map(|_| 1).unwrap_or(0));
             measurement_scope.add(round_instructions_executed, NumSlices::from(1), messages);
             if let Some(instructions_used) = instructions_used {
@@ -2026,6 +2119,8 @@ fn execute_canisters_on_thread(
         messages_executed: total_messages_executed,
         heap_delta: total_heap_delta,
         round_limits,
+        system_tasks_instructions,
+        global_timer_lateness,
     }
 }
 
@@ -2347,6 +2442,31 @@ fn get_instructions_limits_for_subnet_message(
     }
 }
 
+/// Returns true if the next task of the canister fits into the round limits.
+///
+/// Input messages and long executions may run only until the round limit is
+/// reached, while `Heartbeat` and `GlobalTimer` tasks may additionally use the
+/// remaining instructions reserved for system tasks.
+fn may_execute_next_task(
+    canister: &CanisterState,
+    round_limits: &RoundLimits,
+    system_tasks_instructions: RoundInstructions,
+) -> bool {
+    if !round_limits.reached() {
+        return true;
+    }
+    match canister.system_state.task_queue.front() {
+        Some(ExecutionTask::Heartbeat) | Some(ExecutionTask::GlobalTimer) => {
+            system_tasks_instructions > RoundInstructions::from(0)
+        }
+        Some(ExecutionTask::PausedExecution(..))
+        | Some(ExecutionTask::PausedInstallCode(..))
+        | Some(ExecutionTask::AbortedExecution { .. })
+        | Some(ExecutionTask::AbortedInstallCode { .. })
+        | None => false,
+    }
+}
+
 /// If the next execution method (`Message`, `Heartbeat` or `GlobalTimer) may be
 /// scheduled, it is added to the front of the canister's task queue, the
 /// canister ID is added to `heartbeat_and_timer_canister_ids` and `true` is
@@ -19,6 +19,10 @@ pub(super) struct CanisterRoundState {
     /// True when there is an aborted or paused long update execution.
     /// Note: this doesn't include paused or aborted install codes.
     pub(super) has_aborted_or_paused_execution: bool,
+    /// True when the canister has a non-zero compute allocation and its global
+    /// timer has reached the deadline. Such canisters are scheduled ahead of
+    /// other new executions, so that their timers run as early as possible.
+    pub(super) has_due_prioritized_timer: bool,
 }
 
 /// Represents three ordered active Canister ID groups to schedule.
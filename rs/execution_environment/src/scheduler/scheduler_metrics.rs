@@ -1,11 +1,11 @@
-use std::{collections::BTreeMap, time::Duration};
+use std::{collections::BTreeMap, sync::Mutex, time::Duration};
 
 use ic_metrics::{
     buckets::{decimal_buckets, decimal_buckets_with_zero, linear_buckets},
     MetricsRegistry,
 };
 use ic_replicated_state::canister_state::system_state::CyclesUseCase;
-use ic_types::nominal_cycles::NominalCycles;
+use ic_types::{nominal_cycles::NominalCycles, CanisterId, ExecutionRound};
 use prometheus::{
     Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
 };
@@ -38,6 +38,9 @@ pub(super) struct SchedulerMetrics {
     pub(super) expired_ingress_messages_count: IntCounter,
     pub(super) ingress_history_length: IntGauge,
     pub(super) msg_execution_duration: Histogram,
+    pub(super) global_timer_lateness: Histogram,
+    pub(super) global_timer_lateness_by_canister: GaugeVec,
+    late_global_timers: Mutex<LateGlobalTimers>,
     pub(super) registered_canisters: IntGaugeVec,
     pub(super) available_canister_ids: IntGauge,
     /// Metric `consumed_cycles_since_replica_started` is not
@@ -120,6 +123,22 @@ pub(super) const MESSAGE_KIND_CANISTER: &str = "canister";
 pub(super) const OLD_CALL_CONTEXT_CUTOFF_ONE_DAY: Duration = Duration::from_secs(60 * 60 * 24);
 pub(super) const OLD_CALL_CONTEXT_LABEL_ONE_DAY: &str = "1d";
 
+/// The maximum number of canisters whose global timer lateness is exported
+/// individually, to bound the cardinality of the metric.
+pub(super) const MAX_LATE_GLOBAL_TIMER_CANISTERS: usize = 20;
+
+/// The number of rounds over which the largest global timer lateness of each
+/// canister is tracked before the tracking starts over.
+pub(super) const LATE_GLOBAL_TIMERS_WINDOW_ROUNDS: u64 = 600;
+
+/// The largest global timer lateness of the latest canisters in the current
+/// window of rounds.
+#[derive(Default)]
+struct LateGlobalTimers {
+    window_start: Option<ExecutionRound>,
+    lateness: BTreeMap<CanisterId, Duration>,
+}
+
 impl SchedulerMetrics {
     pub(super) fn new(metrics_registry: &MetricsRegistry) -> Self {
         Self {
@@ -200,6 +219,20 @@ impl SchedulerMetrics {
                 "The duration of single message execution in seconds.",
                 metrics_registry,
             ),
+            global_timer_lateness: duration_histogram(
+                "scheduler_global_timer_lateness_seconds",
+                "The delay between the deadline of a canister global timer and \
+                      the start of its execution in seconds.",
+                metrics_registry,
+            ),
+            global_timer_lateness_by_canister: metrics_registry.gauge_vec(
+                "scheduler_global_timer_lateness_by_canister_seconds",
+                "The largest delay between the deadline of a global timer and the start \
+                      of its execution in seconds in the current window of rounds, for the \
+                      canisters with the latest timers.",
+                &["canister_id"],
+            ),
+            late_global_timers: Mutex::new(LateGlobalTimers::default()),
             registered_canisters: metrics_registry.int_gauge_vec(
                 "replicated_state_registered_canisters",
                 "Total number of canisters keyed by their current status.",
@@ -715,4 +748,46 @@ impl SchedulerMetrics {
     pub(super) fn observe_streams_response_bytes(&self, size_bytes: usize) {
         self.streams_response_bytes.set(size_bytes as i64);
     }
+
+    /// Records the lateness of the global timers executed in the given round
+    /// and exports the [`MAX_LATE_GLOBAL_TIMER_CANISTERS`] canisters with the
+    /// latest timers in the current window of rounds.
+    pub(super) fn observe_global_timer_lateness_by_canister(
+        &self,
+        round: ExecutionRound,
+        lateness: Vec<(CanisterId, Duration)>,
+    ) {
+        let mut late_timers = self.late_global_timers.lock().unwrap();
+        let window_expired = late_timers.window_start.map_or(true, |start| {
+            round.get() >= start.get().saturating_add(LATE_GLOBAL_TIMERS_WINDOW_ROUNDS)
+        });
+        if window_expired {
+            late_timers.window_start = Some(round);
+            late_timers.lateness.clear();
+        }
+        if lateness.is_empty() && !window_expired {
+            return;
+        }
+        for (canister_id, lateness) in lateness {
+            let max_lateness = late_timers.lateness.entry(canister_id).or_default();
+            *max_lateness = (*max_lateness).max(lateness);
+        }
+        if late_timers.lateness.len() > MAX_LATE_GLOBAL_TIMER_CANISTERS {
+            let mut latest: Vec<_> = late_timers
+                .lateness
+                .iter()
+                .map(|(canister_id, lateness)| (*canister_id, *lateness))
+                .collect();
+            latest.sort_by(|(_, a), (_, b)| b.cmp(a));
+            latest.truncate(MAX_LATE_GLOBAL_TIMER_CANISTERS);
+            late_timers.lateness = latest.into_iter().collect();
+        }
+
+        self.global_timer_lateness_by_canister.reset();
+        for (canister_id, lateness) in late_timers.lateness.iter() {
+            self.global_timer_lateness_by_canister
+                .with_label_values(&[&canister_id.to_string()])
+                .set(lateness.as_secs_f64());
+        }
+    }
 }
@@ -16,7 +16,7 @@ use ic_config::{
 use ic_error_types::RejectCode;
 use ic_ic00_types::{
     self as ic00, BoundedHttpHeaders, CanisterHttpResponsePayload, CanisterIdRecord,
-    CanisterStatusType, DerivationPath, EcdsaCurve, EmptyBlob, Method, Payload as _,
+    CanisterStatusType, DerivationPath, EcdsaCurve, EmptyBlob, Method, Payload as _, TimerPriority,
 };
 use ic_interfaces::execution_environment::SubnetAvailableMemory;
 use ic_logger::replica_logger::no_op_logger;
@@ -1634,6 +1634,246 @@ fn execute_global_timer_before_messages() {
     assert_eq!(test.ingress_queue_size(canister), 3);
 }
 
+#[test]
+fn global_timer_uses_reserved_instructions_after_round_limit() {
+    let mut test = SchedulerTestBuilder::new()
+        .with_scheduler_config(SchedulerConfig {
+            scheduler_cores: 2,
+            max_instructions_per_round: NumInstructions::new(100),
+            reserved_instructions_per_round_for_system_tasks: NumInstructions::new(1000),
+            max_instructions_per_message: NumInstructions::new(100),
+            max_instructions_per_message_without_dts: NumInstructions::new(100),
+            max_instructions_per_slice: NumInstructions::new(100),
+            instruction_overhead_per_message: NumInstructions::from(0),
+            instruction_overhead_per_canister: NumInstructions::from(0),
+            ..SchedulerConfig::application_subnet()
+        })
+        .build();
+    // With two cores and equal priorities, `canister0` and `timer_canister`
+    // share the first thread.
+    let canister0 = test.create_canister();
+    let canister1 = test.create_canister();
+    let timer_canister = test.create_canister_with(
+        Cycles::new(1_000_000_000_000),
+        ComputeAllocation::zero(),
+        MemoryAllocation::BestEffort,
+        Some(SystemMethod::CanisterGlobalTimer),
+        None,
+        None,
+    );
+    test.set_canister_global_timer(timer_canister, Time::from_nanos_since_unix_epoch(1));
+    test.set_time(Time::from_nanos_since_unix_epoch(1));
+
+    test.send_ingress(canister0, ingress(100));
+    test.send_ingress(canister0, ingress(100));
+    test.send_ingress(canister1, ingress(100));
+    test.expect_global_timer(timer_canister, instructions(1));
+    test.execute_round(ExecutionRoundType::OrdinaryRound);
+
+    // The round limit is reached after one message per thread, but the timer
+    // still runs using the reserved instructions.
+    let metrics = &test.scheduler().metrics;
+    assert_eq!(metrics.round_inner.messages.get_sample_sum(), 3.0);
+    assert_eq!(metrics.global_timer_lateness.get_sample_count(), 1);
+    assert_eq!(test.ingress_queue_size(canister0), 1);
+    assert_eq!(
+        test.canister_state(timer_canister)
+            .system_state
+            .global_timer,
+        CanisterTimer::Inactive
+    );
+}
+
+#[test]
+fn reserved_instructions_for_system_tasks_are_shared_by_threads() {
+    let mut test = SchedulerTestBuilder::new()
+        .with_scheduler_config(SchedulerConfig {
+            scheduler_cores: 2,
+            max_instructions_per_round: NumInstructions::new(100),
+            reserved_instructions_per_round_for_system_tasks: NumInstructions::new(100),
+            max_instructions_per_message: NumInstructions::new(100),
+            max_instructions_per_message_without_dts: NumInstructions::new(100),
+            max_instructions_per_slice: NumInstructions::new(100),
+            instruction_overhead_per_message: NumInstructions::from(0),
+            instruction_overhead_per_canister: NumInstructions::from(0),
+            ..SchedulerConfig::application_subnet()
+        })
+        .build();
+    let mut timer_canisters = vec![];
+    for _ in 0..4 {
+        let canister = test.create_canister_with(
+            Cycles::new(1_000_000_000_000),
+            ComputeAllocation::zero(),
+            MemoryAllocation::BestEffort,
+            Some(SystemMethod::CanisterGlobalTimer),
+            None,
+            None,
+        );
+        test.set_canister_global_timer(canister, Time::from_nanos_since_unix_epoch(1));
+        test.expect_global_timer(canister, instructions(60));
+        timer_canisters.push(canister);
+    }
+    test.set_time(Time::from_nanos_since_unix_epoch(1));
+    test.execute_round(ExecutionRoundType::OrdinaryRound);
+
+    // Each thread gets half of the reserve, which is used up by the first
+    // timer of the thread.
+    let metrics = &test.scheduler().metrics;
+    assert_eq!(metrics.global_timer_lateness.get_sample_count(), 2);
+    let active_timers = timer_canisters
+        .iter()
+        .filter(|canister| {
+            test.canister_state(**canister).system_state.global_timer != CanisterTimer::Inactive
+        })
+        .count();
+    assert_eq!(active_timers, 2);
+}
+
+#[test]
+fn due_global_timer_of_canister_with_compute_allocation_is_scheduled_first() {
+    let mut test = SchedulerTestBuilder::new()
+        .with_scheduler_config(SchedulerConfig {
+            scheduler_cores: 2,
+            instruction_overhead_per_message: NumInstructions::from(0),
+            instruction_overhead_per_canister: NumInstructions::from(0),
+            ..SchedulerConfig::application_subnet()
+        })
+        .build();
+    let canister0 = test.create_canister();
+    let canister1 = test.create_canister();
+    let timer_canister = test.create_canister_with(
+        Cycles::new(1_000_000_000_000),
+        ComputeAllocation::try_from(1).unwrap(),
+        MemoryAllocation::BestEffort,
+        Some(SystemMethod::CanisterGlobalTimer),
+        None,
+        None,
+    );
+    test.canister_state_mut(timer_canister)
+        .system_state
+        .timer_priority = TimerPriority::High;
+    // Skip the first round, which resets the accumulated priorities.
+    test.execute_round(ExecutionRoundType::OrdinaryRound);
+    test.canister_state_mut(timer_canister)
+        .scheduler_state
+        .accumulated_priority = AccumulatedPriority::new(-1_000_000);
+
+    test.set_canister_global_timer(timer_canister, Time::from_nanos_since_unix_epoch(1));
+    test.set_time(Time::from_nanos_since_unix_epoch(1));
+    test.send_ingress(canister0, ingress(1));
+    test.send_ingress(canister1, ingress(1));
+    test.expect_global_timer(timer_canister, instructions(1));
+    test.execute_round(ExecutionRoundType::OrdinaryRound);
+
+    // Despite its low accumulated priority, the canister with the due timer
+    // is the first canister of its thread.
+    assert_eq!(
+        test.canister_state(timer_canister)
+            .system_state
+            .canister_metrics
+            .scheduled_as_first,
+        1
+    );
+}
+
+#[test]
+fn due_global_timer_with_normal_priority_is_not_scheduled_first() {
+    let mut test = SchedulerTestBuilder::new()
+        .with_scheduler_config(SchedulerConfig {
+            scheduler_cores: 2,
+            instruction_overhead_per_message: NumInstructions::from(0),
+            instruction_overhead_per_canister: NumInstructions::from(0),
+            ..SchedulerConfig::application_subnet()
+        })
+        .build();
+    let canister0 = test.create_canister();
+    let canister1 = test.create_canister();
+    let timer_canister = test.create_canister_with(
+        Cycles::new(1_000_000_000_000),
+        ComputeAllocation::try_from(1).unwrap(),
+        MemoryAllocation::BestEffort,
+        Some(SystemMethod::CanisterGlobalTimer),
+        None,
+        None,
+    );
+    // Skip the first round, which resets the accumulated priorities.
+    test.execute_round(ExecutionRoundType::OrdinaryRound);
+    test.canister_state_mut(timer_canister)
+        .scheduler_state
+        .accumulated_priority = AccumulatedPriority::new(-1_000_000);
+
+    test.set_canister_global_timer(timer_canister, Time::from_nanos_since_unix_epoch(1));
+    test.set_time(Time::from_nanos_since_unix_epoch(1));
+    test.send_ingress(canister0, ingress(1));
+    test.send_ingress(canister1, ingress(1));
+    test.expect_global_timer(timer_canister, instructions(1));
+    test.execute_round(ExecutionRoundType::OrdinaryRound);
+
+    // Without a high timer priority, the compute allocation alone does not
+    // move the canister ahead of canisters with a higher accumulated priority.
+    assert_eq!(
+        test.canister_state(timer_canister)
+            .system_state
+            .canister_metrics
+            .scheduled_as_first,
+        0
+    );
+}
+
+#[test]
+fn global_timer_lateness_is_reported_per_canister() {
+    let mut test = SchedulerTestBuilder::new().build();
+    let timer_canister = test.create_canister_with(
+        Cycles::new(1_000_000_000_000),
+        ComputeAllocation::zero(),
+        MemoryAllocation::BestEffort,
+        Some(SystemMethod::CanisterGlobalTimer),
+        None,
+        None,
+    );
+    test.set_canister_global_timer(timer_canister, Time::from_nanos_since_unix_epoch(1));
+    test.set_time(Time::from_nanos_since_unix_epoch(1) + Duration::from_secs(5));
+    test.expect_global_timer(timer_canister, instructions(1));
+    test.execute_round(ExecutionRoundType::OrdinaryRound);
+
+    let metrics = &test.scheduler().metrics;
+    assert_eq!(
+        metrics
+            .global_timer_lateness_by_canister
+            .with_label_values(&[&timer_canister.to_string()])
+            .get(),
+        5.0
+    );
+}
+
+#[test]
+fn global_timer_lateness_by_canister_is_bounded() {
+    const METRIC: &str = "scheduler_global_timer_lateness_by_canister_seconds";
+    let metrics_registry = MetricsRegistry::new();
+    let metrics = SchedulerMetrics::new(&metrics_registry);
+    let lateness: Vec<_> = (0..2 * MAX_LATE_GLOBAL_TIMER_CANISTERS as u64)
+        .map(|i| (canister_test_id(i), Duration::from_secs(i)))
+        .collect();
+    metrics.observe_global_timer_lateness_by_canister(ExecutionRound::new(1), lateness);
+
+    let reported = fetch_gauge_vec(&metrics_registry, METRIC);
+    assert_eq!(reported.len(), MAX_LATE_GLOBAL_TIMER_CANISTERS);
+    // Only the canisters with the latest timers are reported.
+    assert!(reported
+        .values()
+        .all(|lateness| *lateness >= MAX_LATE_GLOBAL_TIMER_CANISTERS as f64));
+
+    // The tracking starts over in the next window.
+    metrics.observe_global_timer_lateness_by_canister(
+        ExecutionRound::new(1 + LATE_GLOBAL_TIMERS_WINDOW_ROUNDS),
+        vec![(canister_test_id(0), Duration::from_secs(1))],
+    );
+    assert_eq!(
+        fetch_gauge_vec(&metrics_registry, METRIC),
+        metric_vec(&[(&[("canister_id", &canister_test_id(0).to_string())], 1.0)])
+    );
+}
+
 #[test]
 fn test_drain_subnet_messages_with_some_long_running_canisters() {
     let mut test = SchedulerTestBuilder::new()
@@ -2275,6 +2515,7 @@ fn canister_is_stopped_if_timeout_occurs_and_ready_to_stop() {
             body: None,
             transform: None,
             max_response_bytes: None,
+            replication: None,
         })
         .unwrap();
 
@@ -3260,6 +3501,7 @@ fn consumed_cycles_http_outcalls_are_added_to_consumed_cycles_total() {
             }),
             context: transform_context,
         }),
+        replication: None,
     };
 
     // Create request to `HttpRequest` method.
@@ -507,6 +507,7 @@ fn simulate_http_request_cost(subnet_type: SubnetType, subnet_size: usize) -> Cy
                         }),
                         context: vec![],
                     }),
+                    replication: None,
                 })
                 .unwrap(),
             ),
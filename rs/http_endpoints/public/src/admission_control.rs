@@ -0,0 +1,550 @@
+//! Admission control for requests to /api/v2/canister/.../call and for
+//! request status lookups in /api/v2/canister/.../read_state
+//!
+//! Ingress messages are rejected with `429 Too Many Requests` instead of being
+//! submitted to the ingress pool if
+//!   * the subnet is overloaded, i.e. the ingress pool is full or execution
+//!     lags too far behind consensus, which is checked before validation, or
+//!   * the target canister or the sender have exhausted their token bucket.
+//!     An empty canister bucket already rejects requests before validation,
+//!     but tokens are only taken after validation, so that only authenticated
+//!     requests consume tokens.
+//!
+//! The limits are taken from the `ingress_admission_config` of the subnet
+//! record and are enforced independently by every replica.
+//!
+//! Every request ID looked up in the `request_status` paths of a read_state
+//! request is charged against a token bucket of the sender, see
+//! [`RequestStatusRateLimiter`].
+use crate::common::make_plaintext_response;
+use hyper::{header, Body, Response, StatusCode};
+use ic_registry_client_helpers::subnet::{IngressAdmissionSettings, RateLimit};
+use ic_types::{CanisterId, UserId};
+use lru::LruCache;
+use std::{
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use strum::IntoStaticStr;
+
+/// Upper bound on the number of token buckets kept per key type. When it is
+/// reached, the least recently used bucket is evicted, which can only make the
+/// limiter more permissive for the key of that bucket.
+const MAX_TRACKED_BUCKETS: usize = 100_000;
+
+/// The `Retry-After` hint used when the ingress pool is full and the subnet
+/// record does not configure admission control.
+pub(crate) const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub(crate) enum AdmissionRejectReason {
+    CanisterRateLimited,
+    SenderRateLimited,
+    RequestStatusRateLimited,
+    IngressPoolFull,
+    ExecutionBacklog,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct AdmissionRejection {
+    pub reason: AdmissionRejectReason,
+    pub retry_after: Duration,
+}
+
+impl AdmissionRejection {
+    /// Creates a `429 Too Many Requests` response with a `Retry-After` header
+    /// in whole seconds.
+    pub(crate) fn into_response(self) -> Response<Body> {
+        let retry_after_secs = seconds_rounded_up(self.retry_after);
+        let message = match self.reason {
+            AdmissionRejectReason::CanisterRateLimited => {
+                "Too many requests to the target canister, try again later."
+            }
+            AdmissionRejectReason::SenderRateLimited => {
+                "Too many requests from the sender, try again later."
+            }
+            AdmissionRejectReason::RequestStatusRateLimited => {
+                "Too many request status lookups from the sender, try again later."
+            }
+            AdmissionRejectReason::IngressPoolFull | AdmissionRejectReason::ExecutionBacklog => {
+                "Service is overloaded, try again later."
+            }
+        };
+        let mut response = make_plaintext_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            format!("{} Retry after {} seconds.", message, retry_after_secs),
+        );
+        response.headers_mut().insert(
+            header::RETRY_AFTER,
+            header::HeaderValue::from(retry_after_secs),
+        );
+        response
+    }
+}
+
+fn seconds_rounded_up(duration: Duration) -> u64 {
+    let secs = duration.as_secs() + u64::from(duration.subsec_nanos() > 0);
+    secs.max(1)
+}
+
+#[derive(Clone, Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * limit.messages_per_second as f64)
+            .min(limit.burst as f64);
+        self.last_refill = now;
+    }
+
+    /// Returns the time until `cost` tokens are available, or `None` if they
+    /// can be taken right away.
+    fn wait_time(&self, limit: RateLimit, cost: u64) -> Option<Duration> {
+        let cost = cost as f64;
+        if self.tokens >= cost {
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                (cost - self.tokens) / limit.messages_per_second as f64,
+            ))
+        }
+    }
+}
+
+struct TokenBuckets<K: Eq + Hash> {
+    buckets: LruCache<K, TokenBucket>,
+}
+
+impl<K: Eq + Hash> TokenBuckets<K> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            buckets: LruCache::new(capacity),
+        }
+    }
+
+    /// Returns the time until `cost` tokens become available in the bucket of
+    /// `key`, or `None` if they can be taken right away. Neither takes tokens
+    /// nor changes the eviction order.
+    fn wait_time(&self, key: &K, limit: RateLimit, cost: u64, now: Instant) -> Option<Duration> {
+        let mut bucket = self.buckets.peek(key)?.clone();
+        bucket.refill(limit, now);
+        bucket.wait_time(limit, cost)
+    }
+
+    /// Takes `cost` tokens from the bucket of `key`, or returns the time until
+    /// they become available.
+    fn try_acquire(
+        &mut self,
+        key: K,
+        limit: RateLimit,
+        cost: u64,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        let mut bucket = match self.buckets.pop(&key) {
+            Some(mut bucket) => {
+                bucket.refill(limit, now);
+                bucket
+            }
+            None => TokenBucket::full(limit, now),
+        };
+        let result = match bucket.wait_time(limit, cost) {
+            Some(wait_time) => Err(wait_time),
+            None => {
+                bucket.tokens -= cost as f64;
+                Ok(())
+            }
+        };
+        self.buckets.put(key, bucket);
+        result
+    }
+}
+
+/// Keeps track of the per-canister and per-sender token buckets.
+///
+/// Tokens are only taken after the signature of the request was verified, so
+/// that nobody can exhaust the bucket of another sender or of a canister with
+/// unauthenticated requests. Before validation, [`Self::check_canister`] only
+/// peeks at the bucket of the target canister, so that requests to a saturated
+/// canister are shed without paying for the signature verification.
+pub(crate) struct IngressRateLimiter {
+    per_canister: Mutex<TokenBuckets<CanisterId>>,
+    per_sender: Mutex<TokenBuckets<UserId>>,
+}
+
+impl Default for IngressRateLimiter {
+    fn default() -> Self {
+        Self {
+            per_canister: Mutex::new(TokenBuckets::new(MAX_TRACKED_BUCKETS)),
+            per_sender: Mutex::new(TokenBuckets::new(MAX_TRACKED_BUCKETS)),
+        }
+    }
+}
+
+impl IngressRateLimiter {
+    /// Rejects the request if the bucket of the target canister is empty,
+    /// without taking a token.
+    pub(crate) fn check_canister(
+        &self,
+        settings: &IngressAdmissionSettings,
+        canister_id: CanisterId,
+        now: Instant,
+    ) -> Result<(), AdmissionRejection> {
+        match settings.per_canister_rate_limit {
+            Some(limit) => {
+                match self
+                    .per_canister
+                    .lock()
+                    .unwrap()
+                    .wait_time(&canister_id, limit, 1, now)
+                {
+                    Some(retry_after) => Err(AdmissionRejection {
+                        reason: AdmissionRejectReason::CanisterRateLimited,
+                        retry_after,
+                    }),
+                    None => Ok(()),
+                }
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Takes a token from both the bucket of the sender and the bucket of the
+    /// target canister, or from neither of them if one of the buckets is
+    /// empty. The sender bucket is checked first, so that a throttled sender
+    /// cannot drain the bucket shared by all callers of the canister.
+    pub(crate) fn try_admit(
+        &self,
+        settings: &IngressAdmissionSettings,
+        canister_id: CanisterId,
+        sender: UserId,
+        now: Instant,
+    ) -> Result<(), AdmissionRejection> {
+        // Always lock the canister buckets first to avoid lock-order inversions.
+        let mut per_canister = self.per_canister.lock().unwrap();
+        let mut per_sender = self.per_sender.lock().unwrap();
+
+        if let Some(limit) = settings.per_sender_rate_limit {
+            if let Some(retry_after) = per_sender.wait_time(&sender, limit, 1, now) {
+                return Err(AdmissionRejection {
+                    reason: AdmissionRejectReason::SenderRateLimited,
+                    retry_after,
+                });
+            }
+        }
+        if let Some(limit) = settings.per_canister_rate_limit {
+            if let Some(retry_after) = per_canister.wait_time(&canister_id, limit, 1, now) {
+                return Err(AdmissionRejection {
+                    reason: AdmissionRejectReason::CanisterRateLimited,
+                    retry_after,
+                });
+            }
+        }
+
+        // Both buckets have a token, so neither acquisition below can fail.
+        if let Some(limit) = settings.per_sender_rate_limit {
+            per_sender
+                .try_acquire(sender, limit, 1, now)
+                .expect("BUG: the sender bucket was checked to have a token");
+        }
+        if let Some(limit) = settings.per_canister_rate_limit {
+            per_canister
+                .try_acquire(canister_id, limit, 1, now)
+                .expect("BUG: the canister bucket was checked to have a token");
+        }
+        Ok(())
+    }
+}
+
+/// Keeps track of the per-sender budgets for request status lookups.
+///
+/// Every distinct request ID in the `request_status` paths of a read_state
+/// request costs one token, so that batched lookups are metered like the
+/// equivalent number of individual requests. The requests are authenticated
+/// before they are charged.
+pub(crate) struct RequestStatusRateLimiter {
+    limit: RateLimit,
+    per_sender: Mutex<TokenBuckets<UserId>>,
+}
+
+impl RequestStatusRateLimiter {
+    pub(crate) fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            per_sender: Mutex::new(TokenBuckets::new(MAX_TRACKED_BUCKETS)),
+        }
+    }
+
+    /// Takes one token per request ID from the bucket of `sender`, or none if
+    /// the bucket does not hold enough tokens.
+    pub(crate) fn try_admit(
+        &self,
+        sender: UserId,
+        num_request_ids: usize,
+        now: Instant,
+    ) -> Result<(), AdmissionRejection> {
+        if num_request_ids == 0 {
+            return Ok(());
+        }
+        self.per_sender
+            .lock()
+            .unwrap()
+            .try_acquire(sender, self.limit, num_request_ids as u64, now)
+            .map_err(|retry_after| AdmissionRejection {
+                reason: AdmissionRejectReason::RequestStatusRateLimited,
+                retry_after,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_test_utilities::types::ids::{canister_test_id, user_test_id};
+
+    fn settings(
+        per_canister_rate_limit: Option<RateLimit>,
+        per_sender_rate_limit: Option<RateLimit>,
+    ) -> IngressAdmissionSettings {
+        IngressAdmissionSettings {
+            per_canister_rate_limit,
+            per_sender_rate_limit,
+            max_execution_backlog_blocks: None,
+            retry_after: DEFAULT_RETRY_AFTER,
+        }
+    }
+
+    #[test]
+    fn admits_burst_and_then_rejects_until_refilled() {
+        let limiter = IngressRateLimiter::default();
+        let settings = settings(
+            Some(RateLimit {
+                messages_per_second: 2,
+                burst: 3,
+            }),
+            None,
+        );
+        let now = Instant::now();
+
+        for i in 0..3 {
+            assert_eq!(
+                limiter.try_admit(&settings, canister_test_id(1), user_test_id(i), now),
+                Ok(())
+            );
+        }
+        let rejection = Err(AdmissionRejection {
+            reason: AdmissionRejectReason::CanisterRateLimited,
+            retry_after: Duration::from_millis(500),
+        });
+        assert_eq!(
+            limiter.check_canister(&settings, canister_test_id(1), now),
+            rejection
+        );
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(1), user_test_id(4), now),
+            rejection
+        );
+
+        // Other canisters have their own bucket.
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(2), user_test_id(1), now),
+            Ok(())
+        );
+
+        let later = now + Duration::from_millis(500);
+        assert_eq!(
+            limiter.check_canister(&settings, canister_test_id(1), later),
+            Ok(())
+        );
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(1), user_test_id(1), later),
+            Ok(())
+        );
+        assert!(limiter
+            .try_admit(&settings, canister_test_id(1), user_test_id(1), later)
+            .is_err());
+    }
+
+    #[test]
+    fn check_canister_does_not_take_tokens() {
+        let limiter = IngressRateLimiter::default();
+        let settings = settings(
+            Some(RateLimit {
+                messages_per_second: 1,
+                burst: 1,
+            }),
+            None,
+        );
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert_eq!(
+                limiter.check_canister(&settings, canister_test_id(1), now),
+                Ok(())
+            );
+        }
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(1), user_test_id(1), now),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn disabled_limits_admit_everything() {
+        let limiter = IngressRateLimiter::default();
+        let settings = settings(None, None);
+        let now = Instant::now();
+
+        for _ in 0..1000 {
+            assert_eq!(
+                limiter.check_canister(&settings, canister_test_id(1), now),
+                Ok(())
+            );
+            assert_eq!(
+                limiter.try_admit(&settings, canister_test_id(1), user_test_id(1), now),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn senders_are_limited_independently() {
+        let limiter = IngressRateLimiter::default();
+        let settings = settings(
+            None,
+            Some(RateLimit {
+                messages_per_second: 1,
+                burst: 1,
+            }),
+        );
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(1), user_test_id(1), now),
+            Ok(())
+        );
+        assert_eq!(
+            limiter
+                .try_admit(&settings, canister_test_id(1), user_test_id(1), now)
+                .unwrap_err()
+                .reason,
+            AdmissionRejectReason::SenderRateLimited
+        );
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(1), user_test_id(2), now),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn throttled_sender_does_not_drain_canister_bucket() {
+        let limiter = IngressRateLimiter::default();
+        let settings = settings(
+            Some(RateLimit {
+                messages_per_second: 1,
+                burst: 2,
+            }),
+            Some(RateLimit {
+                messages_per_second: 1,
+                burst: 1,
+            }),
+        );
+        let now = Instant::now();
+
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(1), user_test_id(1), now),
+            Ok(())
+        );
+        for _ in 0..10 {
+            assert_eq!(
+                limiter
+                    .try_admit(&settings, canister_test_id(1), user_test_id(1), now)
+                    .unwrap_err()
+                    .reason,
+                AdmissionRejectReason::SenderRateLimited
+            );
+        }
+        // The canister bucket still has a token for other senders.
+        assert_eq!(
+            limiter.try_admit(&settings, canister_test_id(1), user_test_id(2), now),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn least_recently_used_bucket_is_evicted() {
+        let limit = RateLimit {
+            messages_per_second: 1,
+            burst: 1,
+        };
+        let mut buckets = TokenBuckets::new(2);
+        let now = Instant::now();
+
+        assert_eq!(buckets.try_acquire(1, limit, 1, now), Ok(()));
+        assert_eq!(buckets.try_acquire(2, limit, 1, now), Ok(()));
+        assert!(buckets.try_acquire(1, limit, 1, now).is_err());
+        // Evicts the bucket of 2, which was used less recently than the one of 1.
+        assert_eq!(buckets.try_acquire(3, limit, 1, now), Ok(()));
+        assert_eq!(buckets.buckets.len(), 2);
+
+        assert!(buckets.try_acquire(1, limit, 1, now).is_err());
+        assert_eq!(buckets.try_acquire(2, limit, 1, now), Ok(()));
+    }
+
+    #[test]
+    fn request_status_lookups_are_charged_per_request_id() {
+        let limiter = RequestStatusRateLimiter::new(RateLimit {
+            messages_per_second: 10,
+            burst: 100,
+        });
+        let now = Instant::now();
+
+        assert_eq!(limiter.try_admit(user_test_id(1), 60, now), Ok(()));
+        // Only 40 tokens are left, so the whole lookup is rejected and does not
+        // take any tokens.
+        assert_eq!(
+            limiter.try_admit(user_test_id(1), 50, now),
+            Err(AdmissionRejection {
+                reason: AdmissionRejectReason::RequestStatusRateLimited,
+                retry_after: Duration::from_secs(1),
+            })
+        );
+        assert_eq!(limiter.try_admit(user_test_id(1), 40, now), Ok(()));
+        assert!(limiter.try_admit(user_test_id(1), 1, now).is_err());
+        // Lookups without request IDs are free.
+        assert_eq!(limiter.try_admit(user_test_id(1), 0, now), Ok(()));
+
+        // Other senders have their own budget.
+        assert_eq!(limiter.try_admit(user_test_id(2), 100, now), Ok(()));
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(limiter.try_admit(user_test_id(1), 10, later), Ok(()));
+    }
+
+    #[test]
+    fn rejection_response_has_retry_after_header() {
+        let response = AdmissionRejection {
+            reason: AdmissionRejectReason::ExecutionBacklog,
+            retry_after: Duration::from_millis(1500),
+        }
+        .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(
+            response.headers().get(header::RETRY_AFTER).unwrap(),
+            &header::HeaderValue::from(2u64)
+        );
+    }
+}
@@ -1,6 +1,9 @@
 //! Module that deals with requests to /api/v2/canister/.../call
 
 use crate::{
+    admission_control::{
+        AdmissionRejectReason, AdmissionRejection, IngressRateLimiter, DEFAULT_RETRY_AFTER,
+    },
     common::{
         get_cors_headers, make_plaintext_response, make_response, remove_effective_principal_id,
     },
@@ -14,13 +17,13 @@ use crossbeam::channel::Sender;
 use http::Request;
 use hyper::{Body, Response, StatusCode};
 use ic_crypto_interfaces_sig_verification::IngressSigVerifier;
-use ic_interfaces::ingress_pool::IngressPoolThrottler;
+use ic_interfaces::{consensus_pool::ConsensusPoolCache, ingress_pool::IngressPoolThrottler};
 use ic_interfaces_registry::RegistryClient;
 use ic_logger::{error, info_sample, replica_logger::no_op_logger, warn, ReplicaLogger};
 use ic_metrics::MetricsRegistry;
 use ic_registry_client_helpers::{
     provisional_whitelist::ProvisionalWhitelistRegistry,
-    subnet::{IngressMessageSettings, SubnetRegistry},
+    subnet::{IngressAdmissionSettings, IngressMessageSettings, SubnetRegistry},
 };
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_types::{
@@ -35,6 +38,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
 use std::task::{Context, Poll};
+use std::time::Instant;
 use tower::{Service, ServiceExt};
 
 #[derive(Clone)]
@@ -48,6 +52,8 @@ pub struct CallService {
     ingress_filter: IngressFilterService,
     ingress_throttler: Arc<RwLock<dyn IngressPoolThrottler + Send + Sync>>,
     ingress_tx: Sender<UnvalidatedArtifactMutation<IngressArtifact>>,
+    rate_limiter: Arc<IngressRateLimiter>,
+    consensus_pool_cache: Option<Arc<dyn ConsensusPoolCache>>,
 }
 
 pub struct CallServiceBuilder {
@@ -61,6 +67,7 @@ pub struct CallServiceBuilder {
     ingress_filter: IngressFilterService,
     ingress_throttler: Arc<RwLock<dyn IngressPoolThrottler + Send + Sync>>,
     ingress_tx: Sender<UnvalidatedArtifactMutation<IngressArtifact>>,
+    consensus_pool_cache: Option<Arc<dyn ConsensusPoolCache>>,
 }
 
 impl CallServiceBuilder {
@@ -84,6 +91,7 @@ impl CallServiceBuilder {
             ingress_filter,
             ingress_throttler,
             ingress_tx,
+            consensus_pool_cache: None,
         }
     }
 
@@ -102,6 +110,16 @@ impl CallServiceBuilder {
         self
     }
 
+    /// Enables shedding of ingress messages when execution lags behind
+    /// consensus, see [`IngressAdmissionSettings::max_execution_backlog_blocks`].
+    pub(crate) fn with_consensus_pool_cache(
+        mut self,
+        consensus_pool_cache: Arc<dyn ConsensusPoolCache>,
+    ) -> Self {
+        self.consensus_pool_cache = Some(consensus_pool_cache);
+        self
+    }
+
     pub fn build(self) -> CallService {
         let log = self.log.unwrap_or(no_op_logger());
         let default_metrics_registry = MetricsRegistry::default();
@@ -122,16 +140,74 @@ impl CallServiceBuilder {
             ingress_filter: self.ingress_filter,
             ingress_throttler: self.ingress_throttler,
             ingress_tx: self.ingress_tx,
+            rate_limiter: Arc::new(IngressRateLimiter::default()),
+            consensus_pool_cache: self.consensus_pool_cache,
         }
     }
 }
 
+impl CallService {
+    /// Checks whether the subnet is too loaded to accept further ingress
+    /// messages.
+    fn check_load(
+        &self,
+        admission_settings: Option<&IngressAdmissionSettings>,
+    ) -> Result<(), AdmissionRejection> {
+        let retry_after = admission_settings
+            .map(|settings| settings.retry_after)
+            .unwrap_or(DEFAULT_RETRY_AFTER);
+
+        if let (Some(max_backlog), Some(consensus_pool_cache)) = (
+            admission_settings.and_then(|settings| settings.max_execution_backlog_blocks),
+            self.consensus_pool_cache.as_ref(),
+        ) {
+            let finalized_block = consensus_pool_cache.finalized_block();
+            let backlog = finalized_block
+                .height
+                .get()
+                .saturating_sub(finalized_block.context.certified_height.get());
+            if backlog > max_backlog {
+                return Err(AdmissionRejection {
+                    reason: AdmissionRejectReason::ExecutionBacklog,
+                    retry_after,
+                });
+            }
+        }
+
+        if self.ingress_throttler.read().unwrap().exceeds_threshold() {
+            return Err(AdmissionRejection {
+                reason: AdmissionRejectReason::IngressPoolFull,
+                retry_after,
+            });
+        }
+        Ok(())
+    }
+}
+
+fn make_rejection_response(
+    metrics: &HttpHandlerMetrics,
+    rejection: AdmissionRejection,
+) -> Response<Body> {
+    metrics
+        .ingress_admission_rejections_total
+        .with_label_values(&[rejection.reason.into()])
+        .inc();
+    rejection.into_response()
+}
+
 fn get_registry_data(
     log: &ReplicaLogger,
     subnet_id: SubnetId,
     registry_version: RegistryVersion,
     registry_client: &dyn RegistryClient,
-) -> Result<(IngressMessageSettings, ProvisionalWhitelist), HttpError> {
+) -> Result<
+    (
+        IngressMessageSettings,
+        Option<IngressAdmissionSettings>,
+        ProvisionalWhitelist,
+    ),
+    HttpError,
+> {
     let settings = match registry_client.get_ingress_message_settings(subnet_id, registry_version) {
         Ok(Some(settings)) => settings,
         Ok(None) => {
@@ -158,6 +234,21 @@ fn get_registry_data(
         }
     };
 
+    let admission_settings = match registry_client
+        .get_ingress_admission_settings(subnet_id, registry_version)
+    {
+        Ok(settings) => settings,
+        Err(err) => {
+            warn!(
+                log,
+                "At registry version {}, get_ingress_admission_settings() failed with {}. Not applying admission control.",
+                registry_version,
+                err
+            );
+            None
+        }
+    };
+
     let provisional_whitelist = match registry_client.get_provisional_whitelist(registry_version) {
         Ok(Some(list)) => list,
         Ok(None) => {
@@ -171,7 +262,7 @@ fn get_registry_data(
             ProvisionalWhitelist::new_empty()
         }
     };
-    Ok((settings, provisional_whitelist))
+    Ok((settings, admission_settings, provisional_whitelist))
 }
 
 /// Handles a call to /api/v2/canister/../call
@@ -236,17 +327,18 @@ impl Service<Request<Bytes>> for CallService {
 
         let message_id = msg.id();
         let registry_version = self.registry_client.get_latest_version();
-        let (ingress_registry_settings, provisional_whitelist) = match get_registry_data(
-            &self.log,
-            self.subnet_id,
-            registry_version,
-            self.registry_client.as_ref(),
-        ) {
-            Ok((s, p)) => (s, p),
-            Err(HttpError { status, message }) => {
-                return Box::pin(async move { Ok(make_plaintext_response(status, message)) });
-            }
-        };
+        let (ingress_registry_settings, admission_settings, provisional_whitelist) =
+            match get_registry_data(
+                &self.log,
+                self.subnet_id,
+                registry_version,
+                self.registry_client.as_ref(),
+            ) {
+                Ok((s, a, p)) => (s, a, p),
+                Err(HttpError { status, message }) => {
+                    return Box::pin(async move { Ok(make_plaintext_response(status, message)) });
+                }
+            };
         if msg.count_bytes() > ingress_registry_settings.max_ingress_bytes_per_message {
             let res = make_plaintext_response(
                 StatusCode::PAYLOAD_TOO_LARGE,
@@ -260,12 +352,29 @@ impl Service<Request<Bytes>> for CallService {
             return Box::pin(async move { Ok(res) });
         }
 
+        // Shed load before spending any resources on validating the request.
+        // The canister bucket is only peeked at here: tokens are taken after
+        // validation, so that unauthenticated requests cannot drain it.
+        if let Err(rejection) = self.check_load(admission_settings.as_ref()).and_then(|()| {
+            match admission_settings.as_ref() {
+                Some(settings) => {
+                    self.rate_limiter
+                        .check_canister(settings, msg.canister_id(), Instant::now())
+                }
+                None => Ok(()),
+            }
+        }) {
+            let res = make_rejection_response(&self.metrics, rejection);
+            return Box::pin(async move { Ok(res) });
+        }
+
         let ingress_tx = self.ingress_tx.clone();
         let ingress_filter = self.ingress_filter.clone();
         let log = self.log.clone();
+        let metrics = self.metrics.clone();
         let validator_executor = self.validator_executor.clone();
         let node_id = self.node_id;
-        let ingress_throttler = self.ingress_throttler.clone();
+        let rate_limiter = self.rate_limiter.clone();
         Box::pin(async move {
             if let Err(http_err) = validator_executor
                 .validate_request(msg.as_ref().clone(), registry_version)
@@ -275,6 +384,21 @@ impl Service<Request<Bytes>> for CallService {
                 return Ok(res);
             }
 
+            // Tokens are only taken for validated requests, so that nobody can
+            // drain the bucket of another sender with forged requests. Senders
+            // with fresh keys or the anonymous principal are cheap to create,
+            // so only the per-canister limit bounds their combined rate.
+            if let Some(settings) = admission_settings.as_ref() {
+                if let Err(rejection) = rate_limiter.try_admit(
+                    settings,
+                    msg.canister_id(),
+                    msg.sender(),
+                    Instant::now(),
+                ) {
+                    return Ok(make_rejection_response(&metrics, rejection));
+                }
+            }
+
             match ingress_filter
                 .oneshot((provisional_whitelist, msg.content().clone()))
                 .await
@@ -288,15 +412,19 @@ impl Service<Request<Bytes>> for CallService {
 
             let ingress_log_entry = msg.log_entry();
 
-            let is_overloaded = ingress_throttler.read().unwrap().exceeds_threshold()
-                || ingress_tx
-                    .try_send(UnvalidatedArtifactMutation::Insert((msg, node_id)))
-                    .is_err();
+            let is_overloaded = ingress_tx
+                .try_send(UnvalidatedArtifactMutation::Insert((msg, node_id)))
+                .is_err();
 
             let response = if is_overloaded {
-                make_plaintext_response(
-                    StatusCode::TOO_MANY_REQUESTS,
-                    "Service is overloaded, try again later.".to_string(),
+                make_rejection_response(
+                    &metrics,
+                    AdmissionRejection {
+                        reason: AdmissionRejectReason::IngressPoolFull,
+                        retry_after: admission_settings
+                            .map(|settings| settings.retry_after)
+                            .unwrap_or(DEFAULT_RETRY_AFTER),
+                    },
                 )
             } else {
                 // We're pretty much done, just need to send the message to ingress and
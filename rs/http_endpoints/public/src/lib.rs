@@ -5,6 +5,7 @@
 //! As much as possible the naming of structs in this module should match the
 //! naming used in the [Interface
 //! Specification](https://sdk.dfinity.org/docs/interface-spec/index.html)
+mod admission_control;
 mod body;
 mod catch_up_package;
 mod common;
@@ -315,6 +316,7 @@ pub fn start_server(
                 .with_logger(log.clone())
                 .with_metrics(metrics.clone())
                 .with_malicious_flags(malicious_flags.clone())
+                .with_consensus_pool_cache(consensus_pool_cache.clone())
                 .build(),
             ),
     );
@@ -371,9 +373,11 @@ pub fn start_server(
     let status_service = StatusService::new_service(
         config.clone(),
         log.clone(),
+        subnet_id,
         nns_subnet_id,
         Arc::clone(&registry_client),
         Arc::clone(&health_status),
+        Arc::clone(&delegation_from_nns),
         state_reader_executor.clone(),
     );
     let dashboard_service =
@@ -1,5 +1,8 @@
-use ic_metrics::{buckets::decimal_buckets, MetricsRegistry};
-use prometheus::{HistogramVec, IntCounter, IntCounterVec};
+use ic_metrics::{
+    buckets::{decimal_buckets, decimal_buckets_with_zero},
+    MetricsRegistry,
+};
+use prometheus::{Histogram, HistogramVec, IntCounter, IntCounterVec};
 
 pub const LABEL_DETAIL: &str = "detail";
 pub const LABEL_PROTOCOL: &str = "protocol";
@@ -9,6 +12,7 @@ pub const LABEL_REQUEST_TYPE: &str = "request_type";
 pub const LABEL_STATUS: &str = "status";
 pub const LABEL_HEALTH_STATUS_BEFORE: &str = "before";
 pub const LABEL_HEALTH_STATUS_AFTER: &str = "after";
+pub const LABEL_REASON: &str = "reason";
 
 /// Placeholder used when we can't determine the appropriate prometheus label.
 pub const LABEL_UNKNOWN: &str = "unknown";
@@ -30,6 +34,10 @@ pub struct HttpHandlerMetrics {
     pub health_status_transitions_total: IntCounterVec,
     pub connection_setup_duration: HistogramVec,
     pub connection_duration: HistogramVec,
+    pub ingress_admission_rejections_total: IntCounterVec,
+    pub read_state_request_status_ids: Histogram,
+    pub read_state_request_status_ids_limit_exceeded_total: IntCounter,
+    pub read_state_request_status_rate_limited_total: IntCounter,
 }
 
 // There is a mismatch between the labels and the public spec.
@@ -92,6 +100,25 @@ impl HttpHandlerMetrics {
                 decimal_buckets(-2, 4),
                 &[LABEL_STATUS, LABEL_PROTOCOL],
             ),
+            ingress_admission_rejections_total: metrics_registry.int_counter_vec(
+                "replica_http_ingress_admission_rejections_total",
+                "Number of ingress messages rejected by admission control, by reason.",
+                &[LABEL_REASON],
+            ),
+            read_state_request_status_ids: metrics_registry.histogram(
+                "replica_http_read_state_request_status_ids",
+                "Number of distinct request IDs looked up by a single read_state request.",
+                // 0, 1, 2, 5, 10, 20, 50, 100, 200, 500
+                decimal_buckets_with_zero(0, 2),
+            ),
+            read_state_request_status_ids_limit_exceeded_total: metrics_registry.int_counter(
+                "replica_http_read_state_request_status_ids_limit_exceeded_total",
+                "Number of read_state requests rejected for looking up too many distinct request IDs.",
+            ),
+            read_state_request_status_rate_limited_total: metrics_registry.int_counter(
+                "replica_http_read_state_request_status_rate_limited_total",
+                "Number of read_state requests rejected because the sender exhausted its budget of request status lookups.",
+            ),
         }
     }
 }
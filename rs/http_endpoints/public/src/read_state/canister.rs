@@ -1,5 +1,6 @@
 use super::{parse_principal_id, verify_principal_ids};
 use crate::{
+    admission_control::RequestStatusRateLimiter,
     common::{cbor_response, into_cbor, make_plaintext_response, remove_effective_principal_id},
     metrics::LABEL_UNKNOWN,
     state_reader_executor::StateReaderExecutor,
@@ -17,6 +18,7 @@ use ic_interfaces_registry::RegistryClient;
 use ic_interfaces_state_manager::StateReader;
 use ic_logger::{error, replica_logger::no_op_logger, ReplicaLogger};
 use ic_metrics::MetricsRegistry;
+use ic_registry_client_helpers::subnet::RateLimit;
 use ic_replicated_state::{canister_state::execution_state::CustomSectionType, ReplicatedState};
 use ic_types::{
     malicious_flags::MaliciousFlags,
@@ -28,13 +30,29 @@ use ic_types::{
     CanisterId, PrincipalId, UserId,
 };
 use ic_validator::CanisterIdSet;
+use std::collections::BTreeSet;
 use std::convert::{Infallible, TryFrom};
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
 use std::task::{Context, Poll};
+use std::time::Instant;
 use tower::Service;
 
+/// The maximum number of distinct request IDs whose status can be looked up
+/// with a single read_state request. This bounds the size of the labeled tree
+/// and of the certificate returned for batched request status lookups.
+pub(crate) const MAX_REQUEST_STATUS_IDS_PER_READ_STATE: usize = 100;
+
+/// The number of request IDs a single sender can look up per second in
+/// request_status paths, and in a burst. The burst allows a sender to look up
+/// the maximum number of request IDs in several consecutive read_state
+/// requests.
+const REQUEST_STATUS_IDS_RATE_LIMIT_PER_SENDER: RateLimit = RateLimit {
+    messages_per_second: 1_000,
+    burst: 10 * MAX_REQUEST_STATUS_IDS_PER_READ_STATE as u64,
+};
+
 #[derive(Clone)]
 pub struct CanisterReadStateService {
     log: ReplicaLogger,
@@ -44,6 +62,7 @@ pub struct CanisterReadStateService {
     state_reader_executor: StateReaderExecutor,
     validator_executor: ValidatorExecutor<ReadState>,
     registry_client: Arc<dyn RegistryClient>,
+    request_status_rate_limiter: Arc<RequestStatusRateLimiter>,
 }
 
 pub struct CanisterReadStateServiceBuilder {
@@ -119,6 +138,9 @@ impl CanisterReadStateServiceBuilder {
                 log,
             ),
             registry_client: self.registry_client,
+            request_status_rate_limiter: Arc::new(RequestStatusRateLimiter::new(
+                REQUEST_STATUS_IDS_RATE_LIMIT_PER_SENDER,
+            )),
         }
     }
 }
@@ -194,6 +216,7 @@ impl Service<Request<Bytes>> for CanisterReadStateService {
         let state_reader_executor = self.state_reader_executor.clone();
         let validator_executor = self.validator_executor.clone();
         let metrics = self.metrics.clone();
+        let request_status_rate_limiter = self.request_status_rate_limiter.clone();
         Box::pin(async move {
             let targets_fut =
                 validator_executor.validate_request(request.clone(), registry_version);
@@ -221,14 +244,32 @@ impl Service<Request<Bytes>> for CanisterReadStateService {
                 };
 
             // Verify authorization for requested paths.
-            if let Err(HttpError { status, message }) = verify_paths(
+            let num_request_status_ids = match verify_paths(
                 certified_state_reader.get_state(),
                 &read_state.source,
                 &read_state.paths,
                 &targets,
                 effective_principal_id,
+                &metrics,
             ) {
-                return Ok(make_plaintext_response(status, message));
+                Ok(num_request_status_ids) => num_request_status_ids,
+                Err(HttpError { status, message }) => {
+                    return Ok(make_plaintext_response(status, message))
+                }
+            };
+            metrics
+                .read_state_request_status_ids
+                .observe(num_request_status_ids as f64);
+
+            // Charge the looked up request IDs to the sender before building
+            // the certificate, which is the expensive part of the request.
+            if let Err(rejection) = request_status_rate_limiter.try_admit(
+                read_state.source,
+                num_request_status_ids,
+                Instant::now(),
+            ) {
+                metrics.read_state_request_status_rate_limited_total.inc();
+                return Ok(rejection.into_response());
             }
 
             // Create labeled tree. This may be an expensive operation and by
@@ -273,14 +314,16 @@ impl Service<Request<Bytes>> for CanisterReadStateService {
 }
 
 // Verifies that the `user` is authorized to retrieve the `paths` requested.
+// Returns the number of distinct request IDs in `request_status` paths.
 fn verify_paths(
     state: &ReplicatedState,
     user: &UserId,
     paths: &[Path],
     targets: &CanisterIdSet,
     effective_principal_id: PrincipalId,
-) -> Result<(), HttpError> {
-    let mut request_status_id: Option<MessageId> = None;
+    metrics: &HttpHandlerMetrics,
+) -> Result<usize, HttpError> {
+    let mut request_status_ids = BTreeSet::new();
 
     // Convert the paths to slices to make it easier to match below.
     let paths: Vec<Vec<&[u8]>> = paths
@@ -322,15 +365,20 @@ fn verify_paths(
             {
                 // Verify that the request was signed by the same user.
                 if let Ok(message_id) = MessageId::try_from(*request_id) {
-                    if let Some(request_status_id) = request_status_id {
-                        if request_status_id != message_id {
-                            return Err(HttpError {
-                                status: StatusCode::BAD_REQUEST,
-                                message:
-                                    "Can only request a single request ID in request_status paths."
-                                        .to_string(),
-                            });
-                        }
+                    if request_status_ids.contains(&message_id) {
+                        continue;
+                    }
+                    if request_status_ids.len() >= MAX_REQUEST_STATUS_IDS_PER_READ_STATE {
+                        metrics
+                            .read_state_request_status_ids_limit_exceeded_total
+                            .inc();
+                        return Err(HttpError {
+                            status: StatusCode::BAD_REQUEST,
+                            message: format!(
+                                "Can request at most {} request IDs in request_status paths.",
+                                MAX_REQUEST_STATUS_IDS_PER_READ_STATE
+                            ),
+                        });
                     }
 
                     let ingress_status = state.get_ingress_status(&message_id);
@@ -356,7 +404,7 @@ fn verify_paths(
                         }
                     }
 
-                    request_status_id = Some(message_id);
+                    request_status_ids.insert(message_id);
                 } else {
                     return Err(HttpError {
                         status: StatusCode::BAD_REQUEST,
@@ -377,7 +425,7 @@ fn verify_paths(
         }
     }
 
-    Ok(())
+    Ok(request_status_ids.len())
 }
 
 fn can_read_canister_metadata(
@@ -559,6 +607,7 @@ mod test {
             CanisterQueues::default(),
             RawQueryStats::default(),
         );
+        let metrics = HttpHandlerMetrics::new(&MetricsRegistry::default());
         assert_eq!(
             verify_paths(
                 &state,
@@ -566,8 +615,9 @@ mod test {
                 &[Path::from(Label::from("time"))],
                 &CanisterIdSet::all(),
                 canister_test_id(1).get(),
+                &metrics,
             ),
-            Ok(())
+            Ok(0)
         );
         assert_eq!(
             verify_paths(
@@ -587,19 +637,81 @@ mod test {
                 ],
                 &CanisterIdSet::all(),
                 canister_test_id(1).get(),
+                &metrics,
             ),
-            Ok(())
+            Ok(1)
+        );
+        assert_eq!(
+            verify_paths(
+                &state,
+                &user_test_id(1),
+                &[
+                    Path::new(vec![Label::from("request_status"), [0; 32].into()]),
+                    Path::new(vec![Label::from("request_status"), [1; 32].into()])
+                ],
+                &CanisterIdSet::all(),
+                canister_test_id(1).get(),
+                &metrics,
+            ),
+            Ok(2)
+        );
+    }
+
+    #[test]
+    fn test_verify_path_limits_request_status_ids() {
+        let subnet_id = subnet_test_id(1);
+        let mut metadata = SystemMetadata::new(subnet_id, SubnetType::Application);
+        metadata.batch_time = mock_time();
+        let state = ReplicatedState::new_from_checkpoint(
+            BTreeMap::new(),
+            metadata,
+            CanisterQueues::default(),
+            RawQueryStats::default(),
+        );
+        let metrics = HttpHandlerMetrics::new(&MetricsRegistry::default());
+        let request_status_paths = |num_ids: usize| -> Vec<Path> {
+            (0..num_ids)
+                .map(|i| {
+                    let mut request_id = [0; 32];
+                    request_id[..8].copy_from_slice(&(i as u64).to_be_bytes());
+                    Path::new(vec![
+                        Label::from("request_status"),
+                        request_id.into(),
+                        Label::from("status"),
+                    ])
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            verify_paths(
+                &state,
+                &user_test_id(1),
+                &request_status_paths(MAX_REQUEST_STATUS_IDS_PER_READ_STATE),
+                &CanisterIdSet::all(),
+                canister_test_id(1).get(),
+                &metrics,
+            ),
+            Ok(MAX_REQUEST_STATUS_IDS_PER_READ_STATE)
+        );
+        assert_eq!(
+            verify_paths(
+                &state,
+                &user_test_id(1),
+                &request_status_paths(MAX_REQUEST_STATUS_IDS_PER_READ_STATE + 1),
+                &CanisterIdSet::all(),
+                canister_test_id(1).get(),
+                &metrics,
+            )
+            .unwrap_err()
+            .status,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            metrics
+                .read_state_request_status_ids_limit_exceeded_total
+                .get(),
+            1
         );
-        assert!(verify_paths(
-            &state,
-            &user_test_id(1),
-            &[
-                Path::new(vec![Label::from("request_status"), [0; 32].into()]),
-                Path::new(vec![Label::from("request_status"), [1; 32].into()])
-            ],
-            &CanisterIdSet::all(),
-            canister_test_id(1).get(),
-        )
-        .is_err());
     }
 }
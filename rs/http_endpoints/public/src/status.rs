@@ -1,23 +1,28 @@
 //! Module that deals with requests to /api/v2/status
-use crate::{common, state_reader_executor::StateReaderExecutor, EndpointService};
+use crate::{
+    common::{self, into_cbor},
+    state_reader_executor::StateReaderExecutor,
+    EndpointService,
+};
 use bytes::Bytes;
 use crossbeam::atomic::AtomicCell;
 use http::Request;
 use hyper::{Body, Response};
 use ic_config::http_handler::Config;
+use ic_crypto_tree_hash::{sparse_labeled_tree_from_paths, Label, Path};
 use ic_crypto_utils_threshold_sig_der::public_key_to_der;
 use ic_interfaces_registry::RegistryClient;
 use ic_logger::{warn, ReplicaLogger};
 use ic_types::{
-    messages::{Blob, HttpStatusResponse, ReplicaHealthStatus},
+    messages::{Blob, Certificate, CertificateDelegation, HttpStatusResponse, ReplicaHealthStatus},
     replica_version::REPLICA_BINARY_HASH,
-    ReplicaVersion, SubnetId,
+    Height, ReplicaVersion, SubnetId,
 };
 use std::{
     convert::Infallible,
     future::Future,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, RwLock},
     task::{Context, Poll},
 };
 use tower::{
@@ -31,27 +36,38 @@ const IC_API_VERSION: &str = "0.18.0";
 #[derive(Clone)]
 pub(crate) struct StatusService {
     log: ReplicaLogger,
+    subnet_id: SubnetId,
     nns_subnet_id: SubnetId,
     registry_client: Arc<dyn RegistryClient>,
     replica_health_status: Arc<AtomicCell<ReplicaHealthStatus>>,
+    delegation_from_nns: Arc<RwLock<Option<CertificateDelegation>>>,
     state_read_executor: StateReaderExecutor,
+    // The node keys certificate is only recomputed when the certified height
+    // changes, as the status endpoint is polled frequently.
+    node_keys_cache: Arc<RwLock<Option<(Height, Blob)>>>,
 }
 
 impl StatusService {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new_service(
         config: Config,
         log: ReplicaLogger,
+        subnet_id: SubnetId,
         nns_subnet_id: SubnetId,
         registry_client: Arc<dyn RegistryClient>,
         replica_health_status: Arc<AtomicCell<ReplicaHealthStatus>>,
+        delegation_from_nns: Arc<RwLock<Option<CertificateDelegation>>>,
         state_read_executor: StateReaderExecutor,
     ) -> EndpointService {
         let base_service = Self {
             log,
+            subnet_id,
             nns_subnet_id,
             registry_client,
             replica_health_status,
+            delegation_from_nns,
             state_read_executor,
+            node_keys_cache: Arc::new(RwLock::new(None)),
         };
         BoxCloneService::new(
             ServiceBuilder::new()
@@ -67,7 +83,7 @@ impl Service<Request<Bytes>> for StatusService {
     type Response = Response<Body>;
     type Error = Infallible;
     #[allow(clippy::type_complexity)]
-    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send + Sync>>;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
 
     fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
         Poll::Ready(Ok(()))
@@ -91,20 +107,83 @@ impl Service<Request<Bytes>> for StatusService {
                 .ok()
         });
 
-        let response = HttpStatusResponse {
-            ic_api_version: IC_API_VERSION.to_string(),
-            // For test networks, and networks that we still reset
-            // rather often, let them indicate the root public key
-            // in /api/v2/status, so that agents can fetch them.
-            // This is convenient, but of course NOT SECURE.
-            //
-            // USE WITH EXTREME CAUTION.
-            root_key: root_key.map(Blob),
-            impl_version: Some(ReplicaVersion::default().to_string()),
-            impl_hash: REPLICA_BINARY_HASH.get().map(|s| s.to_string()),
-            replica_health_status: Some(replica_health_status.load()),
-            certified_height: Some(self.state_read_executor.latest_certified_height()),
-        };
-        Box::pin(async move { Ok(common::cbor_response(&response).0) })
+        let certified_height = self.state_read_executor.latest_certified_height();
+        let subnet_id = self.subnet_id;
+        let delegation_from_nns = self.delegation_from_nns.clone();
+        let state_read_executor = self.state_read_executor.clone();
+        let node_keys_cache = self.node_keys_cache.clone();
+        Box::pin(async move {
+            // Node keys are only certified once the replica is healthy, i.e.
+            // once a certified state and the NNS delegation are available.
+            let node_keys = if replica_health_status.load() == ReplicaHealthStatus::Healthy {
+                get_node_keys_certificate(
+                    subnet_id,
+                    certified_height,
+                    delegation_from_nns,
+                    state_read_executor,
+                    node_keys_cache,
+                )
+                .await
+            } else {
+                None
+            };
+
+            let response = HttpStatusResponse {
+                ic_api_version: IC_API_VERSION.to_string(),
+                // For test networks, and networks that we still reset
+                // rather often, let them indicate the root public key
+                // in /api/v2/status, so that agents can fetch them.
+                // This is convenient, but of course NOT SECURE.
+                //
+                // USE WITH EXTREME CAUTION.
+                root_key: root_key.map(Blob),
+                impl_version: Some(ReplicaVersion::default().to_string()),
+                impl_hash: REPLICA_BINARY_HASH.get().map(|s| s.to_string()),
+                replica_health_status: Some(replica_health_status.load()),
+                certified_height: Some(certified_height),
+                node_keys,
+            };
+            Ok(common::cbor_response(&response).0)
+        })
     }
 }
+
+/// Returns a certificate for the `/subnet/<subnet_id>/node` subtree of the
+/// latest certified state, reusing the cached one if the certified height
+/// did not change.
+async fn get_node_keys_certificate(
+    subnet_id: SubnetId,
+    certified_height: Height,
+    delegation_from_nns: Arc<RwLock<Option<CertificateDelegation>>>,
+    state_read_executor: StateReaderExecutor,
+    node_keys_cache: Arc<RwLock<Option<(Height, Blob)>>>,
+) -> Option<Blob> {
+    if let Some((height, certificate)) = node_keys_cache.read().unwrap().as_ref() {
+        if *height == certified_height {
+            return Some(certificate.clone());
+        }
+    }
+
+    let paths = vec![
+        Path::new(vec![
+            Label::from("subnet"),
+            subnet_id.get().into(),
+            Label::from("node"),
+        ]),
+        Path::from(Label::from("time")),
+    ];
+    let labeled_tree = sparse_labeled_tree_from_paths(&paths).ok()?;
+    let (_state, tree, certification) = state_read_executor
+        .read_certified_state(labeled_tree)
+        .await
+        .ok()
+        .flatten()?;
+
+    let certificate = Blob(into_cbor(&Certificate {
+        tree,
+        signature: Blob(certification.signed.signature.signature.get().0),
+        delegation: delegation_from_nns.read().unwrap().clone(),
+    }));
+    *node_keys_cache.write().unwrap() = Some((certification.height, certificate.clone()));
+    Some(certificate)
+}
@@ -106,9 +106,26 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
 
         let basic_head = warp::head().and(warp::path("head")).map(warp::reply::reply);
 
+        let basic_put = warp::put()
+            .and(warp::path("put"))
+            .and(warp::body::json())
+            .map(|req: u64| Response::builder().body(req.to_string()));
+
+        let basic_patch = warp::patch()
+            .and(warp::path("patch"))
+            .and(warp::body::json())
+            .map(|req: u64| Response::builder().body(req.to_string()));
+
+        let basic_delete = warp::delete()
+            .and(warp::path("delete"))
+            .map(warp::reply::reply);
+
         let routes = basic_post
             .or(basic_get)
             .or(basic_head)
+            .or(basic_put)
+            .or(basic_patch)
+            .or(basic_delete)
             .or(get_response_size)
             .or(get_delay)
             .or(invalid_header);
@@ -220,6 +237,36 @@ MIGHAgEAMBMGByqGSM49AgEGCCqGSM49AwEHBG0wawIBAQQgob29X4H4m2XOkSZE
         assert_eq!(http_response.status, StatusCode::OK.as_u16() as u32);
     }
 
+    #[tokio::test]
+    async fn test_canister_http_server_put_patch_delete() {
+        let server_config = Config {
+            ..Default::default()
+        };
+
+        let url = start_server(CERT_INIT.get_or_init(generate_certs));
+        let mut client = spawn_grpc_server(server_config);
+
+        for (path, method, body) in [
+            ("put", HttpMethod::Put, "420"),
+            ("patch", HttpMethod::Patch, "421"),
+            ("delete", HttpMethod::Delete, ""),
+        ] {
+            let request = tonic::Request::new(CanisterHttpSendRequest {
+                url: format!("https://{}/{}", &url, path),
+                headers: Vec::new(),
+                method: method as i32,
+                body: body.as_bytes().to_vec(),
+                max_response_size_bytes: 512,
+                socks_proxy_allowed: false,
+            });
+
+            let response = client.canister_http_send(request).await;
+            let http_response = response.unwrap().into_inner();
+            assert_eq!(http_response.status, StatusCode::OK.as_u16() as u32);
+            assert_eq!(String::from_utf8_lossy(&http_response.content), body);
+        }
+    }
+
     #[tokio::test]
     async fn test_response_limit_exceeded() {
         // Check if response with higher than allowed response limit is rejected.
@@ -32,6 +32,14 @@ use tokio::{
 use tonic::{transport::Channel, Code};
 use tower::util::Oneshot;
 
+/// Appended to the adapter error of a failed request whose method is not
+/// idempotent, unless the adapter rejected the request before sending it.
+/// Every replica sends the request to the server, so it may have been processed
+/// even though the request failed, and possibly more than once.
+const NON_IDEMPOTENT_REQUEST_CAVEAT: &str = ". The request method is not idempotent: \
+    the server may have processed the request, possibly several times, \
+    so check its state before retrying.";
+
 /// This client is returned if we fail to make connection to canister http adapter.
 pub struct BrokenCanisterHttpClient {}
 
@@ -139,6 +147,7 @@ impl NonBlockingChannel<CanisterHttpRequest> for CanisterHttpAdapterClientImpl {
                     },
             } = canister_http_request;
 
+            let is_idempotent = request_http_method.is_idempotent();
             let adapter_req_timer = Instant::now();
             // Build future that sends and transforms request.
             let adapter_canister_http_response = http_adapter_client
@@ -148,6 +157,9 @@ impl NonBlockingChannel<CanisterHttpRequest> for CanisterHttpAdapterClientImpl {
                         CanisterHttpMethod::GET => HttpMethod::Get.into(),
                         CanisterHttpMethod::POST => HttpMethod::Post.into(),
                         CanisterHttpMethod::HEAD => HttpMethod::Head.into(),
+                        CanisterHttpMethod::PUT => HttpMethod::Put.into(),
+                        CanisterHttpMethod::PATCH => HttpMethod::Patch.into(),
+                        CanisterHttpMethod::DELETE => HttpMethod::Delete.into(),
                     },
                     max_response_size_bytes: request_max_response_bytes.unwrap_or(NumBytes::new(MAX_CANISTER_HTTP_RESPONSE_BYTES)).get(),
                     headers: request_headers
@@ -162,10 +174,13 @@ impl NonBlockingChannel<CanisterHttpRequest> for CanisterHttpAdapterClientImpl {
                     socks_proxy_allowed: matches!(subnet_type, SubnetType::System)
                 })
                 .map_err(|grpc_status| {
-                    (
-                        grpc_status_code_to_reject(grpc_status.code()),
-                        grpc_status.message().to_string(),
-                    )
+                    let mut message = grpc_status.message().to_string();
+                    // The adapter validates the request before sending it, so
+                    // invalid arguments never reach the server.
+                    if !is_idempotent && grpc_status.code() != Code::InvalidArgument {
+                        message.push_str(NON_IDEMPOTENT_REQUEST_CAVEAT);
+                    }
+                    (grpc_status_code_to_reject(grpc_status.code()), message)
                 })
                 .and_then(|adapter_response| async move {
 
@@ -329,7 +344,7 @@ mod tests {
     };
     use ic_test_utilities::types::messages::RequestBuilder;
     use ic_test_utilities_time::mock_time;
-    use ic_types::canister_http::Transform;
+    use ic_types::canister_http::{Replication, Transform};
     use ic_types::{
         canister_http::CanisterHttpMethod,
         messages::{Blob, CallbackId},
@@ -424,6 +439,7 @@ mod tests {
                     context: vec![],
                 }),
                 time: mock_time(),
+                replication: Replication::FullyReplicated,
             },
         }
     }
@@ -619,6 +635,87 @@ mod tests {
         }
     }
 
+    /// Test case where the adapter fails to make a non-idempotent request.
+    /// The error should warn that the server may have processed the request.
+    #[tokio::test]
+    async fn test_client_non_idempotent_request_error() {
+        let mock_grpc_channel =
+            setup_adapter_mock(Err((Code::Unavailable, "adapter unavailable".to_string()))).await;
+        let (svc, _handle) = setup_anonymous_query_mock();
+
+        let mut client = CanisterHttpAdapterClientImpl::new(
+            tokio::runtime::Handle::current(),
+            mock_grpc_channel,
+            svc,
+            100,
+            MetricsRegistry::default(),
+            SubnetType::Application,
+        );
+
+        let mut request = build_mock_canister_http_request(420, mock_time(), None);
+        request.context.http_method = CanisterHttpMethod::PATCH;
+        assert_eq!(client.send(request), Ok(()));
+        loop {
+            match client.try_receive() {
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+                Ok(r) => {
+                    assert_eq!(
+                        r,
+                        build_mock_canister_http_response_reject(
+                            420,
+                            mock_time(),
+                            RejectCode::SysTransient,
+                            format!("adapter unavailable{}", NON_IDEMPOTENT_REQUEST_CAVEAT)
+                        )
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Test case where the adapter rejects a non-idempotent request before
+    /// sending it. The error should not warn that the server may have processed it.
+    #[tokio::test]
+    async fn test_client_non_idempotent_request_invalid_argument() {
+        let mock_grpc_channel = setup_adapter_mock(Err((
+            Code::InvalidArgument,
+            "Url need to specify https scheme".to_string(),
+        )))
+        .await;
+        let (svc, _handle) = setup_anonymous_query_mock();
+
+        let mut client = CanisterHttpAdapterClientImpl::new(
+            tokio::runtime::Handle::current(),
+            mock_grpc_channel,
+            svc,
+            100,
+            MetricsRegistry::default(),
+            SubnetType::Application,
+        );
+
+        let mut request = build_mock_canister_http_request(420, mock_time(), None);
+        request.context.http_method = CanisterHttpMethod::POST;
+        assert_eq!(client.send(request), Ok(()));
+        loop {
+            match client.try_receive() {
+                Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+                Ok(r) => {
+                    assert_eq!(
+                        r,
+                        build_mock_canister_http_response_reject(
+                            420,
+                            mock_time(),
+                            RejectCode::SysFatal,
+                            "Url need to specify https scheme".to_string()
+                        )
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
     /// Test case where transformed response exceeds consensus limit.
     #[tokio::test]
     async fn test_client_transformed_limit() {
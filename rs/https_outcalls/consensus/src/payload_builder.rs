@@ -4,7 +4,10 @@ use crate::{
     metrics::CanisterHttpPayloadBuilderMetrics,
     payload_builder::{
         parse::bytes_to_payload,
-        utils::{group_shares_by_callback_id, grouped_shares_meet_divergence_criteria},
+        utils::{
+            divergence_report, group_shares_by_callback_id,
+            grouped_shares_meet_divergence_criteria, majority_threshold,
+        },
     },
 };
 use ic_consensus_utils::{
@@ -32,7 +35,7 @@ use ic_types::{
     canister_http::{
         CanisterHttpResponse, CanisterHttpResponseContent, CanisterHttpResponseDivergence,
         CanisterHttpResponseMetadata, CanisterHttpResponseProof, CanisterHttpResponseWithConsensus,
-        CANISTER_HTTP_MAX_RESPONSES_PER_BLOCK, CANISTER_HTTP_TIMEOUT_INTERVAL,
+        Replication, CANISTER_HTTP_MAX_RESPONSES_PER_BLOCK, CANISTER_HTTP_TIMEOUT_INTERVAL,
     },
     consensus::Committee,
     crypto::Signed,
@@ -42,7 +45,7 @@ use ic_types::{
     CanisterId, CountBytes, Cycles, Height, NodeId, NumBytes, RegistryVersion, SubnetId,
 };
 use std::{
-    collections::{BTreeSet, HashSet},
+    collections::{BTreeMap, BTreeSet, HashSet},
     mem::size_of,
     sync::{Arc, RwLock},
 };
@@ -54,6 +57,13 @@ mod proptests;
 mod tests;
 mod utils;
 
+/// Appended to the rejects of timed out requests and of requests whose responses
+/// diverged. Such requests may have reached the server, which matters for
+/// requests whose method is not idempotent.
+const SERVER_MAY_HAVE_PROCESSED_REQUEST: &str = " The server may have processed the request, \
+    possibly several times, so check its state before retrying a request whose method is not \
+    idempotent.";
+
 /// Statistics about the number of canister http message types in a canister http payload
 #[derive(Debug, Default)]
 pub struct CanisterHttpBatchStats {
@@ -183,13 +193,14 @@ impl CanisterHttpPayloadBuilderImpl {
                 }
             };
 
-        let faults_tolerated = match self.membership.get_canister_http_committee(height) {
-            Ok(members) => ic_types::consensus::get_faults_tolerated(members.len()),
+        let committee_size = match self.membership.get_canister_http_committee(height) {
+            Ok(members) => members.len(),
             _ => {
                 warn!(self.log, "Failed to get canister http committee");
                 return CanisterHttpPayload::default();
             }
         };
+        let faults_tolerated = ic_types::consensus::get_faults_tolerated(committee_size);
 
         let mut accumulated_size = 0;
         let mut responses_included = 0;
@@ -197,6 +208,10 @@ impl CanisterHttpPayloadBuilderImpl {
         let mut candidates = vec![];
         let mut timeouts = vec![];
         let mut divergence_responses = vec![];
+        // The designated nodes of the outstanding non-replicated requests
+        let mut non_replicated_requests = BTreeMap::new();
+        // The outstanding requests that only need a majority to agree on the response
+        let mut majority_requests = BTreeSet::new();
 
         // Metrics counters
         let mut unique_includable_responses = 0;
@@ -223,6 +238,15 @@ impl CanisterHttpPayloadBuilderImpl {
                 .canister_http_request_contexts
                 .iter()
             {
+                match request.replication {
+                    Replication::FullyReplicated => (),
+                    Replication::NonReplicated(designated_node) => {
+                        non_replicated_requests.insert(*callback_id, designated_node);
+                    }
+                    Replication::Majority => {
+                        majority_requests.insert(*callback_id);
+                    }
+                }
                 unique_includable_responses += 1;
                 let candidate_size = callback_id.count_bytes();
                 let size = NumBytes::new((accumulated_size + candidate_size) as u64);
@@ -276,7 +300,33 @@ impl CanisterHttpPayloadBuilderImpl {
 
             let candidates_and_divergences = response_candidates_by_callback_id
                 .into_iter()
-                .filter_map(|(_, grouped_shares)| {
+                .filter_map(|(callback_id, grouped_shares)| {
+                    if let Some(designated_node) = non_replicated_requests.get(&callback_id) {
+                        // The response to a non-replicated request only needs the share
+                        // of the designated node, shares of other nodes are ignored.
+                        return grouped_shares.iter().find_map(|(metadata, shares)| {
+                            unique_responses_count += 1;
+                            let share = shares
+                                .iter()
+                                .find(|share| share.signature.signer == *designated_node)?;
+                            pool_access
+                                .get_response_content_by_hash(&metadata.content_hash)
+                                .map(|content| {
+                                    CandidateOrDivergence::Candidate((
+                                        metadata.clone(),
+                                        BTreeSet::from([share.signature.clone()]),
+                                        content,
+                                    ))
+                                })
+                        });
+                    }
+                    let (threshold, max_diverging_signers) =
+                        if majority_requests.contains(&callback_id) {
+                            let threshold = majority_threshold(committee_size);
+                            (threshold, committee_size - threshold)
+                        } else {
+                            (threshold, faults_tolerated)
+                        };
                     if let Some((metadata, shares)) = grouped_shares.iter().find(|(_, shares)| {
                         unique_responses_count += 1;
                         let signers: BTreeSet<_> =
@@ -300,7 +350,7 @@ impl CanisterHttpPayloadBuilderImpl {
                         // so now we check whether we have divergence.
                         if grouped_shares_meet_divergence_criteria(
                             &grouped_shares,
-                            faults_tolerated,
+                            max_diverging_signers,
                         ) {
                             Some(CandidateOrDivergence::Divergence(
                                 CanisterHttpResponseDivergence {
@@ -494,12 +544,35 @@ impl CanisterHttpPayloadBuilderImpl {
                     valid_signers,
                 });
             }
-            if valid_signers.len() < threshold {
-                return permanent_error(CanisterHttpPermanentValidationError::NotEnoughSigners {
-                    committee,
-                    signers: valid_signers,
-                    expected_threshold: threshold,
-                });
+            let replication = http_contexts
+                .get(&response.content.id)
+                .map(|context| context.replication)
+                .unwrap_or_default();
+            if let Replication::NonReplicated(designated_node) = replication {
+                // The response to a non-replicated request is signed by the designated node alone
+                if valid_signers != [designated_node] {
+                    return permanent_error(
+                        CanisterHttpPermanentValidationError::NonReplicatedResponseSignerMismatch {
+                            designated_node,
+                            signers: valid_signers,
+                        },
+                    );
+                }
+            } else {
+                let threshold = if replication == Replication::Majority {
+                    majority_threshold(committee.len())
+                } else {
+                    threshold
+                };
+                if valid_signers.len() < threshold {
+                    return permanent_error(
+                        CanisterHttpPermanentValidationError::NotEnoughSigners {
+                            committee,
+                            signers: valid_signers,
+                            expected_threshold: threshold,
+                        },
+                    );
+                }
             }
             self.crypto
                 .verify_aggregate(&response.proof, consensus_registry_version)
@@ -510,13 +583,7 @@ impl CanisterHttpPayloadBuilderImpl {
                 })?;
         }
 
-        let faults_tolerated = match self.membership.get_canister_http_committee(height) {
-            Ok(members) => ic_types::consensus::get_faults_tolerated(members.len()),
-            _ => {
-                warn!(self.log, "Failed to get canister http committee");
-                return transient_error(CanisterHttpTransientValidationError::Membership);
-            }
-        };
+        let faults_tolerated = ic_types::consensus::get_faults_tolerated(committee.len());
 
         for response in &payload.divergence_responses {
             let (valid_signers, invalid_signers): (Vec<NodeId>, Vec<NodeId>) = response
@@ -549,8 +616,24 @@ impl CanisterHttpPayloadBuilderImpl {
                     CanisterHttpPermanentValidationError::DivergenceProofContainsMultipleCallbackIds
                 );
             }
-            for (_, grouped_shares) in grouped_shares {
-                if !grouped_shares_meet_divergence_criteria(&grouped_shares, faults_tolerated) {
+            for (callback_id, grouped_shares) in grouped_shares {
+                let replication = http_contexts
+                    .get(&callback_id)
+                    .map(|context| context.replication)
+                    .unwrap_or_default();
+                let max_diverging_signers = match replication {
+                    Replication::FullyReplicated => faults_tolerated,
+                    Replication::Majority => committee.len() - majority_threshold(committee.len()),
+                    // Only the designated node responds to a non-replicated request, so there
+                    // is nothing to diverge from
+                    Replication::NonReplicated(_) => {
+                        return permanent_error(
+                            CanisterHttpPermanentValidationError::DivergenceProofDoesNotMeetDivergenceCriteria
+                        );
+                    }
+                };
+                if !grouped_shares_meet_divergence_criteria(&grouped_shares, max_diverging_signers)
+                {
                     return permanent_error(
                         CanisterHttpPermanentValidationError::DivergenceProofDoesNotMeetDivergenceCriteria
                     );
@@ -667,7 +750,10 @@ impl IntoMessages<(Vec<Response>, CanisterHttpBatchStats)> for CanisterHttpPaylo
                 *timeout,
                 Payload::Reject(RejectContext::new(
                     RejectCode::SysTransient,
-                    "Canister http request timed out",
+                    format!(
+                        "Canister http request timed out.{}",
+                        SERVER_MAY_HAVE_PROCESSED_REQUEST
+                    ),
                 )),
             )
         });
@@ -684,9 +770,12 @@ impl IntoMessages<(Vec<Response>, CanisterHttpBatchStats)> for CanisterHttpPaylo
                     share.content.id,
                     Payload::Reject(RejectContext::new(
                         RejectCode::SysTransient,
-                        "Canister http responses were different across replicas, \
-                          and no consensus was reached"
-                            .to_string(),
+                        format!(
+                            "Canister http responses were different across replicas, \
+                             and no consensus was reached.{}{}",
+                            SERVER_MAY_HAVE_PROCESSED_REQUEST,
+                            divergence_report(response).to_reject_message_suffix()
+                        ),
                     )),
                 )
             })
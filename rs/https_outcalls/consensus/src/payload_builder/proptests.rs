@@ -9,7 +9,7 @@ use ic_test_utilities_time::mock_time;
 use ic_types::{
     canister_http::{
         CanisterHttpReject, CanisterHttpResponse, CanisterHttpResponseContent,
-        CanisterHttpResponseMetadata, CanisterHttpResponseShare,
+        CanisterHttpResponseMetadata, CanisterHttpResponseShare, CanisterHttpResponseSummary,
     },
     crypto::{crypto_hash, CryptoHash, CryptoHashOf},
     messages::CallbackId,
@@ -161,6 +161,7 @@ fn prop_response_with_shares(
                 timeout: response.timeout,
                 content_hash: crypto_hash(&response),
                 registry_version: RegistryVersion::new(1),
+                summary: CanisterHttpResponseSummary::from(&response.content),
             };
             let shares = metadata_to_shares(num_shares, &metadata);
             (response, shares)
@@ -207,6 +208,7 @@ fn prop_random_metadata(max_timeout: u64) -> impl Strategy<Value = CanisterHttpR
             timeout: mock_time() + Duration::from_millis(timeout),
             content_hash: CryptoHashOf::new(CryptoHash(hash.to_vec())),
             registry_version: RegistryVersion::new(1),
+            summary: CanisterHttpResponseSummary::default(),
         }
     })
 }
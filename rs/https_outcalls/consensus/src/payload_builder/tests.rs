@@ -8,8 +8,10 @@ use crate::payload_builder::parse::{bytes_to_payload, payload_to_bytes};
 use super::CanisterHttpPayloadBuilderImpl;
 use ic_artifact_pool::canister_http_pool::CanisterHttpPoolImpl;
 use ic_consensus_mocks::{dependencies_with_subnet_params, Dependencies};
+use ic_error_types::RejectCode;
+use ic_ic00_types::{CanisterHttpDivergenceReport, CanisterHttpDivergentResponse};
 use ic_interfaces::{
-    batch_payload::{BatchPayloadBuilder, PastPayload, ProposalContext},
+    batch_payload::{BatchPayloadBuilder, IntoMessages, PastPayload, ProposalContext},
     canister_http::{
         CanisterHttpChangeAction, CanisterHttpChangeSet, CanisterHttpPermanentValidationError,
         CanisterHttpTransientValidationError,
@@ -34,14 +36,14 @@ use ic_types::{
     artifact_kind::CanisterHttpArtifact,
     batch::{CanisterHttpPayload, ValidationContext},
     canister_http::{
-        CanisterHttpMethod, CanisterHttpRequestContext, CanisterHttpResponse,
+        CanisterHttpMethod, CanisterHttpReject, CanisterHttpRequestContext, CanisterHttpResponse,
         CanisterHttpResponseContent, CanisterHttpResponseDivergence, CanisterHttpResponseMetadata,
-        CanisterHttpResponseShare, CanisterHttpResponseWithConsensus,
-        CANISTER_HTTP_MAX_RESPONSES_PER_BLOCK, CANISTER_HTTP_TIMEOUT_INTERVAL,
+        CanisterHttpResponseShare, CanisterHttpResponseSummary, CanisterHttpResponseWithConsensus,
+        Replication, CANISTER_HTTP_MAX_RESPONSES_PER_BLOCK, CANISTER_HTTP_TIMEOUT_INTERVAL,
     },
     consensus::get_faults_tolerated,
     crypto::{crypto_hash, BasicSig, BasicSigOf, CryptoHash, CryptoHashOf, Signed},
-    messages::CallbackId,
+    messages::{CallbackId, Payload, RejectContext},
     registry::RegistryClientError,
     signature::{BasicSignature, BasicSignatureBatch},
     time::UNIX_EPOCH,
@@ -328,6 +330,7 @@ fn timeout_priority() {
                     transform: None,
                     // this is the important one
                     time: mock_time(),
+                    replication: Replication::FullyReplicated,
                 };
                 init_state
                     .metadata
@@ -555,6 +558,27 @@ fn hash_validation() {
     }
 }
 
+/// Test that payloads whose signed summary does not match the content don't validate
+#[test]
+fn summary_validation() {
+    let validation_result = run_validatation_test(
+        true,
+        |_, metadata| {
+            // Sign a summary that does not describe the content
+            metadata.summary.size += 1;
+        },
+        &default_validation_context(),
+    );
+    match validation_result {
+        Err(ValidationError::Permanent(
+            PayloadPermanentError::CanisterHttpPayloadValidationError(
+                CanisterHttpPermanentValidationError::SummaryMismatch { .. },
+            ),
+        )) => (),
+        x => panic!("Expected SummaryMismatch, got {:?}", x),
+    }
+}
+
 /// Test that payloads which are timed out don't validate
 #[test]
 fn timeout_validation() {
@@ -761,6 +785,257 @@ fn divergence_response_validation_test() {
     }
 }
 
+/// Check that the response to a non-replicated request is included with the share of the
+/// designated node alone, and that responses signed by other nodes do not validate.
+#[test]
+fn non_replicated_request_test() {
+    let context = default_validation_context();
+    let designated_node = 2;
+
+    test_config_with_http_feature(true, 4, |mut payload_builder, canister_http_pool| {
+        set_http_request_contexts(
+            &mut payload_builder,
+            vec![(0, Replication::NonReplicated(node_test_id(designated_node)))],
+        );
+
+        let (response, metadata) = test_response_and_metadata(0);
+        {
+            // Add the share of the designated node only
+            let mut pool_access = canister_http_pool.write().unwrap();
+            add_own_share_to_pool(
+                pool_access.deref_mut(),
+                &metadata_to_share(designated_node, &metadata),
+                &response,
+            );
+        }
+
+        let payload = payload_builder.build_payload(
+            Height::new(1),
+            NumBytes::new(4 * 1024 * 1024),
+            &[],
+            &context,
+        );
+        let parsed_payload = bytes_to_payload(&payload).expect("Failed to parse the payload");
+        assert_eq!(parsed_payload.num_responses(), 1);
+        assert_eq!(parsed_payload.responses[0].content, response);
+        assert_eq!(
+            parsed_payload.responses[0]
+                .proof
+                .signature
+                .signatures_map
+                .keys()
+                .collect::<Vec<_>>(),
+            vec![&node_test_id(designated_node)]
+        );
+        assert!(payload_builder
+            .validate_payload(
+                Height::new(1),
+                &test_proposal_context(&context),
+                &payload,
+                &[]
+            )
+            .is_ok());
+
+        // A response signed by enough nodes, but not by the designated one, is invalid
+        let mut proof = response_and_metadata_to_proof(&response, &metadata);
+        proof.proof.signature.signatures_map = [0, 1, 3]
+            .into_iter()
+            .map(|node_id| (node_test_id(node_id), BasicSigOf::new(BasicSig(vec![]))))
+            .collect();
+        let payload = CanisterHttpPayload {
+            responses: vec![proof],
+            timeouts: vec![],
+            divergence_responses: vec![],
+        };
+        let payload = payload_to_bytes(&payload, NumBytes::new(4 * 1024 * 1024));
+        match payload_builder.validate_payload(
+            Height::new(1),
+            &test_proposal_context(&context),
+            &payload,
+            &[],
+        ) {
+            Err(ValidationError::Permanent(
+                PayloadPermanentError::CanisterHttpPayloadValidationError(
+                    CanisterHttpPermanentValidationError::NonReplicatedResponseSignerMismatch {
+                        ..
+                    },
+                ),
+            )) => (),
+            x => panic!("Expected NonReplicatedResponseSignerMismatch, got {:?}", x),
+        }
+    });
+}
+
+/// Check that the response to a majority request is included and validates once a simple
+/// majority of the nodes agree on it, while replicated requests still need the full threshold.
+#[test]
+fn majority_request_test() {
+    let context = default_validation_context();
+
+    test_config_with_http_feature(true, 7, |mut payload_builder, canister_http_pool| {
+        set_http_request_contexts(
+            &mut payload_builder,
+            vec![
+                (0, Replication::Majority),
+                (1, Replication::FullyReplicated),
+            ],
+        );
+
+        let (majority_response, majority_metadata) = test_response_and_metadata(0);
+        let (replicated_response, replicated_metadata) = test_response_and_metadata(1);
+        {
+            // 4 out of 7 nodes agree on both responses, while the threshold is 5
+            let mut pool_access = canister_http_pool.write().unwrap();
+            for (response, metadata) in [
+                (&majority_response, &majority_metadata),
+                (&replicated_response, &replicated_metadata),
+            ] {
+                let shares = metadata_to_shares(4, metadata);
+                add_own_share_to_pool(pool_access.deref_mut(), &shares[0], response);
+                add_received_shares_to_pool(pool_access.deref_mut(), shares[1..].to_vec());
+            }
+        }
+
+        let payload = payload_builder.build_payload(
+            Height::new(1),
+            NumBytes::new(4 * 1024 * 1024),
+            &[],
+            &context,
+        );
+        let parsed_payload = bytes_to_payload(&payload).expect("Failed to parse the payload");
+        assert_eq!(parsed_payload.num_responses(), 1);
+        assert_eq!(parsed_payload.responses[0].content, majority_response);
+        assert!(payload_builder
+            .validate_payload(
+                Height::new(1),
+                &test_proposal_context(&context),
+                &payload,
+                &[]
+            )
+            .is_ok());
+
+        // The same number of signers is not enough for a replicated request
+        let mut proof = response_and_metadata_to_proof(&replicated_response, &replicated_metadata);
+        proof.proof.signature.signatures_map = (0..4)
+            .map(|node_id| (node_test_id(node_id), BasicSigOf::new(BasicSig(vec![]))))
+            .collect();
+        let payload = CanisterHttpPayload {
+            responses: vec![proof],
+            timeouts: vec![],
+            divergence_responses: vec![],
+        };
+        let payload = payload_to_bytes(&payload, NumBytes::new(4 * 1024 * 1024));
+        match payload_builder.validate_payload(
+            Height::new(1),
+            &test_proposal_context(&context),
+            &payload,
+            &[],
+        ) {
+            Err(ValidationError::Permanent(
+                PayloadPermanentError::CanisterHttpPayloadValidationError(
+                    CanisterHttpPermanentValidationError::NotEnoughSigners { .. },
+                ),
+            )) => (),
+            x => panic!("Expected NotEnoughSigners, got {:?}", x),
+        }
+    });
+}
+
+/// Check that a divergence response is delivered as a reject, which describes the
+/// different responses without their content.
+#[test]
+fn divergence_report_test() {
+    let (_, metadata) = test_response_and_metadata(0);
+    let (_, reject_metadata) = test_response_and_metadata_with_content(
+        0,
+        CanisterHttpResponseContent::Reject(CanisterHttpReject {
+            reject_code: RejectCode::SysTransient,
+            message: "timeout".to_string(),
+        }),
+    );
+    let mut shares = metadata_to_shares(2, &metadata);
+    shares.push(metadata_to_share(2, &reject_metadata));
+    let payload = CanisterHttpPayload {
+        responses: vec![],
+        timeouts: vec![],
+        divergence_responses: vec![CanisterHttpResponseDivergence { shares }],
+    };
+
+    let (responses, stats) = CanisterHttpPayloadBuilderImpl::into_messages(&payload_to_bytes(
+        &payload,
+        NumBytes::new(4 * 1024 * 1024),
+    ));
+    assert_eq!(stats.divergence_responses, 1);
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].originator_reply_callback, CallbackId::from(0));
+    let Payload::Reject(reject) = &responses[0].response_payload else {
+        panic!("Expected a reject, got {:?}", responses[0].response_payload);
+    };
+    assert_eq!(reject.code(), RejectCode::SysTransient);
+    assert!(reject.message().starts_with(
+        "Canister http responses were different across replicas, and no consensus was \
+         reached. The server may have processed the request, possibly several times, so check \
+         its state before retrying a request whose method is not idempotent. Divergence \
+         report: "
+    ));
+    assert_eq!(
+        CanisterHttpDivergenceReport::from_reject_message(reject.message()),
+        Some(CanisterHttpDivergenceReport {
+            responses: vec![
+                CanisterHttpDivergentResponse {
+                    status: None,
+                    reject_code: None,
+                    size: 3,
+                    replica_count: 2,
+                },
+                CanisterHttpDivergentResponse {
+                    status: None,
+                    reject_code: Some(RejectCode::SysTransient as u32),
+                    size: 7,
+                    replica_count: 1,
+                },
+            ],
+        })
+    );
+}
+
+/// Sets up the state of the payload builder with requests of the given replication
+fn set_http_request_contexts(
+    payload_builder: &mut CanisterHttpPayloadBuilderImpl,
+    contexts: Vec<(u64, Replication)>,
+) {
+    let mut init_state = ic_test_utilities::state::get_initial_state(0, 0);
+    for (callback_id, replication) in contexts {
+        init_state
+            .metadata
+            .subnet_call_context_manager
+            .canister_http_request_contexts
+            .insert(
+                CallbackId::from(callback_id),
+                CanisterHttpRequestContext {
+                    request: RequestBuilder::default().build(),
+                    url: String::new(),
+                    max_response_bytes: None,
+                    headers: vec![],
+                    body: None,
+                    http_method: CanisterHttpMethod::GET,
+                    transform: None,
+                    time: mock_time(),
+                    replication,
+                },
+            );
+    }
+    let state_manager = Arc::new(RefMockStateManager::default());
+    state_manager
+        .get_mut()
+        .expect_get_state_at()
+        .return_const(Ok(ic_interfaces_state_manager::Labeled::new(
+            Height::new(0),
+            Arc::new(init_state),
+        )));
+    payload_builder.state_reader = state_manager;
+}
+
 /// Build some test metadata and response, which is valid and can be used in
 /// different tests
 pub(crate) fn test_response_and_metadata(
@@ -812,6 +1087,7 @@ fn test_response_and_metadata_full(
         timeout: response.timeout,
         content_hash: crypto_hash(&response),
         registry_version: RegistryVersion::new(1),
+        summary: CanisterHttpResponseSummary::from(&response.content),
     };
     (response, metadata)
 }
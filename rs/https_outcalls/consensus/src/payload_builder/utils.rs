@@ -1,8 +1,10 @@
+use ic_ic00_types::{CanisterHttpDivergenceReport, CanisterHttpDivergentResponse};
 use ic_interfaces::canister_http::CanisterHttpPermanentValidationError;
 use ic_types::{
     batch::ValidationContext,
     canister_http::{
-        CanisterHttpResponseMetadata, CanisterHttpResponseShare, CanisterHttpResponseWithConsensus,
+        CanisterHttpResponseDivergence, CanisterHttpResponseMetadata, CanisterHttpResponseShare,
+        CanisterHttpResponseSummary, CanisterHttpResponseWithConsensus,
     },
     crypto::crypto_hash,
     messages::CallbackId,
@@ -15,6 +17,7 @@ use std::collections::{BTreeMap, BTreeSet};
 /// Consistency means:
 /// - The signed metadata is the same as the metadata of the response
 /// - The content_hash is the same as the hash of the content
+/// - The signed summary is the same as the summary of the content
 ///
 /// **NOTE**: The signature is not checked
 pub(crate) fn check_response_consistency(
@@ -48,6 +51,16 @@ pub(crate) fn check_response_consistency(
         });
     }
 
+    // Check the summary matches the content, since it is reported to the canister
+    // if the responses diverge
+    let calculated_summary = CanisterHttpResponseSummary::from(content);
+    if calculated_summary != metadata.summary {
+        return Err(CanisterHttpPermanentValidationError::SummaryMismatch {
+            metadata_summary: metadata.summary.clone(),
+            calculated_summary,
+        });
+    }
+
     Ok(())
 }
 
@@ -128,6 +141,39 @@ pub(crate) fn grouped_shares_meet_divergence_criteria(
     }
 }
 
+/// Returns the number of signers needed to agree on the response to a request
+/// made with [`ic_types::canister_http::Replication::Majority`].
+pub(crate) fn majority_threshold(committee_size: usize) -> usize {
+    committee_size / 2 + 1
+}
+
+/// Describes how the responses in a divergence proof differ, without revealing their content.
+///
+/// The report contains, for each of the distinct responses in decreasing order of support,
+/// its summary and the number of replicas that signed it.
+pub(crate) fn divergence_report(
+    divergence: &CanisterHttpResponseDivergence,
+) -> CanisterHttpDivergenceReport {
+    let mut signers_by_response = BTreeMap::<_, BTreeSet<NodeId>>::new();
+    for share in &divergence.shares {
+        signers_by_response
+            .entry((&share.content.content_hash, &share.content.summary))
+            .or_default()
+            .insert(share.signature.signer);
+    }
+    let mut responses: Vec<_> = signers_by_response
+        .into_iter()
+        .map(|((_, summary), signers)| CanisterHttpDivergentResponse {
+            status: summary.status,
+            reject_code: summary.reject_code.map(|reject_code| reject_code as u32),
+            size: summary.size,
+            replica_count: signers.len() as u64,
+        })
+        .collect();
+    responses.sort_by_key(|response| core::cmp::Reverse(response.replica_count));
+    CanisterHttpDivergenceReport { responses }
+}
+
 pub(crate) fn group_shares_by_callback_id<
     'a,
     Shares: Iterator<Item = &'a CanisterHttpResponseShare>,
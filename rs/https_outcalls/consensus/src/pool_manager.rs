@@ -183,7 +183,10 @@ impl CanisterHttpPoolManagerImpl {
             .collect();
 
         for (id, context) in http_requests {
-            if !request_ids_already_made.contains(&id) {
+            // Non-replicated requests are only made by the designated node.
+            if !request_ids_already_made.contains(&id)
+                && context.is_made_by(self.replica_config.node_id)
+            {
                 let timeout = context.time + Duration::from_secs(5 * 60);
                 if let Err(err) = self
                     .http_adapter_shim
@@ -235,6 +238,7 @@ impl CanisterHttpPoolManagerImpl {
                         timeout: response.timeout,
                         registry_version,
                         content_hash: ic_types::crypto::crypto_hash(&response),
+                        summary: CanisterHttpResponseSummary::from(&response.content),
                     };
                     let signature = if let Ok(signature) = self
                         .crypto
@@ -286,6 +290,13 @@ impl CanisterHttpPoolManagerImpl {
             return Vec::new();
         };
 
+        let state = self.state_reader.get_latest_state();
+        let http_requests = &state
+            .get_ref()
+            .metadata
+            .subnet_call_context_manager
+            .canister_http_request_contexts;
+
         canister_http_pool
             .get_unvalidated_shares()
             .filter_map(|share| {
@@ -312,6 +323,17 @@ impl CanisterHttpPoolManagerImpl {
                             .to_string(),
                     ));
                 }
+                if http_requests
+                    .get(&share.content.id)
+                    .is_some_and(|context| !context.is_made_by(share.signature.signer))
+                {
+                    self.metrics.shares_marked_invalid.inc();
+                    return Some(CanisterHttpChangeAction::HandleInvalid(
+                        share.clone(),
+                        "Share of a non-replicated request signed by a node other than the designated one"
+                            .to_string(),
+                    ));
+                }
                 // TODO: more precise error handling
                 if let Err(err) = self.crypto.verify(share, registry_version) {
                     error!(self.log, "Unable to verify signature of share, {}", err);
@@ -398,7 +420,7 @@ pub mod test {
     use ic_logger::replica_logger::no_op_logger;
     use ic_metrics::MetricsRegistry;
     use ic_registry_subnet_type::SubnetType;
-    use ic_test_utilities::types::ids::subnet_test_id;
+    use ic_test_utilities::types::ids::{node_test_id, subnet_test_id};
     use ic_test_utilities_logger::with_test_replica_logger;
     use ic_types::{
         crypto::{CryptoHash, CryptoHashOf},
@@ -472,6 +494,7 @@ pub mod test {
                     http_method: CanisterHttpMethod::GET,
                     transform: None,
                     time: ic_types::Time::from_nanos_since_unix_epoch(10),
+                    replication: Replication::FullyReplicated,
                 };
 
                 state_manager
@@ -490,6 +513,7 @@ pub mod test {
                     timeout: ic_types::Time::from_nanos_since_unix_epoch(10),
                     registry_version: RegistryVersion::from(1),
                     content_hash: CryptoHashOf::new(CryptoHash(vec![])),
+                    summary: CanisterHttpResponseSummary::default(),
                 };
 
                 let signature = crypto
@@ -620,6 +644,7 @@ pub mod test {
                     http_method: CanisterHttpMethod::GET,
                     transform: None,
                     time: ic_types::Time::from_nanos_since_unix_epoch(10),
+                    replication: Replication::FullyReplicated,
                 };
 
                 // Expect times to be called exactly once to check that already
@@ -670,6 +695,7 @@ pub mod test {
                     timeout: ic_types::Time::from_nanos_since_unix_epoch(10),
                     registry_version: RegistryVersion::from(1),
                     content_hash: CryptoHashOf::new(CryptoHash(vec![])),
+                    summary: CanisterHttpResponseSummary::default(),
                 };
 
                 let signature = crypto
@@ -696,4 +722,70 @@ pub mod test {
             });
         });
     }
+
+    #[test]
+    pub fn test_non_replicated_request_only_made_by_designated_node() {
+        ic_test_utilities::artifact_pool_config::with_test_pool_config(|pool_config| {
+            with_test_replica_logger(|log| {
+                let Dependencies {
+                    pool,
+                    replica_config,
+                    crypto,
+                    state_manager,
+                    registry,
+                    membership,
+                    ..
+                } = dependencies(pool_config.clone(), 4);
+                let mut shim_mock = MockNonBlockingChannel::<CanisterHttpRequest>::new();
+                shim_mock
+                    .expect_try_receive()
+                    .return_const(Err(TryReceiveError::Empty));
+                // The request is designated to another node, so send must not be called.
+                shim_mock.expect_send().times(0);
+
+                let request = CanisterHttpRequestContext {
+                    request: ic_test_utilities::types::messages::RequestBuilder::new().build(),
+                    url: "".to_string(),
+                    max_response_bytes: None,
+                    headers: vec![],
+                    body: None,
+                    http_method: CanisterHttpMethod::GET,
+                    transform: None,
+                    time: ic_types::Time::from_nanos_since_unix_epoch(10),
+                    replication: Replication::NonReplicated(node_test_id(1000)),
+                };
+                assert_ne!(replica_config.node_id, node_test_id(1000));
+
+                let shim: Arc<Mutex<CanisterHttpAdapterClient>> =
+                    Arc::new(Mutex::new(Box::new(shim_mock)));
+
+                state_manager
+                    .get_mut()
+                    .expect_get_latest_state()
+                    .return_const(Labeled::new(
+                        Height::from(1),
+                        Arc::new(state_with_pending_http_calls(BTreeMap::from([(
+                            CallbackId::from(7),
+                            request,
+                        )]))),
+                    ));
+
+                let pool_manager = CanisterHttpPoolManagerImpl::new(
+                    state_manager,
+                    shim,
+                    crypto,
+                    membership,
+                    pool.get_cache(),
+                    replica_config,
+                    Arc::clone(&registry) as Arc<_>,
+                    MetricsRegistry::new(),
+                    log,
+                );
+                let canister_http_pool =
+                    CanisterHttpPoolImpl::new(MetricsRegistry::new(), no_op_logger());
+                let change_set = pool_manager.generate_change_set(&canister_http_pool);
+                assert_eq!(change_set.len(), 0);
+            });
+        });
+    }
 }
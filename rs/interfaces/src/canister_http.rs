@@ -4,7 +4,7 @@ use ic_base_types::RegistryVersion;
 use ic_protobuf::proxy::ProxyDecodeError;
 use ic_types::{
     artifact::CanisterHttpResponseId,
-    canister_http::{CanisterHttpResponse, CanisterHttpResponseShare},
+    canister_http::{CanisterHttpResponse, CanisterHttpResponseShare, CanisterHttpResponseSummary},
     consensus::Threshold,
     crypto::{CryptoError, CryptoHashOf},
     messages::CallbackId,
@@ -35,6 +35,11 @@ pub enum CanisterHttpPermanentValidationError {
         metadata_hash: CryptoHashOf<CanisterHttpResponse>,
         calculated_hash: CryptoHashOf<CanisterHttpResponse>,
     },
+    /// The summary of the signed metadata does not match the summary of the content
+    SummaryMismatch {
+        metadata_summary: CanisterHttpResponseSummary,
+        calculated_summary: CanisterHttpResponseSummary,
+    },
     /// The response has already timed out
     Timeout {
         timed_out_at: Time,
@@ -64,6 +69,12 @@ pub enum CanisterHttpPermanentValidationError {
         signers: Vec<NodeId>,
         expected_threshold: Threshold,
     },
+    /// The proof of a response to a non-replicated request was not signed by the
+    /// designated node alone
+    NonReplicatedResponseSignerMismatch {
+        designated_node: NodeId,
+        signers: Vec<NodeId>,
+    },
     /// The payload contains a duplicate response
     DuplicateResponse(CallbackId),
     DivergenceProofContainsMultipleCallbackIds,
@@ -19,7 +19,10 @@ use crate::{
 use ic_base_types::{NumBytes, SubnetId};
 use ic_types::{
     batch::{BatchPayload, ValidationContext},
-    consensus::{block_maker::SubnetRecords, Payload},
+    consensus::{
+        block_maker::{SubnetLoad, SubnetRecords},
+        Payload,
+    },
     registry::RegistryClientError,
     Height, Time,
 };
@@ -32,12 +35,16 @@ pub trait PayloadBuilder: Send + Sync {
     /// `past_payloads` contains the `Payloads` from all blocks above the
     /// certified height provided in `context`, in descending block height
     /// order.
+    ///
+    /// `subnet_load` is used to adapt the size of the payload to the load of
+    /// the subnet.
     fn get_payload(
         &self,
         height: Height,
         past_payloads: &[(Height, Time, Payload)],
         context: &ValidationContext,
         subnet_records: &SubnetRecords,
+        subnet_load: &SubnetLoad,
     ) -> BatchPayload;
 
     /// Checks whether the provided `payload` is valid given `past_payloads` and
@@ -56,6 +56,8 @@ fn test_submit_and_accept_update_subnet_proposal() {
                 subnet_type: SubnetType::Application.into(),
                 is_halted: false,
                 halt_at_cup_height: false,
+                ingress_admission_config: None,
+                adaptive_payload_sizing_config: None,
                 max_instructions_per_message: 5_000_000_000,
                 max_instructions_per_round: 7_000_000_000,
                 max_instructions_per_install_code: 200_000_000_000,
@@ -117,6 +119,8 @@ fn test_submit_and_accept_update_subnet_proposal() {
                 max_number_of_canisters: Some(200),
                 ssh_readonly_access: Some(vec!["pub_key_0".to_string()]),
                 ssh_backup_access: Some(vec!["pub_key_1".to_string()]),
+                ingress_admission_config: None,
+                adaptive_payload_sizing_config: None,
             };
 
             let proposal_id: ProposalId = submit_external_update_proposal(
@@ -179,6 +183,8 @@ fn test_submit_and_accept_update_subnet_proposal() {
                     subnet_type: SubnetType::Application.into(),
                     is_halted: true,
                     halt_at_cup_height: true,
+                    ingress_admission_config: None,
+                    adaptive_payload_sizing_config: None,
                     max_instructions_per_message: 5_000_000_000,
                     max_instructions_per_round: 8_000_000_000,
                     max_instructions_per_install_code: 200_000_000_000,
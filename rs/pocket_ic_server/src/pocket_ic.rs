@@ -77,6 +77,7 @@ impl PocketIc {
         });
 
         let ii_subnet_split = subnet_configs.ii.is_some();
+        let bitcoin_adapter_uds_path = subnet_configs.bitcoin_adapter_uds_path.clone();
 
         let mut subnet_counter = 0_u64;
         let mut apply_subnet_counter = move || -> u64 {
@@ -171,6 +172,11 @@ impl PocketIc {
                 builder = builder.with_state_dir(state_dir);
             }
 
+            if let (SubnetKind::Bitcoin, Some(uds_path)) = (subnet_kind, &bitcoin_adapter_uds_path)
+            {
+                builder = builder.with_bitcoin_testnet_uds_path(uds_path.clone());
+            }
+
             builder.build_with_subnets(subnets.clone());
 
             // What will be returned to the client:
@@ -533,11 +533,11 @@ pub async fn create_instance(
     }): State<AppState>,
     extract::Json(subnet_configs): extract::Json<ExtendedSubnetConfigSet>,
 ) -> (StatusCode, Json<rest::CreateInstanceResponse>) {
-    if subnet_configs.validate().is_err() {
+    if let Err(message) = subnet_configs.validate() {
         return (
             StatusCode::BAD_REQUEST,
             Json(rest::CreateInstanceResponse::Error {
-                message: "Bad config".to_owned(),
+                message: format!("Bad config: {}", message),
             }),
         );
     }
@@ -85,6 +85,93 @@ pub struct SubnetRecord {
     /// appropriate proposal which sets `is_halted` to `false` is approved.
     #[prost(bool, tag = "28")]
     pub halt_at_cup_height: bool,
+    /// Admission control applied by the replica's HTTP handler to ingress messages
+    /// before they are validated and submitted to the ingress pool. If unset, only
+    /// the node-local ingress pool limits apply.
+    #[prost(message, optional, tag = "29")]
+    pub ingress_admission_config: ::core::option::Option<IngressAdmissionConfig>,
+    /// Bounds for adapting the block payload size to the load of the subnet. If
+    /// unset, block makers always use `max_block_payload_size`.
+    #[prost(message, optional, tag = "30")]
+    pub adaptive_payload_sizing_config: ::core::option::Option<AdaptivePayloadSizingConfig>,
+}
+/// Per-subnet admission control settings for ingress messages.
+///
+/// The rate limits are enforced by each replica independently with token
+/// buckets, i.e. the effective subnet-wide rate is bounded by the per-replica
+/// rate times the number of replicas that receive traffic. A value of 0 disables
+/// the respective limit.
+#[derive(serde::Serialize, serde::Deserialize, candid::CandidType, Eq)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IngressAdmissionConfig {
+    /// Sustained number of ingress messages per second accepted for a single
+    /// target canister.
+    #[prost(uint64, tag = "1")]
+    pub max_messages_per_second_per_canister: u64,
+    /// Number of ingress messages that can be accepted in a burst for a single
+    /// target canister.
+    #[prost(uint64, tag = "2")]
+    pub max_burst_per_canister: u64,
+    /// Sustained number of ingress messages per second accepted from a single
+    /// sender.
+    #[prost(uint64, tag = "3")]
+    pub max_messages_per_second_per_sender: u64,
+    /// Number of ingress messages that can be accepted in a burst from a single
+    /// sender.
+    #[prost(uint64, tag = "4")]
+    pub max_burst_per_sender: u64,
+    /// Ingress messages are shed if the certified height of the latest finalized
+    /// block lags behind the finalized height by more than this number of blocks,
+    /// i.e. if execution cannot keep up with consensus.
+    #[prost(uint64, tag = "5")]
+    pub max_execution_backlog_blocks: u64,
+    /// Number of seconds clients are asked to wait before retrying a shed
+    /// request. Returned in the `Retry-After` header.
+    #[prost(uint64, tag = "6")]
+    pub retry_after_seconds: u64,
+}
+/// Per-subnet settings for adapting the byte budgets of the block payload
+/// sections to the load of the subnet.
+///
+/// Block makers shrink the budget of each section with configured bounds from
+/// its maximum towards its minimum when blocks are finalized slower than
+/// targeted or when finalized blocks are not delivered to message routing fast
+/// enough, and grow it back once the subnet recovers. Validation is not
+/// affected, i.e. blocks are still accepted up to `max_block_payload_size`. A
+/// target of 0 disables the respective signal.
+#[derive(serde::Serialize, serde::Deserialize, candid::CandidType, Eq)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AdaptivePayloadSizingConfig {
+    /// The time in milliseconds since the oldest notarized but not yet finalized
+    /// block was proposed, above which the subnet is considered overloaded.
+    #[prost(uint64, tag = "1")]
+    pub target_finalization_latency_millis: u64,
+    /// The number of finalized blocks that are not yet delivered to message
+    /// routing, above which the subnet is considered overloaded.
+    #[prost(uint64, tag = "2")]
+    pub target_batch_delivery_backlog_blocks: u64,
+    /// The bounds of the byte budgets of individual sections. Sections without
+    /// bounds are limited by `max_block_payload_size` only.
+    #[prost(message, repeated, tag = "3")]
+    pub section_bounds: ::prost::alloc::vec::Vec<PayloadSectionBounds>,
+}
+/// The bounds of the byte budget of a block payload section.
+#[derive(serde::Serialize, serde::Deserialize, candid::CandidType, Eq)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayloadSectionBounds {
+    #[prost(enumeration = "PayloadSection", tag = "1")]
+    pub section: i32,
+    /// The budget in bytes of the section when the subnet is overloaded. For the
+    /// ingress section, never less than `max_ingress_bytes_per_message`.
+    #[prost(uint64, tag = "2")]
+    pub min_bytes: u64,
+    /// The budget in bytes of the section when the subnet is not overloaded.
+    /// Never more than `max_block_payload_size`.
+    #[prost(uint64, tag = "3")]
+    pub max_bytes: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -384,6 +471,57 @@ pub struct EcdsaConfig {
     #[prost(uint64, optional, tag = "6")]
     pub idkg_key_rotation_period_ms: ::core::option::Option<u64>,
 }
+/// A section of the block payload.
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    ::prost::Enumeration,
+)]
+#[repr(i32)]
+pub enum PayloadSection {
+    Unspecified = 0,
+    Ingress = 1,
+    Xnet = 2,
+    SelfValidating = 3,
+    CanisterHttp = 4,
+    QueryStats = 5,
+}
+impl PayloadSection {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            PayloadSection::Unspecified => "PAYLOAD_SECTION_UNSPECIFIED",
+            PayloadSection::Ingress => "PAYLOAD_SECTION_INGRESS",
+            PayloadSection::Xnet => "PAYLOAD_SECTION_XNET",
+            PayloadSection::SelfValidating => "PAYLOAD_SECTION_SELF_VALIDATING",
+            PayloadSection::CanisterHttp => "PAYLOAD_SECTION_CANISTER_HTTP",
+            PayloadSection::QueryStats => "PAYLOAD_SECTION_QUERY_STATS",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PAYLOAD_SECTION_UNSPECIFIED" => Some(Self::Unspecified),
+            "PAYLOAD_SECTION_INGRESS" => Some(Self::Ingress),
+            "PAYLOAD_SECTION_XNET" => Some(Self::Xnet),
+            "PAYLOAD_SECTION_SELF_VALIDATING" => Some(Self::SelfValidating),
+            "PAYLOAD_SECTION_CANISTER_HTTP" => Some(Self::CanisterHttp),
+            "PAYLOAD_SECTION_QUERY_STATS" => Some(Self::QueryStats),
+            _ => None,
+        }
+    }
+}
 #[derive(
     serde::Serialize,
     serde::Deserialize,
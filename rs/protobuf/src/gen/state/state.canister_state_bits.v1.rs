@@ -606,6 +606,9 @@ pub struct CanisterStateBits {
     /// Log visibility for the canister.
     #[prost(enumeration = "LogVisibility", tag = "42")]
     pub log_visibility: i32,
+    /// Priority of the global timer of the canister.
+    #[prost(enumeration = "TimerPriority", tag = "43")]
+    pub timer_priority: i32,
     #[prost(oneof = "canister_state_bits::CanisterStatus", tags = "11, 12, 13")]
     pub canister_status: ::core::option::Option<canister_state_bits::CanisterStatus>,
 }
@@ -775,3 +778,32 @@ impl LogVisibility {
         }
     }
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum TimerPriority {
+    Unspecified = 0,
+    Normal = 1,
+    High = 2,
+}
+impl TimerPriority {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            TimerPriority::Unspecified => "TIMER_PRIORITY_UNSPECIFIED",
+            TimerPriority::Normal => "TIMER_PRIORITY_NORMAL",
+            TimerPriority::High => "TIMER_PRIORITY_HIGH",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "TIMER_PRIORITY_UNSPECIFIED" => Some(Self::Unspecified),
+            "TIMER_PRIORITY_NORMAL" => Some(Self::Normal),
+            "TIMER_PRIORITY_HIGH" => Some(Self::High),
+            _ => None,
+        }
+    }
+}
@@ -1253,6 +1253,19 @@ pub struct CanisterHttpResponseMetadata {
     pub content_hash: ::prost::alloc::vec::Vec<u8>,
     #[prost(uint64, tag = "4")]
     pub registry_version: u64,
+    #[prost(message, optional, tag = "5")]
+    pub summary: ::core::option::Option<CanisterHttpResponseSummary>,
+}
+#[derive(serde::Serialize, serde::Deserialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CanisterHttpResponseSummary {
+    #[prost(uint32, optional, tag = "1")]
+    pub status: ::core::option::Option<u32>,
+    #[prost(uint32, optional, tag = "2")]
+    pub reject_code: ::core::option::Option<u32>,
+    #[prost(uint64, tag = "3")]
+    pub size: u64,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -1303,6 +1316,8 @@ pub struct CanisterHttpResponseWithConsensus {
     pub registry_version: u64,
     #[prost(message, repeated, tag = "7")]
     pub signatures: ::prost::alloc::vec::Vec<CanisterHttpResponseSignature>,
+    #[prost(message, optional, tag = "8")]
+    pub summary: ::core::option::Option<CanisterHttpResponseSummary>,
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -1285,6 +1285,8 @@ impl ProposalPayload<CreateSubnetPayload> for ProposeToCreateSubnetCmd {
             ssh_backup_access: self.ssh_backup_access.clone(),
             max_number_of_canisters: self.max_number_of_canisters.unwrap_or(0),
             ecdsa_config,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         }
     }
 }
@@ -1834,6 +1836,8 @@ impl ProposalPayload<UpdateSubnetPayload> for ProposeToUpdateSubnetCmd {
             ssh_readonly_access: self.ssh_readonly_access.clone(),
             ssh_backup_access: self.ssh_backup_access.clone(),
             max_number_of_canisters: self.max_number_of_canisters,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         }
     }
 }
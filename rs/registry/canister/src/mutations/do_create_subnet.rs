@@ -17,8 +17,8 @@ use ic_ic00_types::{EcdsaKeyId, SetupInitialDKGArgs, SetupInitialDKGResponse};
 use ic_protobuf::registry::{
     node::v1::NodeRecord,
     subnet::v1::{
-        CatchUpPackageContents, EcdsaConfig, GossipConfig, SubnetFeatures as pbSubnetFeatures,
-        SubnetRecord,
+        AdaptivePayloadSizingConfig, CatchUpPackageContents, EcdsaConfig, GossipConfig,
+        IngressAdmissionConfig, SubnetFeatures as pbSubnetFeatures, SubnetRecord,
     },
 };
 use ic_registry_keys::{
@@ -276,6 +276,12 @@ pub struct CreateSubnetPayload {
     pub ssh_backup_access: Vec<String>,
 
     pub ecdsa_config: Option<EcdsaInitialConfig>,
+
+    /// Rate limits and load shedding for ingress messages sent to the subnet.
+    pub ingress_admission_config: Option<IngressAdmissionConfig>,
+
+    /// Bounds for adapting the block payload size to the load of the subnet.
+    pub adaptive_payload_sizing_config: Option<AdaptivePayloadSizingConfig>,
 }
 
 #[derive(CandidType, Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
@@ -344,6 +350,8 @@ impl From<CreateSubnetPayload> for SubnetRecord {
 
             is_halted: val.is_halted,
             halt_at_cup_height: false,
+            ingress_admission_config: val.ingress_admission_config,
+            adaptive_payload_sizing_config: val.adaptive_payload_sizing_config,
 
             max_instructions_per_message: val.max_instructions_per_message,
             max_instructions_per_round: val.max_instructions_per_round,
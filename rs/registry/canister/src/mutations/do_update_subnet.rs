@@ -11,7 +11,10 @@ use serde::Serialize;
 
 use ic_base_types::{subnet_id_into_protobuf, SubnetId};
 use ic_ic00_types::EcdsaKeyId;
-use ic_protobuf::registry::subnet::v1::{SubnetFeatures as pbSubnetFeatures, SubnetRecord};
+use ic_protobuf::registry::subnet::v1::{
+    AdaptivePayloadSizingConfig, IngressAdmissionConfig, SubnetFeatures as pbSubnetFeatures,
+    SubnetRecord,
+};
 use ic_registry_keys::{make_ecdsa_signing_subnet_list_key, make_subnet_record_key};
 use ic_registry_subnet_features::{EcdsaConfig, SubnetFeatures};
 use ic_registry_subnet_type::SubnetType;
@@ -246,6 +249,12 @@ pub struct UpdateSubnetPayload {
 
     pub ssh_readonly_access: Option<Vec<String>>,
     pub ssh_backup_access: Option<Vec<String>>,
+
+    /// Rate limits and load shedding for ingress messages sent to the subnet.
+    pub ingress_admission_config: Option<IngressAdmissionConfig>,
+
+    /// Bounds for adapting the block payload size to the load of the subnet.
+    pub adaptive_payload_sizing_config: Option<AdaptivePayloadSizingConfig>,
 }
 
 // Sets the value of a field in record `a` if the provided value `b` is not
@@ -333,6 +342,8 @@ fn merge_subnet_record(
         max_number_of_canisters,
         ssh_readonly_access,
         ssh_backup_access,
+        ingress_admission_config,
+        adaptive_payload_sizing_config,
     } = payload;
 
     let features: Option<pbSubnetFeatures> = features.map(|v| SubnetFeatures::from(v).into());
@@ -384,6 +395,9 @@ fn merge_subnet_record(
     maybe_set!(subnet_record, ssh_readonly_access);
     maybe_set!(subnet_record, ssh_backup_access);
 
+    maybe_set_option!(subnet_record, ingress_admission_config);
+    maybe_set_option!(subnet_record, adaptive_payload_sizing_config);
+
     subnet_record
 }
 
@@ -396,7 +410,9 @@ mod tests {
     };
     use ic_ic00_types::{EcdsaCurve, EcdsaKeyId};
     use ic_nervous_system_common_test_keys::{TEST_USER1_PRINCIPAL, TEST_USER2_PRINCIPAL};
-    use ic_protobuf::registry::subnet::v1::{GossipConfig, SubnetRecord};
+    use ic_protobuf::registry::subnet::v1::{
+        GossipConfig, PayloadSection, PayloadSectionBounds, SubnetRecord,
+    };
     use ic_registry_subnet_features::DEFAULT_ECDSA_MAX_QUEUE_SIZE;
     use ic_registry_subnet_type::SubnetType;
     use ic_test_utilities::types::ids::subnet_test_id;
@@ -469,6 +485,8 @@ mod tests {
             max_number_of_canisters: Some(10),
             ssh_readonly_access: Some(vec!["pub_key_0".to_string()]),
             ssh_backup_access: Some(vec!["pub_key_1".to_string()]),
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         }
     }
 
@@ -505,6 +523,8 @@ mod tests {
             max_number_of_canisters: None,
             ssh_readonly_access: None,
             ssh_backup_access: None,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         }
     }
 
@@ -534,6 +554,8 @@ mod tests {
             subnet_type: SubnetType::Application.into(),
             is_halted: false,
             halt_at_cup_height: false,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
             max_instructions_per_message: 5_000_000_000,
             max_instructions_per_round: 7_000_000_000,
             max_instructions_per_install_code: 200_000_000_000,
@@ -594,6 +616,23 @@ mod tests {
             max_number_of_canisters: Some(10),
             ssh_readonly_access: Some(vec!["pub_key_0".to_string()]),
             ssh_backup_access: Some(vec!["pub_key_1".to_string()]),
+            ingress_admission_config: Some(IngressAdmissionConfig {
+                max_messages_per_second_per_canister: 100,
+                max_burst_per_canister: 200,
+                max_messages_per_second_per_sender: 10,
+                max_burst_per_sender: 20,
+                max_execution_backlog_blocks: 30,
+                retry_after_seconds: 5,
+            }),
+            adaptive_payload_sizing_config: Some(AdaptivePayloadSizingConfig {
+                target_finalization_latency_millis: 2000,
+                target_batch_delivery_backlog_blocks: 10,
+                section_bounds: vec![PayloadSectionBounds {
+                    section: PayloadSection::Ingress.into(),
+                    min_bytes: 100,
+                    max_bytes: 4 * 1024 * 1024,
+                }],
+            }),
         };
 
         assert_eq!(
@@ -622,6 +661,23 @@ mod tests {
                 subnet_type: SubnetType::Application.into(),
                 is_halted: true,
                 halt_at_cup_height: false,
+                ingress_admission_config: Some(IngressAdmissionConfig {
+                    max_messages_per_second_per_canister: 100,
+                    max_burst_per_canister: 200,
+                    max_messages_per_second_per_sender: 10,
+                    max_burst_per_sender: 20,
+                    max_execution_backlog_blocks: 30,
+                    retry_after_seconds: 5,
+                }),
+                adaptive_payload_sizing_config: Some(AdaptivePayloadSizingConfig {
+                    target_finalization_latency_millis: 2000,
+                    target_batch_delivery_backlog_blocks: 10,
+                    section_bounds: vec![PayloadSectionBounds {
+                        section: PayloadSection::Ingress.into(),
+                        min_bytes: 100,
+                        max_bytes: 4 * 1024 * 1024,
+                    }],
+                }),
                 max_instructions_per_message: 6_000_000_000,
                 max_instructions_per_round: 8_000_000_000,
                 max_instructions_per_install_code: 300_000_000_000,
@@ -676,6 +732,8 @@ mod tests {
             subnet_type: SubnetType::Application.into(),
             is_halted: false,
             halt_at_cup_height: false,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
             max_instructions_per_message: 5_000_000_000,
             max_instructions_per_round: 7_000_000_000,
             max_instructions_per_install_code: 200_000_000_000,
@@ -723,6 +781,8 @@ mod tests {
             max_number_of_canisters: Some(50),
             ssh_readonly_access: None,
             ssh_backup_access: None,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         };
 
         assert_eq!(
@@ -751,6 +811,8 @@ mod tests {
                 subnet_type: SubnetType::Application.into(),
                 is_halted: false,
                 halt_at_cup_height: true,
+                ingress_admission_config: None,
+                adaptive_payload_sizing_config: None,
                 max_instructions_per_message: 5_000_000_000,
                 max_instructions_per_round: 8_000_000_000,
                 max_instructions_per_install_code: 200_000_000_000,
@@ -804,6 +866,8 @@ mod tests {
             subnet_type: SubnetType::Application.into(),
             is_halted: false,
             halt_at_cup_height: false,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
             max_instructions_per_message: 5_000_000_000,
             max_instructions_per_round: 7_000_000_000,
             max_instructions_per_install_code: 200_000_000_000,
@@ -851,6 +915,8 @@ mod tests {
             max_number_of_canisters: None,
             ssh_readonly_access: None,
             ssh_backup_access: None,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         };
 
         merge_subnet_record(subnet_record, payload);
@@ -873,6 +939,8 @@ mod tests {
             subnet_type: SubnetType::Application.into(),
             is_halted: false,
             halt_at_cup_height: false,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
             max_instructions_per_message: 5_000_000_000,
             max_instructions_per_round: 7_000_000_000,
             max_instructions_per_install_code: 200_000_000_000,
@@ -920,6 +988,8 @@ mod tests {
             max_number_of_canisters: None,
             ssh_readonly_access: None,
             ssh_backup_access: None,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         };
 
         assert_eq!(
@@ -948,6 +1018,8 @@ mod tests {
                 subnet_type: SubnetType::Application.into(),
                 is_halted: false,
                 halt_at_cup_height: false,
+                ingress_admission_config: None,
+                adaptive_payload_sizing_config: None,
                 max_instructions_per_message: 5_000_000_000,
                 max_instructions_per_round: 7_000_000_000,
                 max_instructions_per_install_code: 200_000_000_000,
@@ -986,6 +1058,8 @@ mod tests {
             subnet_type: SubnetType::Application.into(),
             is_halted: false,
             halt_at_cup_height: false,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
             max_instructions_per_message: 5_000_000_000,
             max_instructions_per_round: 7_000_000_000,
             max_instructions_per_install_code: 200_000_000_000,
@@ -1033,6 +1107,8 @@ mod tests {
             max_number_of_canisters: None,
             ssh_readonly_access: None,
             ssh_backup_access: None,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         };
 
         assert_eq!(
@@ -1061,6 +1137,8 @@ mod tests {
                 subnet_type: SubnetType::Application.into(),
                 is_halted: false,
                 halt_at_cup_height: false,
+                ingress_admission_config: None,
+                adaptive_payload_sizing_config: None,
                 max_instructions_per_message: 5_000_000_000,
                 max_instructions_per_round: 8_000_000_000,
                 max_instructions_per_install_code: 200_000_000_000,
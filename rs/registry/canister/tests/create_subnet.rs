@@ -10,7 +10,10 @@ use ic_nns_test_utils::{
 use ic_protobuf::registry::crypto::v1::{EcdsaCurve as pbEcdsaCurve, EcdsaKeyId as pbEcdsaKeyId};
 use std::convert::TryFrom;
 
-use ic_protobuf::registry::subnet::v1::{EcdsaConfig, SubnetListRecord, SubnetRecord};
+use ic_protobuf::registry::subnet::v1::{
+    AdaptivePayloadSizingConfig, EcdsaConfig, IngressAdmissionConfig, PayloadSection,
+    PayloadSectionBounds, SubnetListRecord, SubnetRecord,
+};
 use ic_registry_keys::{make_subnet_list_record_key, make_subnet_record_key};
 use ic_registry_subnet_features::DEFAULT_ECDSA_MAX_QUEUE_SIZE;
 use ic_registry_subnet_type::SubnetType;
@@ -170,7 +173,26 @@ fn test_accepted_proposal_mutates_the_registry_some_subnets_present() {
         // first, get current list of subnets created by underlying system
         let initial_subnet_list_record = get_subnet_list_record(&registry).await;
         // create payload message
-        let payload = make_create_subnet_payload(node_ids.clone());
+        let payload = CreateSubnetPayload {
+            ingress_admission_config: Some(IngressAdmissionConfig {
+                max_messages_per_second_per_canister: 100,
+                max_burst_per_canister: 200,
+                max_messages_per_second_per_sender: 10,
+                max_burst_per_sender: 20,
+                max_execution_backlog_blocks: 30,
+                retry_after_seconds: 5,
+            }),
+            adaptive_payload_sizing_config: Some(AdaptivePayloadSizingConfig {
+                target_finalization_latency_millis: 2000,
+                target_batch_delivery_backlog_blocks: 10,
+                section_bounds: vec![PayloadSectionBounds {
+                    section: PayloadSection::Ingress.into(),
+                    min_bytes: 1024 * 1024,
+                    max_bytes: 4 * 1024 * 1024,
+                }],
+            }),
+            ..make_create_subnet_payload(node_ids.clone())
+        };
 
         assert!(
             forward_call_via_universal_canister(
@@ -189,6 +211,14 @@ fn test_accepted_proposal_mutates_the_registry_some_subnets_present() {
 
         // Check if some fields are equal
         assert_eq!(subnet_record.replica_version_id, payload.replica_version_id);
+        assert_eq!(
+            subnet_record.ingress_admission_config,
+            payload.ingress_admission_config
+        );
+        assert_eq!(
+            subnet_record.adaptive_payload_sizing_config,
+            payload.adaptive_payload_sizing_config
+        );
         assert_eq!(
             subnet_record.membership,
             node_ids
@@ -394,5 +424,7 @@ fn make_create_subnet_payload(node_ids: Vec<NodeId>) -> CreateSubnetPayload {
         ssh_readonly_access: vec![],
         ssh_backup_access: vec![],
         ecdsa_config: None,
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
     }
 }
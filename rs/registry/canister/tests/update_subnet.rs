@@ -96,6 +96,8 @@ fn test_the_anonymous_user_cannot_update_a_subnets_configuration() {
             max_number_of_canisters: Some(10),
             ssh_readonly_access: Some(vec!["pub_key_0".to_string()]),
             ssh_backup_access: Some(vec!["pub_key_1".to_string()]),
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         };
 
         // The anonymous end-user tries to update a subnet's configuration, bypassing
@@ -158,6 +160,8 @@ fn test_a_canister_other_than_the_governance_canister_cannot_update_a_subnets_co
             subnet_type: SubnetType::Application.into(),
             is_halted: false,
             halt_at_cup_height: false,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
             max_instructions_per_message: 5_000_000_000,
             max_instructions_per_round: 7_000_000_000,
             max_instructions_per_install_code: 200_000_000_000,
@@ -225,6 +229,8 @@ fn test_a_canister_other_than_the_governance_canister_cannot_update_a_subnets_co
             max_number_of_canisters: Some(100),
             ssh_readonly_access: None,
             ssh_backup_access: None,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         };
 
         // The attacker canister tries to update the subnet's configuration, pretending
@@ -286,6 +292,8 @@ fn test_the_governance_canister_can_update_a_subnets_configuration() {
                             subnet_type: SubnetType::Application.into(),
                             is_halted: false,
                             halt_at_cup_height: false,
+                            ingress_admission_config: None,
+                            adaptive_payload_sizing_config: None,
                             max_instructions_per_message: 5_000_000_000,
                             max_instructions_per_round: 7_000_000_000,
                             max_instructions_per_install_code: 200_000_000_000,
@@ -344,6 +352,8 @@ fn test_the_governance_canister_can_update_a_subnets_configuration() {
             max_number_of_canisters: Some(42),
             ssh_readonly_access: Some(vec!["pub_key_0".to_string()]),
             ssh_backup_access: Some(vec!["pub_key_1".to_string()]),
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
         };
 
         // Attempt to update the subnet's configuration. Since the update happens from
@@ -390,6 +400,8 @@ fn test_the_governance_canister_can_update_a_subnets_configuration() {
                 subnet_type: SubnetType::Application.into(),
                 is_halted: true,
                 halt_at_cup_height: true,
+                ingress_admission_config: None,
+                adaptive_payload_sizing_config: None,
                 max_instructions_per_message: 6_000_000_000,
                 max_instructions_per_round: 8_000_000_000,
                 max_instructions_per_install_code: 300_000_000_000,
@@ -444,6 +456,8 @@ fn test_subnets_configuration_ecdsa_fields_are_updated_correctly() {
             subnet_type: SubnetType::Application.into(),
             is_halted: false,
             halt_at_cup_height: false,
+            ingress_admission_config: None,
+            adaptive_payload_sizing_config: None,
             max_instructions_per_message: 5_000_000_000,
             max_instructions_per_round: 7_000_000_000,
             max_instructions_per_install_code: 200_000_000_000,
@@ -665,5 +679,7 @@ fn empty_update_subnet_payload(subnet_id: SubnetId) -> UpdateSubnetPayload {
         ecdsa_config: None,
         ecdsa_key_signing_enable: None,
         ecdsa_key_signing_disable: None,
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
     }
 }
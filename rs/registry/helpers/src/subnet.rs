@@ -6,7 +6,10 @@ use ic_protobuf::{
     registry::{
         node::v1::NodeRecord,
         replica_version::v1::ReplicaVersionRecord,
-        subnet::v1::{CatchUpPackageContents, GossipConfig, SubnetListRecord, SubnetRecord},
+        subnet::v1::{
+            CatchUpPackageContents, GossipConfig, IngressAdmissionConfig, SubnetListRecord,
+            SubnetRecord,
+        },
     },
     types::v1::SubnetId as SubnetIdProto,
 };
@@ -39,6 +42,54 @@ pub struct IngressMessageSettings {
     pub max_ingress_messages_per_block: usize,
 }
 
+/// Admission control settings applied by the HTTP handler to ingress messages.
+/// Limits that are disabled in the registry record are `None`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct IngressAdmissionSettings {
+    /// Sustained rate and burst size of the per-canister token buckets.
+    pub per_canister_rate_limit: Option<RateLimit>,
+    /// Sustained rate and burst size of the per-sender token buckets.
+    pub per_sender_rate_limit: Option<RateLimit>,
+    /// Maximum number of finalized blocks that may be awaiting execution
+    /// before ingress messages are shed.
+    pub max_execution_backlog_blocks: Option<u64>,
+    /// How long clients are asked to wait before retrying a shed request.
+    pub retry_after: Duration,
+}
+
+/// A token bucket configuration: `messages_per_second` tokens are added per
+/// second, up to at most `burst` tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RateLimit {
+    pub messages_per_second: u64,
+    pub burst: u64,
+}
+
+impl From<IngressAdmissionConfig> for IngressAdmissionSettings {
+    fn from(config: IngressAdmissionConfig) -> Self {
+        let rate_limit = |messages_per_second: u64, burst: u64| {
+            (messages_per_second > 0).then_some(RateLimit {
+                messages_per_second,
+                // A burst smaller than one message would reject everything.
+                burst: burst.max(1),
+            })
+        };
+        Self {
+            per_canister_rate_limit: rate_limit(
+                config.max_messages_per_second_per_canister,
+                config.max_burst_per_canister,
+            ),
+            per_sender_rate_limit: rate_limit(
+                config.max_messages_per_second_per_sender,
+                config.max_burst_per_sender,
+            ),
+            max_execution_backlog_blocks: (config.max_execution_backlog_blocks > 0)
+                .then_some(config.max_execution_backlog_blocks),
+            retry_after: Duration::from_secs(config.retry_after_seconds.max(1)),
+        }
+    }
+}
+
 /// A helper trait that wraps a [RegistryClient] and provides utility methods for
 /// querying subnet information.
 pub trait SubnetRegistry {
@@ -69,6 +120,15 @@ pub trait SubnetRegistry {
         version: RegistryVersion,
     ) -> RegistryClientResult<IngressMessageSettings>;
 
+    /// Returns the ingress admission control settings. `Ok(None)` is returned
+    /// if the subnet record does not exist or does not configure admission
+    /// control.
+    fn get_ingress_admission_settings(
+        &self,
+        subnet_id: SubnetId,
+        version: RegistryVersion,
+    ) -> RegistryClientResult<IngressAdmissionSettings>;
+
     /// Returns gossip config
     fn get_gossip_config(
         &self,
@@ -254,6 +314,18 @@ impl<T: RegistryClient + ?Sized> SubnetRegistry for T {
         )
     }
 
+    fn get_ingress_admission_settings(
+        &self,
+        subnet_id: SubnetId,
+        version: RegistryVersion,
+    ) -> RegistryClientResult<IngressAdmissionSettings> {
+        let bytes = self.get_value(&make_subnet_record_key(subnet_id), version);
+        let subnet = deserialize_registry_value::<SubnetRecord>(bytes)?;
+        Ok(subnet
+            .and_then(|subnet| subnet.ingress_admission_config)
+            .map(IngressAdmissionSettings::from))
+    }
+
     fn get_gossip_config(
         &self,
         subnet_id: SubnetId,
@@ -625,6 +697,52 @@ mod tests {
         assert_eq!(node_ids, Some(vec![node_id(32), node_id(33)]));
     }
 
+    #[test]
+    fn can_get_ingress_admission_settings_from_subnet() {
+        let version = RegistryVersion::from(2);
+        let subnet_record = SubnetRecord {
+            ingress_admission_config: Some(IngressAdmissionConfig {
+                max_messages_per_second_per_canister: 100,
+                max_burst_per_canister: 0,
+                max_messages_per_second_per_sender: 0,
+                max_burst_per_sender: 50,
+                max_execution_backlog_blocks: 20,
+                retry_after_seconds: 5,
+            }),
+            ..Default::default()
+        };
+
+        let registry = create_test_registry_client(
+            version,
+            vec![
+                (subnet_id(4), subnet_record),
+                (subnet_id(5), SubnetRecord::default()),
+            ],
+            None,
+        );
+
+        assert_eq!(
+            registry
+                .get_ingress_admission_settings(subnet_id(4), version)
+                .unwrap(),
+            Some(IngressAdmissionSettings {
+                per_canister_rate_limit: Some(RateLimit {
+                    messages_per_second: 100,
+                    burst: 1,
+                }),
+                per_sender_rate_limit: None,
+                max_execution_backlog_blocks: Some(20),
+                retry_after: Duration::from_secs(5),
+            })
+        );
+        assert_eq!(
+            registry
+                .get_ingress_admission_settings(subnet_id(5), version)
+                .unwrap(),
+            None
+        );
+    }
+
     #[test]
     fn can_get_replica_version_from_subnet() {
         let subnet_id = subnet_id(4);
@@ -5,7 +5,10 @@ use ic_interfaces::{
 };
 use ic_types::{
     batch::{BatchPayload, ValidationContext},
-    consensus::{block_maker::SubnetRecords, Payload},
+    consensus::{
+        block_maker::{SubnetLoad, SubnetRecords},
+        Payload,
+    },
     Height, Time,
 };
 
@@ -20,6 +23,7 @@ impl PayloadBuilder for MockPayloadBuilder {
         _past_payloads: &[(Height, Time, Payload)],
         _context: &ValidationContext,
         _subnet_records: &SubnetRecords,
+        _subnet_load: &SubnetLoad,
     ) -> BatchPayload {
         Default::default()
     }
@@ -6,7 +6,7 @@ use ic_error_types::{ErrorCode, RejectCode};
 use ic_ic00_types::{
     self as ic00, CanisterChange, CanisterIdRecord, CanisterInstallMode,
     CanisterSettingsArgsBuilder, CanisterStatusResultV2, CanisterStatusType, EmptyBlob,
-    InstallCodeArgs, LogVisibility, Method, Payload, UpdateSettingsArgs, IC_00,
+    InstallCodeArgs, LogVisibility, Method, Payload, TimerPriority, UpdateSettingsArgs, IC_00,
 };
 use ic_registry_provisional_whitelist::ProvisionalWhitelist;
 use ic_replica_tests as utils;
@@ -713,6 +713,7 @@ fn can_get_canister_information() {
                 2592000,
                 Some(5_000_000_000_000u128),
                 LogVisibility::default(),
+                TimerPriority::default(),
                 0u128,
                 0u128,
                 0u128,
@@ -771,6 +772,7 @@ fn can_get_canister_information() {
                     259200,
                     None,
                     LogVisibility::default(),
+                    TimerPriority::default(),
                     0u128,
                     0u128,
                     0u128,
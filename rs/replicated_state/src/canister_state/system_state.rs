@@ -10,7 +10,9 @@ use crate::page_map::PageAllocatorFileDescriptor;
 use crate::{CanisterQueues, CanisterState, InputQueueType, PageMap, StateError};
 pub use call_context_manager::{CallContext, CallContextAction, CallContextManager, CallOrigin};
 use ic_base_types::NumSeconds;
-use ic_ic00_types::{CanisterChange, CanisterChangeDetails, CanisterChangeOrigin, LogVisibility};
+use ic_ic00_types::{
+    CanisterChange, CanisterChangeDetails, CanisterChangeOrigin, LogVisibility, TimerPriority,
+};
 use ic_logger::{error, ReplicaLogger};
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
@@ -334,6 +336,10 @@ pub struct SystemState {
 
     /// Log visibility of the canister.
     pub log_visibility: LogVisibility,
+
+    /// Priority of the global timer of the canister. A `High` priority only
+    /// takes effect while the canister has a non-zero compute allocation.
+    pub timer_priority: TimerPriority,
 }
 
 /// A wrapper around the different canister statuses.
@@ -704,6 +710,7 @@ impl SystemState {
             canister_history: CanisterHistory::default(),
             wasm_chunk_store,
             log_visibility: LogVisibility::default(),
+            timer_priority: TimerPriority::default(),
         }
     }
 
@@ -748,6 +755,7 @@ impl SystemState {
         wasm_chunk_store_data: PageMap,
         wasm_chunk_store_metadata: WasmChunkStoreMetadata,
         log_visibility: LogVisibility,
+        timer_priority: TimerPriority,
     ) -> Self {
         Self {
             controllers,
@@ -771,6 +779,7 @@ impl SystemState {
                 wasm_chunk_store_metadata,
             ),
             log_visibility,
+            timer_priority,
         }
     }
 
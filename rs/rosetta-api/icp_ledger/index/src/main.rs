@@ -400,7 +400,7 @@ fn append_blocks(new_blocks: Vec<EncodedBlock>) -> Result<(), String> {
 
         // add the block idx to the indices
         with_account_identifier_block_ids(|account_identifier_block_ids| {
-            for account_identifier in get_account_identifiers(&decoded_block)? {
+            for account_identifier in get_account_identifiers(block_index, &decoded_block)? {
                 account_identifier_block_ids.insert(
                     account_identifier_block_ids_key(account_identifier, block_index),
                     (),
@@ -428,13 +428,45 @@ fn process_balance_changes(block_index: BlockIndex, block: &Block) -> Result<(),
             ..
         } => {
             debit(block_index, from, amount.get_e8s() + fee.get_e8s());
-            credit(block_index, to, amount.get_e8s())
+            credit(block_index, to, amount.get_e8s());
+            // the fee is burned unless a fee collector is set
+            if let Some(fee_collector) = get_fee_collector(block_index, block)? {
+                credit(block_index, fee_collector, fee.get_e8s())
+            }
         }
         Operation::Approve { from, fee, .. } => debit(block_index, from, fee.get_e8s()),
     };
     Ok(())
 }
 
+fn get_fee_collector(
+    block_index: BlockIndex,
+    block: &Block,
+) -> Result<Option<AccountIdentifier>, String> {
+    if block.fee_collector.is_some() {
+        return Ok(block.fee_collector);
+    }
+    let Some(fee_collector_block_index) = block.fee_collector_block_index else {
+        return Ok(None);
+    };
+    let fee_collector_block = with_blocks(|blocks| blocks.get(fee_collector_block_index))
+        .ok_or_else(|| {
+            format!(
+                "Block at index {} has fee_collector_block_index {} but there is no block at that index",
+                block_index, fee_collector_block_index
+            )
+        })?;
+    let fee_collector_block =
+        decode_encoded_block(fee_collector_block_index, fee_collector_block.into())?;
+    match fee_collector_block.fee_collector {
+        Some(fee_collector) => Ok(Some(fee_collector)),
+        None => Err(format!(
+            "Block at index {} has fee_collector_block_index {} but that block has no fee_collector set",
+            block_index, fee_collector_block_index
+        )),
+    }
+}
+
 fn debit(block_index: BlockIndex, account_identifier: AccountIdentifier, amount: u64) {
     change_balance(account_identifier, |balance| {
         if balance < amount {
@@ -464,11 +496,23 @@ fn decode_encoded_block(block_index: BlockIndex, block: EncodedBlock) -> Result<
     })
 }
 
-fn get_account_identifiers(block: &Block) -> Result<Vec<AccountIdentifier>, String> {
+fn get_account_identifiers(
+    block_index: BlockIndex,
+    block: &Block,
+) -> Result<Vec<AccountIdentifier>, String> {
     match block.transaction.operation {
         Operation::Burn { from, .. } => Ok(vec![from]),
         Operation::Mint { to, .. } => Ok(vec![to]),
-        Operation::Transfer { from, to, .. } => Ok(vec![from, to]),
+        Operation::Transfer { from, to, .. } => {
+            let mut account_identifiers = vec![from, to];
+            // the block also changes the balance of the fee collector
+            if let Some(fee_collector) = get_fee_collector(block_index, block)? {
+                if !account_identifiers.contains(&fee_collector) {
+                    account_identifiers.push(fee_collector);
+                }
+            }
+            Ok(account_identifiers)
+        }
         Operation::Approve { from, spender, .. } => Ok(vec![from, spender]),
     }
 }
@@ -861,6 +861,60 @@ fn test_icp_balance_of() {
     );
 }
 
+#[test]
+fn test_fee_collector() {
+    let env = &StateMachine::new();
+    let fee_collector = account(42, 0);
+    let mut initial_balances = HashMap::new();
+    initial_balances.insert(
+        AccountIdentifier::from(account(1, 0)),
+        Tokens::from_e8s(1_000_000_000),
+    );
+    let args = LedgerCanisterInitPayload::builder()
+        .minting_account(AccountIdentifier::new(MINTER_PRINCIPAL, None))
+        .transfer_fee(Tokens::from_e8s(FEE))
+        .token_symbol_and_name(TOKEN_SYMBOL, TOKEN_NAME)
+        .archive_options(default_archive_options())
+        .initial_values(initial_balances)
+        .fee_collector_account(AccountIdentifier::from(fee_collector))
+        .build()
+        .unwrap();
+    let ledger_id = env
+        .install_canister(ledger_wasm(), Encode!(&args).unwrap(), None)
+        .unwrap();
+    let index_id = install_index(env, ledger_id);
+
+    // Enough transfers to archive the block that sets the fee collector.
+    for i in 0..(ARCHIVE_TRIGGER_THRESHOLD + 1) {
+        transfer(env, ledger_id, account(1, 0), account(2, 0), 1_000 + i);
+    }
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+    assert_ledger_index_parity(env, ledger_id, index_id);
+
+    assert_eq!(
+        icrc1_balance_of(env, ledger_id, fee_collector),
+        (ARCHIVE_TRIGGER_THRESHOLD + 1) * FEE
+    );
+    for account in [account(1, 0), account(2, 0), fee_collector] {
+        assert_eq!(
+            icrc1_balance_of(env, ledger_id, account),
+            index_balance_of(env, index_id, account)
+        );
+    }
+
+    // The transfers that paid the fee collector are in its history.
+    let fee_collector_txs =
+        get_account_identifier_transactions(env, index_id, fee_collector, None, u64::MAX);
+    assert_eq!(
+        fee_collector_txs.transactions.len() as u64,
+        ARCHIVE_TRIGGER_THRESHOLD + 1
+    );
+    assert_eq!(
+        fee_collector_txs.balance,
+        (ARCHIVE_TRIGGER_THRESHOLD + 1) * FEE
+    );
+}
+
 #[test]
 fn test_approve_args() {
     let initial_balances = HashMap::new();
@@ -6,7 +6,10 @@ use ic_ledger_canister_core::ledger::{
     self as core_ledger, LedgerContext, LedgerData, TransactionInfo,
 };
 use ic_ledger_core::{
-    approvals::AllowanceTable, balances::Balances, block::EncodedBlock, timestamp::TimeStamp,
+    approvals::{AllowanceTable, HeapAllowancesData},
+    balances::Balances,
+    block::{EncodedBlock, FeeCollector},
+    timestamp::TimeStamp,
 };
 use ic_ledger_core::{block::BlockIndex, tokens::Tokens};
 use ic_ledger_hash_of::HashOf;
@@ -64,7 +67,7 @@ fn unknown_token() -> String {
 pub struct Ledger {
     pub balances: LedgerBalances,
     #[serde(default)]
-    pub approvals: AllowanceTable<ApprovalKey, AccountIdentifier, Tokens>,
+    pub approvals: AllowanceTable<HeapAllowancesData<ApprovalKey, AccountIdentifier, Tokens>>,
     pub blockchain: Blockchain<dfn_runtime::DfnRuntime, IcpLedgerArchiveWasm>,
     // A cap on the maximum number of accounts.
     pub maximum_number_of_accounts: usize,
@@ -105,11 +108,16 @@ pub struct Ledger {
 
     #[serde(default)]
     pub feature_flags: FeatureFlags,
+
+    /// The account that receives the transfer fees. If not set then
+    /// the fees are burned.
+    #[serde(default)]
+    pub fee_collector: Option<FeeCollector<AccountIdentifier>>,
 }
 
 impl LedgerContext for Ledger {
     type AccountId = AccountIdentifier;
-    type Approvals = AllowanceTable<ApprovalKey, Self::AccountId, Tokens>;
+    type Approvals = AllowanceTable<HeapAllowancesData<ApprovalKey, Self::AccountId, Tokens>>;
     type BalancesStore = BTreeMap<AccountIdentifier, Tokens>;
     type Tokens = Tokens;
 
@@ -129,8 +137,8 @@ impl LedgerContext for Ledger {
         &mut self.approvals
     }
 
-    fn fee_collector(&self) -> Option<&ic_ledger_core::block::FeeCollector<Self::AccountId>> {
-        None
+    fn fee_collector(&self) -> Option<&FeeCollector<Self::AccountId>> {
+        self.fee_collector.as_ref()
     }
 }
 
@@ -196,10 +204,8 @@ impl LedgerData for Ledger {
         self.blocks_notified.remove(height);
     }
 
-    fn fee_collector_mut(
-        &mut self,
-    ) -> Option<&mut ic_ledger_core::block::FeeCollector<Self::AccountId>> {
-        None
+    fn fee_collector_mut(&mut self) -> Option<&mut FeeCollector<Self::AccountId>> {
+        self.fee_collector.as_mut()
     }
 }
 
@@ -223,6 +229,7 @@ impl Default for Ledger {
             token_symbol: unknown_token(),
             token_name: unknown_token(),
             feature_flags: FeatureFlags::default(),
+            fee_collector: None,
         }
     }
 }
@@ -327,6 +334,7 @@ impl Ledger {
         feature_flags: Option<FeatureFlags>,
         maximum_number_of_accounts: Option<usize>,
         accounts_overflow_trim_quantity: Option<usize>,
+        fee_collector_account: Option<AccountIdentifier>,
     ) {
         self.token_symbol = token_symbol.unwrap_or_else(|| "ICP".to_string());
         self.token_name = token_name.unwrap_or_else(|| "Internet Computer".to_string());
@@ -336,6 +344,10 @@ impl Ledger {
         if let Some(t) = transaction_window {
             self.transaction_window = t;
         }
+        if fee_collector_account == Some(minting_account) {
+            trap_with("The fee collector account cannot be the same as the minting account");
+        }
+        self.fee_collector = fee_collector_account.map(FeeCollector::from);
 
         for (to, amount) in initial_values.into_iter() {
             self.add_payment_with_timestamp(
@@ -445,6 +457,16 @@ impl Ledger {
         if let Some(feature_flags) = args.feature_flags {
             self.feature_flags = feature_flags;
         }
+        if let Some(change_fee_collector) = args.change_fee_collector {
+            self.fee_collector = change_fee_collector.into();
+            if let Some(fee_collector) = &self.fee_collector {
+                if Some(fee_collector.fee_collector) == self.minting_account_id {
+                    trap_with(
+                        "The fee collector account cannot be the same as the minting account",
+                    );
+                }
+            }
+        }
     }
 }
 
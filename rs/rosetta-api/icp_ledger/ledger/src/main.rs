@@ -3,16 +3,21 @@ use dfn_candid::{candid, candid_one, CandidOne};
 #[allow(unused_imports)]
 use dfn_core::BytesS;
 use dfn_core::{
-    api::{caller, data_certificate, print, set_certified_data, time_nanos, trap_with},
+    api::{
+        caller, data_certificate, is_controller, print, set_certified_data, time_nanos, trap_with,
+    },
     endpoint::reject_on_decode_error::{over, over_async, over_async_may_reject},
     over_init, printer, setup, stable,
 };
 use dfn_protobuf::protobuf;
-use ic_base_types::CanisterId;
+use ic_base_types::{CanisterId, PrincipalId};
 use ic_canister_log::{LogEntry, Sink};
 use ic_icrc1::endpoints::{convert_transfer_error, StandardRecord};
 use ic_ledger_canister_core::{
-    archive::{Archive, ArchiveOptions},
+    archive::{
+        consolidate_archive_nodes, Archive, ArchiveOptions, ConsolidateArchivesArgs,
+        ConsolidatedArchives, FailedToConsolidateArchiveNodes,
+    },
     ledger::{
         apply_transaction, archive_blocks, block_locations, find_block_in_archive, LedgerAccess,
         TransferError as CoreTransferError,
@@ -37,6 +42,12 @@ use icp_ledger::{
 use icrc_ledger_types::icrc1::transfer::TransferArg;
 use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc21::{
+    consent_message::{build_consent_info, TokenInfo},
+    errors::{ErrorInfo, Icrc21Error},
+    requests::ConsentMessageRequest,
+    responses::ConsentInfo,
+};
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value, icrc3::archive::QueryArchiveFn,
 };
@@ -98,6 +109,7 @@ fn init(
     feature_flags: Option<FeatureFlags>,
     maximum_number_of_accounts: Option<usize>,
     accounts_overflow_trim_quantity: Option<usize>,
+    fee_collector_account: Option<AccountIdentifier>,
 ) {
     print(format!(
         "[ledger] init(): minting account is {}",
@@ -116,6 +128,7 @@ fn init(
         feature_flags,
         maximum_number_of_accounts,
         accounts_overflow_trim_quantity,
+        fee_collector_account,
     );
     match max_message_size_bytes {
         None => {
@@ -592,6 +605,10 @@ fn icrc1_supported_standards() -> Vec<StandardRecord> {
             url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
         });
     }
+    standards.push(StandardRecord {
+        name: "ICRC-21".to_string(),
+        url: "https://github.com/dfinity/wg-identity-authentication/blob/main/topics/ICRC-21/icrc_21_consent_msg.md".to_string(),
+    });
     standards
 }
 
@@ -687,6 +704,7 @@ fn canister_init(arg: LedgerCanisterPayload) {
             arg.feature_flags,
             arg.maximum_number_of_accounts,
             arg.accounts_overflow_trim_quantity,
+            arg.fee_collector_account,
         ),
         LedgerCanisterPayload::Upgrade(_) => {
             trap_with("Cannot initialize the canister with an Upgrade argument. Please provide an Init argument.");
@@ -720,6 +738,7 @@ fn main() {
                         arg.feature_flags,
                         arg.maximum_number_of_accounts,
                         arg.accounts_overflow_trim_quantity,
+                        arg.fee_collector_account,
                     ),
                     Err(old_err) =>
                     trap_with(&format!("Unable to decode init argument.\nDecode as new init returned the error {}\nDecode as old init returned the error {}", new_err, old_err))
@@ -1238,6 +1257,39 @@ fn archives_candid() {
     over(candid_one, |()| archives());
 }
 
+/// Merges consecutive archives into a new archive and replaces them in the
+/// archive index. The retired archives are not deleted by the ledger.
+#[candid_method(update, rename = "consolidate_archives")]
+async fn consolidate_archives(
+    args: ConsolidateArchivesArgs,
+) -> Result<ConsolidatedArchives, String> {
+    let nodes = args
+        .archive_ids
+        .into_iter()
+        .map(|archive_id| CanisterId::unchecked_from_principal(PrincipalId(archive_id)))
+        .collect();
+    let max_msg_size = *MAX_MESSAGE_SIZE_BYTES.read().unwrap();
+    consolidate_archive_nodes::<Access>(
+        DebugOutSink,
+        nodes,
+        args.node_max_memory_size_bytes,
+        max_msg_size as u64,
+    )
+    .await
+    .map(ConsolidatedArchives::from)
+    .map_err(|FailedToConsolidateArchiveNodes(msg)| msg)
+}
+
+#[export_name = "canister_update consolidate_archives"]
+fn consolidate_archives_candid() {
+    over_async_may_reject(candid_one, |args: ConsolidateArchivesArgs| async {
+        if !is_controller(&caller()) {
+            return Err("Only the controllers of the ledger can consolidate archives.".to_string());
+        }
+        Ok(consolidate_archives(args).await)
+    })
+}
+
 #[export_name = "canister_query icrc1_metadata"]
 fn icrc1_metadata_candid() {
     over(candid_one, |()| icrc1_metadata())
@@ -1499,6 +1551,30 @@ fn icrc2_allowance_candid() {
     over(candid_one, icrc2_allowance)
 }
 
+#[candid_method(update, rename = "icrc21_canister_call_consent_message")]
+fn icrc21_canister_call_consent_message(
+    consent_msg_request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    if consent_msg_request.method.starts_with("icrc2_")
+        && !LEDGER.read().unwrap().feature_flags.icrc2
+    {
+        return Err(Icrc21Error::UnsupportedCanisterCall(ErrorInfo {
+            description: "ICRC-2 features are not enabled on the ledger.".to_string(),
+        }));
+    }
+    let token = TokenInfo {
+        symbol: icrc1_symbol(),
+        decimals: icrc1_decimals(),
+        fee: icrc1_fee(),
+    };
+    build_consent_info(consent_msg_request, caller().into(), &token)
+}
+
+#[export_name = "canister_update icrc21_canister_call_consent_message"]
+fn icrc21_canister_call_consent_message_candid() {
+    over(candid_one, icrc21_canister_call_consent_message)
+}
+
 candid::export_service!();
 
 #[export_name = "canister_query __get_candid_interface_tmp_hack"]
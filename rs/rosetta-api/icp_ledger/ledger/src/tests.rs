@@ -263,6 +263,7 @@ fn serialize() {
         None,
         None,
         None,
+        None,
     );
 
     let txn = Transaction::new(
@@ -279,6 +280,8 @@ fn serialize() {
         parent_hash: state.blockchain.last_hash,
         transaction: txn,
         timestamp: (SystemTime::UNIX_EPOCH + Duration::new(2000000000, 123456789)).into(),
+        fee_collector: None,
+        fee_collector_block_index: None,
     };
 
     let block_bytes = block.clone().encode();
@@ -305,6 +308,8 @@ fn serialize() {
         parent_hash: Some(block_hash),
         transaction: txn2,
         timestamp: (SystemTime::UNIX_EPOCH + Duration::new(2000000000, 123456790)).into(),
+        fee_collector: None,
+        fee_collector_block_index: None,
     };
 
     state.add_block(block2).unwrap();
@@ -603,6 +608,7 @@ fn get_blocks_returns_correct_blocks() {
         None,
         None,
         None,
+        None,
     );
 
     for i in 0..10 {
@@ -620,6 +626,8 @@ fn get_blocks_returns_correct_blocks() {
             parent_hash: state.blockchain.last_hash,
             transaction: txn,
             timestamp: (SystemTime::UNIX_EPOCH + Duration::new(1, 0)).into(),
+            fee_collector: None,
+            fee_collector_block_index: None,
         };
 
         state.add_block(block).unwrap();
@@ -668,6 +676,7 @@ fn test_purge() {
         None,
         None,
         None,
+        None,
     );
     let little_later = genesis + Duration::from_millis(1);
 
@@ -1371,3 +1380,95 @@ fn test_approval_burn_from() {
     assert_eq!(ctx.balances().account_balance(&spender), Tokens::ZERO);
     assert_eq!(ctx.balances().total_supply().get_e8s(), 90_000);
 }
+
+#[test]
+fn test_fee_collector() {
+    let mut state = Ledger::default();
+    let fee_collector = test_account_id(2);
+
+    state.from_init(
+        vec![(test_account_id(0), Tokens::new(1000000, 0).unwrap())]
+            .into_iter()
+            .collect(),
+        test_account_id(1000),
+        None,
+        SystemTime::UNIX_EPOCH.into(),
+        None,
+        HashSet::new(),
+        None,
+        Some("ICP".into()),
+        Some("icp".into()),
+        None,
+        None,
+        None,
+        Some(fee_collector),
+    );
+
+    let now = TimeStamp::from(SystemTime::UNIX_EPOCH + Duration::new(1, 0));
+    for i in 0..2 {
+        state
+            .add_payment_with_timestamp(
+                Memo(i),
+                Operation::Transfer {
+                    from: test_account_id(0),
+                    to: test_account_id(1),
+                    spender: None,
+                    amount: tokens(1_000),
+                    fee: state.transfer_fee,
+                },
+                None,
+                now,
+            )
+            .unwrap();
+    }
+
+    assert_eq!(
+        state.balances.account_balance(&fee_collector),
+        state.transfer_fee.checked_add(&state.transfer_fee).unwrap()
+    );
+
+    // The fee collector is written to the first block and the
+    // following blocks refer to that block.
+    let blocks: Vec<Block> = state
+        .blockchain
+        .blocks
+        .iter()
+        .map(|b| Block::decode(b.clone()).unwrap())
+        .collect();
+    assert_eq!(blocks.len(), 3);
+    assert_eq!(blocks[0].fee_collector, Some(fee_collector));
+    assert_eq!(blocks[0].fee_collector_block_index, None);
+    for block in &blocks[1..] {
+        assert_eq!(block.fee_collector, None);
+        assert_eq!(block.fee_collector_block_index, Some(0));
+    }
+
+    // Unsetting the fee collector burns the fees again.
+    state.upgrade(icp_ledger::UpgradeArgs {
+        maximum_number_of_accounts: None,
+        icrc1_minting_account: None,
+        feature_flags: None,
+        change_fee_collector: Some(icp_ledger::ChangeFeeCollector::Unset),
+    });
+    state
+        .add_payment_with_timestamp(
+            Memo(2),
+            Operation::Transfer {
+                from: test_account_id(0),
+                to: test_account_id(1),
+                spender: None,
+                amount: tokens(1_000),
+                fee: state.transfer_fee,
+            },
+            None,
+            now,
+        )
+        .unwrap();
+    assert_eq!(
+        state.balances.account_balance(&fee_collector),
+        state.transfer_fee.checked_add(&state.transfer_fee).unwrap()
+    );
+    let last_block = Block::decode(state.blockchain.blocks.last().unwrap().clone()).unwrap();
+    assert_eq!(last_block.fee_collector, None);
+    assert_eq!(last_block.fee_collector_block_index, None);
+}
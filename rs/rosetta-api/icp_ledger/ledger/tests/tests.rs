@@ -5,15 +5,18 @@ use dfn_protobuf::ProtoBuf;
 use ic_base_types::CanisterId;
 use ic_icrc1_ledger_sm_tests::{
     balance_of, default_approve_args, default_transfer_from_args, expect_icrc2_disabled,
-    get_allowance, send_approval, send_transfer_from, supported_standards, transfer, MINTER,
+    get_allowance, send_approval, send_transfer_from, supported_standards, total_supply, transfer,
+    MINTER,
 };
+use ic_ledger_canister_core::archive::{ConsolidateArchivesArgs, ConsolidatedArchives};
 use ic_ledger_core::{block::BlockType, Tokens};
 use ic_state_machine_tests::{ErrorCode, PrincipalId, StateMachine, UserError};
 use icp_ledger::{
-    AccountIdBlob, AccountIdentifier, ArchiveOptions, ArchivedBlocksRange, Block, CandidBlock,
-    CandidOperation, CandidTransaction, FeatureFlags, GetBlocksArgs, GetBlocksRes, GetBlocksResult,
-    InitArgs, LedgerCanisterInitPayload, LedgerCanisterPayload, Operation, QueryBlocksResponse,
-    QueryEncodedBlocksResponse, TimeStamp, UpgradeArgs, DEFAULT_TRANSFER_FEE,
+    AccountIdBlob, AccountIdentifier, ArchiveInfo, ArchiveOptions, ArchivedBlocksRange, Archives,
+    Block, CandidBlock, CandidOperation, CandidTransaction, ChangeFeeCollector, FeatureFlags,
+    GetBlocksArgs, GetBlocksRes, GetBlocksResult, InitArgs, LedgerCanisterInitPayload,
+    LedgerCanisterPayload, Operation, QueryBlocksResponse, QueryEncodedBlocksResponse, TimeStamp,
+    UpgradeArgs, DEFAULT_TRANSFER_FEE,
 };
 use icrc_ledger_types::icrc1::{
     account::Account,
@@ -203,6 +206,11 @@ fn check_transfer_model() {
     ic_icrc1_ledger_sm_tests::check_transfer_model(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_icrc21_standard() {
+    ic_icrc1_ledger_sm_tests::test_icrc21_standard(ledger_wasm(), encode_init_args);
+}
+
 #[test]
 fn check_old_init() {
     let env = StateMachine::new();
@@ -220,6 +228,7 @@ fn check_old_init() {
         feature_flags: None,
         maximum_number_of_accounts: None,
         accounts_overflow_trim_quantity: None,
+        fee_collector_account: None,
     })
     .unwrap();
     env.install_canister(ledger_wasm(), old_init, None)
@@ -589,6 +598,7 @@ fn test_feature_flags() {
             maximum_number_of_accounts: None,
             icrc1_minting_account: None,
             feature_flags: Some(FeatureFlags { icrc2: false }),
+            change_fee_collector: None,
         })))
         .unwrap(),
     )
@@ -610,6 +620,7 @@ fn test_feature_flags() {
             maximum_number_of_accounts: None,
             icrc1_minting_account: None,
             feature_flags: Some(FeatureFlags { icrc2: true }),
+            change_fee_collector: None,
         })))
         .unwrap(),
     )
@@ -620,7 +631,7 @@ fn test_feature_flags() {
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(standards, vec!["ICRC-1", "ICRC-2", "ICRC-21"]);
 
     let block_index =
         send_approval(&env, canister_id, from.0, &approve_args).expect("approval failed");
@@ -637,6 +648,57 @@ fn test_feature_flags() {
     assert_eq!(balance_of(&env, canister_id, spender.0), 0);
 }
 
+#[test]
+fn test_fee_collector() {
+    let ledger_wasm = ledger_wasm();
+
+    let from = PrincipalId::new_user_test_id(1);
+    let to = PrincipalId::new_user_test_id(2);
+    let fee_collector = PrincipalId::new_user_test_id(3);
+
+    let env = StateMachine::new();
+    let mut initial_balances = HashMap::new();
+    initial_balances.insert(Account::from(from.0).into(), Tokens::from_e8s(1_000_000));
+    let payload = LedgerCanisterInitPayload::builder()
+        .minting_account(MINTER.into())
+        .icrc1_minting_account(MINTER)
+        .initial_values(initial_balances)
+        .transfer_fee(Tokens::from_e8s(10_000))
+        .token_symbol_and_name("ICP", "Internet Computer")
+        .fee_collector_account(Account::from(fee_collector.0).into())
+        .build()
+        .unwrap();
+    let canister_id = env
+        .install_canister(
+            ledger_wasm.clone(),
+            CandidOne(payload).into_bytes().unwrap(),
+            None,
+        )
+        .expect("Unable to install the Ledger canister with a fee collector");
+
+    transfer(&env, canister_id, from.0, to.0, 100_000).expect("transfer failed");
+    assert_eq!(balance_of(&env, canister_id, fee_collector.0), 10_000);
+    assert_eq!(balance_of(&env, canister_id, from.0), 1_000_000 - 110_000);
+
+    // Unset the fee collector, the fees are burned again.
+    env.upgrade_canister(
+        canister_id,
+        ledger_wasm,
+        Encode!(&LedgerCanisterPayload::Upgrade(Some(UpgradeArgs {
+            maximum_number_of_accounts: None,
+            icrc1_minting_account: None,
+            feature_flags: None,
+            change_fee_collector: Some(ChangeFeeCollector::Unset),
+        })))
+        .unwrap(),
+    )
+    .unwrap();
+
+    transfer(&env, canister_id, from.0, to.0, 100_000).expect("transfer failed");
+    assert_eq!(balance_of(&env, canister_id, fee_collector.0), 10_000);
+    assert_eq!(total_supply(&env, canister_id), 1_000_000 - 10_000);
+}
+
 #[test]
 fn test_transfer_from_smoke() {
     ic_icrc1_ledger_sm_tests::test_transfer_from_smoke(ledger_wasm(), encode_init_args);
@@ -720,6 +782,140 @@ fn account_identifier_test() {
     assert_eq!(expected_account_id, account_id);
 }
 
+fn list_archives(env: &StateMachine, ledger: CanisterId) -> Vec<ArchiveInfo> {
+    Decode!(
+        &env.query(ledger, "archives", Encode!().unwrap())
+            .expect("failed to query archives")
+            .bytes(),
+        Archives
+    )
+    .expect("failed to decode archives response")
+    .archives
+}
+
+fn consolidate_archives(
+    env: &StateMachine,
+    ledger: CanisterId,
+    caller: PrincipalId,
+    args: &ConsolidateArchivesArgs,
+) -> Result<Result<ConsolidatedArchives, String>, UserError> {
+    let res = env
+        .execute_ingress_as(
+            caller,
+            ledger,
+            "consolidate_archives",
+            Encode!(args).unwrap(),
+        )?
+        .bytes();
+    Ok(Decode!(&res, Result<ConsolidatedArchives, String>).unwrap())
+}
+
+#[test]
+fn test_consolidate_archives() {
+    let env = StateMachine::new();
+    let payload = LedgerCanisterInitPayload::builder()
+        .minting_account(MINTER.into())
+        .icrc1_minting_account(MINTER)
+        .transfer_fee(Tokens::from_e8s(10_000))
+        .token_symbol_and_name("ICP", "Internet Computer")
+        .archive_options(ArchiveOptions {
+            trigger_threshold: 4,
+            num_blocks_to_archive: 4usize,
+            // Small archives so that every archiving round fills one of them.
+            node_max_memory_size_bytes: Some(1_000),
+            max_message_size_bytes: None,
+            controller_id: PrincipalId::new_anonymous(),
+            more_controller_ids: None,
+            cycles_for_archive_creation: None,
+            max_transactions_per_response: None,
+        })
+        .build()
+        .unwrap();
+    // The anonymous principal installing the ledger is its controller.
+    let ledger = env
+        .install_canister(ledger_wasm(), Encode!(&payload).unwrap(), None)
+        .expect("Unable to install the Ledger canister");
+
+    let user = Principal::from_slice(&[1]);
+    for i in 0..24 {
+        transfer(&env, ledger, MINTER, user, 1_000_000 + i).unwrap();
+    }
+    let archives = list_archives(&env, ledger);
+    assert!(
+        archives.len() >= 2,
+        "expected at least 2 archives, got {:?}",
+        archives
+    );
+    let blocks_before = query_blocks(&env, user, ledger, 0, 1000);
+
+    let args = ConsolidateArchivesArgs {
+        archive_ids: archives
+            .iter()
+            .map(|archive| archive.canister_id.get().0)
+            .collect(),
+        node_max_memory_size_bytes: Some(1_000),
+    };
+
+    // Only the controllers of the ledger can consolidate archives.
+    assert!(consolidate_archives(&env, ledger, PrincipalId::new_user_test_id(1), &args).is_err());
+
+    // The blocks do not fit into an archive of the size of a single archive.
+    let err = consolidate_archives(&env, ledger, PrincipalId::new_anonymous(), &args)
+        .unwrap()
+        .unwrap_err();
+    assert!(err.contains("exceeds the capacity"), "{}", err);
+    assert_eq!(list_archives(&env, ledger), archives);
+
+    let args = ConsolidateArchivesArgs {
+        node_max_memory_size_bytes: Some(1024 * 1024),
+        ..args
+    };
+    let consolidated = consolidate_archives(&env, ledger, PrincipalId::new_anonymous(), &args)
+        .unwrap()
+        .expect("failed to consolidate archives");
+    assert_eq!(consolidated.block_range_start, Nat::from(0u8));
+    assert_eq!(consolidated.retired_archive_ids, args.archive_ids);
+    assert_eq!(
+        list_archives(&env, ledger)
+            .into_iter()
+            .map(|archive| archive.canister_id.get().0)
+            .collect::<Vec<_>>(),
+        vec![consolidated.archive_id]
+    );
+
+    // All archived blocks are served by the new archive.
+    let blocks_after = query_blocks(&env, user, ledger, 0, 1000);
+    assert_eq!(
+        blocks_after.first_block_index,
+        blocks_before.first_block_index
+    );
+    let archived_blocks: Vec<_> = blocks_after
+        .archived_blocks
+        .iter()
+        .flat_map(|range| {
+            assert_eq!(range.callback.canister_id, consolidated.archive_id);
+            Decode!(
+                &env.query(
+                    CanisterId::unchecked_from_principal(range.callback.canister_id.into()),
+                    range.callback.method.to_owned(),
+                    Encode!(&GetBlocksArgs {
+                        start: range.start,
+                        length: range.length as usize
+                    })
+                    .unwrap()
+                )
+                .unwrap()
+                .bytes(),
+                GetBlocksResult
+            )
+            .unwrap()
+            .unwrap()
+            .blocks
+        })
+        .collect();
+    assert_eq!(archived_blocks.len() as u64, blocks_after.first_block_index);
+}
+
 #[test]
 fn test_query_archived_blocks() {
     let env = StateMachine::new();
@@ -306,6 +306,12 @@ pub struct Block {
     pub timestamp: ::core::option::Option<TimeStamp>,
     #[prost(message, optional, tag = "3")]
     pub transaction: ::core::option::Option<Transaction>,
+    /// Only set in the first block after the fee collector has been set.
+    #[prost(message, optional, tag = "4")]
+    pub fee_collector: ::core::option::Option<AccountIdentifier>,
+    /// The index of the block that set the current fee collector.
+    #[prost(message, optional, tag = "5")]
+    pub fee_collector_block_index: ::core::option::Option<BlockIndex>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -117,6 +117,8 @@ pub fn apply_operation<C>(
 where
     C: LedgerContext<AccountId = AccountIdentifier, Tokens = Tokens>,
 {
+    let fee_collector = context.fee_collector().map(|fc| fc.fee_collector);
+    let fee_collector = fee_collector.as_ref();
     match operation {
         Operation::Burn {
             from,
@@ -193,7 +195,7 @@ where
                 // account owner.
                 context
                     .balances_mut()
-                    .transfer(from, to, *amount, *fee, None)?;
+                    .transfer(from, to, *amount, *fee, fee_collector)?;
                 return Ok(());
             }
 
@@ -211,7 +213,7 @@ where
             }
             context
                 .balances_mut()
-                .transfer(from, to, *amount, *fee, None)?;
+                .transfer(from, to, *amount, *fee, fee_collector)?;
             context
                 .approvals_mut()
                 .use_allowance(from, &spender.unwrap(), used_allowance, now)
@@ -350,6 +352,13 @@ pub struct Block {
     pub transaction: Transaction,
     /// Nanoseconds since the Unix epoch.
     pub timestamp: TimeStamp,
+    /// The account that received the fee of this block. Only set in the
+    /// first block after the fee collector has been set, the following
+    /// blocks refer to that block through `fee_collector_block_index`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_collector: Option<AccountIdentifier>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_collector_block_index: Option<BlockIndex>,
 }
 
 impl Block {
@@ -427,12 +436,22 @@ impl BlockType for Block {
         transaction: Self::Transaction,
         timestamp: TimeStamp,
         _effective_fee: Tokens,
-        _fee_collector: Option<FeeCollector<AccountIdentifier>>,
+        fee_collector: Option<FeeCollector<AccountIdentifier>>,
     ) -> Self {
+        let (fee_collector, fee_collector_block_index) = match fee_collector {
+            Some(FeeCollector {
+                fee_collector,
+                block_index: None,
+            }) => (Some(fee_collector), None),
+            Some(FeeCollector { block_index, .. }) => (None, block_index),
+            None => (None, None),
+        };
         Self {
             parent_hash,
             transaction,
             timestamp,
+            fee_collector,
+            fee_collector_block_index,
         }
     }
 }
@@ -464,6 +483,21 @@ pub struct LedgerCanisterInitPayload(pub LedgerCanisterPayload);
 #[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
 pub struct LedgerCanisterUpgradePayload(pub LedgerCanisterPayload);
 
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub enum ChangeFeeCollector {
+    Unset,
+    SetTo(AccountIdentifier),
+}
+
+impl From<ChangeFeeCollector> for Option<FeeCollector<AccountIdentifier>> {
+    fn from(value: ChangeFeeCollector) -> Self {
+        match value {
+            ChangeFeeCollector::Unset => None,
+            ChangeFeeCollector::SetTo(account) => Some(FeeCollector::from(account)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
 pub struct UpgradeArgs {
     #[serde(default, skip_serializing_if = "Option::is_none")]
@@ -474,6 +508,9 @@ pub struct UpgradeArgs {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub feature_flags: Option<FeatureFlags>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub change_fee_collector: Option<ChangeFeeCollector>,
 }
 
 // This is how we pass arguments to 'init' in main.rs
@@ -492,6 +529,7 @@ pub struct InitArgs {
     pub feature_flags: Option<FeatureFlags>,
     pub maximum_number_of_accounts: Option<usize>,
     pub accounts_overflow_trim_quantity: Option<usize>,
+    pub fee_collector_account: Option<AccountIdentifier>,
 }
 
 impl LedgerCanisterInitPayload {
@@ -526,6 +564,7 @@ pub struct LedgerCanisterInitPayloadBuilder {
     feature_flags: Option<FeatureFlags>,
     maximum_number_of_accounts: Option<usize>,
     accounts_overflow_trim_quantity: Option<usize>,
+    fee_collector_account: Option<AccountIdentifier>,
 }
 
 impl LedgerCanisterInitPayloadBuilder {
@@ -544,6 +583,7 @@ impl LedgerCanisterInitPayloadBuilder {
             feature_flags: None,
             maximum_number_of_accounts: None,
             accounts_overflow_trim_quantity: None,
+            fee_collector_account: None,
         }
     }
 
@@ -615,6 +655,11 @@ impl LedgerCanisterInitPayloadBuilder {
         self
     }
 
+    pub fn fee_collector_account(mut self, fee_collector_account: AccountIdentifier) -> Self {
+        self.fee_collector_account = Some(fee_collector_account);
+        self
+    }
+
     pub fn build(self) -> Result<LedgerCanisterInitPayload, String> {
         let minting_account = self
             .minting_account
@@ -635,6 +680,13 @@ impl LedgerCanisterInitPayloadBuilder {
             );
         }
 
+        if self.fee_collector_account == Some(minting_account) {
+            return Err(
+                "fee_collector_account cannot be the same account as the minting_account"
+                    .to_string(),
+            );
+        }
+
         Ok(LedgerCanisterInitPayload(LedgerCanisterPayload::Init(
             InitArgs {
                 minting_account,
@@ -650,6 +702,7 @@ impl LedgerCanisterInitPayloadBuilder {
                 feature_flags: self.feature_flags,
                 maximum_number_of_accounts: self.maximum_number_of_accounts,
                 accounts_overflow_trim_quantity: self.accounts_overflow_trim_quantity,
+                fee_collector_account: self.fee_collector_account,
             },
         )))
     }
@@ -659,6 +712,7 @@ pub struct LedgerCanisterUpgradePayloadBuilder {
     maximum_number_of_accounts: Option<usize>,
     icrc1_minting_account: Option<Account>,
     feature_flags: Option<FeatureFlags>,
+    change_fee_collector: Option<ChangeFeeCollector>,
 }
 
 impl LedgerCanisterUpgradePayloadBuilder {
@@ -667,6 +721,7 @@ impl LedgerCanisterUpgradePayloadBuilder {
             maximum_number_of_accounts: None,
             icrc1_minting_account: None,
             feature_flags: None,
+            change_fee_collector: None,
         }
     }
 
@@ -680,12 +735,18 @@ impl LedgerCanisterUpgradePayloadBuilder {
         self
     }
 
+    pub fn change_fee_collector(mut self, change_fee_collector: ChangeFeeCollector) -> Self {
+        self.change_fee_collector = Some(change_fee_collector);
+        self
+    }
+
     pub fn build(self) -> Result<LedgerCanisterUpgradePayload, String> {
         Ok(LedgerCanisterUpgradePayload(
             LedgerCanisterPayload::Upgrade(Some(UpgradeArgs {
                 maximum_number_of_accounts: self.maximum_number_of_accounts,
                 icrc1_minting_account: self.icrc1_minting_account,
                 feature_flags: self.feature_flags,
+                change_fee_collector: self.change_fee_collector,
             })),
         ))
     }
@@ -1028,6 +1089,8 @@ pub struct CandidBlock {
     pub parent_hash: Option<[u8; HASH_LENGTH]>,
     pub transaction: CandidTransaction,
     pub timestamp: TimeStamp,
+    pub fee_collector: Option<AccountIdBlob>,
+    pub fee_collector_block_index: Option<BlockIndex>,
 }
 
 impl From<Block> for CandidBlock {
@@ -1036,6 +1099,8 @@ impl From<Block> for CandidBlock {
             parent_hash,
             transaction,
             timestamp,
+            fee_collector,
+            fee_collector_block_index,
         }: Block,
     ) -> Self {
         Self {
@@ -1047,6 +1112,8 @@ impl From<Block> for CandidBlock {
                 created_at_time: transaction.created_at_time.unwrap_or(timestamp),
             },
             timestamp,
+            fee_collector: fee_collector.map(|fc| fc.to_address()),
+            fee_collector_block_index,
         }
     }
 }
@@ -1058,6 +1125,12 @@ impl TryFrom<CandidBlock> for Block {
             parent_hash: value.parent_hash.map(HashOf::<EncodedBlock>::new),
             transaction: Transaction::try_from(value.transaction)?,
             timestamp: value.timestamp,
+            fee_collector: value
+                .fee_collector
+                .map(AccountIdentifier::from_address)
+                .transpose()
+                .map_err(|err| err.to_string())?,
+            fee_collector_block_index: value.fee_collector_block_index,
         })
     }
 }
@@ -1400,13 +1473,30 @@ mod test {
     }
 
     fn arb_block() -> impl Strategy<Value = Block> {
-        (arb_parent_hash(), arb_transaction(), arb_timestamp()).prop_map(
-            |(parent_hash, transaction, timestamp)| Block {
-                parent_hash,
-                transaction,
-                timestamp,
-            },
+        (
+            arb_parent_hash(),
+            arb_transaction(),
+            arb_timestamp(),
+            proptest::option::of(arb_account()),
+            proptest::option::of(any::<u64>()),
         )
+            .prop_map(
+                |(
+                    parent_hash,
+                    transaction,
+                    timestamp,
+                    fee_collector,
+                    fee_collector_block_index,
+                )| {
+                    Block {
+                        parent_hash,
+                        transaction,
+                        timestamp,
+                        fee_collector,
+                        fee_collector_block_index,
+                    }
+                },
+            )
     }
 
     #[test]
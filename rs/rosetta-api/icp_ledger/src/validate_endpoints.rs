@@ -545,10 +545,17 @@ impl ToProto for Block {
 
         let timestamp = pb.timestamp.ok_or("This block lacks a timestamp")?;
 
+        let fee_collector = match pb.fee_collector {
+            Some(fee_collector) => Some(AccountIdentifier::from_proto(fee_collector)?),
+            None => None,
+        };
+
         Ok(Block {
             parent_hash,
             transaction: Transaction::from_proto(transaction)?,
             timestamp: timestamp_from_proto(timestamp),
+            fee_collector,
+            fee_collector_block_index: pb.fee_collector_block_index.map(|b| b.height),
         })
     }
 
@@ -557,6 +564,10 @@ impl ToProto for Block {
             parent_hash: self.parent_hash.map(hash_into_proto),
             transaction: Some(self.transaction.into_proto()),
             timestamp: Some(timestamp_into_proto(self.timestamp)),
+            fee_collector: self.fee_collector.map(AccountIdentifier::into_proto),
+            fee_collector_block_index: self
+                .fee_collector_block_index
+                .map(|height| protobuf::BlockIndex { height }),
         }
     }
 }
@@ -128,6 +128,8 @@ prop_compose! {
             parent_hash: phash.map(HashOf::new),
             transaction,
             timestamp,
+            fee_collector: None,
+            fee_collector_block_index: None,
         }
     }
 }
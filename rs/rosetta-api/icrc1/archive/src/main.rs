@@ -1,4 +1,4 @@
-use candid::{candid_method, Principal};
+use candid::{candid_method, CandidType, Nat, Principal};
 use ic_canisters_http_types::{HttpRequest, HttpResponse, HttpResponseBuilder};
 use ic_cdk_macros::{init, post_upgrade, query, update};
 use ic_icrc1::{blocks::encoded_block_to_generic_block, Block};
@@ -8,8 +8,10 @@ use ic_stable_structures::{
     cell::Cell as StableCell, log::Log as StableLog, memory_manager::MemoryManager,
     storable::Bound, DefaultMemoryImpl, RestrictedMemory, Storable,
 };
-use icrc_ledger_types::icrc3::blocks::BlockRange;
 use icrc_ledger_types::icrc3::blocks::GenericBlock as IcrcBlock;
+use icrc_ledger_types::icrc3::blocks::{
+    BlockRange, BlockWithId, GetBlocksRequest, GetBlocksResult, ICRC3Value,
+};
 
 use icrc_ledger_types::icrc3::transactions::Transaction;
 use icrc_ledger_types::icrc3::transactions::{GetTransactionsRequest, TransactionRange};
@@ -250,6 +252,40 @@ fn decode_block_range<R>(start: u64, length: u64, decoder: impl Fn(u64, Vec<u8>)
     })
 }
 
+#[derive(CandidType, Deserialize)]
+struct GetEncodedBlocksArgs {
+    start: u64,
+    length: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+enum GetEncodedBlocksError {
+    BadFirstBlockIndex {
+        requested_index: u64,
+        first_valid_index: u64,
+    },
+}
+
+/// Get length encoded blocks starting at start BlockIndex, as they were
+/// appended by the ledger. Used by the ledger to consolidate archives.
+#[query]
+#[candid_method(query)]
+fn get_encoded_blocks(
+    args: GetEncodedBlocksArgs,
+) -> Result<Vec<EncodedBlock>, GetEncodedBlocksError> {
+    let GetEncodedBlocksArgs { start, length } = args;
+    let first_valid_index = with_archive_opts(|opts| opts.block_index_offset);
+    if start < first_valid_index {
+        return Err(GetEncodedBlocksError::BadFirstBlockIndex {
+            requested_index: start,
+            first_valid_index,
+        });
+    }
+    Ok(decode_block_range(start, length, |_, bytes| {
+        EncodedBlock::from(bytes)
+    }))
+}
+
 #[query]
 #[candid_method(query)]
 fn get_transactions(req: GetTransactionsRequest) -> TransactionRange {
@@ -273,6 +309,33 @@ fn get_blocks(req: GetTransactionsRequest) -> BlockRange {
     BlockRange { blocks }
 }
 
+/// Get the blocks in the requested ranges in the ICRC-3 format.
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(reqs: Vec<GetBlocksRequest>) -> GetBlocksResult {
+    let max_blocks = with_archive_opts(|opts| opts.max_transactions_per_response);
+    let mut blocks = vec![];
+    for req in reqs {
+        let (start, length) = req
+            .as_start_and_length()
+            .unwrap_or_else(|msg| ic_cdk::api::trap(&msg));
+        let length = length.min(max_blocks.saturating_sub(blocks.len() as u64));
+        blocks.extend(decode_block_range(start, length, |id, bytes| BlockWithId {
+            id: Nat::from(id),
+            block: ICRC3Value::from(decode_icrc1_block(id, bytes)),
+        }));
+    }
+    // The archive does not know the length of the ledger log, so it reports
+    // the index following the last block it stores.
+    let log_length =
+        with_archive_opts(|opts| opts.block_index_offset) + with_blocks(|blocks| blocks.len());
+    GetBlocksResult {
+        log_length: Nat::from(log_length),
+        blocks,
+        archived_blocks: vec![],
+    }
+}
+
 #[query(hidden = true)]
 fn __get_candid_interface_tmp_hack() -> &'static str {
     include_str!(env!("ARCHIVE_DID_PATH"))
@@ -66,6 +66,70 @@ pub struct GetAccountTransactionsError {
 pub type GetAccountTransactionsResult =
     Result<GetAccountTransactionsResponse, GetAccountTransactionsError>;
 
+/// The maximum number of subaccounts in a single [SearchAccountTransactionsArgs].
+pub const MAX_SUBACCOUNTS_PER_SEARCH: usize = 100;
+
+#[derive(CandidType, Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum TransactionKind {
+    Mint,
+    Burn,
+    Transfer,
+    Approve,
+}
+
+#[derive(CandidType, Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+pub enum Direction {
+    // From the most recent transaction to the oldest one.
+    #[default]
+    Backward,
+    // From the oldest transaction to the most recent one.
+    Forward,
+}
+
+/// Conditions that a transaction has to satisfy to be returned by a search.
+/// Unset fields match every transaction and all bounds are inclusive.
+#[derive(CandidType, Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+pub struct TransactionFilter {
+    pub kinds: Option<Vec<TransactionKind>>,
+    // An account that has to take part in the transaction,
+    // e.g. the other side of a transfer or the spender of an approval.
+    pub counterparty: Option<Account>,
+    pub min_amount: Option<Nat>,
+    pub max_amount: Option<Nat>,
+    pub min_timestamp: Option<u64>,
+    pub max_timestamp: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct SearchAccountTransactionsArgs {
+    pub owner: Principal,
+    // The subaccounts of the owner to search. The transactions of all
+    // subaccounts are merged by txid and returned only once.
+    // If empty then the default subaccount is searched.
+    pub subaccounts: Vec<Subaccount>,
+    // The txid of the last transaction seen by the client.
+    // If None then the results will start from the most recent
+    // txid (or the oldest if the direction is Forward). If set then
+    // start won't be included.
+    pub start: Option<BlockIndex>,
+    // Maximum number of transactions to fetch.
+    pub max_results: Nat,
+    // Defaults to Backward.
+    pub direction: Option<Direction>,
+    pub filter: Option<TransactionFilter>,
+}
+
+#[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
+pub struct SearchAccountTransactionsResponse {
+    pub transactions: Vec<TransactionWithId>,
+    // The txid to use as start of the next request if there may be more
+    // matching transactions. None if the search is complete.
+    pub next_start: Option<BlockIndex>,
+}
+
+pub type SearchAccountTransactionsResult =
+    Result<SearchAccountTransactionsResponse, GetAccountTransactionsError>;
+
 #[derive(CandidType, Debug, Deserialize, PartialEq, Eq)]
 pub struct ListSubaccountsArgs {
     pub owner: Principal,
@@ -9,9 +9,11 @@ use ic_crypto_sha2::Sha256;
 use ic_icrc1::blocks::{encoded_block_to_generic_block, generic_block_to_encoded_block};
 use ic_icrc1::{Block, Operation};
 use ic_icrc1_index_ng::{
-    FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsResponse,
-    GetAccountTransactionsResult, IndexArg, ListSubaccountsArgs, Log, LogEntry, Status,
-    TransactionWithId, DEFAULT_MAX_BLOCKS_PER_RESPONSE,
+    Direction, FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsError,
+    GetAccountTransactionsResponse, GetAccountTransactionsResult, IndexArg, ListSubaccountsArgs,
+    Log, LogEntry, SearchAccountTransactionsArgs, SearchAccountTransactionsResponse,
+    SearchAccountTransactionsResult, Status, TransactionFilter, TransactionKind, TransactionWithId,
+    DEFAULT_MAX_BLOCKS_PER_RESPONSE, MAX_SUBACCOUNTS_PER_SEARCH,
 };
 use ic_ledger_core::block::{BlockIndex as BlockIndex64, BlockType, EncodedBlock};
 use ic_ledger_core::tokens::{CheckedAdd, CheckedSub, Zero};
@@ -54,6 +56,10 @@ const ACCOUNT_DATA_MEMORY_ID: MemoryId = MemoryId::new(4);
 
 const DEFAULT_MAX_WAIT_TIME: Duration = Duration::from_secs(1);
 
+/// The maximum number of blocks inspected by a single [search_account_transactions]
+/// call, whether they match the filter or not.
+const MAX_BLOCKS_SCANNED_PER_SEARCH: usize = 10_000;
+
 #[cfg(not(feature = "u256-tokens"))]
 type Tokens = ic_icrc1_tokens_u64::U64;
 
@@ -786,6 +792,209 @@ fn get_oldest_tx_id(account: Account) -> Option<BlockIndex64> {
     })
 }
 
+#[query]
+#[candid_method(query)]
+fn search_account_transactions(
+    arg: SearchAccountTransactionsArgs,
+) -> SearchAccountTransactionsResult {
+    if arg.subaccounts.len() > MAX_SUBACCOUNTS_PER_SEARCH {
+        return Err(GetAccountTransactionsError {
+            message: format!(
+                "At most {} subaccounts can be searched at once but {} were given",
+                MAX_SUBACCOUNTS_PER_SEARCH,
+                arg.subaccounts.len()
+            ),
+        });
+    }
+    let length = arg
+        .max_results
+        .0
+        .to_u64()
+        .unwrap_or(u64::MAX)
+        .min(with_state(|opts| opts.max_blocks_per_response))
+        .min(usize::MAX as u64) as usize;
+    let start = match arg.start {
+        None => None,
+        Some(start) => Some(
+            start
+                .0
+                .to_u64()
+                .ok_or_else(|| GetAccountTransactionsError {
+                    message: "start must be a u64".to_string(),
+                })?,
+        ),
+    };
+    let direction = arg.direction.unwrap_or_default();
+    let filter = arg.filter.unwrap_or_default();
+
+    let mut account_hashes: Vec<[u8; Sha256::DIGEST_LEN]> = if arg.subaccounts.is_empty() {
+        vec![account_sha256(Account::from(arg.owner))]
+    } else {
+        arg.subaccounts
+            .iter()
+            .map(|subaccount| {
+                account_sha256(Account {
+                    owner: arg.owner,
+                    subaccount: Some(*subaccount),
+                })
+            })
+            .collect()
+    };
+    account_hashes.sort();
+    account_hashes.dedup();
+
+    // The next block id of every account in the requested direction.
+    // The blocks of all accounts are merged by id so that a transaction
+    // between two of the accounts is returned only once.
+    let mut heads: Vec<_> = account_hashes
+        .into_iter()
+        .map(|hash| (hash, next_account_block_id(hash, start, direction)))
+        .collect();
+    let mut transactions = vec![];
+    let mut last_scanned = start;
+    let mut scanned = 0;
+    while transactions.len() < length && scanned < MAX_BLOCKS_SCANNED_PER_SEARCH {
+        let ids = heads.iter().filter_map(|(_, id)| *id);
+        let next_id = match direction {
+            Direction::Backward => ids.max(),
+            Direction::Forward => ids.min(),
+        };
+        let Some(id) = next_id else {
+            break;
+        };
+        for (hash, head) in heads.iter_mut() {
+            if *head == Some(id) {
+                *head = next_account_block_id(*hash, Some(id), direction);
+            }
+        }
+        scanned += 1;
+        last_scanned = Some(id);
+
+        let block = get_decoded_block(id).unwrap_or_else(|| {
+            trap(&format!(
+                "Block {} not found in the block log, account blocks map is corrupted!",
+                id
+            ))
+        });
+        // Block timestamps are non-decreasing, all the remaining blocks
+        // are out of the requested time range.
+        let past_time_range = match direction {
+            Direction::Backward => filter
+                .min_timestamp
+                .is_some_and(|min| block.timestamp < min),
+            Direction::Forward => filter
+                .max_timestamp
+                .is_some_and(|max| block.timestamp > max),
+        };
+        if past_time_range {
+            heads.clear();
+            break;
+        }
+        if matches_filter(&filter, &block) {
+            transactions.push(TransactionWithId {
+                id: id.into(),
+                transaction: block.into(),
+            });
+        }
+    }
+    let next_start = if heads.iter().any(|(_, id)| id.is_some()) {
+        last_scanned.map(Nat::from)
+    } else {
+        None
+    };
+    Ok(SearchAccountTransactionsResponse {
+        transactions,
+        next_start,
+    })
+}
+
+/// Returns the id of the block of the account that comes after `after`
+/// in the given direction or the first one if `after` is None.
+fn next_account_block_id(
+    account_hash: [u8; Sha256::DIGEST_LEN],
+    after: Option<BlockIndex64>,
+    direction: Direction,
+) -> Option<BlockIndex64> {
+    with_account_block_ids(|account_block_ids| match direction {
+        Direction::Backward => {
+            let from = match after {
+                None => u64::MAX,
+                Some(0) => return None,
+                Some(id) => id - 1,
+            };
+            account_block_ids
+                .range((account_hash, Reverse(from))..)
+                .next()
+                .filter(|(k, _)| k.0 == account_hash)
+                .map(|(k, _)| k.1 .0)
+        }
+        Direction::Forward => {
+            // Keys are sorted by decreasing block index, so the next
+            // block is the one just before the key of `after`. Block 0
+            // has no key after it and has to be looked up directly.
+            let key = match after {
+                None => {
+                    let first_key = (account_hash, Reverse(0));
+                    if account_block_ids.get(&first_key).is_some() {
+                        return Some(0);
+                    }
+                    first_key
+                }
+                Some(id) => (account_hash, Reverse(id)),
+            };
+            account_block_ids
+                .iter_upper_bound(&key)
+                .next()
+                .filter(|(k, _)| k.0 == account_hash)
+                .map(|(k, _)| k.1 .0)
+        }
+    })
+}
+
+fn matches_filter(filter: &TransactionFilter, block: &Block<Tokens>) -> bool {
+    let (kind, amount) = match &block.transaction.operation {
+        Operation::Mint { amount, .. } => (TransactionKind::Mint, amount),
+        Operation::Burn { amount, .. } => (TransactionKind::Burn, amount),
+        Operation::Transfer { amount, .. } => (TransactionKind::Transfer, amount),
+        Operation::Approve { amount, .. } => (TransactionKind::Approve, amount),
+    };
+    let amount: Nat = amount.clone().into();
+    filter
+        .kinds
+        .as_ref()
+        .map_or(true, |kinds| kinds.contains(&kind))
+        && filter.counterparty.map_or(true, |counterparty| {
+            get_involved_accounts(block).contains(&counterparty)
+        })
+        && filter
+            .min_amount
+            .as_ref()
+            .map_or(true, |min| &amount >= min)
+        && filter
+            .max_amount
+            .as_ref()
+            .map_or(true, |max| &amount <= max)
+        && filter
+            .min_timestamp
+            .map_or(true, |min| block.timestamp >= min)
+        && filter
+            .max_timestamp
+            .map_or(true, |max| block.timestamp <= max)
+}
+
+// Unlike [get_accounts] this includes the spender of the transaction.
+fn get_involved_accounts(block: &Block<Tokens>) -> Vec<Account> {
+    let mut accounts = get_accounts(block);
+    match block.transaction.operation {
+        Operation::Transfer { spender, .. } | Operation::Burn { spender, .. } => {
+            accounts.extend(spender)
+        }
+        Operation::Approve { spender, .. } => accounts.push(spender),
+        Operation::Mint { .. } => {}
+    }
+    accounts
+}
+
 #[query]
 #[candid_method(query)]
 fn icrc1_balance_of(account: Account) -> Nat {
@@ -5,9 +5,11 @@ use ic_canisters_http_types::{HttpRequest, HttpResponse};
 use ic_icrc1::blocks::generic_block_to_encoded_block;
 use ic_icrc1::Block;
 use ic_icrc1_index_ng::{
-    FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsResponse,
+    Direction, FeeCollectorRanges, GetAccountTransactionsArgs, GetAccountTransactionsResponse,
     GetAccountTransactionsResult, GetBlocksResponse, IndexArg, InitArg as IndexInitArg,
-    ListSubaccountsArgs, Log, Status, TransactionWithId, DEFAULT_MAX_BLOCKS_PER_RESPONSE,
+    ListSubaccountsArgs, Log, SearchAccountTransactionsArgs, SearchAccountTransactionsResult,
+    Status, TransactionFilter, TransactionKind, TransactionWithId, DEFAULT_MAX_BLOCKS_PER_RESPONSE,
+    MAX_SUBACCOUNTS_PER_SEARCH,
 };
 use ic_icrc1_ledger::{
     ChangeFeeCollector, FeatureFlags, InitArgsBuilder as LedgerInitArgsBuilder, LedgerArgument,
@@ -485,6 +487,20 @@ fn get_account_transactions(
         .expect("Failed to perform GetAccountTransactionsArgs")
 }
 
+fn search_account_transactions(
+    env: &StateMachine,
+    index_id: CanisterId,
+    args: &SearchAccountTransactionsArgs,
+) -> SearchAccountTransactionsResult {
+    let req = Encode!(args).expect("Failed to encode SearchAccountTransactionsArgs");
+    let res = env
+        .execute_ingress(index_id, "search_account_transactions", req)
+        .expect("Failed to search_account_transactions")
+        .bytes();
+    Decode!(&res, SearchAccountTransactionsResult)
+        .expect("Failed to decode SearchAccountTransactionsResult")
+}
+
 fn list_subaccounts(
     env: &StateMachine,
     index: CanisterId,
@@ -928,6 +944,147 @@ fn test_get_account_transactions_pagination() {
     }
 }
 
+#[test]
+fn test_search_account_transactions() {
+    let env = &StateMachine::new();
+    let minter = minter_identity().sender().unwrap();
+    let ledger_id = install_ledger(
+        env,
+        vec![
+            (account(1, 0), 1_000_000),
+            (account(1, 1), 1_000_000),
+            (account(2, 0), 1_000_000),
+        ],
+        default_archive_options(),
+        None,
+        minter,
+    );
+    let index_id = install_index_ng(env, ledger_id);
+
+    // Blocks 0, 1 and 2 are the mints at ledger init.
+    transfer(env, ledger_id, account(1, 0), account(2, 0), 100); // 3
+    env.advance_time(Duration::from_secs(60));
+    transfer(env, ledger_id, account(1, 0), account(1, 1), 200_000); // 4
+    approve(env, ledger_id, account(1, 1), account(2, 0), 300); // 5
+    transfer(env, ledger_id, account(2, 0), account(1, 1), 50); // 6
+    wait_until_sync_is_completed(env, index_id, ledger_id);
+
+    let search_args = SearchAccountTransactionsArgs {
+        owner: account(1, 0).owner,
+        subaccounts: vec![
+            account(1, 0).subaccount.unwrap(),
+            account(1, 1).subaccount.unwrap(),
+        ],
+        start: None,
+        max_results: u64::MAX.into(),
+        direction: None,
+        filter: None,
+    };
+    let search = |args: &SearchAccountTransactionsArgs| -> (Vec<u64>, Option<u64>) {
+        let res = search_account_transactions(env, index_id, args).unwrap();
+        (
+            res.transactions
+                .iter()
+                .map(|tx| tx.id.0.to_u64().unwrap())
+                .collect(),
+            res.next_start.map(|n| n.0.to_u64().unwrap()),
+        )
+    };
+    let with_filter = |filter: TransactionFilter| SearchAccountTransactionsArgs {
+        filter: Some(filter),
+        ..search_args.clone()
+    };
+
+    // The transfer between the two subaccounts is returned only once.
+    assert_eq!(search(&search_args), (vec![6, 5, 4, 3, 1, 0], None));
+    let forward_args = SearchAccountTransactionsArgs {
+        direction: Some(Direction::Forward),
+        ..search_args.clone()
+    };
+    assert_eq!(search(&forward_args), (vec![0, 1, 3, 4, 5, 6], None));
+
+    // Pagination in both directions.
+    let mut start = None;
+    let mut pages = vec![];
+    loop {
+        let (ids, next_start) = search(&SearchAccountTransactionsArgs {
+            start: start.map(Nat::from),
+            max_results: 4u64.into(),
+            ..forward_args.clone()
+        });
+        pages.push(ids);
+        start = next_start;
+        if start.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, vec![vec![0, 1, 3, 4], vec![5, 6]]);
+    assert_eq!(
+        search(&SearchAccountTransactionsArgs {
+            start: Some(4u64.into()),
+            max_results: 2u64.into(),
+            ..search_args.clone()
+        }),
+        (vec![3, 1], Some(1))
+    );
+
+    // Filters.
+    assert_eq!(
+        search(&with_filter(TransactionFilter {
+            kinds: Some(vec![TransactionKind::Transfer]),
+            ..Default::default()
+        })),
+        (vec![6, 4, 3], None)
+    );
+    assert_eq!(
+        search(&with_filter(TransactionFilter {
+            counterparty: Some(account(2, 0)),
+            ..Default::default()
+        })),
+        (vec![6, 5, 3], None)
+    );
+    assert_eq!(
+        search(&with_filter(TransactionFilter {
+            min_amount: Some(100u64.into()),
+            max_amount: Some(200_000u64.into()),
+            ..Default::default()
+        })),
+        (vec![5, 4, 3], None)
+    );
+    let block_4_timestamp = get_account_transactions(env, index_id, account(1, 0), None, 1)
+        .transactions[0]
+        .transaction
+        .timestamp;
+    assert_eq!(
+        search(&with_filter(TransactionFilter {
+            min_timestamp: Some(block_4_timestamp),
+            ..Default::default()
+        })),
+        (vec![6, 5, 4], None)
+    );
+    assert_eq!(
+        search(&SearchAccountTransactionsArgs {
+            direction: Some(Direction::Forward),
+            ..with_filter(TransactionFilter {
+                max_timestamp: Some(block_4_timestamp - 1),
+                ..Default::default()
+            })
+        }),
+        (vec![0, 1, 3], None)
+    );
+
+    // Too many subaccounts.
+    assert!(search_account_transactions(
+        env,
+        index_id,
+        &SearchAccountTransactionsArgs {
+            subaccounts: vec![[0; 32]; MAX_SUBACCOUNTS_PER_SEARCH + 1],
+            ..search_args.clone()
+        }
+    )
+    .is_err());
+}
+
 #[test]
 fn test_icrc1_balance_of() {
     // 1 case only because the test is expensive to run.
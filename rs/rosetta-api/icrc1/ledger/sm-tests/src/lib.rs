@@ -18,16 +18,26 @@ use icrc_ledger_types::icrc1::transfer::{Memo, TransferArg, TransferError};
 use icrc_ledger_types::icrc2::allowance::{Allowance, AllowanceArgs};
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
 use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+use icrc_ledger_types::icrc21::errors::Icrc21Error;
+use icrc_ledger_types::icrc21::requests::{
+    ConsentMessageMetadata, ConsentMessageRequest, ConsentMessageSpec, DisplayMessageType,
+};
+use icrc_ledger_types::icrc21::responses::{ConsentInfo, ConsentMessage};
 use icrc_ledger_types::icrc3;
-use icrc_ledger_types::icrc3::archive::ArchiveInfo;
+use icrc_ledger_types::icrc3::archive::{ArchiveInfo, GetArchivesArgs, GetArchivesResult};
 use icrc_ledger_types::icrc3::blocks::BlockRange;
 use icrc_ledger_types::icrc3::blocks::GenericBlock as IcrcBlock;
 use icrc_ledger_types::icrc3::blocks::GetBlocksResponse;
+use icrc_ledger_types::icrc3::blocks::{
+    verify_hash_chain, GetBlocksRequest, GetBlocksResult, ICRC3DataCertificate,
+};
 use icrc_ledger_types::icrc3::transactions::GetTransactionsRequest;
 use icrc_ledger_types::icrc3::transactions::GetTransactionsResponse;
 use icrc_ledger_types::icrc3::transactions::Transaction as Tx;
 use icrc_ledger_types::icrc3::transactions::TransactionRange;
 use icrc_ledger_types::icrc3::transactions::Transfer;
+use icrc_ledger_types::icrc4::balance_of_batch::{BalanceOfBatchError, BalanceOfBatchResult};
+use icrc_ledger_types::icrc4::transfer_batch::{TransferBatchError, TransferBatchResponse};
 use num_traits::ToPrimitive;
 use proptest::prelude::*;
 use proptest::test_runner::{Config as TestRunnerConfig, TestCaseResult, TestRunner};
@@ -228,6 +238,61 @@ pub fn transfer(
     )
 }
 
+pub fn transfer_batch(
+    env: &StateMachine,
+    ledger: CanisterId,
+    from: Principal,
+    args: &[TransferArg],
+) -> TransferBatchResponse {
+    Decode!(
+        &env.execute_ingress_as(
+            PrincipalId(from),
+            ledger,
+            "icrc4_transfer_batch",
+            Encode!(&args).unwrap()
+        )
+        .expect("failed to transfer a batch")
+        .bytes(),
+        TransferBatchResponse
+    )
+    .expect("failed to decode icrc4_transfer_batch response")
+}
+
+pub fn balance_of_batch(
+    env: &StateMachine,
+    ledger: CanisterId,
+    accounts: &[Account],
+) -> Result<Vec<u64>, BalanceOfBatchError> {
+    Ok(Decode!(
+        &env.query(
+            ledger,
+            "icrc4_balance_of_batch",
+            Encode!(&accounts).unwrap()
+        )
+        .expect("failed to query balances")
+        .bytes(),
+        BalanceOfBatchResult
+    )
+    .expect("failed to decode icrc4_balance_of_batch response")?
+    .into_iter()
+    .map(|balance| balance.0.to_u64().unwrap())
+    .collect())
+}
+
+fn maximum_batch_size(env: &StateMachine, ledger: CanisterId, method: &str) -> usize {
+    Decode!(
+        &env.query(ledger, method, Encode!().unwrap())
+            .expect("failed to query the maximum batch size")
+            .bytes(),
+        Option<Nat>
+    )
+    .expect("failed to decode the maximum batch size")
+    .expect("the ledger should have a maximum batch size")
+    .0
+    .to_usize()
+    .unwrap()
+}
+
 fn list_archives(env: &StateMachine, ledger: CanisterId) -> Vec<ArchiveInfo> {
     Decode!(
         &env.query(ledger, "archives", Encode!().unwrap())
@@ -353,6 +418,48 @@ fn get_archive_blocks(
     get_transactions_as(env, archive, start, length, "get_blocks".to_string())
 }
 
+fn icrc3_get_blocks(
+    env: &StateMachine,
+    canister: Principal,
+    args: &[GetBlocksRequest],
+) -> GetBlocksResult {
+    let canister_id = CanisterId::unchecked_from_principal(canister.into());
+    Decode!(
+        &env.query(canister_id, "icrc3_get_blocks", Encode!(args).unwrap())
+            .expect("failed to query icrc3_get_blocks")
+            .bytes(),
+        GetBlocksResult
+    )
+    .expect("failed to decode icrc3_get_blocks response")
+}
+
+fn icrc3_get_archives(
+    env: &StateMachine,
+    ledger: CanisterId,
+    args: GetArchivesArgs,
+) -> GetArchivesResult {
+    Decode!(
+        &env.query(ledger, "icrc3_get_archives", Encode!(&args).unwrap())
+            .expect("failed to query icrc3_get_archives")
+            .bytes(),
+        GetArchivesResult
+    )
+    .expect("failed to decode icrc3_get_archives response")
+}
+
+fn icrc3_get_tip_certificate(
+    env: &StateMachine,
+    ledger: CanisterId,
+) -> Option<ICRC3DataCertificate> {
+    Decode!(
+        &env.query(ledger, "icrc3_get_tip_certificate", Encode!().unwrap())
+            .expect("failed to query icrc3_get_tip_certificate")
+            .bytes(),
+        Option<ICRC3DataCertificate>
+    )
+    .expect("failed to decode icrc3_get_tip_certificate response")
+}
+
 fn get_phash(block: &IcrcBlock) -> Result<Option<Hash>, String> {
     match block {
         IcrcBlock::Map(map) => {
@@ -432,6 +539,26 @@ pub fn metadata(env: &StateMachine, ledger: CanisterId) -> BTreeMap<String, Valu
     .collect()
 }
 
+pub fn icrc21_consent_message(
+    env: &StateMachine,
+    ledger: CanisterId,
+    caller: Principal,
+    request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    Decode!(
+        &env.execute_ingress_as(
+            PrincipalId(caller),
+            ledger,
+            "icrc21_canister_call_consent_message",
+            Encode!(&request).unwrap()
+        )
+        .expect("failed to get the consent message")
+        .bytes(),
+        Result<ConsentInfo, Icrc21Error>
+    )
+    .expect("failed to decode icrc21_canister_call_consent_message response")
+}
+
 pub fn send_approval(
     env: &StateMachine,
     ledger: CanisterId,
@@ -751,7 +878,7 @@ where
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(standards, vec!["ICRC-1", "ICRC-2", "ICRC-21"]);
 }
 pub fn test_metadata<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
 where
@@ -819,7 +946,10 @@ where
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(
+        standards,
+        vec!["ICRC-1", "ICRC-2", "ICRC-21", "ICRC-3", "ICRC-4"]
+    );
 }
 
 pub fn test_total_supply<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
@@ -1565,6 +1695,287 @@ where
     assert_eq!(0, missing_blocks_reply.archived_blocks.len());
 }
 
+pub fn test_icrc3_get_blocks<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1.0), 10_000_000)],
+    );
+
+    for i in 0..ARCHIVE_TRIGGER_THRESHOLD {
+        transfer(&env, canister_id, p1.0, p2.0, 10_000 + i * 10_000).expect("transfer failed");
+    }
+
+    env.run_until_completion(/*max_ticks=*/ 10);
+
+    let archives = icrc3_get_archives(&env, canister_id, GetArchivesArgs { from: None });
+    assert_eq!(archives.len(), 1);
+    assert_eq!(archives[0].start, 0u8);
+    assert_eq!(archives[0].end, NUM_BLOCKS_TO_ARCHIVE - 1);
+    assert_eq!(
+        icrc3_get_archives(
+            &env,
+            canister_id,
+            GetArchivesArgs {
+                from: Some(archives[0].canister_id)
+            }
+        ),
+        vec![]
+    );
+
+    let args = vec![GetBlocksRequest {
+        start: Nat::from(0_u8),
+        length: Nat::from(1_000_000_u64),
+    }];
+    let resp = icrc3_get_blocks(&env, canister_id.get().0, &args);
+    let log_length = ARCHIVE_TRIGGER_THRESHOLD + 1;
+    assert_eq!(resp.log_length, Nat::from(log_length));
+    assert_eq!(
+        resp.blocks.len(),
+        (log_length - NUM_BLOCKS_TO_ARCHIVE) as usize
+    );
+    assert_eq!(resp.blocks[0].id, Nat::from(NUM_BLOCKS_TO_ARCHIVE));
+    assert_eq!(resp.archived_blocks.len(), 1);
+    assert_eq!(
+        resp.archived_blocks[0].args,
+        vec![GetBlocksRequest {
+            start: Nat::from(0_u8),
+            length: Nat::from(NUM_BLOCKS_TO_ARCHIVE),
+        }]
+    );
+
+    let callback = &resp.archived_blocks[0].callback;
+    assert_eq!(callback.canister_id, archives[0].canister_id);
+    let archived = icrc3_get_blocks(&env, callback.canister_id, &resp.archived_blocks[0].args);
+    assert_eq!(archived.blocks.len(), NUM_BLOCKS_TO_ARCHIVE as usize);
+    assert!(archived.archived_blocks.is_empty());
+
+    // The ICRC-3 blocks must have the same hashes as the generic blocks.
+    let legacy_blocks = get_blocks(&env, canister_id.get().0, 0, 1_000_000);
+    for (block, legacy_block) in resp.blocks.iter().zip(legacy_blocks.blocks.iter()) {
+        assert_eq!(block.block.hash(), legacy_block.hash());
+    }
+
+    // Check that the hash chain is correct.
+    let blocks: Vec<_> = archived.blocks.iter().chain(resp.blocks.iter()).collect();
+    for (i, block) in blocks.iter().enumerate() {
+        assert_eq!(block.id, Nat::from(i));
+    }
+    verify_hash_chain(None, blocks.iter().map(|block| &block.block))
+        .expect("invalid hash chain")
+        .expect("the chain must not be empty");
+
+    assert!(icrc3_get_tip_certificate(&env, canister_id).is_some());
+
+    // Check that requesting non-existing blocks does not crash the ledger.
+    let missing_blocks_reply = icrc3_get_blocks(
+        &env,
+        canister_id.get().0,
+        &[GetBlocksRequest {
+            start: Nat::from(log_length + 100),
+            length: Nat::from(5_u8),
+        }],
+    );
+    assert_eq!(0, missing_blocks_reply.blocks.len());
+    assert_eq!(0, missing_blocks_reply.archived_blocks.len());
+}
+
+pub fn test_icrc21_standard<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let (env, canister_id) = setup(ledger_wasm, encode_init_args, vec![]);
+    let from = PrincipalId::new_user_test_id(1);
+    let to = Account::from(PrincipalId::new_user_test_id(2).0);
+    let symbol = match metadata(&env, canister_id).get("icrc1:symbol") {
+        Some(Value::Text(symbol)) => symbol.clone(),
+        symbol => panic!("unexpected icrc1:symbol {:?}", symbol),
+    };
+
+    let transfer_arg = TransferArg {
+        from_subaccount: None,
+        to,
+        fee: None,
+        created_at_time: None,
+        memo: Some(Memo::from(b"consent".to_vec())),
+        amount: Nat::from(1_000_000_u64),
+    };
+    let request = ConsentMessageRequest {
+        method: "icrc1_transfer".to_string(),
+        arg: serde_bytes::ByteBuf::from(Encode!(&transfer_arg).unwrap()),
+        user_preferences: ConsentMessageSpec {
+            metadata: ConsentMessageMetadata {
+                language: "en".to_string(),
+                utc_offset_minutes: None,
+            },
+            device_spec: Some(DisplayMessageType::GenericDisplay),
+        },
+    };
+
+    let consent_info = icrc21_consent_message(&env, canister_id, from.0, request.clone())
+        .expect("failed to build the consent message");
+    assert_eq!(consent_info.metadata.language, "en");
+    match consent_info.consent_message {
+        ConsentMessage::GenericDisplayMessage(message) => {
+            assert!(message.starts_with(&format!("# Send {}", symbol)));
+            assert!(message.contains(&format!("**From:**\n{}", Account::from(from.0))));
+            assert!(message.contains(&format!("**To:**\n{}", to)));
+            assert!(message.contains("**Memo:**\nconsent"));
+        }
+        message => panic!("expected a generic display message, got {:?}", message),
+    }
+
+    let consent_info = icrc21_consent_message(
+        &env,
+        canister_id,
+        from.0,
+        ConsentMessageRequest {
+            user_preferences: ConsentMessageSpec {
+                device_spec: Some(DisplayMessageType::LineDisplay {
+                    characters_per_line: 30,
+                    lines_per_page: 3,
+                }),
+                ..request.user_preferences.clone()
+            },
+            ..request.clone()
+        },
+    )
+    .expect("failed to build the consent message");
+    match consent_info.consent_message {
+        ConsentMessage::LineDisplayMessage { pages } => {
+            assert!(pages.iter().all(|page| page.lines.len() <= 3));
+            assert!(pages
+                .iter()
+                .flat_map(|page| page.lines.iter())
+                .all(|line| line.chars().count() <= 30));
+        }
+        message => panic!("expected a line display message, got {:?}", message),
+    }
+
+    assert!(matches!(
+        icrc21_consent_message(
+            &env,
+            canister_id,
+            from.0,
+            ConsentMessageRequest {
+                method: "icrc1_balance_of".to_string(),
+                ..request
+            },
+        ),
+        Err(Icrc21Error::UnsupportedCanisterCall(_))
+    ));
+}
+
+pub fn test_icrc4_transfer_batch<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
+where
+    T: CandidType,
+{
+    let p1 = PrincipalId::new_user_test_id(1);
+    let p2 = PrincipalId::new_user_test_id(2);
+    let p3 = PrincipalId::new_user_test_id(3);
+    let (env, canister_id) = setup(
+        ledger_wasm,
+        encode_init_args,
+        vec![(Account::from(p1.0), 10_000_000)],
+    );
+    let now = system_time_to_nanos(env.time());
+    let transfer_arg = |to: PrincipalId, amount: u64, memo: u64| TransferArg {
+        from_subaccount: None,
+        to: Account::from(to.0),
+        fee: None,
+        created_at_time: Some(now),
+        memo: Some(Memo::from(memo)),
+        amount: Nat::from(amount),
+    };
+
+    let max_batch_size = maximum_batch_size(&env, canister_id, "icrc4_maximum_update_batch_size");
+
+    // Every transfer of the batch gets its own result and block. The second
+    // transfer is a duplicate of the first one and the third one exceeds the
+    // remaining balance.
+    let results = transfer_batch(
+        &env,
+        canister_id,
+        p1.0,
+        &[
+            transfer_arg(p2, 1_000_000, 1),
+            transfer_arg(p2, 1_000_000, 1),
+            transfer_arg(p3, 100_000_000, 2),
+            transfer_arg(p3, 2_000_000, 3),
+        ],
+    )
+    .expect("the batch should not be rejected");
+    assert_eq!(
+        results,
+        vec![
+            Some(Ok(Nat::from(1_u64))),
+            Some(Err(TransferBatchError::Duplicate {
+                duplicate_of: Nat::from(1_u64)
+            })),
+            Some(Err(TransferBatchError::InsufficientFunds {
+                balance: Nat::from(9_000_000_u64 - FEE)
+            })),
+            Some(Ok(Nat::from(2_u64))),
+        ]
+    );
+    assert_eq!(
+        balance_of_batch(&env, canister_id, &[p1.0.into(), p2.0.into(), p3.0.into()]).unwrap(),
+        vec![10_000_000 - 3_000_000 - 2 * FEE, 1_000_000, 2_000_000]
+    );
+
+    // The legs are recorded as regular ICRC-1 transfers.
+    let blocks = get_blocks(&env, canister_id.get().0, 1, 2).blocks;
+    for (block, (to, amount)) in blocks
+        .into_iter()
+        .zip([(p2, 1_000_000_u64), (p3, 2_000_000_u64)])
+    {
+        let block = Block::<Tokens>::try_from(block).unwrap();
+        assert_eq!(
+            block.transaction.operation,
+            Operation::Transfer {
+                from: Account::from(p1.0),
+                to: Account::from(to.0),
+                spender: None,
+                amount: Tokens::try_from(Nat::from(amount)).unwrap(),
+                fee: None,
+            }
+        );
+    }
+
+    // A batch exceeding the maximum size is rejected as a whole and none of
+    // its transfers is processed.
+    let too_large = vec![transfer_arg(p2, 1, 4); max_batch_size + 1];
+    assert_eq!(
+        transfer_batch(&env, canister_id, p1.0, &too_large),
+        Err(TransferBatchError::TooManyRequests {
+            limit: Nat::from(max_batch_size)
+        })
+    );
+    assert_eq!(1_000_000, balance_of(&env, canister_id, p2.0));
+
+    // Balance queries are limited by the maximum query batch size.
+    let max_query_batch_size =
+        maximum_batch_size(&env, canister_id, "icrc4_maximum_query_batch_size");
+    let accounts = vec![Account::from(p2.0); max_query_batch_size];
+    assert_eq!(
+        balance_of_batch(&env, canister_id, &accounts),
+        Ok(vec![1_000_000; max_query_batch_size])
+    );
+    let accounts = vec![Account::from(p2.0); max_query_batch_size + 1];
+    assert_eq!(
+        balance_of_batch(&env, canister_id, &accounts),
+        Err(BalanceOfBatchError::TooManyRequests {
+            limit: Nat::from(max_query_batch_size)
+        })
+    );
+}
+
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
 pub fn block_encoding_agrees_with_the_schema() {
     use std::path::PathBuf;
@@ -2655,9 +3066,12 @@ pub fn expect_icrc2_disabled(
         "Expected ICRC-2 disabled error, got: {}",
         err.description()
     );
-    let standards = supported_standards(env, canister_id);
-    assert_eq!(standards.len(), 1);
-    assert_eq!(standards[0].name, "ICRC-1");
+    let mut standards: Vec<_> = supported_standards(env, canister_id)
+        .into_iter()
+        .map(|standard| standard.name)
+        .collect();
+    standards.sort();
+    assert_eq!(standards, vec!["ICRC-1", "ICRC-21"]);
 }
 
 pub fn test_feature_flags<T>(ledger_wasm: Vec<u8>, encode_init_args: fn(InitArgs) -> T)
@@ -2729,7 +3143,7 @@ where
         standards.push(standard.name);
     }
     standards.sort();
-    assert_eq!(standards, vec!["ICRC-1", "ICRC-2"]);
+    assert_eq!(standards, vec!["ICRC-1", "ICRC-2", "ICRC-21"]);
 
     let block_index =
         send_approval(&env, canister_id, from.0, &approve_args).expect("approval failed");
@@ -12,7 +12,9 @@ use ic_canister_log::{log, Sink};
 use ic_crypto_tree_hash::{Label, MixedHashTree};
 use ic_icrc1::blocks::encoded_block_to_generic_block;
 use ic_icrc1::{Block, LedgerBalances, Transaction};
-pub use ic_ledger_canister_core::archive::ArchiveOptions;
+pub use ic_ledger_canister_core::archive::{
+    ArchiveOptions, ConsolidateArchivesArgs, ConsolidatedArchives,
+};
 use ic_ledger_canister_core::{
     archive::ArchiveCanisterWasm,
     blockchain::Blockchain,
@@ -20,24 +22,33 @@ use ic_ledger_canister_core::{
     range_utils,
 };
 use ic_ledger_core::{
-    approvals::AllowanceTable,
-    balances::Balances,
+    approvals::{Allowance, AllowanceTable, AllowancesData, HeapAllowancesData},
+    balances::{Balances, BalancesStore, InspectableBalancesStore},
     block::{BlockIndex, BlockType, EncodedBlock, FeeCollector},
     timestamp::TimeStamp,
     tokens::TokensType,
 };
 use ic_ledger_hash_of::HashOf;
+use ic_stable_structures::memory_manager::{MemoryId, MemoryManager, VirtualMemory};
+use ic_stable_structures::{storable::Bound, DefaultMemoryImpl, StableBTreeMap, Storable};
 use icrc_ledger_types::icrc1::account::Account;
+use icrc_ledger_types::icrc1::account::DEFAULT_SUBACCOUNT;
 use icrc_ledger_types::icrc3::transactions::Transaction as Tx;
-use icrc_ledger_types::icrc3::{blocks::GetBlocksResponse, transactions::GetTransactionsResponse};
+use icrc_ledger_types::icrc3::{
+    blocks::{ArchivedBlocks, BlockWithId, GetBlocksRequest, GetBlocksResponse, GetBlocksResult},
+    transactions::GetTransactionsResponse,
+};
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value,
-    icrc3::archive::{ArchivedRange, QueryBlockArchiveFn, QueryTxArchiveFn},
+    icrc3::archive::{ArchivedRange, ICRC3ArchiveFn, QueryBlockArchiveFn, QueryTxArchiveFn},
 };
 use serde::{Deserialize, Serialize};
 use serde_bytes::ByteBuf;
 use std::borrow::Cow;
+use std::cell::RefCell;
 use std::collections::{BTreeMap, VecDeque};
+use std::marker::PhantomData;
+use std::ops::Bound::{Excluded, Unbounded};
 use std::time::Duration;
 
 const TRANSACTION_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
@@ -277,9 +288,20 @@ pub enum LedgerArgument {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(bound = "")]
 pub struct Ledger<Tokens: TokensType> {
+    /// Balances kept in heap memory by ledger versions prior to the stable
+    /// structures migration. Empty once the migration is complete.
+    #[serde(default)]
     balances: LedgerBalances<Tokens>,
+    /// Allowances kept in heap memory by ledger versions prior to the stable
+    /// structures migration. Empty once the migration is complete.
     #[serde(default)]
-    approvals: AllowanceTable<ApprovalKey, Account, Tokens>,
+    approvals: AllowanceTable<HeapAllowancesData<ApprovalKey, Account, Tokens>>,
+    #[serde(default)]
+    stable_balances: Balances<StableBalances<Tokens>>,
+    #[serde(skip)]
+    stable_approvals: AllowanceTable<StableAllowancesData<Tokens>>,
+    #[serde(default = "default_ledger_state")]
+    state: LedgerState,
     blockchain: Blockchain<CdkRuntime, Icrc1ArchiveWasm>,
 
     minting_account: Account,
@@ -307,6 +329,30 @@ pub struct Ledger<Tokens: TokensType> {
     accounts_overflow_trim_quantity: usize,
 }
 
+/// The ledger state with respect to the migration of balances and allowances
+/// from heap memory to stable structures.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedgerState {
+    /// The field is being migrated; the fields before it are done.
+    Migrating(LedgerField),
+    Ready,
+}
+
+/// The ledger fields migrated to stable structures, in migration order.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LedgerField {
+    Allowances,
+    AllowancesExpirations,
+    AllowancesArrivals,
+    Balances,
+}
+
+/// The state of a ledger serialized before the stable structures migration
+/// existed: all its data still lives in heap memory.
+fn default_ledger_state() -> LedgerState {
+    LedgerState::Migrating(LedgerField::Allowances)
+}
+
 fn default_maximum_number_of_accounts() -> usize {
     MAX_ACCOUNTS
 }
@@ -369,6 +415,9 @@ impl<Tokens: TokensType> Ledger<Tokens> {
         let mut ledger = Self {
             balances: LedgerBalances::default(),
             approvals: Default::default(),
+            stable_balances: Balances::default(),
+            stable_approvals: Default::default(),
+            state: LedgerState::Ready,
             blockchain: Blockchain::new_with_archive(archive_options),
             transactions_by_hash: BTreeMap::new(),
             transactions_by_height: VecDeque::new(),
@@ -436,24 +485,28 @@ impl From<ApprovalKey> for (Account, Account) {
 
 impl<Tokens: TokensType> LedgerContext for Ledger<Tokens> {
     type AccountId = Account;
-    type Approvals = AllowanceTable<ApprovalKey, Account, Tokens>;
-    type BalancesStore = BTreeMap<Self::AccountId, Tokens>;
+    type Approvals = AllowanceTable<StableAllowancesData<Tokens>>;
+    type BalancesStore = StableBalances<Tokens>;
     type Tokens = Tokens;
 
     fn balances(&self) -> &Balances<Self::BalancesStore> {
-        &self.balances
+        self.panic_if_not_ready();
+        &self.stable_balances
     }
 
     fn balances_mut(&mut self) -> &mut Balances<Self::BalancesStore> {
-        &mut self.balances
+        self.panic_if_not_ready();
+        &mut self.stable_balances
     }
 
     fn approvals(&self) -> &Self::Approvals {
-        &self.approvals
+        self.panic_if_not_ready();
+        &self.stable_approvals
     }
 
     fn approvals_mut(&mut self) -> &mut Self::Approvals {
-        &mut self.approvals
+        self.panic_if_not_ready();
+        &mut self.stable_approvals
     }
 
     fn fee_collector(&self) -> Option<&FeeCollector<Self::AccountId>> {
@@ -565,6 +618,73 @@ impl<Tokens: TokensType> Ledger<Tokens> {
         &self.feature_flags
     }
 
+    pub fn state(&self) -> LedgerState {
+        self.state
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.state == LedgerState::Ready
+    }
+
+    pub fn panic_if_not_ready(&self) {
+        if !self.is_ready() {
+            panic!("The Ledger is not ready: {:?}", self.state);
+        }
+    }
+
+    /// Moves balances and allowances from heap memory to stable structures
+    /// until either the migration is complete or `should_continue` returns
+    /// false. Returns true if the migration is complete.
+    ///
+    /// The migration is resumable: the data not moved yet stays in the heap
+    /// fields, which are serialized on upgrade together with the [LedgerState].
+    pub fn migrate_to_stable_structures(&mut self, should_continue: impl Fn() -> bool) -> bool {
+        while let LedgerState::Migrating(field) = self.state {
+            if !should_continue() {
+                return false;
+            }
+            let data = self.approvals.allowances_data_mut();
+            let migrated = match field {
+                LedgerField::Allowances => data.pop_first_allowance().map(|(key, allowance)| {
+                    self.stable_approvals
+                        .allowances_data_mut()
+                        .set_allowance(key, allowance)
+                }),
+                LedgerField::AllowancesExpirations => data.pop_first_expiry().map(|(ts, key)| {
+                    self.stable_approvals
+                        .allowances_data_mut()
+                        .insert_expiry(ts, key)
+                }),
+                LedgerField::AllowancesArrivals => data.pop_first_arrival().map(|(ts, key)| {
+                    self.stable_approvals
+                        .allowances_data_mut()
+                        .insert_arrival(ts, key)
+                }),
+                LedgerField::Balances => {
+                    self.stable_balances.token_pool = self.balances.token_pool.clone();
+                    self.balances.store.pop_first().map(|(account, balance)| {
+                        self.stable_balances.store.insert(account, balance)
+                    })
+                }
+            };
+            if migrated.is_none() {
+                self.state = match field {
+                    LedgerField::Allowances => {
+                        LedgerState::Migrating(LedgerField::AllowancesExpirations)
+                    }
+                    LedgerField::AllowancesExpirations => {
+                        LedgerState::Migrating(LedgerField::AllowancesArrivals)
+                    }
+                    LedgerField::AllowancesArrivals => {
+                        LedgerState::Migrating(LedgerField::Balances)
+                    }
+                    LedgerField::Balances => LedgerState::Ready,
+                };
+            }
+        }
+        true
+    }
+
     pub fn upgrade(&mut self, sink: impl Sink + Clone, args: UpgradeArgs) {
         if let Some(upgrade_metadata_args) = args.metadata {
             self.metadata = upgrade_metadata_args
@@ -626,18 +746,25 @@ impl<Tokens: TokensType> Ledger<Tokens> {
         self.construct_hash_tree().digest().0
     }
 
+    /// Constructs the certified tree of the ledger tip as specified by ICRC-3:
+    /// the `last_block_index` label points to the LEB128 encoding of the index
+    /// of the last block and the `last_block_hash` label to its hash.
     pub fn construct_hash_tree(&self) -> MixedHashTree {
         match self.blockchain().last_hash {
             Some(hash) => {
                 let last_block_index = self.blockchain().chain_length().checked_sub(1).unwrap();
+                let mut last_block_index_buf = vec![];
+                Nat::from(last_block_index)
+                    .encode(&mut last_block_index_buf)
+                    .expect("bug: cannot encode a Nat");
                 MixedHashTree::Fork(Box::new((
                     MixedHashTree::Labeled(
-                        Label::from("last_block_index"),
-                        Box::new(MixedHashTree::Leaf(last_block_index.to_be_bytes().to_vec())),
+                        Label::from("last_block_hash"),
+                        Box::new(MixedHashTree::Leaf(hash.as_slice().to_vec())),
                     ),
                     MixedHashTree::Labeled(
-                        Label::from("tip_hash"),
-                        Box::new(MixedHashTree::Leaf(hash.as_slice().to_vec())),
+                        Label::from("last_block_index"),
+                        Box::new(MixedHashTree::Leaf(last_block_index_buf)),
                     ),
                 )))
             }
@@ -715,4 +842,369 @@ impl<Tokens: TokensType> Ledger<Tokens> {
             archived_blocks,
         }
     }
+
+    /// Returns blocks in the specified ranges in the ICRC-3 format.
+    ///
+    /// At most [MAX_TRANSACTIONS_PER_REQUEST] local blocks are returned across
+    /// all ranges. Archived blocks are grouped by the archive storing them.
+    pub fn icrc3_get_blocks(&self, ranges: Vec<(BlockIndex, usize)>) -> GetBlocksResult {
+        let mut blocks = vec![];
+        let mut archived_ranges: BTreeMap<Principal, Vec<GetBlocksRequest>> = BTreeMap::new();
+
+        for (start, length) in ranges {
+            let locations = block_locations(self, start, length);
+
+            let local_blocks_range = range_utils::take(
+                &locations.local_blocks,
+                MAX_TRANSACTIONS_PER_REQUEST.saturating_sub(blocks.len()),
+            );
+            let local_blocks = self.blockchain.block_slice(local_blocks_range.clone());
+            blocks.extend(
+                local_blocks_range
+                    .zip(local_blocks.iter())
+                    .map(|(id, block)| BlockWithId {
+                        id: Nat::from(id),
+                        block: encoded_block_to_generic_block(block).into(),
+                    }),
+            );
+
+            for (canister_id, slice) in locations.archived_blocks {
+                archived_ranges
+                    .entry(canister_id.get().0)
+                    .or_default()
+                    .push(GetBlocksRequest {
+                        start: Nat::from(slice.start),
+                        length: Nat::from(range_utils::range_len(&slice)),
+                    });
+            }
+        }
+
+        GetBlocksResult {
+            log_length: Nat::from(self.blockchain.chain_length()),
+            blocks,
+            archived_blocks: archived_ranges
+                .into_iter()
+                .map(|(canister_id, args)| ArchivedBlocks {
+                    args,
+                    callback: ICRC3ArchiveFn::new(canister_id, "icrc3_get_blocks"),
+                })
+                .collect(),
+        }
+    }
+}
+
+const UPGRADES_MEMORY_ID: MemoryId = MemoryId::new(0);
+const ALLOWANCES_MEMORY_ID: MemoryId = MemoryId::new(1);
+const ALLOWANCES_EXPIRATIONS_MEMORY_ID: MemoryId = MemoryId::new(2);
+const ALLOWANCES_ARRIVALS_MEMORY_ID: MemoryId = MemoryId::new(3);
+const BALANCES_MEMORY_ID: MemoryId = MemoryId::new(4);
+
+type VM = VirtualMemory<DefaultMemoryImpl>;
+type AllowancesMap = StableBTreeMap<ApprovalKey, Vec<u8>, VM>;
+type AllowancesQueue = StableBTreeMap<(u64, ApprovalKey), (), VM>;
+type BalancesMap = StableBTreeMap<AccountKey, Vec<u8>, VM>;
+
+thread_local! {
+    /// Static memory manager to manage the memory available for stable structures.
+    static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> = RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+
+    /// Memory holding the CBOR-encoded ledger state across upgrades.
+    pub static UPGRADES_MEMORY: RefCell<VM> = with_memory_manager(|memory_manager| {
+        RefCell::new(memory_manager.get(UPGRADES_MEMORY_ID))
+    });
+
+    /// (account, spender) -> CBOR-encoded allowance.
+    static ALLOWANCES: RefCell<AllowancesMap> = with_memory_manager(|memory_manager| {
+        RefCell::new(AllowancesMap::init(memory_manager.get(ALLOWANCES_MEMORY_ID)))
+    });
+
+    /// (expiration timestamp, (account, spender)).
+    static ALLOWANCES_EXPIRATIONS: RefCell<AllowancesQueue> = with_memory_manager(|memory_manager| {
+        RefCell::new(AllowancesQueue::init(memory_manager.get(ALLOWANCES_EXPIRATIONS_MEMORY_ID)))
+    });
+
+    /// (arrival timestamp, (account, spender)).
+    static ALLOWANCES_ARRIVALS: RefCell<AllowancesQueue> = with_memory_manager(|memory_manager| {
+        RefCell::new(AllowancesQueue::init(memory_manager.get(ALLOWANCES_ARRIVALS_MEMORY_ID)))
+    });
+
+    /// account -> LEB128-encoded balance.
+    static BALANCES: RefCell<BalancesMap> = with_memory_manager(|memory_manager| {
+        RefCell::new(BalancesMap::init(memory_manager.get(BALANCES_MEMORY_ID)))
+    });
+}
+
+/// A helper function to access the memory manager.
+fn with_memory_manager<R>(f: impl FnOnce(&MemoryManager<DefaultMemoryImpl>) -> R) -> R {
+    MEMORY_MANAGER.with(|cell| f(&cell.borrow()))
+}
+
+const MAX_PRINCIPAL_LEN: usize = 29;
+const ACCOUNT_KEY_LEN: usize = 1 + MAX_PRINCIPAL_LEN + 32;
+
+/// Fixed-size encoding of an account: the principal length, the principal
+/// padded with zeros and the effective subaccount. The byte order matches
+/// the [Ord] implementation of [Account].
+fn encode_account(account: &Account, buf: &mut Vec<u8>) {
+    let owner = account.owner.as_slice();
+    buf.push(owner.len() as u8);
+    buf.extend_from_slice(owner);
+    buf.resize(buf.len() + MAX_PRINCIPAL_LEN - owner.len(), 0);
+    buf.extend_from_slice(account.effective_subaccount());
+}
+
+fn decode_account(bytes: &[u8]) -> Account {
+    assert_eq!(
+        bytes.len(),
+        ACCOUNT_KEY_LEN,
+        "bug: invalid account encoding"
+    );
+    let owner_len = bytes[0] as usize;
+    let owner = Principal::from_slice(&bytes[1..1 + owner_len]);
+    let subaccount: [u8; 32] = bytes[1 + MAX_PRINCIPAL_LEN..].try_into().unwrap();
+    Account {
+        owner,
+        subaccount: (&subaccount != DEFAULT_SUBACCOUNT).then_some(subaccount),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct AccountKey(Account);
+
+impl Storable for AccountKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = Vec::with_capacity(ACCOUNT_KEY_LEN);
+        encode_account(&self.0, &mut buf);
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(decode_account(&bytes))
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: ACCOUNT_KEY_LEN as u32,
+        is_fixed_size: true,
+    };
+}
+
+impl Storable for ApprovalKey {
+    fn to_bytes(&self) -> Cow<[u8]> {
+        let mut buf = Vec::with_capacity(2 * ACCOUNT_KEY_LEN);
+        encode_account(&self.0, &mut buf);
+        encode_account(&self.1, &mut buf);
+        Cow::Owned(buf)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        Self(
+            decode_account(&bytes[..ACCOUNT_KEY_LEN]),
+            decode_account(&bytes[ACCOUNT_KEY_LEN..]),
+        )
+    }
+
+    const BOUND: Bound = Bound::Bounded {
+        max_size: 2 * ACCOUNT_KEY_LEN as u32,
+        is_fixed_size: true,
+    };
+}
+
+fn encode_tokens<Tokens: TokensType>(tokens: Tokens) -> Vec<u8> {
+    let mut buf = vec![];
+    let n: Nat = tokens.into();
+    n.encode(&mut buf).expect("bug: failed to encode tokens");
+    buf
+}
+
+fn decode_tokens<Tokens: TokensType>(bytes: Vec<u8>) -> Tokens {
+    let n = Nat::decode(&mut &bytes[..])
+        .unwrap_or_else(|e| panic!("bug: invalid tokens encoding {:?}: {}", bytes, e));
+    Tokens::try_from(n).unwrap_or_else(|e| panic!("bug: failed to decode tokens: {}", e))
+}
+
+/// Balances stored in stable memory.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(bound = "")]
+pub struct StableBalances<Tokens> {
+    #[serde(skip)]
+    _marker: PhantomData<Tokens>,
+}
+
+impl<Tokens> Default for StableBalances<Tokens> {
+    fn default() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<Tokens: TokensType> StableBalances<Tokens> {
+    fn insert(&mut self, account: Account, balance: Tokens) {
+        BALANCES.with_borrow_mut(|balances| {
+            balances.insert(AccountKey(account), encode_tokens(balance))
+        });
+    }
+}
+
+impl<Tokens: TokensType> BalancesStore for StableBalances<Tokens> {
+    type AccountId = Account;
+    type Tokens = Tokens;
+
+    fn get_balance(&self, k: &Account) -> Option<Tokens> {
+        BALANCES
+            .with_borrow(|balances| balances.get(&AccountKey(*k)))
+            .map(decode_tokens)
+    }
+
+    fn update<F, E>(&mut self, k: Account, mut f: F) -> Result<Tokens, E>
+    where
+        F: FnMut(Option<&Tokens>) -> Result<Tokens, E>,
+    {
+        let prev = self.get_balance(&k);
+        let new_v = f(prev.as_ref())?;
+        if !new_v.is_zero() {
+            self.insert(k, new_v.clone());
+        } else if prev.is_some() {
+            BALANCES.with_borrow_mut(|balances| balances.remove(&AccountKey(k)));
+        }
+        Ok(new_v)
+    }
+}
+
+impl<Tokens: TokensType> InspectableBalancesStore for StableBalances<Tokens> {
+    fn iter(&self) -> Box<dyn Iterator<Item = (Account, Tokens)> + '_> {
+        // The map lives in a thread-local, so the iterator looks up the entry
+        // following the last returned key on every step instead of borrowing
+        // the map for its whole lifetime.
+        let mut last: Option<AccountKey> = None;
+        Box::new(std::iter::from_fn(move || {
+            let next = BALANCES.with_borrow(|balances| {
+                let lower = match &last {
+                    Some(key) => Excluded(*key),
+                    None => Unbounded,
+                };
+                balances.range((lower, Unbounded)).next()
+            });
+            next.map(|(key, balance)| {
+                last = Some(key);
+                (key.0, decode_tokens(balance))
+            })
+        }))
+    }
+
+    fn len(&self) -> usize {
+        BALANCES.with_borrow(|balances| balances.len()) as usize
+    }
+}
+
+/// Allowances stored in stable memory.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(bound = "")]
+pub struct StableAllowancesData<Tokens> {
+    #[serde(skip)]
+    _marker: PhantomData<Tokens>,
+}
+
+impl<Tokens> Default for StableAllowancesData<Tokens> {
+    fn default() -> Self {
+        Self {
+            _marker: PhantomData,
+        }
+    }
+}
+
+fn approval_key((account, spender): &(Account, Account)) -> ApprovalKey {
+    ApprovalKey(*account, *spender)
+}
+
+impl<Tokens: TokensType> AllowancesData for StableAllowancesData<Tokens> {
+    type AccountId = Account;
+    type Tokens = Tokens;
+
+    fn get_allowance(&self, account_spender: &(Account, Account)) -> Option<Allowance<Tokens>> {
+        ALLOWANCES
+            .with_borrow(|allowances| allowances.get(&approval_key(account_spender)))
+            .map(|bytes| {
+                ciborium::de::from_reader(&bytes[..]).expect("bug: failed to decode allowance")
+            })
+    }
+
+    fn set_allowance(&mut self, account_spender: (Account, Account), allowance: Allowance<Tokens>) {
+        let mut buf = vec![];
+        ciborium::ser::into_writer(&allowance, &mut buf).expect("bug: failed to encode allowance");
+        ALLOWANCES
+            .with_borrow_mut(|allowances| allowances.insert(approval_key(&account_spender), buf));
+    }
+
+    fn remove_allowance(&mut self, account_spender: &(Account, Account)) {
+        ALLOWANCES.with_borrow_mut(|allowances| allowances.remove(&approval_key(account_spender)));
+    }
+
+    fn insert_expiry(&mut self, timestamp: TimeStamp, account_spender: (Account, Account)) {
+        let key = (
+            timestamp.as_nanos_since_unix_epoch(),
+            approval_key(&account_spender),
+        );
+        ALLOWANCES_EXPIRATIONS.with_borrow_mut(|expirations| expirations.insert(key, ()));
+    }
+
+    fn remove_expiry(&mut self, timestamp: TimeStamp, account_spender: (Account, Account)) {
+        let key = (
+            timestamp.as_nanos_since_unix_epoch(),
+            approval_key(&account_spender),
+        );
+        ALLOWANCES_EXPIRATIONS.with_borrow_mut(|expirations| expirations.remove(&key));
+    }
+
+    fn insert_arrival(&mut self, timestamp: TimeStamp, account_spender: (Account, Account)) {
+        let key = (
+            timestamp.as_nanos_since_unix_epoch(),
+            approval_key(&account_spender),
+        );
+        ALLOWANCES_ARRIVALS.with_borrow_mut(|arrivals| arrivals.insert(key, ()));
+    }
+
+    fn remove_arrival(&mut self, timestamp: TimeStamp, account_spender: (Account, Account)) {
+        let key = (
+            timestamp.as_nanos_since_unix_epoch(),
+            approval_key(&account_spender),
+        );
+        ALLOWANCES_ARRIVALS.with_borrow_mut(|arrivals| arrivals.remove(&key));
+    }
+
+    fn first_expiry(&self) -> Option<(TimeStamp, (Account, Account))> {
+        ALLOWANCES_EXPIRATIONS
+            .with_borrow(|expirations| expirations.first_key_value())
+            .map(|((ts, key), ())| (TimeStamp::from_nanos_since_unix_epoch(ts), key.into()))
+    }
+
+    fn pop_first_expiry(&mut self) -> Option<(TimeStamp, (Account, Account))> {
+        ALLOWANCES_EXPIRATIONS.with_borrow_mut(|expirations| {
+            let (key, ()) = expirations.first_key_value()?;
+            expirations.remove(&key);
+            Some((TimeStamp::from_nanos_since_unix_epoch(key.0), key.1.into()))
+        })
+    }
+
+    fn oldest_arrivals(&self, n: usize) -> Vec<(Account, Account)> {
+        ALLOWANCES_ARRIVALS.with_borrow(|arrivals| {
+            arrivals
+                .iter()
+                .take(n)
+                .map(|((_ts, key), ())| key.into())
+                .collect()
+        })
+    }
+
+    fn len_allowances(&self) -> usize {
+        ALLOWANCES.with_borrow(|allowances| allowances.len()) as usize
+    }
+
+    fn len_expirations(&self) -> usize {
+        ALLOWANCES_EXPIRATIONS.with_borrow(|expirations| expirations.len()) as usize
+    }
+
+    fn len_arrivals(&self) -> usize {
+        ALLOWANCES_ARRIVALS.with_borrow(|arrivals| arrivals.len()) as usize
+    }
 }
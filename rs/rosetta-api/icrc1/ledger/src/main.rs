@@ -1,28 +1,52 @@
 use candid::candid_method;
 use candid::types::number::Nat;
+use ic_base_types::{CanisterId, PrincipalId};
+use ic_canister_log::log;
 use ic_canister_log::{declare_log_buffer, export};
 use ic_canisters_http_types::{HttpRequest, HttpResponse, HttpResponseBuilder};
-use ic_cdk::api::stable::{StableReader, StableWriter};
+use ic_cdk::api::stable::StableReader;
 use ic_cdk_macros::{init, post_upgrade, pre_upgrade, query, update};
 use ic_icrc1::{
-    endpoints::{convert_transfer_error, StandardRecord},
+    endpoints::{convert_transfer_error, EndpointsTransferError, StandardRecord},
     Operation, Transaction,
 };
-use ic_icrc1_ledger::{Ledger, LedgerArgument};
+use ic_icrc1_ledger::{
+    ConsolidateArchivesArgs, ConsolidatedArchives, Ledger, LedgerArgument, UPGRADES_MEMORY,
+};
+use ic_ledger_canister_core::archive::{
+    consolidate_archive_nodes, FailedToConsolidateArchiveNodes,
+};
 use ic_ledger_canister_core::ledger::{
     apply_transaction, archive_blocks, LedgerAccess, LedgerContext, LedgerData,
     TransferError as CoreTransferError,
 };
 use ic_ledger_core::tokens::Zero;
 use ic_ledger_core::{approvals::Approvals, timestamp::TimeStamp};
+use ic_stable_structures::reader::{BufferedReader, Reader};
+use ic_stable_structures::writer::{BufferedWriter, Writer};
 use icrc_ledger_types::icrc1::transfer::Memo;
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
+use icrc_ledger_types::icrc21::{
+    consent_message::{build_consent_info, TokenInfo},
+    errors::Icrc21Error,
+    requests::ConsentMessageRequest,
+    responses::ConsentInfo,
+};
 use icrc_ledger_types::icrc3::blocks::DataCertificate;
+use icrc_ledger_types::icrc4::{
+    balance_of_batch::{BalanceOfBatchArgs, BalanceOfBatchError, BalanceOfBatchResult},
+    transfer_batch::{
+        TransferBatchArgs, TransferBatchError, TransferBatchResponse, TransferBatchResults,
+    },
+};
 use icrc_ledger_types::{
     icrc::generic_metadata_value::MetadataValue as Value,
     icrc3::{
-        archive::ArchiveInfo,
-        blocks::{GetBlocksRequest, GetBlocksResponse},
+        archive::{ArchiveInfo, GetArchivesArgs, GetArchivesResult, ICRC3ArchiveInfo},
+        blocks::{
+            GetBlocksRequest, GetBlocksResponse, GetBlocksResult, ICRC3DataCertificate,
+            SupportedBlockType,
+        },
         transactions::{GetTransactionsRequest, GetTransactionsResponse},
     },
 };
@@ -37,8 +61,15 @@ use icrc_ledger_types::{
 use num_traits::{bounds::Bounded, ToPrimitive};
 use serde_bytes::ByteBuf;
 use std::cell::RefCell;
+use std::time::Duration;
 
 const MAX_MESSAGE_SIZE: u64 = 1024 * 1024;
+const UPGRADE_BUFFER_SIZE: usize = 2 * 1024 * 1024;
+const MEMORY_MANAGER_MAGIC: &[u8; 3] = b"MGR";
+const MAX_INSTRUCTIONS_FOR_UPGRADE_MIGRATION: u64 = 100_000_000_000;
+const MAX_INSTRUCTIONS_FOR_TIMER_MIGRATION: u64 = 10_000_000_000;
+const MAX_TRANSFERS_PER_BATCH: usize = 100;
+const MAX_ACCOUNTS_PER_BALANCE_QUERY: usize = 1_000;
 
 #[cfg(not(feature = "u256-tokens"))]
 type Tokens = ic_icrc1_tokens_u64::U64;
@@ -94,18 +125,33 @@ fn init(args: LedgerArgument) {
 
 #[pre_upgrade]
 fn pre_upgrade() {
-    Access::with_ledger(|ledger| ciborium::ser::into_writer(ledger, StableWriter::default()))
+    UPGRADES_MEMORY
+        .with_borrow_mut(|memory| {
+            let writer = BufferedWriter::new(UPGRADE_BUFFER_SIZE, Writer::new(memory, 0));
+            Access::with_ledger(|ledger| ciborium::ser::into_writer(ledger, writer))
+        })
         .expect("failed to encode ledger state");
 }
 
 #[post_upgrade]
 fn post_upgrade(args: Option<LedgerArgument>) {
-    LEDGER.with(|cell| {
-        *cell.borrow_mut() = Some(
-            ciborium::de::from_reader(StableReader::default())
-                .expect("failed to decode ledger state"),
-        );
-    });
+    // Ledger versions prior to the stable structures migration wrote the
+    // state directly to stable memory, which then doesn't start with the
+    // memory manager header.
+    let mut magic_bytes = [0u8; 3];
+    ic_cdk::api::stable::stable64_read(0, &mut magic_bytes);
+    let ledger: Ledger<Tokens> = if &magic_bytes == MEMORY_MANAGER_MAGIC {
+        UPGRADES_MEMORY.with_borrow(|memory| {
+            ciborium::de::from_reader(BufferedReader::new(
+                UPGRADE_BUFFER_SIZE,
+                Reader::new(memory, 0),
+            ))
+        })
+    } else {
+        ciborium::de::from_reader(StableReader::default())
+    }
+    .expect("failed to decode ledger state");
+    LEDGER.with(|cell| *cell.borrow_mut() = Some(ledger));
 
     if let Some(args) = args {
         match args {
@@ -117,6 +163,34 @@ fn post_upgrade(args: Option<LedgerArgument>) {
             }
         }
     }
+    // The format of the certified tree might have changed between versions.
+    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+
+    migrate_to_stable_structures(MAX_INSTRUCTIONS_FOR_UPGRADE_MIGRATION);
+}
+
+/// Moves the next part of the heap data to stable structures and schedules
+/// another round if the migration is not complete.
+fn migrate_to_stable_structures(max_instructions: u64) {
+    if Access::with_ledger(Ledger::is_ready) {
+        return;
+    }
+    let done = Access::with_ledger_mut(|ledger| {
+        ledger
+            .migrate_to_stable_structures(|| ic_cdk::api::instruction_counter() < max_instructions)
+    });
+    if done {
+        log!(LOG, "[ledger] migration to stable structures complete");
+    } else {
+        log!(
+            LOG,
+            "[ledger] migration to stable structures in progress: {:?}",
+            Access::with_ledger(Ledger::state)
+        );
+        ic_cdk_timers::set_timer(Duration::from_secs(0), || {
+            migrate_to_stable_structures(MAX_INSTRUCTIONS_FOR_TIMER_MIGRATION)
+        });
+    }
 }
 
 fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> std::io::Result<()> {
@@ -161,6 +235,14 @@ fn encode_metrics(w: &mut ic_metrics_encoder::MetricsEncoder<Vec<u8>>) -> std::i
             ledger.blockchain().num_archived_blocks as f64,
             "Total number of transactions sent to the archive.",
         )?;
+        w.encode_gauge(
+            "ledger_is_ready",
+            if ledger.is_ready() { 1.0 } else { 0.0 },
+            "Whether the migration to stable structures is complete.",
+        )?;
+        if !ledger.is_ready() {
+            return Ok(());
+        }
         let token_pool: Nat = ledger.balances().token_pool.into();
         w.encode_gauge(
             "ledger_balances_token_pool",
@@ -275,6 +357,15 @@ fn icrc1_total_supply() -> Nat {
     Access::with_ledger(|ledger| ledger.balances().total_supply().into())
 }
 
+/// Converts a ledger error into the error type of an endpoint. Traps on
+/// errors that cannot happen for the endpoint.
+fn into_endpoint_error<E>(err: CoreTransferError<Tokens>) -> E
+where
+    E: TryFrom<EndpointsTransferError<Tokens>, Error = String>,
+{
+    E::try_from(convert_transfer_error(err)).unwrap_or_else(|err| ic_cdk::trap(&err))
+}
+
 async fn execute_transfer(
     from_account: Account,
     to: Account,
@@ -284,7 +375,36 @@ async fn execute_transfer(
     memo: Option<Memo>,
     created_at_time: Option<u64>,
 ) -> Result<Nat, CoreTransferError<Tokens>> {
-    let block_idx = Access::with_ledger_mut(|ledger| {
+    let block_idx = execute_transfer_not_async(
+        from_account,
+        to,
+        spender,
+        fee,
+        amount,
+        memo,
+        created_at_time,
+    )?;
+
+    // NB. we need to set the certified data before the first async call to make sure that the
+    // blockchain state agrees with the certificate while archiving is in progress.
+    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+
+    archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
+    Ok(Nat::from(block_idx))
+}
+
+/// Validates the transfer and appends its block to the ledger without
+/// certifying the new state nor archiving blocks.
+fn execute_transfer_not_async(
+    from_account: Account,
+    to: Account,
+    spender: Option<Account>,
+    fee: Option<Nat>,
+    amount: Nat,
+    memo: Option<Memo>,
+    created_at_time: Option<u64>,
+) -> Result<u64, CoreTransferError<Tokens>> {
+    Access::with_ledger_mut(|ledger| {
         let now = TimeStamp::from_nanos_since_unix_epoch(ic_cdk::api::time());
         let created_at_time = created_at_time.map(TimeStamp::from_nanos_since_unix_epoch);
 
@@ -375,14 +495,7 @@ async fn execute_transfer(
 
         let (block_idx, _) = apply_transaction(ledger, tx, now, effective_fee)?;
         Ok(block_idx)
-    })?;
-
-    // NB. we need to set the certified data before the first async call to make sure that the
-    // blockchain state agrees with the certificate while archiving is in progress.
-    ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
-
-    archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
-    Ok(Nat::from(block_idx))
+    })
 }
 
 #[update]
@@ -402,14 +515,82 @@ async fn icrc1_transfer(arg: TransferArg) -> Result<Nat, TransferError> {
         arg.created_at_time,
     )
     .await
-    .map_err(convert_transfer_error)
-    .map_err(|err| {
-        let err: TransferError = match err.try_into() {
-            Ok(err) => err,
-            Err(err) => ic_cdk::trap(&err),
-        };
-        err
-    })
+    .map_err(into_endpoint_error::<TransferError>)
+}
+
+/// Applies the transfers of the batch one after the other in a single message,
+/// so no other call can interleave with them. The batch is not atomic: a
+/// failing transfer does not prevent the following ones from being applied and
+/// does not revert the preceding ones. A batch with more than
+/// [MAX_TRANSFERS_PER_BATCH] transfers is rejected as a whole and none of its
+/// transfers is processed.
+#[update]
+#[candid_method(update)]
+async fn icrc4_transfer_batch(args: TransferBatchArgs) -> TransferBatchResponse {
+    if args.len() > MAX_TRANSFERS_PER_BATCH {
+        return Err(TransferBatchError::TooManyRequests {
+            limit: Nat::from(MAX_TRANSFERS_PER_BATCH),
+        });
+    }
+    let caller = ic_cdk::api::caller();
+    let results: TransferBatchResults = args
+        .into_iter()
+        .map(|arg| {
+            let from_account = Account {
+                owner: caller,
+                subaccount: arg.from_subaccount,
+            };
+            let result = execute_transfer_not_async(
+                from_account,
+                arg.to,
+                None,
+                arg.fee,
+                arg.amount,
+                arg.memo,
+                arg.created_at_time,
+            )
+            .map(Nat::from)
+            .map_err(|err| TransferBatchError::from(into_endpoint_error::<TransferError>(err)));
+            Some(result)
+        })
+        .collect();
+
+    if results.iter().any(|result| matches!(result, Some(Ok(_)))) {
+        // NB. we need to set the certified data before the first async call to make sure that the
+        // blockchain state agrees with the certificate while archiving is in progress.
+        ic_cdk::api::set_certified_data(&Access::with_ledger(Ledger::root_hash));
+
+        archive_blocks::<Access>(&LOG, MAX_MESSAGE_SIZE).await;
+    }
+    Ok(results)
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc4_balance_of_batch(accounts: BalanceOfBatchArgs) -> BalanceOfBatchResult {
+    if accounts.len() > MAX_ACCOUNTS_PER_BALANCE_QUERY {
+        return Err(BalanceOfBatchError::TooManyRequests {
+            limit: Nat::from(MAX_ACCOUNTS_PER_BALANCE_QUERY),
+        });
+    }
+    Ok(Access::with_ledger(|ledger| {
+        accounts
+            .iter()
+            .map(|account| ledger.balances().account_balance(account).into())
+            .collect()
+    }))
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc4_maximum_update_batch_size() -> Option<Nat> {
+    Some(Nat::from(MAX_TRANSFERS_PER_BATCH))
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc4_maximum_query_batch_size() -> Option<Nat> {
+    Some(Nat::from(MAX_ACCOUNTS_PER_BALANCE_QUERY))
 }
 
 #[update]
@@ -429,14 +610,7 @@ async fn icrc2_transfer_from(arg: TransferFromArgs) -> Result<Nat, TransferFromE
         arg.created_at_time,
     )
     .await
-    .map_err(convert_transfer_error)
-    .map_err(|err| {
-        let err: TransferFromError = match err.try_into() {
-            Ok(err) => err,
-            Err(err) => ic_cdk::trap(&err),
-        };
-        err
-    })
+    .map_err(into_endpoint_error::<TransferFromError>)
 }
 
 #[query]
@@ -463,6 +637,33 @@ fn archives() -> Vec<ArchiveInfo> {
     })
 }
 
+/// Merges consecutive archives into a new archive and replaces them in the
+/// archive index. Only the controllers of the ledger can call this endpoint.
+/// The retired archives are not deleted by the ledger.
+#[update]
+#[candid_method(update)]
+async fn consolidate_archives(
+    args: ConsolidateArchivesArgs,
+) -> Result<ConsolidatedArchives, String> {
+    if !ic_cdk::api::is_controller(&ic_cdk::api::caller()) {
+        ic_cdk::trap("only the controllers of the ledger can consolidate archives");
+    }
+    let nodes = args
+        .archive_ids
+        .into_iter()
+        .map(|archive_id| CanisterId::unchecked_from_principal(PrincipalId(archive_id)))
+        .collect();
+    consolidate_archive_nodes::<Access>(
+        &LOG,
+        nodes,
+        args.node_max_memory_size_bytes,
+        MAX_MESSAGE_SIZE,
+    )
+    .await
+    .map(ConsolidatedArchives::from)
+    .map_err(|FailedToConsolidateArchiveNodes(msg)| msg)
+}
+
 #[query(name = "icrc1_supported_standards")]
 #[candid_method(query, rename = "icrc1_supported_standards")]
 fn supported_standards() -> Vec<StandardRecord> {
@@ -475,6 +676,18 @@ fn supported_standards() -> Vec<StandardRecord> {
             name: "ICRC-2".to_string(),
             url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-2".to_string(),
         },
+        StandardRecord {
+            name: "ICRC-3".to_string(),
+            url: "https://github.com/dfinity/ICRC-1/tree/main/standards/ICRC-3".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-4".to_string(),
+            url: "https://github.com/dfinity/ICRC/tree/main/ICRCs/ICRC-4".to_string(),
+        },
+        StandardRecord {
+            name: "ICRC-21".to_string(),
+            url: "https://github.com/dfinity/wg-identity-authentication/blob/main/topics/ICRC-21/icrc_21_consent_msg.md".to_string(),
+        },
     ];
     standards
 }
@@ -509,6 +722,84 @@ fn get_data_certificate() -> DataCertificate {
     }
 }
 
+#[query]
+#[candid_method(query)]
+fn icrc3_get_archives(args: GetArchivesArgs) -> GetArchivesResult {
+    let archives = archives().into_iter().map(|archive| ICRC3ArchiveInfo {
+        canister_id: archive.canister_id,
+        start: archive.block_range_start,
+        end: archive.block_range_end,
+    });
+    match args.from {
+        Some(from) => archives
+            .skip_while(|archive| archive.canister_id != from)
+            .skip(1)
+            .collect(),
+        None => archives.collect(),
+    }
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_tip_certificate() -> Option<ICRC3DataCertificate> {
+    let certificate = ByteBuf::from(ic_cdk::api::data_certificate()?);
+    let hash_tree = Access::with_ledger(|ledger| ledger.construct_hash_tree());
+    let mut tree_buf = vec![];
+    ciborium::ser::into_writer(&hash_tree, &mut tree_buf).unwrap();
+    Some(ICRC3DataCertificate {
+        certificate,
+        hash_tree: ByteBuf::from(tree_buf),
+    })
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_get_blocks(args: Vec<GetBlocksRequest>) -> GetBlocksResult {
+    let ranges = args
+        .iter()
+        .map(|arg| {
+            let (start, length) = arg
+                .as_start_and_length()
+                .unwrap_or_else(|msg| ic_cdk::api::trap(&msg));
+            (start, length as usize)
+        })
+        .collect();
+    Access::with_ledger(|ledger| ledger.icrc3_get_blocks(ranges))
+}
+
+#[query]
+#[candid_method(query)]
+fn icrc3_supported_block_types() -> Vec<SupportedBlockType> {
+    let icrc1_url = "https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-1/README.md";
+    let icrc2_url = "https://github.com/dfinity/ICRC-1/blob/main/standards/ICRC-2/README.md";
+    [
+        ("1burn", icrc1_url),
+        ("1mint", icrc1_url),
+        ("1xfer", icrc1_url),
+        ("2approve", icrc2_url),
+        ("2xfer", icrc2_url),
+    ]
+    .into_iter()
+    .map(|(block_type, url)| SupportedBlockType {
+        block_type: block_type.to_string(),
+        url: url.to_string(),
+    })
+    .collect()
+}
+
+#[update]
+#[candid_method(update)]
+fn icrc21_canister_call_consent_message(
+    consent_msg_request: ConsentMessageRequest,
+) -> Result<ConsentInfo, Icrc21Error> {
+    let token = TokenInfo {
+        symbol: icrc1_symbol(),
+        decimals: icrc1_decimals(),
+        fee: icrc1_fee(),
+    };
+    build_consent_info(consent_msg_request, ic_cdk::api::caller(), &token)
+}
+
 #[update]
 #[candid_method(update)]
 async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
@@ -568,14 +859,7 @@ async fn icrc2_approve(arg: ApproveArgs) -> Result<Nat, ApproveError> {
         };
 
         let (block_idx, _) = apply_transaction(ledger, tx, now, expected_fee_tokens)
-            .map_err(convert_transfer_error)
-            .map_err(|err| {
-                let err: ApproveError = match err.try_into() {
-                    Ok(err) => err,
-                    Err(err) => ic_cdk::trap(&err),
-                };
-                err
-            })?;
+            .map_err(into_endpoint_error::<ApproveError>)?;
         Ok(block_idx)
     })?;
 
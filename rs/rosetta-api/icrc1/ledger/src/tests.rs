@@ -615,3 +615,103 @@ fn test_approval_burn_from() {
     assert_eq!(ctx.balances().account_balance(&spender), Tokens::ZERO);
     assert_eq!(ctx.balances().total_supply().get_e8s(), 90_000);
 }
+
+#[test]
+fn test_storable_keys_roundtrip() {
+    use crate::{AccountKey, ApprovalKey};
+    use ic_stable_structures::Storable;
+
+    let accounts = [
+        test_account_id(1),
+        Account {
+            owner: PrincipalId::new_user_test_id(2).into(),
+            subaccount: Some([7; 32]),
+        },
+        Account {
+            owner: candid::Principal::management_canister(),
+            subaccount: None,
+        },
+    ];
+    for account in accounts {
+        let key = AccountKey(account);
+        assert_eq!(AccountKey::from_bytes(key.to_bytes()), key);
+        for spender in accounts {
+            let key = ApprovalKey(account, spender);
+            assert_eq!(ApprovalKey::from_bytes(key.to_bytes()), key);
+        }
+    }
+}
+
+#[test]
+fn test_migration_to_stable_structures_is_resumable() {
+    use crate::{LedgerField, LedgerState};
+    use ic_ledger_core::approvals::PrunableApprovals;
+
+    let now = ts(12345678);
+    let mut ctx = Ledger::from_init_args(DummyLogger, default_init_args(), now);
+
+    // Simulate the state of a ledger deserialized from the heap-only format.
+    ctx.state = LedgerState::Migrating(LedgerField::Allowances);
+    let spender = test_account_id(100);
+    for i in 1..=5 {
+        ctx.balances
+            .mint(&test_account_id(i), tokens(i * 1000))
+            .unwrap();
+        ctx.approvals
+            .approve(
+                &test_account_id(i),
+                &spender,
+                tokens(i * 10),
+                Some(ts(now.as_nanos_since_unix_epoch() + i)),
+                now,
+                None,
+            )
+            .unwrap();
+    }
+
+    // Interrupt the migration after a few steps, as an instruction limit would.
+    let steps = std::cell::Cell::new(0);
+    let done = ctx.migrate_to_stable_structures(|| {
+        steps.set(steps.get() + 1);
+        steps.get() <= 7
+    });
+    assert!(!done);
+    assert_eq!(
+        ctx.state(),
+        LedgerState::Migrating(LedgerField::AllowancesExpirations)
+    );
+
+    assert!(ctx.migrate_to_stable_structures(|| true));
+    assert!(ctx.is_ready());
+    assert_eq!(ctx.approvals.len(), 0);
+    assert!(ctx.balances.store.is_empty());
+
+    for i in 1..=5 {
+        assert_eq!(
+            ctx.balances().account_balance(&test_account_id(i)),
+            tokens(i * 1000)
+        );
+        assert_eq!(
+            ctx.approvals()
+                .allowance(&test_account_id(i), &spender, now)
+                .amount,
+            tokens(i * 10)
+        );
+    }
+    assert_eq!(ctx.balances().total_supply(), tokens(15_000));
+
+    // The expiration queue was migrated too.
+    let later = ts(now.as_nanos_since_unix_epoch() + 3);
+    assert_eq!(ctx.approvals_mut().prune(later, 10), 3);
+    assert_eq!(ctx.approvals().len(), 2);
+}
+
+#[test]
+#[should_panic(expected = "The Ledger is not ready")]
+fn test_balances_are_unavailable_while_migrating() {
+    use crate::{LedgerField, LedgerState};
+
+    let mut ctx = Ledger::from_init_args(DummyLogger, default_init_args(), ts(1));
+    ctx.state = LedgerState::Migrating(LedgerField::Balances);
+    ctx.balances().account_balance(&test_account_id(1));
+}
@@ -1,6 +1,9 @@
 use candid::{CandidType, Decode, Encode, Nat};
 use ic_base_types::{CanisterId, PrincipalId};
-use ic_icrc1_ledger::{ChangeFeeCollector, FeatureFlags, InitArgs, LedgerArgument};
+use ic_icrc1_ledger::{
+    ChangeFeeCollector, ConsolidateArchivesArgs, ConsolidatedArchives, FeatureFlags, InitArgs,
+    LedgerArgument,
+};
 use ic_icrc1_ledger_sm_tests::{
     get_allowance, send_approval, send_transfer_from, ARCHIVE_TRIGGER_THRESHOLD, BLOB_META_KEY,
     BLOB_META_VALUE, DECIMAL_PLACES, FEE, INT_META_KEY, INT_META_VALUE, MINTER, NAT_META_KEY,
@@ -16,6 +19,9 @@ use icrc_ledger_types::icrc1::transfer::{TransferArg, TransferError};
 use icrc_ledger_types::icrc2::allowance::Allowance;
 use icrc_ledger_types::icrc2::approve::{ApproveArgs, ApproveError};
 use icrc_ledger_types::icrc2::transfer_from::{TransferFromArgs, TransferFromError};
+use icrc_ledger_types::icrc3::archive::ArchiveInfo;
+use icrc_ledger_types::icrc3::blocks::{BlockRange, GenericBlock};
+use icrc_ledger_types::icrc3::transactions::GetTransactionsRequest;
 use num_traits::ToPrimitive;
 use std::path::PathBuf;
 
@@ -142,6 +148,21 @@ fn test_get_blocks() {
     ic_icrc1_ledger_sm_tests::test_get_blocks(ledger_wasm(), encode_init_args);
 }
 
+#[test]
+fn test_icrc3_get_blocks() {
+    ic_icrc1_ledger_sm_tests::test_icrc3_get_blocks(ledger_wasm(), encode_init_args);
+}
+
+#[test]
+fn test_icrc4_transfer_batch() {
+    ic_icrc1_ledger_sm_tests::test_icrc4_transfer_batch(ledger_wasm(), encode_init_args);
+}
+
+#[test]
+fn test_icrc21_standard() {
+    ic_icrc1_ledger_sm_tests::test_icrc21_standard(ledger_wasm(), encode_init_args);
+}
+
 // Generate random blocks and check that their CBOR encoding complies with the CDDL spec.
 #[test]
 fn block_encoding_agrees_with_the_schema() {
@@ -516,6 +537,155 @@ fn test_icrc2_feature_flag_doesnt_disable_icrc2_endpoints() {
     );
 }
 
+fn list_archives(env: &StateMachine, ledger_id: CanisterId) -> Vec<ArchiveInfo> {
+    let res = env
+        .query(ledger_id, "archives", Encode!().unwrap())
+        .expect("Unable to perform archives")
+        .bytes();
+    Decode!(&res, Vec<ArchiveInfo>).unwrap()
+}
+
+fn get_archive_blocks(env: &StateMachine, archive: &ArchiveInfo) -> Vec<GenericBlock> {
+    let args = Encode!(&GetTransactionsRequest {
+        start: archive.block_range_start.clone(),
+        length: archive.block_range_end.clone() - archive.block_range_start.clone()
+            + Nat::from(1u8),
+    })
+    .unwrap();
+    let res = env
+        .query(
+            CanisterId::unchecked_from_principal(PrincipalId(archive.canister_id)),
+            "get_blocks",
+            args,
+        )
+        .expect("Unable to perform get_blocks")
+        .bytes();
+    Decode!(&res, BlockRange).unwrap().blocks
+}
+
+fn consolidate_archives(
+    env: &StateMachine,
+    ledger_id: CanisterId,
+    caller: PrincipalId,
+    args: &ConsolidateArchivesArgs,
+) -> Result<Result<ConsolidatedArchives, String>, String> {
+    let res = env
+        .execute_ingress_as(
+            caller,
+            ledger_id,
+            "consolidate_archives",
+            Encode!(args).unwrap(),
+        )
+        .map_err(|err| err.to_string())?
+        .bytes();
+    Ok(Decode!(&res, Result<ConsolidatedArchives, String>).unwrap())
+}
+
+#[test]
+fn test_consolidate_archives() {
+    let env = StateMachine::new();
+    let init_args = Encode!(&LedgerArgument::Init(InitArgs {
+        minting_account: MINTER,
+        fee_collector_account: None,
+        initial_balances: vec![],
+        transfer_fee: FEE.into(),
+        token_name: TOKEN_NAME.to_string(),
+        decimals: Some(DECIMAL_PLACES),
+        token_symbol: TOKEN_SYMBOL.to_string(),
+        metadata: vec![],
+        archive_options: ArchiveOptions {
+            trigger_threshold: ARCHIVE_TRIGGER_THRESHOLD as usize,
+            num_blocks_to_archive: NUM_BLOCKS_TO_ARCHIVE as usize,
+            // Small archives so that every archiving round fills one of them.
+            node_max_memory_size_bytes: Some(1_000),
+            max_message_size_bytes: None,
+            controller_id: PrincipalId::new_user_test_id(100),
+            more_controller_ids: None,
+            cycles_for_archive_creation: None,
+            max_transactions_per_response: None,
+        },
+        max_memo_length: None,
+        feature_flags: None,
+        maximum_number_of_accounts: None,
+        accounts_overflow_trim_quantity: None,
+    }))
+    .unwrap();
+    // The anonymous principal installing the ledger is its controller.
+    let ledger_id = env
+        .install_canister(ledger_wasm(), init_args, None)
+        .unwrap();
+
+    for i in 0..4 * ARCHIVE_TRIGGER_THRESHOLD {
+        transfer(&env, ledger_id, MINTER, account(1), 1_000_000 + i);
+        env.run_until_completion(/*max_ticks=*/ 10);
+    }
+
+    let archives = list_archives(&env, ledger_id);
+    assert!(
+        archives.len() >= 2,
+        "expected at least 2 archives, got {:?}",
+        archives
+    );
+    let blocks: Vec<GenericBlock> = archives
+        .iter()
+        .flat_map(|archive| get_archive_blocks(&env, archive))
+        .collect();
+    let args = ConsolidateArchivesArgs {
+        archive_ids: archives.iter().map(|archive| archive.canister_id).collect(),
+        node_max_memory_size_bytes: Some(1_000),
+    };
+
+    // The blocks of all archives do not fit into a new archive of the size of
+    // a single archive, which is detected before the new archive is created.
+    let err = consolidate_archives(&env, ledger_id, PrincipalId::new_anonymous(), &args)
+        .unwrap()
+        .unwrap_err();
+    assert!(err.contains("exceeds the capacity"), "{}", err);
+    assert!(!err.contains("can be deleted"), "{}", err);
+    assert_eq!(list_archives(&env, ledger_id), archives);
+
+    let args = ConsolidateArchivesArgs {
+        node_max_memory_size_bytes: Some(1024 * 1024),
+        ..args
+    };
+
+    // Only the controllers of the ledger can consolidate archives.
+    assert!(
+        consolidate_archives(&env, ledger_id, PrincipalId::new_user_test_id(1), &args).is_err()
+    );
+    assert_eq!(list_archives(&env, ledger_id), archives);
+
+    let consolidated = consolidate_archives(&env, ledger_id, PrincipalId::new_anonymous(), &args)
+        .unwrap()
+        .expect("failed to consolidate archives");
+    assert_eq!(consolidated.block_range_start, 0u8);
+    assert_eq!(
+        consolidated.block_range_end,
+        archives.last().unwrap().block_range_end
+    );
+    assert_eq!(consolidated.retired_archive_ids, args.archive_ids);
+
+    let new_archives = list_archives(&env, ledger_id);
+    assert_eq!(
+        new_archives,
+        vec![ArchiveInfo {
+            canister_id: consolidated.archive_id,
+            block_range_start: consolidated.block_range_start,
+            block_range_end: consolidated.block_range_end,
+        }]
+    );
+    assert_eq!(get_archive_blocks(&env, &new_archives[0]), blocks);
+
+    // The ledger keeps archiving to the new archive.
+    for i in 0..ARCHIVE_TRIGGER_THRESHOLD {
+        transfer(&env, ledger_id, MINTER, account(2), 1_000_000 + i);
+    }
+    env.run_until_completion(/*max_ticks=*/ 10);
+    let archives = list_archives(&env, ledger_id);
+    assert_eq!(archives[0].canister_id, new_archives[0].canister_id);
+    assert!(archives[0].block_range_end > new_archives[0].block_range_end);
+}
+
 mod verify_written_blocks {
     use super::*;
     use ic_icrc1_ledger::FeatureFlags;
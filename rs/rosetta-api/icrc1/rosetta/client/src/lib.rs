@@ -134,6 +134,30 @@ impl RosettaClient {
             .await
     }
 
+    pub async fn account_balance(
+        &self,
+        account_balance_request: AccountBalanceRequest,
+    ) -> Result<AccountBalanceResponse, Error> {
+        self.call_endpoint("/account/balance", &account_balance_request)
+            .await
+    }
+
+    pub async fn account_coins(
+        &self,
+        account_coins_request: AccountCoinsRequest,
+    ) -> Result<AccountCoinsResponse, Error> {
+        self.call_endpoint("/account/coins", &account_coins_request)
+            .await
+    }
+
+    pub async fn search_transactions(
+        &self,
+        search_transactions_request: SearchTransactionsRequest,
+    ) -> Result<SearchTransactionsResponse, Error> {
+        self.call_endpoint("/search/transactions", &search_transactions_request)
+            .await
+    }
+
     pub async fn construction_derive(
         &self,
         construction_derive_request: ConstructionDeriveRequest,
@@ -173,4 +197,44 @@ impl RosettaClient {
         )
         .await
     }
+
+    pub async fn construction_payloads(
+        &self,
+        construction_payloads_request: ConstructionPayloadsRequest,
+    ) -> Result<ConstructionPayloadsResponse, Error> {
+        self.call_endpoint("/construction/payloads", &construction_payloads_request)
+            .await
+    }
+
+    pub async fn construction_combine(
+        &self,
+        construction_combine_request: ConstructionCombineRequest,
+    ) -> Result<ConstructionCombineResponse, Error> {
+        self.call_endpoint("/construction/combine", &construction_combine_request)
+            .await
+    }
+
+    pub async fn construction_parse(
+        &self,
+        construction_parse_request: ConstructionParseRequest,
+    ) -> Result<ConstructionParseResponse, Error> {
+        self.call_endpoint("/construction/parse", &construction_parse_request)
+            .await
+    }
+
+    pub async fn construction_hash(
+        &self,
+        construction_hash_request: ConstructionHashRequest,
+    ) -> Result<ConstructionHashResponse, Error> {
+        self.call_endpoint("/construction/hash", &construction_hash_request)
+            .await
+    }
+
+    pub async fn construction_submit(
+        &self,
+        construction_submit_request: ConstructionSubmitRequest,
+    ) -> Result<ConstructionSubmitResponse, Error> {
+        self.call_endpoint("/construction/submit", &construction_submit_request)
+            .await
+    }
 }
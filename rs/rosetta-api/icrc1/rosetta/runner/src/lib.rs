@@ -1,6 +1,6 @@
 use candid::Principal;
 use std::default::Default;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::{Child, Command};
 use std::str::FromStr;
 
@@ -44,6 +44,8 @@ pub struct RosettaOptions {
     pub symbol: Option<String>,
 
     pub decimals: Option<u32>,
+
+    pub multi_tokens_config: Option<PathBuf>,
 }
 
 impl Default for RosettaOptions {
@@ -57,6 +59,7 @@ impl Default for RosettaOptions {
             offline: true,
             symbol: Some(DEFAULT_TOKEN_SYMBOL.to_string()),
             decimals: Some(DEFAULT_DECIMAL_PLACES.into()),
+            multi_tokens_config: None,
         }
     }
 }
@@ -102,6 +105,12 @@ pub async fn start_rosetta(rosetta_bin: &Path, arguments: RosettaOptions) -> Ros
         command = command.arg("--icrc1-decimals").arg(decimals.to_string());
     }
 
+    if let Some(multi_tokens_config) = arguments.multi_tokens_config {
+        command = command
+            .arg("--multi-tokens-config")
+            .arg(multi_tokens_config);
+    }
+
     if arguments.exit_on_sync {
         command = command.arg("--exit-on-sync");
     }
@@ -0,0 +1,143 @@
+use anyhow::{bail, Context};
+use ic_base_types::CanisterId;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, path::Path, path::PathBuf};
+
+/// The configuration of a single ICRC-1 ledger that is served by Rosetta.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenConfig {
+    pub ledger_id: CanisterId,
+
+    /// The symbol of the token. Has to be set together with `icrc1_decimals` when Rosetta is started in offline mode without a populated database.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icrc1_symbol: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icrc1_decimals: Option<u8>,
+
+    /// The file in which the blocks of this ledger are stored. Defaults to `<ledger_id>.sqlite` in the directory of the default store file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub store_file: Option<PathBuf>,
+}
+
+impl TokenConfig {
+    pub fn new(ledger_id: CanisterId) -> Self {
+        Self {
+            ledger_id,
+            icrc1_symbol: None,
+            icrc1_decimals: None,
+            store_file: None,
+        }
+    }
+
+    pub fn are_metadata_args_set(&self) -> bool {
+        self.icrc1_symbol.is_some() && self.icrc1_decimals.is_some()
+    }
+
+    // Returns the store file of this ledger. If no store file is configured the file is placed next to the default store file.
+    pub fn effective_store_file(&self, default_store_file: &Path) -> PathBuf {
+        self.store_file.clone().unwrap_or_else(|| {
+            default_store_file.with_file_name(format!("{}.sqlite", self.ledger_id))
+        })
+    }
+}
+
+/// The configuration file of a Rosetta instance that serves multiple ICRC-1 ledgers.
+/// The file is a JSON object of the form `{"tokens": [{"ledger_id": "<ledger_id>", ...}, ...]}`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiTokenConfig {
+    pub tokens: Vec<TokenConfig>,
+}
+
+impl MultiTokenConfig {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Unable to read the tokens config file {}", path.display()))?;
+        let config: Self = serde_json::from_str(&content).with_context(|| {
+            format!("Unable to parse the tokens config file {}", path.display())
+        })?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Every ledger may only be configured once and no two ledgers may share a store file.
+    fn validate(&self) -> anyhow::Result<()> {
+        let mut ledger_ids = HashSet::new();
+        let mut store_files = HashSet::new();
+        for token in &self.tokens {
+            if !ledger_ids.insert(token.ledger_id) {
+                bail!(
+                    "The ledger {} is configured more than once",
+                    token.ledger_id
+                );
+            }
+            if let Some(store_file) = &token.store_file {
+                if !store_files.insert(store_file) {
+                    bail!(
+                        "The store file {} is used by more than one ledger",
+                        store_file.display()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_parse_multi_token_config() {
+        let ledger_id = CanisterId::from_str("mxzaz-hqaaa-aaaar-qaada-cai").unwrap();
+        let tmpdir = tempfile::tempdir().unwrap();
+        let config_file = tmpdir.path().join("tokens.json");
+
+        std::fs::write(
+            &config_file,
+            format!(
+                r#"{{"tokens": [{{"ledger_id": "{}"}}, {{"ledger_id": "{}", "icrc1_symbol": "XTST", "icrc1_decimals": 8, "store_file": "xtst.sqlite"}}]}}"#,
+                CanisterId::from_u64(1),
+                ledger_id
+            ),
+        )
+        .unwrap();
+        let config = MultiTokenConfig::from_file(&config_file).unwrap();
+        assert_eq!(
+            config.tokens,
+            vec![
+                TokenConfig::new(CanisterId::from_u64(1)),
+                TokenConfig {
+                    ledger_id,
+                    icrc1_symbol: Some("XTST".to_string()),
+                    icrc1_decimals: Some(8),
+                    store_file: Some(PathBuf::from("xtst.sqlite")),
+                }
+            ]
+        );
+        assert_eq!(
+            config.tokens[0].effective_store_file(Path::new("/data/db.sqlite")),
+            PathBuf::from(format!("/data/{}.sqlite", CanisterId::from_u64(1)))
+        );
+        assert_eq!(
+            config.tokens[1].effective_store_file(Path::new("/data/db.sqlite")),
+            PathBuf::from("xtst.sqlite")
+        );
+
+        // The same ledger must not be configured twice
+        std::fs::write(
+            &config_file,
+            format!(
+                r#"{{"tokens": [{{"ledger_id": "{}"}}, {{"ledger_id": "{}"}}]}}"#,
+                ledger_id, ledger_id
+            ),
+        )
+        .unwrap();
+        assert!(MultiTokenConfig::from_file(&config_file)
+            .unwrap_err()
+            .to_string()
+            .contains("configured more than once"));
+    }
+}
@@ -1,3 +1,4 @@
 pub const DEFAULT_BLOCKCHAIN: &str = "Internet Computer";
 pub const ROSETTA_VERSION: &str = "1.4.13";
 pub const NODE_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub const MAX_TRANSACTIONS_PER_SEARCH_TRANSACTIONS_REQUEST: u64 = 10000;
@@ -1,6 +1,6 @@
 use super::{
     storage_operations,
-    types::{MetadataEntry, RosettaBlock, Tokens},
+    types::{MetadataEntry, RosettaBlock, Tokens, TransactionSearchFilter},
 };
 use anyhow::{bail, Result};
 use ic_icrc1::Transaction;
@@ -38,6 +38,7 @@ impl StorageClient {
             .unwrap()
             .execute("PRAGMA foreign_keys = 1", [])?;
         storage_client.create_tables()?;
+        storage_operations::migrate_schema(&mut storage_client.storage_connection.lock().unwrap())?;
         Ok(storage_client)
     }
 
@@ -104,6 +105,16 @@ impl StorageClient {
         storage_operations::get_transaction_at_idx(&open_connection, block_idx)
     }
 
+    // Returns the blocks whose transactions match the search filter, starting with the block with the highest index.
+    // Also returns the total number of matching blocks regardless of the offset and the limit of the search filter.
+    pub fn search_transactions(
+        &self,
+        search_filter: &TransactionSearchFilter,
+    ) -> anyhow::Result<(Vec<RosettaBlock>, u64)> {
+        let open_connection = self.storage_connection.lock().unwrap();
+        storage_operations::search_transactions(&open_connection, search_filter)
+    }
+
     pub fn read_metadata(&self) -> anyhow::Result<Vec<MetadataEntry>> {
         let open_connection = self.storage_connection.lock().unwrap();
         storage_operations::get_metadata(&open_connection)
@@ -420,6 +431,35 @@ mod tests {
               }
            }
 
+          #[test]
+          fn test_migrate_schema_backfills_spenders(blocks in prop::collection::vec(blocks_strategy::<Tokens>(arb_amount::<Tokens>()),1..100)){
+              let storage_client_memory = StorageClient::new_in_memory().unwrap();
+              let rosetta_blocks: Vec<_> = blocks.into_iter().enumerate()
+                  .map(|(index,block)| RosettaBlock::from_icrc_ledger_block(block,index as u64).unwrap())
+                  .collect();
+              storage_client_memory.store_blocks(rosetta_blocks.clone()).unwrap();
+
+              let mut connection = storage_client_memory.storage_connection.lock().unwrap();
+              // Simulate a database that was created before the spender of transfers and burns was stored.
+              connection.execute("UPDATE transactions SET spender_principal = NULL, spender_subaccount = NULL WHERE operation_type IN ('transfer', 'burn')", []).unwrap();
+              connection.pragma_update(None, "user_version", 0).unwrap();
+
+              storage_operations::migrate_schema(&mut connection).unwrap();
+              let schema_version: u32 = connection.pragma_query_value(None, "user_version", |row| row.get(0)).unwrap();
+              assert_eq!(schema_version, storage_operations::SCHEMA_VERSION);
+
+              for rosetta_block in rosetta_blocks{
+                  let expected_spender = match rosetta_block.get_transaction().unwrap().operation {
+                      Operation::Transfer { spender, .. } | Operation::Burn { spender, .. } => spender,
+                      Operation::Approve { spender, .. } => Some(spender),
+                      Operation::Mint { .. } => None,
+                  };
+                  let (spender_principal, spender_subaccount): (Option<Vec<u8>>, Option<Vec<u8>>) = connection.query_row("SELECT spender_principal, spender_subaccount FROM transactions WHERE block_idx = ?1", [rosetta_block.index], |row| Ok((row.get(0)?, row.get(1)?))).unwrap();
+                  assert_eq!(spender_principal, expected_spender.map(|spender| spender.owner.as_slice().to_vec()));
+                  assert_eq!(spender_subaccount, expected_spender.and_then(|spender| spender.subaccount).map(|subaccount| subaccount.to_vec()));
+              }
+           }
+
           #[test]
           fn test_highest_lowest_block_index(blocks in prop::collection::vec(blocks_strategy::<Tokens>(arb_amount::<Tokens>()),1..100)){
               let storage_client_memory = StorageClient::new_in_memory().unwrap();
@@ -1,4 +1,6 @@
-use crate::common::storage::types::{MetadataEntry, RosettaBlock, RosettaToken, Tokens};
+use crate::common::storage::types::{
+    MetadataEntry, RosettaBlock, RosettaToken, Tokens, TransactionSearchFilter,
+};
 use crate::common::utils::utils::create_progress_bar;
 use anyhow::{anyhow, bail, Context};
 use candid::Principal;
@@ -8,12 +10,72 @@ use ic_ledger_core::tokens::{CheckedAdd, CheckedSub};
 use icrc_ledger_types::icrc1::account::Account;
 use icrc_ledger_types::icrc1::transfer::Memo;
 use num_traits::Bounded;
-use rusqlite::{named_params, params, Params};
+use rusqlite::{named_params, params, params_from_iter, Params};
 use rusqlite::{Connection, Statement, ToSql};
 use serde_bytes::ByteBuf;
 use std::collections::{BTreeMap, HashMap};
 use std::str::FromStr;
 
+// The version of the database schema, stored in the `user_version` pragma of the database.
+// Databases that were created before the schema was versioned have version 0.
+// Version 1: the spender of transfer and burn transactions is stored in the transactions table.
+pub const SCHEMA_VERSION: u32 = 1;
+
+// Migrates the database to the current schema version. Each migration step is applied at most once.
+pub fn migrate_schema(connection: &mut Connection) -> anyhow::Result<()> {
+    let schema_version: u32 =
+        connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if schema_version > SCHEMA_VERSION {
+        bail!(
+            "The database has schema version {} but this version of Rosetta only supports schema versions up to {}",
+            schema_version,
+            SCHEMA_VERSION
+        );
+    }
+
+    let tx = connection.transaction()?;
+    if schema_version < 1 {
+        backfill_spenders(&tx)?;
+    }
+    tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+    tx.commit()?;
+    Ok(())
+}
+
+// Transfer and burn transactions used to be stored without their spender.
+// Re-derives the spender of these transactions from the stored blocks.
+fn backfill_spenders(connection: &Connection) -> anyhow::Result<()> {
+    const BATCH_SIZE: u64 = 100000;
+    let mut select_stmt = connection.prepare(
+        "SELECT b.idx,b.serialized_block FROM blocks b JOIN transactions t ON b.idx = t.block_idx WHERE t.operation_type IN ('transfer', 'burn') AND t.spender_principal IS NULL AND b.idx >= ?1 ORDER BY b.idx LIMIT ?2",
+    )?;
+    let mut update_stmt = connection.prepare(
+        "UPDATE transactions SET spender_principal = ?1, spender_subaccount = ?2 WHERE block_idx = ?3",
+    )?;
+
+    let mut next_block_idx = 0;
+    loop {
+        let rosetta_blocks = read_blocks(&mut select_stmt, params![next_block_idx, BATCH_SIZE])?;
+        let Some(last_block) = rosetta_blocks.last() else {
+            return Ok(());
+        };
+        next_block_idx = last_block.index + 1;
+        for rosetta_block in rosetta_blocks {
+            let spender = match rosetta_block.get_transaction()?.operation {
+                Operation::Transfer { spender, .. } | Operation::Burn { spender, .. } => spender,
+                Operation::Mint { .. } | Operation::Approve { .. } => None,
+            };
+            if let Some(spender) = spender {
+                update_stmt.execute(params![
+                    spender.owner.as_slice().to_vec(),
+                    spender.subaccount,
+                    rosetta_block.index
+                ])?;
+            }
+        }
+    }
+}
+
 pub fn store_metadata(connection: &Connection, metadata: Vec<MetadataEntry>) -> anyhow::Result<()> {
     connection.execute_batch("BEGIN TRANSACTION;")?;
 
@@ -311,30 +373,34 @@ pub fn store_blocks(
             ic_icrc1::Operation::Transfer {
                 from,
                 to,
+                spender,
                 amount,
                 fee,
-                ..
             } => (
                 "transfer",
                 Some(from.owner),
                 from.subaccount,
                 Some(to.owner),
                 to.subaccount,
-                None,
-                None,
+                spender.map(|spender| spender.owner),
+                spender.and_then(|spender| spender.subaccount),
                 amount,
                 None,
                 fee,
                 None,
             ),
-            ic_icrc1::Operation::Burn { from, amount, .. } => (
+            ic_icrc1::Operation::Burn {
+                from,
+                spender,
+                amount,
+            } => (
                 "burn",
                 Some(from.owner),
                 from.subaccount,
                 None,
                 None,
-                None,
-                None,
+                spender.map(|spender| spender.owner),
+                spender.and_then(|spender| spender.subaccount),
                 amount,
                 None,
                 None,
@@ -493,6 +559,84 @@ pub fn get_transactions_by_hash(
     read_transactions(&mut stmt, params![hash.as_slice().to_vec()])
 }
 
+// Returns the blocks whose transactions match the given search filter, ordered from the highest to the lowest block index,
+// together with the total number of matching blocks disregarding the offset and the limit of the filter.
+pub fn search_transactions(
+    connection: &Connection,
+    search_filter: &TransactionSearchFilter,
+) -> anyhow::Result<(Vec<RosettaBlock>, u64)> {
+    let mut conditions: Vec<String> = vec![];
+    let mut condition_params: Vec<Box<dyn ToSql>> = vec![];
+
+    if let Some(transaction_hash) = &search_filter.transaction_hash {
+        conditions.push("t.tx_hash = ?".to_owned());
+        condition_params.push(Box::new(transaction_hash.as_slice().to_vec()));
+    }
+    if let Some(account) = &search_filter.account {
+        // Transactions store the default subaccount either as NULL or as the zero subaccount
+        let mut account_conditions = vec![];
+        for column in ["from", "to", "spender"] {
+            account_conditions.push(format!(
+                "(t.{column}_principal = ? AND COALESCE(t.{column}_subaccount, zeroblob(32)) = ?)"
+            ));
+            condition_params.push(Box::new(account.owner.as_slice().to_vec()));
+            condition_params.push(Box::new(account.effective_subaccount().to_vec()));
+        }
+        conditions.push(format!("({})", account_conditions.join(" OR ")));
+    }
+    if let Some(principal) = &search_filter.principal {
+        conditions.push(
+            "(t.from_principal = ? OR t.to_principal = ? OR t.spender_principal = ?)".to_owned(),
+        );
+        for _ in 0..3 {
+            condition_params.push(Box::new(principal.as_slice().to_vec()));
+        }
+    }
+    if let Some(operation_type) = &search_filter.operation_type {
+        conditions.push("t.operation_type = ?".to_owned());
+        condition_params.push(Box::new(operation_type.clone()));
+    }
+
+    let mut clauses = vec![];
+    let mut params: Vec<Box<dyn ToSql>> = vec![];
+    if let Some(max_block) = search_filter.max_block {
+        clauses.push("t.block_idx <= ?".to_owned());
+        params.push(Box::new(max_block));
+    }
+    if !conditions.is_empty() {
+        let operator = if search_filter.match_any {
+            " OR "
+        } else {
+            " AND "
+        };
+        clauses.push(format!("({})", conditions.join(operator)));
+        params.extend(condition_params);
+    }
+    let where_clause = if clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", clauses.join(" AND "))
+    };
+
+    let total_count: u64 = connection
+        .prepare(&format!(
+            "SELECT COUNT(*) FROM transactions t {}",
+            where_clause
+        ))?
+        .query_row(params_from_iter(params.iter()), |row| row.get(0))
+        .context("Unable to count the transactions matching the search filter")?;
+
+    params.push(Box::new(search_filter.limit));
+    params.push(Box::new(search_filter.offset));
+    let mut stmt = connection.prepare(&format!(
+        "SELECT b.idx,b.serialized_block FROM blocks b JOIN transactions t ON b.idx = t.block_idx {} ORDER BY b.idx DESC LIMIT ? OFFSET ?",
+        where_clause
+    ))?;
+    let blocks = read_blocks(&mut stmt, params_from_iter(params.iter()))?;
+
+    Ok((blocks, total_count))
+}
+
 pub fn get_account_balance_at_highest_block_idx(
     connection: &Connection,
     account: &Account,
@@ -679,7 +823,10 @@ where
                         })?,
                         subaccount: to_subaccount,
                     },
-                    spender: None,
+                    spender: maybe_spender_principal.map(|owner| Account {
+                        owner,
+                        subaccount: spender_subaccount,
+                    }),
                     amount,
                     fee,
                 },
@@ -690,7 +837,10 @@ where
                         })?,
                         subaccount: from_subaccount,
                     },
-                    spender: None,
+                    spender: maybe_spender_principal.map(|owner| Account {
+                        owner,
+                        subaccount: spender_subaccount,
+                    }),
                     amount,
                 },
                 "approve" => Operation::Approve {
@@ -1,5 +1,5 @@
 use anyhow::{Context, Result};
-use candid::Nat;
+use candid::{Nat, Principal};
 use ic_icrc1::blocks::{
     encoded_block_to_generic_block, generic_block_to_encoded_block,
     generic_transaction_from_generic_block,
@@ -130,6 +130,21 @@ impl From<&RosettaBlock> for BlockIdentifier {
     }
 }
 
+// The conditions under which transactions are searched for in the transactions table.
+// If `match_any` is set, a transaction has to satisfy any of the conditions instead of all of them.
+// The `max_block` bound and the pagination parameters are applied independently of `match_any`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionSearchFilter {
+    pub transaction_hash: Option<ByteBuf>,
+    pub account: Option<Account>,
+    pub principal: Option<Principal>,
+    pub operation_type: Option<String>,
+    pub match_any: bool,
+    pub max_block: Option<u64>,
+    pub offset: u64,
+    pub limit: u64,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 pub struct MetadataEntry {
     pub key: String,
@@ -252,7 +252,18 @@ pub fn icrc1_rosetta_block_to_rosetta_core_operation(
     currency: Currency,
 ) -> anyhow::Result<rosetta_core::objects::Operation> {
     let icrc1_transaction = rosetta_block.get_transaction()?;
-    Ok(match icrc1_transaction.operation {
+    Ok(icrc1_operation_to_rosetta_core_operation(
+        icrc1_transaction.operation,
+        currency,
+    ))
+}
+
+// Converts an icrc1 Operation into an Operation from the rosetta_core crate
+pub fn icrc1_operation_to_rosetta_core_operation(
+    operation: ic_icrc1::Operation<RosettaToken>,
+    currency: Currency,
+) -> rosetta_core::objects::Operation {
+    match operation {
         ic_icrc1::Operation::Mint { to, amount } => {
             // A Mint operation only has one OperationIdentifier and thus no related Operations
             rosetta_core::objects::Operation::new(
@@ -337,7 +348,7 @@ pub fn icrc1_rosetta_block_to_rosetta_core_operation(
                 .into(),
             ),
         ),
-    })
+    }
 }
 
 // Takes in a rosetta_core operation that fully defines an icrc1 Operation
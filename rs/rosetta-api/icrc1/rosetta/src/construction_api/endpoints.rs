@@ -1,38 +1,32 @@
 use super::services;
-use crate::{
-    common::{types::Error, utils::utils::verify_network_id},
-    AppState,
-};
+use crate::{common::types::Error, MultiTokenAppState};
 use axum::{extract::State, response::Result, Json};
 use rosetta_core::{request_types::*, response_types::*};
 use std::sync::Arc;
 
 pub async fn construction_derive(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<ConstructionDeriveRequest>,
 ) -> Result<Json<ConstructionDeriveResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&err))?;
+    state.get_token_state(&request.network_identifier)?;
     Ok(Json(services::construction_derive(
         request.public_key.clone(),
     )?))
 }
 
 pub async fn construction_preprocess(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<ConstructionPreprocessRequest>,
 ) -> Result<Json<ConstructionPreprocessResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&err))?;
+    state.get_token_state(&request.network_identifier)?;
     Ok(Json(services::construction_preprocess()))
 }
 
 pub async fn construction_metadata(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<ConstructionMetadataRequest>,
 ) -> Result<Json<ConstructionMetadataResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&err))?;
+    let state = state.get_token_state(&request.network_identifier)?;
     Ok(Json(
         services::construction_metadata(
             request
@@ -46,3 +40,67 @@ pub async fn construction_metadata(
         .await?,
     ))
 }
+
+pub async fn construction_payloads(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<ConstructionPayloadsRequest>,
+) -> Result<Json<ConstructionPayloadsResponse>> {
+    let state = state.get_token_state(&request.network_identifier)?;
+    Ok(Json(services::construction_payloads(
+        request.operations.clone(),
+        request
+            .metadata
+            .clone()
+            .try_into()
+            .map_err(|err: String| Error::parsing_unsuccessful(&err))?,
+        &state.ledger_id,
+        request.public_keys.clone().unwrap_or_default(),
+    )?))
+}
+
+pub async fn construction_combine(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<ConstructionCombineRequest>,
+) -> Result<Json<ConstructionCombineResponse>> {
+    state.get_token_state(&request.network_identifier)?;
+    Ok(Json(services::construction_combine(
+        request.unsigned_transaction.clone(),
+        request.signatures.clone(),
+    )?))
+}
+
+pub async fn construction_parse(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<ConstructionParseRequest>,
+) -> Result<Json<ConstructionParseResponse>> {
+    let state = state.get_token_state(&request.network_identifier)?;
+    Ok(Json(services::construction_parse(
+        request.transaction.clone(),
+        request.signed,
+        state.metadata.clone().into(),
+    )?))
+}
+
+pub async fn construction_hash(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<ConstructionHashRequest>,
+) -> Result<Json<ConstructionHashResponse>> {
+    state.get_token_state(&request.network_identifier)?;
+    Ok(Json(services::construction_hash(
+        request.signed_transaction.clone(),
+    )?))
+}
+
+pub async fn construction_submit(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<ConstructionSubmitRequest>,
+) -> Result<Json<ConstructionSubmitResponse>> {
+    let state = state.get_token_state(&request.network_identifier)?;
+    Ok(Json(
+        services::construction_submit(
+            request.signed_transaction.clone(),
+            state.icrc1_agent.clone(),
+        )
+        .await?,
+    ))
+}
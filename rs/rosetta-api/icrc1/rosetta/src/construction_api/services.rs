@@ -1,14 +1,33 @@
-use super::types::ConstructionMetadataRequestOptions;
-use crate::common::types::Error;
-use ic_base_types::PrincipalId;
+use super::types::{
+    ConstructionMetadataRequestOptions, ConstructionPayloadsRequestMetadata, SignedTransaction,
+    UnsignedTransaction,
+};
+use super::utils::{
+    build_envelope_content, build_envelope_pairs, build_icrc1_ledger_canister_method_args,
+    build_icrc1_transaction_from_envelope_content, build_ingress_expiries, build_signing_payloads,
+    caller_from_envelope_content, signature_type_from_curve_type, submit_signed_transaction,
+};
+use crate::common::{
+    storage::types::RosettaToken,
+    types::{Error, TransactionMetadata},
+    utils::utils::{
+        icrc1_operation_to_rosetta_core_operation, rosetta_core_operation_to_icrc1_operation,
+    },
+};
+use ic_base_types::{CanisterId, PrincipalId};
+use ic_icrc1::Transaction;
+use ic_ledger_canister_core::ledger::LedgerTransaction;
 use icrc_ledger_agent::{CallMode, Icrc1Agent};
-use icrc_ledger_types::icrc1::account::Account;
-use rosetta_core::objects::{Amount, Currency};
+use icrc_ledger_types::icrc1::{account::Account, transfer::Memo};
+use rosetta_core::identifiers::TransactionIdentifier;
+use rosetta_core::objects::{Amount, Currency, ObjectMap, Operation, Signature};
 use rosetta_core::response_types::*;
 use rosetta_core::{
     convert::principal_id_from_public_key, objects::PublicKey,
     response_types::ConstructionDeriveResponse,
 };
+use serde_bytes::ByteBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 
 pub fn construction_derive(public_key: PublicKey) -> Result<ConstructionDeriveResponse, Error> {
@@ -51,9 +70,159 @@ pub async fn construction_metadata(
     })
 }
 
+pub fn construction_payloads(
+    mut operations: Vec<Operation>,
+    metadata: ConstructionPayloadsRequestMetadata,
+    ledger_id: &CanisterId,
+    public_keys: Vec<PublicKey>,
+) -> Result<ConstructionPayloadsResponse, Error> {
+    // An icrc1 ledger call executes exactly one icrc1 operation.
+    if operations.len() != 1 {
+        return Err(Error::processing_construction_failed(&format!(
+            "Expected exactly one operation but got {}",
+            operations.len()
+        )));
+    }
+    let operation = rosetta_core_operation_to_icrc1_operation(operations.remove(0))
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+
+    let created_at_time = metadata
+        .created_at_time
+        .unwrap_or_else(|| ic_types::time::current_time().as_nanos_since_unix_epoch());
+    let (method_name, arg, caller) = build_icrc1_ledger_canister_method_args(
+        operation,
+        metadata.memo.map(Memo::from),
+        created_at_time,
+    )?;
+
+    let public_key = public_keys
+        .into_iter()
+        .find(|public_key| {
+            principal_id_from_public_key(public_key)
+                .map(|principal_id| principal_id.0 == caller)
+                .unwrap_or(false)
+        })
+        .ok_or_else(|| {
+            Error::processing_construction_failed(&format!(
+                "No public key was provided for the caller {}",
+                caller
+            ))
+        })?;
+
+    let ingress_expiries = build_ingress_expiries(metadata.ingress_start, metadata.ingress_end);
+    if ingress_expiries.is_empty() {
+        return Err(Error::processing_construction_failed(
+            &"The ingress interval does not contain any ingress expiry",
+        ));
+    }
+
+    let unsigned_transaction = UnsignedTransaction {
+        envelope_content: build_envelope_content(ledger_id, &method_name, arg, &caller),
+        ingress_expiries,
+    };
+    let account: Account = caller.into();
+    let payloads = build_signing_payloads(
+        &unsigned_transaction,
+        account.into(),
+        signature_type_from_curve_type(&public_key.curve_type)?,
+    );
+
+    Ok(ConstructionPayloadsResponse {
+        unsigned_transaction: unsigned_transaction.to_string(),
+        payloads,
+    })
+}
+
+pub fn construction_combine(
+    unsigned_transaction: String,
+    signatures: Vec<Signature>,
+) -> Result<ConstructionCombineResponse, Error> {
+    let unsigned_transaction = UnsignedTransaction::from_str(&unsigned_transaction)
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let envelope_pairs = build_envelope_pairs(unsigned_transaction, &signatures)
+        .map_err(|err| Error::processing_construction_failed(&err))?;
+    Ok(ConstructionCombineResponse {
+        signed_transaction: SignedTransaction { envelope_pairs }.to_string(),
+    })
+}
+
+pub fn construction_parse(
+    transaction: String,
+    transaction_is_signed: bool,
+    currency: Currency,
+) -> Result<ConstructionParseResponse, Error> {
+    let envelope_content = if transaction_is_signed {
+        SignedTransaction::from_str(&transaction)
+            .and_then(|signed_transaction| signed_transaction.envelope_content().cloned())
+    } else {
+        UnsignedTransaction::from_str(&transaction)
+            .map(|unsigned_transaction| unsigned_transaction.envelope_content)
+    }
+    .map_err(|err| Error::parsing_unsuccessful(&err))?;
+
+    let icrc1_transaction = build_icrc1_transaction_from_envelope_content(&envelope_content)
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let caller = caller_from_envelope_content(&envelope_content)
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let metadata: TransactionMetadata = icrc1_transaction.clone().into();
+
+    Ok(ConstructionParseResponse {
+        operations: vec![icrc1_operation_to_rosetta_core_operation(
+            icrc1_transaction.operation,
+            currency,
+        )],
+        account_identifier_signers: transaction_is_signed.then(|| {
+            let signer: Account = caller.into();
+            vec![signer.into()]
+        }),
+        metadata: (!metadata.is_empty()).then(|| metadata.into()),
+    })
+}
+
+pub fn construction_hash(signed_transaction: String) -> Result<ConstructionHashResponse, Error> {
+    let signed_transaction = SignedTransaction::from_str(&signed_transaction)
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let icrc1_transaction = signed_transaction
+        .envelope_content()
+        .map_err(anyhow::Error::msg)
+        .and_then(build_icrc1_transaction_from_envelope_content)
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    Ok(ConstructionHashResponse {
+        transaction_identifier: transaction_identifier(&icrc1_transaction),
+        metadata: ObjectMap::new(),
+    })
+}
+
+pub async fn construction_submit(
+    signed_transaction: String,
+    icrc1_agent: Arc<Icrc1Agent>,
+) -> Result<ConstructionSubmitResponse, Error> {
+    let signed_transaction = SignedTransaction::from_str(&signed_transaction)
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let icrc1_transaction = signed_transaction
+        .envelope_content()
+        .map_err(anyhow::Error::msg)
+        .and_then(build_icrc1_transaction_from_envelope_content)
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+
+    submit_signed_transaction(&icrc1_agent, &signed_transaction).await?;
+
+    Ok(ConstructionSubmitResponse {
+        transaction_identifier: transaction_identifier(&icrc1_transaction),
+        metadata: None,
+    })
+}
+
+// The hash of the icrc1 transaction is the same hash that identifies the transaction
+// in the synchronized blocks once the ledger has executed it.
+fn transaction_identifier(transaction: &Transaction<RosettaToken>) -> TransactionIdentifier {
+    TransactionIdentifier::from_bytes(&ByteBuf::from(transaction.hash().as_slice().to_vec()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use candid::Principal;
     use ic_canister_client_sender::{Ed25519KeyPair, Secp256k1KeyPair};
     use proptest::prelude::any;
     use proptest::proptest;
@@ -78,7 +247,159 @@ mod tests {
         );
     }
 
+    fn tokens(amount: u64) -> RosettaToken {
+        RosettaToken::from_str(&amount.to_string()).unwrap()
+    }
+
+    // Returns one operation for every ledger method supported by the construction api,
+    // each of which has to be sent by the given caller.
+    fn supported_operations(caller: Principal) -> Vec<ic_icrc1::Operation<RosettaToken>> {
+        let other = PrincipalId::new_user_test_id(1).0;
+        vec![
+            ic_icrc1::Operation::Transfer {
+                from: Account {
+                    owner: caller,
+                    subaccount: Some([1; 32]),
+                },
+                to: other.into(),
+                spender: None,
+                amount: tokens(100),
+                fee: Some(tokens(10)),
+            },
+            ic_icrc1::Operation::Transfer {
+                from: other.into(),
+                to: other.into(),
+                spender: Some(caller.into()),
+                amount: tokens(100),
+                fee: None,
+            },
+            ic_icrc1::Operation::Approve {
+                from: caller.into(),
+                spender: other.into(),
+                amount: tokens(100),
+                expected_allowance: Some(tokens(50)),
+                expires_at: Some(u64::MAX),
+                fee: None,
+            },
+        ]
+    }
+
+    fn call_construction_payloads_combine_parse_hash<T: RosettaSupportedKeyPair>(key_pair: &T) {
+        let currency = Currency::default();
+        let public_key = ic_rosetta_test_utils::to_public_key(key_pair);
+        let caller = key_pair.generate_principal_id().unwrap().0;
+        let ledger_id = CanisterId::from_u64(1);
+        let memo = vec![1, 2, 3];
+        let created_at_time = 1_000_000;
+
+        for operation in supported_operations(caller) {
+            let rosetta_operation =
+                icrc1_operation_to_rosetta_core_operation(operation.clone(), currency.clone());
+            let payloads = construction_payloads(
+                vec![rosetta_operation.clone()],
+                ConstructionPayloadsRequestMetadata {
+                    memo: Some(memo.clone()),
+                    created_at_time: Some(created_at_time),
+                    ingress_start: None,
+                    ingress_end: None,
+                },
+                &ledger_id,
+                vec![public_key.clone()],
+            )
+            .unwrap();
+
+            // Every ingress expiry needs a signature for the update and the read-state call.
+            let unsigned_transaction =
+                UnsignedTransaction::from_str(&payloads.unsigned_transaction).unwrap();
+            assert!(!unsigned_transaction.ingress_expiries.is_empty());
+            assert_eq!(
+                payloads.payloads.len(),
+                2 * unsigned_transaction.ingress_expiries.len()
+            );
+
+            let parsed = construction_parse(
+                payloads.unsigned_transaction.clone(),
+                false,
+                currency.clone(),
+            )
+            .unwrap();
+            assert_eq!(parsed.operations, vec![rosetta_operation.clone()]);
+            assert_eq!(parsed.account_identifier_signers, None);
+
+            let signatures = payloads
+                .payloads
+                .into_iter()
+                .map(|payload| Signature {
+                    signature_type: payload.signature_type.clone().unwrap(),
+                    hex_bytes: hex::encode(
+                        key_pair.sign(&hex::decode(&payload.hex_bytes).unwrap()),
+                    ),
+                    public_key: public_key.clone(),
+                    signing_payload: payload,
+                })
+                .collect();
+            let signed_transaction =
+                construction_combine(payloads.unsigned_transaction, signatures)
+                    .unwrap()
+                    .signed_transaction;
+
+            let parsed =
+                construction_parse(signed_transaction.clone(), true, currency.clone()).unwrap();
+            let signer: Account = caller.into();
+            assert_eq!(parsed.operations, vec![rosetta_operation]);
+            assert_eq!(parsed.account_identifier_signers, Some(vec![signer.into()]));
+
+            let expected_transaction = Transaction {
+                operation,
+                created_at_time: Some(created_at_time),
+                memo: Some(Memo::from(memo.clone())),
+            };
+            assert_eq!(
+                construction_hash(signed_transaction)
+                    .unwrap()
+                    .transaction_identifier,
+                transaction_identifier(&expected_transaction)
+            );
+        }
+    }
+
+    #[test]
+    fn test_construction_payloads_rejects_unsupported_operations() {
+        let key_pair = Ed25519KeyPair::generate_from_u64(0);
+        let caller = key_pair.generate_principal_id().unwrap().0;
+        let mint = ic_icrc1::Operation::Mint {
+            to: caller.into(),
+            amount: tokens(100),
+        };
+        assert_eq!(
+            construction_payloads(
+                vec![icrc1_operation_to_rosetta_core_operation(
+                    mint,
+                    Currency::default()
+                )],
+                ConstructionPayloadsRequestMetadata::default(),
+                &CanisterId::from_u64(1),
+                vec![ic_rosetta_test_utils::to_public_key(&key_pair)],
+            ),
+            Err(Error::unsupported_operation(
+                crate::common::types::OperationType::Mint
+            ))
+        );
+    }
+
     proptest! {
+        #[test]
+        fn test_construction_payloads_combine_parse_hash_ed(seed in any::<u64>()) {
+            let key_pair = Ed25519KeyPair::generate_from_u64(seed);
+            call_construction_payloads_combine_parse_hash(&key_pair);
+        }
+
+        #[test]
+        fn test_construction_payloads_combine_parse_hash_secp(seed in any::<u64>()) {
+            let key_pair = Secp256k1KeyPair::generate_from_u64(seed);
+            call_construction_payloads_combine_parse_hash(&key_pair);
+        }
+
         #[test]
         fn test_construction_derive_ed(seed in any::<u64>()) {
             let key_pair = Ed25519KeyPair::generate_from_u64(seed);
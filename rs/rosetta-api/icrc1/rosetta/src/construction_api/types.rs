@@ -1,6 +1,10 @@
+use ic_rosetta_api::models::EnvelopePair;
+use ic_types::messages::HttpCanisterUpdate;
 use rosetta_core::objects::*;
 use serde::Deserialize;
 use serde::Serialize;
+use std::str::FromStr;
+use strum_macros::{Display, EnumString};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct ConstructionMetadataRequestOptions {
@@ -23,3 +27,119 @@ impl TryFrom<Option<ObjectMap>> for ConstructionMetadataRequestOptions {
             .map_err(|e| format!("Could not parse MetadataOptions from JSON object: {}", e))
     }
 }
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct ConstructionPayloadsRequestMetadata {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<Vec<u8>>,
+
+    /// The creation time of the transaction in nanoseconds since the UNIX epoch.
+    /// Defaults to the time at which `/construction/payloads` is called.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created_at_time: Option<u64>,
+
+    /// The earliest time in nanoseconds since the UNIX epoch at which the
+    /// transaction can be submitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingress_start: Option<u64>,
+
+    /// The latest time in nanoseconds since the UNIX epoch at which the
+    /// transaction can be submitted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ingress_end: Option<u64>,
+}
+
+impl From<ConstructionPayloadsRequestMetadata> for ObjectMap {
+    fn from(m: ConstructionPayloadsRequestMetadata) -> Self {
+        match serde_json::to_value(m) {
+            Ok(serde_json::Value::Object(o)) => o,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl TryFrom<Option<ObjectMap>> for ConstructionPayloadsRequestMetadata {
+    type Error = String;
+    fn try_from(o: Option<ObjectMap>) -> Result<Self, Self::Error> {
+        serde_json::from_value(serde_json::Value::Object(o.unwrap_or_default())).map_err(|e| {
+            format!(
+                "Could not parse ConstructionPayloadsRequestMetadata from JSON object: {}",
+                e
+            )
+        })
+    }
+}
+
+/// The ledger methods that can be called through the Construction API.
+#[derive(Debug, Clone, PartialEq, Eq, Display, EnumString)]
+pub enum CanisterMethodName {
+    #[strum(serialize = "icrc1_transfer")]
+    Icrc1Transfer,
+    #[strum(serialize = "icrc2_approve")]
+    Icrc2Approve,
+    #[strum(serialize = "icrc2_transfer_from")]
+    Icrc2TransferFrom,
+}
+
+/// An unsigned ledger call together with the ingress expiries for which
+/// signatures are requested. Every ingress expiry results in one signing
+/// payload for the update call and one for the corresponding read-state call.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct UnsignedTransaction {
+    pub envelope_content: HttpCanisterUpdate,
+    pub ingress_expiries: Vec<u64>,
+}
+
+impl std::fmt::Display for UnsignedTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(serde_cbor::to_vec(self).unwrap()))
+    }
+}
+
+impl FromStr for UnsignedTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_cbor::from_slice(
+            hex::decode(s)
+                .map_err(|err| format!("{:?}", err))?
+                .as_slice(),
+        )
+        .map_err(|err| format!("{:?}", err))
+    }
+}
+
+/// The signed update and read-state envelopes of a ledger call, one pair for
+/// every ingress expiry of the corresponding [`UnsignedTransaction`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SignedTransaction {
+    pub envelope_pairs: Vec<EnvelopePair>,
+}
+
+impl SignedTransaction {
+    /// Returns the content of the update call. All envelope pairs share the
+    /// same content apart from the ingress expiry.
+    pub fn envelope_content(&self) -> Result<&HttpCanisterUpdate, String> {
+        self.envelope_pairs
+            .first()
+            .map(|pair| pair.update_content())
+            .ok_or_else(|| "Signed transaction does not contain any envelopes".to_owned())
+    }
+}
+
+impl std::fmt::Display for SignedTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", hex::encode(serde_cbor::to_vec(self).unwrap()))
+    }
+}
+
+impl FromStr for SignedTransaction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_cbor::from_slice(
+            hex::decode(s)
+                .map_err(|err| format!("{:?}", err))?
+                .as_slice(),
+        )
+        .map_err(|err| format!("{:?}", err))
+    }
+}
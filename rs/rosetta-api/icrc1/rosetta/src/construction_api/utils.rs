@@ -0,0 +1,536 @@
+use super::types::{CanisterMethodName, SignedTransaction, UnsignedTransaction};
+use crate::common::{
+    storage::types::RosettaToken,
+    types::{Error, OperationType},
+};
+use anyhow::{bail, Context};
+use candid::{Decode, Encode, Nat, Principal};
+use ic_agent::agent::{Replied, RequestStatusResponse};
+use ic_base_types::CanisterId;
+use ic_canister_client_sender::{Ed25519KeyPair as EdKeypair, Secp256k1KeyPair};
+use ic_icrc1::{Operation, Transaction};
+use ic_rosetta_api::{
+    convert::make_read_state_from_update, models::EnvelopePair, request_handler::make_sig_data,
+};
+use ic_types::messages::{
+    Blob, HttpCallContent, HttpCanisterUpdate, HttpReadStateContent, HttpRequestEnvelope, MessageId,
+};
+use icrc_ledger_agent::Icrc1Agent;
+use icrc_ledger_types::{
+    icrc1::{
+        account::Account,
+        transfer::{Memo, TransferArg, TransferError},
+    },
+    icrc2::{
+        approve::{ApproveArgs, ApproveError},
+        transfer_from::{TransferFromArgs, TransferFromError},
+    },
+};
+use rosetta_core::{
+    identifiers::AccountIdentifier,
+    models::RosettaSupportedKeyPair,
+    objects::{CurveType, Signature, SignatureType, SigningPayload},
+};
+use std::{collections::HashMap, time::Duration};
+
+const MIN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const MAX_POLL_INTERVAL: Duration = Duration::from_secs(10);
+const POLL_INTERVAL_MULTIPLIER: f32 = 1.3;
+const SUBMIT_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Builds the candid encoded argument of the ledger method that executes the
+/// given operation. Returns the method name, the argument and the principal
+/// that has to send the call.
+pub fn build_icrc1_ledger_canister_method_args(
+    operation: Operation<RosettaToken>,
+    memo: Option<Memo>,
+    created_at_time: u64,
+) -> Result<(CanisterMethodName, Vec<u8>, Principal), Error> {
+    let created_at_time = Some(created_at_time);
+    let (method_name, arg, caller) = match operation {
+        Operation::Transfer {
+            from,
+            to,
+            spender: None,
+            amount,
+            fee,
+        } => (
+            CanisterMethodName::Icrc1Transfer,
+            Encode!(&TransferArg {
+                from_subaccount: from.subaccount,
+                to,
+                fee: fee.map(Nat::from),
+                created_at_time,
+                memo,
+                amount: amount.into(),
+            }),
+            from.owner,
+        ),
+        Operation::Transfer {
+            from,
+            to,
+            spender: Some(spender),
+            amount,
+            fee,
+        } => (
+            CanisterMethodName::Icrc2TransferFrom,
+            Encode!(&TransferFromArgs {
+                spender_subaccount: spender.subaccount,
+                from,
+                to,
+                amount: amount.into(),
+                fee: fee.map(Nat::from),
+                memo,
+                created_at_time,
+            }),
+            spender.owner,
+        ),
+        Operation::Approve {
+            from,
+            spender,
+            amount,
+            expected_allowance,
+            expires_at,
+            fee,
+        } => (
+            CanisterMethodName::Icrc2Approve,
+            Encode!(&ApproveArgs {
+                from_subaccount: from.subaccount,
+                spender,
+                amount: amount.into(),
+                expected_allowance: expected_allowance.map(Nat::from),
+                expires_at,
+                fee: fee.map(Nat::from),
+                memo,
+                created_at_time,
+            }),
+            from.owner,
+        ),
+        Operation::Mint { .. } => return Err(Error::unsupported_operation(OperationType::Mint)),
+        Operation::Burn { .. } => return Err(Error::unsupported_operation(OperationType::Burn)),
+    };
+    Ok((
+        method_name,
+        arg.map_err(|err| Error::parsing_unsuccessful(&err))?,
+        caller,
+    ))
+}
+
+/// Reconstructs the icrc1 transaction that the ledger creates when `caller`
+/// calls `method_name` with the candid encoded argument `arg`.
+pub fn build_icrc1_transaction_from_canister_method_args(
+    method_name: &CanisterMethodName,
+    caller: &Principal,
+    arg: &[u8],
+) -> anyhow::Result<Transaction<RosettaToken>> {
+    let nat_to_tokens = |n: Nat| RosettaToken::try_from(n).map_err(anyhow::Error::msg);
+    Ok(match method_name {
+        CanisterMethodName::Icrc1Transfer => {
+            let TransferArg {
+                from_subaccount,
+                to,
+                fee,
+                created_at_time,
+                memo,
+                amount,
+            } = Decode!(arg, TransferArg)?;
+            Transaction {
+                operation: Operation::Transfer {
+                    from: Account {
+                        owner: *caller,
+                        subaccount: from_subaccount,
+                    },
+                    to,
+                    spender: None,
+                    amount: nat_to_tokens(amount)?,
+                    fee: fee.map(nat_to_tokens).transpose()?,
+                },
+                created_at_time,
+                memo,
+            }
+        }
+        CanisterMethodName::Icrc2TransferFrom => {
+            let TransferFromArgs {
+                spender_subaccount,
+                from,
+                to,
+                amount,
+                fee,
+                memo,
+                created_at_time,
+            } = Decode!(arg, TransferFromArgs)?;
+            Transaction {
+                operation: Operation::Transfer {
+                    from,
+                    to,
+                    spender: Some(Account {
+                        owner: *caller,
+                        subaccount: spender_subaccount,
+                    }),
+                    amount: nat_to_tokens(amount)?,
+                    fee: fee.map(nat_to_tokens).transpose()?,
+                },
+                created_at_time,
+                memo,
+            }
+        }
+        CanisterMethodName::Icrc2Approve => {
+            let ApproveArgs {
+                from_subaccount,
+                spender,
+                amount,
+                expected_allowance,
+                expires_at,
+                fee,
+                memo,
+                created_at_time,
+            } = Decode!(arg, ApproveArgs)?;
+            Transaction {
+                operation: Operation::Approve {
+                    from: Account {
+                        owner: *caller,
+                        subaccount: from_subaccount,
+                    },
+                    spender,
+                    amount: nat_to_tokens(amount)?,
+                    expected_allowance: expected_allowance.map(nat_to_tokens).transpose()?,
+                    expires_at,
+                    fee: fee.map(nat_to_tokens).transpose()?,
+                },
+                created_at_time,
+                memo,
+            }
+        }
+    })
+}
+
+/// Reconstructs the icrc1 transaction from the content of an update call to the ledger.
+pub fn build_icrc1_transaction_from_envelope_content(
+    envelope_content: &HttpCanisterUpdate,
+) -> anyhow::Result<Transaction<RosettaToken>> {
+    let method_name = envelope_content
+        .method_name
+        .parse::<CanisterMethodName>()
+        .with_context(|| format!("Unsupported method name {}", envelope_content.method_name))?;
+    let caller = caller_from_envelope_content(envelope_content)?;
+    build_icrc1_transaction_from_canister_method_args(
+        &method_name,
+        &caller,
+        envelope_content.arg.0.as_slice(),
+    )
+}
+
+pub fn caller_from_envelope_content(
+    envelope_content: &HttpCanisterUpdate,
+) -> anyhow::Result<Principal> {
+    Principal::try_from_slice(envelope_content.sender.0.as_slice())
+        .context("Could not parse the sender of the envelope content")
+}
+
+/// Creates the content of an update call to the ledger. The ingress expiry is
+/// set once the signing payloads for the different ingress windows are created.
+pub fn build_envelope_content(
+    ledger_id: &CanisterId,
+    method_name: &CanisterMethodName,
+    arg: Vec<u8>,
+    caller: &Principal,
+) -> HttpCanisterUpdate {
+    HttpCanisterUpdate {
+        canister_id: Blob(ledger_id.get().to_vec()),
+        method_name: method_name.to_string(),
+        arg: Blob(arg),
+        // The ledger deduplicates transactions with the same content and created_at_time,
+        // so a nonce would only allow the same transaction to land on chain twice.
+        nonce: None,
+        sender: Blob(caller.as_slice().to_vec()),
+        ingress_expiry: 0,
+    }
+}
+
+/// Returns the ingress expiries of all ingress windows between `ingress_start`
+/// and `ingress_end`, both given in nanoseconds since the UNIX epoch.
+pub fn build_ingress_expiries(ingress_start: Option<u64>, ingress_end: Option<u64>) -> Vec<u64> {
+    let interval =
+        ic_constants::MAX_INGRESS_TTL - ic_constants::PERMITTED_DRIFT - Duration::from_secs(120);
+
+    let ingress_start = ingress_start
+        .map(ic_types::time::Time::from_nanos_since_unix_epoch)
+        .unwrap_or_else(ic_types::time::current_time);
+
+    let ingress_end = ingress_end
+        .map(ic_types::time::Time::from_nanos_since_unix_epoch)
+        .unwrap_or_else(|| ingress_start + interval);
+
+    let mut ingress_expiries = vec![];
+    let mut now = ingress_start;
+    while now < ingress_end {
+        let ingress_expiry = (now
+            + ic_constants::MAX_INGRESS_TTL.saturating_sub(ic_constants::PERMITTED_DRIFT))
+        .as_nanos_since_unix_epoch();
+        ingress_expiries.push(ingress_expiry);
+        now += interval;
+    }
+    ingress_expiries
+}
+
+pub fn signature_type_from_curve_type(curve_type: &CurveType) -> Result<SignatureType, Error> {
+    match curve_type {
+        CurveType::Edwards25519 => Ok(SignatureType::Ed25519),
+        CurveType::Secp256K1 => Ok(SignatureType::Ecdsa),
+        curve_type => Err(Error::parsing_unsuccessful(&format!(
+            "Curve type {:?} is not supported",
+            curve_type
+        ))),
+    }
+}
+
+/// Creates the signing payloads of the update call and of the read-state call
+/// for every ingress expiry of the unsigned transaction.
+pub fn build_signing_payloads(
+    unsigned_transaction: &UnsignedTransaction,
+    account_identifier: AccountIdentifier,
+    signature_type: SignatureType,
+) -> Vec<SigningPayload> {
+    let mut payloads = vec![];
+    for ingress_expiry in &unsigned_transaction.ingress_expiries {
+        let mut update = unsigned_transaction.envelope_content.clone();
+        update.ingress_expiry = *ingress_expiry;
+        let read_state = make_read_state_from_update(&update);
+        let read_state_message_id = MessageId::from(read_state.representation_independent_hash());
+        for message_id in [update.id(), read_state_message_id] {
+            payloads.push(SigningPayload {
+                address: None,
+                account_identifier: Some(account_identifier.clone()),
+                hex_bytes: hex::encode(make_sig_data(&message_id)),
+                signature_type: Some(signature_type.clone()),
+            });
+        }
+    }
+    payloads
+}
+
+fn build_sender_pubkey(signature: &Signature) -> anyhow::Result<Blob> {
+    let public_key = match signature.signature_type {
+        SignatureType::Ed25519 => {
+            EdKeypair::der_encode_pk(EdKeypair::hex_decode_pk(&signature.public_key.hex_bytes)?)?
+        }
+        SignatureType::Ecdsa => Secp256k1KeyPair::der_encode_pk(Secp256k1KeyPair::hex_decode_pk(
+            &signature.public_key.hex_bytes,
+        )?)?,
+        ref signature_type => bail!("Signature type {} is not supported", signature_type),
+    };
+    Ok(Blob(public_key))
+}
+
+/// Assembles the signed update and read-state envelopes of every ingress
+/// window from the unsigned transaction and the signatures of its payloads.
+pub fn build_envelope_pairs(
+    unsigned_transaction: UnsignedTransaction,
+    signatures: &[Signature],
+) -> anyhow::Result<Vec<EnvelopePair>> {
+    let signatures_by_sig_data = signatures
+        .iter()
+        .map(|signature| {
+            Ok((
+                hex::decode(&signature.signing_payload.hex_bytes)?,
+                signature,
+            ))
+        })
+        .collect::<anyhow::Result<HashMap<Vec<u8>, &Signature>>>()?;
+
+    let mut envelope_pairs = vec![];
+    for ingress_expiry in unsigned_transaction.ingress_expiries {
+        let mut update = unsigned_transaction.envelope_content.clone();
+        update.ingress_expiry = ingress_expiry;
+        let read_state = make_read_state_from_update(&update);
+
+        let update_signature = signatures_by_sig_data
+            .get(&make_sig_data(&update.id()))
+            .context("Could not find signature for the update call")?;
+        let read_state_signature = signatures_by_sig_data
+            .get(&make_sig_data(&MessageId::from(
+                read_state.representation_independent_hash(),
+            )))
+            .context("Could not find signature for the read-state call")?;
+
+        envelope_pairs.push(EnvelopePair {
+            update: HttpRequestEnvelope::<HttpCallContent> {
+                content: HttpCallContent::Call { update },
+                sender_pubkey: Some(build_sender_pubkey(update_signature)?),
+                sender_sig: Some(Blob(hex::decode(&update_signature.hex_bytes)?)),
+                sender_delegation: None,
+            },
+            read_state: HttpRequestEnvelope::<HttpReadStateContent> {
+                content: HttpReadStateContent::ReadState { read_state },
+                sender_pubkey: Some(build_sender_pubkey(read_state_signature)?),
+                sender_sig: Some(Blob(hex::decode(&read_state_signature.hex_bytes)?)),
+                sender_delegation: None,
+            },
+        });
+    }
+    Ok(envelope_pairs)
+}
+
+/// Submits the envelope pair that is valid at the current time and polls the
+/// status of the request until the ledger has replied.
+/// Returns the block index of the transaction created by the ledger.
+pub async fn submit_signed_transaction(
+    icrc1_agent: &Icrc1Agent,
+    signed_transaction: &SignedTransaction,
+) -> Result<Nat, Error> {
+    let now = ic_types::time::current_time();
+    let EnvelopePair { update, read_state } = signed_transaction
+        .envelope_pairs
+        .iter()
+        .find(|envelope_pair| {
+            let ingress_expiry = ic_types::time::Time::from_nanos_since_unix_epoch(
+                envelope_pair.update_content().ingress_expiry,
+            );
+            let ingress_start = ingress_expiry.saturating_sub(
+                ic_constants::MAX_INGRESS_TTL.saturating_sub(ic_constants::PERMITTED_DRIFT),
+            );
+            ingress_start <= now && ingress_expiry > now
+        })
+        .ok_or_else(|| {
+            Error::processing_construction_failed(
+                &"None of the envelopes of the signed transaction is valid at the current time",
+            )
+        })?;
+
+    let method_name = match &update.content {
+        HttpCallContent::Call { update } => update
+            .method_name
+            .parse::<CanisterMethodName>()
+            .map_err(|err| Error::parsing_unsuccessful(&err))?,
+    };
+
+    let update_bytes =
+        serde_cbor::to_vec(update).map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let read_state_bytes =
+        serde_cbor::to_vec(read_state).map_err(|err| Error::parsing_unsuccessful(&err))?;
+
+    let request_id = icrc1_agent
+        .agent
+        .update_signed(icrc1_agent.ledger_canister_id, update_bytes)
+        .await
+        .map_err(|err| Error::ledger_communication_unsuccessful(&err))?;
+
+    let deadline = std::time::Instant::now() + SUBMIT_TIMEOUT;
+    let mut poll_interval = MIN_POLL_INTERVAL;
+    let reply = loop {
+        match icrc1_agent
+            .agent
+            .request_status_signed(
+                &request_id,
+                icrc1_agent.ledger_canister_id,
+                read_state_bytes.clone(),
+            )
+            .await
+            .map_err(|err| Error::ledger_communication_unsuccessful(&err))?
+        {
+            RequestStatusResponse::Replied(Replied::CallReplied(reply)) => break reply,
+            RequestStatusResponse::Rejected(reject) => {
+                return Err(Error::processing_construction_failed(&reject))
+            }
+            RequestStatusResponse::Done => {
+                return Err(Error::processing_construction_failed(
+                    &"The reply of the ledger is no longer available",
+                ))
+            }
+            RequestStatusResponse::Unknown
+            | RequestStatusResponse::Received
+            | RequestStatusResponse::Processing => {}
+        }
+        if std::time::Instant::now() + poll_interval > deadline {
+            return Err(Error::processing_construction_failed(
+                &"The ledger did not reply to the submitted transaction in time",
+            ));
+        }
+        tokio::time::sleep(poll_interval).await;
+        poll_interval = poll_interval
+            .mul_f32(POLL_INTERVAL_MULTIPLIER)
+            .min(MAX_POLL_INTERVAL);
+    };
+
+    decode_ledger_reply(method_name, &reply)
+}
+
+/// Decodes the reply of the ledger to a submitted transaction into the index of
+/// the block that contains the transaction.
+/// A transaction that was already submitted, e.g. by a retry of the client, is
+/// rejected by the ledger as a duplicate. This is reported as success with the
+/// index of the block that contains the original transaction.
+fn decode_ledger_reply(method_name: CanisterMethodName, reply: &[u8]) -> Result<Nat, Error> {
+    let decode_error = |err: candid::Error| Error::parsing_unsuccessful(&err);
+    match method_name {
+        CanisterMethodName::Icrc1Transfer => {
+            match Decode!(reply, Result<Nat, TransferError>).map_err(decode_error)? {
+                Ok(block_index) => Ok(block_index),
+                Err(TransferError::Duplicate { duplicate_of }) => Ok(duplicate_of),
+                Err(err) => Err(Error::processing_construction_failed(&err)),
+            }
+        }
+        CanisterMethodName::Icrc2Approve => {
+            match Decode!(reply, Result<Nat, ApproveError>).map_err(decode_error)? {
+                Ok(block_index) => Ok(block_index),
+                Err(ApproveError::Duplicate { duplicate_of }) => Ok(duplicate_of),
+                Err(err) => Err(Error::processing_construction_failed(&err)),
+            }
+        }
+        CanisterMethodName::Icrc2TransferFrom => {
+            match Decode!(reply, Result<Nat, TransferFromError>).map_err(decode_error)? {
+                Ok(block_index) => Ok(block_index),
+                Err(TransferFromError::Duplicate { duplicate_of }) => Ok(duplicate_of),
+                Err(err) => Err(Error::processing_construction_failed(&err)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ledger_reply_reports_duplicates_as_success() {
+        let reply = Encode!(&Result::<Nat, TransferError>::Ok(Nat::from(5_u64))).unwrap();
+        assert_eq!(
+            decode_ledger_reply(CanisterMethodName::Icrc1Transfer, &reply),
+            Ok(Nat::from(5_u64))
+        );
+
+        let reply = Encode!(&Result::<Nat, TransferError>::Err(
+            TransferError::Duplicate {
+                duplicate_of: Nat::from(3_u64)
+            }
+        ))
+        .unwrap();
+        assert_eq!(
+            decode_ledger_reply(CanisterMethodName::Icrc1Transfer, &reply),
+            Ok(Nat::from(3_u64))
+        );
+
+        let reply = Encode!(&Result::<Nat, ApproveError>::Err(ApproveError::Duplicate {
+            duplicate_of: Nat::from(4_u64)
+        }))
+        .unwrap();
+        assert_eq!(
+            decode_ledger_reply(CanisterMethodName::Icrc2Approve, &reply),
+            Ok(Nat::from(4_u64))
+        );
+
+        let reply = Encode!(&Result::<Nat, TransferFromError>::Err(
+            TransferFromError::Duplicate {
+                duplicate_of: Nat::from(6_u64)
+            }
+        ))
+        .unwrap();
+        assert_eq!(
+            decode_ledger_reply(CanisterMethodName::Icrc2TransferFrom, &reply),
+            Ok(Nat::from(6_u64))
+        );
+
+        let reply = Encode!(&Result::<Nat, TransferError>::Err(TransferError::TooOld)).unwrap();
+        assert!(decode_ledger_reply(CanisterMethodName::Icrc1Transfer, &reply).is_err());
+    }
+}
@@ -1,8 +1,5 @@
 use super::services;
-use crate::{
-    common::{types::Error, utils::utils::verify_network_id},
-    AppState,
-};
+use crate::{common::types::Error, MultiTokenAppState};
 use axum::{extract::State, http::StatusCode, response::Result, Json};
 use ic_rosetta_api::models::MempoolResponse;
 use rosetta_core::{request_types::*, response_types::*};
@@ -13,40 +10,41 @@ pub async fn health() -> (StatusCode, Json<()>) {
 }
 
 pub async fn network_list(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     _request: Json<MetadataRequest>,
 ) -> Json<NetworkListResponse> {
     Json(services::network_list(
-        &state.icrc1_agent.ledger_canister_id,
+        &state
+            .ledger_ids()
+            .into_iter()
+            .map(|ledger_id| ledger_id.into())
+            .collect::<Vec<_>>(),
     ))
 }
 
 pub async fn network_options(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<NetworkRequest>,
 ) -> Result<Json<NetworkOptionsResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    let state = state.get_token_state(&request.network_identifier)?;
     Ok(Json(services::network_options(
         &state.icrc1_agent.ledger_canister_id,
     )))
 }
 
 pub async fn network_status(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<NetworkRequest>,
 ) -> Result<Json<NetworkStatusResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    let state = state.get_token_state(&request.network_identifier)?;
     Ok(Json(services::network_status(state.storage.clone())?))
 }
 
 pub async fn block(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<BlockRequest>,
 ) -> Result<Json<BlockResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    let state = state.get_token_state(&request.network_identifier)?;
     Ok(Json(services::block(
         state.storage.clone(),
         request.block_identifier.clone(),
@@ -55,11 +53,10 @@ pub async fn block(
 }
 
 pub async fn block_transaction(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<BlockTransactionRequest>,
 ) -> Result<Json<BlockTransactionResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    let state = state.get_token_state(&request.network_identifier)?;
     Ok(Json(services::block_transaction(
         state.storage.clone(),
         request.block_identifier.clone(),
@@ -69,19 +66,55 @@ pub async fn block_transaction(
 }
 
 pub async fn mempool(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<NetworkRequest>,
 ) -> Result<Json<MempoolResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    state.get_token_state(&request.network_identifier)?;
     Ok(Json(MempoolResponse::new(vec![])))
 }
 
 pub async fn mempool_transaction(
-    State(state): State<Arc<AppState>>,
+    State(state): State<Arc<MultiTokenAppState>>,
     request: Json<MempoolTransactionRequest>,
 ) -> Result<Json<MempoolTransactionResponse>> {
-    verify_network_id(&request.network_identifier, &state)
-        .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+    state.get_token_state(&request.network_identifier)?;
     Err(Error::mempool_transaction_missing().into())
 }
+
+pub async fn account_balance(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<AccountBalanceRequest>,
+) -> Result<Json<AccountBalanceResponse>> {
+    let state = state.get_token_state(&request.network_identifier)?;
+    Ok(Json(services::account_balance(
+        state.storage.clone(),
+        request.account_identifier.clone(),
+        request.block_identifier.clone(),
+        request.currencies.clone(),
+        state.metadata.clone(),
+    )?))
+}
+
+pub async fn account_coins(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<AccountCoinsRequest>,
+) -> Result<Json<AccountCoinsResponse>> {
+    let state = state.get_token_state(&request.network_identifier)?;
+    Ok(Json(services::account_coins(
+        state.storage.clone(),
+        request.account_identifier.clone(),
+        request.include_mempool,
+    )?))
+}
+
+pub async fn search_transactions(
+    State(state): State<Arc<MultiTokenAppState>>,
+    request: Json<SearchTransactionsRequest>,
+) -> Result<Json<SearchTransactionsResponse>> {
+    let state = state.get_token_state(&request.network_identifier)?;
+    Ok(Json(services::search_transactions(
+        state.storage.clone(),
+        request.0,
+        state.metadata.clone(),
+    )?))
+}
@@ -1,8 +1,13 @@
 use crate::{
     common::{
-        constants::{NODE_VERSION, ROSETTA_VERSION},
-        storage::storage_client::StorageClient,
-        types::Error,
+        constants::{
+            MAX_TRANSACTIONS_PER_SEARCH_TRANSACTIONS_REQUEST, NODE_VERSION, ROSETTA_VERSION,
+        },
+        storage::{
+            storage_client::StorageClient,
+            types::{RosettaBlock, Tokens, TransactionSearchFilter},
+        },
+        types::{Error, OperationType},
         utils::utils::{
             convert_timestamp_to_millis, get_rosetta_block_from_block_identifier,
             get_rosetta_block_from_partial_block_identifier,
@@ -13,16 +18,23 @@ use crate::{
     Metadata,
 };
 use candid::Principal;
+use ic_ledger_core::tokens::Zero;
 use ic_rosetta_api::DEFAULT_BLOCKCHAIN;
-use rosetta_core::{identifiers::*, miscellaneous::Version, objects::*, response_types::*};
-use std::sync::Arc;
+use icrc_ledger_types::icrc1::account::Account;
+use rosetta_core::{
+    identifiers::*, miscellaneous::Version, objects::*, request_types::*, response_types::*,
+};
+use serde_bytes::ByteBuf;
+use std::{str::FromStr, sync::Arc};
 
-pub fn network_list(ledger_id: &Principal) -> NetworkListResponse {
+pub fn network_list(ledger_ids: &[Principal]) -> NetworkListResponse {
     NetworkListResponse {
-        network_identifiers: vec![NetworkIdentifier::new(
-            DEFAULT_BLOCKCHAIN.to_owned(),
-            ledger_id.to_string(),
-        )],
+        network_identifiers: ledger_ids
+            .iter()
+            .map(|ledger_id| {
+                NetworkIdentifier::new(DEFAULT_BLOCKCHAIN.to_owned(), ledger_id.to_string())
+            })
+            .collect(),
     }
 }
 
@@ -128,13 +140,217 @@ pub fn block(
     )))
 }
 
+// Returns the block that the partial block identifier points to or the current block if neither index nor hash are set
+fn get_rosetta_block_or_current_block(
+    storage_client: Arc<StorageClient>,
+    partial_block_identifier: Option<PartialBlockIdentifier>,
+) -> Result<RosettaBlock, Error> {
+    match partial_block_identifier {
+        Some(partial_block_identifier)
+            if partial_block_identifier.index.is_some()
+                || partial_block_identifier.hash.is_some() =>
+        {
+            get_rosetta_block_from_partial_block_identifier(
+                partial_block_identifier,
+                storage_client,
+            )
+            .map_err(|err| Error::invalid_block_identifier(&err))
+        }
+        _ => storage_client
+            .get_block_with_highest_block_idx()
+            .map_err(|e| Error::unable_to_find_block(&e))?
+            .ok_or_else(|| Error::unable_to_find_block(&"Current block not found".to_owned())),
+    }
+}
+
+pub fn account_balance(
+    storage_client: Arc<StorageClient>,
+    account_identifier: AccountIdentifier,
+    partial_block_identifier: Option<PartialBlockIdentifier>,
+    currencies: Option<Vec<Currency>>,
+    metadata: Metadata,
+) -> Result<AccountBalanceResponse, Error> {
+    let account: Account = account_identifier
+        .try_into()
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let rosetta_block =
+        get_rosetta_block_or_current_block(storage_client.clone(), partial_block_identifier)?;
+    let currency: Currency = metadata.into();
+
+    // The ledger only holds balances in a single currency
+    if currencies.is_some_and(|currencies| !currencies.contains(&currency)) {
+        return Ok(AccountBalanceResponse::new(
+            BlockIdentifier::from(&rosetta_block),
+            vec![],
+        ));
+    }
+
+    // Accounts that have not been involved in any transaction up to this block have a balance of zero
+    let balance = storage_client
+        .get_account_balance_at_block_idx(&account, rosetta_block.index)
+        .map_err(|err| Error::unable_to_find_account_balance(&err))?
+        .unwrap_or_else(Tokens::zero);
+
+    Ok(AccountBalanceResponse::new(
+        BlockIdentifier::from(&rosetta_block),
+        vec![Amount::new(balance.to_string(), currency)],
+    ))
+}
+
+pub fn account_coins(
+    storage_client: Arc<StorageClient>,
+    account_identifier: AccountIdentifier,
+    include_mempool: bool,
+) -> Result<AccountCoinsResponse, Error> {
+    // ICRC-1 ledgers are account based and have no mempool, so there are never any coins to return
+    if include_mempool {
+        return Err(Error::request_processing_error(
+            &"ICRC Rosetta does not support mempool coins".to_owned(),
+        ));
+    }
+    let _: Account = account_identifier
+        .try_into()
+        .map_err(|err| Error::parsing_unsuccessful(&err))?;
+    let rosetta_block = get_rosetta_block_or_current_block(storage_client, None)?;
+
+    Ok(AccountCoinsResponse::new(
+        BlockIdentifier::from(&rosetta_block),
+        vec![],
+    ))
+}
+
+pub fn search_transactions(
+    storage_client: Arc<StorageClient>,
+    request: SearchTransactionsRequest,
+    metadata: Metadata,
+) -> Result<SearchTransactionsResponse, Error> {
+    let currency: Currency = metadata.into();
+
+    if request.coin_identifier.is_some() {
+        return Err(Error::invalid_search_request(
+            &"Searching for transactions by coin identifier is not supported".to_owned(),
+        ));
+    }
+    if request.status.is_some() {
+        return Err(Error::invalid_search_request(
+            &"Searching for transactions by operation status is not supported".to_owned(),
+        ));
+    }
+    if request
+        .currency
+        .as_ref()
+        .is_some_and(|requested_currency| *requested_currency != currency)
+    {
+        return Err(Error::invalid_search_request(&format!(
+            "The ledger only supports the currency {:?}",
+            currency
+        )));
+    }
+
+    let max_block = request
+        .max_block
+        .map(|max_block| {
+            u64::try_from(max_block)
+                .map_err(|_| Error::invalid_search_request(&"max_block must not be negative"))
+        })
+        .transpose()?;
+    let offset = u64::try_from(request.offset.unwrap_or(0))
+        .map_err(|_| Error::invalid_search_request(&"offset must not be negative"))?;
+    let limit = match request.limit {
+        Some(limit) => u64::try_from(limit)
+            .map_err(|_| Error::invalid_search_request(&"limit must not be negative"))?
+            .min(MAX_TRANSACTIONS_PER_SEARCH_TRANSACTIONS_REQUEST),
+        None => MAX_TRANSACTIONS_PER_SEARCH_TRANSACTIONS_REQUEST,
+    };
+
+    let transaction_hash = request
+        .transaction_identifier
+        .map(|transaction_identifier| {
+            ByteBuf::try_from(transaction_identifier)
+                .map_err(|err| Error::parsing_unsuccessful(&err))
+        })
+        .transpose()?;
+    let account = request
+        .account_identifier
+        .map(|account_identifier| {
+            account_identifier
+                .try_into()
+                .map_err(|err| Error::parsing_unsuccessful(&err))
+        })
+        .transpose()?;
+    let principal = request
+        .address
+        .map(|address| {
+            Principal::from_str(&address).map_err(|err| Error::parsing_unsuccessful(&err))
+        })
+        .transpose()?;
+    let operation_type = request
+        ._type
+        .map(|operation_type| {
+            OperationType::from_str(&operation_type)
+                .map(|operation_type| operation_type.to_string().to_lowercase())
+                .map_err(|err| Error::invalid_search_request(&err))
+        })
+        .transpose()?;
+
+    // Only successful transactions are stored in the ledger
+    if request.success == Some(false) {
+        return Ok(SearchTransactionsResponse::new(vec![], 0, None));
+    }
+
+    let (rosetta_blocks, total_count) = storage_client
+        .search_transactions(&TransactionSearchFilter {
+            transaction_hash,
+            account,
+            principal,
+            operation_type,
+            match_any: request.operator == Some(Operator::Or),
+            max_block,
+            offset,
+            limit,
+        })
+        .map_err(|err| Error::unable_to_find_block(&err))?;
+
+    let next_offset = offset + rosetta_blocks.len() as u64;
+    let transactions = rosetta_blocks
+        .into_iter()
+        .map(|rosetta_block| {
+            Ok(BlockTransaction::new(
+                BlockIdentifier::from(&rosetta_block),
+                icrc1_rosetta_block_to_rosetta_core_transaction(rosetta_block, currency.clone())
+                    .map_err(|err| Error::failed_to_build_block_response(&err))?,
+            ))
+        })
+        .collect::<Result<Vec<BlockTransaction>, Error>>()?;
+
+    Ok(SearchTransactionsResponse::new(
+        transactions,
+        total_count as i64,
+        (next_offset < total_count).then_some(next_offset as i64),
+    ))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::common::storage::types::{RosettaBlock, Tokens};
+    use crate::common::utils::utils::icrc1_rosetta_block_to_rosetta_core_operation;
+    use ic_icrc1::Operation;
     use ic_icrc1_test_utils::valid_blockchain_strategy;
     use proptest::prelude::*;
 
+    fn involved_accounts(operation: &Operation<Tokens>) -> Vec<Account> {
+        match operation {
+            Operation::Mint { to, .. } => vec![*to],
+            Operation::Burn { from, spender, .. } => {
+                vec![*from].into_iter().chain(*spender).collect()
+            }
+            Operation::Transfer {
+                from, to, spender, ..
+            } => vec![*from, *to].into_iter().chain(*spender).collect(),
+            Operation::Approve { from, spender, .. } => vec![*from, *spender],
+        }
+    }
+
     const BLOCKHAIN_LENGTH: usize = 1000;
 
     proptest! {
@@ -370,5 +586,140 @@ mod test {
                     assert!(block_transaction_res.unwrap_err().0.description.unwrap().contains(format!("Both index {} and hash {} were provided but they do not match the same block",valid_block_idx.clone(),invalid_block_hash.clone()).as_str()));
                 }
         }
+
+        #[test]
+        fn test_account_balance_service(blockchain in valid_blockchain_strategy::<Tokens>(BLOCKHAIN_LENGTH)){
+            let storage_client_memory = Arc::new(StorageClient::new_in_memory().unwrap());
+            let metadata = Metadata{
+                symbol: "ICP".to_string(),
+                decimals: 8
+            };
+            let currency: Currency = metadata.clone().into();
+            let unknown_account = Account{owner: Principal::anonymous(), subaccount: Some([1;32])};
+
+            // If the storage is empty the service should return an error
+            let account_balance_res = account_balance(storage_client_memory.clone(),unknown_account.into(),None,None,metadata.clone());
+            assert!(account_balance_res.unwrap_err().0.message.contains("Unable to find block"));
+
+            if !blockchain.is_empty() {
+                let mut rosetta_blocks = vec![];
+                for (index,block) in blockchain.clone().into_iter().enumerate(){
+                    rosetta_blocks.push(RosettaBlock::from_icrc_ledger_block(block,index as u64).unwrap());
+                }
+                storage_client_memory.store_blocks(rosetta_blocks.clone()).unwrap();
+                storage_client_memory.update_account_balances().unwrap();
+
+                for rosetta_block in rosetta_blocks.iter(){
+                    for account in involved_accounts(&rosetta_block.get_transaction().unwrap().operation){
+                        let expected_balance = storage_client_memory.get_account_balance_at_block_idx(&account,rosetta_block.index).unwrap().unwrap_or_else(Tokens::zero);
+
+                        // The balance at a certain block index should be the balance stored for that index
+                        let account_balance_res = account_balance(storage_client_memory.clone(),account.into(),Some(PartialBlockIdentifier{index:Some(rosetta_block.index),hash:None}),None,metadata.clone()).unwrap();
+                        assert_eq!(account_balance_res,AccountBalanceResponse::new(BlockIdentifier::from(rosetta_block),vec![Amount::new(expected_balance.to_string(),currency.clone())]));
+
+                        // Looking the block up by its hash should result in the same balance
+                        let account_balance_res_by_hash = account_balance(storage_client_memory.clone(),account.into(),Some(PartialBlockIdentifier{index:None,hash:Some(hex::encode(&rosetta_block.block_hash))}),None,metadata.clone()).unwrap();
+                        assert_eq!(account_balance_res,account_balance_res_by_hash);
+                    }
+                }
+
+                let last_block = rosetta_blocks.last().unwrap();
+
+                // If no block identifier is provided the balance at the current block should be returned
+                let account_balance_res = account_balance(storage_client_memory.clone(),unknown_account.into(),None,None,metadata.clone()).unwrap();
+                assert_eq!(account_balance_res,AccountBalanceResponse::new(BlockIdentifier::from(last_block),vec![Amount::new(Tokens::zero().to_string(),currency.clone())]));
+
+                // If a currency other than the ledger currency is requested no balances should be returned
+                let other_currency = Currency{symbol:"XTC".to_string(),decimals:8,metadata:None};
+                let account_balance_res = account_balance(storage_client_memory.clone(),unknown_account.into(),None,Some(vec![other_currency]),metadata.clone()).unwrap();
+                assert!(account_balance_res.balances.is_empty());
+
+                // If the block identifier does not exist the service should return an error
+                let account_balance_res = account_balance(storage_client_memory.clone(),unknown_account.into(),Some(PartialBlockIdentifier{index:Some(last_block.index+1),hash:None}),None,metadata.clone());
+                assert!(account_balance_res.unwrap_err().0.description.unwrap().contains(&format!("Block at index {} could not be found",last_block.index+1)));
+            }
+        }
+
+        #[test]
+        fn test_search_transactions_service(blockchain in valid_blockchain_strategy::<Tokens>(BLOCKHAIN_LENGTH)){
+            let storage_client_memory = Arc::new(StorageClient::new_in_memory().unwrap());
+            let metadata = Metadata{
+                symbol: "ICP".to_string(),
+                decimals: 8
+            };
+            let currency: Currency = metadata.clone().into();
+            let network_identifier = NetworkIdentifier::new(DEFAULT_BLOCKCHAIN.to_owned(), Principal::anonymous().to_string());
+            let mut rosetta_blocks = vec![];
+            for (index,block) in blockchain.clone().into_iter().enumerate(){
+                rosetta_blocks.push(RosettaBlock::from_icrc_ledger_block(block,index as u64).unwrap());
+            }
+            storage_client_memory.store_blocks(rosetta_blocks.clone()).unwrap();
+
+            let to_block_transaction = |rosetta_block: &RosettaBlock| BlockTransaction::new(BlockIdentifier::from(rosetta_block),icrc1_rosetta_block_to_rosetta_core_transaction(rosetta_block.clone(),currency.clone()).unwrap());
+
+            // Without any conditions all transactions should be returned, starting with the most recent one
+            let search_res = search_transactions(storage_client_memory.clone(),SearchTransactionsRequest::new(network_identifier.clone()),metadata.clone()).unwrap();
+            assert_eq!(search_res.total_count,rosetta_blocks.len() as i64);
+            assert_eq!(search_res.transactions,rosetta_blocks.iter().rev().map(to_block_transaction).collect::<Vec<_>>());
+            assert_eq!(search_res.next_offset,None);
+
+            // Negative pagination parameters should be rejected
+            let mut request = SearchTransactionsRequest::new(network_identifier.clone());
+            request.limit = Some(-1);
+            assert!(search_transactions(storage_client_memory.clone(),request,metadata.clone()).unwrap_err().0.description.unwrap().contains("limit must not be negative"));
+
+            if !rosetta_blocks.is_empty() {
+                let selected_block = &rosetta_blocks[rosetta_blocks.len()/2];
+                let selected_operation = selected_block.get_transaction().unwrap().operation;
+
+                // Searching by transaction hash should return all blocks with that transaction hash
+                let mut request = SearchTransactionsRequest::new(network_identifier.clone());
+                request.transaction_identifier = Some(selected_block.get_transaction_identifier());
+                let search_res = search_transactions(storage_client_memory.clone(),request,metadata.clone()).unwrap();
+                assert!(search_res.transactions.contains(&to_block_transaction(selected_block)));
+                assert!(search_res.transactions.iter().all(|tx| tx.transaction.transaction_identifier == selected_block.get_transaction_identifier()));
+
+                // Searching by account should return every block that involves that account up to max_block
+                let account = involved_accounts(&selected_operation)[0];
+                let mut request = SearchTransactionsRequest::new(network_identifier.clone());
+                request.account_identifier = Some(account.into());
+                request.max_block = Some(selected_block.index as i64);
+                let expected_blocks = rosetta_blocks.iter().filter(|block| block.index <= selected_block.index && involved_accounts(&block.get_transaction().unwrap().operation).contains(&account)).rev().map(to_block_transaction).collect::<Vec<_>>();
+                let search_res = search_transactions(storage_client_memory.clone(),request.clone(),metadata.clone()).unwrap();
+                assert_eq!(search_res.total_count,expected_blocks.len() as i64);
+                assert_eq!(search_res.transactions,expected_blocks);
+
+                // Paginating through the results should return the same transactions
+                request.limit = Some(1);
+                let mut paginated_transactions = vec![];
+                let mut next_offset = Some(0);
+                while let Some(offset) = next_offset {
+                    request.offset = Some(offset);
+                    let search_res = search_transactions(storage_client_memory.clone(),request.clone(),metadata.clone()).unwrap();
+                    assert_eq!(search_res.total_count,expected_blocks.len() as i64);
+                    paginated_transactions.extend(search_res.transactions);
+                    next_offset = search_res.next_offset;
+                }
+                assert_eq!(paginated_transactions,expected_blocks);
+
+                // Searching by operation type should only return transactions of that type
+                let operation_type = icrc1_rosetta_block_to_rosetta_core_operation(selected_block.clone(),currency.clone()).unwrap()._type;
+                let mut request = SearchTransactionsRequest::new(network_identifier.clone());
+                request._type = Some(operation_type.clone());
+                let search_res = search_transactions(storage_client_memory.clone(),request.clone(),metadata.clone()).unwrap();
+                assert!(search_res.total_count > 0);
+                assert!(search_res.transactions.iter().all(|tx| tx.transaction.operations.iter().all(|op| op._type == operation_type)));
+
+                // Combining conditions with the or operator should return the union of the matches
+                request.account_identifier = Some(account.into());
+                request.operator = Some(Operator::Or);
+                let expected_count = rosetta_blocks.iter().filter(|block| {
+                    let operation = block.get_transaction().unwrap().operation;
+                    involved_accounts(&operation).contains(&account) || icrc1_rosetta_block_to_rosetta_core_operation((*block).clone(),currency.clone()).unwrap()._type == operation_type
+                }).count();
+                let search_res = search_transactions(storage_client_memory.clone(),request,metadata.clone()).unwrap();
+                assert_eq!(search_res.total_count,expected_count as i64);
+            }
+        }
     }
 }
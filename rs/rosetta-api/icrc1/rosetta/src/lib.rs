@@ -1,11 +1,19 @@
 use anyhow::{bail, Context};
-use common::storage::{storage_client::StorageClient, types::MetadataEntry};
+use common::{
+    storage::{storage_client::StorageClient, types::MetadataEntry},
+    types::Error,
+    utils::utils::verify_network_id,
+};
 use ic_base_types::CanisterId;
 use icrc_ledger_agent::Icrc1Agent;
 use icrc_ledger_types::icrc::generic_metadata_value::MetadataValue;
 use num_traits::ToPrimitive;
-use rosetta_core::objects::Currency;
-use std::{collections::HashMap, sync::Arc};
+use rosetta_core::{identifiers::NetworkIdentifier, objects::Currency};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    str::FromStr,
+    sync::{Arc, RwLock},
+};
 
 pub mod common;
 pub mod construction_api;
@@ -19,6 +27,58 @@ pub struct AppState {
     pub metadata: Metadata,
 }
 
+/// The state of a Rosetta server that serves one or more ICRC-1 ledgers.
+/// Every ledger has its own `AppState` and is selected through the network field of the `NetworkIdentifier` of a request, which is the ledger id.
+#[derive(Default)]
+pub struct MultiTokenAppState {
+    token_states: RwLock<BTreeMap<CanisterId, Arc<AppState>>>,
+}
+
+impl MultiTokenAppState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds the state of a ledger. Returns false if a state for the ledger already exists, in which case the existing state is kept.
+    pub fn add_token_state(&self, state: Arc<AppState>) -> bool {
+        match self.token_states.write().unwrap().entry(state.ledger_id) {
+            Entry::Occupied(_) => false,
+            Entry::Vacant(entry) => {
+                entry.insert(state);
+                true
+            }
+        }
+    }
+
+    pub fn contains_ledger(&self, ledger_id: &CanisterId) -> bool {
+        self.token_states.read().unwrap().contains_key(ledger_id)
+    }
+
+    pub fn ledger_ids(&self) -> Vec<CanisterId> {
+        self.token_states.read().unwrap().keys().cloned().collect()
+    }
+
+    // Returns the state of the ledger that the network identifier refers to.
+    pub fn get_token_state(
+        &self,
+        network_identifier: &NetworkIdentifier,
+    ) -> Result<Arc<AppState>, Error> {
+        let state = CanisterId::from_str(&network_identifier.network)
+            .ok()
+            .and_then(|ledger_id| self.token_states.read().unwrap().get(&ledger_id).cloned())
+            .ok_or_else(|| {
+                Error::invalid_network_id(&format!(
+                    "No ledger is served for the network identifier {:?}. Served ledgers: {:?}",
+                    network_identifier,
+                    self.ledger_ids()
+                ))
+            })?;
+        verify_network_id(network_identifier, &state)
+            .map_err(|err| Error::invalid_network_id(&format!("{:?}", err)))?;
+        Ok(state)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Metadata {
     pub symbol: String,
@@ -74,3 +134,72 @@ impl Metadata {
         Ok(Self { symbol, decimals })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_agent::{
+        agent::http_transport::reqwest_transport::ReqwestHttpReplicaV2Transport, Agent,
+    };
+    use ic_rosetta_api::DEFAULT_BLOCKCHAIN;
+
+    fn token_state(ledger_id: CanisterId) -> Arc<AppState> {
+        let agent = Agent::builder()
+            .with_transport(ReqwestHttpReplicaV2Transport::create("http://localhost:0").unwrap())
+            .build()
+            .unwrap();
+        Arc::new(AppState {
+            icrc1_agent: Arc::new(Icrc1Agent {
+                agent,
+                ledger_canister_id: ledger_id.into(),
+            }),
+            ledger_id,
+            storage: Arc::new(StorageClient::new_in_memory().unwrap()),
+            metadata: Metadata::from_args("XTST".to_string(), 8),
+        })
+    }
+
+    fn network_identifier(ledger_id: CanisterId) -> NetworkIdentifier {
+        NetworkIdentifier::new(DEFAULT_BLOCKCHAIN.to_owned(), ledger_id.to_string())
+    }
+
+    #[test]
+    fn test_get_token_state() {
+        let ledger_1 = CanisterId::from_u64(1);
+        let ledger_2 = CanisterId::from_u64(2);
+        let state = MultiTokenAppState::new();
+        assert!(state.add_token_state(token_state(ledger_1)));
+        assert!(state.add_token_state(token_state(ledger_2)));
+        // A ledger can only be added once
+        assert!(!state.add_token_state(token_state(ledger_1)));
+        assert_eq!(state.ledger_ids(), vec![ledger_1, ledger_2]);
+
+        // Requests are routed to the ledger of their network identifier
+        for ledger_id in [ledger_1, ledger_2] {
+            let token_state = state
+                .get_token_state(&network_identifier(ledger_id))
+                .unwrap();
+            assert_eq!(token_state.ledger_id, ledger_id);
+        }
+
+        // Ledgers that are not served are rejected
+        let err = state
+            .get_token_state(&network_identifier(CanisterId::from_u64(3)))
+            .unwrap_err();
+        assert_eq!(err.0.code, Error::invalid_network_id(&"").0.code);
+
+        // The network identifier has to match the blockchain as well
+        assert!(state
+            .get_token_state(&NetworkIdentifier::new(
+                "Bitcoin".to_owned(),
+                ledger_1.to_string()
+            ))
+            .is_err());
+        assert!(state
+            .get_token_state(&NetworkIdentifier::new(
+                DEFAULT_BLOCKCHAIN.to_owned(),
+                "not a ledger id".to_owned()
+            ))
+            .is_err());
+    }
+}
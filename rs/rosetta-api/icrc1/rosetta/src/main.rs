@@ -12,20 +12,29 @@ use ic_agent::{
 };
 use ic_base_types::CanisterId;
 use ic_icrc_rosetta::{
-    common::storage::{storage_client::StorageClient, types::MetadataEntry},
+    common::{
+        config::{MultiTokenConfig, TokenConfig},
+        storage::{storage_client::StorageClient, types::MetadataEntry},
+    },
     construction_api::endpoints::*,
     data_api::endpoints::*,
     ledger_blocks_synchronization::blocks_synchronizer::start_synching_blocks,
-    AppState, Metadata,
+    AppState, Metadata, MultiTokenAppState,
 };
 use icrc_ledger_agent::{CallMode, Icrc1Agent};
 use lazy_static::lazy_static;
-use std::{net::TcpListener, sync::Arc};
+use std::{
+    collections::HashSet,
+    net::TcpListener,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 use std::{path::PathBuf, process};
+use tokio::task::JoinSet;
 use tower_http::classify::{ServerErrorsAsFailures, SharedClassifier};
 use tower_http::trace::TraceLayer;
 use tower_request_id::{RequestId, RequestIdLayer};
-use tracing::{debug, error_span, info, Level, Span};
+use tracing::{debug, error, error_span, info, warn, Level, Span};
 use url::Url;
 
 lazy_static! {
@@ -49,8 +58,19 @@ enum NetworkType {
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    /// The ledger to serve. Can be combined with [multi_tokens_config] to serve additional ledgers.
     #[arg(short, long)]
-    ledger_id: CanisterId,
+    ledger_id: Option<CanisterId>,
+
+    /// A JSON file that lists the ICRC-1 ledgers to serve, in addition to [ledger_id].
+    /// Every ledger gets its own store file and is selected through the network field of the NetworkIdentifier.
+    /// The file is polled for newly added ledgers while Rosetta is running.
+    #[arg(long)]
+    multi_tokens_config: Option<PathBuf>,
+
+    /// The interval in seconds in which the [multi_tokens_config] file is checked for newly added ledgers.
+    #[arg(long, default_value_t = 60)]
+    multi_tokens_config_poll_interval_secs: u64,
 
     #[arg(long)]
     icrc1_symbol: Option<String>,
@@ -72,7 +92,8 @@ struct Args {
     #[arg(short, long, value_enum, default_value_t = StoreType::File)]
     store_type: StoreType,
 
-    /// The file to use for the store if [store_type] is file.
+    /// The file to use for the store of [ledger_id] if [store_type] is file.
+    /// Ledgers from [multi_tokens_config] without a store file are stored in `<ledger_id>.sqlite` in the directory of this file.
     #[arg(short = 'f', long, default_value = "db.sqlite")]
     store_file: PathBuf,
 
@@ -124,8 +145,55 @@ impl Args {
         })
     }
 
-    fn are_metadata_args_set(&self) -> bool {
-        self.icrc1_symbol.is_some() && self.icrc1_decimals.is_some()
+    /// Returns the configurations of all ledgers that should be served at startup.
+    fn token_configs(&self) -> Result<Vec<TokenConfig>> {
+        let mut tokens = match &self.multi_tokens_config {
+            Some(path) => MultiTokenConfig::from_file(path)?.tokens,
+            None => vec![],
+        };
+        if let Some(ledger_id) = self.ledger_id {
+            if tokens.iter().any(|token| token.ledger_id == ledger_id) {
+                bail!(
+                    "The ledger {} is configured both through --ledger-id and in the multi tokens config",
+                    ledger_id
+                );
+            }
+            tokens.insert(
+                0,
+                TokenConfig {
+                    ledger_id,
+                    icrc1_symbol: self.icrc1_symbol.clone(),
+                    icrc1_decimals: self.icrc1_decimals,
+                    store_file: Some(self.store_file.clone()),
+                },
+            );
+        }
+        if tokens.is_empty() {
+            bail!("Either --ledger-id or --multi-tokens-config has to be set");
+        }
+        if let StoreType::File = self.store_type {
+            // Ledgers without a configured store file are stored next to --store-file and may collide with it.
+            let mut store_files = HashSet::new();
+            for token in &tokens {
+                let store_file = token.effective_store_file(&self.store_file);
+                if !store_files.insert(store_file.clone()) {
+                    bail!(
+                        "The store file {} is used by more than one ledger",
+                        store_file.display()
+                    );
+                }
+            }
+        }
+        Ok(tokens)
+    }
+
+    fn create_storage(&self, token: &TokenConfig) -> Result<StorageClient> {
+        match self.store_type {
+            StoreType::InMemory => StorageClient::new_in_memory(),
+            StoreType::File => {
+                StorageClient::new_persistent(&token.effective_store_file(&self.store_file))
+            }
+        }
     }
 }
 
@@ -164,39 +232,40 @@ fn add_request_span() -> FnTraceLayer {
 }
 
 async fn load_metadata(
-    args: &Args,
+    offline: bool,
+    token: &TokenConfig,
     icrc1_agent: &Icrc1Agent,
     storage: &StorageClient,
 ) -> anyhow::Result<Metadata> {
-    if args.offline {
+    if offline {
         let db_metadata_entries = storage.read_metadata()?;
         // If metadata is empty and the args are not set, bail out.
-        if db_metadata_entries.is_empty() && !args.are_metadata_args_set() {
+        if db_metadata_entries.is_empty() && !token.are_metadata_args_set() {
             bail!("Metadata must be initialized by starting Rosetta in online mode first or by providing ICRC-1 metadata arguments.");
         }
 
         // If metadata is set in args and not entries are found in the database,
         // return the metadata from the args.
-        if args.are_metadata_args_set() && db_metadata_entries.is_empty() {
+        if token.are_metadata_args_set() && db_metadata_entries.is_empty() {
             return Ok(Metadata::from_args(
-                args.icrc1_symbol.clone().unwrap(),
-                args.icrc1_decimals.unwrap(),
+                token.icrc1_symbol.clone().unwrap(),
+                token.icrc1_decimals.unwrap(),
             ));
         }
 
         // Populate a metadata object with the database entries.
         let db_metadata = Metadata::from_metadata_entries(&db_metadata_entries)?;
         // If the metadata args are not set, return using the db metadata.
-        if !args.are_metadata_args_set() {
+        if !token.are_metadata_args_set() {
             return Ok(db_metadata);
         }
 
         // Extract the symbol and decimals from the arguments.
-        let symbol = args
+        let symbol = token
             .icrc1_symbol
             .clone()
             .context("ICRC-1 symbol should be provided in offline mode.")?;
-        let decimals = args
+        let decimals = token
             .icrc1_decimals
             .context("ICRC-1 decimals should be provided in offline mode.")?;
 
@@ -241,16 +310,101 @@ async fn load_metadata(
     Metadata::from_metadata_entries(&ic_metadata_entries)
 }
 
+/// Synchronizes the blocks of a ledger and updates the account balances of its store.
+async fn sync_token(
+    args: &Args,
+    agent: &Agent,
+    token: &TokenConfig,
+) -> Result<(Arc<Icrc1Agent>, Arc<StorageClient>)> {
+    let storage = Arc::new(args.create_storage(token)?);
+    let icrc1_agent = Arc::new(Icrc1Agent {
+        agent: agent.clone(),
+        ledger_canister_id: token.ledger_id.into(),
+    });
+
+    if !args.offline {
+        info!("Starting to sync blocks of ledger {}", token.ledger_id);
+        start_synching_blocks(
+            icrc1_agent.clone(),
+            storage.clone(),
+            *MAXIMUM_BLOCKS_PER_REQUEST,
+        )
+        .await?;
+    }
+
+    info!(
+        "Starting to update account balances of ledger {}",
+        token.ledger_id
+    );
+    // Once the entire blockchain has been synched and no gaps remain, the account_balance table can be updated
+    storage.update_account_balances()?;
+
+    Ok((icrc1_agent, storage))
+}
+
+async fn load_token_state(args: &Args, agent: &Agent, token: &TokenConfig) -> Result<AppState> {
+    let (icrc1_agent, storage) = sync_token(args, agent, token).await?;
+    let metadata = load_metadata(args.offline, token, &icrc1_agent, &storage).await?;
+    Ok(AppState {
+        icrc1_agent,
+        ledger_id: token.ledger_id,
+        storage,
+        metadata,
+    })
+}
+
+/// Periodically reads the multi tokens config file and starts serving ledgers that were added to it.
+/// Every new ledger is synchronized in its own task, ledgers that could not be loaded are retried on the next poll.
+/// Ledgers that are removed from the file keep being served until Rosetta is restarted.
+async fn add_tokens_from_config(
+    args: Arc<Args>,
+    agent: Agent,
+    shared_state: Arc<MultiTokenAppState>,
+) {
+    let loading_ledgers = Arc::new(Mutex::new(HashSet::new()));
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        args.multi_tokens_config_poll_interval_secs,
+    ));
+    loop {
+        interval.tick().await;
+        let tokens = match args.token_configs() {
+            Ok(tokens) => tokens,
+            Err(err) => {
+                warn!("Unable to read the multi tokens config: {:?}", err);
+                continue;
+            }
+        };
+        for token in tokens {
+            if shared_state.contains_ledger(&token.ledger_id)
+                || !loading_ledgers.lock().unwrap().insert(token.ledger_id)
+            {
+                continue;
+            }
+            info!("Adding ledger {}", token.ledger_id);
+            let args = args.clone();
+            let agent = agent.clone();
+            let shared_state = shared_state.clone();
+            let loading_ledgers = loading_ledgers.clone();
+            tokio::spawn(async move {
+                match load_token_state(&args, &agent, &token).await {
+                    Ok(state) => {
+                        shared_state.add_token_state(Arc::new(state));
+                    }
+                    Err(err) => error!("Unable to add ledger {}: {:?}", token.ledger_id, err),
+                }
+                loading_ledgers.lock().unwrap().remove(&token.ledger_id);
+            });
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let args = Args::parse();
+    let args = Arc::new(Args::parse());
 
     init_logs(args.log_level);
 
-    let storage = Arc::new(match args.store_type {
-        StoreType::InMemory => StorageClient::new_in_memory()?,
-        StoreType::File => StorageClient::new_persistent(&args.store_file)?,
-    });
+    let tokens = args.token_configs()?;
 
     let network_url = args.effective_network_url();
 
@@ -275,37 +429,43 @@ async fn main() -> Result<()> {
         ic_agent.status().await?.replica_health_status
     );
 
-    let icrc1_agent = Arc::new(Icrc1Agent {
-        agent: ic_agent,
-        ledger_canister_id: args.ledger_id.into(),
-    });
-
-    if !args.offline {
-        info!("Starting to sync blocks");
-        start_synching_blocks(
-            icrc1_agent.clone(),
-            storage.clone(),
-            *MAXIMUM_BLOCKS_PER_REQUEST,
-        )
-        .await?;
-    }
-
-    info!("Starting to update account balances");
-    // Once the entire blockchain has been synched and no gaps remain, the account_balance table can be updated
-    storage.update_account_balances()?;
-
     // If the option of exiting after the synchronization is completed is set we can exit rosetta
     if args.exit_on_sync {
+        for token in tokens.iter() {
+            sync_token(&args, &ic_agent, token).await?;
+        }
         process::exit(0);
     }
 
-    let metadata = load_metadata(&args, &icrc1_agent, &storage).await?;
-    let shared_state = Arc::new(AppState {
-        icrc1_agent,
-        ledger_id: args.ledger_id,
-        storage: storage.clone(),
-        metadata,
-    });
+    // Every ledger is synchronized in its own task. A ledger that fails to load does not prevent the others from being served.
+    let mut token_tasks = JoinSet::new();
+    for token in tokens {
+        let args = args.clone();
+        let ic_agent = ic_agent.clone();
+        token_tasks.spawn(async move {
+            let ledger_id = token.ledger_id;
+            (ledger_id, load_token_state(&args, &ic_agent, &token).await)
+        });
+    }
+    let shared_state = Arc::new(MultiTokenAppState::new());
+    while let Some(token_task) = token_tasks.join_next().await {
+        match token_task? {
+            (_, Ok(token_state)) => {
+                shared_state.add_token_state(Arc::new(token_state));
+            }
+            (ledger_id, Err(err)) => error!("Unable to load ledger {}: {:?}", ledger_id, err),
+        }
+    }
+
+    if args.multi_tokens_config.is_some() {
+        tokio::spawn(add_tokens_from_config(
+            args.clone(),
+            ic_agent.clone(),
+            shared_state.clone(),
+        ));
+    } else if shared_state.ledger_ids().is_empty() {
+        bail!("None of the configured ledgers could be loaded");
+    }
 
     let app = Router::new()
         .route("/health", get(health))
@@ -316,9 +476,17 @@ async fn main() -> Result<()> {
         .route("/block/transaction", post(block_transaction))
         .route("/mempool", post(mempool))
         .route("/mempool/transaction", post(mempool_transaction))
+        .route("/account/balance", post(account_balance))
+        .route("/account/coins", post(account_coins))
+        .route("/search/transactions", post(search_transactions))
         .route("/construction/derive", post(construction_derive))
         .route("/construction/preprocess", post(construction_preprocess))
         .route("/construction/metadata", post(construction_metadata))
+        .route("/construction/payloads", post(construction_payloads))
+        .route("/construction/combine", post(construction_combine))
+        .route("/construction/parse", post(construction_parse))
+        .route("/construction/hash", post(construction_hash))
+        .route("/construction/submit", post(construction_submit))
         // This layer creates a span for each http request and attaches
         // the request_id, HTTP Method and path to it.
         .layer(add_request_span())
@@ -330,7 +498,7 @@ async fn main() -> Result<()> {
 
     let tcp_listener = TcpListener::bind(format!("0.0.0.0:{}", args.get_port()))?;
 
-    if let Some(port_file) = args.port_file {
+    if let Some(port_file) = &args.port_file {
         std::fs::write(port_file, tcp_listener.local_addr()?.port().to_string())?;
     }
 
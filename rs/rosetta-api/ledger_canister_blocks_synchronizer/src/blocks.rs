@@ -1,4 +1,7 @@
-use self::database_access::INSERT_INTO_TRANSACTIONS_STATEMENT;
+use self::database_access::{
+    INSERT_INTO_FEE_COLLECTORS_STATEMENT, INSERT_INTO_TRANSACTIONS_STATEMENT,
+    SELECT_FEE_COLLECTOR_STATEMENT,
+};
 use crate::iso8601_to_timestamp;
 use ic_ledger_core::block::{BlockIndex, BlockType, EncodedBlock};
 use ic_ledger_core::tokens::CheckedAdd;
@@ -52,6 +55,38 @@ mod database_access {
         Ok(())
     }
 
+    // The fee collectors are kept in their own table which is not pruned, as
+    // blocks after the pruning point can still refer to a pruned block
+    // through fee_collector_block_index.
+    pub const INSERT_INTO_FEE_COLLECTORS_STATEMENT: &str =
+        "INSERT INTO fee_collectors (block_idx, account) VALUES (?1, ?2)";
+
+    pub const SELECT_FEE_COLLECTOR_STATEMENT: &str =
+        "SELECT account FROM fee_collectors WHERE block_idx = ?1";
+
+    pub fn push_fee_collector(
+        connection: &mut Connection,
+        block: &Block,
+        index: &u64,
+    ) -> Result<(), BlockStoreError> {
+        let mut stmt = connection
+            .prepare(INSERT_INTO_FEE_COLLECTORS_STATEMENT)
+            .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+        push_fee_collector_execution(block, &mut stmt, index)
+    }
+
+    pub fn push_fee_collector_execution(
+        block: &Block,
+        stmt: &mut Statement,
+        index: &u64,
+    ) -> Result<(), BlockStoreError> {
+        if let Some(fee_collector) = block.fee_collector {
+            stmt.execute(params![index, fee_collector.to_hex()])
+                .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+        }
+        Ok(())
+    }
+
     pub const INSERT_INTO_TRANSACTIONS_STATEMENT: &str = "INSERT INTO transactions (block_idx, tx_hash, operation_type, from_account, to_account, amount, fee, created_at_time, memo, icrc1_memo, spender_account, allowance, expected_allowance, expires_at) VALUES (:index, :tx_hash, :op, :from, :to, :tokens, :fee, :created_at_time, :memo, :icrc1_memo, :spender, :allowance, :expected_allowance, :expires_at)";
 
     pub fn push_transaction(
@@ -422,12 +457,41 @@ mod database_access {
             None => Ok(None),
         }
     }
+    // Returns the account that received the fee of the given block, if any.
+    // The fee collector is either set in the block itself or in the block
+    // referenced by fee_collector_block_index, which may have been pruned.
+    pub fn get_fee_collector(
+        block_idx: u64,
+        block: &Block,
+        stmt_select_fee_collector: &mut Statement,
+    ) -> Result<Option<AccountIdentifier>, BlockStoreError> {
+        if block.fee_collector.is_some() {
+            return Ok(block.fee_collector);
+        }
+        let Some(fee_collector_block_index) = block.fee_collector_block_index else {
+            return Ok(None);
+        };
+        let fee_collector: String = stmt_select_fee_collector
+            .query_row(params![fee_collector_block_index], |row| row.get(0))
+            .map_err(|e| {
+                BlockStoreError::Other(format!(
+                    "Block at index {} has fee_collector_block_index {} but no fee collector is stored for that block: {}",
+                    block_idx, fee_collector_block_index, e
+                ))
+            })?;
+        AccountIdentifier::from_hex(&fee_collector)
+            .map(Some)
+            .map_err(BlockStoreError::Other)
+    }
+
     pub fn update_balance_book_execution(
         hb: &HashedBlock,
         stmt_select: &mut Statement,
         stmt_insert: &mut Statement,
+        stmt_select_fee_collector: &mut Statement,
     ) -> Result<(), BlockStoreError> {
         let block = Block::decode(hb.block.clone()).unwrap();
+        let fee_collector = get_fee_collector(hb.index, &block, stmt_select_fee_collector)?;
         let operation_type = block.transaction.operation;
         let mut new_balances: Vec<(String, u64)> = vec![];
         let mut extract_latest_balance =
@@ -532,6 +596,26 @@ mod database_access {
                         }
                     }
                 }
+                // The fee is burned unless a fee collector is set.
+                if let Some(fee_collector) = fee_collector {
+                    let fee_collector = fee_collector.to_hex();
+                    match new_balances
+                        .iter_mut()
+                        .find(|(account, _)| *account == fee_collector)
+                    {
+                        Some(balance) => balance.1 += fee.get_e8s(),
+                        None => match extract_latest_balance(
+                            AccountIdentifier::from_hex(&fee_collector)
+                                .map_err(BlockStoreError::Other)?,
+                        )? {
+                            Some(mut balance) => {
+                                balance.1 += fee.get_e8s();
+                                new_balances.push(balance);
+                            }
+                            None => new_balances.push((fee_collector, fee.get_e8s())),
+                        },
+                    }
+                }
             }
         }
 
@@ -558,7 +642,15 @@ mod database_access {
         let mut stmt_insert = con
             .prepare("INSERT INTO account_balances (block_idx,account,tokens) VALUES (?1,?2,?3)")
             .expect("Couldn't prepare statement");
-        update_balance_book_execution(hb, &mut stmt_select, &mut stmt_insert)
+        let mut stmt_select_fee_collector = con
+            .prepare(SELECT_FEE_COLLECTOR_STATEMENT)
+            .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+        update_balance_book_execution(
+            hb,
+            &mut stmt_select,
+            &mut stmt_insert,
+            &mut stmt_select_fee_collector,
+        )
     }
 
     pub fn get_all_accounts(
@@ -809,6 +901,15 @@ impl Blocks {
             "#,
             [],
         )?;
+        connection.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS fee_collectors (
+                block_idx INTEGER NOT NULL PRIMARY KEY,
+                account VARCHAR(64) NOT NULL
+            )
+            "#,
+            [],
+        )?;
 
         Ok(())
     }
@@ -912,6 +1013,18 @@ impl Blocks {
         let mut connection = self.connection.lock().unwrap();
         database_access::get_transaction(&mut connection, block_idx)
     }
+
+    pub fn get_fee_collector(
+        &self,
+        block_idx: u64,
+        block: &Block,
+    ) -> Result<Option<AccountIdentifier>, BlockStoreError> {
+        let connection = self.connection.lock().unwrap();
+        let mut stmt_select_fee_collector = connection
+            .prepare(SELECT_FEE_COLLECTOR_STATEMENT)
+            .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+        database_access::get_fee_collector(block_idx, block, &mut stmt_select_fee_collector)
+    }
     fn check_table_coherence(&self) -> Result<(), BlockStoreError> {
         let mut connection = self.connection.lock().unwrap();
         let mut block_indices =
@@ -1090,11 +1203,9 @@ impl Blocks {
         con.execute_batch("BEGIN TRANSACTION;")
             .map_err(|e| BlockStoreError::Other(format!("{}", e)))?;
         database_access::push_hashed_block(&mut con, hb)?;
-        database_access::push_transaction(
-            &mut con,
-            &Block::decode(hb.block.clone()).unwrap().transaction,
-            &hb.index,
-        )?;
+        let block = Block::decode(hb.block.clone()).unwrap();
+        database_access::push_fee_collector(&mut con, &block, &hb.index)?;
+        database_access::push_transaction(&mut con, &block.transaction, &hb.index)?;
         database_access::update_balance_book(&mut con, hb)?;
         con.execute_batch("COMMIT TRANSACTION;")
             .map_err(|e| BlockStoreError::Other(format!("{}", e)))?;
@@ -1123,6 +1234,12 @@ impl Blocks {
         let mut stmt_insert = connection
             .prepare("INSERT INTO account_balances (block_idx,account,tokens) VALUES (?1,?2,?3)")
             .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+        let mut stmt_fee_collector = connection
+            .prepare(INSERT_INTO_FEE_COLLECTORS_STATEMENT)
+            .map_err(|e| BlockStoreError::Other(e.to_string()))?;
+        let mut stmt_select_fee_collector = connection
+            .prepare(SELECT_FEE_COLLECTOR_STATEMENT)
+            .map_err(|e| BlockStoreError::Other(e.to_string()))?;
 
         for hb in &batch {
             match database_access::push_hashed_block_execution(hb, &mut stmt_hb) {
@@ -1134,8 +1251,22 @@ impl Blocks {
                     return Err(e);
                 }
             };
+            let block = Block::decode(hb.block.clone()).unwrap();
+            match database_access::push_fee_collector_execution(
+                &block,
+                &mut stmt_fee_collector,
+                &hb.index,
+            ) {
+                Ok(_) => (),
+                Err(e) => {
+                    connection
+                        .execute_batch("ROLLBACK TRANSACTION;")
+                        .map_err(|e| BlockStoreError::Other(format!("{}", e)))?;
+                    return Err(e);
+                }
+            }
             match database_access::push_transaction_execution(
-                &Block::decode(hb.block.clone()).unwrap().transaction,
+                &block.transaction,
                 &mut stmt_tx,
                 &hb.index,
             ) {
@@ -1151,6 +1282,7 @@ impl Blocks {
                 hb,
                 &mut stmt_select,
                 &mut stmt_insert,
+                &mut stmt_select_fee_collector,
             ) {
                 Ok(_) => (),
                 Err(e) => {
@@ -1,15 +1,24 @@
 use ic_ledger_canister_blocks_synchronizer::{
     balance_book::{BalanceBook, ClientBalancesStore},
-    blocks::{BlockStoreError, Blocks},
+    blocks::{BlockStoreError, Blocks, HashedBlock},
     timestamp_to_iso8601,
 };
-use ic_ledger_canister_blocks_synchronizer_test_utils::{create_tmp_dir, sample_data::Scribe};
+use ic_ledger_canister_blocks_synchronizer_test_utils::{
+    create_tmp_dir,
+    sample_data::{acc_id, Scribe},
+};
 use ic_ledger_canister_core::ledger::{LedgerContext, LedgerTransaction};
 use ic_ledger_core::{
-    approvals::AllowanceTable, balances::BalancesStore, block::BlockType, timestamp::TimeStamp,
-    tokens::CheckedAdd, Tokens,
+    approvals::{AllowanceTable, HeapAllowancesData},
+    balances::BalancesStore,
+    block::{BlockType, FeeCollector},
+    timestamp::TimeStamp,
+    tokens::CheckedAdd,
+    Tokens,
+};
+use icp_ledger::{
+    apply_operation, AccountIdentifier, ApprovalKey, Block, Memo, Operation, Transaction,
 };
-use icp_ledger::{apply_operation, AccountIdentifier, ApprovalKey, Block, Operation};
 use rusqlite::params;
 use std::path::Path;
 
@@ -17,7 +26,7 @@ pub(crate) fn sqlite_on_disk_store(path: &Path) -> Blocks {
     Blocks::new_persistent(path).unwrap()
 }
 
-type Approvals = AllowanceTable<ApprovalKey, AccountIdentifier, Tokens>;
+type Approvals = AllowanceTable<HeapAllowancesData<ApprovalKey, AccountIdentifier, Tokens>>;
 
 #[derive(Default)]
 struct TestContext {
@@ -167,13 +176,13 @@ async fn store_account_balances_test() {
         if let Some(acc_str) = from_account {
             let id = AccountIdentifier::from_hex(acc_str.as_str()).unwrap();
             let amount_from = store.get_account_balance(&id, &hb.index).unwrap();
-            let amount_local = *context.balance_book.store.get_balance(&id).unwrap();
+            let amount_local = context.balance_book.store.get_balance(&id).unwrap();
             assert_eq!(amount_from, amount_local);
         }
         if let Some(acc_str) = to_account {
             let id = AccountIdentifier::from_hex(acc_str.as_str()).unwrap();
             let amount_to = store.get_account_balance(&id, &hb.index).unwrap();
-            let amount_local = *context.balance_book.store.get_balance(&id).unwrap();
+            let amount_local = context.balance_book.store.get_balance(&id).unwrap();
             assert_eq!(amount_to, amount_local);
         }
     }
@@ -282,6 +291,86 @@ async fn store_prune_and_load_test() {
     verify_balance_snapshot(&scribe, &mut store, 30);
 }
 
+#[actix_rt::test]
+async fn store_prune_fee_collector_test() {
+    let tmpdir = create_tmp_dir();
+    let mut store = sqlite_on_disk_store(tmpdir.path());
+    let scribe = Scribe::new_with_sample_data(2, 0);
+    let from = scribe.accounts[0];
+    let to = scribe.accounts[1];
+    let fee_collector = acc_id(100);
+    let fee = Tokens::from_e8s(10_000);
+
+    let mut blocks: Vec<HashedBlock> = scribe.blockchain.iter().cloned().collect();
+    let fee_collector_block_index = blocks.len() as u64;
+    let add_transfer = |store: &mut Blocks, blocks: &mut Vec<HashedBlock>| {
+        let parent = blocks.last().unwrap();
+        let index = parent.index + 1;
+        let block = Block::from_transaction(
+            Some(parent.hash),
+            Transaction {
+                operation: Operation::Transfer {
+                    from,
+                    to,
+                    amount: Tokens::from_e8s(1),
+                    fee,
+                    spender: None,
+                },
+                memo: Memo(index),
+                icrc1_memo: None,
+                created_at_time: None,
+            },
+            TimeStamp::from_nanos_since_unix_epoch(index),
+            fee,
+            Some(FeeCollector {
+                fee_collector,
+                block_index: (index != fee_collector_block_index)
+                    .then_some(fee_collector_block_index),
+            }),
+        );
+        let timestamp = block.timestamp;
+        let hb = HashedBlock::hash_block(block.encode(), Some(parent.hash), index, timestamp);
+        store.push(&hb).unwrap();
+        store.set_hashed_block_to_verified(&hb.index).unwrap();
+        blocks.push(hb);
+    };
+
+    for hb in &blocks {
+        store.push(hb).unwrap();
+        store.set_hashed_block_to_verified(&hb.index).unwrap();
+    }
+    for _ in 0..3 {
+        add_transfer(&mut store, &mut blocks);
+    }
+
+    // Prune the block that sets the fee collector.
+    let prune_at = fee_collector_block_index + 2;
+    store.prune(&blocks[prune_at as usize]).unwrap();
+    assert!(store.get_hashed_block(&fee_collector_block_index).is_err());
+
+    // The blocks after the pruning point still resolve their fee collector,
+    // also the ones added after pruning.
+    add_transfer(&mut store, &mut blocks);
+    for hb in &blocks[prune_at as usize..] {
+        let block = Block::decode(hb.block.clone()).unwrap();
+        assert_eq!(
+            block.fee_collector_block_index,
+            Some(fee_collector_block_index)
+        );
+        assert_eq!(
+            store.get_fee_collector(hb.index, &block).unwrap(),
+            Some(fee_collector)
+        );
+    }
+    let last_idx = blocks.last().unwrap().index;
+    assert_eq!(
+        store
+            .get_account_balance(&fee_collector, &last_idx)
+            .unwrap(),
+        Tokens::from_e8s(4 * fee.get_e8s())
+    );
+}
+
 fn prune(scribe: &Scribe, store: &mut Blocks, prune_at: u64) {
     let oldest_idx = prune_at;
     let oldest_block = scribe.blockchain.get(oldest_idx as usize).unwrap();
@@ -1,5 +1,6 @@
+use crate::ledger::{LedgerAccess, LedgerData};
 use crate::{runtime::Runtime, spawn};
-use candid::{CandidType, Encode};
+use candid::{CandidType, Encode, Nat, Principal};
 use ic_base_types::{CanisterId, PrincipalId};
 use ic_canister_log::{log, Sink};
 use ic_ic00_types::IC_00;
@@ -7,9 +8,11 @@ use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::collections::VecDeque;
 use std::marker::PhantomData;
+use std::ops::RangeInclusive;
 use std::sync::{Arc, RwLock};
 
-use ic_ledger_core::block::EncodedBlock;
+use ic_ledger_core::block::{BlockType, EncodedBlock};
+use ic_ledger_hash_of::HashOf;
 
 fn default_cycles_for_archive_creation() -> u64 {
     0
@@ -304,32 +307,58 @@ async fn create_and_initialize_node_canister<Rt: Runtime, Wasm: ArchiveCanisterW
     log_sink: impl Sink,
     archive: &Arc<RwLock<Option<Archive<Rt, Wasm>>>>,
 ) -> Result<(CanisterId, usize, u64), FailedToArchiveBlocks> {
-    log!(log_sink, "[archive] calling create_canister()");
+    let (node_block_height_offset, node_max_memory_size_bytes) =
+        inspect_archive(archive, |archive| {
+            let node_block_height_offset: u64 = archive
+                .nodes_block_ranges
+                .last()
+                .map(|(_, height_to)| *height_to + 1)
+                .unwrap_or(0);
+            (node_block_height_offset, archive.node_max_memory_size_bytes)
+        });
 
-    let (
-        cycles_for_archive_creation,
+    let node_canister_id = create_node_canister(
+        &log_sink,
+        archive,
         node_block_height_offset,
         node_max_memory_size_bytes,
-        controller_ids,
-        max_transactions_per_response,
-    ) = inspect_archive(archive, |archive| {
-        let node_block_height_offset: u64 = archive
-            .nodes_block_ranges
-            .last()
-            .map(|(_, height_to)| *height_to + 1)
-            .unwrap_or(0);
-        (
-            archive.cycles_for_archive_creation,
-            node_block_height_offset,
-            archive.node_max_memory_size_bytes,
-            vec![archive.controller_id]
-                .into_iter()
-                .chain(archive.more_controller_ids.clone().unwrap_or_default())
-                .collect(),
-            archive.max_transactions_per_response,
-        )
+    )
+    .await?;
+
+    let node_index = inspect_archive(archive, |archive| {
+        archive.nodes.push(node_canister_id);
+        archive.last_node_index()
     });
 
+    let (remaining_capacity,): (u64,) = Rt::call(node_canister_id, "remaining_capacity", 0, ())
+        .await
+        .map_err(|(_, msg)| FailedToArchiveBlocks(msg))?;
+
+    Ok((node_canister_id, node_index, remaining_capacity))
+}
+
+// Helper function to create a node canister serving blocks from the given offset.
+// The new node is not added to the archive index.
+async fn create_node_canister<Rt: Runtime, Wasm: ArchiveCanisterWasm>(
+    log_sink: &impl Sink,
+    archive: &Arc<RwLock<Option<Archive<Rt, Wasm>>>>,
+    node_block_height_offset: u64,
+    node_max_memory_size_bytes: u64,
+) -> Result<CanisterId, FailedToArchiveBlocks> {
+    log!(log_sink, "[archive] calling create_canister()");
+
+    let (cycles_for_archive_creation, controller_ids, max_transactions_per_response) =
+        inspect_archive(archive, |archive| {
+            (
+                archive.cycles_for_archive_creation,
+                vec![archive.controller_id]
+                    .into_iter()
+                    .chain(archive.more_controller_ids.clone().unwrap_or_default())
+                    .collect(),
+                archive.max_transactions_per_response,
+            )
+        });
+
     let node_canister_id: CanisterId = spawn::create_canister::<Rt>(cycles_for_archive_creation)
         .await
         .map_err(|(code, msg)| FailedToArchiveBlocks(format!("{} {}", code, msg)))?;
@@ -384,16 +413,7 @@ async fn create_and_initialize_node_canister<Rt: Runtime, Wasm: ArchiveCanisterW
         FailedToArchiveBlocks(s)
     })?;
 
-    let node_index = inspect_archive(archive, |archive| {
-        archive.nodes.push(node_canister_id);
-        archive.last_node_index()
-    });
-
-    let (remaining_capacity,): (u64,) = Rt::call(node_canister_id, "remaining_capacity", 0, ())
-        .await
-        .map_err(|(_, msg)| FailedToArchiveBlocks(msg))?;
-
-    Ok((node_canister_id, node_index, remaining_capacity))
+    Ok(node_canister_id)
 }
 
 /// Helper function to find the CanisterId of the node that can accept
@@ -474,3 +494,353 @@ fn take_prefix(blocks: &mut VecDeque<EncodedBlock>, mut max_size: u64) -> Vec<En
 /// This error type should only be returned in the case where an await has been
 /// passed but we do not think that the archive canister has received the blocks.
 pub struct FailedToArchiveBlocks(pub String);
+
+/// The arguments of the `get_encoded_blocks` endpoint of archive nodes.
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug)]
+struct GetEncodedBlocksArgs {
+    start: u64,
+    length: u64,
+}
+
+/// The outcome of a successful [consolidate_archive_nodes] call.
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct ConsolidatedArchiveNodes {
+    /// The node that stores the blocks of all the consolidated nodes.
+    pub node: CanisterId,
+    /// The inclusive range of blocks stored in the new node.
+    pub block_range: (u64, u64),
+    /// The nodes that were removed from the archive index.
+    /// They are not used anymore and their controllers can delete them.
+    pub retired_nodes: Vec<CanisterId>,
+}
+
+/// The arguments of the `consolidate_archives` endpoint of ledgers.
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct ConsolidateArchivesArgs {
+    /// The archives to merge, consecutive in the order returned by `archives`.
+    pub archive_ids: Vec<Principal>,
+    /// The capacity of the new archive in bytes. Defaults to the capacity of
+    /// the archives created by the ledger.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node_max_memory_size_bytes: Option<u64>,
+}
+
+/// The result of the `consolidate_archives` endpoint of ledgers.
+#[derive(Serialize, Deserialize, CandidType, Clone, Debug, PartialEq, Eq)]
+pub struct ConsolidatedArchives {
+    pub archive_id: Principal,
+    pub block_range_start: Nat,
+    pub block_range_end: Nat,
+    /// The archives that were replaced by the new archive. The ledger does
+    /// not use them anymore and their controllers can delete them.
+    pub retired_archive_ids: Vec<Principal>,
+}
+
+impl From<ConsolidatedArchiveNodes> for ConsolidatedArchives {
+    fn from(consolidated: ConsolidatedArchiveNodes) -> Self {
+        Self {
+            archive_id: consolidated.node.get().0,
+            block_range_start: Nat::from(consolidated.block_range.0),
+            block_range_end: Nat::from(consolidated.block_range.1),
+            retired_archive_ids: consolidated
+                .retired_nodes
+                .into_iter()
+                .map(|node| node.get().0)
+                .collect(),
+        }
+    }
+}
+
+/// The error returned when consolidating archive nodes fails.
+/// The archive index is only changed if the consolidation succeeds.
+pub struct FailedToConsolidateArchiveNodes(pub String);
+
+/// Copies the blocks of consecutive archive `nodes` into a single new node
+/// and replaces the nodes by the new node in the archive index.
+///
+/// The copied blocks must form a hash chain, including the links to the
+/// blocks of the neighbouring nodes or of the ledger, and must fit into the
+/// new node, otherwise the index is left unchanged. Both conditions are
+/// checked before the new node is created. The new node can hold up to
+/// `node_max_memory_size_bytes`, or the size of regular nodes if not set.
+pub async fn consolidate_archive_nodes<LA: LedgerAccess>(
+    log_sink: impl Sink + Clone,
+    nodes: Vec<CanisterId>,
+    node_max_memory_size_bytes: Option<u64>,
+    max_ledger_msg_size_bytes: u64,
+) -> Result<ConsolidatedArchiveNodes, FailedToConsolidateArchiveNodes> {
+    let archive = LA::with_ledger(|ledger| ledger.blockchain().archive.clone());
+
+    // NOTE: the guard prevents archiving from changing the index and the
+    // blocks of the ledger while the blocks are being copied.
+    let _archiving_guard = match ArchivingGuard::new(Arc::clone(&archive)) {
+        Ok(guard) => guard,
+        Err(ArchivingGuardError::NoArchive) => {
+            return Err(FailedToConsolidateArchiveNodes(
+                "archiving is not enabled".to_string(),
+            ))
+        }
+        Err(ArchivingGuardError::AlreadyArchiving) => {
+            return Err(FailedToConsolidateArchiveNodes(
+                "the ledger is archiving blocks, try again later".to_string(),
+            ))
+        }
+    };
+
+    let (node_indices, node_ranges, previous_block, next_block, max_chunk_size, max_memory) =
+        inspect_archive(&archive, |archive| {
+            let first = match nodes.first() {
+                Some(first) => archive.nodes.iter().position(|node| node == first),
+                None => None,
+            };
+            let node_indices = match first {
+                Some(first) if nodes.len() >= 2 => first..=first + nodes.len() - 1,
+                _ => {
+                    return Err(FailedToConsolidateArchiveNodes(
+                        "at least two archive nodes of the index are required".to_string(),
+                    ))
+                }
+            };
+            if archive.nodes.get(node_indices.clone()) != Some(&nodes[..]) {
+                return Err(FailedToConsolidateArchiveNodes(
+                    "the archive nodes must be consecutive in the archive index".to_string(),
+                ));
+            }
+            let node_ranges = match archive.nodes_block_ranges.get(node_indices.clone()) {
+                Some(node_ranges) => node_ranges.to_vec(),
+                None => {
+                    return Err(FailedToConsolidateArchiveNodes(
+                        "the last archive node does not store any blocks".to_string(),
+                    ))
+                }
+            };
+            // The blocks right before and after the consolidated range, if they are archived.
+            let previous_block = node_indices
+                .start()
+                .checked_sub(1)
+                .map(|i| (archive.nodes[i], archive.nodes_block_ranges[i].1));
+            let next_block = archive
+                .nodes_block_ranges
+                .get(node_indices.end() + 1)
+                .map(|(from, _)| (archive.nodes[node_indices.end() + 1], *from));
+            Ok((
+                node_indices,
+                node_ranges,
+                previous_block,
+                next_block,
+                archive
+                    .max_message_size_bytes
+                    .min(max_ledger_msg_size_bytes),
+                node_max_memory_size_bytes.unwrap_or(archive.node_max_memory_size_bytes),
+            ))
+        })?;
+    let block_range = (node_ranges[0].0, node_ranges[node_ranges.len() - 1].1);
+
+    let previous_hash = match previous_block {
+        Some((node, index)) => Some(<LA::Ledger as LedgerData>::Block::block_hash(
+            &fetch_encoded_block::<LA>(node, index).await?,
+        )),
+        None => None,
+    };
+
+    // Check the hash chain and the size of the blocks before creating the new
+    // node, so that a consolidation that cannot succeed leaves no node behind.
+    let mut parent_hash = previous_hash;
+    let mut size_bytes: u64 = 0;
+    for (node, (from, to)) in nodes.iter().zip(node_ranges.iter()) {
+        let mut next_index = *from;
+        while next_index <= *to {
+            let blocks =
+                fetch_chained_blocks::<LA>(*node, next_index..=*to, &mut parent_hash).await?;
+            next_index += blocks.len() as u64;
+            size_bytes += blocks
+                .iter()
+                .map(|block| block.size_bytes() as u64)
+                .sum::<u64>();
+        }
+    }
+    if size_bytes > max_memory {
+        return Err(FailedToConsolidateArchiveNodes(format!(
+            "the blocks of the archive nodes take {} bytes, which exceeds the capacity of {} bytes of the new node",
+            size_bytes, max_memory
+        )));
+    }
+    match next_block {
+        Some((node, index)) => {
+            let block = fetch_encoded_block::<LA>(node, index).await?;
+            check_parent_hash::<<LA::Ledger as LedgerData>::Block>(&block, parent_hash, index)
+                .map_err(FailedToConsolidateArchiveNodes)?;
+        }
+        // The last consolidated node is the last archive node, so the next
+        // block is stored in the ledger, if the ledger has any blocks.
+        None => LA::with_ledger(|ledger| {
+            let blockchain = ledger.blockchain();
+            let index = block_range.1 + 1;
+            match blockchain.get(index) {
+                Some(block) => check_parent_hash::<<LA::Ledger as LedgerData>::Block>(
+                    block,
+                    parent_hash,
+                    index,
+                ),
+                None if blockchain.last_hash == parent_hash => Ok(()),
+                None => Err(format!(
+                    "the hash of the last archived block is {:?}, expected {:?}",
+                    parent_hash, blockchain.last_hash
+                )),
+            }
+        })
+        .map_err(FailedToConsolidateArchiveNodes)?,
+    }
+
+    log!(
+        log_sink,
+        "[archive] consolidating archive nodes {:?} with blocks {:?} of {} bytes",
+        nodes,
+        block_range,
+        size_bytes
+    );
+    let new_node = create_node_canister(&log_sink, &archive, block_range.0, max_memory)
+        .await
+        .map_err(|FailedToArchiveBlocks(msg)| FailedToConsolidateArchiveNodes(msg))?;
+    let failed = |msg: String| {
+        FailedToConsolidateArchiveNodes(format!(
+            "{}; the new archive node {} is not used and can be deleted",
+            msg, new_node
+        ))
+    };
+
+    // The blocks are fetched again instead of being kept in memory, as
+    // archive nodes can hold more blocks than fit into the heap of the ledger.
+    let mut parent_hash = previous_hash;
+    for (node, (from, to)) in nodes.iter().zip(node_ranges.iter()) {
+        let mut next_index = *from;
+        while next_index <= *to {
+            let blocks = fetch_chained_blocks::<LA>(*node, next_index..=*to, &mut parent_hash)
+                .await
+                .map_err(|FailedToConsolidateArchiveNodes(msg)| failed(msg))?;
+            next_index += blocks.len() as u64;
+
+            let mut blocks: VecDeque<_> = blocks.into();
+            while !blocks.is_empty() {
+                let chunk = take_prefix(&mut blocks, max_chunk_size);
+                if chunk.is_empty() {
+                    return Err(failed("empty chunk".to_string()));
+                }
+                let result: Result<(), _> = <LA::Ledger as LedgerData>::Runtime::call(
+                    new_node,
+                    "append_blocks",
+                    0,
+                    (chunk,),
+                )
+                .await;
+                result.map_err(|(_, msg)| failed(msg))?;
+            }
+        }
+    }
+
+    inspect_archive(&archive, |archive| {
+        archive
+            .nodes
+            .splice(node_indices.clone(), std::iter::once(new_node));
+        archive
+            .nodes_block_ranges
+            .splice(node_indices, std::iter::once(block_range));
+    });
+    log!(
+        log_sink,
+        "[archive] replaced archive nodes {:?} with {}",
+        nodes,
+        new_node
+    );
+
+    Ok(ConsolidatedArchiveNodes {
+        node: new_node,
+        block_range,
+        retired_nodes: nodes,
+    })
+}
+
+/// Fetches the encoded blocks with indices in `range` from the archive `node`.
+/// The node can return fewer blocks than requested.
+async fn fetch_encoded_blocks<LA: LedgerAccess>(
+    node: CanisterId,
+    range: RangeInclusive<u64>,
+) -> Result<Vec<EncodedBlock>, FailedToConsolidateArchiveNodes> {
+    let args = GetEncodedBlocksArgs {
+        start: *range.start(),
+        length: range.end() - range.start() + 1,
+    };
+    let result: Result<(Result<Vec<EncodedBlock>, candid::Reserved>,), _> =
+        <LA::Ledger as LedgerData>::Runtime::call(node, "get_encoded_blocks", 0, (args,)).await;
+    match result {
+        Ok((Ok(blocks),)) => Ok(blocks),
+        Ok((Err(_),)) => Err(FailedToConsolidateArchiveNodes(format!(
+            "archive node {} does not serve blocks {:?}",
+            node, range
+        ))),
+        // Archive nodes installed by older ledger versions do not have the
+        // `get_encoded_blocks` endpoint.
+        Err((code, msg)) => Err(FailedToConsolidateArchiveNodes(format!(
+            "failed to fetch blocks {:?} from archive node {}, code {}: {}; archive nodes without \
+             the get_encoded_blocks endpoint have to be upgraded before they can be consolidated",
+            range, node, code, msg
+        ))),
+    }
+}
+
+async fn fetch_encoded_block<LA: LedgerAccess>(
+    node: CanisterId,
+    index: u64,
+) -> Result<EncodedBlock, FailedToConsolidateArchiveNodes> {
+    fetch_encoded_blocks::<LA>(node, index..=index)
+        .await?
+        .pop()
+        .ok_or_else(|| {
+            FailedToConsolidateArchiveNodes(format!(
+                "archive node {} returned no block at index {}",
+                node, index
+            ))
+        })
+}
+
+/// Fetches the next blocks with indices in `range` from the archive `node`
+/// and checks that they extend the hash chain ending with `parent_hash`,
+/// which is advanced to the hash of the last fetched block.
+async fn fetch_chained_blocks<LA: LedgerAccess>(
+    node: CanisterId,
+    range: RangeInclusive<u64>,
+    parent_hash: &mut Option<HashOf<EncodedBlock>>,
+) -> Result<Vec<EncodedBlock>, FailedToConsolidateArchiveNodes> {
+    let first_index = *range.start();
+    let blocks = fetch_encoded_blocks::<LA>(node, range).await?;
+    if blocks.is_empty() {
+        return Err(FailedToConsolidateArchiveNodes(format!(
+            "archive node {} returned no block at index {}",
+            node, first_index
+        )));
+    }
+    for (index, block) in (first_index..).zip(blocks.iter()) {
+        check_parent_hash::<<LA::Ledger as LedgerData>::Block>(block, *parent_hash, index)
+            .map_err(FailedToConsolidateArchiveNodes)?;
+        *parent_hash = Some(<LA::Ledger as LedgerData>::Block::block_hash(block));
+    }
+    Ok(blocks)
+}
+
+fn check_parent_hash<B: BlockType>(
+    block: &EncodedBlock,
+    expected_parent_hash: Option<HashOf<EncodedBlock>>,
+    index: u64,
+) -> Result<(), String> {
+    let block = B::decode(block.clone())
+        .map_err(|err| format!("failed to decode block {}: {}", index, err))?;
+    if block.parent_hash() != expected_parent_hash {
+        return Err(format!(
+            "the parent hash of block {} is {:?}, expected {:?}",
+            index,
+            block.parent_hash(),
+            expected_parent_hash
+        ));
+    }
+    Ok(())
+}
@@ -1,7 +1,6 @@
 use crate::timestamp::TimeStamp;
 use crate::tokens::{TokensType, Zero};
 use serde::{Deserialize, Serialize};
-use std::collections::btree_map::Entry;
 use std::collections::{BTreeMap, BTreeSet};
 use std::marker::PhantomData;
 
@@ -82,8 +81,70 @@ impl<Tokens: Zero> Default for Allowance<Tokens> {
     }
 }
 
+/// Storage backend of an [AllowanceTable].
+///
+/// Allowances are keyed by the `(account, spender)` pair. Besides the
+/// allowances themselves, the storage keeps two queues: the expiration queue
+/// ordered by the allowance expiration time, and the arrival queue ordered by
+/// the time the allowance was last set.
+pub trait AllowancesData {
+    type AccountId;
+    type Tokens;
+
+    fn get_allowance(
+        &self,
+        account_spender: &(Self::AccountId, Self::AccountId),
+    ) -> Option<Allowance<Self::Tokens>>;
+
+    fn set_allowance(
+        &mut self,
+        account_spender: (Self::AccountId, Self::AccountId),
+        allowance: Allowance<Self::Tokens>,
+    );
+
+    fn remove_allowance(&mut self, account_spender: &(Self::AccountId, Self::AccountId));
+
+    fn insert_expiry(
+        &mut self,
+        timestamp: TimeStamp,
+        account_spender: (Self::AccountId, Self::AccountId),
+    );
+
+    fn remove_expiry(
+        &mut self,
+        timestamp: TimeStamp,
+        account_spender: (Self::AccountId, Self::AccountId),
+    );
+
+    fn insert_arrival(
+        &mut self,
+        timestamp: TimeStamp,
+        account_spender: (Self::AccountId, Self::AccountId),
+    );
+
+    fn remove_arrival(
+        &mut self,
+        timestamp: TimeStamp,
+        account_spender: (Self::AccountId, Self::AccountId),
+    );
+
+    fn first_expiry(&self) -> Option<(TimeStamp, (Self::AccountId, Self::AccountId))>;
+
+    fn pop_first_expiry(&mut self) -> Option<(TimeStamp, (Self::AccountId, Self::AccountId))>;
+
+    /// Returns up to `n` (account, spender) pairs with the oldest arrival time.
+    fn oldest_arrivals(&self, n: usize) -> Vec<(Self::AccountId, Self::AccountId)>;
+
+    fn len_allowances(&self) -> usize;
+
+    fn len_expirations(&self) -> usize;
+
+    fn len_arrivals(&self) -> usize;
+}
+
+/// Allowances data kept in heap memory.
 #[derive(Serialize, Deserialize, Debug)]
-pub struct AllowanceTable<K, AccountId, Tokens>
+pub struct HeapAllowancesData<K, AccountId, Tokens>
 where
     K: Ord,
 {
@@ -96,37 +157,166 @@ where
     _marker: PhantomData<fn(&AccountId, &AccountId) -> K>,
 }
 
-impl<K: Ord, AccountId, Tokens> Default for AllowanceTable<K, AccountId, Tokens> {
+impl<K: Ord, AccountId, Tokens> Default for HeapAllowancesData<K, AccountId, Tokens> {
+    fn default() -> Self {
+        Self {
+            allowances: BTreeMap::new(),
+            expiration_queue: BTreeSet::new(),
+            arrival_queue: BTreeSet::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<K, AccountId, Tokens> HeapAllowancesData<K, AccountId, Tokens>
+where
+    K: Ord + Into<(AccountId, AccountId)>,
+{
+    /// Removes and returns the allowance with the smallest key.
+    pub fn pop_first_allowance(&mut self) -> Option<((AccountId, AccountId), Allowance<Tokens>)> {
+        self.allowances
+            .pop_first()
+            .map(|(key, allowance)| (key.into(), allowance))
+    }
+
+    /// Removes and returns the oldest entry of the arrival queue.
+    pub fn pop_first_arrival(&mut self) -> Option<(TimeStamp, (AccountId, AccountId))> {
+        self.arrival_queue
+            .pop_first()
+            .map(|(ts, key)| (ts, key.into()))
+    }
+}
+
+impl<K, AccountId, Tokens> AllowancesData for HeapAllowancesData<K, AccountId, Tokens>
+where
+    K: Ord + for<'a> From<(&'a AccountId, &'a AccountId)> + Clone,
+    K: Into<(AccountId, AccountId)>,
+    Tokens: Clone,
+{
+    type AccountId = AccountId;
+    type Tokens = Tokens;
+
+    fn get_allowance(
+        &self,
+        (account, spender): &(AccountId, AccountId),
+    ) -> Option<Allowance<Tokens>> {
+        self.allowances.get(&K::from((account, spender))).cloned()
+    }
+
+    fn set_allowance(
+        &mut self,
+        (account, spender): (AccountId, AccountId),
+        allowance: Allowance<Tokens>,
+    ) {
+        self.allowances
+            .insert(K::from((&account, &spender)), allowance);
+    }
+
+    fn remove_allowance(&mut self, (account, spender): &(AccountId, AccountId)) {
+        self.allowances.remove(&K::from((account, spender)));
+    }
+
+    fn insert_expiry(&mut self, timestamp: TimeStamp, (account, spender): (AccountId, AccountId)) {
+        self.expiration_queue
+            .insert((timestamp, K::from((&account, &spender))));
+    }
+
+    fn remove_expiry(&mut self, timestamp: TimeStamp, (account, spender): (AccountId, AccountId)) {
+        self.expiration_queue
+            .remove(&(timestamp, K::from((&account, &spender))));
+    }
+
+    fn insert_arrival(&mut self, timestamp: TimeStamp, (account, spender): (AccountId, AccountId)) {
+        self.arrival_queue
+            .insert((timestamp, K::from((&account, &spender))));
+    }
+
+    fn remove_arrival(&mut self, timestamp: TimeStamp, (account, spender): (AccountId, AccountId)) {
+        self.arrival_queue
+            .remove(&(timestamp, K::from((&account, &spender))));
+    }
+
+    fn first_expiry(&self) -> Option<(TimeStamp, (AccountId, AccountId))> {
+        self.expiration_queue
+            .first()
+            .map(|(ts, key)| (*ts, key.clone().into()))
+    }
+
+    fn pop_first_expiry(&mut self) -> Option<(TimeStamp, (AccountId, AccountId))> {
+        self.expiration_queue
+            .pop_first()
+            .map(|(ts, key)| (ts, key.into()))
+    }
+
+    fn oldest_arrivals(&self, n: usize) -> Vec<(AccountId, AccountId)> {
+        self.arrival_queue
+            .iter()
+            .take(n)
+            .map(|(_ts, key)| key.clone().into())
+            .collect()
+    }
+
+    fn len_allowances(&self) -> usize {
+        self.allowances.len()
+    }
+
+    fn len_expirations(&self) -> usize {
+        self.expiration_queue.len()
+    }
+
+    fn len_arrivals(&self) -> usize {
+        self.arrival_queue.len()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(transparent)]
+pub struct AllowanceTable<AD: AllowancesData> {
+    allowances_data: AD,
+}
+
+impl<AD> Default for AllowanceTable<AD>
+where
+    AD: AllowancesData + Default,
+{
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<K, AccountId, Tokens> AllowanceTable<K, AccountId, Tokens>
+impl<AD> AllowanceTable<AD>
 where
-    K: Ord,
+    AD: AllowancesData,
 {
-    pub fn new() -> Self {
+    pub fn new() -> Self
+    where
+        AD: Default,
+    {
         Self {
-            allowances: BTreeMap::new(),
-            expiration_queue: BTreeSet::new(),
-            arrival_queue: BTreeSet::new(),
-            _marker: PhantomData,
+            allowances_data: AD::default(),
         }
     }
 
+    pub fn allowances_data(&self) -> &AD {
+        &self.allowances_data
+    }
+
+    pub fn allowances_data_mut(&mut self) -> &mut AD {
+        &mut self.allowances_data
+    }
+
     fn check_postconditions(&self) {
         debug_assert!(
-            self.expiration_queue.len() <= self.allowances.len(),
+            self.allowances_data.len_expirations() <= self.allowances_data.len_allowances(),
             "expiration queue length ({}) larger than allowances length ({})",
-            self.expiration_queue.len(),
-            self.allowances.len()
+            self.allowances_data.len_expirations(),
+            self.allowances_data.len_allowances()
         );
         debug_assert!(
-            self.arrival_queue.len() == self.allowances.len(),
+            self.allowances_data.len_arrivals() == self.allowances_data.len_allowances(),
             "arrival_queue length ({}) should be equal to allowances length ({})",
-            self.arrival_queue.len(),
-            self.allowances.len()
+            self.allowances_data.len_arrivals(),
+            self.allowances_data.len_allowances()
         );
     }
 
@@ -137,26 +327,25 @@ where
     }
 }
 
-impl<K, AccountId, Tokens> Approvals for AllowanceTable<K, AccountId, Tokens>
+impl<AD> Approvals for AllowanceTable<AD>
 where
-    K: Ord + for<'a> From<(&'a AccountId, &'a AccountId)> + Clone,
-    K: Into<(AccountId, AccountId)>,
-    AccountId: std::cmp::PartialEq,
-    Tokens: TokensType,
+    AD: AllowancesData,
+    AD::AccountId: std::cmp::PartialEq + Clone,
+    AD::Tokens: TokensType,
 {
-    type AccountId = AccountId;
-    type Tokens = Tokens;
+    type AccountId = AD::AccountId;
+    type Tokens = AD::Tokens;
 
     fn allowance(
         &self,
-        account: &AccountId,
-        spender: &AccountId,
+        account: &Self::AccountId,
+        spender: &Self::AccountId,
         now: TimeStamp,
-    ) -> Allowance<Tokens> {
-        let key = K::from((account, spender));
-        match self.allowances.get(&key) {
+    ) -> Allowance<Self::Tokens> {
+        let key = (account.clone(), spender.clone());
+        match self.allowances_data.get_allowance(&key) {
             Some(allowance) if allowance.expires_at.unwrap_or_else(remote_future) > now => {
-                allowance.clone()
+                allowance
             }
             _ => Allowance::default(),
         }
@@ -164,13 +353,13 @@ where
 
     fn approve(
         &mut self,
-        account: &AccountId,
-        spender: &AccountId,
-        amount: Tokens,
+        account: &Self::AccountId,
+        spender: &Self::AccountId,
+        amount: Self::Tokens,
         expires_at: Option<TimeStamp>,
         now: TimeStamp,
-        expected_allowance: Option<Tokens>,
-    ) -> Result<Tokens, ApproveError<Tokens>> {
+        expected_allowance: Option<Self::Tokens>,
+    ) -> Result<Self::Tokens, ApproveError<Self::Tokens>> {
         self.with_postconditions_check(|table| {
             if account == spender {
                 return Err(ApproveError::SelfApproval);
@@ -180,66 +369,73 @@ where
                 return Err(ApproveError::ExpiredApproval { now });
             }
 
-            let key = K::from((account, spender));
+            let key = (account.clone(), spender.clone());
 
-            match table.allowances.entry(key.clone()) {
-                Entry::Vacant(e) => {
-                    if amount == Tokens::zero() {
+            match table.allowances_data.get_allowance(&key) {
+                None => {
+                    if amount == Self::Tokens::zero() {
                         return Ok(amount);
                     }
                     if let Some(expected_allowance) = expected_allowance {
                         if !expected_allowance.is_zero() {
                             return Err(ApproveError::AllowanceChanged {
-                                current_allowance: Tokens::zero(),
+                                current_allowance: Self::Tokens::zero(),
                             });
                         }
                     }
                     if let Some(expires_at) = expires_at {
-                        table.expiration_queue.insert((expires_at, key.clone()));
+                        table.allowances_data.insert_expiry(expires_at, key.clone());
                     }
-                    table.arrival_queue.insert((now, key));
-                    e.insert(Allowance {
-                        amount: amount.clone(),
-                        expires_at,
-                        arrived_at: now,
-                    });
+                    table.allowances_data.insert_arrival(now, key.clone());
+                    table.allowances_data.set_allowance(
+                        key,
+                        Allowance {
+                            amount: amount.clone(),
+                            expires_at,
+                            arrived_at: now,
+                        },
+                    );
                     Ok(amount)
                 }
-                Entry::Occupied(mut e) => {
-                    let allowance = e.get_mut();
+                Some(old_allowance) => {
                     if let Some(expected_allowance) = expected_allowance {
-                        if expected_allowance != allowance.amount {
+                        if expected_allowance != old_allowance.amount {
                             return Err(ApproveError::AllowanceChanged {
-                                current_allowance: allowance.amount.clone(),
+                                current_allowance: old_allowance.amount,
                             });
                         }
                     }
                     table
-                        .arrival_queue
-                        .remove(&(allowance.arrived_at, key.clone()));
-                    if amount == Tokens::zero() {
-                        if let Some(expires_at) = e.get().expires_at {
-                            table.expiration_queue.remove(&(expires_at, key.clone()));
+                        .allowances_data
+                        .remove_arrival(old_allowance.arrived_at, key.clone());
+                    if amount == Self::Tokens::zero() {
+                        if let Some(expires_at) = old_allowance.expires_at {
+                            table.allowances_data.remove_expiry(expires_at, key.clone());
                         }
-                        e.remove();
+                        table.allowances_data.remove_allowance(&key);
                         return Ok(amount);
                     }
-                    table.arrival_queue.insert((now, key.clone()));
-                    allowance.amount = amount;
-                    allowance.arrived_at = now;
-                    let old_expiration = std::mem::replace(&mut allowance.expires_at, expires_at);
+                    table.allowances_data.insert_arrival(now, key.clone());
 
-                    if expires_at != old_expiration {
-                        if let Some(old_expiration) = old_expiration {
+                    if expires_at != old_allowance.expires_at {
+                        if let Some(old_expiration) = old_allowance.expires_at {
                             table
-                                .expiration_queue
-                                .remove(&(old_expiration, key.clone()));
+                                .allowances_data
+                                .remove_expiry(old_expiration, key.clone());
                         }
                         if let Some(expires_at) = expires_at {
-                            table.expiration_queue.insert((expires_at, key));
+                            table.allowances_data.insert_expiry(expires_at, key.clone());
                         }
                     }
-                    Ok(e.get().amount.clone())
+                    table.allowances_data.set_allowance(
+                        key,
+                        Allowance {
+                            amount: amount.clone(),
+                            expires_at,
+                            arrived_at: now,
+                        },
+                    );
+                    Ok(amount)
                 }
             }
         })
@@ -247,23 +443,22 @@ where
 
     fn use_allowance(
         &mut self,
-        account: &AccountId,
-        spender: &AccountId,
-        amount: Tokens,
+        account: &Self::AccountId,
+        spender: &Self::AccountId,
+        amount: Self::Tokens,
         now: TimeStamp,
-    ) -> Result<Tokens, InsufficientAllowance<Tokens>> {
+    ) -> Result<Self::Tokens, InsufficientAllowance<Self::Tokens>> {
         self.with_postconditions_check(|table| {
-            let key = K::from((account, spender));
+            let key = (account.clone(), spender.clone());
 
-            match table.allowances.entry(key.clone()) {
-                Entry::Vacant(_) => Err(InsufficientAllowance(Tokens::zero())),
-                Entry::Occupied(mut e) => {
-                    if e.get().expires_at.unwrap_or_else(remote_future) <= now {
-                        Err(InsufficientAllowance(Tokens::zero()))
+            match table.allowances_data.get_allowance(&key) {
+                None => Err(InsufficientAllowance(Self::Tokens::zero())),
+                Some(mut allowance) => {
+                    if allowance.expires_at.unwrap_or_else(remote_future) <= now {
+                        Err(InsufficientAllowance(Self::Tokens::zero()))
                     } else {
-                        let allowance = e.get_mut();
                         if allowance.amount < amount {
-                            return Err(InsufficientAllowance(allowance.amount.clone()));
+                            return Err(InsufficientAllowance(allowance.amount));
                         }
                         allowance.amount = allowance
                             .amount
@@ -271,11 +466,15 @@ where
                             .expect("Underflow when using allowance");
                         let rest = allowance.amount.clone();
                         if rest.is_zero() {
-                            if let Some(expires_at) = e.get().expires_at {
-                                table.expiration_queue.remove(&(expires_at, key.clone()));
+                            if let Some(expires_at) = allowance.expires_at {
+                                table.allowances_data.remove_expiry(expires_at, key.clone());
                             }
-                            table.arrival_queue.remove(&(e.get().arrived_at, key));
-                            e.remove();
+                            table
+                                .allowances_data
+                                .remove_arrival(allowance.arrived_at, key.clone());
+                            table.allowances_data.remove_allowance(&key);
+                        } else {
+                            table.allowances_data.set_allowance(key, allowance);
                         }
                         Ok(rest)
                     }
@@ -285,28 +484,22 @@ where
     }
 
     fn select_approvals_to_trim(&self, n: usize) -> Vec<(Self::AccountId, Self::AccountId)> {
-        let mut result = vec![];
-        for (_expiration, key) in &self.arrival_queue {
-            if result.len() >= n {
-                break;
-            }
-            result.push(key.clone().into());
-        }
-        result
+        self.allowances_data.oldest_arrivals(n)
     }
 }
 
-impl<K, AccountId, Tokens> PrunableApprovals for AllowanceTable<K, AccountId, Tokens>
+impl<AD> PrunableApprovals for AllowanceTable<AD>
 where
-    K: Ord + Clone,
+    AD: AllowancesData,
+    AD::AccountId: Clone,
 {
     fn prune(&mut self, now: TimeStamp, limit: usize) -> usize {
         self.with_postconditions_check(|table| {
             let mut pruned = 0;
             for _ in 0..limit {
-                match table.expiration_queue.first() {
+                match table.allowances_data.first_expiry() {
                     Some((ts, _key)) => {
-                        if *ts > now {
+                        if ts > now {
                             return pruned;
                         }
                     }
@@ -314,13 +507,13 @@ where
                         return pruned;
                     }
                 }
-                if let Some((_, key)) = table.expiration_queue.pop_first() {
-                    if let Some(allowance) = table.allowances.get(&key) {
+                if let Some((_, key)) = table.allowances_data.pop_first_expiry() {
+                    if let Some(allowance) = table.allowances_data.get_allowance(&key) {
                         if allowance.expires_at.unwrap_or_else(remote_future) <= now {
                             table
-                                .arrival_queue
-                                .remove(&(allowance.arrived_at, key.clone()));
-                            table.allowances.remove(&key);
+                                .allowances_data
+                                .remove_arrival(allowance.arrived_at, key.clone());
+                            table.allowances_data.remove_allowance(&key);
                             pruned += 1;
                         }
                     }
@@ -331,7 +524,7 @@ where
     }
 
     fn len(&self) -> usize {
-        self.allowances.len()
+        self.allowances_data.len_allowances()
     }
 }
 
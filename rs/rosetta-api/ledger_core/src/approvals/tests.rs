@@ -14,7 +14,7 @@ fn tokens(n: u64) -> Tokens {
     Tokens::from_e8s(n)
 }
 
-#[derive(PartialEq, Eq, Hash)]
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct Account(u64);
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -32,7 +32,7 @@ impl From<Key> for (Account, Account) {
     }
 }
 
-type TestAllowanceTable = AllowanceTable<Key, Account, Tokens>;
+type TestAllowanceTable = AllowanceTable<HeapAllowancesData<Key, Account, Tokens>>;
 
 #[test]
 fn allowance_table_default() {
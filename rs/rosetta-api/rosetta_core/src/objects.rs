@@ -258,6 +258,82 @@ impl ::std::str::FromStr for Direction {
     }
 }
 
+/// Coin contains its unique identifier and the amount it represents.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGeneric))]
+pub struct Coin {
+    /// CoinIdentifier uniquely identifies a Coin.
+    pub coin_identifier: CoinIdentifier,
+
+    /// Amount is some Value of a Currency. It is considered invalid to specify a Value without a Currency.
+    pub amount: Amount,
+}
+
+impl Coin {
+    pub fn new(coin_identifier: CoinIdentifier, amount: Amount) -> Coin {
+        Coin {
+            coin_identifier,
+            amount,
+        }
+    }
+}
+
+/// Operator is used by query-related endpoints to determine how to apply conditions.
+/// If this field is not populated, the default and value will be used.
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGenericEnum))]
+pub enum Operator {
+    /// If any condition is satisfied, it is considered a match.
+    #[serde(rename = "or")]
+    Or,
+
+    /// If all conditions are satisfied, it is considered a match.
+    #[serde(rename = "and")]
+    And,
+}
+
+impl ::std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        match *self {
+            Operator::Or => write!(f, "or"),
+            Operator::And => write!(f, "and"),
+        }
+    }
+}
+
+impl ::std::str::FromStr for Operator {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "or" => Ok(Operator::Or),
+            "and" => Ok(Operator::And),
+            _ => Err(()),
+        }
+    }
+}
+
+/// BlockTransaction contains a populated Transaction and the BlockIdentifier that contains it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGeneric))]
+pub struct BlockTransaction {
+    /// The block_identifier uniquely identifies a block in a particular network.
+    pub block_identifier: BlockIdentifier,
+
+    /// Transactions contain an array of Operations that are attributable to the same TransactionIdentifier.
+    pub transaction: Transaction,
+}
+
+impl BlockTransaction {
+    pub fn new(block_identifier: BlockIdentifier, transaction: Transaction) -> BlockTransaction {
+        BlockTransaction {
+            block_identifier,
+            transaction,
+        }
+    }
+}
+
 /// Transactions contain an array of Operations that are attributable to the
 /// same TransactionIdentifier.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
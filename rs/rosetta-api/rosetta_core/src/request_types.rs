@@ -88,6 +88,152 @@ impl BlockTransactionRequest {
     }
 }
 
+/// An AccountBalanceRequest is utilized to make a balance request on the
+/// /account/balance endpoint. If the block_identifier is populated, a
+/// historical balance query should be performed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGeneric))]
+pub struct AccountBalanceRequest {
+    /// The network_identifier specifies which network a particular object is associated with.
+    pub network_identifier: NetworkIdentifier,
+
+    /// The account_identifier uniquely identifies an account within a network.
+    pub account_identifier: AccountIdentifier,
+
+    /// When fetching data by BlockIdentifier, it may be possible to only specify the index or hash. If neither property is specified, it is assumed that the client is making a request at the current block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub block_identifier: Option<PartialBlockIdentifier>,
+
+    /// In some cases, the caller may not want to retrieve all available balances for an AccountIdentifier. If the currencies field is populated, only balances for the specified currencies will be returned. If not populated, all available balances will be returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currencies: Option<Vec<Currency>>,
+}
+
+impl AccountBalanceRequest {
+    pub fn new(
+        network_identifier: NetworkIdentifier,
+        account_identifier: AccountIdentifier,
+        block_identifier: Option<PartialBlockIdentifier>,
+    ) -> AccountBalanceRequest {
+        AccountBalanceRequest {
+            network_identifier,
+            account_identifier,
+            block_identifier,
+            currencies: None,
+        }
+    }
+}
+
+/// AccountCoinsRequest is utilized to make a request on the /account/coins endpoint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGeneric))]
+pub struct AccountCoinsRequest {
+    /// The network_identifier specifies which network a particular object is associated with.
+    pub network_identifier: NetworkIdentifier,
+
+    /// The account_identifier uniquely identifies an account within a network.
+    pub account_identifier: AccountIdentifier,
+
+    /// Include state from the mempool when looking up an account's unspent coins. Note, using this functionality breaks any guarantee of idempotency.
+    pub include_mempool: bool,
+
+    /// In some cases, the caller may not want to retrieve coins for all currencies for an AccountIdentifier. If the currencies field is populated, only coins for the specified currencies will be returned. If not populated, all unspent coins will be returned.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currencies: Option<Vec<Currency>>,
+}
+
+impl AccountCoinsRequest {
+    pub fn new(
+        network_identifier: NetworkIdentifier,
+        account_identifier: AccountIdentifier,
+    ) -> AccountCoinsRequest {
+        AccountCoinsRequest {
+            network_identifier,
+            account_identifier,
+            include_mempool: false,
+            currencies: None,
+        }
+    }
+}
+
+/// SearchTransactionsRequest is used to search for transactions matching a set
+/// of provided conditions in canonical blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "conversion", derive(LabelledGeneric))]
+pub struct SearchTransactionsRequest {
+    /// The network_identifier specifies which network a particular object is associated with.
+    pub network_identifier: NetworkIdentifier,
+
+    /// Operator is used by query-related endpoints to determine how to apply conditions. If this field is not populated, the default and value will be used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<Operator>,
+
+    /// max_block is the largest block index to consider when searching for transactions. If this field is not populated, the current block is considered the max_block. If you do not specify a max_block, it is possible a newly synced block will interfere with paginated transaction queries (as the offset could become invalid with newly added rows).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_block: Option<i64>,
+
+    /// offset is the offset into the query result to start returning transactions. If any search conditions are changed, the query offset will change and you must restart your search iteration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<i64>,
+
+    /// limit is the maximum number of transactions to return in one call. The implementation may return <= limit transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i64>,
+
+    /// The transaction_identifier uniquely identifies a transaction in a particular network and block or in the mempool.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_identifier: Option<TransactionIdentifier>,
+
+    /// The account_identifier uniquely identifies an account within a network.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_identifier: Option<AccountIdentifier>,
+
+    /// CoinIdentifier uniquely identifies a Coin.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coin_identifier: Option<CoinIdentifier>,
+
+    /// Currency is composed of a canonical Symbol and Decimals.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<Currency>,
+
+    /// status is the network-specific operation type.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// type is the network-specific operation type.
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub _type: Option<String>,
+
+    /// address is AccountIdentifier.Address. This is used to get all transactions related to an AccountIdentifier.Address, regardless of SubAccountIdentifier.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+
+    /// success is a synthetic condition populated by parsing network-specific operation statuses (using the mapping provided in /network/options).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+}
+
+impl SearchTransactionsRequest {
+    pub fn new(network_identifier: NetworkIdentifier) -> SearchTransactionsRequest {
+        SearchTransactionsRequest {
+            network_identifier,
+            operator: None,
+            max_block: None,
+            offset: None,
+            limit: None,
+            transaction_identifier: None,
+            account_identifier: None,
+            coin_identifier: None,
+            currency: None,
+            status: None,
+            _type: None,
+            address: None,
+            success: None,
+        }
+    }
+}
+
 /// A MempoolTransactionRequest is utilized to retrieve a transaction from the
 /// mempool.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
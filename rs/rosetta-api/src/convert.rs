@@ -2,9 +2,9 @@ mod state;
 
 use crate::convert::state::State;
 use crate::errors::ApiError;
-use crate::models::amount::{from_amount, ledgeramount_from_amount};
+use crate::models::amount::{from_amount, ledgeramount_from_amount, signed_amount};
 use crate::models::operation::OperationType;
-use crate::models::{self, AccountIdentifier, BlockIdentifier, Operation};
+use crate::models::{self, AccountIdentifier, BlockIdentifier, Operation, OperationIdentifier};
 use crate::request::request_result::RequestResult;
 use crate::request::transaction_operation_results::TransactionOperationResults;
 use crate::request::transaction_results::TransactionResults;
@@ -34,8 +34,10 @@ use std::convert::{TryFrom, TryInto};
 /// This module converts from ledger_canister data structures to Rosetta data
 /// structures
 
+/// The `fee_collector` is the account that received the fee of the block, if any.
 pub fn block_to_transaction(
     hb: &HashedBlock,
+    fee_collector: Option<icp_ledger::AccountIdentifier>,
     token_name: &str,
 ) -> Result<models::Transaction, ApiError> {
     let block = Block::decode(hb.block.clone())
@@ -44,7 +46,23 @@ pub fn block_to_transaction(
     let transaction_identifier = TransactionIdentifier::from(&transaction);
     let operation = transaction.operation;
     let operations = {
-        let mut ops = Request::requests_to_operations(&[Request::Transfer(operation)], token_name)?;
+        let mut ops =
+            Request::requests_to_operations(&[Request::Transfer(operation.clone())], token_name)?;
+        // The fee of a transfer is credited to the fee collector instead of being burned.
+        if let (LedgerOperation::Transfer { fee, .. }, Some(fee_collector)) =
+            (operation, fee_collector)
+        {
+            ops.push(Operation {
+                operation_identifier: OperationIdentifier::new(ops.len() as u64),
+                _type: OperationType::Fee.to_string(),
+                status: None,
+                account: Some(to_model_account_identifier(&fee_collector)),
+                amount: Some(signed_amount(i128::from(fee.get_e8s()), token_name)),
+                related_operations: None,
+                coin_change: None,
+                metadata: None,
+            });
+        }
         for op in ops.iter_mut() {
             op.status = Some(STATUS_COMPLETED.to_string());
         }
@@ -216,6 +216,7 @@ impl RosettaRequestHandler {
 
         let transactions = vec![convert::block_to_transaction(
             &hb,
+            get_fee_collector(&blocks, &hb)?,
             self.ledger.token_symbol(),
         )?];
         let block = Some(models::Block::new(
@@ -245,7 +246,11 @@ impl RosettaRequestHandler {
             hash: Some(msg.block_identifier.hash),
         });
         let hb = get_block(&blocks, b_id)?;
-        let transaction = convert::block_to_transaction(&hb, self.ledger.token_symbol())?;
+        let transaction = convert::block_to_transaction(
+            &hb,
+            get_fee_collector(&blocks, &hb)?,
+            self.ledger.token_symbol(),
+        )?;
         Ok(BlockTransactionResponse::new(transaction))
     }
 
@@ -433,7 +438,11 @@ impl RosettaRequestHandler {
         for hb in block_range.into_iter().rev() {
             txs.push(BlockTransaction::new(
                 convert::block_id(&hb)?,
-                convert::block_to_transaction(&hb, self.ledger.token_symbol())?,
+                convert::block_to_transaction(
+                    &hb,
+                    get_fee_collector(&blocks, &hb)?,
+                    self.ledger.token_symbol(),
+                )?,
             ));
         }
 
@@ -582,7 +591,11 @@ impl RosettaRequestHandler {
                 let hb = blocks.get_hashed_block(&i)?;
                 txs.push(BlockTransaction::new(
                     convert::block_id(&hb)?,
-                    convert::block_to_transaction(&hb, self.ledger.token_symbol())?,
+                    convert::block_to_transaction(
+                        &hb,
+                        get_fee_collector(&blocks, &hb)?,
+                        self.ledger.token_symbol(),
+                    )?,
                 ));
             } else {
                 return Err(ApiError::InvalidBlockId(true, Default::default()));
@@ -648,6 +661,15 @@ fn block_height_to_index(height: BlockIndex) -> i128 {
     i128::from(height)
 }
 
+fn get_fee_collector(
+    blocks: &Blocks,
+    hb: &HashedBlock,
+) -> Result<Option<icp_ledger::AccountIdentifier>, ApiError> {
+    let block = Block::decode(hb.block.clone())
+        .map_err(|err| ApiError::internal_error(format!("Cannot decode block: {}", err)))?;
+    Ok(blocks.get_fee_collector(hb.index, &block)?)
+}
+
 fn get_block(
     blocks: &Blocks,
     block_id: Option<PartialBlockIdentifier>,
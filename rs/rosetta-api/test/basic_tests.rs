@@ -365,6 +365,7 @@ async fn blocks_test() {
         let resp = req_handler.block(msg).await.unwrap();
         let transactions = vec![ic_rosetta_api::convert::block_to_transaction(
             &block,
+            None,
             ic_rosetta_api::DEFAULT_TOKEN_SYMBOL,
         )
         .unwrap()];
@@ -658,6 +658,12 @@ pub fn caller() -> PrincipalId {
     PrincipalId::try_from(bytes).unwrap()
 }
 
+/// Returns true if the given principal is a controller of this canister.
+pub fn is_controller(principal_id: &PrincipalId) -> bool {
+    let bytes = principal_id.as_slice();
+    unsafe { ic0::is_controller(bytes.as_ptr() as u32, bytes.len() as u32) != 0 }
+}
+
 /// Returns this canister's id as a blob.
 pub fn id() -> CanisterId {
     let len: u32 = unsafe { ic0::canister_self_size() };
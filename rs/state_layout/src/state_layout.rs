@@ -3,7 +3,7 @@ use crate::utils::do_copy;
 
 use ic_base_types::{NumBytes, NumSeconds};
 use ic_config::flag_status::FlagStatus;
-use ic_ic00_types::LogVisibility;
+use ic_ic00_types::{LogVisibility, TimerPriority};
 use ic_logger::{error, info, warn, ReplicaLogger};
 use ic_metrics::{buckets::decimal_buckets, MetricsRegistry};
 use ic_protobuf::{
@@ -164,6 +164,7 @@ pub struct CanisterStateBits {
     pub wasm_chunk_store_metadata: WasmChunkStoreMetadata,
     pub total_query_stats: TotalQueryStats,
     pub log_visibility: LogVisibility,
+    pub timer_priority: TimerPriority,
 }
 
 /// This struct contains bits of the `CanisterSnapshot` that are not already
@@ -1790,6 +1791,7 @@ impl From<CanisterStateBits> for pb_canister_state_bits::CanisterStateBits {
             wasm_chunk_store_metadata: Some((&item.wasm_chunk_store_metadata).into()),
             total_query_stats: Some((&item.total_query_stats).into()),
             log_visibility: item.log_visibility.into(),
+            timer_priority: item.timer_priority.into(),
         }
     }
 }
@@ -1910,6 +1912,7 @@ impl TryFrom<pb_canister_state_bits::CanisterStateBits> for CanisterStateBits {
             )
             .unwrap_or_default(),
             log_visibility: LogVisibility::from(value.log_visibility),
+            timer_priority: TimerPriority::from(value.timer_priority),
         })
     }
 }
@@ -53,6 +53,7 @@ fn default_canister_state_bits() -> CanisterStateBits {
         wasm_chunk_store_metadata: WasmChunkStoreMetadata::default(),
         total_query_stats: TotalQueryStats::default(),
         log_visibility: LogVisibility::default(),
+        timer_priority: TimerPriority::default(),
     }
 }
 
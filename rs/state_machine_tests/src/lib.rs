@@ -1,5 +1,14 @@
 use candid::Decode;
 use core::sync::atomic::Ordering;
+#[cfg(feature = "bitcoin")]
+use ic_btc_adapter_client::setup_bitcoin_adapter_clients;
+#[cfg(feature = "bitcoin")]
+use ic_btc_consensus::BitcoinPayloadBuilder;
+use ic_btc_types_internal::BitcoinAdapterResponse;
+#[cfg(feature = "bitcoin")]
+use ic_config::adapters::AdaptersConfig;
+#[cfg(feature = "bitcoin")]
+use ic_config::bitcoin_payload_builder_config::Config as BitcoinPayloadBuilderConfig;
 use ic_config::flag_status::FlagStatus;
 use ic_config::{execution_environment::Config as HypervisorConfig, subnet_config::SubnetConfig};
 use ic_consensus::consensus::payload_builder::PayloadBuilderImpl;
@@ -29,6 +38,8 @@ use ic_ingress_manager::{CustomRandomState, IngressManager};
 use ic_interfaces::ingress_pool::{
     IngressPool, PoolSection, UnvalidatedIngressArtifact, ValidatedIngressArtifact,
 };
+#[cfg(feature = "bitcoin")]
+use ic_interfaces::self_validating_payload::SelfValidatingPayloadBuilder;
 use ic_interfaces::{
     certification::{Verifier, VerifierError},
     consensus::PayloadBuilder as ConsensusPayloadBuilder,
@@ -90,7 +101,7 @@ use ic_test_utilities_time::FastForwardTimeSource;
 use ic_types::artifact::IngressMessageId;
 use ic_types::batch::{BlockmakerMetrics, QueryStatsPayload, TotalQueryStats, ValidationContext};
 pub use ic_types::canister_http::{CanisterHttpMethod, CanisterHttpRequestContext};
-use ic_types::consensus::block_maker::SubnetRecords;
+use ic_types::consensus::block_maker::{SubnetLoad, SubnetRecords};
 use ic_types::consensus::certification::CertificationContent;
 use ic_types::crypto::threshold_sig::ni_dkg::{NiDkgId, NiDkgTag, NiDkgTargetSubnet};
 pub use ic_types::crypto::threshold_sig::ThresholdSigPublicKey;
@@ -117,6 +128,11 @@ use ic_types::{
     CountBytes, CryptoHashOfPartialState, Height, NodeId, NumberOfNodes, Randomness,
     RegistryVersion,
 };
+#[cfg(feature = "bitcoin")]
+use ic_types::{
+    batch::{SelfValidatingPayload, MAX_BITCOIN_PAYLOAD_IN_BYTES},
+    NumBytes,
+};
 pub use ic_types::{
     ingress::{IngressState, IngressStatus, WasmResult},
     messages::{HttpRequestError, MessageId},
@@ -138,7 +154,7 @@ pub use slog::Level;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::io::stderr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::string::ToString;
 use std::sync::Arc;
@@ -570,6 +586,8 @@ pub struct StateMachine {
     ingress_manager: Arc<IngressManager>,
     ingress_filter: Arc<dyn IngressFilter<State = ReplicatedState>>,
     payload_builder: Arc<RwLock<Option<PayloadBuilderImpl>>>,
+    #[cfg(feature = "bitcoin")]
+    bitcoin_payload_builder: Option<BitcoinPayloadBuilder>,
     message_routing: SyncMessageRouting,
     metrics_registry: MetricsRegistry,
     ingress_history_reader: Box<dyn IngressHistoryReader>,
@@ -620,6 +638,7 @@ pub struct StateMachineBuilder {
     features: SubnetFeatures,
     runtime: Option<Arc<Runtime>>,
     registry_data_provider: Arc<ProtoRegistryDataProvider>,
+    bitcoin_testnet_uds_path: Option<PathBuf>,
     lsmt_override: Option<FlagStatus>,
     public_key: ThresholdSigPublicKey,
     secret_key: SecretKeyBytes,
@@ -676,6 +695,7 @@ impl StateMachineBuilder {
             },
             runtime: None,
             registry_data_provider: Arc::new(ProtoRegistryDataProvider::new()),
+            bitcoin_testnet_uds_path: None,
             lsmt_override: None,
             public_key,
             secret_key,
@@ -818,6 +838,23 @@ impl StateMachineBuilder {
         }
     }
 
+    /// Connects the state machine to the Bitcoin adapter listening on the given
+    /// socket. The adapter serves the requests of the Bitcoin canister for the
+    /// testnet and regtest networks, which are answered on every [StateMachine::tick]
+    /// and [StateMachine::execute_round].
+    ///
+    /// The adapter client runs on the state machine's runtime, which must therefore
+    /// be a multi-threaded runtime with IO enabled (see [Self::with_runtime]).
+    ///
+    /// Requires the `bitcoin` feature.
+    #[cfg(feature = "bitcoin")]
+    pub fn with_bitcoin_testnet_uds_path(self, bitcoin_testnet_uds_path: PathBuf) -> Self {
+        Self {
+            bitcoin_testnet_uds_path: Some(bitcoin_testnet_uds_path),
+            ..self
+        }
+    }
+
     pub fn with_subnet_key_seed(self, seed: [u8; 32]) -> Self {
         let (own_subnet_id, public_key, secret_key) =
             Self::compute_subnet_id_and_key_from_seed(seed);
@@ -872,6 +909,7 @@ impl StateMachineBuilder {
             }),
             registry_version,
             self.registry_data_provider,
+            self.bitcoin_testnet_uds_path,
             self.lsmt_override,
             self.public_key,
             self.secret_key,
@@ -988,6 +1026,7 @@ impl StateMachine {
             &[], // Because the latest state is certified, we do not need to provide any `past_payloads`.
             &validation_context,
             &subnet_records,
+            &SubnetLoad::default(),
         );
 
         // Convert payload produced by `PayloadBuilderImpl` into `PayloadBuilder`
@@ -999,7 +1038,8 @@ impl StateMachine {
             .collect();
         let mut payload = PayloadBuilder::new()
             .with_ingress_messages(ingress_messages)
-            .with_xnet_payload(xnet_payload);
+            .with_xnet_payload(xnet_payload)
+            .with_bitcoin_adapter_responses(self.get_bitcoin_adapter_responses());
 
         // Push responses to ECDSA management canister calls into `PayloadBuilder`.
         let sign_with_ecdsa_contexts = state
@@ -1063,6 +1103,7 @@ impl StateMachine {
         runtime: Arc<Runtime>,
         registry_version: RegistryVersion,
         registry_data_provider: Arc<ProtoRegistryDataProvider>,
+        bitcoin_testnet_uds_path: Option<PathBuf>,
         lsmt_override: Option<FlagStatus>,
         public_key: ThresholdSigPublicKey,
         secret_key: SecretKeyBytes,
@@ -1206,6 +1247,36 @@ impl StateMachine {
             },
         );
 
+        // Requests to the Bitcoin adapter are made by the same payload builder as on
+        // a replica. The mainnet adapter is never connected.
+        #[cfg(not(feature = "bitcoin"))]
+        assert!(
+            bitcoin_testnet_uds_path.is_none(),
+            "Connecting to a Bitcoin adapter requires the `bitcoin` feature"
+        );
+        #[cfg(feature = "bitcoin")]
+        let bitcoin_payload_builder = bitcoin_testnet_uds_path.map(|uds_path| {
+            let adapter_clients = setup_bitcoin_adapter_clients(
+                replica_logger.clone(),
+                &metrics_registry,
+                runtime.handle().clone(),
+                AdaptersConfig {
+                    bitcoin_testnet_uds_path: Some(uds_path),
+                    ..AdaptersConfig::default()
+                },
+            );
+            BitcoinPayloadBuilder::new(
+                state_manager.clone(),
+                &metrics_registry,
+                adapter_clients.btc_mainnet_client,
+                adapter_clients.btc_testnet_client,
+                subnet_id,
+                registry_client.clone(),
+                BitcoinPayloadBuilderConfig::default(),
+                replica_logger.clone(),
+            )
+        });
+
         let time_source = FastForwardTimeSource::new();
         time_source.set_time(time).unwrap();
         let consensus_time = Arc::new(PocketConsensusTime::new(time));
@@ -1242,6 +1313,8 @@ impl StateMachine {
             ingress_manager: ingress_manager.clone(),
             ingress_filter: execution_services.sync_ingress_filter,
             payload_builder: Arc::new(RwLock::new(None)), // set by `StateMachineBuilder::build_with_subnets`
+            #[cfg(feature = "bitcoin")]
+            bitcoin_payload_builder,
             ingress_history_reader: execution_services.ingress_history_reader,
             message_routing,
             metrics_registry,
@@ -1537,7 +1610,8 @@ impl StateMachine {
     /// Triggers a single round of execution without any new inputs.  The state
     /// machine will invoke heartbeats and make progress on pending async calls.
     pub fn tick(&self) {
-        let mut payload = PayloadBuilder::default();
+        let mut payload = PayloadBuilder::default()
+            .with_bitcoin_adapter_responses(self.get_bitcoin_adapter_responses());
         let state = self.state_manager.get_latest_state().take();
         let sign_with_ecdsa_contexts = state
             .metadata
@@ -1574,6 +1648,33 @@ impl StateMachine {
         self.execute_payload(payload);
     }
 
+    /// Returns the responses of the Bitcoin adapter to the outstanding requests of
+    /// the Bitcoin canister in the latest state. Returns no responses if the state
+    /// machine is not connected to a Bitcoin adapter.
+    #[cfg(feature = "bitcoin")]
+    fn get_bitcoin_adapter_responses(&self) -> Vec<BitcoinAdapterResponse> {
+        let Some(bitcoin_payload_builder) = &self.bitcoin_payload_builder else {
+            return vec![];
+        };
+        let validation_context = ValidationContext {
+            time: self.get_time(),
+            registry_version: self.registry_client.get_latest_version(),
+            certified_height: self.state_manager.latest_state_height(),
+        };
+        let past_payloads: &[&SelfValidatingPayload] = &[];
+        let (payload, _) = bitcoin_payload_builder.get_self_validating_payload(
+            &validation_context,
+            past_payloads,
+            NumBytes::new(MAX_BITCOIN_PAYLOAD_IN_BYTES),
+        );
+        payload.get().to_vec()
+    }
+
+    #[cfg(not(feature = "bitcoin"))]
+    fn get_bitcoin_adapter_responses(&self) -> Vec<BitcoinAdapterResponse> {
+        vec![]
+    }
+
     /// Makes the state machine tick until there are no more messages in the system.
     /// This method is useful if you need to wait for asynchronous canister communication to
     /// complete.
@@ -1618,7 +1719,7 @@ impl StateMachine {
             messages: BatchMessages {
                 signed_ingress_msgs: payload.ingress_messages,
                 certified_stream_slices: payload.xnet_payload.stream_slices,
-                bitcoin_adapter_responses: vec![],
+                bitcoin_adapter_responses: payload.bitcoin_adapter_responses,
                 query_stats: payload.query_stats,
             },
             randomness: Randomness::from(seed),
@@ -2711,6 +2812,7 @@ pub struct PayloadBuilder {
     ingress_messages: Vec<SignedIngress>,
     xnet_payload: XNetPayload,
     consensus_responses: Vec<Response>,
+    bitcoin_adapter_responses: Vec<BitcoinAdapterResponse>,
     query_stats: Option<QueryStatsPayload>,
 }
 
@@ -2722,6 +2824,7 @@ impl Default for PayloadBuilder {
             ingress_messages: Default::default(),
             xnet_payload: Default::default(),
             consensus_responses: Default::default(),
+            bitcoin_adapter_responses: Default::default(),
             query_stats: Default::default(),
         }
         .with_max_expiry_time_from_now(GENESIS.into())
@@ -2764,6 +2867,16 @@ impl PayloadBuilder {
         }
     }
 
+    pub fn with_bitcoin_adapter_responses(
+        self,
+        bitcoin_adapter_responses: Vec<BitcoinAdapterResponse>,
+    ) -> Self {
+        Self {
+            bitcoin_adapter_responses,
+            ..self
+        }
+    }
+
     pub fn with_query_stats(self, query_stats: Option<QueryStatsPayload>) -> Self {
         Self {
             query_stats,
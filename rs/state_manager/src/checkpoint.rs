@@ -410,6 +410,7 @@ pub fn load_canister_state<P: ReadPolicy>(
         wasm_chunk_store_data,
         canister_state_bits.wasm_chunk_store_metadata,
         canister_state_bits.log_visibility,
+        canister_state_bits.timer_priority,
     );
 
     let canister_state = CanisterState {
@@ -995,6 +995,7 @@ fn serialize_canister_to_tip(
                 .clone(),
             total_query_stats: canister_state.scheduler_state.total_query_stats.clone(),
             log_visibility: canister_state.system_state.log_visibility,
+            timer_priority: canister_state.system_state.timer_priority,
         }
         .into(),
     )?;
@@ -4,7 +4,8 @@ use ic_interfaces_registry::LocalStoreCertifiedTimeReader;
 use ic_protobuf::registry::crypto::v1::AlgorithmId;
 use ic_protobuf::registry::crypto::v1::PublicKey as PublicKeyProto;
 use ic_protobuf::registry::subnet::v1::{
-    CatchUpPackageContents, InitialNiDkgTranscriptRecord, SubnetListRecord, SubnetRecord,
+    AdaptivePayloadSizingConfig, CatchUpPackageContents, InitialNiDkgTranscriptRecord,
+    SubnetListRecord, SubnetRecord,
 };
 use ic_registry_client_fake::FakeRegistryClient;
 use ic_registry_keys::{
@@ -210,6 +211,8 @@ pub fn test_subnet_record() -> SubnetRecord {
         subnet_type: SubnetType::Application.into(),
         is_halted: false,
         halt_at_cup_height: false,
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
         max_instructions_per_message: 5_000_000_000,
         max_instructions_per_round: 7_000_000_000,
         max_instructions_per_install_code: 200_000_000_000,
@@ -321,6 +324,14 @@ impl SubnetRecordBuilder {
         self
     }
 
+    pub fn with_adaptive_payload_sizing_config(
+        mut self,
+        adaptive_payload_sizing_config: AdaptivePayloadSizingConfig,
+    ) -> Self {
+        self.record.adaptive_payload_sizing_config = Some(adaptive_payload_sizing_config);
+        self
+    }
+
     pub fn build(self) -> SubnetRecord {
         self.record
     }
@@ -98,6 +98,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -125,6 +126,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -155,6 +157,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 0,
                 },
@@ -177,6 +180,7 @@ pub fn test(env: TestEnv) {
                 context: vec![0, 1, 2],
             }),
             max_response_bytes: None,
+            replication: None,
         };
         test_results.push(
             test_canister_http_property(
@@ -227,6 +231,7 @@ pub fn test(env: TestEnv) {
                 context: vec![0, 1, 2],
             }),
             max_response_bytes: Some(16384),
+            replication: None,
         };
         test_results.push(
             test_canister_http_property(
@@ -282,6 +287,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: Some(4 * 1024 * 1024),
+                        replication: None,
                     },
                     cycles: 0,
                 },
@@ -309,6 +315,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -336,6 +343,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -366,6 +374,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -393,6 +402,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: Some(8 * 1024),
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -421,6 +431,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -448,6 +459,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -475,6 +487,7 @@ pub fn test(env: TestEnv) {
                             context: vec![0, 1, 2],
                         }),
                         max_response_bytes: None,
+                        replication: None,
                     },
                     cycles: 500_000_000_000,
                 },
@@ -149,6 +149,7 @@ pub fn test(env: TestEnv) {
                             }),
                             method: HttpMethod::GET,
                             max_response_bytes: None,
+                            replication: None,
                         },
                         cycles: 500_000_000_000,
                     },
@@ -197,6 +198,7 @@ pub fn test(env: TestEnv) {
                             }),
                             method: HttpMethod::GET,
                             max_response_bytes: None,
+                            replication: None,
                         },
                         cycles: 500_000_000_000,
                     },
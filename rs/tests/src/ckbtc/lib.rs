@@ -211,6 +211,8 @@ fn empty_subnet_update() -> UpdateSubnetPayload {
         max_number_of_canisters: None,
         ssh_readonly_access: None,
         ssh_backup_access: None,
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
     }
 }
 
@@ -603,6 +603,8 @@ pub async fn submit_create_application_subnet_proposal(
         ssh_readonly_access: vec![],
         ssh_backup_access: vec![],
         ecdsa_config: None,
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
     };
 
     submit_external_proposal_with_test_id(governance, NnsFunction::CreateSubnet, payload).await
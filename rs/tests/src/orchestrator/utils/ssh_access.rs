@@ -153,6 +153,8 @@ pub(crate) fn get_updatesubnetpayload_with_keys(
         max_number_of_canisters: None,
         ssh_readonly_access: readonly_keys,
         ssh_backup_access: backup_keys,
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
     }
 }
 
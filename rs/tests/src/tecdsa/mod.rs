@@ -78,6 +78,8 @@ pub(crate) fn empty_subnet_update() -> UpdateSubnetPayload {
         max_number_of_canisters: None,
         ssh_readonly_access: None,
         ssh_backup_access: None,
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
     }
 }
 
@@ -386,6 +388,8 @@ pub(crate) async fn create_new_subnet_with_keys(
             signature_request_timeout_ns: None,
             idkg_key_rotation_period_ms: None,
         }),
+        ingress_admission_config: None,
+        adaptive_payload_sizing_config: None,
     };
     execute_create_subnet_proposal(governance, payload, logger).await;
 }
@@ -16,7 +16,9 @@ pub enum TryFromError {
 /// of user-facing errors.
 ///
 /// See <https://sdk.dfinity.org/docs/interface-spec/index.html#reject-codes>
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, EnumIter)]
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, EnumIter,
+)]
 pub enum RejectCode {
     SysFatal = 1,
     SysTransient = 2,
@@ -1,5 +1,5 @@
 use crate::{BoundedVec, DataSize, Payload};
-use candid::{CandidType, Deserialize};
+use candid::{CandidType, Decode, Deserialize};
 use ic_base_types::PrincipalId;
 use serde::Serialize;
 
@@ -64,12 +64,13 @@ pub type BoundedHttpHeaders = BoundedVec<
 //     url : text;
 //     max_response_bytes: opt nat64;
 //     headers : vec http_header;
-//     method : variant { get; head; post };
+//     method : variant { get; head; post; put; patch; delete };
 //     body : opt blob;
 //     transform : opt record {
 //       function : func (record {response : http_response; context : blob}) -> (http_response) query;
 //       context : blob;
 //     };
+//     replication : opt variant { replicated; non_replicated; majority };
 //   })`
 #[derive(CandidType, Deserialize, Debug, Clone, PartialEq)]
 pub struct CanisterHttpRequestArgs {
@@ -79,6 +80,8 @@ pub struct CanisterHttpRequestArgs {
     pub body: Option<Vec<u8>>,
     pub method: HttpMethod,
     pub transform: Option<TransformContext>,
+    /// How many replicas make the request. Requests are replicated if not specified.
+    pub replication: Option<HttpOutcallReplication>,
 }
 
 impl Payload<'_> for CanisterHttpRequestArgs {}
@@ -91,6 +94,29 @@ impl CanisterHttpRequestArgs {
             .as_ref()
             .map(|transform_context| PrincipalId::from(transform_context.function.0.principal))
     }
+
+    /// Returns true if the request is made by a single replica.
+    pub fn is_non_replicated(&self) -> bool {
+        self.replication == Some(HttpOutcallReplication::NonReplicated)
+    }
+}
+
+/// Enum used for encoding/decoding:
+/// `variant { replicated; non_replicated; majority }`
+///
+/// The response to a non-replicated request is not verified by the other
+/// replicas, so the canister has to trust the replica making the request.
+/// Such responses carry the [`UNVERIFIED_RESPONSE_HEADER`].
+/// The response to a `majority` request is accepted once a majority of the
+/// replicas agree on it, instead of the usual two thirds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, CandidType, Serialize, Deserialize)]
+pub enum HttpOutcallReplication {
+    #[serde(rename = "replicated")]
+    Replicated,
+    #[serde(rename = "non_replicated")]
+    NonReplicated,
+    #[serde(rename = "majority")]
+    Majority,
 }
 
 #[test]
@@ -113,6 +139,7 @@ fn test_http_headers_max_number() {
             body: None,
             method: HttpMethod::GET,
             transform: None,
+            replication: None,
         };
 
         // Act.
@@ -166,6 +193,7 @@ fn test_http_headers_max_total_size() {
             body: None,
             method: HttpMethod::GET,
             transform: None,
+            replication: None,
         };
 
         // Act.
@@ -213,6 +241,7 @@ fn test_http_headers_max_element_size() {
             body: None,
             method: HttpMethod::GET,
             transform: None,
+            replication: None,
         };
 
         // Act.
@@ -240,6 +269,44 @@ fn test_http_headers_max_element_size() {
     }
 }
 
+#[test]
+fn test_http_outcall_replication_decoding() {
+    let args = CanisterHttpRequestArgs {
+        url: "http://example.com".to_string(),
+        max_response_bytes: None,
+        headers: BoundedHttpHeaders::new(vec![]),
+        body: None,
+        method: HttpMethod::GET,
+        transform: None,
+        replication: Some(HttpOutcallReplication::NonReplicated),
+    };
+    let decoded = CanisterHttpRequestArgs::decode(&args.encode()).unwrap();
+    assert_eq!(decoded, args);
+    assert!(decoded.is_non_replicated());
+
+    // Requests of canisters unaware of the field are replicated.
+    #[derive(CandidType)]
+    struct LegacyArgs {
+        url: String,
+        max_response_bytes: Option<u64>,
+        headers: Vec<HttpHeader>,
+        body: Option<Vec<u8>>,
+        method: HttpMethod,
+        transform: Option<TransformContext>,
+    }
+    let legacy = LegacyArgs {
+        url: "http://example.com".to_string(),
+        max_response_bytes: None,
+        headers: vec![],
+        body: None,
+        method: HttpMethod::GET,
+        transform: None,
+    };
+    let decoded = CanisterHttpRequestArgs::decode(&candid::Encode!(&legacy).unwrap()).unwrap();
+    assert_eq!(decoded.replication, None);
+    assert!(!decoded.is_non_replicated());
+}
+
 /// Struct used for encoding/decoding
 /// `(record {
 /// name: text;
@@ -281,6 +348,18 @@ fn test_http_header_data_size() {
     }
 }
 
+/// Enum used for encoding/decoding:
+/// `variant { get; head; post; put; patch; delete }`
+///
+/// Replicated requests are sent to the server by every replica of the subnet.
+/// Servers must therefore tolerate receiving the same request several times,
+/// which only idempotent methods (all but `post` and `patch`) guarantee.
+///
+/// A request that is rejected after it was sent, e.g. because the connection
+/// failed, the request timed out or the responses diverged, may still have been
+/// processed by the server. The reject messages point this out, and for
+/// non-idempotent methods the canister should check the state of the server
+/// before retrying.
 #[derive(Clone, Debug, PartialEq, CandidType, Eq, Hash, Serialize, Deserialize)]
 pub enum HttpMethod {
     #[serde(rename = "get")]
@@ -289,6 +368,38 @@ pub enum HttpMethod {
     POST,
     #[serde(rename = "head")]
     HEAD,
+    #[serde(rename = "put")]
+    PUT,
+    #[serde(rename = "patch")]
+    PATCH,
+    #[serde(rename = "delete")]
+    DELETE,
+}
+
+#[test]
+fn test_http_method_decoding() {
+    for method in [
+        HttpMethod::GET,
+        HttpMethod::POST,
+        HttpMethod::HEAD,
+        HttpMethod::PUT,
+        HttpMethod::PATCH,
+        HttpMethod::DELETE,
+    ] {
+        let args = CanisterHttpRequestArgs {
+            url: "http://example.com".to_string(),
+            max_response_bytes: None,
+            headers: BoundedHttpHeaders::new(vec![]),
+            body: Some(b"{}".to_vec()),
+            method,
+            transform: None,
+            replication: None,
+        };
+        assert_eq!(
+            CanisterHttpRequestArgs::decode(&args.encode()).unwrap(),
+            args
+        );
+    }
 }
 
 /// Represents the response for a canister http request.
@@ -307,3 +418,116 @@ pub struct CanisterHttpResponsePayload {
 }
 
 impl Payload<'_> for CanisterHttpResponsePayload {}
+
+/// Name of the header that the subnet adds to the response of a non-replicated
+/// request after the transform function was applied, with the value `true`.
+/// Its presence tells the canister that the response was made by a single
+/// replica and not verified by the other replicas of the subnet.
+pub const UNVERIFIED_RESPONSE_HEADER: &str = "x-ic-unverified-response";
+
+impl CanisterHttpResponsePayload {
+    /// Marks the response as not verified by the subnet by adding the
+    /// [`UNVERIFIED_RESPONSE_HEADER`].
+    pub fn mark_unverified(&mut self) {
+        self.headers.push(HttpHeader {
+            name: UNVERIFIED_RESPONSE_HEADER.to_string(),
+            value: "true".to_string(),
+        });
+    }
+
+    /// Returns true if the response was made by a single replica and not
+    /// verified by the subnet.
+    pub fn is_unverified(&self) -> bool {
+        self.headers
+            .iter()
+            .any(|header| header.name.eq_ignore_ascii_case(UNVERIFIED_RESPONSE_HEADER))
+    }
+}
+
+/// Marks the start of the hex-encoded [`CanisterHttpDivergenceReport`] at the
+/// end of the message of a reject that is delivered when the replicas did not
+/// reach consensus on the response to a canister http request.
+pub const DIVERGENCE_REPORT_PREFIX: &str = " Divergence report: ";
+
+/// Describes how the responses of the replicas to a canister http request
+/// differed, if no consensus was reached on one of them, without revealing
+/// their content.
+/// Struct used for encoding/decoding
+/// `(record {
+///     responses: vec record {
+///         status: opt nat32;
+///         reject_code: opt nat32;
+///         size: nat64;
+///         replica_count: nat64;
+///     };
+/// })`;
+#[derive(CandidType, Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanisterHttpDivergenceReport {
+    /// The distinct responses in decreasing order of support.
+    pub responses: Vec<CanisterHttpDivergentResponse>,
+}
+
+/// One of the distinct responses in a [`CanisterHttpDivergenceReport`].
+#[derive(CandidType, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanisterHttpDivergentResponse {
+    /// The status code of the transformed response. Not set if the request was
+    /// rejected or the transformed response is not a valid `http_response`.
+    pub status: Option<u32>,
+    /// The reject code, if the request was rejected.
+    pub reject_code: Option<u32>,
+    /// The size of the body of the transformed response, of the whole
+    /// transformed response if it is not a valid `http_response`, or of the
+    /// reject message.
+    pub size: u64,
+    /// The number of replicas that signed the response.
+    pub replica_count: u64,
+}
+
+impl Payload<'_> for CanisterHttpDivergenceReport {}
+
+impl CanisterHttpDivergenceReport {
+    /// Returns the suffix of the reject message that carries the report.
+    pub fn to_reject_message_suffix(&self) -> String {
+        format!("{}{}", DIVERGENCE_REPORT_PREFIX, hex::encode(self.encode()))
+    }
+
+    /// Extracts the report from the message of a reject, or returns `None` if
+    /// the message does not carry a valid report.
+    pub fn from_reject_message(message: &str) -> Option<Self> {
+        let (_, encoded) = message.rsplit_once(DIVERGENCE_REPORT_PREFIX)?;
+        let bytes = hex::decode(encoded).ok()?;
+        Decode!(&bytes, Self).ok()
+    }
+}
+
+#[test]
+fn test_divergence_report_reject_message_round_trip() {
+    let report = CanisterHttpDivergenceReport {
+        responses: vec![
+            CanisterHttpDivergentResponse {
+                status: Some(200),
+                reject_code: None,
+                size: 1024,
+                replica_count: 8,
+            },
+            CanisterHttpDivergentResponse {
+                status: None,
+                reject_code: Some(2),
+                size: 7,
+                replica_count: 5,
+            },
+        ],
+    };
+    let message = format!(
+        "No consensus was reached.{}",
+        report.to_reject_message_suffix()
+    );
+    assert_eq!(
+        CanisterHttpDivergenceReport::from_reject_message(&message),
+        Some(report)
+    );
+    assert_eq!(
+        CanisterHttpDivergenceReport::from_reject_message("No consensus was reached."),
+        None
+    );
+}
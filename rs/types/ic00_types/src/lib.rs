@@ -8,8 +8,10 @@ use arbitrary::{Arbitrary, Result as ArbitraryResult, Unstructured};
 pub use bounded_vec::*;
 use candid::{CandidType, Decode, Deserialize, Encode};
 pub use http::{
-    BoundedHttpHeaders, CanisterHttpRequestArgs, CanisterHttpResponsePayload, HttpHeader,
-    HttpMethod, TransformArgs, TransformContext, TransformFunc,
+    BoundedHttpHeaders, CanisterHttpDivergenceReport, CanisterHttpDivergentResponse,
+    CanisterHttpRequestArgs, CanisterHttpResponsePayload, HttpHeader, HttpMethod,
+    HttpOutcallReplication, TransformArgs, TransformContext, TransformFunc,
+    DIVERGENCE_REPORT_PREFIX, UNVERIFIED_RESPONSE_HEADER,
 };
 use ic_base_types::{CanisterId, NodeId, NumBytes, PrincipalId, RegistryVersion, SubnetId};
 use ic_error_types::{ErrorCode, UserError};
@@ -699,6 +701,46 @@ impl From<i32> for LogVisibility {
     }
 }
 
+/// Priority of the global timer of a canister.
+///
+/// Due timers of canisters with a `high` timer priority are scheduled ahead of
+/// other new executions, but only while the canister has a non-zero compute
+/// allocation.
+/// ```text
+/// variant {
+///    normal;
+///    high;
+/// }
+/// ```
+#[derive(Default, Clone, Copy, CandidType, Deserialize, Debug, PartialEq, Eq)]
+pub enum TimerPriority {
+    #[default]
+    #[serde(rename = "normal")]
+    Normal,
+    #[serde(rename = "high")]
+    High,
+}
+
+impl From<TimerPriority> for i32 {
+    fn from(item: TimerPriority) -> Self {
+        match item {
+            TimerPriority::Normal => 1,
+            TimerPriority::High => 2,
+        }
+    }
+}
+
+impl From<i32> for TimerPriority {
+    fn from(item: i32) -> Self {
+        match item {
+            0 => Self::default(),
+            1 => Self::Normal,
+            2 => Self::High,
+            _ => panic!("Unsupported value"),
+        }
+    }
+}
+
 /// Struct used for encoding/decoding
 /// `(record {
 ///     controller : principal;
@@ -707,6 +749,7 @@ impl From<i32> for LogVisibility {
 ///     freezing_threshold: nat;
 ///     reserved_cycles_limit: nat;
 ///     log_visibility: log_visibility;
+///     timer_priority: timer_priority;
 /// })`
 #[derive(CandidType, Clone, Deserialize, Debug, Eq, PartialEq)]
 pub struct DefiniteCanisterSettingsArgs {
@@ -717,9 +760,11 @@ pub struct DefiniteCanisterSettingsArgs {
     freezing_threshold: candid::Nat,
     reserved_cycles_limit: candid::Nat,
     log_visibility: LogVisibility,
+    timer_priority: TimerPriority,
 }
 
 impl DefiniteCanisterSettingsArgs {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         controller: PrincipalId,
         controllers: Vec<PrincipalId>,
@@ -728,6 +773,7 @@ impl DefiniteCanisterSettingsArgs {
         freezing_threshold: u64,
         reserved_cycles_limit: Option<u128>,
         log_visibility: LogVisibility,
+        timer_priority: TimerPriority,
     ) -> Self {
         let memory_allocation = candid::Nat::from(memory_allocation.unwrap_or(0));
         let reserved_cycles_limit = candid::Nat::from(reserved_cycles_limit.unwrap_or(0));
@@ -739,6 +785,7 @@ impl DefiniteCanisterSettingsArgs {
             freezing_threshold: candid::Nat::from(freezing_threshold),
             reserved_cycles_limit,
             log_visibility,
+            timer_priority,
         }
     }
 
@@ -753,6 +800,10 @@ impl DefiniteCanisterSettingsArgs {
     pub fn log_visibility(&self) -> LogVisibility {
         self.log_visibility
     }
+
+    pub fn timer_priority(&self) -> TimerPriority {
+        self.timer_priority
+    }
 }
 
 impl Payload<'_> for DefiniteCanisterSettingsArgs {}
@@ -869,6 +920,7 @@ impl CanisterStatusResultV2 {
         freezing_threshold: u64,
         reserved_cycles_limit: Option<u128>,
         log_visibility: LogVisibility,
+        timer_priority: TimerPriority,
         idle_cycles_burned_per_day: u128,
         reserved_cycles: u128,
         query_num_calls: u128,
@@ -893,6 +945,7 @@ impl CanisterStatusResultV2 {
                 freezing_threshold,
                 reserved_cycles_limit,
                 log_visibility,
+                timer_priority,
             ),
             freezing_threshold: candid::Nat::from(freezing_threshold),
             idle_cycles_burned_per_day: candid::Nat::from(idle_cycles_burned_per_day),
@@ -1500,6 +1553,7 @@ fn verify_max_bounded_controllers_length() {
 ///     freezing_threshold: opt nat;
 ///     reserved_cycles_limit: opt nat;
 ///     log_visibility : opt log_visibility;
+///     timer_priority : opt timer_priority;
 /// })`
 #[derive(Default, Clone, CandidType, Deserialize, Debug, PartialEq, Eq)]
 pub struct CanisterSettingsArgs {
@@ -1511,6 +1565,7 @@ pub struct CanisterSettingsArgs {
     pub freezing_threshold: Option<candid::Nat>,
     pub reserved_cycles_limit: Option<candid::Nat>,
     pub log_visibility: Option<LogVisibility>,
+    pub timer_priority: Option<TimerPriority>,
 }
 
 impl Payload<'_> for CanisterSettingsArgs {}
@@ -1527,6 +1582,7 @@ impl CanisterSettingsArgs {
             freezing_threshold: None,
             reserved_cycles_limit: None,
             log_visibility: None,
+            timer_priority: None,
         }
     }
 
@@ -1544,6 +1600,7 @@ pub struct CanisterSettingsArgsBuilder {
     freezing_threshold: Option<candid::Nat>,
     reserved_cycles_limit: Option<candid::Nat>,
     log_visibility: Option<LogVisibility>,
+    timer_priority: Option<TimerPriority>,
 }
 
 #[allow(dead_code)]
@@ -1561,6 +1618,7 @@ impl CanisterSettingsArgsBuilder {
             freezing_threshold: self.freezing_threshold,
             reserved_cycles_limit: self.reserved_cycles_limit,
             log_visibility: self.log_visibility,
+            timer_priority: self.timer_priority,
         }
     }
 
@@ -1636,6 +1694,14 @@ impl CanisterSettingsArgsBuilder {
             ..self
         }
     }
+
+    /// Sets the priority of the global timer.
+    pub fn with_timer_priority(self, timer_priority: TimerPriority) -> Self {
+        Self {
+            timer_priority: Some(timer_priority),
+            ..self
+        }
+    }
 }
 
 /// Struct used for encoding/decoding
@@ -2,7 +2,7 @@ use crate::{
     canister_http::{
         CanisterHttpReject, CanisterHttpRequestId, CanisterHttpResponse,
         CanisterHttpResponseContent, CanisterHttpResponseDivergence, CanisterHttpResponseMetadata,
-        CanisterHttpResponseShare, CanisterHttpResponseWithConsensus,
+        CanisterHttpResponseShare, CanisterHttpResponseSummary, CanisterHttpResponseWithConsensus,
     },
     crypto::{BasicSig, BasicSigOf, CryptoHash, CryptoHashOf, Signed},
     messages::CallbackId,
@@ -61,6 +61,9 @@ impl From<&CanisterHttpResponseWithConsensus> for pb::CanisterHttpResponseWithCo
             }),
             hash: payload.proof.content.content_hash.clone().get().0,
             registry_version: payload.proof.content.registry_version.get(),
+            summary: Some(pb::CanisterHttpResponseSummary::from(
+                &payload.proof.content.summary,
+            )),
             signatures: payload
                 .proof
                 .signature
@@ -115,6 +118,7 @@ impl TryFrom<pb::CanisterHttpResponseWithConsensus> for CanisterHttpResponseWith
                         payload.hash,
                     )),
                     registry_version: RegistryVersion::new(payload.registry_version),
+                    summary: summary_try_from_option(payload.summary)?,
                 },
                 signature: BasicSignatureBatch {
                     signatures_map: payload
@@ -190,16 +194,7 @@ impl TryFrom<pb::CanisterHttpResponseContent> for CanisterHttpResponseContent {
                 }
                 pb::canister_http_response_content::Status::Reject(error) => {
                     CanisterHttpResponseContent::Reject(CanisterHttpReject {
-                        reject_code: RejectCode::try_from(error.reject_code as u64).map_err(
-                            |err| match err {
-                                TryFromError::ValueOutOfRange(range) => {
-                                    ProxyDecodeError::ValueOutOfRange {
-                                        typ: "reject_code",
-                                        err: format!("value out of range: {}", range),
-                                    }
-                                }
-                            },
-                        )?,
+                        reject_code: reject_code_try_from_proto(error.reject_code)?,
                         message: error.message,
                     })
                 }
@@ -208,6 +203,50 @@ impl TryFrom<pb::CanisterHttpResponseContent> for CanisterHttpResponseContent {
     }
 }
 
+fn reject_code_try_from_proto(reject_code: u32) -> Result<RejectCode, ProxyDecodeError> {
+    RejectCode::try_from(reject_code as u64).map_err(|err| match err {
+        TryFromError::ValueOutOfRange(range) => ProxyDecodeError::ValueOutOfRange {
+            typ: "reject_code",
+            err: format!("value out of range: {}", range),
+        },
+    })
+}
+
+impl From<&CanisterHttpResponseSummary> for pb::CanisterHttpResponseSummary {
+    fn from(summary: &CanisterHttpResponseSummary) -> Self {
+        pb::CanisterHttpResponseSummary {
+            status: summary.status,
+            reject_code: summary.reject_code.map(|reject_code| reject_code as u32),
+            size: summary.size,
+        }
+    }
+}
+
+impl TryFrom<pb::CanisterHttpResponseSummary> for CanisterHttpResponseSummary {
+    type Error = ProxyDecodeError;
+
+    fn try_from(summary: pb::CanisterHttpResponseSummary) -> Result<Self, Self::Error> {
+        Ok(CanisterHttpResponseSummary {
+            status: summary.status,
+            reject_code: summary
+                .reject_code
+                .map(reject_code_try_from_proto)
+                .transpose()?,
+            size: summary.size,
+        })
+    }
+}
+
+/// Shares and responses signed before summaries were introduced have an empty summary.
+fn summary_try_from_option(
+    summary: Option<pb::CanisterHttpResponseSummary>,
+) -> Result<CanisterHttpResponseSummary, ProxyDecodeError> {
+    summary
+        .map(CanisterHttpResponseSummary::try_from)
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
 impl From<CanisterHttpResponseShare> for pb::CanisterHttpShare {
     fn from(share: CanisterHttpResponseShare) -> Self {
         pb::CanisterHttpShare {
@@ -216,6 +255,9 @@ impl From<CanisterHttpResponseShare> for pb::CanisterHttpShare {
                 timeout: share.content.timeout.as_nanos_since_unix_epoch(),
                 content_hash: share.content.content_hash.clone().get().0,
                 registry_version: share.content.registry_version.get(),
+                summary: Some(pb::CanisterHttpResponseSummary::from(
+                    &share.content.summary,
+                )),
             }),
             signature: Some(pb::CanisterHttpResponseSignature {
                 signer: share.signature.signer.get().into_vec(),
@@ -235,6 +277,7 @@ impl TryFrom<pb::CanisterHttpShare> for CanisterHttpResponseShare {
         let timeout = Time::from_nanos_since_unix_epoch(metadata.timeout);
         let content_hash = CryptoHashOf::new(CryptoHash(metadata.content_hash.clone()));
         let registry_version = RegistryVersion::new(metadata.registry_version);
+        let summary = summary_try_from_option(metadata.summary)?;
         let signature = share
             .signature
             .ok_or(ProxyDecodeError::MissingField("share.signature"))?;
@@ -244,6 +287,7 @@ impl TryFrom<pb::CanisterHttpShare> for CanisterHttpResponseShare {
                 timeout,
                 content_hash,
                 registry_version,
+                summary,
             },
             signature: BasicSignature {
                 signer: NodeId::from(PrincipalId::try_from(signature.signer)?),
@@ -287,6 +331,11 @@ mod tests {
                         0, 1, 2, 3,
                     ])),
                     registry_version: RegistryVersion::new(1),
+                    summary: CanisterHttpResponseSummary {
+                        status: Some(200),
+                        reject_code: None,
+                        size: 17,
+                    },
                 },
                 signature: BasicSignatureBatch {
                     signatures_map: vec![(
@@ -316,6 +365,11 @@ mod tests {
                         0, 1, 2, 3,
                     ])),
                     registry_version: RegistryVersion::new(1),
+                    summary: CanisterHttpResponseSummary {
+                        status: None,
+                        reject_code: Some(RejectCode::SysTransient),
+                        size: 12,
+                    },
                 },
                 signature: BasicSignature {
                     signer: NodeId::from(PrincipalId::new_node_test_id(1)),
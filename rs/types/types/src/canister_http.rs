@@ -41,17 +41,28 @@
 //! The blockmaker indicates, which requests have timed out, i.e. the blocktime of the latest finalized block is higher than
 //! the timestamp of a request plus the timeout interval. This condition is verifiable by the other nodes in the network.
 //! Once a timeout has made it into a finalized block, the request is answered with an error message.
+//!
+//! Non-replicated requests (see [`Replication::NonReplicated`]) skip steps 1 to 3 on all but one node.
+//! Only the node designated by execution makes the request and signs the metadata of the response.
+//! Since no other node holds the content of the response, it is included by the designated node
+//! once it makes a block, with a proof containing its single signature.
+//! Such a response is not verified by the other nodes: the canister has to trust the designated node
+//! or verify the response itself. If the designated node does not respond, the request times out (see 4c).
 use crate::{
     crypto::{CryptoHashOf, Signed},
     messages::{CallbackId, RejectContext, Request},
+    node_id_into_protobuf, node_id_try_from_option,
     signature::*,
-    CanisterId, CountBytes, RegistryVersion, Time,
+    CanisterId, CountBytes, NodeId, RegistryVersion, Time,
 };
 use ic_base_types::{NumBytes, PrincipalId};
 use ic_error_types::{ErrorCode, RejectCode, UserError};
 #[cfg(test)]
 use ic_exhaustive_derive::ExhaustiveSet;
-use ic_ic00_types::{CanisterHttpRequestArgs, HttpHeader, HttpMethod, TransformContext};
+use ic_ic00_types::{
+    CanisterHttpRequestArgs, CanisterHttpResponsePayload, HttpHeader, HttpMethod,
+    HttpOutcallReplication, Payload, TransformContext,
+};
 use ic_protobuf::{
     proxy::{try_from_option_field, ProxyDecodeError},
     state::system_metadata::v1 as pb_metadata,
@@ -110,6 +121,19 @@ impl From<TransformContext> for Transform {
     }
 }
 
+/// Which nodes make a canister http request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Replication {
+    /// All nodes of the subnet make the request and agree on the response.
+    #[default]
+    FullyReplicated,
+    /// Only the given node makes the request. Its response is not verified by the other nodes.
+    NonReplicated(NodeId),
+    /// All nodes of the subnet make the request, and a simple majority of them has to agree
+    /// on the response.
+    Majority,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct CanisterHttpRequestContext {
     pub request: Request,
@@ -121,6 +145,10 @@ pub struct CanisterHttpRequestContext {
     pub http_method: CanisterHttpMethod,
     pub transform: Option<Transform>,
     pub time: Time,
+    /// Converting [`CanisterHttpRequestArgs`] never yields a non-replicated context.
+    /// Execution designates the node of non-replicated requests.
+    #[serde(default)]
+    pub replication: Replication,
 }
 
 impl From<&CanisterHttpRequestContext> for pb_metadata::CanisterHttpRequestContext {
@@ -151,6 +179,11 @@ impl From<&CanisterHttpRequestContext> for pb_metadata::CanisterHttpRequestConte
                 .map(|transform| transform.context.clone()),
             http_method: pb_metadata::HttpMethod::from(&context.http_method).into(),
             time: context.time.as_nanos_since_unix_epoch(),
+            non_replicated_node_id: match context.replication {
+                Replication::FullyReplicated | Replication::Majority => None,
+                Replication::NonReplicated(node_id) => Some(node_id_into_protobuf(node_id)),
+            },
+            majority: context.replication == Replication::Majority,
         }
     }
 }
@@ -209,6 +242,11 @@ impl TryFrom<pb_metadata::CanisterHttpRequestContext> for CanisterHttpRequestCon
                 .try_into()?,
             transform,
             time: Time::from_nanos_since_unix_epoch(context.time),
+            replication: match (context.non_replicated_node_id, context.majority) {
+                (None, false) => Replication::FullyReplicated,
+                (None, true) => Replication::Majority,
+                (node_id, _) => Replication::NonReplicated(node_id_try_from_option(node_id)?),
+            },
         })
     }
 }
@@ -328,14 +366,29 @@ impl TryFrom<(Time, &Request, CanisterHttpRequestArgs)> for CanisterHttpRequestC
                 HttpMethod::GET => CanisterHttpMethod::GET,
                 HttpMethod::POST => CanisterHttpMethod::POST,
                 HttpMethod::HEAD => CanisterHttpMethod::HEAD,
+                HttpMethod::PUT => CanisterHttpMethod::PUT,
+                HttpMethod::PATCH => CanisterHttpMethod::PATCH,
+                HttpMethod::DELETE => CanisterHttpMethod::DELETE,
             },
             transform: args.transform.map(From::from),
             time,
+            replication: match args.replication {
+                Some(HttpOutcallReplication::Majority) => Replication::Majority,
+                _ => Replication::FullyReplicated,
+            },
         })
     }
 }
 
 impl CanisterHttpRequestContext {
+    /// Returns true if the given node has to make the request.
+    pub fn is_made_by(&self, node_id: NodeId) -> bool {
+        match self.replication {
+            Replication::FullyReplicated | Replication::Majority => true,
+            Replication::NonReplicated(designated_node_id) => designated_node_id == node_id,
+        }
+    }
+
     /// Calculate the size of all unbounded struct elements.
     pub fn variable_parts_size(&self) -> NumBytes {
         let request_size = self.url.len()
@@ -527,6 +580,23 @@ pub enum CanisterHttpMethod {
     GET,
     POST,
     HEAD,
+    PUT,
+    PATCH,
+    DELETE,
+}
+
+impl CanisterHttpMethod {
+    /// Returns true if sending the request several times has the same effect
+    /// on the server as sending it once.
+    pub fn is_idempotent(&self) -> bool {
+        match self {
+            CanisterHttpMethod::GET
+            | CanisterHttpMethod::HEAD
+            | CanisterHttpMethod::PUT
+            | CanisterHttpMethod::DELETE => true,
+            CanisterHttpMethod::POST | CanisterHttpMethod::PATCH => false,
+        }
+    }
 }
 
 impl From<&CanisterHttpMethod> for pb_metadata::HttpMethod {
@@ -535,6 +605,9 @@ impl From<&CanisterHttpMethod> for pb_metadata::HttpMethod {
             CanisterHttpMethod::GET => pb_metadata::HttpMethod::Get,
             CanisterHttpMethod::POST => pb_metadata::HttpMethod::Post,
             CanisterHttpMethod::HEAD => pb_metadata::HttpMethod::Head,
+            CanisterHttpMethod::PUT => pb_metadata::HttpMethod::Put,
+            CanisterHttpMethod::PATCH => pb_metadata::HttpMethod::Patch,
+            CanisterHttpMethod::DELETE => pb_metadata::HttpMethod::Delete,
         }
     }
 }
@@ -547,6 +620,9 @@ impl TryFrom<pb_metadata::HttpMethod> for CanisterHttpMethod {
             pb_metadata::HttpMethod::Get => Ok(CanisterHttpMethod::GET),
             pb_metadata::HttpMethod::Post => Ok(CanisterHttpMethod::POST),
             pb_metadata::HttpMethod::Head => Ok(CanisterHttpMethod::HEAD),
+            pb_metadata::HttpMethod::Put => Ok(CanisterHttpMethod::PUT),
+            pb_metadata::HttpMethod::Patch => Ok(CanisterHttpMethod::PATCH),
+            pb_metadata::HttpMethod::Delete => Ok(CanisterHttpMethod::DELETE),
             pb_metadata::HttpMethod::Unspecified => Err(ProxyDecodeError::ValueOutOfRange {
                 typ: "ic_protobuf::state::system_metadata::v1::HttpMethod",
                 err: "Unspecified HttpMethod".to_string(),
@@ -593,6 +669,72 @@ pub struct CanisterHttpResponseMetadata {
     pub timeout: Time,
     pub content_hash: CryptoHashOf<CanisterHttpResponse>,
     pub registry_version: RegistryVersion,
+    pub summary: CanisterHttpResponseSummary,
+}
+
+/// A summary of some [`CanisterHttpResponseContent`], which leaves out the body.
+///
+/// The summary is signed along with the content hash, such that the canister can be told
+/// how the responses of the replicas differed, if no consensus was reached on one of them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(test, derive(ExhaustiveSet))]
+pub struct CanisterHttpResponseSummary {
+    /// The status code of the transformed response.
+    /// Not set if the request was rejected or the transformed response is not a valid `http_response`.
+    pub status: Option<u32>,
+    /// The [`RejectCode`], if the request was rejected.
+    pub reject_code: Option<RejectCode>,
+    /// The size of the body of the transformed response, of the whole transformed response if
+    /// it is not a valid `http_response`, or of the reject message.
+    pub size: u64,
+}
+
+impl From<&CanisterHttpResponseContent> for CanisterHttpResponseSummary {
+    fn from(content: &CanisterHttpResponseContent) -> Self {
+        match content {
+            CanisterHttpResponseContent::Success(data) => {
+                match CanisterHttpResponsePayload::decode(data) {
+                    Ok(payload) => Self {
+                        status: u32::try_from(payload.status).ok(),
+                        reject_code: None,
+                        size: payload.body.len() as u64,
+                    },
+                    Err(_) => Self {
+                        status: None,
+                        reject_code: None,
+                        size: data.len() as u64,
+                    },
+                }
+            }
+            CanisterHttpResponseContent::Reject(reject) => Self {
+                status: None,
+                reject_code: Some(reject.reject_code),
+                size: reject.message.len() as u64,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for CanisterHttpResponseSummary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.reject_code, self.status) {
+            (Some(reject_code), _) => write!(
+                f,
+                "a reject with code {} and a message of {} bytes",
+                reject_code as u64, self.size
+            ),
+            (None, Some(status)) => write!(
+                f,
+                "a response with status {} and a body of {} bytes",
+                status, self.size
+            ),
+            (None, None) => write!(
+                f,
+                "a response of {} bytes that is not a valid http_response",
+                self.size
+            ),
+        }
+    }
 }
 
 impl CountBytes for CanisterHttpResponseMetadata {
@@ -654,6 +796,7 @@ mod tests {
                 metadata: None,
             },
             time: UNIX_EPOCH,
+            replication: Replication::FullyReplicated,
         };
 
         let expected_size = context.url.len()
@@ -695,6 +838,7 @@ mod tests {
                 metadata: None,
             },
             time: UNIX_EPOCH,
+            replication: Replication::FullyReplicated,
         };
 
         let expected_size = context.url.len()
@@ -706,4 +850,100 @@ mod tests {
             NumBytes::from(expected_size as u64)
         );
     }
+
+    #[test]
+    fn test_non_replicated_context_round_trip() {
+        let context = CanisterHttpRequestContext {
+            url: "https://example.com".to_string(),
+            headers: vec![],
+            body: None,
+            max_response_bytes: None,
+            http_method: CanisterHttpMethod::GET,
+            transform: None,
+            request: Request {
+                receiver: CanisterId::ic_00(),
+                sender: CanisterId::ic_00(),
+                sender_reply_callback: CallbackId::from(3),
+                payment: Cycles::new(10),
+                method_name: "http_request".to_string(),
+                method_payload: Vec::new(),
+                metadata: None,
+            },
+            time: UNIX_EPOCH,
+            replication: Replication::NonReplicated(NodeId::from(PrincipalId::new_node_test_id(1))),
+        };
+
+        let pb = pb_metadata::CanisterHttpRequestContext::from(&context);
+        assert!(pb.non_replicated_node_id.is_some());
+        assert_eq!(CanisterHttpRequestContext::try_from(pb).unwrap(), context);
+        assert!(context.is_made_by(NodeId::from(PrincipalId::new_node_test_id(1))));
+        assert!(!context.is_made_by(NodeId::from(PrincipalId::new_node_test_id(2))));
+
+        let context = CanisterHttpRequestContext {
+            replication: Replication::Majority,
+            ..context
+        };
+        let pb = pb_metadata::CanisterHttpRequestContext::from(&context);
+        assert!(pb.majority && pb.non_replicated_node_id.is_none());
+        assert_eq!(CanisterHttpRequestContext::try_from(pb).unwrap(), context);
+        assert!(context.is_made_by(NodeId::from(PrincipalId::new_node_test_id(2))));
+    }
+
+    #[test]
+    fn test_response_summary() {
+        let payload = CanisterHttpResponsePayload {
+            status: 404,
+            headers: vec![HttpHeader {
+                name: "name".to_string(),
+                value: "value".to_string(),
+            }],
+            body: b"not found".to_vec(),
+        };
+        assert_eq!(
+            CanisterHttpResponseSummary::from(&CanisterHttpResponseContent::Success(
+                payload.encode()
+            )),
+            CanisterHttpResponseSummary {
+                status: Some(404),
+                reject_code: None,
+                size: 9,
+            }
+        );
+        assert_eq!(
+            CanisterHttpResponseSummary::from(&CanisterHttpResponseContent::Success(vec![1, 2, 3])),
+            CanisterHttpResponseSummary {
+                status: None,
+                reject_code: None,
+                size: 3,
+            }
+        );
+        assert_eq!(
+            CanisterHttpResponseSummary::from(&CanisterHttpResponseContent::Reject(
+                CanisterHttpReject {
+                    reject_code: RejectCode::SysFatal,
+                    message: "error".to_string(),
+                }
+            )),
+            CanisterHttpResponseSummary {
+                status: None,
+                reject_code: Some(RejectCode::SysFatal),
+                size: 5,
+            }
+        );
+    }
+
+    #[test]
+    fn test_http_method_round_trip() {
+        for method in [
+            CanisterHttpMethod::GET,
+            CanisterHttpMethod::POST,
+            CanisterHttpMethod::HEAD,
+            CanisterHttpMethod::PUT,
+            CanisterHttpMethod::PATCH,
+            CanisterHttpMethod::DELETE,
+        ] {
+            let pb = pb_metadata::HttpMethod::from(&method);
+            assert_eq!(CanisterHttpMethod::try_from(pb).unwrap(), method);
+        }
+    }
 }
@@ -1,4 +1,5 @@
 use ic_protobuf::registry::subnet::v1::SubnetRecord;
+use std::time::Duration;
 
 /// A collection of subnet records, that are relevant for constructing a block
 pub struct SubnetRecords {
@@ -12,3 +13,16 @@ pub struct SubnetRecords {
     /// [`ic_types::batch::ValidationContext`].
     pub context_version: SubnetRecord,
 }
+
+/// Load signals of the subnet observed by the block maker, that are used to
+/// adapt the size of the block payload.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubnetLoad {
+    /// The time since the oldest notarized but not yet finalized block on the
+    /// chain of the proposed block was proposed.
+    pub finalization_latency: Duration,
+
+    /// The number of finalized blocks that have not yet been delivered to
+    /// message routing.
+    pub batch_delivery_backlog: u64,
+}
@@ -760,6 +760,12 @@ pub struct HttpStatusResponse {
     pub replica_health_status: Option<ReplicaHealthStatus>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub certified_height: Option<Height>,
+    /// A CBOR-encoded [`Certificate`] for the `/subnet/<subnet_id>/node` subtree
+    /// of the replica's subnet, including the delegation from the NNS subnet.
+    /// Clients use it to verify the node signatures on query responses
+    /// against the public keys of the subnet's nodes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_keys: Option<Blob>,
 }
 
 fn to_authentication<C>(env: &HttpRequestEnvelope<C>) -> Result<Authentication, HttpRequestError> {
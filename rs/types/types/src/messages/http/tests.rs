@@ -732,6 +732,7 @@ mod cbor_serialization {
                 impl_hash: None,
                 replica_health_status: Some(ReplicaHealthStatus::Starting),
                 certified_height: None,
+                node_keys: None,
             },
             Value::Map(btreemap! {
                 text("ic_api_version") => text("foobar"),
@@ -751,6 +752,7 @@ mod cbor_serialization {
                 impl_hash: None,
                 replica_health_status: Some(ReplicaHealthStatus::Healthy),
                 certified_height: None,
+                node_keys: None,
             },
             Value::Map(btreemap! {
                 text("ic_api_version") => text("foobar"),
@@ -771,6 +773,7 @@ mod cbor_serialization {
                 impl_hash: None,
                 replica_health_status: None,
                 certified_height: None,
+                node_keys: None,
             },
             Value::Map(btreemap! {
                 text("ic_api_version") => text("foobar"),
@@ -790,6 +793,7 @@ mod cbor_serialization {
                 impl_hash: None,
                 replica_health_status: Some(ReplicaHealthStatus::Healthy),
                 certified_height: Some(AmountOf::new(1)),
+                node_keys: None,
             },
             Value::Map(btreemap! {
                 text("ic_api_version") => text("foobar"),
@@ -801,6 +805,27 @@ mod cbor_serialization {
         );
     }
 
+    #[test]
+    fn encoding_status_with_node_keys() {
+        assert_cbor_ser_equal(
+            &HttpStatusResponse {
+                ic_api_version: "foobar".to_string(),
+                root_key: None,
+                impl_version: Some("0.0".to_string()),
+                impl_hash: None,
+                replica_health_status: Some(ReplicaHealthStatus::Healthy),
+                certified_height: None,
+                node_keys: Some(Blob(vec![4, 5, 6])),
+            },
+            Value::Map(btreemap! {
+                text("ic_api_version") => text("foobar"),
+                text("impl_version") => text("0.0"),
+                text("replica_health_status") => text("healthy"),
+                text("node_keys") => bytes(&[4, 5, 6]),
+            }),
+        );
+    }
+
     #[test]
     fn encoding_delegation() {
         assert_cbor_ser_equal(